@@ -0,0 +1,124 @@
+//! Anchor-based relative positioning.
+//!
+//! Places an actor by its divergence from a fixed set of named reference
+//! actors ("distance to USA vs distance to CHN"). The result is a small,
+//! fixed-length coordinate vector suitable for compass-style plots and as
+//! a feature block for downstream models. Nothing is recorded.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use serde::{Deserialize, Serialize};
+
+/// An actor's coordinates relative to anchor actors
+///
+/// Vectors are parallel to `anchors`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorPosition {
+    pub actor_id: String,
+    pub anchors: Vec<String>,
+    /// Symmetric KL Φ to each anchor
+    pub phi: Vec<f64>,
+    /// Jensen-Shannon divergence to each anchor
+    pub js: Vec<f64>,
+    /// Hellinger distance to each anchor
+    pub hellinger: Vec<f64>,
+}
+
+impl AnchorPosition {
+    /// Closest anchor by Jensen-Shannon divergence
+    pub fn nearest(&self) -> Option<&str> {
+        self.js
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| self.anchors[i].as_str())
+    }
+
+    /// Affinity to each anchor, summing to 1
+    ///
+    /// Proportional to `1 - H` (Hellinger similarity); uniform when the
+    /// actor is maximally far from every anchor.
+    pub fn affinities(&self) -> Vec<f64> {
+        let similarity: Vec<f64> = self.hellinger.iter().map(|h| 1.0 - h).collect();
+        let total: f64 = similarity.iter().sum();
+        if total > 0.0 {
+            similarity.iter().map(|s| s / total).collect()
+        } else {
+            vec![1.0 / self.anchors.len().max(1) as f64; self.anchors.len()]
+        }
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Divergences from `actor_id` to each anchor actor
+    pub fn position_relative_to_anchors(
+        &self,
+        actor_id: &str,
+        anchors: &[&str],
+    ) -> Result<AnchorPosition> {
+        if anchors.is_empty() {
+            return Err(DivergenceError::ConfigError(
+                "At least one anchor actor is required".to_string(),
+            ));
+        }
+        let scheme = self.scheme_for(actor_id)?;
+
+        let mut position = AnchorPosition {
+            actor_id: scheme.actor_id.clone(),
+            anchors: anchors.iter().map(|a| a.to_string()).collect(),
+            phi: Vec::with_capacity(anchors.len()),
+            js: Vec::with_capacity(anchors.len()),
+            hellinger: Vec::with_capacity(anchors.len()),
+        };
+        for anchor in anchors {
+            let anchor_scheme = self.scheme_for(anchor)?;
+            let metrics = scheme.all_metrics(anchor_scheme)?;
+            position.phi.push(metrics.symmetric_kl);
+            position.js.push(metrics.jensen_shannon);
+            position.hellinger.push(metrics.hellinger);
+        }
+        Ok(position)
+    }
+
+    /// Anchor positions of every actor, in registration order
+    ///
+    /// Anchors themselves are included (at zero distance to themselves).
+    pub fn all_anchor_positions(&self, anchors: &[&str]) -> Result<Vec<AnchorPosition>> {
+        self.schemes
+            .keys()
+            .map(|actor| self.position_relative_to_anchors(actor, anchors))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_positions() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("USA", Some(vec![0.8, 0.1, 0.1]), None);
+        model.register_actor("CHN", Some(vec![0.1, 0.1, 0.8]), None);
+        model.register_actor("IND", Some(vec![0.6, 0.2, 0.2]), None);
+
+        let position = model
+            .position_relative_to_anchors("IND", &["USA", "CHN"])
+            .unwrap();
+        assert_eq!(position.anchors, vec!["USA", "CHN"]);
+        assert!(position.phi[0] < position.phi[1]);
+        assert_eq!(position.nearest(), Some("USA"));
+        let affinities = position.affinities();
+        assert!((affinities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(affinities[0] > affinities[1]);
+
+        let all = model.all_anchor_positions(&["USA", "CHN"]).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].phi[0].abs() < 1e-12);
+
+        assert!(model.position_relative_to_anchors("IND", &[]).is_err());
+        assert!(model
+            .position_relative_to_anchors("IND", &["USA", "EU"])
+            .is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! Archetype priors for warm-starting actors.
+//!
+//! A newly registered actor normally starts from the uniform scheme, which
+//! claims the actor has no worldview at all and makes its first Φ values
+//! meaningless. Archetypes give new actors an informed starting point
+//! (hawkish, dovish, or an analyst-defined profile) that observations then
+//! override.
+//!
+//! The built-in hawkish and dovish archetypes assume categories are ordered
+//! from most cooperative (index 0) to most conflictual (last index), as with
+//! Goldstein-scale bins.
+//!
+//! ## Prior strength
+//!
+//! A prior of strength `s` behaves like `s` pseudo-observations: the
+//! learning rate for the n-th update is
+//!
+//! ```text
+//! η_n = η + (1 - η) / (s + n + 1)
+//! ```
+//!
+//! so the prior keeps weight `s / (s + n) · (1 - η)^n` after n updates.
+//! Weak priors are overridden within a few observations; as `s → ∞` the
+//! rate falls back to plain η.
+
+use crate::error::{DivergenceError, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Sharpness of the built-in hawkish/dovish profiles
+const ARCHETYPE_TILT: f64 = 2.0;
+
+/// Named prior worldview
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Archetype {
+    /// Mass tilted toward the conflictual end of the category scale
+    Hawkish,
+    /// Mass tilted toward the cooperative end of the category scale
+    Dovish,
+    /// No prior information
+    Uniform,
+    /// Profile defined in the model's [`ArchetypeLibrary`]
+    Custom(String),
+}
+
+impl Archetype {
+    /// Built-in profile over `n_categories`; `None` for custom archetypes
+    pub fn builtin_distribution(&self, n_categories: usize) -> Option<Vec<f64>> {
+        let n = n_categories.max(1);
+        let position = |i: usize| {
+            if n == 1 {
+                0.0
+            } else {
+                i as f64 / (n - 1) as f64
+            }
+        };
+        let weights: Vec<f64> = match self {
+            Archetype::Hawkish => (0..n)
+                .map(|i| (ARCHETYPE_TILT * position(i)).exp())
+                .collect(),
+            Archetype::Dovish => (0..n)
+                .map(|i| (ARCHETYPE_TILT * (1.0 - position(i))).exp())
+                .collect(),
+            Archetype::Uniform => vec![1.0; n],
+            Archetype::Custom(_) => return None,
+        };
+        let total: f64 = weights.iter().sum();
+        Some(weights.into_iter().map(|w| w / total).collect())
+    }
+}
+
+/// Registry of custom archetype profiles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchetypeLibrary {
+    profiles: IndexMap<String, Vec<f64>>,
+}
+
+impl ArchetypeLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define (or replace) a named profile
+    pub fn define(&mut self, name: impl Into<String>, distribution: Vec<f64>) -> Result<()> {
+        if distribution.is_empty()
+            || distribution.iter().any(|&p| !p.is_finite() || p < 0.0)
+            || distribution.iter().sum::<f64>() <= 0.0
+        {
+            return Err(DivergenceError::InvalidDistribution(
+                "Archetype profile must be non-empty, finite and non-negative".to_string(),
+            ));
+        }
+        self.profiles.insert(name.into(), distribution);
+        Ok(())
+    }
+
+    /// Profile for a custom archetype name
+    pub fn get(&self, name: &str) -> Option<&[f64]> {
+        self.profiles.get(name).map(|p| p.as_slice())
+    }
+
+    /// Defined profile names, in definition order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(|k| k.as_str())
+    }
+
+    /// Resolve an archetype to a distribution over `n_categories`
+    pub fn resolve(&self, archetype: &Archetype, n_categories: usize) -> Result<Vec<f64>> {
+        if let Some(dist) = archetype.builtin_distribution(n_categories) {
+            return Ok(dist);
+        }
+        let Archetype::Custom(name) = archetype else {
+            unreachable!("built-in archetypes always resolve");
+        };
+        let profile = self
+            .get(name)
+            .ok_or_else(|| DivergenceError::ConfigError(format!("Unknown archetype: {}", name)))?;
+        if profile.len() != n_categories {
+            return Err(DivergenceError::DimensionMismatch {
+                expected: n_categories,
+                got: profile.len(),
+            });
+        }
+        Ok(profile.to_vec())
+    }
+}
+
+/// Learning rate for the next update under a prior of the given strength
+pub fn prior_learning_rate(learning_rate: f64, prior_strength: f64, observations: u64) -> f64 {
+    let eta = learning_rate.clamp(0.0, 1.0);
+    eta + (1.0 - eta) / (prior_strength.max(0.0) + observations as f64 + 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_profiles() {
+        let hawk = Archetype::Hawkish.builtin_distribution(5).unwrap();
+        let dove = Archetype::Dovish.builtin_distribution(5).unwrap();
+        assert!(hawk.windows(2).all(|w| w[0] < w[1]));
+        assert!(dove.windows(2).all(|w| w[0] > w[1]));
+        assert!((hawk.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((hawk[4] - dove[0]).abs() < 1e-12);
+        assert_eq!(
+            Archetype::Uniform.builtin_distribution(4).unwrap(),
+            vec![0.25; 4]
+        );
+    }
+
+    #[test]
+    fn test_custom_profiles() {
+        let mut library = ArchetypeLibrary::new();
+        library.define("isolationist", vec![0.1, 0.8, 0.1]).unwrap();
+        assert!(library.define("broken", vec![-1.0, 2.0]).is_err());
+
+        let custom = Archetype::Custom("isolationist".to_string());
+        assert_eq!(library.resolve(&custom, 3).unwrap(), vec![0.1, 0.8, 0.1]);
+        assert!(library.resolve(&custom, 4).is_err());
+        assert!(library
+            .resolve(&Archetype::Custom("missing".to_string()), 3)
+            .is_err());
+    }
+
+    #[test]
+    fn test_prior_learning_rate() {
+        // No prior: the first observation replaces it entirely
+        assert_eq!(prior_learning_rate(0.1, 0.0, 0), 1.0);
+        // Strong priors fall back toward plain η
+        assert!((prior_learning_rate(0.1, 1e9, 0) - 0.1).abs() < 1e-6);
+        assert!(prior_learning_rate(0.1, 5.0, 0) > prior_learning_rate(0.1, 5.0, 10));
+    }
+}
@@ -0,0 +1,171 @@
+//! Audit log of runtime configuration changes.
+//!
+//! Changes made through [`CompressionDynamicsModel::update_config`] (and
+//! the streaming processor's threshold updates) are diffed setting by
+//! setting and appended to an in-model log with their timestamp. The log
+//! is serialized with the model and survives `clear_history`, so a
+//! snapshot always carries the settings that produced it.
+//!
+//! Settings are dotted paths into the serialized config, e.g.
+//! `learning_rate` or `risk_index.default_weight`; lists such as
+//! `risk_index.dyad_weights` are recorded whole.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::{CompressionDynamicsModel, ModelConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One recorded setting change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp_ms: i64,
+    /// Dotted path of the setting
+    pub setting: String,
+    pub old_value: Value,
+    pub new_value: Value,
+}
+
+impl CompressionDynamicsModel {
+    /// Change the model configuration at runtime, logging every setting
+    /// that differs afterwards
+    ///
+    /// Returns the number of settings changed. `n_categories` cannot be
+    /// changed this way (use `remap_categories`); attempting it leaves the
+    /// configuration untouched.
+    pub fn update_config(
+        &mut self,
+        timestamp_ms: i64,
+        update: impl FnOnce(&mut ModelConfig),
+    ) -> Result<usize> {
+        let mut config = self.config.clone();
+        update(&mut config);
+        if config.n_categories != self.config.n_categories {
+            return Err(DivergenceError::ConfigError(
+                "n_categories cannot be changed through update_config".to_string(),
+            ));
+        }
+        config.validate()?;
+        let before = config_value(&self.config)?;
+        let after = config_value(&config)?;
+        self.config = config;
+        Ok(self.record_config_changes("", before, after, timestamp_ms))
+    }
+
+    /// Recorded setting changes, oldest first
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        &self.audit_log
+    }
+
+    /// Recorded changes of one setting (or every setting below a prefix)
+    pub fn audit_entries_for<'a>(
+        &'a self,
+        setting: &'a str,
+    ) -> impl Iterator<Item = &'a AuditEntry> {
+        self.audit_log.iter().filter(move |e| {
+            e.setting == setting
+                || e.setting
+                    .strip_prefix(setting)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+
+    /// Diff two serialized configurations into the audit log under `prefix`
+    pub(crate) fn record_config_changes(
+        &mut self,
+        prefix: &str,
+        before: Value,
+        after: Value,
+        timestamp_ms: i64,
+    ) -> usize {
+        let start = self.audit_log.len();
+        diff_values(prefix, before, after, timestamp_ms, &mut self.audit_log);
+        self.audit_log.len() - start
+    }
+}
+
+pub(crate) fn config_value<T: Serialize>(config: &T) -> Result<Value> {
+    serde_json::to_value(config).map_err(|e| DivergenceError::SerializationError(e.to_string()))
+}
+
+fn diff_values(
+    path: &str,
+    before: Value,
+    after: Value,
+    timestamp_ms: i64,
+    log: &mut Vec<AuditEntry>,
+) {
+    match (before, after) {
+        (Value::Object(mut old), Value::Object(new)) => {
+            for (key, new_value) in new {
+                let old_value = old.remove(&key).unwrap_or(Value::Null);
+                diff_values(&join(path, &key), old_value, new_value, timestamp_ms, log);
+            }
+            for (key, old_value) in old {
+                diff_values(&join(path, &key), old_value, Value::Null, timestamp_ms, log);
+            }
+        }
+        (old_value, new_value) if old_value != new_value => log.push(AuditEntry {
+            timestamp_ms,
+            setting: path.to_string(),
+            old_value,
+            new_value,
+        }),
+        _ => {}
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk_index::DyadWeight;
+
+    #[test]
+    fn test_update_config_audit_log() {
+        let mut model = CompressionDynamicsModel::new(3);
+        let changed = model
+            .update_config(1_000, |c| {
+                c.learning_rate = 0.2;
+                c.risk_index.default_weight = 0.0;
+                c.risk_index.dyad_weights.push(DyadWeight {
+                    actor_a: "A".to_string(),
+                    actor_b: "B".to_string(),
+                    weight: 2.0,
+                });
+            })
+            .unwrap();
+        assert_eq!(changed, 3);
+        assert_eq!(model.config().learning_rate, 0.2);
+
+        let log = model.audit_log();
+        assert_eq!(log[0].setting, "learning_rate");
+        assert_eq!(log[0].old_value, Value::from(0.1));
+        assert_eq!(log[0].new_value, Value::from(0.2));
+        assert_eq!(log[0].timestamp_ms, 1_000);
+        assert_eq!(model.audit_entries_for("risk_index").count(), 2);
+        assert_eq!(model.audit_entries_for("risk").count(), 0);
+
+        // No-op updates and rejected updates leave no trace
+        assert_eq!(
+            model
+                .update_config(2_000, |c| c.learning_rate = 0.2)
+                .unwrap(),
+            0
+        );
+        assert!(model.update_config(3_000, |c| c.n_categories = 5).is_err());
+        assert_eq!(model.config().n_categories, 3);
+        assert_eq!(model.audit_log().len(), 3);
+
+        // Serialized with state, kept across history clears
+        model.clear_history();
+        let restored = CompressionDynamicsModel::from_json(&model.to_json().unwrap()).unwrap();
+        assert_eq!(restored.audit_log(), model.audit_log());
+    }
+}
@@ -0,0 +1,782 @@
+//! Avro codec and Confluent Schema Registry support.
+//!
+//! [`AvroCodec`] encodes events and alerts as Avro binary against the
+//! schemas in [`EVENT_AVRO_SCHEMA`] and [`ALERT_AVRO_SCHEMA`].
+//! [`SchemaRegistry`] registers those schemas and frames payloads in the
+//! Confluent wire format, so Kafka consumers using the Confluent
+//! deserializers read them without extra configuration:
+//!
+//! ```text
+//! ┌──────┬──────────────────┬──────────────────┐
+//! │ 0x00 │ schema ID (u32   │ Avro binary body │
+//! │ magic│ big endian)      │                  │
+//! └──────┴──────────────────┴──────────────────┘
+//! ```
+//!
+//! Subjects follow the topic name strategy (`<topic>-value`). Registered
+//! IDs and fetched schemas are cached, so the registry is only called once
+//! per subject and per ID.
+//!
+//! Schema resolution between different writer and reader schemas is not
+//! supported: a payload decodes only if its writer schema is the one this
+//! build uses. Event evolution happens through `schema_version` instead
+//! (see [`crate::event_schema`]).
+
+use crate::error::{DivergenceError, Result};
+use crate::event_schema::{validate_decoded_event, EVENT_SCHEMA_VERSION};
+use crate::latency::AlertLatency;
+use crate::scheme::RiskLevel;
+use crate::streaming::{DivergenceAlert, StreamEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Avro schema of [`StreamEvent`]
+pub const EVENT_AVRO_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "StreamEvent",
+  "namespace": "divergence.v1",
+  "fields": [
+    {"name": "schema_version", "type": "int"},
+    {"name": "event_id", "type": "string"},
+    {"name": "actor_id", "type": "string"},
+    {"name": "observation", "type": {"type": "array", "items": "double"}},
+    {"name": "target_actor_id", "type": ["null", "string"], "default": null},
+    {"name": "target_observation", "type": ["null", {"type": "array", "items": "double"}], "default": null},
+    {"name": "timestamp_ms", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+    {"name": "source", "type": "string"},
+    {"name": "metadata", "type": {"type": "map", "values": "string"}}
+  ]
+}"#;
+
+/// Avro schema of [`DivergenceAlert`]
+pub const ALERT_AVRO_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "DivergenceAlert",
+  "namespace": "divergence.v1",
+  "fields": [
+    {"name": "alert_id", "type": "string"},
+    {"name": "actor_a", "type": "string"},
+    {"name": "actor_b", "type": "string"},
+    {"name": "phi", "type": "double"},
+    {"name": "js", "type": "double"},
+    {"name": "phi_z", "type": ["null", "double"], "default": null},
+    {"name": "phi_adjusted", "type": ["null", "double"], "default": null},
+    {"name": "p_value", "type": ["null", "double"], "default": null},
+    {"name": "robustness", "type": ["null", "double"], "default": null},
+    {"name": "d_phi_dt", "type": "double"},
+    {"name": "risk_level", "type": {"type": "enum", "name": "RiskLevel",
+      "symbols": ["LOW", "MODERATE", "ELEVATED", "HIGH", "CRITICAL"]}},
+    {"name": "escalation_probability", "type": "double"},
+    {"name": "grievance_a_to_b", "type": "double"},
+    {"name": "grievance_b_to_a", "type": "double"},
+    {"name": "timestamp_ms", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+    {"name": "reason", "type": "string"},
+    {"name": "latency", "type": ["null", {"type": "record", "name": "AlertLatency",
+      "fields": [
+        {"name": "ingested_ms", "type": "long"},
+        {"name": "processed_ms", "type": "long"},
+        {"name": "emitted_ms", "type": ["null", "long"], "default": null}
+      ]}], "default": null}
+  ]
+}"#;
+
+/// First byte of a Confluent-framed payload
+pub const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+/// Avro binary encoding of an engine type
+pub trait AvroCodec: Sized {
+    /// Writer schema (JSON)
+    const SCHEMA: &'static str;
+
+    /// Encode as Avro binary, without framing
+    fn encode_avro(&self) -> Vec<u8>;
+
+    /// Decode unframed Avro binary written with [`SCHEMA`](Self::SCHEMA)
+    fn decode_avro(bytes: &[u8]) -> Result<Self>;
+}
+
+/// Prefix an Avro body with the Confluent header
+pub fn encode_confluent(schema_id: u32, body: &[u8]) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(5 + body.len());
+    framed.push(CONFLUENT_MAGIC_BYTE);
+    framed.extend_from_slice(&schema_id.to_be_bytes());
+    framed.extend_from_slice(body);
+    framed
+}
+
+/// Split a Confluent-framed payload into schema ID and Avro body
+pub fn decode_confluent(bytes: &[u8]) -> Result<(u32, &[u8])> {
+    match bytes {
+        [CONFLUENT_MAGIC_BYTE, a, b, c, d, body @ ..] => {
+            Ok((u32::from_be_bytes([*a, *b, *c, *d]), body))
+        }
+        _ => Err(DivergenceError::SerializationError(
+            "Not a Confluent-framed Avro payload".to_string(),
+        )),
+    }
+}
+
+fn write_long(out: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_double(out: &mut Vec<u8>, value: f64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    write_long(out, value.len() as i64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn write_doubles(out: &mut Vec<u8>, values: &[f64]) {
+    if !values.is_empty() {
+        write_long(out, values.len() as i64);
+        for &v in values {
+            write_double(out, v);
+        }
+    }
+    write_long(out, 0);
+}
+
+fn write_optional_double(out: &mut Vec<u8>, value: Option<f64>) {
+    match value {
+        None => write_long(out, 0),
+        Some(v) => {
+            write_long(out, 1);
+            write_double(out, v);
+        }
+    }
+}
+
+fn write_optional_long(out: &mut Vec<u8>, value: Option<i64>) {
+    match value {
+        None => write_long(out, 0),
+        Some(v) => {
+            write_long(out, 1);
+            write_long(out, v);
+        }
+    }
+}
+
+/// Cursor over an Avro body
+struct Reader<'a> {
+    bytes: &'a [u8],
+    record: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], record: &'static str) -> Self {
+        Self { bytes, record }
+    }
+
+    fn error(&self, problem: impl std::fmt::Display) -> DivergenceError {
+        DivergenceError::SerializationError(format!("Invalid {} Avro: {}", self.record, problem))
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < n {
+            return Err(self.error("unexpected end of input"));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn long(&mut self) -> Result<i64> {
+        let mut n: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok((n >> 1) as i64 ^ -((n & 1) as i64));
+            }
+        }
+        Err(self.error("varint too long"))
+    }
+
+    fn length(&mut self) -> Result<usize> {
+        let n = self.long()?;
+        usize::try_from(n).map_err(|_| self.error(format!("negative length {}", n)))
+    }
+
+    fn double(&mut self) -> Result<f64> {
+        let bytes = self.take(8)?;
+        Ok(f64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let n = self.length()?;
+        let bytes = self.take(n)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| self.error(e))
+    }
+
+    /// Item count of the next array or map block (0 at the end)
+    fn block(&mut self) -> Result<usize> {
+        let count = self.long()?;
+        if count < 0 {
+            // Negative counts are followed by the block's byte size
+            self.long()?;
+        }
+        Ok(count.unsigned_abs() as usize)
+    }
+
+    fn doubles(&mut self) -> Result<Vec<f64>> {
+        let mut values = Vec::new();
+        loop {
+            match self.block()? {
+                0 => return Ok(values),
+                n => {
+                    for _ in 0..n {
+                        values.push(self.double()?);
+                    }
+                }
+            }
+        }
+    }
+
+    fn union_branch(&mut self, field: &str) -> Result<bool> {
+        match self.long()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            n => Err(self.error(format!("{}: union branch {} out of range", field, n))),
+        }
+    }
+
+    fn optional_double(&mut self, field: &str) -> Result<Option<f64>> {
+        Ok(if self.union_branch(field)? {
+            Some(self.double()?)
+        } else {
+            None
+        })
+    }
+
+    fn optional_long(&mut self, field: &str) -> Result<Option<i64>> {
+        Ok(if self.union_branch(field)? {
+            Some(self.long()?)
+        } else {
+            None
+        })
+    }
+
+    fn finish(self) -> Result<()> {
+        if !self.bytes.is_empty() {
+            return Err(self.error(format!("{} trailing bytes", self.bytes.len())));
+        }
+        Ok(())
+    }
+}
+
+const RISK_LEVELS: [RiskLevel; 5] = [
+    RiskLevel::Low,
+    RiskLevel::Moderate,
+    RiskLevel::Elevated,
+    RiskLevel::High,
+    RiskLevel::Critical,
+];
+
+impl AvroCodec for StreamEvent {
+    const SCHEMA: &'static str = EVENT_AVRO_SCHEMA;
+
+    fn encode_avro(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64 + 8 * self.observation.len());
+        write_long(&mut out, i64::from(self.schema_version));
+        write_string(&mut out, &self.event_id);
+        write_string(&mut out, &self.actor_id);
+        write_doubles(&mut out, &self.observation);
+        match &self.target_actor_id {
+            None => write_long(&mut out, 0),
+            Some(target) => {
+                write_long(&mut out, 1);
+                write_string(&mut out, target);
+            }
+        }
+        match &self.target_observation {
+            None => write_long(&mut out, 0),
+            Some(observation) => {
+                write_long(&mut out, 1);
+                write_doubles(&mut out, observation);
+            }
+        }
+        write_long(&mut out, self.timestamp_ms);
+        write_string(&mut out, &self.source);
+        if !self.metadata.is_empty() {
+            write_long(&mut out, self.metadata.len() as i64);
+            for (key, value) in &self.metadata {
+                write_string(&mut out, key);
+                write_string(&mut out, value);
+            }
+        }
+        write_long(&mut out, 0);
+        out
+    }
+
+    fn decode_avro(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes, "StreamEvent");
+        let version = reader.long()?;
+        let version = match u32::try_from(version) {
+            Ok(v) if (1..=EVENT_SCHEMA_VERSION).contains(&v) => v,
+            _ if version > i64::from(EVENT_SCHEMA_VERSION) => {
+                return Err(DivergenceError::SerializationError(format!(
+                    "Unsupported event schema version {} (newest supported is {})",
+                    version, EVENT_SCHEMA_VERSION
+                )))
+            }
+            _ => return Err(reader.error(format!("schema_version: invalid {}", version))),
+        };
+        let event_id = reader.string()?;
+        let actor_id = reader.string()?;
+        let observation = reader.doubles()?;
+        let target_actor_id = if reader.union_branch("target_actor_id")? {
+            Some(reader.string()?)
+        } else {
+            None
+        };
+        let target_observation = if reader.union_branch("target_observation")? {
+            Some(Arc::from(reader.doubles()?))
+        } else {
+            None
+        };
+        let timestamp_ms = reader.long()?;
+        let source = reader.string()?;
+        let mut metadata = HashMap::new();
+        loop {
+            match reader.block()? {
+                0 => break,
+                n => {
+                    for _ in 0..n {
+                        let key = reader.string()?;
+                        let value = reader.string()?;
+                        metadata.insert(Arc::from(key), Arc::from(value));
+                    }
+                }
+            }
+        }
+        reader.finish()?;
+
+        let event = StreamEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            event_id,
+            actor_id,
+            observation: Arc::from(observation),
+            target_actor_id,
+            target_observation,
+            timestamp_ms,
+            source: Arc::from(source),
+            metadata,
+            ingested_ms: None,
+        };
+        let violations = validate_decoded_event(&event, version);
+        if !violations.is_empty() {
+            let problems: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+            return Err(DivergenceError::SerializationError(format!(
+                "Invalid StreamEvent Avro: {}",
+                problems.join("; ")
+            )));
+        }
+        Ok(event)
+    }
+}
+
+impl AvroCodec for DivergenceAlert {
+    const SCHEMA: &'static str = ALERT_AVRO_SCHEMA;
+
+    fn encode_avro(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(160);
+        write_string(&mut out, &self.alert_id);
+        write_string(&mut out, &self.actor_a);
+        write_string(&mut out, &self.actor_b);
+        write_double(&mut out, self.phi);
+        write_double(&mut out, self.js);
+        write_optional_double(&mut out, self.phi_z);
+        write_optional_double(&mut out, self.phi_adjusted);
+        write_optional_double(&mut out, self.p_value);
+        write_optional_double(&mut out, self.robustness);
+        write_double(&mut out, self.d_phi_dt);
+        let symbol = RISK_LEVELS
+            .iter()
+            .position(|&level| level == self.risk_level)
+            .expect("every risk level has a symbol");
+        write_long(&mut out, symbol as i64);
+        write_double(&mut out, self.escalation_probability);
+        write_double(&mut out, self.grievance_a_to_b);
+        write_double(&mut out, self.grievance_b_to_a);
+        write_long(&mut out, self.timestamp_ms);
+        write_string(&mut out, &self.reason);
+        match &self.latency {
+            None => write_long(&mut out, 0),
+            Some(latency) => {
+                write_long(&mut out, 1);
+                write_long(&mut out, latency.ingested_ms);
+                write_long(&mut out, latency.processed_ms);
+                write_optional_long(&mut out, latency.emitted_ms);
+            }
+        }
+        out
+    }
+
+    fn decode_avro(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes, "DivergenceAlert");
+        let alert = DivergenceAlert {
+            alert_id: reader.string()?,
+            actor_a: reader.string()?,
+            actor_b: reader.string()?,
+            phi: reader.double()?,
+            js: reader.double()?,
+            phi_z: reader.optional_double("phi_z")?,
+            phi_adjusted: reader.optional_double("phi_adjusted")?,
+            p_value: reader.optional_double("p_value")?,
+            robustness: reader.optional_double("robustness")?,
+            d_phi_dt: reader.double()?,
+            risk_level: {
+                let symbol = reader.long()?;
+                usize::try_from(symbol)
+                    .ok()
+                    .and_then(|i| RISK_LEVELS.get(i).copied())
+                    .ok_or_else(|| reader.error(format!("risk_level: no symbol {}", symbol)))?
+            },
+            escalation_probability: reader.double()?,
+            grievance_a_to_b: reader.double()?,
+            grievance_b_to_a: reader.double()?,
+            timestamp_ms: reader.long()?,
+            reason: reader.string()?,
+            latency: if reader.union_branch("latency")? {
+                Some(AlertLatency {
+                    ingested_ms: reader.long()?,
+                    processed_ms: reader.long()?,
+                    emitted_ms: reader.optional_long("emitted_ms")?,
+                })
+            } else {
+                None
+            },
+        };
+        reader.finish()?;
+        Ok(alert)
+    }
+}
+
+/// Connection settings for a [`SchemaRegistry`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaRegistryConfig {
+    /// Base URL, e.g. `http://localhost:8081`
+    pub url: String,
+    /// Basic auth user and password (Confluent Cloud API key and secret)
+    #[serde(default)]
+    pub basic_auth: Option<(String, String)>,
+    pub timeout_ms: u64,
+}
+
+impl SchemaRegistryConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            basic_auth: None,
+            timeout_ms: 10_000,
+        }
+    }
+
+    /// Authenticate with HTTP basic auth
+    pub fn with_basic_auth(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.basic_auth = Some((user.into(), password.into()));
+        self
+    }
+}
+
+/// Confluent Schema Registry client with ID and schema caches
+pub struct SchemaRegistry {
+    http: reqwest::Client,
+    config: SchemaRegistryConfig,
+    /// Registered schema ID per subject
+    ids: Mutex<HashMap<String, u32>>,
+    /// Schemas fetched by ID
+    schemas: Mutex<HashMap<u32, Value>>,
+}
+
+fn registry_error(call: &str, target: &str, error: impl std::fmt::Display) -> DivergenceError {
+    DivergenceError::ConfigError(format!(
+        "Schema registry {} for {} failed: {}",
+        call, target, error
+    ))
+}
+
+fn parse_schema(schema: &str) -> Result<Value> {
+    serde_json::from_str(schema)
+        .map_err(|e| DivergenceError::SerializationError(format!("Invalid Avro schema: {}", e)))
+}
+
+impl SchemaRegistry {
+    pub fn new(config: SchemaRegistryConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .map_err(|e| registry_error("client setup", &config.url, e))?;
+        Ok(Self {
+            http,
+            config,
+            ids: Mutex::new(HashMap::new()),
+            schemas: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Settings
+    pub fn config(&self) -> &SchemaRegistryConfig {
+        &self.config
+    }
+
+    async fn call(
+        &self,
+        request: reqwest::RequestBuilder,
+        call: &str,
+        target: &str,
+    ) -> Result<Value> {
+        let request = match &self.config.basic_auth {
+            Some((user, password)) => request.basic_auth(user, Some(password)),
+            None => request,
+        };
+        let response = request
+            .header("Accept", "application/vnd.schemaregistry.v1+json")
+            .send()
+            .await
+            .map_err(|e| registry_error(call, target, e))?;
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| registry_error(call, target, e))?;
+        if !status.is_success() {
+            let message = body
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("no message");
+            return Err(registry_error(
+                call,
+                target,
+                format!("{} ({})", status, message),
+            ));
+        }
+        Ok(body)
+    }
+
+    /// ID of `schema` under `subject`, registering it if new
+    pub async fn register(&self, subject: &str, schema: &str) -> Result<u32> {
+        if let Some(&id) = self.ids.lock().unwrap().get(subject) {
+            return Ok(id);
+        }
+        let url = format!("{}/subjects/{}/versions", self.config.url, subject);
+        let request = self
+            .http
+            .post(url)
+            .header("Content-Type", "application/vnd.schemaregistry.v1+json")
+            .body(serde_json::json!({ "schema": schema }).to_string());
+        let body = self.call(request, "register", subject).await?;
+        let id = body
+            .get("id")
+            .and_then(Value::as_u64)
+            .and_then(|id| u32::try_from(id).ok())
+            .ok_or_else(|| registry_error("register", subject, "response has no schema id"))?;
+        self.ids.lock().unwrap().insert(subject.to_string(), id);
+        Ok(id)
+    }
+
+    /// Schema registered under `id`
+    pub async fn schema(&self, id: u32) -> Result<Value> {
+        if let Some(schema) = self.schemas.lock().unwrap().get(&id) {
+            return Ok(schema.clone());
+        }
+        let target = format!("schema {}", id);
+        let url = format!("{}/schemas/ids/{}", self.config.url, id);
+        let body = self.call(self.http.get(url), "lookup", &target).await?;
+        let schema = body
+            .get("schema")
+            .and_then(Value::as_str)
+            .ok_or_else(|| registry_error("lookup", &target, "response has no schema"))
+            .and_then(parse_schema)?;
+        self.schemas.lock().unwrap().insert(id, schema.clone());
+        Ok(schema)
+    }
+
+    /// Encode `value` for `topic`, framed with its registered schema ID
+    pub async fn serialize<T: AvroCodec>(&self, topic: &str, value: &T) -> Result<Vec<u8>> {
+        let id = self
+            .register(&format!("{}-value", topic), T::SCHEMA)
+            .await?;
+        Ok(encode_confluent(id, &value.encode_avro()))
+    }
+
+    /// Decode a framed payload, checking its writer schema
+    pub async fn deserialize<T: AvroCodec>(&self, bytes: &[u8]) -> Result<T> {
+        let (id, body) = decode_confluent(bytes)?;
+        if self.schema(id).await? != parse_schema(T::SCHEMA)? {
+            return Err(DivergenceError::SerializationError(format!(
+                "Schema {} does not match this build's Avro schema",
+                id
+            )));
+        }
+        T::decode_avro(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn alert() -> DivergenceAlert {
+        DivergenceAlert {
+            alert_id: "a1".to_string(),
+            actor_a: "A".to_string(),
+            actor_b: "B".to_string(),
+            phi: 2.5,
+            js: 0.4,
+            phi_z: Some(3.0),
+            phi_adjusted: None,
+            p_value: Some(0.01),
+            robustness: None,
+            d_phi_dt: -0.1,
+            risk_level: RiskLevel::Critical,
+            escalation_probability: 0.6,
+            grievance_a_to_b: 0.3,
+            grievance_b_to_a: 0.0,
+            timestamp_ms: 1_700_000_000_000,
+            reason: "test".to_string(),
+            latency: None,
+        }
+    }
+
+    #[test]
+    fn test_avro_roundtrips() {
+        assert!(parse_schema(EVENT_AVRO_SCHEMA).is_ok());
+        assert!(parse_schema(ALERT_AVRO_SCHEMA).is_ok());
+
+        let event = StreamEvent::new("e1", "A", vec![0.25, 0.5, 0.25], -42)
+            .with_source(Arc::from("gdelt"))
+            .with_source_actor(Arc::from("C"))
+            .with_target("B", vec![0.1, 0.9]);
+        let back = StreamEvent::decode_avro(&event.encode_avro()).unwrap();
+        assert_eq!(back.event_id, "e1");
+        assert_eq!(back.timestamp_ms, -42);
+        assert_eq!(&*back.observation, &*event.observation);
+        assert_eq!(back.target_observation.as_deref(), Some(&[0.1, 0.9][..]));
+        assert_eq!(back.source_actor(), Some("C"));
+
+        let back = DivergenceAlert::decode_avro(&alert().encode_avro()).unwrap();
+        assert_eq!(back.risk_level, RiskLevel::Critical);
+        assert_eq!(back.phi_z, Some(3.0));
+        assert_eq!(back.robustness, None);
+        assert_eq!(back.reason, "test");
+        assert_eq!(back.latency, None);
+        let stamped = DivergenceAlert {
+            latency: Some(AlertLatency {
+                ingested_ms: 1_700_000_000_000,
+                processed_ms: 1_700_000_000_040,
+                emitted_ms: Some(1_700_000_000_055),
+            }),
+            ..alert()
+        };
+        let back = DivergenceAlert::decode_avro(&stamped.encode_avro()).unwrap();
+        assert_eq!(back.latency, stamped.latency);
+
+        let framed = encode_confluent(7, b"body");
+        assert_eq!(framed[..5], [0, 0, 0, 0, 7]);
+        assert_eq!(decode_confluent(&framed).unwrap(), (7, &b"body"[..]));
+        assert!(decode_confluent(&[1, 0, 0, 0, 7]).is_err());
+
+        // Truncated, trailing and invalid payloads
+        let bytes = alert().encode_avro();
+        assert!(DivergenceAlert::decode_avro(&bytes[..bytes.len() - 1]).is_err());
+        assert!(DivergenceAlert::decode_avro(&[&bytes[..], &[0]].concat()).is_err());
+        let unobserved = StreamEvent::new("e2", "A", vec![], 0).encode_avro();
+        assert!(StreamEvent::decode_avro(&unobserved).is_err());
+    }
+
+    /// Minimal registry: registers everything as ID 7, serves the event schema
+    async fn serve_registry(listener: TcpListener, calls: Arc<AtomicUsize>) {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            calls.fetch_add(1, Ordering::SeqCst);
+            // Read the whole request, so closing does not reset the connection
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            loop {
+                let n = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            let request = String::from_utf8_lossy(&request).to_string();
+            let (status, body) = if request.starts_with("POST /subjects/events-value/versions") {
+                ("200 OK", serde_json::json!({ "id": 7 }))
+            } else if request.starts_with("GET /schemas/ids/7 ") {
+                ("200 OK", serde_json::json!({ "schema": EVENT_AVRO_SCHEMA }))
+            } else {
+                (
+                    "404 Not Found",
+                    serde_json::json!({ "error_code": 40403, "message": "Schema not found" }),
+                )
+            };
+            let body = body.to_string();
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schema_registry_serde() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let calls = Arc::new(AtomicUsize::new(0));
+        tokio::spawn(serve_registry(listener, calls.clone()));
+
+        let registry = SchemaRegistry::new(SchemaRegistryConfig::new(url)).unwrap();
+        let event = StreamEvent::new("e1", "A", vec![0.5, 0.5], 10);
+        let bytes = registry.serialize("events", &event).await.unwrap();
+        assert_eq!(bytes[..5], [0, 0, 0, 0, 7]);
+        let back: StreamEvent = registry.deserialize(&bytes).await.unwrap();
+        assert_eq!(back.event_id, "e1");
+
+        // IDs and schemas are cached
+        registry.serialize("events", &event).await.unwrap();
+        registry.deserialize::<StreamEvent>(&bytes).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // The writer schema must be the reader's
+        let err = registry
+            .deserialize::<DivergenceAlert>(&bytes)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("does not match"));
+        let err = registry
+            .serialize("alerts", &alert())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Schema not found"), "{}", err);
+    }
+}
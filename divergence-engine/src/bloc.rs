@@ -0,0 +1,424 @@
+//! Temporal bloc tracking.
+//!
+//! Dyad alerts watch pairs; structural shifts - an actor drifting out of
+//! its camp, two camps fusing - only show up at the level of clusters.
+//! [`BlocTracker`] re-clusters the model at each step and matches the new
+//! clusters to the previous step's blocs, so blocs keep stable ids and
+//! changes come out as [`BlocEvent`]s.
+//!
+//! Clustering is evolutionary: the distance matrix handed to k-medoids is
+//!
+//! ```text
+//! D_t = (1 - w)·D_now + w·D_{t-1}
+//! ```
+//!
+//! for actor pairs seen at the previous step, which damps flip-flopping
+//! of actors near a border. Matching is greedy by Jaccard overlap of
+//! member sets. A cluster that takes the majority of two or more old
+//! blocs is a merge; an old bloc whose members make up the majority of
+//! two or more new clusters has split.
+
+use crate::cluster::{distance_matrix, k_medoids, silhouette_scores, ClusterMetric};
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::CompressionScheme;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Bloc tracker settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlocTrackerConfig {
+    /// Clusters per step
+    pub k: usize,
+
+    /// Dissimilarity between actors
+    pub metric: ClusterMetric,
+
+    /// Weight w of the previous step's distances (0 = independent steps)
+    pub history_weight: f64,
+
+    /// Minimum Jaccard overlap for a cluster to continue a bloc
+    pub min_overlap: f64,
+}
+
+impl Default for BlocTrackerConfig {
+    fn default() -> Self {
+        Self {
+            k: 2,
+            metric: ClusterMetric::default(),
+            history_weight: 0.3,
+            min_overlap: 0.3,
+        }
+    }
+}
+
+/// A tracked bloc
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bloc {
+    pub id: u64,
+    /// Members in registration order
+    pub members: Vec<String>,
+    pub medoid: String,
+    /// Step at which the bloc first appeared
+    pub formed_ms: i64,
+}
+
+/// Structural change between two steps
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BlocEvent {
+    /// A bloc with no predecessor appeared
+    Formed { bloc: u64, members: Vec<String> },
+    /// A bloc has no successor
+    Dissolved { bloc: u64 },
+    /// Several blocs fused; `into` may reuse one of their ids
+    Merged { from: Vec<u64>, into: u64 },
+    /// A bloc broke up; `into` may reuse its id
+    Split { from: u64, into: Vec<u64> },
+    /// An actor entered a bloc
+    Joined { actor: String, bloc: u64 },
+    /// An actor left a bloc
+    Left { actor: String, bloc: u64 },
+}
+
+/// Events from one tracker step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlocUpdate {
+    pub timestamp_ms: i64,
+    pub events: Vec<BlocEvent>,
+    pub mean_silhouette: f64,
+}
+
+/// Matches worldview clusters across time steps
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlocTracker {
+    config: BlocTrackerConfig,
+    blocs: Vec<Bloc>,
+    actors: Vec<String>,
+    distances: Vec<Vec<f64>>,
+    next_id: u64,
+}
+
+impl BlocTracker {
+    pub fn new(config: BlocTrackerConfig) -> Result<Self> {
+        if !(0.0..1.0).contains(&config.history_weight) {
+            return Err(DivergenceError::ConfigError(format!(
+                "History weight must be in [0, 1), got {}",
+                config.history_weight
+            )));
+        }
+        Ok(Self {
+            config,
+            ..Default::default()
+        })
+    }
+
+    pub fn config(&self) -> &BlocTrackerConfig {
+        &self.config
+    }
+
+    /// Blocs as of the last step
+    pub fn blocs(&self) -> &[Bloc] {
+        &self.blocs
+    }
+
+    /// Bloc an actor belonged to at the last step
+    pub fn bloc_of(&self, actor_id: &str) -> Option<&Bloc> {
+        self.blocs
+            .iter()
+            .find(|b| b.members.iter().any(|m| m == actor_id))
+    }
+
+    /// Cluster the model's current schemes and diff against the last step
+    ///
+    /// With fewer actors than `k`, every actor forms its own cluster.
+    pub fn step(
+        &mut self,
+        model: &CompressionDynamicsModel,
+        timestamp_ms: i64,
+    ) -> Result<BlocUpdate> {
+        let actors: Vec<String> = model.schemes.keys().cloned().collect();
+        let schemes: Vec<&CompressionScheme> = model.schemes.values().collect();
+        let mut dist = distance_matrix(&schemes, self.config.metric)?;
+        self.blend_history(&actors, &mut dist);
+
+        let k = self.config.k.min(actors.len());
+        let (medoids, assignments) = if k == 0 {
+            (Vec::new(), Vec::new())
+        } else {
+            k_medoids(&dist, k)?
+        };
+        let mean_silhouette = if actors.is_empty() {
+            0.0
+        } else {
+            silhouette_scores(&dist, &assignments).iter().sum::<f64>() / actors.len() as f64
+        };
+
+        let clusters: Vec<Vec<String>> = (0..k)
+            .map(|c| {
+                actors
+                    .iter()
+                    .zip(assignments.iter())
+                    .filter(|(_, &a)| a == c)
+                    .map(|(actor, _)| actor.clone())
+                    .collect()
+            })
+            .collect();
+
+        let (ids, mut events) = self.match_clusters(&clusters);
+
+        let previous: IndexMap<&str, u64> = self
+            .blocs
+            .iter()
+            .flat_map(|b| b.members.iter().map(move |m| (m.as_str(), b.id)))
+            .collect();
+        if !self.blocs.is_empty() {
+            for (cluster, &id) in clusters.iter().zip(ids.iter()) {
+                for actor in cluster {
+                    match previous.get(actor.as_str()) {
+                        Some(&old) if old == id => {}
+                        Some(&old) => {
+                            events.push(BlocEvent::Left {
+                                actor: actor.clone(),
+                                bloc: old,
+                            });
+                            events.push(BlocEvent::Joined {
+                                actor: actor.clone(),
+                                bloc: id,
+                            });
+                        }
+                        None => events.push(BlocEvent::Joined {
+                            actor: actor.clone(),
+                            bloc: id,
+                        }),
+                    }
+                }
+            }
+        }
+
+        let formed: IndexMap<u64, i64> = self.blocs.iter().map(|b| (b.id, b.formed_ms)).collect();
+        self.blocs = clusters
+            .into_iter()
+            .zip(ids)
+            .zip(medoids)
+            .map(|((members, id), medoid)| Bloc {
+                id,
+                members,
+                medoid: actors[medoid].clone(),
+                formed_ms: formed.get(&id).copied().unwrap_or(timestamp_ms),
+            })
+            .collect();
+        self.actors = actors;
+        self.distances = dist;
+
+        Ok(BlocUpdate {
+            timestamp_ms,
+            events,
+            mean_silhouette,
+        })
+    }
+
+    /// Mix in the previous step's distances for pairs seen then
+    fn blend_history(&self, actors: &[String], dist: &mut [Vec<f64>]) {
+        let w = self.config.history_weight;
+        if w <= 0.0 || self.actors.is_empty() {
+            return;
+        }
+        let old_index: Vec<Option<usize>> = actors
+            .iter()
+            .map(|a| self.actors.iter().position(|o| o == a))
+            .collect();
+        for i in 0..actors.len() {
+            for j in 0..actors.len() {
+                if let (Some(oi), Some(oj)) = (old_index[i], old_index[j]) {
+                    dist[i][j] = (1.0 - w) * dist[i][j] + w * self.distances[oi][oj];
+                }
+            }
+        }
+    }
+
+    /// Assign bloc ids to new clusters and emit structural events
+    fn match_clusters(&mut self, clusters: &[Vec<String>]) -> (Vec<u64>, Vec<BlocEvent>) {
+        let old: Vec<HashSet<&str>> = self
+            .blocs
+            .iter()
+            .map(|b| b.members.iter().map(|m| m.as_str()).collect())
+            .collect();
+        let new: Vec<HashSet<&str>> = clusters
+            .iter()
+            .map(|c| c.iter().map(|m| m.as_str()).collect())
+            .collect();
+        let overlap = |i: usize, j: usize| old[i].intersection(&new[j]).count();
+
+        // Greedy one-to-one continuation by Jaccard overlap
+        let mut pairs: Vec<(f64, usize, usize)> = Vec::new();
+        for (i, old_members) in old.iter().enumerate() {
+            for (j, new_members) in new.iter().enumerate() {
+                let shared = overlap(i, j);
+                let union = old_members.len() + new_members.len() - shared;
+                if shared > 0 {
+                    pairs.push((shared as f64 / union as f64, i, j));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut old_matched = vec![false; old.len()];
+        let mut ids: Vec<Option<u64>> = vec![None; new.len()];
+        for (jaccard, i, j) in pairs {
+            if jaccard >= self.config.min_overlap && !old_matched[i] && ids[j].is_none() {
+                old_matched[i] = true;
+                ids[j] = Some(self.blocs[i].id);
+            }
+        }
+        let ids: Vec<u64> = ids
+            .into_iter()
+            .map(|id| {
+                id.unwrap_or_else(|| {
+                    self.next_id += 1;
+                    self.next_id - 1
+                })
+            })
+            .collect();
+
+        let mut events = Vec::new();
+        let mut old_explained = old_matched;
+        let mut new_explained: Vec<bool> = (0..new.len())
+            .map(|j| self.blocs.iter().any(|b| b.id == ids[j]))
+            .collect();
+
+        for j in 0..new.len() {
+            let sources: Vec<usize> = (0..old.len())
+                .filter(|&i| 2 * overlap(i, j) > old[i].len())
+                .collect();
+            if sources.len() >= 2 {
+                for &i in &sources {
+                    old_explained[i] = true;
+                }
+                new_explained[j] = true;
+                events.push(BlocEvent::Merged {
+                    from: sources.iter().map(|&i| self.blocs[i].id).collect(),
+                    into: ids[j],
+                });
+            }
+        }
+        for (i, explained) in old_explained.iter_mut().enumerate() {
+            let targets: Vec<usize> = (0..new.len())
+                .filter(|&j| 2 * overlap(i, j) > new[j].len())
+                .collect();
+            if targets.len() >= 2 {
+                for &j in &targets {
+                    new_explained[j] = true;
+                }
+                *explained = true;
+                events.push(BlocEvent::Split {
+                    from: self.blocs[i].id,
+                    into: targets.iter().map(|&j| ids[j]).collect(),
+                });
+            }
+        }
+
+        for (i, bloc) in self.blocs.iter().enumerate() {
+            if !old_explained[i] {
+                events.push(BlocEvent::Dissolved { bloc: bloc.id });
+            }
+        }
+        for j in 0..new.len() {
+            if !new_explained[j] {
+                events.push(BlocEvent::Formed {
+                    bloc: ids[j],
+                    members: clusters[j].clone(),
+                });
+            }
+        }
+        (ids, events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(k: usize) -> BlocTracker {
+        BlocTracker::new(BlocTrackerConfig {
+            k,
+            history_weight: 0.0,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_defection_emits_leave_and_join() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("USA", Some(vec![0.8, 0.1, 0.1]), None);
+        model.register_actor("GBR", Some(vec![0.75, 0.15, 0.1]), None);
+        model.register_actor("TUR", Some(vec![0.7, 0.2, 0.1]), None);
+        model.register_actor("RUS", Some(vec![0.1, 0.1, 0.8]), None);
+        model.register_actor("CHN", Some(vec![0.1, 0.2, 0.7]), None);
+
+        let mut blocs = tracker(2);
+        let first = blocs.step(&model, 0).unwrap();
+        assert_eq!(
+            first
+                .events
+                .iter()
+                .filter(|e| matches!(e, BlocEvent::Formed { .. }))
+                .count(),
+            2
+        );
+        let west = blocs.bloc_of("USA").unwrap().id;
+        let east = blocs.bloc_of("RUS").unwrap().id;
+
+        model.register_actor("TUR", Some(vec![0.1, 0.15, 0.75]), None);
+        let second = blocs.step(&model, 1).unwrap();
+        assert_eq!(
+            second.events,
+            vec![
+                BlocEvent::Left {
+                    actor: "TUR".to_string(),
+                    bloc: west
+                },
+                BlocEvent::Joined {
+                    actor: "TUR".to_string(),
+                    bloc: east
+                },
+            ]
+        );
+        assert_eq!(blocs.bloc_of("TUR").unwrap().formed_ms, 0);
+    }
+
+    #[test]
+    fn test_merge_and_split() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A1", Some(vec![0.8, 0.1, 0.1]), None);
+        model.register_actor("A2", Some(vec![0.75, 0.15, 0.1]), None);
+        model.register_actor("B1", Some(vec![0.1, 0.8, 0.1]), None);
+        model.register_actor("B2", Some(vec![0.15, 0.75, 0.1]), None);
+        model.register_actor("C1", Some(vec![0.1, 0.1, 0.8]), None);
+        model.register_actor("C2", Some(vec![0.1, 0.15, 0.75]), None);
+
+        let mut blocs = tracker(3);
+        blocs.step(&model, 0).unwrap();
+        let a = blocs.bloc_of("A1").unwrap().id;
+        let b = blocs.bloc_of("B1").unwrap().id;
+
+        // A and B converge; C splits into two camps
+        model.register_actor("B1", Some(vec![0.78, 0.12, 0.1]), None);
+        model.register_actor("B2", Some(vec![0.77, 0.13, 0.1]), None);
+        model.register_actor("C2", Some(vec![0.45, 0.1, 0.45]), None);
+        let update = blocs.step(&model, 1).unwrap();
+
+        assert!(update.events.iter().any(|e| matches!(
+            e,
+            BlocEvent::Merged { from, .. } if from.contains(&a) && from.contains(&b)
+        )));
+        assert_eq!(blocs.bloc_of("A1"), blocs.bloc_of("B2"));
+        assert_ne!(blocs.bloc_of("C1"), blocs.bloc_of("C2"));
+        assert!(BlocTracker::new(BlocTrackerConfig {
+            history_weight: 1.0,
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
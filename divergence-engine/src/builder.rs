@@ -0,0 +1,269 @@
+//! Distribution builder - glue between raw token feeds and scheme updates.
+//!
+//! Feeds usually arrive as a stream of category hits (event codes, topic
+//! labels, keyword matches) rather than ready-made observation vectors.
+//! [`DistributionBuilder`] accumulates those hits and emits a normalized
+//! observation vector on each flush, ready for
+//! [`CompressionDynamicsModel::update_scheme`].
+//!
+//! Optional tf-idf weighting treats each flushed window as a document, so
+//! categories that show up in every window are down-weighted relative to
+//! categories that are distinctive for the current window.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::registry::CategoryRegistry;
+use crate::scheme::CompressionScheme;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Weighting applied to accumulated counts on flush
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TermWeighting {
+    /// Raw term frequencies
+    #[default]
+    Raw,
+    /// Term frequency × smoothed inverse window frequency
+    ///
+    /// idf_i = ln((1 + N) / (1 + df_i)) + 1
+    TfIdf,
+}
+
+/// Accumulates category hits and produces observation vectors per flush
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionBuilder {
+    counts: Vec<f64>,
+    registry: Option<Arc<CategoryRegistry>>,
+    weighting: TermWeighting,
+    document_frequency: Vec<f64>,
+    n_windows: usize,
+    n_pending: usize,
+}
+
+impl DistributionBuilder {
+    /// Create a builder over `n_categories` positional categories
+    pub fn new(n_categories: usize) -> Self {
+        Self {
+            counts: vec![0.0; n_categories],
+            registry: None,
+            weighting: TermWeighting::default(),
+            document_frequency: vec![0.0; n_categories],
+            n_windows: 0,
+            n_pending: 0,
+        }
+    }
+
+    /// Create a builder whose string tokens map onto the given category names
+    pub fn with_categories(categories: &[String]) -> Self {
+        Self::with_registry(CategoryRegistry::from_names(categories).into_shared())
+    }
+
+    /// Create a builder whose string tokens resolve through a shared registry
+    pub fn with_registry(registry: Arc<CategoryRegistry>) -> Self {
+        let mut builder = Self::new(registry.len());
+        builder.registry = Some(registry);
+        builder
+    }
+
+    /// Create a builder matching a scheme's category space
+    pub fn for_scheme(scheme: &CompressionScheme) -> Self {
+        match scheme.registry() {
+            Some(registry) => Self::with_registry(Arc::clone(registry)),
+            None => Self::with_categories(&scheme.categories),
+        }
+    }
+
+    /// Set the weighting applied on flush
+    pub fn with_weighting(mut self, weighting: TermWeighting) -> Self {
+        self.weighting = weighting;
+        self
+    }
+
+    /// Number of categories
+    #[inline]
+    pub fn n_categories(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Number of hits accumulated since the last flush
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.n_pending
+    }
+
+    /// Number of windows flushed so far
+    #[inline]
+    pub fn n_windows(&self) -> usize {
+        self.n_windows
+    }
+
+    /// Record a hit on a category index
+    pub fn push(&mut self, category: usize) -> Result<()> {
+        self.push_weighted(category, 1.0)
+    }
+
+    /// Record a weighted hit on a category index
+    pub fn push_weighted(&mut self, category: usize, weight: f64) -> Result<()> {
+        if category >= self.counts.len() {
+            return Err(DivergenceError::DimensionMismatch {
+                expected: self.counts.len(),
+                got: category + 1,
+            });
+        }
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(DivergenceError::InvalidDistribution(format!(
+                "Weight must be finite and non-negative, got {}",
+                weight
+            )));
+        }
+        self.counts[category] += weight;
+        self.n_pending += 1;
+        Ok(())
+    }
+
+    /// Record a hit on a named category
+    pub fn push_token(&mut self, token: &str) -> Result<()> {
+        let idx = self
+            .registry
+            .as_ref()
+            .and_then(|r| r.get(token))
+            .ok_or_else(|| DivergenceError::UnknownCategory(token.to_string()))?;
+        self.push(idx)
+    }
+
+    /// Record hits from an iterator of category indices
+    pub fn extend<I: IntoIterator<Item = usize>>(&mut self, categories: I) -> Result<()> {
+        for category in categories {
+            self.push(category)?;
+        }
+        Ok(())
+    }
+
+    /// Record hits from an iterator of named categories
+    pub fn extend_tokens<'a, I: IntoIterator<Item = &'a str>>(&mut self, tokens: I) -> Result<()> {
+        for token in tokens {
+            self.push_token(token)?;
+        }
+        Ok(())
+    }
+
+    /// Emit the normalized observation vector for the current window and reset
+    ///
+    /// Returns `None` if nothing was recorded since the last flush.
+    pub fn flush(&mut self) -> Option<Vec<f64>> {
+        if self.n_pending == 0 {
+            return None;
+        }
+
+        for (df, &c) in self.document_frequency.iter_mut().zip(self.counts.iter()) {
+            if c > 0.0 {
+                *df += 1.0;
+            }
+        }
+        self.n_windows += 1;
+
+        let n = self.counts.len();
+        let mut observation = std::mem::replace(&mut self.counts, vec![0.0; n]);
+        self.n_pending = 0;
+
+        if self.weighting == TermWeighting::TfIdf {
+            let n = self.n_windows as f64;
+            for (x, &df) in observation.iter_mut().zip(self.document_frequency.iter()) {
+                *x *= ((1.0 + n) / (1.0 + df)).ln() + 1.0;
+            }
+        }
+
+        let sum: f64 = observation.iter().sum();
+        if sum <= 0.0 {
+            return None;
+        }
+        for x in observation.iter_mut() {
+            *x /= sum;
+        }
+        Some(observation)
+    }
+
+    /// Flush the current window straight into an actor's scheme
+    ///
+    /// Returns `Ok(false)` if there was nothing to flush.
+    pub fn flush_into(
+        &mut self,
+        model: &mut CompressionDynamicsModel,
+        actor_id: &str,
+        timestamp_ms: Option<i64>,
+    ) -> Result<bool> {
+        match self.flush() {
+            Some(observation) => {
+                model.update_scheme(actor_id, &observation, timestamp_ms)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flush_normalizes_and_resets() {
+        let mut builder = DistributionBuilder::new(3);
+        builder.extend([0, 0, 1, 0]).unwrap();
+
+        let obs = builder.flush().unwrap();
+        assert!((obs[0] - 0.75).abs() < 1e-12);
+        assert!((obs[1] - 0.25).abs() < 1e-12);
+        assert_eq!(obs[2], 0.0);
+
+        assert_eq!(builder.pending(), 0);
+        assert!(builder.flush().is_none());
+        assert!(builder.push(3).is_err());
+    }
+
+    #[test]
+    fn test_named_tokens() {
+        let names: Vec<String> = ["cooperate", "threaten", "protest"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut builder = DistributionBuilder::with_categories(&names);
+
+        builder
+            .extend_tokens(["threaten", "protest", "threaten"])
+            .unwrap();
+        let obs = builder.flush().unwrap();
+        assert!(obs[1] > obs[2]);
+
+        assert!(matches!(
+            builder.push_token("invade"),
+            Err(DivergenceError::UnknownCategory(_))
+        ));
+    }
+
+    #[test]
+    fn test_tfidf_downweights_ubiquitous_categories() {
+        let mut builder = DistributionBuilder::new(2).with_weighting(TermWeighting::TfIdf);
+
+        builder.extend([0, 0]).unwrap();
+        builder.flush().unwrap();
+
+        // Category 0 appears in every window, category 1 only in this one
+        builder.extend([0, 1]).unwrap();
+        let obs = builder.flush().unwrap();
+        assert!(obs[1] > obs[0]);
+    }
+
+    #[test]
+    fn test_flush_into_model() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", None, None);
+
+        let mut builder = DistributionBuilder::new(3);
+        assert!(!builder.flush_into(&mut model, "A", Some(0)).unwrap());
+
+        builder.extend([2, 2, 2]).unwrap();
+        assert!(builder.flush_into(&mut model, "A", Some(1)).unwrap());
+        assert!(model.get_scheme("A").unwrap().distribution()[2] > 1.0 / 3.0);
+    }
+}
@@ -0,0 +1,344 @@
+//! Granger-style influence testing between actors.
+//!
+//! Actor X Granger-causes actor Y when X's past improves a prediction of Y
+//! beyond what Y's own past gives. Both series are aligned on shared
+//! timestamps and two least-squares autoregressions of order `p` are fit:
+//!
+//! ```text
+//! restricted:   y_t = c + Σ_{i=1..p} a_i y_{t-i}
+//! unrestricted: y_t = c + Σ_{i=1..p} a_i y_{t-i} + Σ_{i=1..p} b_i x_{t-i}
+//!
+//! LR = n · ln(RSS_r / RSS_u)  ~  χ²(p)   under "X does not precede Y"
+//! ```
+//!
+//! The series per actor is one of:
+//! - entropy of each recorded scheme
+//! - grievance: the prediction error each observation added
+//! - Φ: mean recorded Φ over the actor's dyads at each timestamp
+//!
+//! Running the test over every ordered pair gives a directed influence
+//! matrix for the tension network. Granger precedence is predictive, not
+//! causal in the structural sense.
+
+use crate::covariates::solve;
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::stats::chi_squared_sf;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Per-actor series the test runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CausalSignal {
+    /// Entropy of each recorded scheme
+    #[default]
+    Entropy,
+    /// Prediction error each observation added to grievance
+    Grievance,
+    /// Mean recorded Φ over the actor's dyads
+    Phi,
+}
+
+/// Granger test settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GrangerConfig {
+    pub signal: CausalSignal,
+    /// Autoregressive order p
+    pub lags: usize,
+    /// Significance level for [`InfluenceMatrix::significant`]
+    pub alpha: f64,
+}
+
+impl Default for GrangerConfig {
+    fn default() -> Self {
+        Self {
+            signal: CausalSignal::Entropy,
+            lags: 2,
+            alpha: 0.05,
+        }
+    }
+}
+
+/// Outcome of one directed test, cause → effect
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrangerResult {
+    pub cause: String,
+    pub effect: String,
+    pub signal: CausalSignal,
+    pub lags: usize,
+    /// Regression rows after dropping the first `lags` samples
+    pub n_obs: usize,
+    /// Likelihood-ratio statistic
+    pub statistic: f64,
+    pub p_value: f64,
+    /// Share of the restricted residual variance the cause explains
+    pub strength: f64,
+}
+
+impl GrangerResult {
+    /// Whether the cause precedes the effect at level `alpha`
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+/// Directed Granger tests over every ordered actor pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfluenceMatrix {
+    pub config: GrangerConfig,
+    pub actors: Vec<String>,
+    /// `p_values[cause][effect]`; `None` on the diagonal and where a pair
+    /// has too little aligned history
+    pub p_values: Vec<Vec<Option<f64>>>,
+    /// `strength[cause][effect]`, 0 where untested
+    pub strength: Vec<Vec<f64>>,
+    results: Vec<GrangerResult>,
+}
+
+impl InfluenceMatrix {
+    /// Test result for one ordered pair
+    pub fn result(&self, cause: &str, effect: &str) -> Option<&GrangerResult> {
+        self.results
+            .iter()
+            .find(|r| r.cause == cause && r.effect == effect)
+    }
+
+    /// All completed tests
+    pub fn results(&self) -> &[GrangerResult] {
+        &self.results
+    }
+
+    /// Significant influences at the configured alpha, most significant first
+    pub fn significant(&self) -> Vec<&GrangerResult> {
+        let mut hits: Vec<&GrangerResult> = self
+            .results
+            .iter()
+            .filter(|r| r.is_significant(self.config.alpha))
+            .collect();
+        hits.sort_by(|a, b| {
+            a.p_value
+                .total_cmp(&b.p_value)
+                .then(b.strength.total_cmp(&a.strength))
+        });
+        hits
+    }
+
+    /// Actors ranked by how many others they significantly precede
+    pub fn influencers(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> = self.actors.iter().map(|a| (a.as_str(), 0)).collect();
+        for r in self.significant() {
+            if let Some(entry) = counts.iter_mut().find(|(a, _)| *a == r.cause) {
+                entry.1 += 1;
+            }
+        }
+        counts.sort_by_key(|&(_, n)| Reverse(n));
+        counts
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Test whether `cause`'s recorded dynamics precede `effect`'s
+    pub fn granger_test(
+        &self,
+        cause: &str,
+        effect: &str,
+        config: &GrangerConfig,
+    ) -> Result<GrangerResult> {
+        let cause = self.require_actor(cause)?;
+        let effect = self.require_actor(effect)?;
+        if config.lags == 0 {
+            return Err(DivergenceError::ConfigError(
+                "Granger test needs at least one lag".to_string(),
+            ));
+        }
+
+        let x_series = self.causal_series(cause, config.signal);
+        let y_series = self.causal_series(effect, config.signal);
+        let (x, y): (Vec<f64>, Vec<f64>) = x_series
+            .iter()
+            .filter_map(|(t, x)| y_series.get(t).map(|y| (*x, *y)))
+            .unzip();
+
+        let p = config.lags;
+        let n_obs = y.len().saturating_sub(p);
+        // Unrestricted model has 2p + 1 coefficients; keep a few spare rows
+        if n_obs < 2 * p + 4 {
+            return Err(DivergenceError::ConfigError(format!(
+                "{} aligned samples are too few for a lag-{} Granger test",
+                y.len(),
+                p
+            )));
+        }
+
+        let rss_restricted = residual_sum_of_squares(&y, &[&y], p)?;
+        if rss_restricted < 1e-15 {
+            return Err(DivergenceError::NumericalError(format!(
+                "{} series is perfectly predictable from its own past",
+                effect
+            )));
+        }
+        let rss_unrestricted = residual_sum_of_squares(&y, &[&y, &x], p)?.max(1e-300);
+
+        let statistic = (n_obs as f64 * (rss_restricted / rss_unrestricted).ln()).max(0.0);
+        Ok(GrangerResult {
+            cause: cause.to_string(),
+            effect: effect.to_string(),
+            signal: config.signal,
+            lags: p,
+            n_obs,
+            statistic,
+            p_value: chi_squared_sf(statistic, p),
+            strength: (1.0 - rss_unrestricted / rss_restricted).clamp(0.0, 1.0),
+        })
+    }
+
+    /// Granger tests over every ordered pair of registered actors
+    ///
+    /// Pairs that cannot be tested are left as `None`.
+    pub fn influence_matrix(&self, config: &GrangerConfig) -> InfluenceMatrix {
+        let actors: Vec<String> = self.schemes.keys().cloned().collect();
+        let n = actors.len();
+        let mut p_values = vec![vec![None; n]; n];
+        let mut strength = vec![vec![0.0; n]; n];
+        let mut results = Vec::new();
+
+        for (i, cause) in actors.iter().enumerate() {
+            for (j, effect) in actors.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                if let Ok(result) = self.granger_test(cause, effect, config) {
+                    p_values[i][j] = Some(result.p_value);
+                    strength[i][j] = result.strength;
+                    results.push(result);
+                }
+            }
+        }
+
+        InfluenceMatrix {
+            config: *config,
+            actors,
+            p_values,
+            strength,
+            results,
+        }
+    }
+
+    /// An actor's series for `signal`, keyed by timestamp
+    fn causal_series(&self, actor: &str, signal: CausalSignal) -> BTreeMap<i64, f64> {
+        match signal {
+            CausalSignal::Entropy => self
+                .history
+                .iter()
+                .filter(|e| e.actor_id == actor)
+                .map(|e| (e.timestamp_ms, e.scheme.entropy()))
+                .collect(),
+            CausalSignal::Grievance => self
+                .history
+                .iter()
+                .filter(|e| e.actor_id == actor)
+                .map(|e| (e.timestamp_ms, e.prediction_error))
+                .collect(),
+            CausalSignal::Phi => {
+                let mut sums: BTreeMap<i64, (f64, usize)> = BTreeMap::new();
+                for p in &self.potentials {
+                    let Some(t) = p.timestamp_ms else { continue };
+                    if p.actor_a == actor || p.actor_b == actor {
+                        let entry = sums.entry(t).or_insert((0.0, 0));
+                        entry.0 += p.phi;
+                        entry.1 += 1;
+                    }
+                }
+                sums.into_iter()
+                    .map(|(t, (sum, count))| (t, sum / count as f64))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// RSS of regressing `y[t]` on an intercept and `p` lags of each regressor
+fn residual_sum_of_squares(y: &[f64], regressors: &[&[f64]], p: usize) -> Result<f64> {
+    let k = 1 + regressors.len() * p;
+    let row = |t: usize| -> Vec<f64> {
+        let mut r = Vec::with_capacity(k);
+        r.push(1.0);
+        for series in regressors {
+            r.extend((1..=p).map(|lag| series[t - lag]));
+        }
+        r
+    };
+
+    let mut xtx = vec![0.0; k * k];
+    let mut xty = vec![0.0; k];
+    for (t, &target) in y.iter().enumerate().skip(p) {
+        let r = row(t);
+        for i in 0..k {
+            xty[i] += r[i] * target;
+            for j in 0..k {
+                xtx[i * k + j] += r[i] * r[j];
+            }
+        }
+    }
+    let beta = solve(&mut xtx, xty, k).map_err(|_| {
+        DivergenceError::NumericalError(
+            "Granger regressors are collinear; vary the series or lower the lag".to_string(),
+        )
+    })?;
+
+    Ok((p..y.len())
+        .map(|t| {
+            let fitted: f64 = row(t).iter().zip(&beta).map(|(x, b)| x * b).sum();
+            (y[t] - fitted).powi(2)
+        })
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_granger_detects_leader() {
+        let mut model = CompressionDynamicsModel::new(3);
+        for actor in ["A", "B", "C"] {
+            model.register_actor(actor, None, None);
+        }
+
+        // B follows A's previous observation; C moves on its own
+        let mut seed = 11u64;
+        let mut noise = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (seed >> 33) as f64 / (1u64 << 31) as f64
+        };
+        let obs = |w: f64| [w, (1.0 - w) * 0.5, (1.0 - w) * 0.5];
+        let mut prev_a = 0.5;
+        for t in 0..120 {
+            let a = 0.1 + 0.8 * noise();
+            let c = 0.1 + 0.8 * noise();
+            let b = (0.9 * prev_a + 0.1 * noise()).clamp(0.05, 0.95);
+            model.update_scheme("A", &obs(a), Some(t)).unwrap();
+            model.update_scheme("B", &obs(b), Some(t)).unwrap();
+            model.update_scheme("C", &obs(c), Some(t)).unwrap();
+            prev_a = a;
+        }
+
+        let config = GrangerConfig::default();
+        let ab = model.granger_test("A", "B", &config).unwrap();
+        assert!(ab.is_significant(0.01));
+        assert_eq!(ab.n_obs, 118);
+
+        let matrix = model.influence_matrix(&config);
+        assert_eq!(matrix.actors, vec!["A", "B", "C"]);
+        assert!(matrix.p_values[0][0].is_none());
+        let top = matrix.significant()[0];
+        assert_eq!((top.cause.as_str(), top.effect.as_str()), ("A", "B"));
+        assert_eq!(matrix.influencers()[0].0, "A");
+        assert!(matrix.result("C", "B").unwrap().p_value > ab.p_value);
+
+        assert!(model
+            .granger_test("A", "B", &GrangerConfig { lags: 80, ..config })
+            .is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! CloudEvents envelopes for alerts.
+//!
+//! Wraps [`DivergenceAlert`]s in CloudEvents 1.0 envelopes (JSON structured
+//! mode), so event routers (Knative triggers, EventBridge rules) can route
+//! them on the standard attributes without an adapter:
+//!
+//! ```text
+//! {
+//!   "specversion": "1.0",
+//!   "id": "<alert_id>",
+//!   "source": "/stations/north",
+//!   "type": "io.divergence.alert.critical",
+//!   "subject": "RUS/UKR",
+//!   "time": "2023-11-14T22:13:20.000Z",
+//!   "datacontenttype": "application/json",
+//!   "risklevel": "critical",
+//!   "data": { ...alert... }
+//! }
+//! ```
+//!
+//! `type` and `subject` are templates over `{risk}`, `{actor_a}` and
+//! `{actor_b}`; `risklevel` is an extension attribute for filters that only
+//! match exact values. The alert ID doubles as the CloudEvents `id`, so
+//! `source` + `id` stays unique across retries of the same alert.
+//!
+//! Sinks with a JSON payload take a [`CloudEventsConfig`] to emit envelopes
+//! instead of bare alerts.
+
+use crate::error::{DivergenceError, Result};
+use crate::seasonal::civil_from_days;
+use crate::streaming::DivergenceAlert;
+use serde::{Deserialize, Serialize};
+
+/// CloudEvents specification version emitted
+pub const CLOUDEVENTS_SPEC_VERSION: &str = "1.0";
+
+/// Content type of a structured-mode CloudEvent
+pub const CLOUDEVENTS_CONTENT_TYPE: &str = "application/cloudevents+json";
+
+/// Envelope attributes for emitted alerts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CloudEventsConfig {
+    /// `source` attribute: URI-reference identifying this engine
+    pub source: String,
+    /// `type` attribute template
+    pub event_type: String,
+    /// `subject` attribute template (`None`: omitted)
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// `dataschema` attribute: URI of the alert schema (`None`: omitted)
+    #[serde(default)]
+    pub data_schema: Option<String>,
+}
+
+impl CloudEventsConfig {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            event_type: "io.divergence.alert.{risk}".to_string(),
+            subject: Some("{actor_a}/{actor_b}".to_string()),
+            data_schema: None,
+        }
+    }
+
+    /// Use a `type` template
+    pub fn with_type(mut self, event_type: impl Into<String>) -> Self {
+        self.event_type = event_type.into();
+        self
+    }
+
+    /// Use a `subject` template, or omit the attribute
+    pub fn with_subject(mut self, subject: Option<String>) -> Self {
+        self.subject = subject;
+        self
+    }
+
+    /// Reference a schema for the alert payload
+    pub fn with_data_schema(mut self, data_schema: impl Into<String>) -> Self {
+        self.data_schema = Some(data_schema.into());
+        self
+    }
+
+    /// Envelope for an alert
+    pub fn envelope(&self, alert: &DivergenceAlert) -> CloudEvent {
+        CloudEvent {
+            specversion: CLOUDEVENTS_SPEC_VERSION.to_string(),
+            id: alert.alert_id.clone(),
+            source: self.source.clone(),
+            event_type: fill_template(&self.event_type, alert),
+            subject: self.subject.as_ref().map(|s| fill_template(s, alert)),
+            time: rfc3339_ms(alert.timestamp_ms),
+            datacontenttype: "application/json".to_string(),
+            dataschema: self.data_schema.clone(),
+            risklevel: alert.risk_level.as_str().to_lowercase(),
+            data: alert.clone(),
+        }
+    }
+
+    /// Structured-mode JSON of an alert's envelope
+    pub fn encode(&self, alert: &DivergenceAlert) -> Result<Vec<u8>> {
+        serde_json::to_vec(&self.envelope(alert))
+            .map_err(|e| DivergenceError::SerializationError(e.to_string()))
+    }
+}
+
+/// CloudEvents 1.0 envelope around an alert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudEvent {
+    pub specversion: String,
+    pub id: String,
+    pub source: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// RFC 3339 timestamp of the alert
+    pub time: String,
+    pub datacontenttype: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dataschema: Option<String>,
+    /// Extension attribute: lowercase risk level
+    pub risklevel: String,
+    pub data: DivergenceAlert,
+}
+
+/// JSON payload for an alert: bare, or in an envelope when configured
+pub fn alert_payload(
+    alert: &DivergenceAlert,
+    cloudevents: Option<&CloudEventsConfig>,
+) -> Result<Vec<u8>> {
+    match cloudevents {
+        Some(config) => config.encode(alert),
+        None => serde_json::to_vec(alert)
+            .map_err(|e| DivergenceError::SerializationError(e.to_string())),
+    }
+}
+
+fn fill_template(template: &str, alert: &DivergenceAlert) -> String {
+    template
+        .replace("{risk}", &alert.risk_level.as_str().to_lowercase())
+        .replace("{actor_a}", &alert.actor_a)
+        .replace("{actor_b}", &alert.actor_b)
+}
+
+/// UTC RFC 3339 timestamp with milliseconds
+pub(crate) fn rfc3339_ms(timestamp_ms: i64) -> String {
+    const MS_PER_DAY: i64 = 86_400_000;
+    let (year, month, day) = civil_from_days(timestamp_ms.div_euclid(MS_PER_DAY));
+    let ms = timestamp_ms.rem_euclid(MS_PER_DAY);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::RiskLevel;
+    use serde_json::Value;
+
+    fn alert() -> DivergenceAlert {
+        DivergenceAlert {
+            alert_id: "a1".to_string(),
+            actor_a: "RUS".to_string(),
+            actor_b: "UKR".to_string(),
+            phi: 4.5,
+            js: 0.4,
+            phi_z: None,
+            phi_adjusted: None,
+            p_value: None,
+            robustness: None,
+            d_phi_dt: 0.1,
+            risk_level: RiskLevel::Critical,
+            escalation_probability: 0.8,
+            grievance_a_to_b: 0.0,
+            grievance_b_to_a: 0.0,
+            timestamp_ms: 1_700_000_000_123,
+            reason: "test".to_string(),
+            latency: None,
+        }
+    }
+
+    #[test]
+    fn test_cloudevents_envelope() {
+        let config = CloudEventsConfig::new("/stations/north");
+        let json: Value = serde_json::from_slice(&config.encode(&alert()).unwrap()).unwrap();
+        assert_eq!(json["specversion"], "1.0");
+        assert_eq!(json["id"], "a1");
+        assert_eq!(json["source"], "/stations/north");
+        assert_eq!(json["type"], "io.divergence.alert.critical");
+        assert_eq!(json["subject"], "RUS/UKR");
+        assert_eq!(json["time"], "2023-11-14T22:13:20.123Z");
+        assert_eq!(json["risklevel"], "critical");
+        assert_eq!(json["data"]["phi"], 4.5);
+        assert!(json.get("dataschema").is_none());
+
+        let config = config
+            .with_type("org.example.conflict")
+            .with_subject(None)
+            .with_data_schema("https://example.org/alert.json");
+        let event = config.envelope(&alert());
+        assert_eq!(event.event_type, "org.example.conflict");
+        let json = serde_json::to_value(&event).unwrap();
+        assert!(json.get("subject").is_none());
+        assert_eq!(json["dataschema"], "https://example.org/alert.json");
+
+        // Bare alerts unless configured
+        let bare: Value = serde_json::from_slice(&alert_payload(&alert(), None).unwrap()).unwrap();
+        assert_eq!(bare["alert_id"], "a1");
+
+        assert_eq!(rfc3339_ms(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(rfc3339_ms(-1), "1969-12-31T23:59:59.999Z");
+        assert_eq!(rfc3339_ms(951_782_400_000), "2000-02-29T00:00:00.000Z");
+    }
+}
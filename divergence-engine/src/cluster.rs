@@ -0,0 +1,279 @@
+//! Clustering of actors by worldview.
+//!
+//! Blocs show up in the pairwise divergence matrix as groups of actors that
+//! are close to each other and far from everyone else. `cluster_actors`
+//! finds them with k-medoids (PAM): each cluster is represented by one of
+//! its own actors, so centers are real worldviews rather than averages, and
+//! any divergence can serve as the dissimilarity, metric or not.
+//!
+//! Initialization is the greedy BUILD step and refinement the SWAP step,
+//! both deterministic, so the same model always yields the same clusters.
+//! Silhouette scores `s = (b - a) / max(a, b)` rate each assignment: `a` is
+//! the mean distance to the actor's own cluster, `b` to the nearest other
+//! cluster. Values near 1 mark clear members, near 0 actors on a border.
+
+use crate::divergence::{hellinger_distance, jensen_shannon, symmetric_kl};
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::CompressionScheme;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Dissimilarity used to compare actors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ClusterMetric {
+    /// Jensen-Shannon distance √JS (a true metric, bounded by 1)
+    #[default]
+    JensenShannon,
+    /// Hellinger distance
+    Hellinger,
+    /// Symmetric KL (Φ); unbounded and not a metric
+    SymmetricKl,
+}
+
+impl ClusterMetric {
+    /// Dissimilarity between two distributions
+    pub fn distance(&self, p: &[f64], q: &[f64]) -> Result<f64> {
+        match self {
+            ClusterMetric::JensenShannon => Ok(jensen_shannon(p, q)?.max(0.0).sqrt()),
+            ClusterMetric::Hellinger => hellinger_distance(p, q),
+            ClusterMetric::SymmetricKl => symmetric_kl(p, q),
+        }
+    }
+}
+
+/// Symmetric pairwise distance matrix over schemes
+pub fn distance_matrix(
+    schemes: &[&CompressionScheme],
+    metric: ClusterMetric,
+) -> Result<Vec<Vec<f64>>> {
+    let n = schemes.len();
+    let mut dist = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let d = metric.distance(schemes[i].distribution(), schemes[j].distribution())?;
+            dist[i][j] = d;
+            dist[j][i] = d;
+        }
+    }
+    Ok(dist)
+}
+
+/// k-medoids (PAM) over a distance matrix
+///
+/// Returns the medoid indices and each point's cluster (an index into the
+/// medoids).
+pub fn k_medoids(dist: &[Vec<f64>], k: usize) -> Result<(Vec<usize>, Vec<usize>)> {
+    let n = dist.len();
+    if k == 0 || k > n {
+        return Err(DivergenceError::ConfigError(format!(
+            "Cluster count must be in 1..={}, got {}",
+            n, k
+        )));
+    }
+
+    let cost = |medoids: &[usize]| -> f64 {
+        (0..n)
+            .map(|i| {
+                medoids
+                    .iter()
+                    .map(|&m| dist[i][m])
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .sum()
+    };
+
+    // BUILD: greedily add the medoid that lowers total cost the most
+    let mut medoids: Vec<usize> = Vec::with_capacity(k);
+    while medoids.len() < k {
+        let mut best = (f64::INFINITY, 0);
+        let candidates: Vec<usize> = (0..n).filter(|c| !medoids.contains(c)).collect();
+        for candidate in candidates {
+            medoids.push(candidate);
+            let c = cost(&medoids);
+            medoids.pop();
+            if c < best.0 {
+                best = (c, candidate);
+            }
+        }
+        medoids.push(best.1);
+    }
+
+    // SWAP: exchange a medoid for a non-medoid while that lowers cost
+    let mut current = cost(&medoids);
+    loop {
+        let mut best = (current, None);
+        let candidates: Vec<usize> = (0..n).filter(|c| !medoids.contains(c)).collect();
+        for slot in 0..k {
+            for &candidate in &candidates {
+                let previous = medoids[slot];
+                medoids[slot] = candidate;
+                let c = cost(&medoids);
+                medoids[slot] = previous;
+                if c < best.0 - 1e-12 {
+                    best = (c, Some((slot, candidate)));
+                }
+            }
+        }
+        match best.1 {
+            Some((slot, candidate)) => {
+                medoids[slot] = candidate;
+                current = best.0;
+            }
+            None => break,
+        }
+    }
+
+    let assignments = (0..n)
+        .map(|i| {
+            (0..k)
+                .min_by(|&a, &b| dist[i][medoids[a]].total_cmp(&dist[i][medoids[b]]))
+                .unwrap_or(0)
+        })
+        .collect();
+    Ok((medoids, assignments))
+}
+
+/// Silhouette score of every point under an assignment
+///
+/// Points in singleton clusters, and all points when there is only one
+/// cluster, score 0.
+pub fn silhouette_scores(dist: &[Vec<f64>], assignments: &[usize]) -> Vec<f64> {
+    let n = dist.len();
+    let k = assignments.iter().max().map_or(0, |&c| c + 1);
+    let mut sizes = vec![0usize; k];
+    for &c in assignments {
+        sizes[c] += 1;
+    }
+
+    (0..n)
+        .map(|i| {
+            let own = assignments[i];
+            if sizes[own] <= 1 {
+                return 0.0;
+            }
+            let mut sums = vec![0.0; k];
+            for j in (0..n).filter(|&j| j != i) {
+                sums[assignments[j]] += dist[i][j];
+            }
+            let a = sums[own] / (sizes[own] - 1) as f64;
+            let b = (0..k)
+                .filter(|&c| c != own && sizes[c] > 0)
+                .map(|c| sums[c] / sizes[c] as f64)
+                .fold(f64::INFINITY, f64::min);
+            if !b.is_finite() {
+                return 0.0;
+            }
+            let scale = a.max(b);
+            if scale > 0.0 {
+                (b - a) / scale
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Actors partitioned into worldview clusters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorClustering {
+    pub metric: ClusterMetric,
+    /// Representative actor of each cluster
+    pub medoids: Vec<String>,
+    /// Cluster index per actor, in registration order
+    pub assignments: IndexMap<String, usize>,
+    /// Silhouette score per actor
+    pub silhouettes: IndexMap<String, f64>,
+    /// Mean silhouette over all actors
+    pub mean_silhouette: f64,
+    /// Sum of distances from each actor to its medoid
+    pub total_cost: f64,
+}
+
+impl ActorClustering {
+    /// Number of clusters
+    pub fn k(&self) -> usize {
+        self.medoids.len()
+    }
+
+    /// Members of a cluster, in registration order
+    pub fn members(&self, cluster: usize) -> Vec<&str> {
+        self.assignments
+            .iter()
+            .filter(|(_, &c)| c == cluster)
+            .map(|(actor, _)| actor.as_str())
+            .collect()
+    }
+
+    /// Cluster an actor belongs to
+    pub fn cluster_of(&self, actor_id: &str) -> Option<usize> {
+        self.assignments.get(actor_id).copied()
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Partition all actors into `k` worldview clusters
+    pub fn cluster_actors(&self, k: usize, metric: ClusterMetric) -> Result<ActorClustering> {
+        let schemes: Vec<&CompressionScheme> = self.schemes.values().collect();
+        let dist = distance_matrix(&schemes, metric)?;
+        let (medoids, assignments) = k_medoids(&dist, k)?;
+        let silhouettes = silhouette_scores(&dist, &assignments);
+
+        let total_cost = assignments
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| dist[i][medoids[c]])
+            .sum();
+        let mean_silhouette = silhouettes.iter().sum::<f64>() / silhouettes.len() as f64;
+        let names: Vec<String> = self.schemes.keys().cloned().collect();
+
+        Ok(ActorClustering {
+            metric,
+            medoids: medoids.iter().map(|&m| names[m].clone()).collect(),
+            assignments: names.iter().cloned().zip(assignments).collect(),
+            silhouettes: names.into_iter().zip(silhouettes).collect(),
+            mean_silhouette,
+            total_cost,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bloc_model() -> CompressionDynamicsModel {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("USA", Some(vec![0.8, 0.1, 0.1]), None);
+        model.register_actor("RUS", Some(vec![0.1, 0.1, 0.8]), None);
+        model.register_actor("GBR", Some(vec![0.75, 0.15, 0.1]), None);
+        model.register_actor("CHN", Some(vec![0.1, 0.2, 0.7]), None);
+        model.register_actor("FRA", Some(vec![0.7, 0.2, 0.1]), None);
+        model
+    }
+
+    #[test]
+    fn test_cluster_actors_finds_blocs() {
+        let model = bloc_model();
+        let clusters = model
+            .cluster_actors(2, ClusterMetric::JensenShannon)
+            .unwrap();
+
+        assert_eq!(clusters.k(), 2);
+        let west = clusters.cluster_of("USA").unwrap();
+        assert_eq!(clusters.members(west), vec!["USA", "GBR", "FRA"]);
+        assert_eq!(clusters.cluster_of("RUS"), clusters.cluster_of("CHN"));
+        assert!(clusters.medoids.contains(&"GBR".to_string()));
+        assert!(clusters.mean_silhouette > 0.7);
+
+        assert!(model.cluster_actors(0, ClusterMetric::Hellinger).is_err());
+        assert!(model.cluster_actors(6, ClusterMetric::Hellinger).is_err());
+    }
+
+    #[test]
+    fn test_silhouette_edge_cases() {
+        let dist = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+        assert_eq!(silhouette_scores(&dist, &[0, 1]), vec![0.0, 0.0]);
+        assert_eq!(silhouette_scores(&dist, &[0, 0]), vec![0.0, 0.0]);
+    }
+}
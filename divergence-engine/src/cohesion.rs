@@ -0,0 +1,192 @@
+//! Intra-actor cohesion.
+//!
+//! For a parent in the [actor hierarchy](crate::hierarchy), cohesion is the
+//! mean pairwise Φ among its direct children's rolled-up schemes. Rising
+//! internal divergence means the actor is fragmenting, a precursor signal
+//! distinct from conflict with other actors.
+//!
+//! The trend is the least-squares slope of recorded cohesion samples (plus
+//! the current value) per sample, over the model's `phi_baseline_window`.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use serde::{Deserialize, Serialize};
+
+/// A recorded cohesion measurement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CohesionSample {
+    pub timestamp_ms: i64,
+    pub mean_divergence: f64,
+}
+
+/// Internal divergence of a parent actor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cohesion {
+    pub parent: String,
+    pub n_children: usize,
+    /// Mean pairwise Φ among children (0 = fully cohesive)
+    pub mean_divergence: f64,
+    /// Most divergent pair of children and their Φ
+    pub widest_split: (String, String, f64),
+    /// Slope of mean divergence per sample, once a sample is recorded
+    pub trend: Option<f64>,
+}
+
+impl Cohesion {
+    /// Whether internal divergence is rising faster than `min_slope`
+    pub fn is_fragmenting(&self, min_slope: f64) -> bool {
+        self.trend.is_some_and(|t| t > min_slope)
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Current cohesion of a parent, without recording it
+    pub fn cohesion(&self, parent: &str) -> Result<Cohesion> {
+        let children = self.children_of(parent);
+        if children.len() < 2 {
+            return Err(DivergenceError::ConfigError(format!(
+                "{} needs at least two children for cohesion",
+                parent
+            )));
+        }
+        let schemes = children
+            .iter()
+            .map(|(child, _)| self.rolled_up_scheme(child))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut total = 0.0;
+        let mut pairs = 0;
+        let mut widest = (0, 1, f64::NEG_INFINITY);
+        for i in 0..schemes.len() {
+            for j in (i + 1)..schemes.len() {
+                let phi = schemes[i].symmetric_divergence(&schemes[j])?;
+                total += phi;
+                pairs += 1;
+                if phi > widest.2 {
+                    widest = (i, j, phi);
+                }
+            }
+        }
+        let mean_divergence = total / pairs as f64;
+
+        let trend = self.cohesion_history.get(parent).and_then(|samples| {
+            let window = self.config.phi_baseline_window.max(2);
+            let mut series: Vec<f64> = samples
+                .iter()
+                .rev()
+                .take(window - 1)
+                .rev()
+                .map(|s| s.mean_divergence)
+                .collect();
+            series.push(mean_divergence);
+            linear_slope(&series)
+        });
+
+        Ok(Cohesion {
+            parent: parent.to_string(),
+            n_children: schemes.len(),
+            mean_divergence,
+            widest_split: (
+                children[widest.0].0.to_string(),
+                children[widest.1].0.to_string(),
+                widest.2,
+            ),
+            trend,
+        })
+    }
+
+    /// Compute cohesion and append it to the parent's cohesion history
+    pub fn record_cohesion(&mut self, parent: &str, timestamp_ms: i64) -> Result<Cohesion> {
+        let cohesion = self.cohesion(parent)?;
+        self.cohesion_history
+            .entry(parent.to_string())
+            .or_default()
+            .push(CohesionSample {
+                timestamp_ms,
+                mean_divergence: cohesion.mean_divergence,
+            });
+        Ok(cohesion)
+    }
+
+    /// Recorded cohesion samples of a parent, oldest first
+    pub fn cohesion_history(&self, parent: &str) -> &[CohesionSample] {
+        self.cohesion_history
+            .get(parent)
+            .map_or(&[], |samples| samples.as_slice())
+    }
+
+    /// Cohesion of every parent whose internal divergence is rising faster
+    /// than `min_slope`
+    pub fn fragmentation_alerts(&self, min_slope: f64) -> Vec<Cohesion> {
+        let mut seen = std::collections::HashSet::new();
+        self.parents
+            .values()
+            .map(|link| link.parent.as_str())
+            .filter(|p| seen.insert(*p))
+            .filter_map(|p| self.cohesion(p).ok())
+            .filter(|c| c.is_fragmenting(min_slope))
+            .collect()
+    }
+}
+
+/// Least-squares slope of a series against its index
+fn linear_slope(series: &[f64]) -> Option<f64> {
+    if series.len() < 2 {
+        return None;
+    }
+    let n = series.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = series.iter().sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (i, y) in series.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        cov += dx * (y - mean_y);
+        var += dx * dx;
+    }
+    Some(cov / var)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cohesion_and_fragmentation() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("HAWKS", Some(vec![0.5, 0.3, 0.2]), None);
+        model.register_actor("DOVES", Some(vec![0.4, 0.3, 0.3]), None);
+        model.register_actor("CENTER", Some(vec![0.45, 0.3, 0.25]), None);
+        for child in ["HAWKS", "DOVES", "CENTER"] {
+            model.set_parent(child, "STATE", 1.0).unwrap();
+        }
+
+        let first = model.record_cohesion("STATE", 0).unwrap();
+        assert_eq!(first.n_children, 3);
+        assert_eq!(first.trend, None);
+        assert_eq!(first.widest_split.0, "HAWKS");
+        assert_eq!(first.widest_split.1, "DOVES");
+
+        // Factions pull apart
+        for t in 1..5 {
+            model
+                .update_scheme("HAWKS", &[0.9, 0.05, 0.05], Some(t))
+                .unwrap();
+            model
+                .update_scheme("DOVES", &[0.05, 0.05, 0.9], Some(t))
+                .unwrap();
+            model.record_cohesion("STATE", t).unwrap();
+        }
+        let current = model.cohesion("STATE").unwrap();
+        assert!(current.mean_divergence > first.mean_divergence);
+        assert!(current.is_fragmenting(0.0));
+        assert_eq!(model.cohesion_history("STATE").len(), 5);
+
+        let alerts = model.fragmentation_alerts(0.0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].parent, "STATE");
+
+        model.remove_parent("CENTER");
+        model.remove_parent("DOVES");
+        assert!(model.cohesion("STATE").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Cross-correlation and lag analysis between dyad Φ series.
+//!
+//! Tension spreading from one dyad to another shows up as one recorded Φ
+//! series leading the other. The two series are aligned on the timestamps
+//! both recorded (potentials without a timestamp are skipped), then
+//! correlated at every lag in `-max_lag..=max_lag`:
+//!
+//! ```text
+//! r(k) = corr(Φ₁[t], Φ₂[t + k])     k > 0: dyad 1 leads by k samples
+//! ```
+//!
+//! The best lag has the highest correlation (ties go to the shorter lag).
+//! Raw levels are correlated, so two dyads that merely share a trend also
+//! correlate; treat a lead as a hypothesis, not a cause.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Correlation at one lag
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LagCorrelation {
+    /// Samples by which the first dyad leads the second (negative: trails)
+    pub lag: i64,
+    pub correlation: f64,
+    /// Overlapping samples used
+    pub n: usize,
+}
+
+/// Correlation-by-lag between two dyads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrossCorrelation {
+    pub dyad1: (String, String),
+    pub dyad2: (String, String),
+    /// Aligned samples shared by both series
+    pub n_aligned: usize,
+    /// One entry per lag with enough overlap, most negative lag first
+    pub by_lag: Vec<LagCorrelation>,
+    pub best: Option<LagCorrelation>,
+}
+
+/// Directed edge of the contagion graph: `leader` tension precedes
+/// `follower` tension
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContagionEdge {
+    pub leader: (String, String),
+    pub follower: (String, String),
+    /// Lead in samples (always positive)
+    pub lag: i64,
+    pub correlation: f64,
+}
+
+/// Fewest overlapping samples a lag needs to be scored
+const MIN_OVERLAP: usize = 3;
+
+impl CompressionDynamicsModel {
+    /// Correlate two dyads' recorded Φ series at lags up to `max_lag`
+    pub fn dyad_cross_correlation(
+        &self,
+        dyad1: (&str, &str),
+        dyad2: (&str, &str),
+        max_lag: usize,
+    ) -> Result<CrossCorrelation> {
+        for actor in [dyad1.0, dyad1.1, dyad2.0, dyad2.1] {
+            self.require_actor(actor)?;
+        }
+        let (x, y) = self.aligned_phi(dyad1, dyad2);
+        if x.len() < max_lag + MIN_OVERLAP {
+            return Err(DivergenceError::ConfigError(format!(
+                "{} aligned samples are too few for lags up to {}",
+                x.len(),
+                max_lag
+            )));
+        }
+
+        let max_lag = max_lag as i64;
+        let by_lag: Vec<LagCorrelation> = (-max_lag..=max_lag)
+            .filter_map(|lag| lagged_correlation(&x, &y, lag))
+            .collect();
+        let best = by_lag.iter().copied().reduce(|best, c| {
+            if c.correlation > best.correlation
+                || (c.correlation == best.correlation && c.lag.abs() < best.lag.abs())
+            {
+                c
+            } else {
+                best
+            }
+        });
+
+        Ok(CrossCorrelation {
+            dyad1: (dyad1.0.to_string(), dyad1.1.to_string()),
+            dyad2: (dyad2.0.to_string(), dyad2.1.to_string()),
+            n_aligned: x.len(),
+            by_lag,
+            best,
+        })
+    }
+
+    /// Lead/lag edges between every pair of dyads with recorded history
+    ///
+    /// An edge is kept when the best lag is positive in one direction and
+    /// its correlation reaches `min_correlation`. Pairs with too little
+    /// aligned history are skipped. Strongest edges come first.
+    pub fn contagion_graph(&self, max_lag: usize, min_correlation: f64) -> Vec<ContagionEdge> {
+        let mut dyads: Vec<(&str, &str)> = Vec::new();
+        for p in &self.potentials {
+            let dyad = (p.actor_a.as_str(), p.actor_b.as_str());
+            let known = dyads.iter().any(|&(a, b)| (a, b) == dyad || (b, a) == dyad);
+            if !known {
+                dyads.push(dyad);
+            }
+        }
+
+        let mut edges = Vec::new();
+        for i in 0..dyads.len() {
+            for j in (i + 1)..dyads.len() {
+                let Ok(cc) = self.dyad_cross_correlation(dyads[i], dyads[j], max_lag) else {
+                    continue;
+                };
+                let Some(best) = cc.best.filter(|b| b.lag != 0) else {
+                    continue;
+                };
+                if best.correlation < min_correlation {
+                    continue;
+                }
+                let (leader, follower) = if best.lag > 0 {
+                    (cc.dyad1, cc.dyad2)
+                } else {
+                    (cc.dyad2, cc.dyad1)
+                };
+                edges.push(ContagionEdge {
+                    leader,
+                    follower,
+                    lag: best.lag.abs(),
+                    correlation: best.correlation,
+                });
+            }
+        }
+        edges.sort_by(|a, b| b.correlation.total_cmp(&a.correlation));
+        edges
+    }
+
+    /// Φ values of two dyads at the timestamps both recorded, oldest first
+    fn aligned_phi(&self, dyad1: (&str, &str), dyad2: (&str, &str)) -> (Vec<f64>, Vec<f64>) {
+        let series = |(a, b): (&str, &str)| -> BTreeMap<i64, f64> {
+            self.get_dyad_history(a, b)
+                .into_iter()
+                .filter_map(|p| p.timestamp_ms.map(|t| (t, p.phi)))
+                .collect()
+        };
+        let first = series(dyad1);
+        let second = series(dyad2);
+        first
+            .iter()
+            .filter_map(|(t, x)| second.get(t).map(|y| (*x, *y)))
+            .unzip()
+    }
+}
+
+/// Pearson correlation of `x[t]` with `y[t + lag]`
+fn lagged_correlation(x: &[f64], y: &[f64], lag: i64) -> Option<LagCorrelation> {
+    let shift = lag.unsigned_abs() as usize;
+    let (xs, ys) = if lag >= 0 {
+        (&x[..x.len() - shift], &y[shift..])
+    } else {
+        (&x[shift..], &y[..y.len() - shift])
+    };
+    let n = xs.len();
+    if n < MIN_OVERLAP {
+        return None;
+    }
+    let mean_x = xs.iter().sum::<f64>() / n as f64;
+    let mean_y = ys.iter().sum::<f64>() / n as f64;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (a, b) in xs.iter().zip(ys) {
+        cov += (a - mean_x) * (b - mean_y);
+        var_x += (a - mean_x).powi(2);
+        var_y += (b - mean_y).powi(2);
+    }
+    let denom = (var_x * var_y).sqrt();
+    (denom > 0.0).then(|| LagCorrelation {
+        lag,
+        correlation: cov / denom,
+        n,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_correlation_finds_lead() {
+        let mut model = CompressionDynamicsModel::new(3);
+        for actor in ["A", "B", "C", "D"] {
+            model.register_actor(actor, Some(vec![0.4, 0.3, 0.3]), None);
+        }
+
+        // A's irregular swings reach C two steps later
+        let mut seed = 7u64;
+        let swings: Vec<f64> = (0..42)
+            .map(|_| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                0.35 + 0.6 * ((seed >> 33) as f64 / (1u64 << 31) as f64)
+            })
+            .collect();
+        let swing = |t: i64| swings[(t + 2) as usize];
+        for t in 0..40 {
+            let a = swing(t);
+            let c = swing(t - 2);
+            model
+                .update_scheme("A", &[a, (1.0 - a) / 2.0, (1.0 - a) / 2.0], Some(t))
+                .unwrap();
+            model
+                .update_scheme("C", &[c, (1.0 - c) / 2.0, (1.0 - c) / 2.0], Some(t))
+                .unwrap();
+            model.update_scheme("B", &[0.4, 0.3, 0.3], Some(t)).unwrap();
+            model.update_scheme("D", &[0.4, 0.3, 0.3], Some(t)).unwrap();
+            model.compute_conflict_potential("A", "B").unwrap();
+            model.compute_conflict_potential("C", "D").unwrap();
+        }
+
+        let cc = model
+            .dyad_cross_correlation(("A", "B"), ("C", "D"), 4)
+            .unwrap();
+        assert_eq!(cc.n_aligned, 40);
+        assert_eq!(cc.by_lag.len(), 9);
+        let best = cc.best.unwrap();
+        assert_eq!(best.lag, 2);
+        assert!(best.correlation > 0.9);
+
+        let edges = model.contagion_graph(4, 0.5);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].leader, ("A".to_string(), "B".to_string()));
+        assert_eq!(edges[0].lag, 2);
+
+        assert!(model
+            .dyad_cross_correlation(("A", "B"), ("C", "D"), 40)
+            .is_err());
+        assert!(model
+            .dyad_cross_correlation(("A", "Z"), ("C", "D"), 1)
+            .is_err());
+    }
+}
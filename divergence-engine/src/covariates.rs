@@ -0,0 +1,224 @@
+//! Exogenous covariates for Φ.
+//!
+//! Divergence alone misses drivers that live outside the category space:
+//! trade volume, troop movement indices, sanctions intensity. Covariates
+//! are attached per dyad per timestamp, and a ridge regression of recorded
+//! Φ on those covariates learns how much each one moves divergence:
+//!
+//! ```text
+//! Φ ≈ β₀ + Σ_k β_k · x_k
+//! ```
+//!
+//! The fit keeps only sufficient statistics (XᵀX and Xᵀy), so memory is
+//! O(k²) regardless of how many potentials are recorded. Once fitted, the
+//! covariate-explained part of Φ (`Σ β_k x_k`) enters the escalation logit
+//! alongside Φ itself.
+
+use crate::error::{DivergenceError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Covariate values attached to a dyad at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CovariateSnapshot {
+    pub timestamp_ms: i64,
+    pub values: Vec<f64>,
+}
+
+/// Ridge regression of Φ on named covariates
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CovariateRegression {
+    names: Vec<String>,
+    ridge: f64,
+    /// XᵀX over [1, x₁..x_k], row-major (k+1)²
+    xtx: Vec<f64>,
+    /// Xᵀy over [1, x₁..x_k]
+    xty: Vec<f64>,
+    n_samples: usize,
+    /// Fitted [β₀, β₁..β_k]
+    coefficients: Option<Vec<f64>>,
+}
+
+impl CovariateRegression {
+    /// Create an unfitted regression over the named covariates
+    ///
+    /// `ridge` penalizes the covariate weights (not the intercept).
+    pub fn new(names: Vec<String>, ridge: f64) -> Self {
+        let p = names.len() + 1;
+        Self {
+            names,
+            ridge: ridge.max(0.0),
+            xtx: vec![0.0; p * p],
+            xty: vec![0.0; p],
+            n_samples: 0,
+            coefficients: None,
+        }
+    }
+
+    /// Covariate names, in value order
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Number of covariates
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether no covariates are defined
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Number of (covariates, Φ) samples accumulated
+    pub fn n_samples(&self) -> usize {
+        self.n_samples
+    }
+
+    /// Check a value vector matches the covariate names
+    pub fn check(&self, values: &[f64]) -> Result<()> {
+        if values.len() != self.names.len() {
+            return Err(DivergenceError::DimensionMismatch {
+                expected: self.names.len(),
+                got: values.len(),
+            });
+        }
+        if let Some(bad) = values.iter().find(|v| !v.is_finite()) {
+            return Err(DivergenceError::NumericalError(format!(
+                "Covariate value must be finite, got {}",
+                bad
+            )));
+        }
+        Ok(())
+    }
+
+    /// Accumulate one (covariates, Φ) sample
+    pub fn observe(&mut self, values: &[f64], phi: f64) -> Result<()> {
+        self.check(values)?;
+        let p = self.names.len() + 1;
+        let x = |i: usize| if i == 0 { 1.0 } else { values[i - 1] };
+        for i in 0..p {
+            let xi = x(i);
+            self.xty[i] += xi * phi;
+            for j in 0..p {
+                self.xtx[i * p + j] += xi * x(j);
+            }
+        }
+        self.n_samples += 1;
+        Ok(())
+    }
+
+    /// Solve for coefficients from the accumulated samples
+    pub fn fit(&mut self) -> Result<&[f64]> {
+        let p = self.names.len() + 1;
+        if self.n_samples < p {
+            return Err(DivergenceError::ConfigError(format!(
+                "Need at least {} samples to fit {} covariates, have {}",
+                p,
+                self.names.len(),
+                self.n_samples
+            )));
+        }
+
+        let mut a = self.xtx.clone();
+        for i in 1..p {
+            a[i * p + i] += self.ridge;
+        }
+        let beta = solve(&mut a, self.xty.clone(), p)?;
+        Ok(self.coefficients.insert(beta))
+    }
+
+    /// Fitted [β₀, β₁..β_k], if `fit` has succeeded
+    pub fn coefficients(&self) -> Option<&[f64]> {
+        self.coefficients.as_deref()
+    }
+
+    /// Covariate-explained Φ, Σ β_k x_k (intercept excluded)
+    ///
+    /// `None` until fitted.
+    pub fn contribution(&self, values: &[f64]) -> Option<f64> {
+        let beta = self.coefficients.as_ref()?;
+        if values.len() + 1 != beta.len() {
+            return None;
+        }
+        Some(beta[1..].iter().zip(values).map(|(b, x)| b * x).sum())
+    }
+
+    /// Predicted Φ, β₀ + Σ β_k x_k
+    pub fn predict(&self, values: &[f64]) -> Option<f64> {
+        Some(self.coefficients.as_ref()?[0] + self.contribution(values)?)
+    }
+}
+
+/// Solve A·x = b in place by Gaussian elimination with partial pivoting
+pub(crate) fn solve(a: &mut [f64], mut b: Vec<f64>, n: usize) -> Result<Vec<f64>> {
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i * n + col].abs().total_cmp(&a[j * n + col].abs()))
+            .unwrap_or(col);
+        if a[pivot * n + col].abs() < 1e-12 {
+            return Err(DivergenceError::NumericalError(
+                "Covariate design is singular; add ridge or vary the covariates".to_string(),
+            ));
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap(col * n + k, pivot * n + k);
+            }
+            b.swap(col, pivot);
+        }
+        for row in (col + 1)..n {
+            let factor = a[row * n + col] / a[col * n + col];
+            for k in col..n {
+                a[row * n + k] -= factor * a[col * n + k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let tail: f64 = ((row + 1)..n).map(|k| a[row * n + k] * x[k]).sum();
+        x[row] = (b[row] - tail) / a[row * n + row];
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_linear_relationship() {
+        let mut reg = CovariateRegression::new(vec!["trade".into(), "troops".into()], 0.0);
+        assert!(reg.fit().is_err());
+
+        for i in 0..20 {
+            let trade = (i % 5) as f64;
+            let troops = (i % 3) as f64 * 0.5;
+            let phi = 0.5 - 0.2 * trade + 1.5 * troops;
+            reg.observe(&[trade, troops], phi).unwrap();
+        }
+
+        let beta = reg.fit().unwrap().to_vec();
+        assert!((beta[0] - 0.5).abs() < 1e-9);
+        assert!((beta[1] + 0.2).abs() < 1e-9);
+        assert!((beta[2] - 1.5).abs() < 1e-9);
+        assert!((reg.contribution(&[1.0, 2.0]).unwrap() - 2.8).abs() < 1e-9);
+        assert!(reg.observe(&[1.0], 0.0).is_err());
+    }
+
+    #[test]
+    fn test_constant_covariate_needs_ridge() {
+        let mut reg = CovariateRegression::new(vec!["flat".into()], 0.0);
+        for _ in 0..5 {
+            reg.observe(&[1.0], 2.0).unwrap();
+        }
+        assert!(reg.fit().is_err());
+
+        let mut ridged = CovariateRegression::new(vec!["flat".into()], 1.0);
+        for _ in 0..5 {
+            ridged.observe(&[1.0], 2.0).unwrap();
+        }
+        assert!(ridged.fit().is_ok());
+    }
+}
@@ -0,0 +1,271 @@
+//! Snapshot diffing.
+//!
+//! [`ModelDiff::compute`] compares two model states, typically yesterday's
+//! snapshot against today's, and reports what changed:
+//!
+//! ```text
+//! drift(actor) = Φ(C_old, C_new) = D_KL(old || new) + D_KL(new || old)
+//! ```
+//!
+//! plus actors that appeared or disappeared and dyads (present in both
+//! states) whose [`RiskLevel`] moved. Nothing is recorded on either model.
+//!
+//! An actor whose categories changed is reported with the categories it
+//! gained and lost, and its drift is measured on the categories kept (see
+//! [`crate::support`]).
+
+use crate::error::Result;
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::RiskLevel;
+use crate::support::SharedSupport;
+use serde::{Deserialize, Serialize};
+
+/// How far an actor's scheme moved between snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemeDrift {
+    pub actor_id: String,
+    /// Φ between the actor's old and new self
+    pub phi: f64,
+}
+
+/// Categories an actor gained or lost between snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryChange {
+    pub actor_id: String,
+    /// Categories only in the new scheme
+    pub added: Vec<String>,
+    /// Categories only in the old scheme
+    pub removed: Vec<String>,
+}
+
+/// A dyad whose risk level differs between snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskLevelChange {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub old_phi: f64,
+    pub new_phi: f64,
+    pub old_level: RiskLevel,
+    pub new_level: RiskLevel,
+}
+
+/// Structured differences between two model states
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelDiff {
+    /// Actors only in the new state
+    pub added_actors: Vec<String>,
+    /// Actors only in the old state
+    pub removed_actors: Vec<String>,
+    /// Actors in both states, largest drift first
+    ///
+    /// Actors keeping none of their categories have no drift entry.
+    pub scheme_drift: Vec<SchemeDrift>,
+    /// Actors in both states whose categories changed
+    #[serde(default)]
+    pub category_changes: Vec<CategoryChange>,
+    /// Dyads in both states whose risk level changed
+    pub risk_changes: Vec<RiskLevelChange>,
+}
+
+impl ModelDiff {
+    /// Compare an old model state against a new one
+    pub fn compute(old: &CompressionDynamicsModel, new: &CompressionDynamicsModel) -> Result<Self> {
+        let old_actors = old.actors();
+        let new_actors = new.actors();
+
+        let added_actors = new_actors
+            .iter()
+            .filter(|a| old.get_scheme(a).is_none())
+            .map(|a| a.to_string())
+            .collect();
+        let removed_actors = old_actors
+            .iter()
+            .filter(|a| new.get_scheme(a).is_none())
+            .map(|a| a.to_string())
+            .collect();
+
+        let shared: Vec<&str> = old_actors
+            .into_iter()
+            .filter(|a| new.get_scheme(a).is_some())
+            .collect();
+
+        let mut scheme_drift = Vec::with_capacity(shared.len());
+        let mut category_changes = Vec::new();
+        for actor in &shared {
+            let (Some(before), Some(after)) = (old.get_scheme(actor), new.get_scheme(actor)) else {
+                continue;
+            };
+            let (names_before, names_after) = (before.category_names(), after.category_names());
+            if names_before == names_after {
+                scheme_drift.push(SchemeDrift {
+                    actor_id: actor.to_string(),
+                    phi: before.symmetric_divergence(after)?,
+                });
+                continue;
+            }
+
+            category_changes.push(CategoryChange {
+                actor_id: actor.to_string(),
+                added: names_after
+                    .iter()
+                    .filter(|n| !names_before.contains(n))
+                    .cloned()
+                    .collect(),
+                removed: names_before
+                    .iter()
+                    .filter(|n| !names_after.contains(n))
+                    .cloned()
+                    .collect(),
+            });
+            if let Ok(kept) = before.compare_on_shared_support(after, SharedSupport::Intersection) {
+                scheme_drift.push(SchemeDrift {
+                    actor_id: actor.to_string(),
+                    phi: kept.phi,
+                });
+            }
+        }
+        scheme_drift.sort_by(|a, b| b.phi.total_cmp(&a.phi));
+
+        let mut risk_changes = Vec::new();
+        for i in 0..shared.len() {
+            for j in (i + 1)..shared.len() {
+                let (Some(old_phi), Some(new_phi)) = (
+                    dyad_phi(old, shared[i], shared[j]),
+                    dyad_phi(new, shared[i], shared[j]),
+                ) else {
+                    continue;
+                };
+                let old_level = RiskLevel::from_phi(old_phi);
+                let new_level = RiskLevel::from_phi(new_phi);
+                if old_level != new_level {
+                    risk_changes.push(RiskLevelChange {
+                        actor_a: shared[i].to_string(),
+                        actor_b: shared[j].to_string(),
+                        old_phi,
+                        new_phi,
+                        old_level,
+                        new_level,
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            added_actors,
+            removed_actors,
+            scheme_drift,
+            category_changes,
+            risk_changes,
+        })
+    }
+
+    /// Whether the two states are equivalent for reporting purposes
+    ///
+    /// Drift below `drift_tolerance` is ignored.
+    pub fn is_unchanged(&self, drift_tolerance: f64) -> bool {
+        self.added_actors.is_empty()
+            && self.removed_actors.is_empty()
+            && self.category_changes.is_empty()
+            && self.risk_changes.is_empty()
+            && self.scheme_drift.iter().all(|d| d.phi <= drift_tolerance)
+    }
+}
+
+/// Φ of a dyad, on the shared categories if the actors' differ
+///
+/// `None` when the actors share no categories.
+fn dyad_phi(model: &CompressionDynamicsModel, actor_a: &str, actor_b: &str) -> Option<f64> {
+    model
+        .peek_potential(actor_a, actor_b)
+        .map(|p| p.phi)
+        .or_else(|_| {
+            model
+                .compare_on_shared_support(actor_a, actor_b)
+                .map(|c| c.phi)
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_diff() {
+        let mut old = CompressionDynamicsModel::new(3);
+        old.register_actor("A", Some(vec![0.4, 0.3, 0.3]), None);
+        old.register_actor("B", Some(vec![0.3, 0.3, 0.4]), None);
+        old.register_actor("GONE", Some(vec![0.3, 0.4, 0.3]), None);
+
+        let unchanged = ModelDiff::compute(&old, &old).unwrap();
+        assert!(unchanged.is_unchanged(0.0));
+
+        let mut new = CompressionDynamicsModel::new(3);
+        new.register_actor("A", Some(vec![0.9, 0.05, 0.05]), None);
+        new.register_actor("B", Some(vec![0.3, 0.3, 0.4]), None);
+        new.register_actor("NEW", None, None);
+
+        let diff = ModelDiff::compute(&old, &new).unwrap();
+        assert_eq!(diff.added_actors, vec!["NEW"]);
+        assert_eq!(diff.removed_actors, vec!["GONE"]);
+        assert_eq!(diff.scheme_drift[0].actor_id, "A");
+        assert!(diff.scheme_drift[0].phi > 0.1);
+        assert!(diff.scheme_drift[1].phi < 1e-9);
+
+        assert_eq!(diff.risk_changes.len(), 1);
+        let change = &diff.risk_changes[0];
+        assert_eq!(
+            (change.actor_a.as_str(), change.actor_b.as_str()),
+            ("A", "B")
+        );
+        assert_eq!(change.old_level, RiskLevel::Low);
+        assert!(change.new_phi > change.old_phi);
+        assert!(!diff.is_unchanged(f64::INFINITY));
+        assert!(diff.category_changes.is_empty());
+    }
+
+    #[test]
+    fn test_model_diff_category_changes() {
+        let names = |names: &[&str]| Some(names.iter().map(|n| n.to_string()).collect());
+        let mut old = CompressionDynamicsModel::new(3);
+        old.register_actor("A", Some(vec![0.4, 0.3, 0.3]), names(&["x", "y", "z"]));
+        old.register_actor("B", Some(vec![0.3, 0.3, 0.4]), names(&["x", "y", "z"]));
+
+        let mut new = CompressionDynamicsModel::new(4);
+        new.register_actor(
+            "A",
+            Some(vec![0.4, 0.3, 0.2, 0.1]),
+            names(&["x", "y", "w", "v"]),
+        );
+        new.register_actor(
+            "B",
+            Some(vec![0.3, 0.3, 0.3, 0.1]),
+            names(&["x", "y", "z", "w"]),
+        );
+
+        let diff = ModelDiff::compute(&old, &new).unwrap();
+        assert_eq!(
+            diff.category_changes,
+            vec![
+                CategoryChange {
+                    actor_id: "A".to_string(),
+                    added: vec!["w".to_string(), "v".to_string()],
+                    removed: vec!["z".to_string()],
+                },
+                CategoryChange {
+                    actor_id: "B".to_string(),
+                    added: vec!["w".to_string()],
+                    removed: vec![],
+                },
+            ]
+        );
+        // Drift on the kept categories: A's x/y shares are unchanged
+        let drift_a = diff
+            .scheme_drift
+            .iter()
+            .find(|d| d.actor_id == "A")
+            .unwrap();
+        assert!(drift_a.phi < 1e-9);
+        assert!(!diff.is_unchanged(f64::INFINITY));
+    }
+}
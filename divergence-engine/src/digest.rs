@@ -0,0 +1,484 @@
+//! Periodic digests.
+//!
+//! Operators who do not watch the alert feed live want one summary per day
+//! or week. A [`DigestBuilder`] snapshots Φ and grievance at the start of a
+//! period, collects the period's alerts, and at the end reports:
+//!
+//! ```text
+//! alerts          counts per risk level, most severe first
+//! Φ movers        dyads whose Φ changed most, |Φ_end − Φ_start|
+//! bloc changes    BlocTracker events between period start and end
+//! grievance       actors whose windowed grievance grew most
+//! ```
+//!
+//! [`Digest`] serializes to JSON and renders as Markdown. With
+//! [`StreamConfig::digest`](crate::streaming::StreamConfig::digest) set,
+//! the stream processor closes periods on event time and
+//! [`run_pipeline`](crate::streaming::run_pipeline) hands each digest to
+//! [`AlertSink::send_digest`](crate::streaming::AlertSink::send_digest).
+
+use crate::bloc::{BlocEvent, BlocTracker, BlocTrackerConfig};
+use crate::cloudevents::rfc3339_ms;
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::RiskLevel;
+use crate::streaming::DivergenceAlert;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// One day in ms
+pub const DAY_MS: i64 = 86_400_000;
+
+/// Risk levels, most severe first
+const SEVERITY: [RiskLevel; 5] = [
+    RiskLevel::Critical,
+    RiskLevel::High,
+    RiskLevel::Elevated,
+    RiskLevel::Moderate,
+    RiskLevel::Low,
+];
+
+fn severity_rank(level: RiskLevel) -> usize {
+    SEVERITY.iter().position(|&l| l == level).unwrap_or(0)
+}
+
+/// Digest settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestConfig {
+    /// Period length in event time (ms)
+    pub interval_ms: i64,
+    /// Φ movers reported
+    pub top_movers: usize,
+    /// Grievance risers reported
+    pub top_grievance: usize,
+    /// Track blocs to report their changes (off by default)
+    #[serde(default)]
+    pub blocs: Option<BlocTrackerConfig>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self::daily()
+    }
+}
+
+impl DigestConfig {
+    pub fn daily() -> Self {
+        Self {
+            interval_ms: DAY_MS,
+            top_movers: 10,
+            top_grievance: 10,
+            blocs: None,
+        }
+    }
+
+    pub fn weekly() -> Self {
+        Self {
+            interval_ms: 7 * DAY_MS,
+            ..Self::daily()
+        }
+    }
+
+    /// Report bloc changes
+    pub fn with_blocs(mut self, config: BlocTrackerConfig) -> Self {
+        self.blocs = Some(config);
+        self
+    }
+}
+
+/// Alerts raised at one risk level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertCount {
+    pub risk_level: RiskLevel,
+    pub count: usize,
+}
+
+/// A dyad's Φ change over the period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhiMover {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub phi_start: f64,
+    pub phi_end: f64,
+    /// `phi_end − phi_start`
+    pub change: f64,
+}
+
+/// An actor's windowed grievance change over the period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrievanceGrowth {
+    pub actor_id: String,
+    pub grievance_start: f64,
+    pub grievance_end: f64,
+    /// `grievance_end − grievance_start`
+    pub growth: f64,
+}
+
+/// Summary of one period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Digest {
+    pub period_start_ms: i64,
+    pub period_end_ms: i64,
+    /// Non-zero counts, most severe first
+    pub alert_counts: Vec<AlertCount>,
+    /// The period's alerts, most severe first, then oldest first
+    pub alerts: Vec<DivergenceAlert>,
+    /// Largest |ΔΦ| first; dyads of actors registered during the period
+    /// are not included
+    pub phi_movers: Vec<PhiMover>,
+    pub bloc_events: Vec<BlocEvent>,
+    /// Largest growth first; only actors whose grievance grew
+    pub grievance_growth: Vec<GrievanceGrowth>,
+}
+
+impl Digest {
+    pub fn alert_total(&self) -> usize {
+        self.alerts.len()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| DivergenceError::SerializationError(e.to_string()))
+    }
+
+    /// Markdown rendering for email or chat, listing at most `top_alerts`
+    /// alerts
+    pub fn to_markdown(&self, top_alerts: usize) -> String {
+        let mut md = String::new();
+        // Writing to a String cannot fail
+        let _ = writeln!(md, "# Divergence digest\n");
+        let _ = writeln!(
+            md,
+            "{} – {}\n",
+            rfc3339_ms(self.period_start_ms),
+            rfc3339_ms(self.period_end_ms)
+        );
+
+        let _ = writeln!(md, "## Alerts ({})\n", self.alert_total());
+        if self.alerts.is_empty() {
+            let _ = writeln!(md, "No alerts.\n");
+        } else {
+            for count in &self.alert_counts {
+                let _ = writeln!(md, "- {}: {}", count.risk_level, count.count);
+            }
+            let _ = writeln!(md, "\n| Time | Dyad | Risk | Φ | Reason |");
+            let _ = writeln!(md, "|---|---|---|---|---|");
+            for alert in self.alerts.iter().take(top_alerts) {
+                let _ = writeln!(
+                    md,
+                    "| {} | {}–{} | {} | {:.3} | {} |",
+                    rfc3339_ms(alert.timestamp_ms),
+                    alert.actor_a,
+                    alert.actor_b,
+                    alert.risk_level,
+                    alert.phi,
+                    alert.reason.replace('|', "\\|")
+                );
+            }
+            if self.alerts.len() > top_alerts {
+                let _ = writeln!(md, "\n…and {} more.", self.alerts.len() - top_alerts);
+            }
+            md.push('\n');
+        }
+
+        let _ = writeln!(md, "## Largest Φ movers\n");
+        if self.phi_movers.is_empty() {
+            let _ = writeln!(md, "No dyads tracked.\n");
+        } else {
+            let _ = writeln!(md, "| Dyad | Φ start | Φ end | Change |");
+            let _ = writeln!(md, "|---|---|---|---|");
+            for m in &self.phi_movers {
+                let _ = writeln!(
+                    md,
+                    "| {}–{} | {:.3} | {:.3} | {:+.3} |",
+                    m.actor_a, m.actor_b, m.phi_start, m.phi_end, m.change
+                );
+            }
+            md.push('\n');
+        }
+
+        let _ = writeln!(md, "## Bloc changes\n");
+        if self.bloc_events.is_empty() {
+            let _ = writeln!(md, "No bloc changes.\n");
+        } else {
+            for event in &self.bloc_events {
+                let _ = writeln!(md, "- {}", describe_bloc_event(event));
+            }
+            md.push('\n');
+        }
+
+        let _ = writeln!(md, "## Grievance growth\n");
+        if self.grievance_growth.is_empty() {
+            let _ = writeln!(md, "No grievance growth.");
+        } else {
+            let _ = writeln!(md, "| Actor | Start | End | Growth |");
+            let _ = writeln!(md, "|---|---|---|---|");
+            for g in &self.grievance_growth {
+                let _ = writeln!(
+                    md,
+                    "| {} | {:.4} | {:.4} | {:+.4} |",
+                    g.actor_id, g.grievance_start, g.grievance_end, g.growth
+                );
+            }
+        }
+        md
+    }
+}
+
+fn describe_bloc_event(event: &BlocEvent) -> String {
+    let ids = |ids: &[u64]| {
+        ids.iter()
+            .map(|id| format!("#{}", id))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match event {
+        BlocEvent::Formed { bloc, members } => {
+            format!("Bloc #{} formed: {}", bloc, members.join(", "))
+        }
+        BlocEvent::Dissolved { bloc } => format!("Bloc #{} dissolved", bloc),
+        BlocEvent::Merged { from, into } => format!("Blocs {} merged into #{}", ids(from), into),
+        BlocEvent::Split { from, into } => format!("Bloc #{} split into {}", from, ids(into)),
+        BlocEvent::Joined { actor, bloc } => format!("{} joined bloc #{}", actor, bloc),
+        BlocEvent::Left { actor, bloc } => format!("{} left bloc #{}", actor, bloc),
+    }
+}
+
+/// Collects one period's activity into a [`Digest`]
+#[derive(Debug, Clone)]
+pub struct DigestBuilder {
+    config: DigestConfig,
+    period_start_ms: i64,
+    phi_start: HashMap<(String, String), f64>,
+    grievance_start: HashMap<String, f64>,
+    alerts: Vec<DivergenceAlert>,
+    blocs: Option<BlocTracker>,
+}
+
+impl DigestBuilder {
+    /// Start the first period at `start_ms`
+    pub fn new(
+        config: DigestConfig,
+        model: &CompressionDynamicsModel,
+        start_ms: i64,
+    ) -> Result<Self> {
+        if config.interval_ms <= 0 {
+            return Err(DivergenceError::ConfigError(format!(
+                "Digest interval must be positive, got {}",
+                config.interval_ms
+            )));
+        }
+        let blocs = match &config.blocs {
+            Some(bloc_config) => {
+                let mut tracker = BlocTracker::new(bloc_config.clone())?;
+                // Establish the starting blocs; their formation is not news
+                tracker.step(model, start_ms)?;
+                Some(tracker)
+            }
+            None => None,
+        };
+        let mut builder = Self {
+            config,
+            period_start_ms: start_ms,
+            phi_start: HashMap::new(),
+            grievance_start: HashMap::new(),
+            alerts: Vec::new(),
+            blocs,
+        };
+        builder.snapshot(model);
+        Ok(builder)
+    }
+
+    pub fn config(&self) -> &DigestConfig {
+        &self.config
+    }
+
+    pub fn period_start_ms(&self) -> i64 {
+        self.period_start_ms
+    }
+
+    /// Whether the current period has ended by `timestamp_ms`
+    pub fn is_due(&self, timestamp_ms: i64) -> bool {
+        timestamp_ms - self.period_start_ms >= self.config.interval_ms
+    }
+
+    /// Add alerts raised during the period
+    pub fn record_alerts(&mut self, alerts: &[DivergenceAlert]) {
+        self.alerts.extend_from_slice(alerts);
+    }
+
+    /// Close the period at `end_ms` and start the next one there
+    pub fn finish(&mut self, model: &CompressionDynamicsModel, end_ms: i64) -> Result<Digest> {
+        let bloc_events = match &mut self.blocs {
+            Some(tracker) => tracker.step(model, end_ms)?.events,
+            None => Vec::new(),
+        };
+
+        let mut alerts = std::mem::take(&mut self.alerts);
+        alerts.sort_by_key(|a| (severity_rank(a.risk_level), a.timestamp_ms));
+        let alert_counts = SEVERITY
+            .iter()
+            .map(|&risk_level| AlertCount {
+                risk_level,
+                count: alerts.iter().filter(|a| a.risk_level == risk_level).count(),
+            })
+            .filter(|c| c.count > 0)
+            .collect();
+
+        let mut phi_movers: Vec<PhiMover> = model
+            .peek_all_potentials()
+            .into_iter()
+            .filter_map(|p| {
+                let phi_start = *self
+                    .phi_start
+                    .get(&(p.actor_a.clone(), p.actor_b.clone()))?;
+                Some(PhiMover {
+                    change: p.phi - phi_start,
+                    phi_start,
+                    phi_end: p.phi,
+                    actor_a: p.actor_a,
+                    actor_b: p.actor_b,
+                })
+            })
+            .collect();
+        phi_movers.sort_by(|a, b| b.change.abs().total_cmp(&a.change.abs()));
+        phi_movers.truncate(self.config.top_movers);
+
+        let mut grievance_growth: Vec<GrievanceGrowth> = model
+            .grievances
+            .values()
+            .map(|g| {
+                let grievance_start = self
+                    .grievance_start
+                    .get(&g.actor_id)
+                    .copied()
+                    .unwrap_or(0.0);
+                GrievanceGrowth {
+                    actor_id: g.actor_id.clone(),
+                    grievance_start,
+                    grievance_end: g.window_error,
+                    growth: g.window_error - grievance_start,
+                }
+            })
+            .filter(|g| g.growth > 0.0)
+            .collect();
+        grievance_growth.sort_by(|a, b| b.growth.total_cmp(&a.growth));
+        grievance_growth.truncate(self.config.top_grievance);
+
+        let digest = Digest {
+            period_start_ms: self.period_start_ms,
+            period_end_ms: end_ms,
+            alert_counts,
+            alerts,
+            phi_movers,
+            bloc_events,
+            grievance_growth,
+        };
+        self.period_start_ms = end_ms;
+        self.snapshot(model);
+        Ok(digest)
+    }
+
+    fn snapshot(&mut self, model: &CompressionDynamicsModel) {
+        self.phi_start = model
+            .peek_all_potentials()
+            .into_iter()
+            .map(|p| ((p.actor_a, p.actor_b), p.phi))
+            .collect();
+        self.grievance_start = model
+            .grievances
+            .values()
+            .map(|g| (g.actor_id.clone(), g.window_error))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(id: &str, risk_level: RiskLevel, timestamp_ms: i64) -> DivergenceAlert {
+        DivergenceAlert {
+            alert_id: id.to_string(),
+            actor_a: "A".to_string(),
+            actor_b: "B".to_string(),
+            phi: 3.0,
+            js: 0.4,
+            phi_z: None,
+            phi_adjusted: None,
+            p_value: None,
+            robustness: None,
+            d_phi_dt: 0.1,
+            risk_level,
+            escalation_probability: 0.5,
+            grievance_a_to_b: 0.0,
+            grievance_b_to_a: 0.0,
+            timestamp_ms,
+            reason: "Φ=3.000 exceeds threshold".to_string(),
+            latency: None,
+        }
+    }
+
+    #[test]
+    fn test_digest() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.6, 0.2, 0.2]), None);
+        model.register_actor("B", Some(vec![0.2, 0.2, 0.6]), None);
+        model.register_actor("C", Some(vec![0.5, 0.3, 0.2]), None);
+
+        let config = DigestConfig::daily().with_blocs(BlocTrackerConfig::default());
+        let mut builder = DigestBuilder::new(config, &model, 0).unwrap();
+        assert!(!builder.is_due(DAY_MS - 1));
+        assert!(builder.is_due(DAY_MS));
+
+        builder.record_alerts(&[
+            alert("a1", RiskLevel::High, 10),
+            alert("a2", RiskLevel::Critical, 20),
+            alert("a3", RiskLevel::High, 5),
+        ]);
+        for t in 1..20 {
+            model
+                .update_scheme("C", &[0.1, 0.1, 0.8], Some(t * 1_000))
+                .unwrap();
+        }
+
+        let digest = builder.finish(&model, DAY_MS).unwrap();
+        assert_eq!(
+            digest.alert_counts,
+            [
+                AlertCount {
+                    risk_level: RiskLevel::Critical,
+                    count: 1
+                },
+                AlertCount {
+                    risk_level: RiskLevel::High,
+                    count: 2
+                },
+            ]
+        );
+        let ids: Vec<&str> = digest.alerts.iter().map(|a| a.alert_id.as_str()).collect();
+        assert_eq!(ids, ["a2", "a3", "a1"]);
+        // C moved; A–B did not
+        assert_eq!(digest.phi_movers.len(), 3);
+        assert_ne!(digest.phi_movers[0].actor_b, "B");
+        assert_eq!(digest.phi_movers[2].change, 0.0);
+        assert_eq!(digest.grievance_growth[0].actor_id, "C");
+        // C left A's camp for B's
+        assert!(!digest.bloc_events.is_empty());
+
+        let json: serde_json::Value = serde_json::from_str(&digest.to_json().unwrap()).unwrap();
+        assert_eq!(json["alerts"].as_array().unwrap().len(), 3);
+        let md = digest.to_markdown(2);
+        assert!(md.contains("## Alerts (3)"));
+        assert!(md.contains("- CRITICAL: 1"));
+        assert!(md.contains("…and 1 more."));
+        assert!(md.contains("1970-01-02T00:00:00.000Z"));
+
+        // The next period starts where this one ended
+        assert_eq!(builder.period_start_ms(), DAY_MS);
+        let digest = builder.finish(&model, 2 * DAY_MS).unwrap();
+        assert!(digest.alerts.is_empty());
+        assert!(digest.phi_movers.iter().all(|m| m.change == 0.0));
+        assert!(digest.to_markdown(20).contains("No alerts."));
+    }
+}
@@ -1,6 +1,7 @@
 //! Core divergence calculations.
 //!
 //! Implements information-theoretic divergence measures:
+//! - Cross-entropy and perplexity
 //! - KL Divergence (Kullback-Leibler)
 //! - Jensen-Shannon Divergence
 //! - Hellinger Distance
@@ -9,6 +10,7 @@
 //! All operations are optimized for SIMD and cache efficiency.
 
 use crate::error::{DivergenceError, Result};
+use serde::{Deserialize, Serialize};
 
 /// Epsilon for numerical stability (avoids log(0))
 pub const EPSILON: f64 = 1e-10;
@@ -41,6 +43,96 @@ pub fn smooth(dist: &mut [f64], epsilon: f64) {
     normalize(dist);
 }
 
+/// Strategy for keeping scheme distributions away from zero
+///
+/// Tradeoffs:
+/// - `Additive(ε)` adds ε to every category and renormalizes. Cheap and
+///   keeps every KL term finite, but re-applied on every update it drags
+///   small distributions toward uniform, and two schemes that differ only
+///   in how often they were smoothed report a small nonzero Φ.
+/// - `Jeffreys` treats the distribution as `effective_n` observations and
+///   adds a 0.5 pseudocount per category. Shrinkage is scale-aware (strong
+///   for thin evidence, negligible for large `effective_n`) rather than a
+///   fixed ε.
+/// - `None` leaves the distribution untouched. Identical schemes give
+///   exactly zero Φ; zeros are handled by the guarded KL (`0·log 0 = 0`,
+///   and a zero in the reference distribution is floored at [`EPSILON`]),
+///   so divergence toward an unsupported category is large but finite.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Smoothing {
+    /// No smoothing (rely on guarded KL)
+    None,
+    /// Add ε to every category, then renormalize
+    Additive(f64),
+    /// Jeffreys prior: 0.5 pseudocount per category over `effective_n` observations
+    Jeffreys { effective_n: f64 },
+}
+
+impl Default for Smoothing {
+    fn default() -> Self {
+        Smoothing::Additive(SMOOTHING)
+    }
+}
+
+impl Smoothing {
+    /// Normalize a distribution and apply this strategy in place
+    pub fn apply(&self, dist: &mut [f64]) {
+        normalize(dist);
+        match *self {
+            Smoothing::None => {}
+            Smoothing::Additive(epsilon) => smooth(dist, epsilon),
+            Smoothing::Jeffreys { effective_n } => {
+                for x in dist.iter_mut() {
+                    *x = *x * effective_n + 0.5;
+                }
+                normalize(dist);
+            }
+        }
+    }
+
+    /// Mass assigned to a category with no evidence in an `n_categories` space
+    pub fn floor_mass(&self, n_categories: usize) -> f64 {
+        match *self {
+            Smoothing::None => 0.0,
+            Smoothing::Additive(epsilon) => epsilon,
+            Smoothing::Jeffreys { effective_n } => 0.5 / (effective_n + 0.5 * n_categories as f64),
+        }
+    }
+
+    /// Check parameters are finite and non-negative
+    pub fn validate(&self) -> Result<()> {
+        let value = match *self {
+            Smoothing::None => return Ok(()),
+            Smoothing::Additive(epsilon) => epsilon,
+            Smoothing::Jeffreys { effective_n } => effective_n,
+        };
+        if !value.is_finite() || value < 0.0 {
+            return Err(DivergenceError::ConfigError(format!(
+                "Smoothing parameter must be finite and non-negative, got {}",
+                value
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// How KL treats categories where q has zero mass but p does not
+///
+/// D_KL(P || Q) is only finite when P is absolutely continuous with respect
+/// to Q. `Clamp` hides violations behind an [`EPSILON`] floor, which makes a
+/// smoothing artifact and a genuine blowup look alike (both are large but
+/// finite). The strict policies surface them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SupportPolicy {
+    /// Floor q_i at EPSILON (finite, default)
+    #[default]
+    Clamp,
+    /// Return `f64::INFINITY`
+    Infinite,
+    /// Return [`DivergenceError::SupportMismatch`]
+    Error,
+}
+
 /// Shannon entropy H(P) = -Σ p_i * log2(p_i)
 ///
 /// Higher entropy = more diffuse distribution
@@ -53,6 +145,36 @@ pub fn entropy(p: &[f64]) -> f64 {
         .sum()
 }
 
+/// Cross-entropy H(P, Q) = -Σ p_i * log2(q_i) = H(P) + D_KL(P || Q)
+///
+/// Expected bits to encode outcomes drawn from P with a code built for Q,
+/// e.g. realized observation frequencies P scored against predictions Q.
+/// Categories where p_i = 0 contribute nothing; q_i is floored at
+/// [`EPSILON`].
+#[inline]
+pub fn cross_entropy(p: &[f64], q: &[f64]) -> Result<f64> {
+    if p.len() != q.len() {
+        return Err(DivergenceError::DimensionMismatch {
+            expected: p.len(),
+            got: q.len(),
+        });
+    }
+    Ok(p.iter()
+        .zip(q)
+        .filter(|(&pi, _)| pi > 0.0)
+        .map(|(&pi, &qi)| -pi * qi.max(EPSILON).log2())
+        .sum())
+}
+
+/// Perplexity 2^H(P)
+///
+/// The effective number of equally likely categories: 1 for a point mass,
+/// n for uniform over n.
+#[inline]
+pub fn perplexity(p: &[f64]) -> f64 {
+    entropy(p).exp2()
+}
+
 /// KL Divergence D_KL(P || Q) = Σ p_i * log2(p_i / q_i)
 ///
 /// Measures information lost when using Q to approximate P.
@@ -62,8 +184,20 @@ pub fn entropy(p: &[f64]) -> f64 {
 /// - Non-negative: D_KL(P || Q) >= 0
 /// - Zero iff P = Q
 /// - Asymmetric: D_KL(P || Q) != D_KL(Q || P)
+///
+/// Guarded for unsmoothed inputs: categories where p_i = 0 contribute
+/// nothing, and q_i is floored at [`EPSILON`].
 #[inline]
 pub fn kl_divergence(p: &[f64], q: &[f64]) -> Result<f64> {
+    kl_divergence_with(p, q, SupportPolicy::Clamp)
+}
+
+/// KL divergence with an explicit absolute-continuity policy
+///
+/// Only exact zeros in q count as missing support; tiny smoothed masses
+/// are real (if improbable) support and are never treated as violations.
+#[inline]
+pub fn kl_divergence_with(p: &[f64], q: &[f64], policy: SupportPolicy) -> Result<f64> {
     if p.len() != q.len() {
         return Err(DivergenceError::DimensionMismatch {
             expected: p.len(),
@@ -73,6 +207,18 @@ pub fn kl_divergence(p: &[f64], q: &[f64]) -> Result<f64> {
 
     let mut kl = 0.0;
     for i in 0..p.len() {
+        if p[i] <= 0.0 {
+            continue;
+        }
+        if q[i] <= 0.0 {
+            match policy {
+                SupportPolicy::Clamp => {}
+                SupportPolicy::Infinite => return Ok(f64::INFINITY),
+                SupportPolicy::Error => {
+                    return Err(DivergenceError::SupportMismatch { index: i, p: p[i] })
+                }
+            }
+        }
         let pi = p[i].max(EPSILON);
         let qi = q[i].max(EPSILON);
         kl += pi * (pi / qi).ln();
@@ -93,6 +239,12 @@ pub fn symmetric_kl(p: &[f64], q: &[f64]) -> Result<f64> {
     Ok(kl_divergence(p, q)? + kl_divergence(q, p)?)
 }
 
+/// Symmetric KL divergence with an explicit absolute-continuity policy
+#[inline]
+pub fn symmetric_kl_with(p: &[f64], q: &[f64], policy: SupportPolicy) -> Result<f64> {
+    Ok(kl_divergence_with(p, q, policy)? + kl_divergence_with(q, p, policy)?)
+}
+
 /// Jensen-Shannon Divergence
 ///
 /// JS(P,Q) = 0.5 * D_KL(P || M) + 0.5 * D_KL(Q || M)
@@ -195,6 +347,29 @@ pub fn cosine_similarity(p: &[f64], q: &[f64]) -> Result<f64> {
     Ok(dot / (norm_p * norm_q))
 }
 
+/// Wasserstein-1 (Earth Mover's) distance over ordered categories
+///
+/// W1(P, Q) = Σ |CDF_P(i) - CDF_Q(i)|, in units of category steps.
+/// Only meaningful when category order carries meaning (e.g. Goldstein
+/// bins); unlike KL it rewards shifting mass to a *nearby* category.
+#[inline]
+pub fn wasserstein_1d(p: &[f64], q: &[f64]) -> Result<f64> {
+    if p.len() != q.len() {
+        return Err(DivergenceError::DimensionMismatch {
+            expected: p.len(),
+            got: q.len(),
+        });
+    }
+
+    let mut cdf_diff = 0.0;
+    let mut emd = 0.0;
+    for (&pi, &qi) in p.iter().zip(q.iter()) {
+        cdf_diff += pi - qi;
+        emd += cdf_diff.abs();
+    }
+    Ok(emd)
+}
+
 /// Compute all divergence metrics at once (batch optimization)
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DivergenceMetrics {
@@ -205,10 +380,32 @@ pub struct DivergenceMetrics {
     pub hellinger: f64,
     pub bhattacharyya: f64,
     pub cosine: f64,
+    /// Pearson χ²(P || Q) = Σ (p_i - q_i)² / q_i
+    ///
+    /// Dominated by categories Q considers rare; far more sensitive to
+    /// small-q outliers than KL.
+    #[serde(default)]
+    pub chi_squared: f64,
+    /// Jeffreys divergence J(P, Q) = Σ (p_i - q_i) log2(p_i / q_i)
+    ///
+    /// Equal to `symmetric_kl` in exact arithmetic; reported under its
+    /// conventional name and accumulated term-wise.
+    #[serde(default)]
+    pub jeffreys: f64,
+    /// Lin's K-divergence K(P || Q) = D_KL(P || (P + Q) / 2), in bits
+    ///
+    /// Bounded by 1 and always finite, even without smoothing.
+    #[serde(default)]
+    pub k_divergence: f64,
+    /// Wasserstein-1 distance, treating category indices as ordered
+    ///
+    /// See [`wasserstein_1d`]; ignore for unordered category spaces.
+    #[serde(default)]
+    pub wasserstein: f64,
 }
 
 impl DivergenceMetrics {
-    /// Compute all metrics in a single pass where possible
+    /// Compute all metrics in a single pass
     pub fn compute(p: &[f64], q: &[f64]) -> Result<Self> {
         if p.len() != q.len() {
             return Err(DivergenceError::DimensionMismatch {
@@ -220,23 +417,43 @@ impl DivergenceMetrics {
         // Single-pass computation for efficiency
         let mut kl_p_q = 0.0;
         let mut kl_q_p = 0.0;
+        let mut js_p = 0.0;
+        let mut js_q = 0.0;
+        let mut chi_squared = 0.0;
+        let mut jeffreys = 0.0;
         let mut hellinger_sum = 0.0;
         let mut bhattacharyya_sum = 0.0;
         let mut dot = 0.0;
         let mut norm_p_sq = 0.0;
         let mut norm_q_sq = 0.0;
-        let mut m_vec = Vec::with_capacity(p.len());
+        let mut cdf_diff = 0.0;
+        let mut wasserstein = 0.0;
 
         for i in 0..p.len() {
             let pi = p[i].max(EPSILON);
             let qi = q[i].max(EPSILON);
             let mi = 0.5 * (pi + qi);
 
-            m_vec.push(mi);
-
-            // KL divergence terms
-            kl_p_q += pi * (pi / qi).ln();
-            kl_q_p += qi * (qi / pi).ln();
+            // KL and Jensen-Shannon terms (guarded: 0·log 0 = 0)
+            if p[i] > 0.0 {
+                kl_p_q += pi * (pi / qi).ln();
+                js_p += pi * (pi / mi).ln();
+            }
+            if q[i] > 0.0 {
+                kl_q_p += qi * (qi / pi).ln();
+                js_q += qi * (qi / mi).ln();
+            }
+
+            // Pearson χ² and Jeffreys
+            let diff = pi - qi;
+            chi_squared += diff * diff / qi;
+            if p[i] > 0.0 || q[i] > 0.0 {
+                jeffreys += diff * (pi / qi).ln();
+            }
+
+            // Wasserstein-1 (running CDF difference)
+            cdf_diff += p[i] - q[i];
+            wasserstein += cdf_diff.abs();
 
             // Hellinger
             let sqrt_diff = pi.sqrt() - qi.sqrt();
@@ -251,22 +468,13 @@ impl DivergenceMetrics {
             norm_q_sq += qi * qi;
         }
 
-        // Convert KL from nats to bits
+        // Convert log terms from nats to bits
         let ln2 = std::f64::consts::LN_2;
         kl_p_q /= ln2;
         kl_q_p /= ln2;
-
-        // Jensen-Shannon (needs second pass for M)
-        let mut js_p = 0.0;
-        let mut js_q = 0.0;
-        for i in 0..p.len() {
-            let pi = p[i].max(EPSILON);
-            let qi = q[i].max(EPSILON);
-            let mi = m_vec[i];
-            js_p += pi * (pi / mi).ln();
-            js_q += qi * (qi / mi).ln();
-        }
+        let k_divergence = js_p / ln2;
         let jensen_shannon = 0.5 * (js_p + js_q) / ln2;
+        jeffreys /= ln2;
 
         let hellinger = (0.5 * hellinger_sum).sqrt();
         let cosine = if norm_p_sq > EPSILON && norm_q_sq > EPSILON {
@@ -283,6 +491,10 @@ impl DivergenceMetrics {
             hellinger,
             bhattacharyya: bhattacharyya_sum,
             cosine,
+            chi_squared,
+            jeffreys,
+            k_divergence,
+            wasserstein,
         })
     }
 }
@@ -315,6 +527,25 @@ mod tests {
         assert!(approx_eq(h, 0.0, 0.001));
     }
 
+    #[test]
+    fn test_cross_entropy_and_perplexity() {
+        let p = vec![0.5, 0.25, 0.25, 0.0];
+        let q = vec![0.25, 0.25, 0.25, 0.25];
+        // H(P, Q) = H(P) + D_KL(P || Q)
+        let h = cross_entropy(&p, &q).unwrap();
+        assert!(approx_eq(h, 2.0, 1e-9));
+        assert!(approx_eq(
+            h,
+            entropy(&p) + kl_divergence(&p, &q).unwrap(),
+            1e-6
+        ));
+        assert!(approx_eq(cross_entropy(&p, &p).unwrap(), entropy(&p), 1e-6));
+        assert!(cross_entropy(&p, &[0.5, 0.5]).is_err());
+
+        assert!(approx_eq(perplexity(&q), 4.0, 1e-9));
+        assert!(approx_eq(perplexity(&[1.0, 0.0]), 1.0, 1e-9));
+    }
+
     #[test]
     fn test_kl_divergence() {
         let p = vec![0.5, 0.5];
@@ -331,6 +562,36 @@ mod tests {
         assert!(kl > 0.0);
     }
 
+    #[test]
+    fn test_support_policy() {
+        let p = vec![0.5, 0.5, 0.0];
+        let q = vec![1.0, 0.0, 0.0];
+
+        // Clamped: large but finite
+        let clamped = kl_divergence(&p, &q).unwrap();
+        assert!(clamped.is_finite() && clamped > 10.0);
+
+        assert_eq!(
+            kl_divergence_with(&p, &q, SupportPolicy::Infinite).unwrap(),
+            f64::INFINITY
+        );
+        assert!(matches!(
+            kl_divergence_with(&p, &q, SupportPolicy::Error),
+            Err(DivergenceError::SupportMismatch { index: 1, .. })
+        ));
+
+        // q covers p's support (p's zero at index 2 is irrelevant)
+        assert!(kl_divergence_with(&q, &p, SupportPolicy::Error).is_ok());
+
+        // Smoothed mass counts as support
+        let mut q_smooth = q.clone();
+        smooth(&mut q_smooth, SMOOTHING);
+        assert!(kl_divergence_with(&p, &q_smooth, SupportPolicy::Error).is_ok());
+        assert!(symmetric_kl_with(&p, &q, SupportPolicy::Infinite)
+            .unwrap()
+            .is_infinite());
+    }
+
     #[test]
     fn test_jensen_shannon_bounds() {
         let p = vec![1.0, 0.0];
@@ -371,4 +632,42 @@ mod tests {
             0.001
         ));
     }
+
+    #[test]
+    fn test_extended_metrics() {
+        let p = vec![0.4, 0.3, 0.2, 0.1];
+        let q = vec![0.25, 0.25, 0.25, 0.25];
+        let metrics = DivergenceMetrics::compute(&p, &q).unwrap();
+
+        // Extended metrics agree with their direct definitions
+        let chi: f64 = p.iter().zip(&q).map(|(a, b)| (a - b).powi(2) / b).sum();
+        assert!(approx_eq(metrics.chi_squared, chi, 1e-9));
+        assert!(approx_eq(metrics.jeffreys, metrics.symmetric_kl, 1e-9));
+
+        let m: Vec<f64> = p.iter().zip(&q).map(|(a, b)| 0.5 * (a + b)).collect();
+        let k = kl_divergence(&p, &m).unwrap();
+        assert!(approx_eq(metrics.k_divergence, k, 1e-9));
+        assert!(approx_eq(
+            metrics.jensen_shannon,
+            jensen_shannon(&p, &q).unwrap(),
+            1e-9
+        ));
+        assert!(approx_eq(
+            metrics.wasserstein,
+            wasserstein_1d(&p, &q).unwrap(),
+            1e-12
+        ));
+    }
+
+    #[test]
+    fn test_wasserstein_rewards_nearby_mass() {
+        let p = vec![1.0, 0.0, 0.0];
+        let near = vec![0.0, 1.0, 0.0];
+        let far = vec![0.0, 0.0, 1.0];
+
+        assert!(approx_eq(wasserstein_1d(&p, &near).unwrap(), 1.0, 1e-12));
+        assert!(approx_eq(wasserstein_1d(&p, &far).unwrap(), 2.0, 1e-12));
+        assert!(approx_eq(wasserstein_1d(&p, &p).unwrap(), 0.0, 1e-12));
+        assert!(wasserstein_1d(&p, &[1.0]).is_err());
+    }
 }
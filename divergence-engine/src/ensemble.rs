@@ -0,0 +1,297 @@
+//! Ensembles of escalation predictors.
+//!
+//! With `ModelConfig::predictor` set to [`PredictorKind::Ensemble`],
+//! `predict_escalation` runs every member of `ModelConfig::ensemble` and
+//! combines their probabilities:
+//!
+//! ```text
+//! WeightedAverage:  P = Σ w_i·p_i / Σ w_i
+//! Max:              P = max_i p_i
+//! Stacking:         P = σ(b + Σ w_i·logit(p_i))
+//! ```
+//!
+//! Members are referred to by name. `"logistic"` is the built-in formula,
+//! `"regime"` the [`RegimePredictor`] with default parameters and
+//! `"learned"` the attached learned predictor; any other name must be
+//! registered with [`register_predictor`](CompressionDynamicsModel::register_predictor),
+//! which may also override `"regime"` and `"learned"`. Each member sees the
+//! dyad's full feature history, so sequence models such as the regime
+//! filter can carry belief across steps.
+//!
+//! [`PredictorKind::Ensemble`]: crate::predictor::PredictorKind::Ensemble
+
+use crate::error::{DivergenceError, Result};
+use crate::features::FeatureVector;
+use crate::model::CompressionDynamicsModel;
+use crate::predictor::LearnedPredictor;
+use crate::regime::RegimePredictor;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Name of the built-in logistic formula
+pub const LOGISTIC: &str = "logistic";
+/// Name of the regime filter
+pub const REGIME: &str = "regime";
+/// Name of the attached learned predictor
+pub const LEARNED: &str = "learned";
+
+/// Keeps stacking logits finite
+const LOGIT_CLAMP: f64 = 1e-6;
+
+/// How member probabilities are combined
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum EnsembleMethod {
+    /// Weight-normalized mean
+    #[default]
+    WeightedAverage,
+    /// Most alarmed member; weights are ignored
+    Max,
+    /// Logistic regression over member logits
+    Stacking { bias: f64 },
+}
+
+/// A named ensemble member and its weight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnsembleMember {
+    pub name: String,
+    pub weight: f64,
+}
+
+impl EnsembleMember {
+    pub fn new(name: impl Into<String>, weight: f64) -> Self {
+        Self {
+            name: name.into(),
+            weight,
+        }
+    }
+}
+
+/// Ensemble members and combination rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnsembleConfig {
+    pub members: Vec<EnsembleMember>,
+    #[serde(default)]
+    pub method: EnsembleMethod,
+}
+
+impl Default for EnsembleConfig {
+    /// Logistic formula and regime filter, equally weighted
+    fn default() -> Self {
+        Self {
+            members: vec![
+                EnsembleMember::new(LOGISTIC, 0.5),
+                EnsembleMember::new(REGIME, 0.5),
+            ],
+            method: EnsembleMethod::WeightedAverage,
+        }
+    }
+}
+
+/// One member's contribution to an ensemble prediction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PredictorBreakdown {
+    pub name: String,
+    pub probability: f64,
+    pub weight: f64,
+}
+
+/// Combine member probabilities according to `method`
+pub fn combine(method: EnsembleMethod, breakdown: &[PredictorBreakdown]) -> Result<f64> {
+    if breakdown.is_empty() {
+        return Err(DivergenceError::ConfigError(
+            "Ensemble has no members".to_string(),
+        ));
+    }
+    let probability = match method {
+        EnsembleMethod::WeightedAverage => {
+            let total: f64 = breakdown.iter().map(|m| m.weight).sum();
+            if total <= 0.0 {
+                return Err(DivergenceError::ConfigError(
+                    "Ensemble weights must sum to a positive value".to_string(),
+                ));
+            }
+            breakdown
+                .iter()
+                .map(|m| m.weight * m.probability)
+                .sum::<f64>()
+                / total
+        }
+        EnsembleMethod::Max => breakdown.iter().map(|m| m.probability).fold(0.0, f64::max),
+        EnsembleMethod::Stacking { bias } => {
+            let logit = bias
+                + breakdown
+                    .iter()
+                    .map(|m| {
+                        let p = m.probability.clamp(LOGIT_CLAMP, 1.0 - LOGIT_CLAMP);
+                        m.weight * (p / (1.0 - p)).ln()
+                    })
+                    .sum::<f64>();
+            1.0 / (1.0 + (-logit).exp())
+        }
+    };
+    Ok(probability.clamp(0.0, 1.0))
+}
+
+impl CompressionDynamicsModel {
+    /// Register a named predictor for use as an ensemble member
+    ///
+    /// Replaces any predictor already registered under `name`.
+    pub fn register_predictor(
+        &mut self,
+        name: impl Into<String>,
+        predictor: Arc<dyn LearnedPredictor>,
+    ) -> Result<()> {
+        let name = name.into();
+        if name == LOGISTIC {
+            return Err(DivergenceError::ConfigError(format!(
+                "'{}' is reserved for the built-in formula",
+                LOGISTIC
+            )));
+        }
+        self.predictors.insert(name, predictor);
+        Ok(())
+    }
+
+    /// Names of registered predictors, in registration order
+    pub fn registered_predictors(&self) -> Vec<&str> {
+        self.predictors.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Run every ensemble member and combine their probabilities
+    ///
+    /// `logistic` is the built-in formula's probability, already computed
+    /// by the caller.
+    pub(crate) fn ensemble_probability(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        logistic: f64,
+        current: FeatureVector,
+    ) -> Result<(f64, Vec<PredictorBreakdown>)> {
+        let ensemble = &self.config.ensemble;
+        let history = if ensemble.members.iter().any(|m| m.name != LOGISTIC) {
+            let mut history = self.export_features(actor_a, actor_b)?;
+            history.push(current);
+            history
+        } else {
+            Vec::new()
+        };
+        let mut breakdown = Vec::with_capacity(ensemble.members.len());
+
+        for member in &ensemble.members {
+            let probability = if member.name == LOGISTIC {
+                logistic
+            } else {
+                self.ensemble_member(&member.name)?
+                    .predict_history(&history)?
+            };
+            breakdown.push(PredictorBreakdown {
+                name: member.name.clone(),
+                probability: probability.clamp(0.0, 1.0),
+                weight: member.weight,
+            });
+        }
+
+        Ok((combine(ensemble.method, &breakdown)?, breakdown))
+    }
+
+    fn ensemble_member(&self, name: &str) -> Result<Arc<dyn LearnedPredictor>> {
+        if let Some(predictor) = self.predictors.get(name) {
+            return Ok(predictor.clone());
+        }
+        match name {
+            REGIME => Ok(Arc::new(RegimePredictor::default())),
+            LEARNED => self.learned_predictor.clone().ok_or_else(|| {
+                DivergenceError::ConfigError("No learned predictor attached".to_string())
+            }),
+            _ => Err(DivergenceError::ConfigError(format!(
+                "Unknown ensemble member: {}",
+                name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use crate::predictor::PredictorKind;
+
+    #[derive(Debug)]
+    struct Constant(f64);
+
+    impl LearnedPredictor for Constant {
+        fn predict(&self, _features: &FeatureVector) -> Result<f64> {
+            Ok(self.0)
+        }
+    }
+
+    fn member(name: &str, probability: f64, weight: f64) -> PredictorBreakdown {
+        PredictorBreakdown {
+            name: name.to_string(),
+            probability,
+            weight,
+        }
+    }
+
+    #[test]
+    fn test_combine_methods() {
+        let members = [member("a", 0.2, 1.0), member("b", 0.8, 3.0)];
+        let avg = combine(EnsembleMethod::WeightedAverage, &members).unwrap();
+        assert!((avg - 0.65).abs() < 1e-12);
+        assert_eq!(combine(EnsembleMethod::Max, &members).unwrap(), 0.8);
+
+        // Unit weights on complementary logits cancel
+        let members = [member("a", 0.2, 1.0), member("b", 0.8, 1.0)];
+        let stacked = combine(EnsembleMethod::Stacking { bias: 0.0 }, &members).unwrap();
+        assert!((stacked - 0.5).abs() < 1e-12);
+
+        assert!(combine(EnsembleMethod::Max, &[]).is_err());
+        assert!(combine(EnsembleMethod::WeightedAverage, &[member("a", 0.5, 0.0)]).is_err());
+    }
+
+    #[test]
+    fn test_ensemble_prediction_breakdown() {
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 3,
+            predictor: PredictorKind::Ensemble,
+            ensemble: EnsembleConfig {
+                members: vec![
+                    EnsembleMember::new(LOGISTIC, 1.0),
+                    EnsembleMember::new(REGIME, 1.0),
+                    EnsembleMember::new("analyst", 2.0),
+                ],
+                method: EnsembleMethod::WeightedAverage,
+            },
+            ..Default::default()
+        });
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.3, 0.6]), None);
+
+        // Unregistered member
+        assert!(model.peek_escalation("A", "B", 0.5, 0.0).is_err());
+        assert!(model
+            .register_predictor(LOGISTIC, Arc::new(Constant(0.5)))
+            .is_err());
+
+        model
+            .register_predictor("analyst", Arc::new(Constant(0.9)))
+            .unwrap();
+        assert_eq!(model.registered_predictors(), vec!["analyst"]);
+        let prediction = model.predict_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert_eq!(prediction.predictor, PredictorKind::Ensemble);
+        assert_eq!(prediction.breakdown.len(), 3);
+        assert_eq!(prediction.breakdown[2].probability, 0.9);
+
+        let expected =
+            (prediction.breakdown[0].probability + prediction.breakdown[1].probability + 2.0 * 0.9)
+                / 4.0;
+        assert!((prediction.probability - expected).abs() < 1e-12);
+
+        // Non-ensemble predictions carry no breakdown
+        model.config.predictor = PredictorKind::Logistic;
+        let logistic = model.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert!(logistic.breakdown.is_empty());
+    }
+}
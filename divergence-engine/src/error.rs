@@ -1,5 +1,6 @@
 //! Error types for the divergence engine.
 
+use std::fmt;
 use thiserror::Error;
 
 /// Main error type for divergence engine operations.
@@ -10,13 +11,40 @@ pub enum DivergenceError {
     DimensionMismatch { expected: usize, got: usize },
 
     /// Actor not found in model
-    #[error("Unknown actor: {0}")]
-    UnknownActor(String),
+    #[error(
+        "Unknown actor: {actor}{}",
+        suggestion.as_ref().map(|s| format!(" (did you mean {}?)", s)).unwrap_or_default()
+    )]
+    UnknownActor {
+        actor: String,
+        /// Closest registered actor ID, when one is near enough
+        suggestion: Option<String>,
+    },
+
+    /// Tenant not found in a model manager
+    #[error("Unknown tenant: {0}")]
+    UnknownTenant(String),
+
+    /// Category not present in the category space
+    #[error("Unknown category: {0}")]
+    UnknownCategory(String),
+
+    /// Scheme does not share the model's category registry
+    #[error("Category registry mismatch for actor: {0}")]
+    RegistryMismatch(String),
+
+    /// D_KL(P || Q) undefined: Q has zero mass where P is positive
+    #[error("Absolute continuity violated: q has zero support at index {index} where p = {p}")]
+    SupportMismatch { index: usize, p: f64 },
 
     /// Invalid probability distribution
     #[error("Invalid distribution: {0}")]
     InvalidDistribution(String),
 
+    /// Observation refused by the outlier filter
+    #[error("Observation rejected as outlier: distance {distance} exceeds {threshold}")]
+    OutlierRejected { distance: f64, threshold: f64 },
+
     /// Numerical error (overflow, underflow, NaN)
     #[error("Numerical error: {0}")]
     NumericalError(String),
@@ -28,19 +56,142 @@ pub enum DivergenceError {
     /// Serialization error
     #[error("Serialization error: {0}")]
     SerializationError(String),
+
+    /// Error annotated with the actor, event or dyad it occurred for
+    #[error("{source} ({context})")]
+    Context {
+        context: ErrorContext,
+        source: Box<DivergenceError>,
+    },
+}
+
+/// Actor, event and dyad an error occurred for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub actor_id: Option<String>,
+    pub event_id: Option<String>,
+    pub dyad: Option<(String, String)>,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(event_id) = &self.event_id {
+            parts.push(format!("event {}", event_id));
+        }
+        if let Some(actor_id) = &self.actor_id {
+            parts.push(format!("actor {}", actor_id));
+        }
+        if let Some((a, b)) = &self.dyad {
+            parts.push(format!("dyad {}-{}", a, b));
+        }
+        f.write_str(&parts.join(", "))
+    }
 }
 
 /// Result type alias for divergence operations.
 pub type Result<T> = std::result::Result<T, DivergenceError>;
 
 impl DivergenceError {
+    /// Unknown-actor error without a suggestion
+    pub fn unknown_actor(actor: impl Into<String>) -> Self {
+        DivergenceError::UnknownActor {
+            actor: actor.into(),
+            suggestion: None,
+        }
+    }
+
     /// Check if this is a recoverable error
     pub fn is_recoverable(&self) -> bool {
         matches!(
-            self,
-            DivergenceError::NumericalError(_) | DivergenceError::InvalidDistribution(_)
+            self.root(),
+            DivergenceError::NumericalError(_)
+                | DivergenceError::InvalidDistribution(_)
+                | DivergenceError::OutlierRejected { .. }
         )
     }
+
+    /// The error without any context annotations
+    pub fn root(&self) -> &DivergenceError {
+        match self {
+            DivergenceError::Context { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    /// Context the error was annotated with, if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            DivergenceError::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Annotate with an actor, keeping any actor already recorded
+    pub fn with_actor(self, actor_id: &str) -> Self {
+        self.annotate(|c| {
+            c.actor_id.get_or_insert_with(|| actor_id.to_string());
+        })
+    }
+
+    /// Annotate with an event, keeping any event already recorded
+    pub fn with_event(self, event_id: &str) -> Self {
+        self.annotate(|c| {
+            c.event_id.get_or_insert_with(|| event_id.to_string());
+        })
+    }
+
+    /// Annotate with a dyad, keeping any dyad already recorded
+    pub fn with_dyad(self, actor_a: &str, actor_b: &str) -> Self {
+        self.annotate(|c| {
+            c.dyad
+                .get_or_insert_with(|| (actor_a.to_string(), actor_b.to_string()));
+        })
+    }
+
+    fn annotate(self, f: impl FnOnce(&mut ErrorContext)) -> Self {
+        match self {
+            DivergenceError::Context {
+                mut context,
+                source,
+            } => {
+                f(&mut context);
+                DivergenceError::Context { context, source }
+            }
+            err => {
+                let mut context = ErrorContext::default();
+                f(&mut context);
+                DivergenceError::Context {
+                    context,
+                    source: Box::new(err),
+                }
+            }
+        }
+    }
+}
+
+/// Context annotations on results
+pub trait ResultExt<T> {
+    /// Annotate an error with an actor
+    fn with_actor(self, actor_id: &str) -> Result<T>;
+    /// Annotate an error with an event
+    fn with_event(self, event_id: &str) -> Result<T>;
+    /// Annotate an error with a dyad
+    fn with_dyad(self, actor_a: &str, actor_b: &str) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn with_actor(self, actor_id: &str) -> Result<T> {
+        self.map_err(|e| e.with_actor(actor_id))
+    }
+
+    fn with_event(self, event_id: &str) -> Result<T> {
+        self.map_err(|e| e.with_event(event_id))
+    }
+
+    fn with_dyad(self, actor_a: &str, actor_b: &str) -> Result<T> {
+        self.map_err(|e| e.with_dyad(actor_a, actor_b))
+    }
 }
 
 #[cfg(feature = "wasm")]
@@ -49,3 +200,37 @@ impl From<DivergenceError> for wasm_bindgen::JsValue {
         wasm_bindgen::JsValue::from_str(&err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::CompressionDynamicsModel;
+
+    #[test]
+    fn test_context_annotations() {
+        let mut model = CompressionDynamicsModel::new(3);
+        let err = model
+            .update_scheme("USA", &[0.5, 0.5], Some(1))
+            .with_event("evt-7")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Dimension mismatch: expected 3, got 2 (event evt-7, actor USA)"
+        );
+        assert!(matches!(
+            err.root(),
+            DivergenceError::DimensionMismatch {
+                expected: 3,
+                got: 2
+            }
+        ));
+
+        let err = err.with_actor("RUS").with_dyad("USA", "RUS");
+        let context = err.context().unwrap();
+        assert_eq!(context.actor_id.as_deref(), Some("USA"));
+        assert_eq!(context.dyad, Some(("USA".to_string(), "RUS".to_string())));
+        assert!(DivergenceError::NumericalError("nan".into())
+            .with_actor("USA")
+            .is_recoverable());
+    }
+}
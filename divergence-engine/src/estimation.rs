@@ -0,0 +1,333 @@
+//! Divergence estimation from raw sample counts.
+//!
+//! Upstream feeds frequently deliver categorical *counts* per actor per
+//! window rather than fully-formed distributions. Plugging raw frequencies
+//! into KL is both unstable (unseen categories → infinite divergence) and
+//! biased upward for small samples. This module provides:
+//!
+//! - Shrinkage priors (additive pseudocounts and James-Stein shrinkage
+//!   toward uniform) for turning counts into distributions
+//! - First-order (Miller-Madow style) bias correction for KL and JS
+//!
+//! ```text
+//! E[D̂_KL] ≈ D_KL + (K - 1) / 2n_P + (Σ p_i/q_i - 1) / 2n_Q
+//! ```
+
+use crate::divergence::{kl_divergence, EPSILON};
+use crate::error::{DivergenceError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Prior used to turn raw counts into a probability distribution
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum CountPrior {
+    /// Maximum likelihood (raw frequencies, no smoothing)
+    MaximumLikelihood,
+    /// Laplace prior: add 1 to every category
+    Laplace,
+    /// Jeffreys prior: add 0.5 to every category
+    #[default]
+    Jeffreys,
+    /// Perks prior: add 1/K to every category
+    Perks,
+    /// Custom additive pseudocount per category
+    Pseudocount(f64),
+    /// James-Stein shrinkage of the ML estimate toward uniform
+    /// (Hausser & Strimmer, 2009) with data-driven intensity
+    JamesStein,
+}
+
+impl CountPrior {
+    /// Additive pseudocount per category, if this is an additive prior
+    fn pseudocount(&self, n_categories: usize) -> Option<f64> {
+        match *self {
+            CountPrior::MaximumLikelihood => Some(0.0),
+            CountPrior::Laplace => Some(1.0),
+            CountPrior::Jeffreys => Some(0.5),
+            CountPrior::Perks => Some(1.0 / n_categories as f64),
+            CountPrior::Pseudocount(a) => Some(a),
+            CountPrior::JamesStein => None,
+        }
+    }
+}
+
+/// Validate a count vector and return its total
+fn validate_counts(counts: &[f64]) -> Result<f64> {
+    if counts.is_empty() {
+        return Err(DivergenceError::InvalidDistribution(
+            "Count vector is empty".to_string(),
+        ));
+    }
+    if counts.iter().any(|&c| !c.is_finite() || c < 0.0) {
+        return Err(DivergenceError::InvalidDistribution(
+            "Counts must be finite and non-negative".to_string(),
+        ));
+    }
+    Ok(counts.iter().sum())
+}
+
+/// Estimate a probability distribution from category counts under a prior
+pub fn estimate_distribution(counts: &[f64], prior: CountPrior) -> Result<Vec<f64>> {
+    let total = validate_counts(counts)?;
+    let k = counts.len();
+
+    match prior.pseudocount(k) {
+        Some(alpha) => {
+            if !alpha.is_finite() || alpha < 0.0 {
+                return Err(DivergenceError::ConfigError(format!(
+                    "Pseudocount must be finite and non-negative, got {}",
+                    alpha
+                )));
+            }
+            let denom = total + alpha * k as f64;
+            if denom <= 0.0 {
+                return Err(DivergenceError::InvalidDistribution(
+                    "No observations and no prior mass".to_string(),
+                ));
+            }
+            Ok(counts.iter().map(|&c| (c + alpha) / denom).collect())
+        }
+        None => {
+            let target = 1.0 / k as f64;
+            if total <= 0.0 {
+                return Ok(vec![target; k]);
+            }
+            let ml: Vec<f64> = counts.iter().map(|&c| c / total).collect();
+            let lambda = james_stein_intensity(&ml, total);
+            Ok(ml
+                .iter()
+                .map(|&p| lambda * target + (1.0 - lambda) * p)
+                .collect())
+        }
+    }
+}
+
+/// Optimal James-Stein shrinkage intensity toward the uniform target
+///
+/// λ* = (1 - Σ θ̂²) / ((n - 1) Σ (t - θ̂)²), clamped to [0, 1]
+fn james_stein_intensity(ml: &[f64], n: f64) -> f64 {
+    let target = 1.0 / ml.len() as f64;
+    let sum_sq: f64 = ml.iter().map(|&p| p * p).sum();
+    let dist_sq: f64 = ml.iter().map(|&p| (target - p).powi(2)).sum();
+
+    if n <= 1.0 || dist_sq < EPSILON {
+        return 1.0;
+    }
+    ((1.0 - sum_sq) / ((n - 1.0) * dist_sq)).clamp(0.0, 1.0)
+}
+
+/// First-order bias of plug-in entropy: Σ Var(x̂_i) / 2x_i (nats)
+///
+/// `variances` holds Var(x̂_i) for each category.
+fn entropy_bias(dist: &[f64], variances: impl Iterator<Item = f64>) -> f64 {
+    dist.iter()
+        .zip(variances)
+        .filter(|(&x, _)| x > EPSILON)
+        .map(|(&x, v)| v / (2.0 * x))
+        .sum()
+}
+
+/// Estimator for divergences between two count vectors
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CountEstimator {
+    /// Prior used to turn counts into distributions
+    pub prior: CountPrior,
+
+    /// Subtract the first-order finite-sample bias from the estimate
+    pub bias_correction: bool,
+}
+
+impl Default for CountEstimator {
+    fn default() -> Self {
+        Self {
+            prior: CountPrior::default(),
+            bias_correction: true,
+        }
+    }
+}
+
+impl CountEstimator {
+    /// Create an estimator with the given prior and bias correction enabled
+    pub fn new(prior: CountPrior) -> Self {
+        Self {
+            prior,
+            bias_correction: true,
+        }
+    }
+
+    /// Enable or disable bias correction
+    pub fn with_bias_correction(mut self, enabled: bool) -> Self {
+        self.bias_correction = enabled;
+        self
+    }
+
+    fn estimate_pair(&self, p_counts: &[f64], q_counts: &[f64]) -> Result<EstimatedPair> {
+        if p_counts.len() != q_counts.len() {
+            return Err(DivergenceError::DimensionMismatch {
+                expected: p_counts.len(),
+                got: q_counts.len(),
+            });
+        }
+        Ok(EstimatedPair {
+            p: estimate_distribution(p_counts, self.prior)?,
+            q: estimate_distribution(q_counts, self.prior)?,
+            n_p: p_counts.iter().sum(),
+            n_q: q_counts.iter().sum(),
+        })
+    }
+
+    /// Estimate D_KL(P || Q) in bits from sample counts
+    pub fn kl_divergence(&self, p_counts: &[f64], q_counts: &[f64]) -> Result<f64> {
+        let pair = self.estimate_pair(p_counts, q_counts)?;
+        let kl = kl_divergence(&pair.p, &pair.q)?;
+
+        if !self.bias_correction {
+            return Ok(kl);
+        }
+        Ok((kl - pair.kl_bias_nats() / std::f64::consts::LN_2).max(0.0))
+    }
+
+    /// Estimate symmetric KL (Φ) in bits from sample counts
+    pub fn symmetric_kl(&self, p_counts: &[f64], q_counts: &[f64]) -> Result<f64> {
+        Ok(self.kl_divergence(p_counts, q_counts)? + self.kl_divergence(q_counts, p_counts)?)
+    }
+
+    /// Estimate Jensen-Shannon divergence in bits from sample counts
+    ///
+    /// Uses JS = H(M) - ½(H(P) + H(Q)) with each entropy bias-corrected.
+    pub fn jensen_shannon(&self, p_counts: &[f64], q_counts: &[f64]) -> Result<f64> {
+        let pair = self.estimate_pair(p_counts, q_counts)?;
+        let m: Vec<f64> = pair
+            .p
+            .iter()
+            .zip(pair.q.iter())
+            .map(|(&pi, &qi)| 0.5 * (pi + qi))
+            .collect();
+
+        let js = 0.5 * kl_divergence(&pair.p, &m)? + 0.5 * kl_divergence(&pair.q, &m)?;
+
+        if !self.bias_correction {
+            return Ok(js);
+        }
+
+        // Plug-in entropies are biased low by Σ Var / 2x, so
+        // JS_plugin ≈ JS - bias(H_M) + ½(bias(H_P) + bias(H_Q))
+        let var_p = pair.multinomial_variances(&pair.p, pair.n_p);
+        let var_q = pair.multinomial_variances(&pair.q, pair.n_q);
+        let var_m = var_p.iter().zip(var_q.iter()).map(|(a, b)| 0.25 * (a + b));
+
+        let bias_m = entropy_bias(&m, var_m);
+        let bias_p = entropy_bias(&pair.p, var_p.iter().copied());
+        let bias_q = entropy_bias(&pair.q, var_q.iter().copied());
+        let bias = 0.5 * (bias_p + bias_q) - bias_m;
+
+        Ok((js - bias / std::f64::consts::LN_2).clamp(0.0, 1.0))
+    }
+}
+
+/// Two count vectors after prior smoothing, with their sample sizes
+struct EstimatedPair {
+    p: Vec<f64>,
+    q: Vec<f64>,
+    n_p: f64,
+    n_q: f64,
+}
+
+impl EstimatedPair {
+    /// Per-category multinomial variance p(1-p)/n (zero if no samples)
+    fn multinomial_variances(&self, dist: &[f64], n: f64) -> Vec<f64> {
+        if n <= 0.0 {
+            return vec![0.0; dist.len()];
+        }
+        dist.iter().map(|&x| x * (1.0 - x) / n).collect()
+    }
+
+    /// First-order bias of plug-in D_KL(P || Q) in nats
+    fn kl_bias_nats(&self) -> f64 {
+        let mut bias = 0.0;
+        if self.n_p > 0.0 {
+            bias += entropy_bias(
+                &self.p,
+                self.multinomial_variances(&self.p, self.n_p).into_iter(),
+            );
+        }
+        if self.n_q > 0.0 {
+            let ratio: f64 = self
+                .p
+                .iter()
+                .zip(self.q.iter())
+                .map(|(&pi, &qi)| pi / qi.max(EPSILON))
+                .sum();
+            bias += (ratio - 1.0).max(0.0) / (2.0 * self.n_q);
+        }
+        bias
+    }
+}
+
+/// Estimate D_KL(P || Q) from counts with the default estimator
+pub fn kl_from_counts(p_counts: &[f64], q_counts: &[f64], prior: CountPrior) -> Result<f64> {
+    CountEstimator::new(prior).kl_divergence(p_counts, q_counts)
+}
+
+/// Estimate Jensen-Shannon divergence from counts with the default estimator
+pub fn js_from_counts(p_counts: &[f64], q_counts: &[f64], prior: CountPrior) -> Result<f64> {
+    CountEstimator::new(prior).jensen_shannon(p_counts, q_counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_distribution_priors() {
+        let counts = vec![3.0, 1.0, 0.0];
+
+        let ml = estimate_distribution(&counts, CountPrior::MaximumLikelihood).unwrap();
+        assert!((ml[0] - 0.75).abs() < 1e-12);
+        assert_eq!(ml[2], 0.0);
+
+        let laplace = estimate_distribution(&counts, CountPrior::Laplace).unwrap();
+        assert!((laplace[2] - 1.0 / 7.0).abs() < 1e-12);
+
+        let js = estimate_distribution(&counts, CountPrior::JamesStein).unwrap();
+        assert!(js[2] > 0.0);
+        assert!((js.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_counts() {
+        assert!(estimate_distribution(&[], CountPrior::Laplace).is_err());
+        assert!(estimate_distribution(&[1.0, -1.0], CountPrior::Laplace).is_err());
+        assert!(estimate_distribution(&[0.0, 0.0], CountPrior::MaximumLikelihood).is_err());
+        assert!(kl_from_counts(&[1.0, 2.0], &[1.0], CountPrior::Laplace).is_err());
+    }
+
+    #[test]
+    fn test_bias_correction_reduces_estimate() {
+        // Samples from the same distribution: the true divergence is zero,
+        // but the plug-in estimate is positive.
+        let p = vec![12.0, 9.0, 5.0, 4.0];
+        let q = vec![10.0, 11.0, 3.0, 6.0];
+
+        let raw = CountEstimator::new(CountPrior::Jeffreys)
+            .with_bias_correction(false)
+            .kl_divergence(&p, &q)
+            .unwrap();
+        let corrected = kl_from_counts(&p, &q, CountPrior::Jeffreys).unwrap();
+
+        assert!(raw > 0.0);
+        assert!(corrected < raw);
+        assert!(corrected >= 0.0);
+    }
+
+    #[test]
+    fn test_js_from_counts_bounds() {
+        let p = vec![50.0, 0.0, 0.0];
+        let q = vec![0.0, 0.0, 50.0];
+
+        let js = js_from_counts(&p, &q, CountPrior::Laplace).unwrap();
+        assert!(js > 0.5 && js <= 1.0);
+
+        let same = js_from_counts(&p, &p, CountPrior::Laplace).unwrap();
+        assert!(same < 1e-9);
+    }
+}
@@ -0,0 +1,325 @@
+//! Versioned schema for [`StreamEvent`] payloads.
+//!
+//! Events carry a `schema_version`, so producers and the processor can be
+//! upgraded independently: the processor validates a payload against the
+//! version it declares, then upgrades it step by step to the current one.
+//!
+//! ```text
+//! v1  event_id, actor_id, observation, timestamp_ms, source, metadata
+//!     (payloads without schema_version)
+//! v2  + schema_version, target_actor_id, target_observation
+//! ```
+//!
+//! Validation is strict and reports every offending field at once, rather
+//! than serde's first error:
+//!
+//! ```text
+//! Invalid event payload e17 (schema v2): observation: expected a non-empty
+//! array of finite numbers; colour: unknown field
+//! ```
+//!
+//! Payloads from a newer schema than this build understands are rejected
+//! with the newest supported version in the message.
+
+use crate::error::{DivergenceError, Result};
+use crate::streaming::StreamEvent;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Schema version of events this build produces
+pub const EVENT_SCHEMA_VERSION: u32 = 2;
+
+/// Version assumed for payloads without `schema_version`
+pub const LEGACY_EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Fields each schema version defines
+fn known_fields(version: u32) -> &'static [&'static str] {
+    const V1: &[&str] = &[
+        "event_id",
+        "actor_id",
+        "observation",
+        "timestamp_ms",
+        "source",
+        "metadata",
+        // Allowed so a v1 payload may state its version explicitly
+        "schema_version",
+    ];
+    const V2: &[&str] = &[
+        "event_id",
+        "actor_id",
+        "observation",
+        "timestamp_ms",
+        "source",
+        "metadata",
+        "schema_version",
+        "target_actor_id",
+        "target_observation",
+    ];
+    match version {
+        1 => V1,
+        _ => V2,
+    }
+}
+
+/// A field that does not match the schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub field: String,
+    pub problem: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.problem)
+    }
+}
+
+/// Schema version a payload declares
+pub fn declared_version(payload: &Value) -> Result<u32> {
+    let version = match payload.get("schema_version") {
+        None => LEGACY_EVENT_SCHEMA_VERSION,
+        Some(v) => v
+            .as_u64()
+            .filter(|&v| v >= 1)
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| {
+                DivergenceError::SerializationError(format!("Invalid event schema_version: {}", v))
+            })?,
+    };
+    if version > EVENT_SCHEMA_VERSION {
+        return Err(DivergenceError::SerializationError(format!(
+            "Unsupported event schema version {} (newest supported is {})",
+            version, EVENT_SCHEMA_VERSION
+        )));
+    }
+    Ok(version)
+}
+
+/// Every way a payload departs from the schema version it declares
+pub fn validate_event(payload: &Value, version: u32) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    let mut violation = |field: &str, problem: &str| {
+        violations.push(SchemaViolation {
+            field: field.to_string(),
+            problem: problem.to_string(),
+        })
+    };
+    let Some(object) = payload.as_object() else {
+        violation("(event)", "expected an object");
+        return violations;
+    };
+
+    for field in object.keys() {
+        if !known_fields(version).contains(&field.as_str()) {
+            violation(field, "unknown field");
+        }
+    }
+
+    let field = |name: &str| object.get(name).filter(|v| !v.is_null());
+    for name in ["event_id", "actor_id"] {
+        match field(name) {
+            None => violation(name, "missing"),
+            Some(Value::String(s)) if !s.is_empty() => {}
+            Some(_) => violation(name, "expected a non-empty string"),
+        }
+    }
+    match field("observation") {
+        None => violation("observation", "missing"),
+        Some(v) if !is_observation(v) => violation(
+            "observation",
+            "expected a non-empty array of finite numbers",
+        ),
+        Some(_) => {}
+    }
+    match field("timestamp_ms") {
+        None => violation("timestamp_ms", "missing"),
+        Some(v) if v.as_i64().is_none() => violation("timestamp_ms", "expected an integer"),
+        Some(_) => {}
+    }
+    match field("source") {
+        None => violation("source", "missing"),
+        Some(Value::String(_)) => {}
+        Some(_) => violation("source", "expected a string"),
+    }
+    if let Some(metadata) = field("metadata") {
+        let strings = metadata
+            .as_object()
+            .is_some_and(|m| m.values().all(Value::is_string));
+        if !strings {
+            violation("metadata", "expected an object of string values");
+        }
+    }
+
+    if version >= 2 {
+        let target = field("target_actor_id");
+        let target_observation = field("target_observation");
+        match target {
+            Some(Value::String(s)) if !s.is_empty() => {}
+            Some(_) => violation("target_actor_id", "expected a non-empty string"),
+            None => {}
+        }
+        if target_observation.is_some_and(|v| !is_observation(v)) {
+            violation(
+                "target_observation",
+                "expected a non-empty array of finite numbers",
+            );
+        }
+        match (target, target_observation) {
+            (Some(_), None) => violation("target_observation", "required with target_actor_id"),
+            (None, Some(_)) => violation("target_actor_id", "required with target_observation"),
+            _ => {}
+        }
+    }
+
+    violations
+}
+
+/// [`validate_event`] for an event a binary codec decoded directly
+pub fn validate_decoded_event(event: &StreamEvent, version: u32) -> Vec<SchemaViolation> {
+    let mut payload = match serde_json::to_value(event) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return vec![SchemaViolation {
+                field: "(event)".to_string(),
+                problem: e.to_string(),
+            }]
+        }
+    };
+    if let Some(object) = payload.as_object_mut() {
+        // Unset optional fields, not fields the payload carried
+        object.retain(|_, v| !v.is_null());
+    }
+    validate_event(&payload, version)
+}
+
+fn is_observation(value: &Value) -> bool {
+    value.as_array().is_some_and(|values| {
+        !values.is_empty()
+            && values
+                .iter()
+                .all(|v| v.as_f64().is_some_and(|x| x.is_finite()))
+    })
+}
+
+/// Upgrade a valid payload from `version` to [`EVENT_SCHEMA_VERSION`]
+pub fn upgrade_event(mut payload: Map<String, Value>, version: u32) -> Map<String, Value> {
+    let mut version = version;
+    while version < EVENT_SCHEMA_VERSION {
+        match version {
+            // v1 → v2: interaction fields are new and optional
+            1 => {}
+            _ => unreachable!("no upgrade from schema v{}", version),
+        }
+        version += 1;
+    }
+    payload.insert("schema_version".to_string(), Value::from(version));
+    payload
+}
+
+impl StreamEvent {
+    /// Decode an event payload strictly, upgrading older schema versions
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let payload: Value = serde_json::from_slice(bytes)
+            .map_err(|e| DivergenceError::SerializationError(format!("Invalid JSON: {}", e)))?;
+        Self::from_json_value(payload)
+    }
+
+    /// As [`from_json`](Self::from_json), from parsed JSON
+    pub fn from_json_value(payload: Value) -> Result<Self> {
+        let version = declared_version(&payload)?;
+        let violations = validate_event(&payload, version);
+        if !violations.is_empty() {
+            let event_id = payload
+                .get("event_id")
+                .and_then(Value::as_str)
+                .map(|id| format!(" {}", id))
+                .unwrap_or_default();
+            let problems: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+            return Err(DivergenceError::SerializationError(format!(
+                "Invalid event payload{} (schema v{}): {}",
+                event_id,
+                version,
+                problems.join("; ")
+            )));
+        }
+        let Value::Object(object) = payload else {
+            unreachable!("validated as an object");
+        };
+        serde_json::from_value(Value::Object(upgrade_event(object, version)))
+            .map_err(|e| DivergenceError::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_event_schema_versions() {
+        // Legacy payload: no schema_version, upgraded on read
+        let v1 = json!({
+            "event_id": "e1", "actor_id": "A", "observation": [0.5, 0.5],
+            "timestamp_ms": 10, "source": "gdelt"
+        });
+        let event = StreamEvent::from_json_value(v1.clone()).unwrap();
+        assert_eq!(event.schema_version, EVENT_SCHEMA_VERSION);
+        assert_eq!(event.event_id, "e1");
+
+        // Current events roundtrip
+        let current = StreamEvent::new("e2", "A", vec![0.2, 0.8], 20).with_target("B", vec![1.0]);
+        let bytes = serde_json::to_vec(&current).unwrap();
+        let back = StreamEvent::from_json(&bytes).unwrap();
+        assert_eq!(back.target_actor_id.as_deref(), Some("B"));
+
+        // Interaction fields did not exist in v1
+        let mut v1_target = v1.clone();
+        v1_target["target_actor_id"] = json!("B");
+        let err = StreamEvent::from_json_value(v1_target).unwrap_err();
+        assert!(err.to_string().contains("target_actor_id: unknown field"));
+
+        // Newer producers are rejected with the supported version
+        let mut future = v1;
+        future["schema_version"] = json!(EVENT_SCHEMA_VERSION + 1);
+        let err = StreamEvent::from_json_value(future).unwrap_err();
+        assert!(err.to_string().contains("newest supported is 2"));
+    }
+
+    #[test]
+    fn test_event_schema_lists_every_violation() {
+        let payload = json!({
+            "schema_version": 2,
+            "event_id": "e3",
+            "actor_id": "",
+            "observation": [0.5, "x"],
+            "source": "feed",
+            "metadata": {"geo": 3},
+            "target_actor_id": "B",
+            "colour": "red"
+        });
+        let mut fields: Vec<String> = validate_event(&payload, 2)
+            .into_iter()
+            .map(|v| v.field)
+            .collect();
+        fields.sort();
+        assert_eq!(
+            fields,
+            [
+                "actor_id",
+                "colour",
+                "metadata",
+                "observation",
+                "target_observation",
+                "timestamp_ms"
+            ]
+        );
+
+        let err = StreamEvent::from_json_value(payload)
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Serialization error: Invalid event payload e3 (schema v2): "));
+        assert!(err.contains("timestamp_ms: missing"));
+        assert!(StreamEvent::from_json(b"[1, 2]").is_err());
+        assert!(StreamEvent::from_json(b"{").is_err());
+    }
+}
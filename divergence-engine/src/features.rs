@@ -0,0 +1,345 @@
+//! Fixed-schema feature vectors for ML pipelines.
+//!
+//! Users who want to train their own escalation classifiers need the
+//! engine's view of a dyad as plain numbers. [`FeatureVector`] lays it out
+//! in the order given by [`FEATURE_NAMES`]; the schema only ever grows at
+//! the end, so column indices stay valid across versions.
+//!
+//! The variance phase is a lightweight analogue of the variance inflection
+//! detector in `nucleation`: over the dyad's recent Φ window it compares
+//! the variance of the newer half with that of the older half,
+//!
+//! ```text
+//! r = Var(newer) / Var(older)
+//! ```
+//!
+//! and reports `Approaching` for r ≥ 2, `Critical` for r ≥ 4 and
+//! `Transitioning` when the latest Φ sits more than 3σ (of the older
+//! half) from the older mean.
+
+use crate::error::Result;
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::ConflictPotential;
+use serde::{Deserialize, Serialize};
+
+/// Number of columns in a feature vector
+pub const N_FEATURES: usize = 13;
+
+/// Column names, in vector order
+pub const FEATURE_NAMES: [&str; N_FEATURES] = [
+    "phi",
+    "js",
+    "hellinger",
+    "asymmetry",
+    "d_phi_dt",
+    "grievance_a",
+    "grievance_b",
+    "entropy_a",
+    "entropy_b",
+    "phase_stable",
+    "phase_approaching",
+    "phase_critical",
+    "phase_transitioning",
+];
+
+/// Φ samples needed before a phase other than `Stable` is reported
+pub const MIN_PHASE_SAMPLES: usize = 6;
+
+/// Variance-dynamics phase of a Φ series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum VariancePhase {
+    #[default]
+    Stable,
+    Approaching,
+    Critical,
+    Transitioning,
+}
+
+impl VariancePhase {
+    /// Classify the most recent point of a Φ series (oldest first)
+    pub fn from_series(phi: &[f64]) -> Self {
+        if phi.len() < MIN_PHASE_SAMPLES {
+            return VariancePhase::Stable;
+        }
+        let (older, newer) = phi.split_at(phi.len() / 2);
+        let (mean_old, var_old) = mean_var(older);
+        let (_, var_new) = mean_var(newer);
+        let latest = phi[phi.len() - 1];
+
+        let std_old = var_old.sqrt();
+        if std_old > 1e-12 && (latest - mean_old).abs() > 3.0 * std_old {
+            return VariancePhase::Transitioning;
+        }
+        let ratio = var_new / var_old.max(1e-12);
+        if ratio >= 4.0 {
+            VariancePhase::Critical
+        } else if ratio >= 2.0 {
+            VariancePhase::Approaching
+        } else {
+            VariancePhase::Stable
+        }
+    }
+
+    /// One-hot encoding `[stable, approaching, critical, transitioning]`
+    pub fn one_hot(&self) -> [f64; 4] {
+        let mut encoded = [0.0; 4];
+        encoded[*self as usize] = 1.0;
+        encoded
+    }
+}
+
+fn mean_var(xs: &[f64]) -> (f64, f64) {
+    let n = xs.len() as f64;
+    let mean = xs.iter().sum::<f64>() / n;
+    let var = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    (mean, var)
+}
+
+/// Engine-derived features for one dyad at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureVector {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub timestamp_ms: Option<i64>,
+    /// Values in [`FEATURE_NAMES`] order
+    pub values: [f64; N_FEATURES],
+}
+
+impl FeatureVector {
+    /// Value of a named feature
+    pub fn get(&self, name: &str) -> Option<f64> {
+        FEATURE_NAMES
+            .iter()
+            .position(|&n| n == name)
+            .map(|i| self.values[i])
+    }
+
+    /// Variance phase encoded in the one-hot columns
+    pub fn phase(&self) -> VariancePhase {
+        match self.values[9..].iter().position(|&v| v > 0.5) {
+            Some(1) => VariancePhase::Approaching,
+            Some(2) => VariancePhase::Critical,
+            Some(3) => VariancePhase::Transitioning,
+            _ => VariancePhase::Stable,
+        }
+    }
+}
+
+/// Render feature vectors as CSV with a header row
+pub fn features_to_csv(vectors: &[FeatureVector]) -> String {
+    let mut csv = format!("actor_a,actor_b,timestamp_ms,{}\n", FEATURE_NAMES.join(","));
+    for v in vectors {
+        let values: Vec<String> = v.values.iter().map(|x| x.to_string()).collect();
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            v.actor_a,
+            v.actor_b,
+            v.timestamp_ms.map(|t| t.to_string()).unwrap_or_default(),
+            values.join(",")
+        ));
+    }
+    csv
+}
+
+impl CompressionDynamicsModel {
+    /// Current features for a dyad, without recording anything
+    ///
+    /// dΦ/dt and the variance phase are measured against the dyad's
+    /// recorded potentials, as in [`peek_escalation`](Self::peek_escalation).
+    pub fn feature_vector(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        timestamp_ms: i64,
+    ) -> Result<FeatureVector> {
+        let current = self.peek_potential(actor_a, actor_b)?;
+        let (actor_a, actor_b) = (current.actor_a.as_str(), current.actor_b.as_str());
+        let mut phis: Vec<f64> = self
+            .get_dyad_history(actor_a, actor_b)
+            .iter()
+            .map(|p| p.phi)
+            .collect();
+        let d_phi = phis.last().map_or(0.0, |last| current.phi - last);
+        phis.push(current.phi);
+
+        let grievance = |actor: &str| self.grievances.get(actor).map_or(0.0, |g| g.window_error);
+        Ok(self.assemble(
+            actor_a,
+            actor_b,
+            Some(timestamp_ms),
+            &current,
+            d_phi,
+            (grievance(actor_a), grievance(actor_b)),
+            self.phase_window(&phis),
+        ))
+    }
+
+    /// Features for every recorded potential of a dyad, oldest first
+    ///
+    /// Grievances are reconstructed as of each potential's timestamp;
+    /// potentials without a timestamp use current grievances.
+    pub fn export_features(&self, actor_a: &str, actor_b: &str) -> Result<Vec<FeatureVector>> {
+        let actor_a = self.require_actor(actor_a)?;
+        let actor_b = self.require_actor(actor_b)?;
+        let history = self.get_dyad_history(actor_a, actor_b);
+        let phis: Vec<f64> = history.iter().map(|p| p.phi).collect();
+
+        Ok(history
+            .iter()
+            .enumerate()
+            .map(|(k, potential)| {
+                let d_phi = if k > 0 { phis[k] - phis[k - 1] } else { 0.0 };
+                let grievances = (
+                    self.grievance_at(actor_a, potential.timestamp_ms),
+                    self.grievance_at(actor_b, potential.timestamp_ms),
+                );
+                self.assemble(
+                    actor_a,
+                    actor_b,
+                    potential.timestamp_ms,
+                    potential,
+                    d_phi,
+                    grievances,
+                    self.phase_window(&phis[..=k]),
+                )
+            })
+            .collect())
+    }
+
+    /// Feature history of every dyad with recorded potentials
+    pub fn export_all_features(&self) -> Vec<FeatureVector> {
+        let actors = self.actors();
+        let mut vectors = Vec::new();
+        for i in 0..actors.len() {
+            for j in (i + 1)..actors.len() {
+                if let Ok(dyad) = self.export_features(actors[i], actors[j]) {
+                    vectors.extend(dyad);
+                }
+            }
+        }
+        vectors
+    }
+
+    fn phase_window(&self, phis: &[f64]) -> VariancePhase {
+        let window = self.config.phi_baseline_window.max(MIN_PHASE_SAMPLES);
+        VariancePhase::from_series(&phis[phis.len().saturating_sub(window)..])
+    }
+
+    /// Windowed grievance an actor had at `timestamp_ms`
+    fn grievance_at(&self, actor_id: &str, timestamp_ms: Option<i64>) -> f64 {
+        let Some(grievance) = self.grievances.get(actor_id) else {
+            return 0.0;
+        };
+        let Some(ts) = timestamp_ms else {
+            return grievance.window_error;
+        };
+        let n = self
+            .history
+            .iter()
+            .filter(|e| e.actor_id == actor_id && e.timestamp_ms <= ts)
+            .count()
+            .min(grievance.error_history.len());
+        let start = n.saturating_sub(self.config.grievance_window);
+        let window = &grievance.error_history[start..n];
+        if window.is_empty() {
+            0.0
+        } else {
+            window.iter().sum::<f64>() / window.len() as f64
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn assemble(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        timestamp_ms: Option<i64>,
+        potential: &ConflictPotential,
+        d_phi: f64,
+        grievances: (f64, f64),
+        phase: VariancePhase,
+    ) -> FeatureVector {
+        let (entropy_a, entropy_b) = if potential.actor_a == actor_a {
+            (potential.entropy_a, potential.entropy_b)
+        } else {
+            (potential.entropy_b, potential.entropy_a)
+        };
+        let [stable, approaching, critical, transitioning] = phase.one_hot();
+        FeatureVector {
+            actor_a: actor_a.to_string(),
+            actor_b: actor_b.to_string(),
+            timestamp_ms,
+            values: [
+                potential.phi,
+                potential.js,
+                potential.hellinger,
+                potential.asymmetry(),
+                d_phi,
+                grievances.0,
+                grievances.1,
+                entropy_a,
+                entropy_b,
+                stable,
+                approaching,
+                critical,
+                transitioning,
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variance_phase() {
+        assert_eq!(VariancePhase::from_series(&[1.0; 4]), VariancePhase::Stable);
+        let calm = [1.0, 1.1, 0.9, 1.0, 1.1, 0.9, 1.0, 1.1];
+        assert_eq!(VariancePhase::from_series(&calm), VariancePhase::Stable);
+        let widening = [1.0, 1.05, 0.95, 1.0, 1.2, 0.8, 1.25, 0.9];
+        assert_eq!(
+            VariancePhase::from_series(&widening),
+            VariancePhase::Critical
+        );
+        let jump = [1.0, 1.1, 0.9, 1.0, 1.1, 0.9, 1.0, 3.0];
+        assert_eq!(
+            VariancePhase::from_series(&jump),
+            VariancePhase::Transitioning
+        );
+        assert_eq!(VariancePhase::Critical.one_hot(), [0.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_feature_vector_and_export() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("B", Some(vec![0.2, 0.3, 0.5]), None);
+        for t in 0..5 {
+            model
+                .update_scheme("A", &[0.9, 0.05, 0.05], Some(t * 1000))
+                .unwrap();
+            model.compute_conflict_potential("B", "A").unwrap();
+        }
+
+        let current = model.feature_vector("A", "B", 9000).unwrap();
+        assert_eq!(current.timestamp_ms, Some(9000));
+        assert_eq!(current.get("d_phi_dt"), Some(0.0));
+        assert!(current.get("grievance_a").unwrap() > 0.0);
+        assert_eq!(current.get("grievance_b"), Some(0.0));
+        assert!(current.get("entropy_a").unwrap() < current.get("entropy_b").unwrap());
+        assert_eq!(current.phase(), VariancePhase::Transitioning);
+
+        let history = model.export_features("A", "B").unwrap();
+        assert_eq!(history.len(), 5);
+        assert!(history[1].get("d_phi_dt").unwrap() > 0.0);
+        // Grievance as of the first potential covers one observation only
+        assert!(history[0].get("grievance_a").unwrap() > current.get("grievance_a").unwrap());
+        assert_eq!(model.export_all_features().len(), 5);
+
+        let csv = features_to_csv(&history);
+        assert_eq!(csv.lines().count(), 6);
+        assert!(csv.starts_with("actor_a,actor_b,timestamp_ms,phi,js"));
+        assert!(model.export_features("A", "Z").is_err());
+    }
+}
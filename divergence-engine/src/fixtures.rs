@@ -0,0 +1,515 @@
+//! Golden-scenario regression corpus.
+//!
+//! Canonical, fully deterministic scenarios with the metric trajectories
+//! this engine version produces for them. Downstream crates can replay a
+//! scenario after upgrading and assert the engine still reproduces the
+//! known outputs:
+//!
+//! - [`escalation`]: a 2014-style slide from cooperation into armed
+//!   confrontation as communication collapses
+//! - [`detente`]: a hostile dyad converging back toward cooperation
+//! - [`flash_crisis`]: a calm dyad hit by a three-day shock, then recovery
+//!
+//! Each step updates every listed actor, then calls `predict_escalation` on
+//! the watched dyad. Expected values are stored rounded to 1e-9, so
+//! compare with [`GOLDEN_TOLERANCE`] or looser.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::{CompressionDynamicsModel, ModelConfig};
+use serde::{Deserialize, Serialize};
+
+/// Tolerance the stored trajectories are accurate to
+pub const GOLDEN_TOLERANCE: f64 = 1e-6;
+
+/// Category names shared by all scenarios
+pub const SCENARIO_CATEGORIES: [&str; 4] =
+    ["cooperation", "friction", "posturing", "armed_conflict"];
+
+const DAY_MS: i64 = 86_400_000;
+/// 2014-01-01T00:00:00Z
+const EPOCH_MS: i64 = 1_388_534_400_000;
+
+const COOPERATIVE: [f64; 4] = [0.70, 0.20, 0.07, 0.03];
+const TENSE: [f64; 4] = [0.15, 0.35, 0.35, 0.15];
+const HOSTILE: [f64; 4] = [0.03, 0.12, 0.35, 0.50];
+
+/// Observations and context for one scenario step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioStep {
+    pub timestamp_ms: i64,
+    /// (actor, observation) pairs applied in order
+    pub observations: Vec<(String, Vec<f64>)>,
+    pub communication_level: f64,
+    pub shock_intensity: f64,
+}
+
+/// Per-step metrics of the watched dyad
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trajectory {
+    pub phi: Vec<f64>,
+    pub escalation_probability: Vec<f64>,
+}
+
+impl Trajectory {
+    /// Largest absolute Φ and probability differences, and the first step
+    /// where either exceeds `tolerance`
+    ///
+    /// A length mismatch counts as divergence at the shorter length. Fails
+    /// if either trajectory has different numbers of Φ and probability
+    /// values.
+    pub fn compare(&self, other: &Trajectory, tolerance: f64) -> Result<TrajectoryComparison> {
+        self.check_lengths()?;
+        other.check_lengths()?;
+        let max_error = |a: &[f64], b: &[f64]| {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| (x - y).abs())
+                .fold(0.0, f64::max)
+        };
+        let steps = self.phi.len().min(other.phi.len());
+        let first_divergent_step = (0..steps)
+            .find(|&i| {
+                (self.phi[i] - other.phi[i]).abs() > tolerance
+                    || (self.escalation_probability[i] - other.escalation_probability[i]).abs()
+                        > tolerance
+            })
+            .or((self.phi.len() != other.phi.len()).then_some(steps));
+        Ok(TrajectoryComparison {
+            max_phi_error: max_error(&self.phi, &other.phi),
+            max_probability_error: max_error(
+                &self.escalation_probability,
+                &other.escalation_probability,
+            ),
+            first_divergent_step,
+        })
+    }
+
+    fn check_lengths(&self) -> Result<()> {
+        if self.phi.len() != self.escalation_probability.len() {
+            return Err(DivergenceError::ConfigError(format!(
+                "Trajectory has {} Φ values but {} escalation probabilities",
+                self.phi.len(),
+                self.escalation_probability.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of comparing two trajectories
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrajectoryComparison {
+    pub max_phi_error: f64,
+    pub max_probability_error: f64,
+    pub first_divergent_step: Option<usize>,
+}
+
+impl TrajectoryComparison {
+    /// Whether the trajectories agree within the tolerance compared at
+    pub fn matches(&self) -> bool {
+        self.first_divergent_step.is_none()
+    }
+}
+
+/// A canonical scenario and its expected trajectory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub description: String,
+    pub config: ModelConfig,
+    /// (actor, initial distribution) pairs registered before the first step
+    pub actors: Vec<(String, Vec<f64>)>,
+    /// Dyad whose metrics are tracked
+    pub dyad: (String, String),
+    pub steps: Vec<ScenarioStep>,
+    pub expected: Trajectory,
+}
+
+impl Scenario {
+    /// Fresh model with the scenario's actors registered
+    pub fn model(&self) -> CompressionDynamicsModel {
+        let categories: Vec<String> = SCENARIO_CATEGORIES.iter().map(|c| c.to_string()).collect();
+        let mut model = CompressionDynamicsModel::with_config(self.config.clone());
+        for (actor, initial) in &self.actors {
+            model.register_actor(
+                actor.as_str(),
+                Some(initial.clone()),
+                Some(categories.clone()),
+            );
+        }
+        model
+    }
+
+    /// Replay the scenario on a fresh model
+    pub fn replay(&self) -> Result<Trajectory> {
+        self.replay_on(&mut self.model())
+    }
+
+    /// Replay the scenario on a caller-prepared model
+    pub fn replay_on(&self, model: &mut CompressionDynamicsModel) -> Result<Trajectory> {
+        let (a, b) = (&self.dyad.0, &self.dyad.1);
+        let mut trajectory = Trajectory::default();
+        for step in &self.steps {
+            for (actor, observation) in &step.observations {
+                model.update_scheme(actor, observation, Some(step.timestamp_ms))?;
+            }
+            let prediction =
+                model.predict_escalation(a, b, step.communication_level, step.shock_intensity)?;
+            trajectory.phi.push(prediction.current_phi);
+            trajectory
+                .escalation_probability
+                .push(prediction.probability);
+        }
+        Ok(trajectory)
+    }
+
+    /// Replay and compare against the expected trajectory
+    pub fn check(&self, tolerance: f64) -> Result<TrajectoryComparison> {
+        self.replay()?.compare(&self.expected, tolerance)
+    }
+}
+
+/// Every canonical scenario
+pub fn all() -> Vec<Scenario> {
+    vec![escalation(), detente(), flash_crisis()]
+}
+
+/// Cooperation sliding into armed confrontation over 24 days
+///
+/// NORTH drifts toward hostile postures while SOUTH only grows tense;
+/// communication falls from 0.8 to 0.2 and a mid-crisis shock hits on
+/// days 16–17.
+pub fn escalation() -> Scenario {
+    let steps = (0..24)
+        .map(|t| {
+            let w = t as f64 / 23.0;
+            step(
+                t,
+                &[
+                    ("NORTH", mix(&COOPERATIVE, &HOSTILE, 0.9 * w)),
+                    ("SOUTH", mix(&COOPERATIVE, &TENSE, 0.6 * w)),
+                ],
+                0.8 - 0.6 * w,
+                if (16..=17).contains(&t) { 0.3 } else { 0.0 },
+            )
+        })
+        .collect();
+    scenario(
+        "escalation",
+        "Cooperative dyad sliding into armed confrontation as communication collapses",
+        [COOPERATIVE, COOPERATIVE],
+        steps,
+        Trajectory {
+            phi: ESCALATION_PHI.to_vec(),
+            escalation_probability: ESCALATION_PROBABILITY.to_vec(),
+        },
+    )
+}
+
+/// A hostile dyad converging toward cooperation over 24 days
+///
+/// NORTH starts hostile and moves to cooperative observations while SOUTH
+/// stays cooperative; communication rises from 0.2 to 0.9.
+pub fn detente() -> Scenario {
+    let steps = (0..24)
+        .map(|t| {
+            let w = t as f64 / 23.0;
+            step(
+                t,
+                &[
+                    ("NORTH", mix(&HOSTILE, &COOPERATIVE, w)),
+                    ("SOUTH", COOPERATIVE.to_vec()),
+                ],
+                0.2 + 0.7 * w,
+                0.0,
+            )
+        })
+        .collect();
+    scenario(
+        "detente",
+        "Hostile dyad converging back toward cooperation as dialogue resumes",
+        [HOSTILE, COOPERATIVE],
+        steps,
+        Trajectory {
+            phi: DETENTE_PHI.to_vec(),
+            escalation_probability: DETENTE_PROBABILITY.to_vec(),
+        },
+    )
+}
+
+/// A calm dyad hit by a three-day shock on days 10–12, then recovering
+pub fn flash_crisis() -> Scenario {
+    let steps = (0..24)
+        .map(|t| {
+            let crisis = (10..=12).contains(&t);
+            step(
+                t,
+                &[
+                    ("NORTH", if crisis { HOSTILE } else { COOPERATIVE }.to_vec()),
+                    ("SOUTH", COOPERATIVE.to_vec()),
+                ],
+                if crisis { 0.3 } else { 0.7 },
+                if crisis { 0.8 } else { 0.0 },
+            )
+        })
+        .collect();
+    scenario(
+        "flash_crisis",
+        "Calm dyad hit by a sudden three-day shock, then recovering",
+        [COOPERATIVE, COOPERATIVE],
+        steps,
+        Trajectory {
+            phi: FLASH_CRISIS_PHI.to_vec(),
+            escalation_probability: FLASH_CRISIS_PROBABILITY.to_vec(),
+        },
+    )
+}
+
+fn scenario(
+    name: &str,
+    description: &str,
+    initial: [[f64; 4]; 2],
+    steps: Vec<ScenarioStep>,
+    expected: Trajectory,
+) -> Scenario {
+    Scenario {
+        name: name.to_string(),
+        description: description.to_string(),
+        config: ModelConfig {
+            n_categories: SCENARIO_CATEGORIES.len(),
+            ..Default::default()
+        },
+        actors: vec![
+            ("NORTH".to_string(), initial[0].to_vec()),
+            ("SOUTH".to_string(), initial[1].to_vec()),
+        ],
+        dyad: ("NORTH".to_string(), "SOUTH".to_string()),
+        steps,
+        expected,
+    }
+}
+
+fn step(
+    day: i64,
+    observations: &[(&str, Vec<f64>)],
+    communication_level: f64,
+    shock_intensity: f64,
+) -> ScenarioStep {
+    ScenarioStep {
+        timestamp_ms: EPOCH_MS + day * DAY_MS,
+        observations: observations
+            .iter()
+            .map(|(actor, obs)| (actor.to_string(), obs.clone()))
+            .collect(),
+        communication_level,
+        shock_intensity,
+    }
+}
+
+fn mix(from: &[f64; 4], to: &[f64; 4], w: f64) -> Vec<f64> {
+    from.iter()
+        .zip(to)
+        .map(|(a, b)| (1.0 - w) * a + w * b)
+        .collect()
+}
+
+const ESCALATION_PHI: [f64; 24] = [
+    0.000000000,
+    0.000117346,
+    0.000931415,
+    0.003234293,
+    0.007732671,
+    0.014927275,
+    0.025113359,
+    0.038428415,
+    0.054904999,
+    0.074513533,
+    0.097192929,
+    0.122871179,
+    0.151478785,
+    0.182957449,
+    0.217265831,
+    0.254383636,
+    0.294314940,
+    0.337091414,
+    0.382775978,
+    0.431467390,
+    0.483306320,
+    0.538483611,
+    0.597251712,
+    0.659940736,
+];
+const ESCALATION_PROBABILITY: [f64; 24] = [
+    0.440286351,
+    0.442297880,
+    0.444559459,
+    0.447200927,
+    0.450285140,
+    0.453827520,
+    0.457819516,
+    0.462243441,
+    0.467079701,
+    0.472309879,
+    0.477917913,
+    0.483890496,
+    0.490217185,
+    0.496890400,
+    0.503905436,
+    0.511260507,
+    0.578315908,
+    0.586157067,
+    0.535395107,
+    0.544157310,
+    0.553302682,
+    0.562854113,
+    0.572841777,
+    0.583305174,
+];
+const DETENTE_PHI: [f64; 24] = [
+    5.661470379,
+    5.543168418,
+    5.340309707,
+    5.088371253,
+    4.813371678,
+    4.531083158,
+    4.250110697,
+    3.974845673,
+    3.707392475,
+    3.448664102,
+    3.198971076,
+    2.958330843,
+    2.726628772,
+    2.503701479,
+    2.289379703,
+    2.083510239,
+    1.885967250,
+    1.696658413,
+    1.515528924,
+    1.342565060,
+    1.177798401,
+    1.021311601,
+    0.873246585,
+    0.733816410,
+];
+const DETENTE_PROBABILITY: [f64; 24] = [
+    0.941073769,
+    0.937181779,
+    0.930373572,
+    0.921134346,
+    0.909854546,
+    0.896812545,
+    0.882202761,
+    0.866169827,
+    0.848835044,
+    0.830313787,
+    0.810725844,
+    0.790200789,
+    0.768879939,
+    0.746915974,
+    0.724471001,
+    0.701713632,
+    0.678815577,
+    0.655948116,
+    0.633278806,
+    0.610968672,
+    0.589170096,
+    0.568025555,
+    0.547667320,
+    0.528218253,
+];
+const FLASH_CRISIS_PHI: [f64; 24] = [
+    0.000000000,
+    0.000000000,
+    0.000000000,
+    0.000000000,
+    0.000000000,
+    0.000000000,
+    0.000000000,
+    0.000000000,
+    0.000000000,
+    0.000000000,
+    0.087703122,
+    0.259830908,
+    0.467187076,
+    0.392791570,
+    0.330093307,
+    0.277229318,
+    0.232651952,
+    0.195068340,
+    0.163393733,
+    0.136714891,
+    0.114260947,
+    0.095379917,
+    0.079519574,
+    0.066211729,
+];
+const FLASH_CRISIS_PROBABILITY: [f64; 24] = [
+    0.447692090,
+    0.447692090,
+    0.447692090,
+    0.447692090,
+    0.447692090,
+    0.447692090,
+    0.447692090,
+    0.447692090,
+    0.447692090,
+    0.447692090,
+    0.663998452,
+    0.699742586,
+    0.728151729,
+    0.505153840,
+    0.496933477,
+    0.489958087,
+    0.484039264,
+    0.479017033,
+    0.474755625,
+    0.471139817,
+    0.468071798,
+    0.465468505,
+    0.463259372,
+    0.461384435,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenarios_reproduce_golden_trajectories() {
+        for scenario in all() {
+            let check = scenario.check(GOLDEN_TOLERANCE).unwrap();
+            assert!(check.matches(), "{} diverged: {:?}", scenario.name, check);
+            assert_eq!(scenario.steps.len(), scenario.expected.phi.len());
+        }
+
+        let detente = detente();
+        assert!(detente.expected.phi.windows(2).all(|w| w[1] < w[0]));
+
+        let mut drifted = flash_crisis().expected;
+        drifted.phi[11] += 1e-3;
+        let check = drifted
+            .compare(&flash_crisis().expected, GOLDEN_TOLERANCE)
+            .unwrap();
+        assert_eq!(check.first_divergent_step, Some(11));
+        drifted.phi[11] -= 1e-3;
+
+        // Inconsistent trajectories are rejected, not indexed past
+        drifted.phi.pop();
+        assert!(drifted
+            .compare(&flash_crisis().expected, GOLDEN_TOLERANCE)
+            .is_err());
+        assert!(flash_crisis()
+            .expected
+            .compare(&drifted, GOLDEN_TOLERANCE)
+            .is_err());
+
+        drifted.escalation_probability.pop();
+        assert_eq!(
+            drifted
+                .compare(&flash_crisis().expected, GOLDEN_TOLERANCE)
+                .unwrap()
+                .first_divergent_step,
+            Some(23)
+        );
+    }
+}
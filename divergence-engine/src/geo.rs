@@ -0,0 +1,366 @@
+//! Geo-tagged actors and spatial tension surfaces.
+//!
+//! Actors may carry a point location, a region tag (typically an ISO
+//! country code) or both. Dyadic Φ is then aggregated spatially:
+//!
+//! - per region pair: mean and max Φ over every dyad whose actors sit in
+//!   the two regions, drawn as a line between region centroids;
+//! - per grid cell: mean Φ of every dyad involving an actor located in the
+//!   cell, drawn as the cell's polygon.
+//!
+//! Both export as GeoJSON `FeatureCollection`s for mapping front-ends.
+//! Untagged actors are skipped. Nothing is recorded.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Location of an actor
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GeoLocation {
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Region tag, e.g. an ISO 3166 country code
+    pub region: Option<String>,
+}
+
+impl GeoLocation {
+    /// Point location without a region
+    pub fn point(lat: f64, lon: f64) -> Self {
+        Self {
+            lat: Some(lat),
+            lon: Some(lon),
+            region: None,
+        }
+    }
+
+    /// Region tag without coordinates
+    pub fn region(region: impl Into<String>) -> Self {
+        Self {
+            region: Some(region.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Add a region tag
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// `(lat, lon)` when both are set
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        self.lat.zip(self.lon)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.lat.is_some() != self.lon.is_some() {
+            return Err(DivergenceError::ConfigError(
+                "Latitude and longitude must be set together".to_string(),
+            ));
+        }
+        if let Some((lat, lon)) = self.coordinates() {
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                return Err(DivergenceError::ConfigError(format!(
+                    "Coordinates out of range: ({}, {})",
+                    lat, lon
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Aggregated Φ between two regions
+///
+/// Regions are ordered so that `region_a <= region_b`; a region paired
+/// with itself holds its internal dyads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionTension {
+    pub region_a: String,
+    pub region_b: String,
+    pub mean_phi: f64,
+    pub max_phi: f64,
+    pub n_dyads: usize,
+    /// Mean coordinates of located actors in each region
+    pub centroid_a: Option<(f64, f64)>,
+    pub centroid_b: Option<(f64, f64)>,
+}
+
+/// Aggregated Φ for one grid cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridCellTension {
+    /// South-west corner `(lat, lon)`
+    pub origin: (f64, f64),
+    pub cell_degrees: f64,
+    /// Located actors in the cell
+    pub actors: Vec<String>,
+    pub mean_phi: f64,
+    pub max_phi: f64,
+    pub n_dyads: usize,
+}
+
+impl CompressionDynamicsModel {
+    /// Tag an actor with a location
+    pub fn set_actor_location(&mut self, actor_id: &str, location: GeoLocation) -> Result<()> {
+        let actor_id = self.require_actor(actor_id)?.to_string();
+        location.validate()?;
+        self.locations.insert(actor_id, location);
+        Ok(())
+    }
+
+    /// Location of an actor, if tagged
+    pub fn actor_location(&self, actor_id: &str) -> Option<&GeoLocation> {
+        self.locations
+            .get(self.resolve_actor(actor_id).unwrap_or(actor_id))
+    }
+
+    /// Current Φ aggregated by region pair, highest mean first
+    pub fn regional_tension(&self) -> Vec<RegionTension> {
+        let mut centroids: IndexMap<&str, (f64, f64, usize)> = IndexMap::new();
+        for location in self.locations.values() {
+            if let (Some(region), Some((lat, lon))) = (&location.region, location.coordinates()) {
+                let c = centroids.entry(region.as_str()).or_insert((0.0, 0.0, 0));
+                c.0 += lat;
+                c.1 += lon;
+                c.2 += 1;
+            }
+        }
+        let centroid = |region: &str| {
+            centroids
+                .get(region)
+                .map(|&(lat, lon, n)| (lat / n as f64, lon / n as f64))
+        };
+
+        let mut pairs: IndexMap<(&str, &str), (f64, f64, usize)> = IndexMap::new();
+        for potential in self.peek_all_potentials() {
+            let region = |actor: &str| self.locations.get(actor).and_then(|l| l.region.as_deref());
+            let (Some(ra), Some(rb)) = (region(&potential.actor_a), region(&potential.actor_b))
+            else {
+                continue;
+            };
+            let key = if ra <= rb { (ra, rb) } else { (rb, ra) };
+            let entry = pairs.entry(key).or_insert((0.0, 0.0, 0));
+            entry.0 += potential.phi;
+            entry.1 = entry.1.max(potential.phi);
+            entry.2 += 1;
+        }
+
+        let mut tensions: Vec<RegionTension> = pairs
+            .into_iter()
+            .map(|((ra, rb), (sum, max, n))| RegionTension {
+                region_a: ra.to_string(),
+                region_b: rb.to_string(),
+                mean_phi: sum / n as f64,
+                max_phi: max,
+                n_dyads: n,
+                centroid_a: centroid(ra),
+                centroid_b: centroid(rb),
+            })
+            .collect();
+        tensions.sort_by(|a, b| b.mean_phi.total_cmp(&a.mean_phi));
+        tensions
+    }
+
+    /// Current Φ aggregated onto a lat/lon grid
+    ///
+    /// Each dyad counts toward the cells of both its located actors (once
+    /// if they share a cell).
+    pub fn tension_grid(&self, cell_degrees: f64) -> Result<Vec<GridCellTension>> {
+        if cell_degrees <= 0.0 || cell_degrees > 180.0 {
+            return Err(DivergenceError::ConfigError(
+                "Grid cell size must be in (0, 180] degrees".to_string(),
+            ));
+        }
+        let cell_of = |actor: &str| {
+            self.locations
+                .get(actor)
+                .and_then(|l| l.coordinates())
+                .map(|(lat, lon)| {
+                    (
+                        (lat / cell_degrees).floor() as i64,
+                        (lon / cell_degrees).floor() as i64,
+                    )
+                })
+        };
+
+        let mut cells: IndexMap<(i64, i64), GridCellTension> = IndexMap::new();
+        for actor in self.schemes.keys() {
+            if let Some(cell) = cell_of(actor) {
+                cells
+                    .entry(cell)
+                    .or_insert_with(|| GridCellTension {
+                        origin: (cell.0 as f64 * cell_degrees, cell.1 as f64 * cell_degrees),
+                        cell_degrees,
+                        actors: Vec::new(),
+                        mean_phi: 0.0,
+                        max_phi: 0.0,
+                        n_dyads: 0,
+                    })
+                    .actors
+                    .push(actor.clone());
+            }
+        }
+
+        for potential in self.peek_all_potentials() {
+            let ca = cell_of(&potential.actor_a);
+            let cb = cell_of(&potential.actor_b);
+            let mut touched = [ca, cb];
+            if ca == cb {
+                touched[1] = None;
+            }
+            for cell in touched.into_iter().flatten() {
+                let c = &mut cells[&cell];
+                c.mean_phi += potential.phi;
+                c.max_phi = c.max_phi.max(potential.phi);
+                c.n_dyads += 1;
+            }
+        }
+        Ok(cells
+            .into_values()
+            .map(|mut c| {
+                if c.n_dyads > 0 {
+                    c.mean_phi /= c.n_dyads as f64;
+                }
+                c
+            })
+            .collect())
+    }
+}
+
+/// Region-pair tensions as a GeoJSON `FeatureCollection`
+///
+/// Pairs with both centroids become `LineString` features (a `Point` for a
+/// region with itself); pairs without coordinates carry a `null` geometry.
+pub fn region_tension_geojson(tensions: &[RegionTension]) -> String {
+    let features: Vec<Value> = tensions
+        .iter()
+        .map(|t| {
+            let geometry = match (t.centroid_a, t.centroid_b) {
+                (Some(a), Some(_)) if t.region_a == t.region_b => {
+                    json!({"type": "Point", "coordinates": [a.1, a.0]})
+                }
+                (Some(a), Some(b)) => {
+                    json!({"type": "LineString", "coordinates": [[a.1, a.0], [b.1, b.0]]})
+                }
+                _ => Value::Null,
+            };
+            json!({
+                "type": "Feature",
+                "geometry": geometry,
+                "properties": {
+                    "region_a": t.region_a,
+                    "region_b": t.region_b,
+                    "mean_phi": t.mean_phi,
+                    "max_phi": t.max_phi,
+                    "n_dyads": t.n_dyads,
+                },
+            })
+        })
+        .collect();
+    json!({"type": "FeatureCollection", "features": features}).to_string()
+}
+
+/// Grid cell tensions as a GeoJSON `FeatureCollection` of cell polygons
+pub fn tension_grid_geojson(cells: &[GridCellTension]) -> String {
+    let features: Vec<Value> = cells
+        .iter()
+        .map(|c| {
+            let (lat0, lon0) = c.origin;
+            let (lat1, lon1) = (lat0 + c.cell_degrees, lon0 + c.cell_degrees);
+            json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [[
+                        [lon0, lat0], [lon1, lat0], [lon1, lat1], [lon0, lat1], [lon0, lat0]
+                    ]],
+                },
+                "properties": {
+                    "actors": c.actors,
+                    "mean_phi": c.mean_phi,
+                    "max_phi": c.max_phi,
+                    "n_dyads": c.n_dyads,
+                },
+            })
+        })
+        .collect();
+    json!({"type": "FeatureCollection", "features": features}).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geo_model() -> CompressionDynamicsModel {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("KYIV", Some(vec![0.7, 0.2, 0.1]), None);
+        model.register_actor("LVIV", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("MOSCOW", Some(vec![0.1, 0.2, 0.7]), None);
+        model.register_actor("NOMAD", Some(vec![0.3, 0.4, 0.3]), None);
+        model
+            .set_actor_location("KYIV", GeoLocation::point(50.45, 30.52).with_region("UA"))
+            .unwrap();
+        model
+            .set_actor_location("LVIV", GeoLocation::point(49.84, 24.03).with_region("UA"))
+            .unwrap();
+        model
+            .set_actor_location("MOSCOW", GeoLocation::point(55.75, 37.62).with_region("RU"))
+            .unwrap();
+        model
+    }
+
+    #[test]
+    fn test_regional_tension() {
+        let model = geo_model();
+        let tensions = model.regional_tension();
+        assert_eq!(tensions.len(), 2);
+        assert_eq!(
+            (tensions[0].region_a.as_str(), tensions[0].region_b.as_str()),
+            ("RU", "UA")
+        );
+        assert_eq!(tensions[0].n_dyads, 2);
+        assert!(tensions[0].mean_phi > tensions[1].mean_phi);
+
+        let geojson: Value = serde_json::from_str(&region_tension_geojson(&tensions)).unwrap();
+        assert_eq!(geojson["features"][0]["geometry"]["type"], "LineString");
+        assert_eq!(geojson["features"][1]["geometry"]["type"], "Point");
+    }
+
+    #[test]
+    fn test_tension_grid() {
+        let model = geo_model();
+        let cells = model.tension_grid(10.0).unwrap();
+        // Kyiv and Moscow share a 10° cell; Lviv is one cell west
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].actors, vec!["KYIV", "MOSCOW"]);
+        // KYIV-MOSCOW once, plus each with LVIV and NOMAD
+        assert_eq!(cells[0].n_dyads, 5);
+
+        let geojson: Value = serde_json::from_str(&tension_grid_geojson(&cells)).unwrap();
+        assert_eq!(geojson["features"].as_array().unwrap().len(), 2);
+        assert!(model.tension_grid(0.0).is_err());
+    }
+
+    #[test]
+    fn test_location_validation() {
+        let mut model = geo_model();
+        let half = GeoLocation {
+            lat: Some(10.0),
+            ..Default::default()
+        };
+        assert!(model.set_actor_location("NOMAD", half).is_err());
+        assert!(model
+            .set_actor_location("NOMAD", GeoLocation::point(95.0, 0.0))
+            .is_err());
+        assert!(model
+            .set_actor_location("GHOST", GeoLocation::region("XX"))
+            .is_err());
+        assert_eq!(
+            model.actor_location("MOSCOW").unwrap().region.as_deref(),
+            Some("RU")
+        );
+    }
+}
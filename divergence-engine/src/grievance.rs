@@ -0,0 +1,223 @@
+//! Grievance formulas.
+//!
+//! Grievance integrates how badly an actor's scheme predicted what it then
+//! observed. How "badly" is measured is a modelling choice, selected with
+//! `ModelConfig::grievance`:
+//!
+//! ```text
+//! SquaredError        Σ (o_i - p_i)²                   default
+//! Surprisal           -Σ o_i log2 p_i                  bits
+//! Hinge { tolerance } max(0, Σ (o_i - p_i)² - tolerance)
+//! ```
+//!
+//! `p` is the actor's scheme before the update and `o` the observation.
+//! Each formula is a [`GrievanceModel`]; the error it returns is what
+//! accumulates into grievance and is recorded in history.
+//!
+//! Alongside the raw integral, each grievance keeps a decayed one that
+//! models forgiveness: it halves every `ModelConfig::grievance_half_life_ms`
+//! and [`pardon`](CompressionDynamicsModel::pardon) removes a fraction of it
+//! at reconciliation events. With a half-life set, escalation prediction
+//! reads the decayed value.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::CompressionScheme;
+use serde::{Deserialize, Serialize};
+
+/// Prediction error of an observation under an actor's scheme
+pub trait GrievanceModel {
+    /// Grievance `observation` adds, given the `scheme` that predicted it
+    fn prediction_error(&self, scheme: &CompressionScheme, observation: &[f64]) -> Result<f64>;
+}
+
+/// Squared Euclidean distance between observation and scheme
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SquaredError;
+
+impl GrievanceModel for SquaredError {
+    fn prediction_error(&self, scheme: &CompressionScheme, observation: &[f64]) -> Result<f64> {
+        Ok(scheme
+            .distribution()
+            .iter()
+            .zip(observation)
+            .map(|(&p, &o)| (o - p).powi(2))
+            .sum())
+    }
+}
+
+/// Bits of surprise, see [`CompressionScheme::surprisal`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SurprisalError;
+
+impl GrievanceModel for SurprisalError {
+    fn prediction_error(&self, scheme: &CompressionScheme, observation: &[f64]) -> Result<f64> {
+        scheme.surprisal(observation)
+    }
+}
+
+/// Squared error beyond a tolerance; smaller errors add nothing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HingeError {
+    pub tolerance: f64,
+}
+
+impl GrievanceModel for HingeError {
+    fn prediction_error(&self, scheme: &CompressionScheme, observation: &[f64]) -> Result<f64> {
+        let error = SquaredError.prediction_error(scheme, observation)?;
+        Ok((error - self.tolerance).max(0.0))
+    }
+}
+
+/// Grievance formula selectable in `ModelConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum GrievanceFormula {
+    /// [`SquaredError`]
+    #[default]
+    SquaredError,
+    /// [`SurprisalError`]
+    Surprisal,
+    /// [`HingeError`]
+    Hinge { tolerance: f64 },
+}
+
+impl GrievanceModel for GrievanceFormula {
+    fn prediction_error(&self, scheme: &CompressionScheme, observation: &[f64]) -> Result<f64> {
+        match *self {
+            GrievanceFormula::SquaredError => SquaredError.prediction_error(scheme, observation),
+            GrievanceFormula::Surprisal => SurprisalError.prediction_error(scheme, observation),
+            GrievanceFormula::Hinge { tolerance } => {
+                HingeError { tolerance }.prediction_error(scheme, observation)
+            }
+        }
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Forgive `fraction` of an actor's decayed grievance
+    ///
+    /// Models a reconciliation event. Returns the decayed grievance left.
+    /// Needs `grievance_half_life_ms`: without it escalation reads the
+    /// windowed error, which a pardon would not change.
+    pub fn pardon(&mut self, actor_id: &str, fraction: f64) -> Result<f64> {
+        if self.config.grievance_half_life_ms.is_none() {
+            return Err(DivergenceError::ConfigError(
+                "Pardons need grievance_half_life_ms to be set".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(DivergenceError::ConfigError(format!(
+                "Pardon fraction must be in [0, 1], got {}",
+                fraction
+            )));
+        }
+        let actor_id = self
+            .resolve_actor(actor_id)
+            .ok_or_else(|| self.unknown_actor(actor_id))?
+            .to_string();
+        let grievance = self
+            .grievances
+            .get_mut(&actor_id)
+            .ok_or_else(|| DivergenceError::unknown_actor(&actor_id))?;
+        grievance.decayed_error *= 1.0 - fraction;
+        Ok(grievance.decayed_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{EventAttribution, Grievance, ModelConfig};
+
+    #[test]
+    fn test_grievance_formulas() {
+        let scheme = CompressionScheme::new("A", vec![0.5, 0.5], None);
+        let near = [0.6, 0.4];
+        let far = [1.0, 0.0];
+        let squared = SquaredError.prediction_error(&scheme, &far).unwrap();
+        assert!((squared - 0.5).abs() < 1e-6);
+        assert!((SurprisalError.prediction_error(&scheme, &far).unwrap() - 1.0).abs() < 1e-6);
+
+        let hinge = HingeError { tolerance: 0.1 };
+        assert_eq!(hinge.prediction_error(&scheme, &near).unwrap(), 0.0);
+        assert!((hinge.prediction_error(&scheme, &far).unwrap() - 0.4).abs() < 1e-6);
+
+        // The configured formula feeds grievance and history
+        let config = ModelConfig {
+            n_categories: 2,
+            grievance: GrievanceFormula::Hinge { tolerance: 0.1 },
+            ..Default::default()
+        };
+        let mut model = CompressionDynamicsModel::with_config(config);
+        model.register_actor("A", Some(vec![0.5, 0.5]), None);
+        model.update_scheme("A", &near, Some(0)).unwrap();
+        let view = model.view();
+        assert_eq!(view.grievance("A").unwrap().cumulative_error, 0.0);
+        assert!(view.grievance("A").unwrap().cumulative_surprisal > 0.0);
+    }
+
+    #[test]
+    fn test_grievance_decay_and_pardon() {
+        const DAY: i64 = 86_400_000;
+        let config = ModelConfig {
+            n_categories: 2,
+            grievance_half_life_ms: Some(DAY),
+            ..Default::default()
+        };
+        let mut model = CompressionDynamicsModel::with_config(config);
+        model.register_actor("A", Some(vec![0.5, 0.5]), None);
+        model.register_actor("B", Some(vec![0.5, 0.5]), None);
+        model.update_scheme("A", &[1.0, 0.0], Some(0)).unwrap();
+        let first = model.view().grievance("A").unwrap().decayed_error;
+        model.update_scheme("A", &[1.0, 0.0], Some(DAY)).unwrap();
+
+        let g = model.view().grievance("A").unwrap().clone();
+        let second = g.error_history[1];
+        assert!((g.decayed_error - (first / 2.0 + second)).abs() < 1e-9);
+        assert!(g.decayed_error < g.cumulative_error);
+        assert!((g.decayed_error_at(2 * DAY, DAY) - g.decayed_error / 2.0).abs() < 1e-9);
+
+        // Escalation reads the decayed value; a pardon lowers it
+        let before = model.peek_escalation("A", "B", 0.0, 0.0).unwrap();
+        let left = model.pardon("A", 0.5).unwrap();
+        assert!((left - g.decayed_error / 2.0).abs() < 1e-12);
+        let after = model.peek_escalation("A", "B", 0.0, 0.0).unwrap();
+        assert!(after.avg_grievance < before.avg_grievance);
+        assert!(after.probability < before.probability);
+        assert_eq!(
+            model.view().grievance("A").unwrap().cumulative_error,
+            g.cumulative_error
+        );
+
+        assert!(model.pardon("A", 1.5).is_err());
+        assert!(model.pardon("Z", 0.5).is_err());
+
+        // Directed grievance decays too
+        let attribution = |source: &str| EventAttribution {
+            source_actor: Some(source.to_string()),
+            ..Default::default()
+        };
+        model
+            .update_scheme_with_attribution("A", &[0.0, 1.0], Some(2 * DAY), attribution("B"))
+            .unwrap();
+        let directed = model.directed_grievance("A", "B").unwrap().decayed_error;
+        model
+            .update_scheme_with_attribution("A", &[0.0, 1.0], Some(3 * DAY), attribution("B"))
+            .unwrap();
+        let g = model.directed_grievance("A", "B").unwrap();
+        assert!((g.decayed_error - (directed / 2.0 + g.error_history[1])).abs() < 1e-9);
+
+        // Snapshots from before decay start from the full integral
+        let mut json = serde_json::to_value(g).unwrap();
+        json.as_object_mut().unwrap().remove("decayed_error");
+        let restored: Grievance = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.decayed_error, g.cumulative_error);
+
+        assert!(model
+            .update_config(0, |c| c.grievance_half_life_ms = Some(0))
+            .is_err());
+        let mut plain = CompressionDynamicsModel::new(2);
+        plain.register_actor("A", None, None);
+        assert!(plain.pardon("A", 0.5).is_err());
+    }
+}
@@ -0,0 +1,398 @@
+//! Hawkes-process modeling of dyad event clustering.
+//!
+//! Conflict events are self-exciting: an alert or shock raises the odds of
+//! another one shortly after. A Hawkes process with an exponential kernel
+//! captures this as an event intensity
+//!
+//! ```text
+//! λ_m(t) = μ_m + Σ_n α_mn · β · Σ_{t_j of type n, t_j < t} e^{-β(t - t_j)}
+//! ```
+//!
+//! where μ is the background rate, α the expected number of follow-up
+//! events each event triggers (the branching ratio), and β the decay rate.
+//! The univariate process treats every dyad event alike; the bivariate one
+//! marks events by initiator so that each actor can excite the other.
+//!
+//! Times are measured in hours, so intensities are events per hour. The
+//! excitation state is updated recursively, making `record` and
+//! `intensity` O(dims); `fit` runs expectation-maximization over the
+//! retained events with β held fixed.
+
+use crate::error::{DivergenceError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const MS_PER_HOUR: f64 = 3_600_000.0;
+
+/// Event marking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum HawkesKind {
+    /// All dyad events share one intensity
+    #[default]
+    Univariate,
+    /// Events are marked by initiator; each side excites both
+    Bivariate,
+}
+
+impl HawkesKind {
+    /// Number of marked event types
+    pub fn dims(&self) -> usize {
+        match self {
+            HawkesKind::Univariate => 1,
+            HawkesKind::Bivariate => 2,
+        }
+    }
+}
+
+/// Hawkes process settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HawkesConfig {
+    pub kind: HawkesKind,
+
+    /// Kernel decay rate β (per hour)
+    pub decay: f64,
+
+    /// Background rate μ per event type (events per hour) before fitting
+    pub baseline: f64,
+
+    /// Branching ratio α per source before fitting
+    pub excitation: f64,
+
+    /// Events retained for fitting
+    pub max_events: usize,
+
+    /// EM iterations per `fit`
+    pub em_iterations: usize,
+}
+
+impl Default for HawkesConfig {
+    fn default() -> Self {
+        Self {
+            kind: HawkesKind::default(),
+            decay: 0.1,
+            baseline: 0.01,
+            excitation: 0.3,
+            max_events: 1000,
+            em_iterations: 50,
+        }
+    }
+}
+
+/// Self-exciting point process over a dyad's event times
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HawkesProcess {
+    config: HawkesConfig,
+    /// Background rate per event type
+    mu: Vec<f64>,
+    /// Branching ratios, row-major: `alpha[m * dims + n]` is how strongly
+    /// type-n events excite type m
+    alpha: Vec<f64>,
+    events: VecDeque<(i64, usize)>,
+    /// Σ e^{-β(t - t_j)} per source type, as of `last_event_ms`
+    excitation: Vec<f64>,
+    last_event_ms: Option<i64>,
+    fitted: bool,
+}
+
+impl HawkesProcess {
+    /// Create a process with the configured prior parameters
+    pub fn new(config: HawkesConfig) -> Self {
+        let d = config.kind.dims();
+        Self {
+            mu: vec![config.baseline.max(0.0); d],
+            alpha: vec![config.excitation.max(0.0) / d as f64; d * d],
+            events: VecDeque::new(),
+            excitation: vec![0.0; d],
+            last_event_ms: None,
+            fitted: false,
+            config,
+        }
+    }
+
+    /// Settings this process was built with
+    pub fn config(&self) -> &HawkesConfig {
+        &self.config
+    }
+
+    /// Number of marked event types
+    pub fn dims(&self) -> usize {
+        self.mu.len()
+    }
+
+    /// Background rates μ (events per hour)
+    pub fn baseline(&self) -> &[f64] {
+        &self.mu
+    }
+
+    /// Branching ratio of type-`source` events onto type `target`
+    pub fn excitation(&self, target: usize, source: usize) -> f64 {
+        self.alpha[target * self.dims() + source]
+    }
+
+    /// Whether parameters have been fitted to data
+    pub fn is_fitted(&self) -> bool {
+        self.fitted
+    }
+
+    /// Retained events as (timestamp, mark)
+    pub fn events(&self) -> &VecDeque<(i64, usize)> {
+        &self.events
+    }
+
+    /// Timestamp of the most recent event
+    pub fn last_event_ms(&self) -> Option<i64> {
+        self.last_event_ms
+    }
+
+    /// Record an event of type `mark`
+    ///
+    /// Events earlier than the last recorded one are treated as
+    /// simultaneous with it.
+    pub fn record(&mut self, timestamp_ms: i64, mark: usize) -> Result<()> {
+        if mark >= self.dims() {
+            return Err(DivergenceError::DimensionMismatch {
+                expected: self.dims(),
+                got: mark + 1,
+            });
+        }
+        let t = self
+            .last_event_ms
+            .map_or(timestamp_ms, |l| timestamp_ms.max(l));
+        self.decay_to(t);
+        self.excitation[mark] += 1.0;
+        self.last_event_ms = Some(t);
+
+        if self.events.len() == self.config.max_events.max(1) {
+            self.events.pop_front();
+        }
+        self.events.push_back((t, mark));
+        Ok(())
+    }
+
+    fn decay_to(&mut self, timestamp_ms: i64) {
+        if let Some(last) = self.last_event_ms {
+            let factor = self.decay_factor(last, timestamp_ms);
+            for s in &mut self.excitation {
+                *s *= factor;
+            }
+        }
+    }
+
+    fn decay_factor(&self, from_ms: i64, to_ms: i64) -> f64 {
+        let hours = (to_ms - from_ms).max(0) as f64 / MS_PER_HOUR;
+        (-self.config.decay * hours).exp()
+    }
+
+    /// Intensity λ_m(t) per event type (events per hour)
+    ///
+    /// Times before the last event are evaluated at the last event.
+    pub fn intensity(&self, timestamp_ms: i64) -> Vec<f64> {
+        let d = self.dims();
+        let factor = self
+            .last_event_ms
+            .map_or(0.0, |last| self.decay_factor(last, timestamp_ms));
+        let beta = self.config.decay;
+        (0..d)
+            .map(|m| {
+                self.mu[m]
+                    + (0..d)
+                        .map(|n| self.alpha[m * d + n] * beta * self.excitation[n] * factor)
+                        .sum::<f64>()
+            })
+            .collect()
+    }
+
+    /// Total intensity across event types
+    pub fn total_intensity(&self, timestamp_ms: i64) -> f64 {
+        self.intensity(timestamp_ms).iter().sum()
+    }
+
+    /// Self-excited intensity relative to background, λ/μ - 1
+    ///
+    /// Zero when recent events have decayed away; unitless, so it can
+    /// enter the escalation logit directly.
+    pub fn excitation_ratio(&self, timestamp_ms: i64) -> f64 {
+        let mu: f64 = self.mu.iter().sum();
+        if mu <= 0.0 {
+            return 0.0;
+        }
+        (self.total_intensity(timestamp_ms) / mu - 1.0).max(0.0)
+    }
+
+    /// Spectral radius of the branching matrix
+    ///
+    /// Below 1 the process is stationary; at or above 1 each event
+    /// triggers at least one more on average and activity explodes.
+    pub fn branching_ratio(&self) -> f64 {
+        match self.dims() {
+            1 => self.alpha[0],
+            _ => {
+                let (a, b, c, d) = (self.alpha[0], self.alpha[1], self.alpha[2], self.alpha[3]);
+                let half_trace = (a + d) / 2.0;
+                let disc = ((a - d) / 2.0).powi(2) + b * c;
+                half_trace + disc.max(0.0).sqrt()
+            }
+        }
+    }
+
+    /// Fit μ and α to the retained events by expectation-maximization
+    ///
+    /// β stays at the configured decay. Requires at least two events
+    /// spanning a positive interval.
+    pub fn fit(&mut self) -> Result<()> {
+        let (first, last) = match (self.events.front(), self.events.back()) {
+            (Some(&(first, _)), Some(&(last, _))) if self.events.len() >= 2 => (first, last),
+            _ => {
+                return Err(DivergenceError::ConfigError(format!(
+                    "Need at least 2 events to fit a Hawkes process, have {}",
+                    self.events.len()
+                )))
+            }
+        };
+        let span = (last - first) as f64 / MS_PER_HOUR;
+        if span <= 0.0 {
+            return Err(DivergenceError::ConfigError(
+                "Hawkes events must span a positive interval".to_string(),
+            ));
+        }
+
+        let d = self.dims();
+        let beta = self.config.decay;
+
+        // Expected offspring window per source type: ∫ β e^{-β(T - t_j)} over [t_j, T]
+        let mut compensator = vec![0.0; d];
+        for &(t, n) in &self.events {
+            compensator[n] += 1.0 - self.decay_factor(t, last);
+        }
+
+        // EM cannot move parameters off zero
+        for mu in &mut self.mu {
+            *mu = mu.max(1e-9);
+        }
+        for a in &mut self.alpha {
+            *a = a.max(1e-9);
+        }
+
+        for _ in 0..self.config.em_iterations {
+            let mut background = vec![0.0; d];
+            let mut triggered = vec![0.0; d * d];
+            let mut state = vec![0.0; d];
+            let mut prev: Option<i64> = None;
+
+            for &(t, m) in &self.events {
+                if let Some(p) = prev {
+                    let factor = self.decay_factor(p, t);
+                    for s in &mut state {
+                        *s *= factor;
+                    }
+                }
+                let lambda = self.mu[m]
+                    + (0..d)
+                        .map(|n| self.alpha[m * d + n] * beta * state[n])
+                        .sum::<f64>();
+                if lambda > 0.0 {
+                    background[m] += self.mu[m] / lambda;
+                    for n in 0..d {
+                        triggered[m * d + n] += self.alpha[m * d + n] * beta * state[n] / lambda;
+                    }
+                }
+                state[m] += 1.0;
+                prev = Some(t);
+            }
+
+            for m in 0..d {
+                self.mu[m] = background[m] / span;
+                for n in 0..d {
+                    self.alpha[m * d + n] = if compensator[n] > 0.0 {
+                        triggered[m * d + n] / compensator[n]
+                    } else {
+                        0.0
+                    };
+                }
+            }
+        }
+
+        if self
+            .mu
+            .iter()
+            .chain(self.alpha.iter())
+            .any(|v| !v.is_finite())
+        {
+            return Err(DivergenceError::NumericalError(
+                "Hawkes fit diverged".to_string(),
+            ));
+        }
+        self.fitted = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    #[test]
+    fn test_intensity_decays_after_events() {
+        let mut process = HawkesProcess::new(HawkesConfig::default());
+        let quiet = process.total_intensity(0);
+        assert!((quiet - 0.01).abs() < 1e-12);
+
+        process.record(0, 0).unwrap();
+        process.record(HOUR, 0).unwrap();
+        assert!(process.total_intensity(HOUR) > quiet);
+        assert!(process.excitation_ratio(HOUR) > process.excitation_ratio(48 * HOUR));
+        assert!(process.excitation_ratio(1000 * HOUR) < 1e-3);
+        assert!(process.record(2 * HOUR, 1).is_err());
+    }
+
+    #[test]
+    fn test_fit_separates_bursts_from_regular_events() {
+        let config = HawkesConfig {
+            decay: 1.0,
+            ..Default::default()
+        };
+
+        // Tight bursts of five events every ten days
+        let mut bursty = HawkesProcess::new(config.clone());
+        for burst in 0..10 {
+            for k in 0..5 {
+                bursty.record(burst * 240 * HOUR + k * HOUR / 2, 0).unwrap();
+            }
+        }
+        bursty.fit().unwrap();
+
+        // The same number of events, evenly spread
+        let mut regular = HawkesProcess::new(config);
+        for k in 0..50 {
+            regular.record(k * 48 * HOUR, 0).unwrap();
+        }
+        regular.fit().unwrap();
+
+        assert!(bursty.branching_ratio() > 0.5);
+        assert!(regular.branching_ratio() < 0.1);
+        assert!(bursty.is_fitted());
+    }
+
+    #[test]
+    fn test_bivariate_cross_excitation() {
+        let config = HawkesConfig {
+            kind: HawkesKind::Bivariate,
+            decay: 1.0,
+            ..Default::default()
+        };
+        let mut process = HawkesProcess::new(config);
+
+        // Every action by side 0 draws a response from side 1
+        for k in 0..30 {
+            let t = k * 100 * HOUR;
+            process.record(t, 0).unwrap();
+            process.record(t + HOUR / 2, 1).unwrap();
+        }
+        process.fit().unwrap();
+
+        assert!(process.excitation(1, 0) > process.excitation(0, 1));
+        assert!(process.excitation(1, 0) > 0.5);
+    }
+}
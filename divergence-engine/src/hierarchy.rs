@@ -0,0 +1,192 @@
+//! Actor hierarchies (state → factions → leaders).
+//!
+//! Registered actors can be attached to a parent with a weight. A parent
+//! with children has a rolled-up scheme, the weighted mixture of its
+//! children's (themselves rolled-up) schemes:
+//!
+//! ```text
+//! p_parent = Σ_i w_i · p_i / Σ_i w_i
+//! ```
+//!
+//! Parents need not be registered: a state can exist purely as the
+//! aggregate of its factions. A registered parent's own scheme is ignored
+//! once it has children. Potentials can be computed between nodes at any
+//! level, e.g. a faction against a foreign state.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::{CompressionScheme, ConflictPotential};
+use serde::{Deserialize, Serialize};
+
+/// Link from an actor to its parent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParentLink {
+    pub parent: String,
+    /// Share of the parent's roll-up
+    pub weight: f64,
+}
+
+impl CompressionDynamicsModel {
+    /// Attach a registered actor to a parent, replacing any previous parent
+    pub fn set_parent(&mut self, child: &str, parent: &str, weight: f64) -> Result<()> {
+        let child = self.require_actor(child)?.to_string();
+        let child = child.as_str();
+        let parent = self.resolve_actor(parent).unwrap_or(parent).to_string();
+        let parent = parent.as_str();
+        if !(weight.is_finite() && weight > 0.0) {
+            return Err(DivergenceError::ConfigError(
+                "Hierarchy weights must be positive".to_string(),
+            ));
+        }
+        // Walking up from the parent must not reach the child
+        let mut node = Some(parent);
+        while let Some(current) = node {
+            if current == child {
+                return Err(DivergenceError::ConfigError(format!(
+                    "Making {} a child of {} would create a cycle",
+                    child, parent
+                )));
+            }
+            node = self.parent_of(current);
+        }
+        self.parents.insert(
+            child.to_string(),
+            ParentLink {
+                parent: parent.to_string(),
+                weight,
+            },
+        );
+        Ok(())
+    }
+
+    /// Detach an actor from its parent, returning the removed link
+    pub fn remove_parent(&mut self, child: &str) -> Option<ParentLink> {
+        let child = self.resolve_actor(child).unwrap_or(child).to_string();
+        self.parents.shift_remove(&child)
+    }
+
+    /// Parent of an actor, if attached
+    pub fn parent_of(&self, actor_id: &str) -> Option<&str> {
+        let actor_id = self.resolve_actor(actor_id).unwrap_or(actor_id);
+        self.parents.get(actor_id).map(|l| l.parent.as_str())
+    }
+
+    /// Direct children of a node with their weights, in attachment order
+    pub fn children_of(&self, parent: &str) -> Vec<(&str, f64)> {
+        let parent = self.resolve_actor(parent).unwrap_or(parent);
+        self.parents
+            .iter()
+            .filter(|(_, link)| link.parent == parent)
+            .map(|(child, link)| (child.as_str(), link.weight))
+            .collect()
+    }
+
+    /// Registered leaf actors below a node (the node itself if it is a leaf)
+    pub fn leaf_actors(&self, node: &str) -> Vec<&str> {
+        let children = self.children_of(node);
+        if children.is_empty() {
+            return self
+                .schemes
+                .get_key_value(node)
+                .map(|(k, _)| vec![k.as_str()])
+                .unwrap_or_default();
+        }
+        children
+            .into_iter()
+            .flat_map(|(child, _)| self.leaf_actors(child))
+            .collect()
+    }
+
+    /// Scheme of a node at any hierarchy level
+    ///
+    /// Leaves return their own scheme; parents the weighted mixture of
+    /// their children's rolled-up schemes.
+    pub fn rolled_up_scheme(&self, node: &str) -> Result<CompressionScheme> {
+        let children = self.children_of(node);
+        if children.is_empty() {
+            return self.scheme_for(node).cloned();
+        }
+
+        let total: f64 = children.iter().map(|(_, w)| w).sum();
+        let mut template: Option<CompressionScheme> = None;
+        let mut mixture: Vec<f64> = Vec::new();
+        let mut timestamp_ms = None;
+        for (child, weight) in children {
+            let scheme = self.rolled_up_scheme(child)?;
+            if mixture.is_empty() {
+                mixture = vec![0.0; scheme.n_categories()];
+            }
+            for (m, p) in mixture.iter_mut().zip(scheme.distribution()) {
+                *m += weight / total * p;
+            }
+            timestamp_ms = timestamp_ms.max(scheme.timestamp_ms);
+            template.get_or_insert(scheme);
+        }
+
+        let template = template.expect("parent has children");
+        let mut rolled = template.derived(node.to_string(), mixture);
+        rolled.timestamp_ms = timestamp_ms;
+        Ok(rolled)
+    }
+
+    /// Conflict potential between two nodes at any hierarchy level,
+    /// without recording it
+    pub fn hierarchy_potential(&self, node_a: &str, node_b: &str) -> Result<ConflictPotential> {
+        let scheme_a = self.rolled_up_scheme(node_a)?;
+        let scheme_b = self.rolled_up_scheme(node_b)?;
+        let mut potential = ConflictPotential::compute(&scheme_a, &scheme_b)?;
+        potential.timestamp_ms = scheme_a.timestamp_ms.max(scheme_b.timestamp_ms);
+        Ok(potential)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hierarchy_model() -> CompressionDynamicsModel {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("HAWKS", Some(vec![0.8, 0.1, 0.1]), None);
+        model.register_actor("DOVES", Some(vec![0.1, 0.1, 0.8]), None);
+        model.register_actor("LEADER", Some(vec![0.7, 0.2, 0.1]), None);
+        model.register_actor("RIVAL", Some(vec![0.1, 0.2, 0.7]), None);
+        model.set_parent("HAWKS", "STATE", 3.0).unwrap();
+        model.set_parent("DOVES", "STATE", 1.0).unwrap();
+        model
+    }
+
+    #[test]
+    fn test_rolled_up_scheme() {
+        let model = hierarchy_model();
+        let state = model.rolled_up_scheme("STATE").unwrap();
+        assert_eq!(state.actor_id, "STATE");
+        let p = state.distribution();
+        assert!((p[0] - (0.75 * 0.8 + 0.25 * 0.1)).abs() < 1e-3);
+        assert_eq!(model.children_of("STATE").len(), 2);
+        assert_eq!(model.leaf_actors("STATE"), vec!["HAWKS", "DOVES"]);
+        assert!(model.rolled_up_scheme("NOBODY").is_err());
+
+        // Mixing in the doves pulls the state toward the rival
+        let vs_rival = model.hierarchy_potential("STATE", "RIVAL").unwrap();
+        let hawks_vs_rival = model.hierarchy_potential("HAWKS", "RIVAL").unwrap();
+        assert!(vs_rival.phi < hawks_vs_rival.phi);
+    }
+
+    #[test]
+    fn test_nested_hierarchy_and_cycles() {
+        let mut model = hierarchy_model();
+        model.set_parent("LEADER", "HAWKS", 1.0).unwrap();
+        // HAWKS now rolls up to its leader only
+        let hawks = model.rolled_up_scheme("HAWKS").unwrap();
+        assert!((hawks.distribution()[0] - 0.7).abs() < 1e-3);
+        assert_eq!(model.leaf_actors("STATE"), vec!["LEADER", "DOVES"]);
+
+        assert!(model.set_parent("HAWKS", "LEADER", 1.0).is_err());
+        assert!(model.set_parent("DOVES", "DOVES", 1.0).is_err());
+        assert!(model.set_parent("DOVES", "STATE", 0.0).is_err());
+        assert!(model.set_parent("GHOST", "STATE", 1.0).is_err());
+
+        assert_eq!(model.remove_parent("LEADER").unwrap().parent, "HAWKS");
+        assert_eq!(model.parent_of("LEADER"), None);
+    }
+}
@@ -0,0 +1,497 @@
+//! Scheme history storage.
+//!
+//! Every update records the actor's scheme. Stored in full that is `8·n`
+//! bytes of distribution per entry, which dominates state size on long
+//! runs. Under [`HistoryStorage::Quantized`] each distribution is kept as
+//! u16 fixed-point levels, delta-encoded against the actor's previous
+//! quantized entry and packed as zigzag varints:
+//!
+//! ```text
+//! level_i = round(p_i · 65535)
+//! delta_i = level_i(t) − level_i(t−1)      one byte while |delta_i| < 64
+//! ```
+//!
+//! An actor's first quantized entry, and the first after its category
+//! layout changes or the state is reloaded, is a keyframe of absolute
+//! levels. Entries are decoded transparently on read: each probability is
+//! within `1/131070` of the recorded one before renormalization, and
+//! levels that round to zero get the scheme's smoothing floor back.
+//!
+//! [`HistoryPolicy`] thins out which updates are recorded at all.
+
+use crate::divergence::jensen_shannon;
+use crate::model::{EventAttribution, ModelConfig, SchemeHistoryEntry};
+use crate::scheme::CompressionScheme;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Fixed-point scale of a quantized probability
+const LEVELS: f64 = u16::MAX as f64;
+
+/// How scheme history entries are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HistoryStorage {
+    /// Full f64 schemes
+    #[default]
+    Full,
+    /// u16 fixed-point distributions, delta-encoded per actor
+    Quantized,
+}
+
+/// Which scheme updates are recorded in history
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum HistoryPolicy {
+    /// Every update
+    #[default]
+    EveryUpdate,
+    /// Every `n`-th update of each actor (`n = 0` behaves as 1)
+    EveryN { n: u64 },
+    /// Updates that moved the scheme more than `epsilon` in Jensen-Shannon
+    /// distance `√JS` from the actor's last recorded entry
+    OnChange { epsilon: f64 },
+    /// No updates; schemes are recorded when the streaming processor raises
+    /// an alert, or through
+    /// [`record_scheme`](crate::CompressionDynamicsModel::record_scheme)
+    OnAlert,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum StoredEntry {
+    Full(SchemeHistoryEntry),
+    Quantized(QuantizedEntry),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QuantizedEntry {
+    timestamp_ms: i64,
+    actor_id: String,
+    category_version: u64,
+    prediction_error: f64,
+    attribution: Option<EventAttribution>,
+    scheme_timestamp_ms: Option<i64>,
+    observations: u64,
+    /// Index into `SchemeHistory::layouts`
+    layout: usize,
+    /// Absolute levels rather than deltas
+    keyframe: bool,
+    /// Zigzag varint levels or deltas
+    levels: Vec<u8>,
+}
+
+/// Recorded scheme updates, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct SchemeHistory {
+    entries: Vec<StoredEntry>,
+    /// Scheme fields other than the distribution, shared by quantized
+    /// entries
+    layouts: Vec<CompressionScheme>,
+    /// Per actor: layout and levels of the latest quantized entry
+    bases: HashMap<String, (usize, Vec<u16>)>,
+    /// Per actor: distribution of the latest entry, kept under
+    /// [`HistoryPolicy::OnChange`]
+    recorded: HashMap<String, Vec<f64>>,
+}
+
+impl SchemeHistory {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of recorded entries
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing has been recorded
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.layouts.clear();
+        self.bases.clear();
+        self.recorded.clear();
+    }
+
+    /// Entries oldest first, decoding quantized ones
+    pub fn iter(&self) -> HistoryIter<'_> {
+        HistoryIter {
+            history: self,
+            entries: self.entries.iter(),
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Decoded entries, oldest first
+    pub fn into_entries(self) -> Vec<SchemeHistoryEntry> {
+        self.iter().map(Cow::into_owned).collect()
+    }
+
+    /// Bytes spent on stored distributions
+    pub fn distribution_bytes(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|e| match e {
+                StoredEntry::Full(e) => e.scheme.n_categories() * std::mem::size_of::<f64>(),
+                StoredEntry::Quantized(q) => q.levels.len(),
+            })
+            .sum()
+    }
+
+    /// Record an entry
+    pub(crate) fn push(&mut self, entry: SchemeHistoryEntry, storage: HistoryStorage) {
+        match storage {
+            HistoryStorage::Full => self.entries.push(StoredEntry::Full(entry)),
+            HistoryStorage::Quantized => {
+                let quantized = self.quantize(entry);
+                self.entries.push(StoredEntry::Quantized(quantized));
+            }
+        }
+    }
+
+    /// Record an update if the configured policy asks for it
+    ///
+    /// Returns whether the entry was recorded.
+    pub(crate) fn record(&mut self, entry: SchemeHistoryEntry, config: &ModelConfig) -> bool {
+        let wanted = match config.history_policy {
+            HistoryPolicy::EveryUpdate => true,
+            HistoryPolicy::EveryN { n } => {
+                entry.scheme.observation_count().is_multiple_of(n.max(1))
+            }
+            HistoryPolicy::OnChange { epsilon } => match self.recorded.get(&entry.actor_id) {
+                Some(last) => jensen_shannon(last, entry.scheme.distribution())
+                    .map_or(true, |js| js.max(0.0).sqrt() > epsilon),
+                None => true,
+            },
+            HistoryPolicy::OnAlert => false,
+        };
+        if wanted {
+            self.force_record(entry, config);
+        }
+        wanted
+    }
+
+    /// Record an entry whatever the policy
+    pub(crate) fn force_record(&mut self, entry: SchemeHistoryEntry, config: &ModelConfig) {
+        if let HistoryPolicy::OnChange { .. } = config.history_policy {
+            self.recorded
+                .insert(entry.actor_id.clone(), entry.scheme.distribution().to_vec());
+        }
+        self.push(entry, config.history_storage);
+    }
+
+    fn quantize(&mut self, entry: SchemeHistoryEntry) -> QuantizedEntry {
+        let scheme = entry.scheme;
+        let levels: Vec<u16> = scheme
+            .distribution()
+            .iter()
+            .map(|p| (p.clamp(0.0, 1.0) * LEVELS).round() as u16)
+            .collect();
+
+        let base = self
+            .bases
+            .remove(&entry.actor_id)
+            .filter(|(layout, _)| self.layouts[*layout].same_layout(&scheme));
+        let layout = match &base {
+            Some((layout, _)) => *layout,
+            None => match self.layouts.iter().rposition(|l| l.same_layout(&scheme)) {
+                Some(layout) => layout,
+                None => {
+                    self.layouts.push(scheme.clone());
+                    self.layouts.len() - 1
+                }
+            },
+        };
+
+        let mut packed = Vec::with_capacity(levels.len());
+        for (i, &level) in levels.iter().enumerate() {
+            let previous = base.as_ref().map_or(0, |(_, b)| b[i]);
+            write_varint(&mut packed, i32::from(level) - i32::from(previous));
+        }
+        self.bases.insert(entry.actor_id.clone(), (layout, levels));
+
+        QuantizedEntry {
+            timestamp_ms: entry.timestamp_ms,
+            actor_id: entry.actor_id,
+            category_version: entry.category_version,
+            prediction_error: entry.prediction_error,
+            attribution: entry.attribution,
+            scheme_timestamp_ms: scheme.timestamp_ms,
+            observations: scheme.observation_count(),
+            layout,
+            keyframe: base.is_none(),
+            levels: packed,
+        }
+    }
+}
+
+/// Decoding iterator over a [`SchemeHistory`]
+pub struct HistoryIter<'a> {
+    history: &'a SchemeHistory,
+    entries: std::slice::Iter<'a, StoredEntry>,
+    /// Per actor: levels of the latest quantized entry decoded
+    levels: HashMap<&'a str, Vec<u16>>,
+}
+
+impl<'a> Iterator for HistoryIter<'a> {
+    type Item = Cow<'a, SchemeHistoryEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let q = match self.entries.next()? {
+            StoredEntry::Full(e) => return Some(Cow::Borrowed(e)),
+            StoredEntry::Quantized(q) => q,
+        };
+        let layout = &self.history.layouts[q.layout];
+        let n = layout.n_categories();
+
+        let base = self.levels.entry(q.actor_id.as_str()).or_default();
+        if q.keyframe || base.len() != n {
+            *base = vec![0; n];
+        }
+        let mut bytes = q.levels.iter().copied();
+        for level in base.iter_mut() {
+            let delta = read_varint(&mut bytes).unwrap_or(0);
+            *level = (i32::from(*level) + delta).clamp(0, u16::MAX as i32) as u16;
+        }
+
+        let floor = layout.smoothing().floor_mass(n);
+        let distribution = base
+            .iter()
+            .map(|&l| if l == 0 { floor } else { f64::from(l) / LEVELS })
+            .collect();
+
+        Some(Cow::Owned(SchemeHistoryEntry {
+            timestamp_ms: q.timestamp_ms,
+            actor_id: q.actor_id.clone(),
+            scheme: layout.restored(distribution, q.scheme_timestamp_ms, q.observations),
+            category_version: q.category_version,
+            prediction_error: q.prediction_error,
+            attribution: q.attribution.clone(),
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a> IntoIterator for &'a SchemeHistory {
+    type Item = Cow<'a, SchemeHistoryEntry>;
+    type IntoIter = HistoryIter<'a>;
+
+    fn into_iter(self) -> HistoryIter<'a> {
+        self.iter()
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, value: i32) {
+    let mut zigzag = ((value << 1) ^ (value >> 31)) as u32;
+    while zigzag >= 0x80 {
+        out.push((zigzag as u8) | 0x80);
+        zigzag >>= 7;
+    }
+    out.push(zigzag as u8);
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Option<i32> {
+    let mut zigzag = 0u32;
+    let mut shift = 0;
+    loop {
+        let byte = bytes.next()?;
+        zigzag |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Some((zigzag >> 1) as i32 ^ -((zigzag & 1) as i32))
+}
+
+// Histories without quantized entries serialize as a plain entry list, as
+// before quantization existed.
+
+#[derive(Serialize)]
+struct StoredRef<'a> {
+    entries: &'a [StoredEntry],
+    layouts: &'a [CompressionScheme],
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HistoryRepr {
+    Entries(Vec<SchemeHistoryEntry>),
+    Stored {
+        entries: Vec<StoredEntry>,
+        layouts: Vec<CompressionScheme>,
+    },
+}
+
+impl Serialize for SchemeHistory {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        if self.layouts.is_empty() {
+            s.collect_seq(self.entries.iter().filter_map(|e| match e {
+                StoredEntry::Full(e) => Some(e),
+                StoredEntry::Quantized(_) => None,
+            }))
+        } else {
+            StoredRef {
+                entries: &self.entries,
+                layouts: &self.layouts,
+            }
+            .serialize(s)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SchemeHistory {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        Ok(match HistoryRepr::deserialize(d)? {
+            HistoryRepr::Entries(entries) => Self {
+                entries: entries.into_iter().map(StoredEntry::Full).collect(),
+                ..Default::default()
+            },
+            HistoryRepr::Stored { entries, layouts } => {
+                if let Some(bad) = entries.iter().find_map(|e| match e {
+                    StoredEntry::Quantized(q) if q.layout >= layouts.len() => Some(q.layout),
+                    _ => None,
+                }) {
+                    return Err(serde::de::Error::custom(format!(
+                        "history entry references unknown layout {}",
+                        bad
+                    )));
+                }
+                Self {
+                    entries,
+                    layouts,
+                    ..Default::default()
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{CompressionDynamicsModel, ModelConfig};
+
+    fn drifting_model(storage: HistoryStorage) -> CompressionDynamicsModel {
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 50,
+            history_storage: storage,
+            ..Default::default()
+        });
+        for t in 0..200 {
+            for (k, actor) in ["A", "B"].iter().enumerate() {
+                let mut obs = vec![0.01; 50];
+                obs[(t / 20 + k * 7) % 50] = 1.0;
+                model.update_scheme(actor, &obs, Some(t as i64)).unwrap();
+            }
+        }
+        model
+    }
+
+    #[test]
+    fn test_varint_roundtrip() {
+        let mut out = Vec::new();
+        let values = [0, 1, -1, 63, -64, 64, 65535, -65535];
+        for v in values {
+            write_varint(&mut out, v);
+        }
+        assert_eq!(out[..3], [0, 2, 1]);
+        let mut bytes = out.into_iter();
+        for v in values {
+            assert_eq!(read_varint(&mut bytes), Some(v));
+        }
+        assert_eq!(read_varint(&mut bytes), None);
+    }
+
+    #[test]
+    fn test_quantized_history_matches_full() {
+        let full = drifting_model(HistoryStorage::Full);
+        let quantized = drifting_model(HistoryStorage::Quantized);
+        assert_eq!(full.history.len(), quantized.history.len());
+        assert!(full.history.distribution_bytes() > 7 * quantized.history.distribution_bytes());
+
+        for (f, q) in full.history.iter().zip(quantized.history.iter()) {
+            assert_eq!(f.actor_id, q.actor_id);
+            assert_eq!(f.timestamp_ms, q.timestamp_ms);
+            assert_eq!(f.scheme.observation_count(), q.scheme.observation_count());
+            for (a, b) in f.scheme.distribution().iter().zip(q.scheme.distribution()) {
+                assert!((a - b).abs() < 1e-3);
+            }
+            assert!(q.scheme.distribution().iter().all(|&p| p > 0.0));
+        }
+
+        // Reloading keeps entries decodable and appending keeps working
+        let mut restored =
+            CompressionDynamicsModel::from_json(&quantized.to_json().unwrap()).unwrap();
+        restored.update_scheme("A", &[1.0; 50], Some(200)).unwrap();
+        let entries = restored.history.into_entries();
+        assert_eq!(entries.len(), 401);
+        let last = quantized.history.iter().nth(398).unwrap();
+        assert_eq!(
+            entries[398].scheme.distribution(),
+            last.scheme.distribution()
+        );
+    }
+
+    #[test]
+    fn test_history_policies() {
+        let run = |policy: HistoryPolicy| {
+            let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+                n_categories: 3,
+                history_policy: policy,
+                ..Default::default()
+            });
+            for t in 0..10 {
+                let obs = if t < 5 {
+                    [1.0, 0.0, 0.0]
+                } else {
+                    [0.0, 0.0, 1.0]
+                };
+                model
+                    .update_scheme_attributed("A", &obs, Some(t), "B")
+                    .unwrap();
+            }
+            model
+        };
+
+        assert_eq!(run(HistoryPolicy::EveryUpdate).history.len(), 10);
+        let every_third = run(HistoryPolicy::EveryN { n: 3 });
+        let counts: Vec<u64> = every_third
+            .history
+            .iter()
+            .map(|e| e.scheme.observation_count())
+            .collect();
+        assert_eq!(counts, [3, 6, 9]);
+        assert!(every_third.directed_grievance("A", "B").is_some());
+
+        // Only steps that add up to a visible move are recorded
+        let on_change = run(HistoryPolicy::OnChange { epsilon: 0.1 });
+        let times: Vec<i64> = on_change.history.iter().map(|e| e.timestamp_ms).collect();
+        assert_eq!(times, [0, 3, 6, 8]);
+
+        let mut on_alert = run(HistoryPolicy::OnAlert);
+        assert!(on_alert.history.is_empty());
+        on_alert.record_scheme("A", 42).unwrap();
+        assert_eq!(on_alert.history.iter().next().unwrap().timestamp_ms, 42);
+        assert!(on_alert.record_scheme("Z", 42).is_err());
+    }
+
+    #[test]
+    fn test_full_history_serializes_as_list() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.update_scheme("A", &[1.0, 0.0, 0.0], Some(1)).unwrap();
+        let json = serde_json::to_value(&model.history).unwrap();
+        assert!(json.is_array());
+        let history: SchemeHistory = serde_json::from_value(json).unwrap();
+        assert_eq!(history.len(), 1);
+    }
+}
@@ -0,0 +1,298 @@
+//! Ranked intervention plans.
+//!
+//! Combines the reconciliation path, the communication term and the
+//! sensitivity analysis into a list of concrete candidate interventions for
+//! a dyad, each scored by the escalation probability it removes per unit
+//! cost. Candidates are:
+//!
+//! - raising the communication level by fixed steps,
+//! - aligning the dyad's top diverging categories: both actors move a
+//!   fraction of the way toward their midpoint on that category,
+//! - reducing both actors' windowed grievance by a fraction.
+//!
+//! Projections re-evaluate the logistic formula with the changed input, so
+//! they are exact for that formula rather than first-order estimates:
+//!
+//! ```text
+//! reduction  = P − P'
+//! efficiency = reduction / cost
+//! ```
+
+use crate::divergence::symmetric_kl;
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::sensitivity::EscalationSensitivity;
+use serde::{Deserialize, Serialize};
+
+/// Cost per unit of each intervention type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterventionCosts {
+    /// Per unit of communication level added
+    pub communication: f64,
+    /// Per unit of probability mass moved across both actors
+    pub alignment: f64,
+    /// Per unit of mean grievance removed
+    pub grievance: f64,
+}
+
+impl Default for InterventionCosts {
+    fn default() -> Self {
+        Self {
+            communication: 1.0,
+            alignment: 2.0,
+            grievance: 1.0,
+        }
+    }
+}
+
+/// Candidate generation settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterventionConfig {
+    /// Communication increases to evaluate
+    pub communication_steps: Vec<f64>,
+    /// Number of top diverging categories to consider aligning
+    pub alignment_categories: usize,
+    /// Fraction of the gap closed on an aligned category
+    pub alignment_fraction: f64,
+    /// Fraction of grievance removed
+    pub grievance_fraction: f64,
+    pub costs: InterventionCosts,
+}
+
+impl Default for InterventionConfig {
+    fn default() -> Self {
+        Self {
+            communication_steps: vec![0.1, 0.25, 0.5],
+            alignment_categories: 3,
+            alignment_fraction: 0.5,
+            grievance_fraction: 0.5,
+            costs: InterventionCosts::default(),
+        }
+    }
+}
+
+/// What an intervention changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InterventionKind {
+    IncreaseCommunication { amount: f64 },
+    AlignCategory { category: String, fraction: f64 },
+    ReduceGrievance { fraction: f64 },
+}
+
+/// A scored candidate intervention
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Intervention {
+    pub kind: InterventionKind,
+    pub cost: f64,
+    /// Logistic escalation probability after the intervention
+    pub projected_probability: f64,
+    /// Baseline minus projected probability
+    pub reduction: f64,
+    /// Reduction per unit cost
+    pub efficiency: f64,
+}
+
+/// Interventions for a dyad, most efficient first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterventionPlan {
+    pub actor_a: String,
+    pub actor_b: String,
+    /// Logistic escalation probability without intervention
+    pub baseline_probability: f64,
+    /// Sensitivity at the baseline operating point
+    pub sensitivity: EscalationSensitivity,
+    pub interventions: Vec<Intervention>,
+}
+
+impl InterventionPlan {
+    /// Most efficient intervention, if any reduces escalation
+    pub fn best(&self) -> Option<&Intervention> {
+        self.interventions.first().filter(|i| i.reduction > 0.0)
+    }
+
+    /// Greedy selection of the most efficient interventions within a budget
+    ///
+    /// Reductions are not re-projected jointly, so the summed reduction of
+    /// the selection is an approximation.
+    pub fn within_budget(&self, budget: f64) -> Vec<&Intervention> {
+        let mut spent = 0.0;
+        let mut chosen = Vec::new();
+        for intervention in &self.interventions {
+            if intervention.reduction <= 0.0 {
+                break;
+            }
+            if spent + intervention.cost <= budget {
+                spent += intervention.cost;
+                chosen.push(intervention);
+            }
+        }
+        chosen
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Rank candidate interventions for a dyad by escalation reduction per
+    /// unit cost, without recording anything
+    pub fn plan_interventions(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        communication_level: f64,
+        shock_intensity: f64,
+        config: &InterventionConfig,
+    ) -> Result<InterventionPlan> {
+        if !(0.0..=1.0).contains(&config.alignment_fraction)
+            || !(0.0..=1.0).contains(&config.grievance_fraction)
+        {
+            return Err(DivergenceError::ConfigError(
+                "Intervention fractions must be in [0, 1]".to_string(),
+            ));
+        }
+        let actor_a = self.require_actor(actor_a)?;
+        let actor_b = self.require_actor(actor_b)?;
+        let prediction =
+            self.peek_escalation(actor_a, actor_b, communication_level, shock_intensity)?;
+        let sensitivity =
+            self.escalation_sensitivity(actor_a, actor_b, communication_level, shock_intensity)?;
+        let contributions = prediction.contributions;
+        let base_logit = contributions.total();
+        let baseline = sigmoid(base_logit);
+        let model_config = &self.config;
+
+        let mut interventions = Vec::new();
+        let mut push = |kind: InterventionKind, cost: f64, logit: f64| {
+            let projected = sigmoid(logit);
+            let reduction = baseline - projected;
+            interventions.push(Intervention {
+                kind,
+                cost,
+                projected_probability: projected,
+                reduction,
+                efficiency: if cost > 0.0 { reduction / cost } else { 0.0 },
+            });
+        };
+
+        for &amount in &config.communication_steps {
+            push(
+                InterventionKind::IncreaseCommunication { amount },
+                amount * config.costs.communication,
+                base_logit - model_config.escalation_beta * amount,
+            );
+        }
+
+        if prediction.avg_grievance > 0.0 && config.grievance_fraction > 0.0 {
+            let removed = prediction.avg_grievance * config.grievance_fraction;
+            push(
+                InterventionKind::ReduceGrievance {
+                    fraction: config.grievance_fraction,
+                },
+                removed * config.costs.grievance,
+                base_logit - 0.5 * removed,
+            );
+        }
+
+        // Aligning a category changes Φ, and with it the dΦ/dt term
+        let path = self.find_alignment_path(actor_a, actor_b, 0.0)?;
+        let dist_a = self.schemes[actor_a].distribution();
+        let dist_b = self.schemes[actor_b].distribution();
+        let last_phi = prediction.current_phi - prediction.d_phi_dt;
+        for divergence in path
+            .diverging_categories
+            .iter()
+            .take(config.alignment_categories)
+        {
+            let Some(k) = self.schemes[actor_a].category_index(&divergence.category) else {
+                continue;
+            };
+            let (aligned_a, aligned_b, moved) =
+                align_category(dist_a, dist_b, k, config.alignment_fraction);
+            if moved <= 0.0 {
+                continue;
+            }
+            let phi = symmetric_kl(&aligned_a, &aligned_b)?;
+            let d_phi = phi - last_phi;
+            let logit = base_logit - contributions.phi - contributions.d_phi
+                + model_config.escalation_alpha * phi
+                + model_config.escalation_gamma * d_phi.max(0.0);
+            push(
+                InterventionKind::AlignCategory {
+                    category: divergence.category.clone(),
+                    fraction: config.alignment_fraction,
+                },
+                moved * config.costs.alignment,
+                logit,
+            );
+        }
+
+        interventions.sort_by(|a, b| b.efficiency.total_cmp(&a.efficiency));
+        Ok(InterventionPlan {
+            actor_a: actor_a.to_string(),
+            actor_b: actor_b.to_string(),
+            baseline_probability: baseline,
+            sensitivity,
+            interventions,
+        })
+    }
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Move both distributions `fraction` of the way to their midpoint on
+/// category `k`, renormalizing. Returns the new distributions and the
+/// probability mass moved.
+fn align_category(p: &[f64], q: &[f64], k: usize, fraction: f64) -> (Vec<f64>, Vec<f64>, f64) {
+    let shift = 0.5 * fraction * (q[k] - p[k]);
+    let mut p = p.to_vec();
+    let mut q = q.to_vec();
+    p[k] += shift;
+    q[k] -= shift;
+    for dist in [&mut p, &mut q] {
+        let total: f64 = dist.iter().sum();
+        dist.iter_mut().for_each(|x| *x /= total);
+    }
+    (p, q, 2.0 * shift.abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intervention_plan() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.7, 0.2, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.2, 0.7]), None);
+        model.update_scheme("A", &[0.9, 0.05, 0.05], None).unwrap();
+
+        let plan = model
+            .plan_interventions("A", "B", 0.2, 0.0, &InterventionConfig::default())
+            .unwrap();
+        assert!(plan.interventions.len() >= 5);
+        for pair in plan.interventions.windows(2) {
+            assert!(pair[0].efficiency >= pair[1].efficiency);
+        }
+        assert!(plan
+            .interventions
+            .iter()
+            .any(|i| matches!(i.kind, InterventionKind::AlignCategory { .. })));
+        assert!(plan
+            .interventions
+            .iter()
+            .any(|i| matches!(i.kind, InterventionKind::ReduceGrievance { .. })));
+
+        let best = plan.best().unwrap();
+        assert!(best.projected_probability < plan.baseline_probability);
+        assert!((plan.sensitivity.probability - plan.baseline_probability).abs() < 1e-12);
+
+        let affordable = plan.within_budget(0.3);
+        assert!(affordable.iter().map(|i| i.cost).sum::<f64>() <= 0.3);
+
+        let bad = InterventionConfig {
+            alignment_fraction: 1.5,
+            ..Default::default()
+        };
+        assert!(model.plan_interventions("A", "B", 0.2, 0.0, &bad).is_err());
+    }
+}
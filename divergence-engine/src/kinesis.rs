@@ -0,0 +1,534 @@
+//! AWS Kinesis event source.
+//!
+//! [`KinesisEventSource`] polls every shard of a Kinesis data stream with
+//! `GetRecords`. Each record holds [`StreamEvent`] JSON, one event or an
+//! array; records that fail to parse are skipped and counted.
+//!
+//! ## Shards
+//!
+//! Shards are listed on the first `receive` and again whenever one closes
+//! (after a split or merge). A child shard is only read once its parents
+//! (both, after a merge) have been read to the end, so per-key ordering
+//! survives resharding. Shards read to the end and fully acknowledged are
+//! remembered with their final checkpoint, so later listings do not read
+//! them again. Expired iterators are reacquired after the last delivered
+//! record.
+//!
+//! ## Checkpoints
+//!
+//! A shard's checkpoint is the last record whose events, and every earlier
+//! record's events, have been acknowledged:
+//!
+//! ```text
+//! delivered:     s1  s2  s3  s4
+//! acknowledged:  s1      s3  s4
+//! checkpoint:    s1  (s2 still in flight)
+//! ```
+//!
+//! [`KinesisEventSource::checkpoints`] exposes them for persistence and
+//! [`KinesisEventSource::with_checkpoints`] resumes from them, giving
+//! at-least-once delivery across restarts.
+
+use crate::error::{DivergenceError, Result};
+use crate::streaming::{decode_events, EventSource, StreamEvent};
+use async_trait::async_trait;
+use aws_sdk_kinesis::error::DisplayErrorContext;
+use aws_sdk_kinesis::types::ShardIteratorType;
+use aws_sdk_kinesis::Client;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+
+/// Where to start reading shards that have no checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StartPosition {
+    /// Oldest record still retained
+    #[default]
+    TrimHorizon,
+    /// Only records written after the iterator is acquired
+    Latest,
+}
+
+/// Settings for a [`KinesisEventSource`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KinesisConfig {
+    pub stream_name: String,
+    pub start: StartPosition,
+    /// `Limit` for each `GetRecords` call (at most 10,000)
+    pub records_per_call: i32,
+    /// Pause after a round over all shards that returned nothing
+    pub poll_interval_ms: u64,
+    /// Consecutive failed API calls before the source reports unhealthy
+    pub max_consecutive_errors: usize,
+}
+
+impl KinesisConfig {
+    pub fn new(stream_name: impl Into<String>) -> Self {
+        Self {
+            stream_name: stream_name.into(),
+            start: StartPosition::TrimHorizon,
+            records_per_call: 1000,
+            poll_interval_ms: 1000,
+            max_consecutive_errors: 10,
+        }
+    }
+}
+
+/// Kinesis sequence number, ordered numerically
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SequenceNumber(String);
+
+impl Ord for SequenceNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Decimal strings without leading zeros
+        (self.0.len(), &self.0).cmp(&(other.0.len(), &other.0))
+    }
+}
+
+impl PartialOrd for SequenceNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Read position and acknowledgement state of one shard
+#[derive(Debug, Default)]
+struct ShardCursor {
+    /// Parent and, after a merge, adjacent parent
+    parents: Vec<String>,
+    iterator: Option<String>,
+    /// Read to the end; no further records will arrive
+    closed: bool,
+    /// Last record handed out
+    position: Option<SequenceNumber>,
+    checkpoint: Option<SequenceNumber>,
+    /// Unacknowledged event count per delivered record
+    in_flight: BTreeMap<SequenceNumber, usize>,
+    /// Acknowledged records past the checkpoint
+    acked: BTreeSet<SequenceNumber>,
+}
+
+impl ShardCursor {
+    fn resume_from(checkpoint: Option<SequenceNumber>) -> Self {
+        Self {
+            position: checkpoint.clone(),
+            checkpoint,
+            ..Default::default()
+        }
+    }
+
+    fn deliver(&mut self, seq: SequenceNumber, n_events: usize) {
+        self.position = Some(seq.clone());
+        if n_events == 0 {
+            self.acked.insert(seq);
+            self.advance();
+        } else {
+            self.in_flight.insert(seq, n_events);
+        }
+    }
+
+    fn ack(&mut self, seq: &SequenceNumber) {
+        if let Some(remaining) = self.in_flight.get_mut(seq) {
+            *remaining -= 1;
+            if *remaining == 0 {
+                self.in_flight.remove(seq);
+                self.acked.insert(seq.clone());
+                self.advance();
+            }
+        }
+    }
+
+    /// Move the checkpoint up to the oldest record still in flight
+    fn advance(&mut self) {
+        let horizon = self.in_flight.keys().next().cloned();
+        while let Some(first) = self.acked.first() {
+            if horizon.as_ref().is_some_and(|h| first > h) {
+                break;
+            }
+            self.checkpoint = self.acked.pop_first();
+        }
+    }
+
+    /// Read to the end with every record acknowledged
+    fn finished(&self) -> bool {
+        self.closed && self.in_flight.is_empty()
+    }
+}
+
+/// Event source polling the shards of a Kinesis data stream
+pub struct KinesisEventSource {
+    client: Client,
+    config: KinesisConfig,
+    shards: BTreeMap<String, ShardCursor>,
+    /// Final checkpoints of shards read to the end and fully acknowledged
+    finished: BTreeMap<String, Option<SequenceNumber>>,
+    /// Checkpoints to resume from, for shards not listed yet
+    resume: HashMap<String, String>,
+    /// Record each delivered event came from
+    pending: HashMap<String, Vec<(String, SequenceNumber)>>,
+    needs_listing: bool,
+    consecutive_errors: usize,
+    malformed: u64,
+}
+
+impl KinesisEventSource {
+    /// Source reading through an existing client
+    pub fn new(client: Client, config: KinesisConfig) -> Self {
+        Self {
+            client,
+            config,
+            shards: BTreeMap::new(),
+            finished: BTreeMap::new(),
+            resume: HashMap::new(),
+            pending: HashMap::new(),
+            needs_listing: true,
+            consecutive_errors: 0,
+            malformed: 0,
+        }
+    }
+
+    /// Source with a client configured from the environment (region,
+    /// credentials chain)
+    pub async fn from_env(config: KinesisConfig) -> Self {
+        let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        Self::new(Client::new(&sdk_config), config)
+    }
+
+    /// Resume shards after these sequence numbers (shard ID → sequence
+    /// number), as returned by [`Self::checkpoints`]
+    pub fn with_checkpoints(mut self, checkpoints: HashMap<String, String>) -> Self {
+        self.resume = checkpoints;
+        self
+    }
+
+    /// Settings
+    pub fn config(&self) -> &KinesisConfig {
+        &self.config
+    }
+
+    /// Last fully acknowledged sequence number per shard
+    pub fn checkpoints(&self) -> HashMap<String, String> {
+        let mut checkpoints = self.resume.clone();
+        let tracked = self
+            .shards
+            .iter()
+            .map(|(shard_id, cursor)| (shard_id, &cursor.checkpoint));
+        for (shard_id, checkpoint) in self.finished.iter().chain(tracked) {
+            if let Some(seq) = checkpoint {
+                checkpoints.insert(shard_id.clone(), seq.0.clone());
+            }
+        }
+        checkpoints
+    }
+
+    /// Records skipped because they did not parse as events
+    pub fn malformed_records(&self) -> u64 {
+        self.malformed
+    }
+
+    fn api_error(&mut self, call: &str, error: impl std::fmt::Display) -> DivergenceError {
+        self.consecutive_errors += 1;
+        DivergenceError::ConfigError(format!(
+            "Kinesis {} on {} failed: {}",
+            call, self.config.stream_name, error
+        ))
+    }
+
+    async fn list_shards(&mut self) -> Result<()> {
+        let mut next_token: Option<String> = None;
+        loop {
+            // The stream name must be omitted when paging
+            let request = match &next_token {
+                Some(token) => self.client.list_shards().next_token(token),
+                None => self
+                    .client
+                    .list_shards()
+                    .stream_name(&self.config.stream_name),
+            };
+            let output = match request.send().await {
+                Ok(output) => output,
+                Err(e) => return Err(self.api_error("ListShards", DisplayErrorContext(e))),
+            };
+            for shard in output.shards() {
+                let parents = shard
+                    .parent_shard_id()
+                    .into_iter()
+                    .chain(shard.adjacent_parent_shard_id())
+                    .map(str::to_string)
+                    .collect();
+                self.track_shard(shard.shard_id(), parents);
+            }
+            next_token = output.next_token().map(str::to_string);
+            if next_token.is_none() {
+                break;
+            }
+        }
+        self.needs_listing = false;
+        Ok(())
+    }
+
+    /// Track a listed shard, unless it is tracked already or finished
+    fn track_shard(&mut self, shard_id: &str, parents: Vec<String>) {
+        if self.finished.contains_key(shard_id) {
+            return;
+        }
+        let resume = self.resume.remove(shard_id).map(SequenceNumber);
+        self.shards
+            .entry(shard_id.to_string())
+            .or_insert_with(|| ShardCursor {
+                parents,
+                ..ShardCursor::resume_from(resume)
+            });
+    }
+
+    /// Open shards whose parents, if still tracked, have been read to the
+    /// end
+    fn readable_shards(&self) -> Vec<String> {
+        self.shards
+            .iter()
+            .filter(|(_, cursor)| !cursor.closed)
+            .filter(|(_, cursor)| {
+                cursor
+                    .parents
+                    .iter()
+                    .all(|parent| self.shards.get(parent).is_none_or(|parent| parent.closed))
+            })
+            .map(|(shard_id, _)| shard_id.clone())
+            .collect()
+    }
+
+    async fn shard_iterator(&mut self, shard_id: &str) -> Result<Option<String>> {
+        let cursor = &self.shards[shard_id];
+        if cursor.iterator.is_some() {
+            return Ok(cursor.iterator.clone());
+        }
+        let request = self
+            .client
+            .get_shard_iterator()
+            .stream_name(&self.config.stream_name)
+            .shard_id(shard_id);
+        let request = match (&cursor.position, self.config.start) {
+            (Some(seq), _) => request
+                .shard_iterator_type(ShardIteratorType::AfterSequenceNumber)
+                .starting_sequence_number(&seq.0),
+            (None, StartPosition::TrimHorizon) => {
+                request.shard_iterator_type(ShardIteratorType::TrimHorizon)
+            }
+            (None, StartPosition::Latest) => request.shard_iterator_type(ShardIteratorType::Latest),
+        };
+        match request.send().await {
+            Ok(output) => Ok(output.shard_iterator().map(str::to_string)),
+            Err(e) => Err(self.api_error("GetShardIterator", DisplayErrorContext(e))),
+        }
+    }
+
+    /// Read one batch from a shard into `events`
+    async fn read_shard(&mut self, shard_id: &str, events: &mut Vec<StreamEvent>) -> Result<()> {
+        let Some(iterator) = self.shard_iterator(shard_id).await? else {
+            self.shards.get_mut(shard_id).unwrap().closed = true;
+            self.needs_listing = true;
+            return Ok(());
+        };
+        let result = self
+            .client
+            .get_records()
+            .shard_iterator(&iterator)
+            .limit(self.config.records_per_call)
+            .send()
+            .await;
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => {
+                let service = e.as_service_error();
+                if service.is_some_and(|s| s.is_expired_iterator_exception()) {
+                    // Reacquired after the last delivered record next round
+                    self.shards.get_mut(shard_id).unwrap().iterator = None;
+                    return Ok(());
+                }
+                if service.is_some_and(|s| s.is_provisioned_throughput_exceeded_exception()) {
+                    // Throttled: retry this shard next round
+                    return Ok(());
+                }
+                return Err(self.api_error("GetRecords", DisplayErrorContext(e)));
+            }
+        };
+
+        let cursor = self.shards.get_mut(shard_id).unwrap();
+        for record in output.records() {
+            let seq = SequenceNumber(record.sequence_number().to_string());
+            let decoded = match decode_events(record.data().as_ref()) {
+                Some(decoded) => decoded,
+                None => {
+                    self.malformed += 1;
+                    Vec::new()
+                }
+            };
+            cursor.deliver(seq.clone(), decoded.len());
+            for event in decoded {
+                self.pending
+                    .entry(event.event_id.clone())
+                    .or_default()
+                    .push((shard_id.to_string(), seq.clone()));
+                events.push(event);
+            }
+        }
+        cursor.iterator = output.next_shard_iterator().map(str::to_string);
+        if cursor.iterator.is_none() {
+            cursor.closed = true;
+            self.needs_listing = true;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventSource for KinesisEventSource {
+    async fn receive(&mut self) -> Result<Vec<StreamEvent>> {
+        let mut events = Vec::new();
+        let mut failure = None;
+
+        let listed = if self.needs_listing {
+            self.list_shards().await
+        } else {
+            Ok(())
+        };
+        match listed {
+            Ok(()) => {
+                for shard_id in self.readable_shards() {
+                    if let Err(e) = self.read_shard(&shard_id, &mut events).await {
+                        failure = Some(e);
+                    }
+                }
+            }
+            Err(e) => failure = Some(e),
+        }
+
+        match failure {
+            Some(e) if self.consecutive_errors >= self.config.max_consecutive_errors => {
+                return Err(e)
+            }
+            Some(_) => {}
+            None => self.consecutive_errors = 0,
+        }
+        if events.is_empty() {
+            tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+        }
+        Ok(events)
+    }
+
+    async fn acknowledge(&mut self, event_ids: &[String]) -> Result<()> {
+        for event_id in event_ids {
+            let Some(records) = self.pending.get_mut(event_id) else {
+                continue;
+            };
+            // Duplicate IDs are acknowledged oldest delivery first
+            let (shard_id, seq) = records.remove(0);
+            if records.is_empty() {
+                self.pending.remove(event_id);
+            }
+            if let Some(cursor) = self.shards.get_mut(&shard_id) {
+                cursor.ack(&seq);
+            }
+        }
+        // Keep only the final checkpoint of shards that are done
+        let done: Vec<String> = self
+            .shards
+            .iter()
+            .filter(|(_, cursor)| cursor.finished())
+            .map(|(shard_id, _)| shard_id.clone())
+            .collect();
+        for shard_id in done {
+            let cursor = self.shards.remove(&shard_id).expect("listed above");
+            self.finished.insert(shard_id, cursor.checkpoint);
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.consecutive_errors < self.config.max_consecutive_errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seq(n: u64) -> SequenceNumber {
+        SequenceNumber(n.to_string())
+    }
+
+    #[test]
+    fn test_sequence_number_order() {
+        assert!(seq(9) < seq(10));
+        assert!(seq(495) < seq(1000));
+        assert!(
+            SequenceNumber("49590338271490256608559692538361571095921575989136588898".into())
+                > seq(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_waits_for_oldest_in_flight() {
+        let mut cursor = ShardCursor::resume_from(Some(seq(5)));
+        cursor.deliver(seq(10), 1);
+        cursor.deliver(seq(20), 2);
+        cursor.deliver(seq(30), 0); // malformed record
+        cursor.deliver(seq(40), 1);
+
+        cursor.ack(&seq(40));
+        cursor.ack(&seq(20));
+        assert_eq!(cursor.checkpoint, Some(seq(5)));
+
+        cursor.ack(&seq(10));
+        assert_eq!(cursor.checkpoint, Some(seq(10)));
+
+        // 20 had two events; 30 and 40 follow once it is done
+        cursor.ack(&seq(20));
+        assert_eq!(cursor.checkpoint, Some(seq(40)));
+        assert!(cursor.in_flight.is_empty() && cursor.acked.is_empty());
+        assert_eq!(cursor.position, Some(seq(40)));
+
+        // Unknown sequence numbers are ignored
+        cursor.ack(&seq(50));
+        assert_eq!(cursor.checkpoint, Some(seq(40)));
+        assert!(!cursor.finished());
+        cursor.closed = true;
+        assert!(cursor.finished());
+    }
+
+    #[tokio::test]
+    async fn test_merged_shards() {
+        let config = aws_sdk_kinesis::Config::builder()
+            .behavior_version(aws_config::BehaviorVersion::latest())
+            .region(aws_config::Region::new("us-east-1"))
+            .build();
+        let mut source =
+            KinesisEventSource::new(Client::from_conf(config), KinesisConfig::new("events"));
+        source.track_shard("p1", Vec::new());
+        source.track_shard("p2", Vec::new());
+        source.track_shard("child", vec!["p1".to_string(), "p2".to_string()]);
+        assert_eq!(source.readable_shards(), ["p1", "p2"]);
+
+        let close = |source: &mut KinesisEventSource, shard_id: &str, n: u64| {
+            let cursor = source.shards.get_mut(shard_id).unwrap();
+            cursor.deliver(seq(n), 0);
+            cursor.closed = true;
+        };
+        close(&mut source, "p1", 10);
+        source.acknowledge(&[]).await.unwrap();
+        // The child waits for its adjacent parent too
+        assert_eq!(source.readable_shards(), ["p2"]);
+
+        // A finished shard is not read again when listed
+        source.track_shard("p1", Vec::new());
+        assert!(!source.shards.contains_key("p1"));
+
+        close(&mut source, "p2", 20);
+        source.acknowledge(&[]).await.unwrap();
+        assert_eq!(source.readable_shards(), ["child"]);
+        let checkpoints = source.checkpoints();
+        assert_eq!(checkpoints["p1"], "10");
+        assert_eq!(checkpoints["p2"], "20");
+    }
+}
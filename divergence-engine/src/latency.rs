@@ -0,0 +1,163 @@
+//! End-to-end latency tracking for the streaming pipeline.
+//!
+//! Each event is stamped with the wall-clock time it entered the pipeline;
+//! alerts carry that stamp forward together with the time they were
+//! produced and the time the sink was handed them:
+//!
+//! ```text
+//! receive ──────▶ process_batch ──────▶ sink.send
+//! ingested_ms     processed_ms          emitted_ms
+//!    └── processing ──┘                     │
+//!    └────────────── end to end ────────────┘
+//! ```
+//!
+//! [`StreamProcessor::latency`](crate::streaming::StreamProcessor::latency)
+//! reports percentiles over the most recent samples, so a slow period shows
+//! up rather than being averaged away by a long quiet history. Times are
+//! wall clock, unrelated to event timestamps, which may lag by hours for
+//! feeds like GDELT.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Samples kept per latency window
+pub const LATENCY_WINDOW: usize = 10_000;
+
+/// Milliseconds since the Unix epoch
+pub(crate) fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Pipeline timestamps of an alert (wall clock, ms)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertLatency {
+    /// When the triggering event entered the pipeline
+    pub ingested_ms: i64,
+    /// When the processor produced the alert
+    pub processed_ms: i64,
+    /// When the alert was handed to the sink (`None` until then)
+    #[serde(default)]
+    pub emitted_ms: Option<i64>,
+}
+
+impl AlertLatency {
+    /// Ingest to processing, in ms
+    pub fn processing_ms(&self) -> i64 {
+        self.processed_ms - self.ingested_ms
+    }
+
+    /// Ingest to emission, in ms
+    pub fn end_to_end_ms(&self) -> Option<i64> {
+        self.emitted_ms.map(|emitted| emitted - self.ingested_ms)
+    }
+}
+
+/// Percentiles of a latency window
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// Samples recorded in total (not just in the window)
+    pub count: u64,
+    pub p50_ms: i64,
+    pub p90_ms: i64,
+    pub p99_ms: i64,
+    pub max_ms: i64,
+}
+
+/// Latency percentiles of the pipeline stages
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct LatencyReport {
+    /// Event ingest to processed, per event
+    pub processing: LatencyStats,
+    /// Event ingest to alert emitted, per delivered alert
+    pub end_to_end: LatencyStats,
+}
+
+/// Most recent latency samples
+#[derive(Debug, Clone)]
+pub struct LatencyWindow {
+    samples: VecDeque<i64>,
+    capacity: usize,
+    count: u64,
+}
+
+impl Default for LatencyWindow {
+    fn default() -> Self {
+        Self::new(LATENCY_WINDOW)
+    }
+}
+
+impl LatencyWindow {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: capacity.max(1),
+            count: 0,
+        }
+    }
+
+    /// Record a sample; clock skew can make it negative, which reads as 0
+    pub fn record(&mut self, latency_ms: i64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency_ms.max(0));
+        self.count += 1;
+    }
+
+    /// Nearest-rank percentiles of the window
+    pub fn stats(&self) -> LatencyStats {
+        let mut sorted: Vec<i64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = |q: f64| match sorted.len() {
+            0 => 0,
+            n => sorted[((q * n as f64).ceil() as usize).clamp(1, n) - 1],
+        };
+        LatencyStats {
+            count: self.count,
+            p50_ms: rank(0.50),
+            p90_ms: rank(0.90),
+            p99_ms: rank(0.99),
+            max_ms: sorted.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_window_percentiles() {
+        let mut window = LatencyWindow::new(100);
+        assert_eq!(window.stats(), LatencyStats::default());
+
+        for ms in 1..=100 {
+            window.record(ms);
+        }
+        let stats = window.stats();
+        assert_eq!(
+            (stats.p50_ms, stats.p90_ms, stats.p99_ms, stats.max_ms),
+            (50, 90, 99, 100)
+        );
+
+        // Old samples leave the window; the total keeps counting
+        for _ in 0..100 {
+            window.record(5);
+        }
+        window.record(-3);
+        let stats = window.stats();
+        assert_eq!(stats.count, 201);
+        assert_eq!((stats.p50_ms, stats.max_ms), (5, 5));
+
+        let latency = AlertLatency {
+            ingested_ms: 1_000,
+            processed_ms: 1_040,
+            emitted_ms: Some(1_100),
+        };
+        assert_eq!(latency.processing_ms(), 40);
+        assert_eq!(latency.end_to_end_ms(), Some(100));
+    }
+}
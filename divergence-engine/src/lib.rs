@@ -17,6 +17,14 @@
 //! - `std` (default): Standard library support
 //! - `wasm`: WebAssembly bindings via wasm-bindgen
 //! - `streaming`: Async streaming interface for real-time data
+//! - `onnx`: ONNX-backed learned escalation predictor
+//! - `ws`: WebSocket event source for the streaming interface
+//! - `aws`: AWS Kinesis event source and SNS alert sink for the streaming interface
+//! - `nats`: NATS JetStream event source and alert sink
+//! - `mqtt`: MQTT alert sink for edge deployments
+//! - `sqlite`: SQLite event source for replay and alert sink for archival
+//! - `proto`: Protobuf codec for events, alerts and conflict potentials
+//! - `avro`: Avro codec for events and alerts with Confluent Schema Registry framing
 //!
 //! ## Example
 //!
@@ -37,10 +45,84 @@
 //! println!("Φ(USA, RUS) = {:.4}", potential.phi);
 //! ```
 
+pub mod anchor;
+pub mod archetype;
+pub mod audit;
+pub mod bloc;
+pub mod builder;
+pub mod causality;
+pub mod cluster;
+pub mod cohesion;
+pub mod contagion;
+pub mod covariates;
+pub mod diff;
 pub mod divergence;
+pub mod ensemble;
 pub mod error;
+pub mod estimation;
+pub mod features;
+pub mod fixtures;
+pub mod geo;
+pub mod grievance;
+pub mod hawkes;
+pub mod hierarchy;
+pub mod history;
+pub mod interventions;
+pub mod manager;
+pub mod matching;
 pub mod model;
+pub mod morph;
+pub mod outlier;
+pub mod plot;
+pub mod predictor;
+pub mod regime;
+pub mod registry;
+pub mod risk_index;
+pub mod risk_ranking;
+pub mod rivalry;
+pub mod rng;
+pub mod robustness;
 pub mod scheme;
+pub mod seasonal;
+pub mod sensitivity;
+pub mod shared;
+pub mod simulation;
+pub mod stats;
+pub mod support;
+pub mod view;
+
+#[cfg(feature = "streaming")]
+pub mod cloudevents;
+
+#[cfg(feature = "streaming")]
+pub mod digest;
+
+#[cfg(feature = "streaming")]
+pub mod event_schema;
+
+#[cfg(feature = "streaming")]
+pub mod latency;
+
+#[cfg(feature = "avro")]
+pub mod avro;
+
+#[cfg(feature = "aws")]
+pub mod kinesis;
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
+#[cfg(feature = "nats")]
+pub mod nats;
+
+#[cfg(feature = "proto")]
+pub mod proto;
+
+#[cfg(feature = "aws")]
+pub mod sns;
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 #[cfg(feature = "streaming")]
 pub mod streaming;
@@ -48,15 +130,93 @@ pub mod streaming;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
+#[cfg(feature = "ws")]
+pub mod ws;
+
 // Re-exports
+pub use anchor::*;
+pub use archetype::*;
+pub use audit::*;
+pub use bloc::*;
+pub use builder::*;
+pub use causality::*;
+pub use cluster::*;
+pub use cohesion::*;
+pub use contagion::*;
+pub use covariates::*;
+pub use diff::*;
 pub use divergence::*;
+pub use ensemble::*;
 pub use error::*;
+pub use estimation::*;
+pub use features::*;
+pub use geo::*;
+pub use grievance::*;
+pub use hawkes::*;
+pub use hierarchy::*;
+pub use history::*;
+pub use interventions::*;
+pub use manager::*;
+pub use matching::*;
 pub use model::*;
+pub use morph::*;
+pub use outlier::*;
+pub use plot::*;
+pub use predictor::*;
+pub use regime::*;
+pub use registry::*;
+pub use risk_index::*;
+pub use risk_ranking::*;
+pub use rng::*;
+pub use robustness::*;
 pub use scheme::*;
+pub use seasonal::*;
+pub use sensitivity::*;
+pub use shared::*;
+pub use simulation::*;
+pub use stats::*;
+pub use support::*;
+pub use view::*;
+
+#[cfg(feature = "streaming")]
+pub use cloudevents::*;
+
+#[cfg(feature = "streaming")]
+pub use digest::*;
+
+#[cfg(feature = "streaming")]
+pub use event_schema::*;
+
+#[cfg(feature = "streaming")]
+pub use latency::*;
+
+#[cfg(feature = "avro")]
+pub use avro::*;
+
+#[cfg(feature = "aws")]
+pub use kinesis::*;
+
+#[cfg(feature = "mqtt")]
+pub use mqtt::*;
+
+#[cfg(feature = "nats")]
+pub use nats::*;
+
+#[cfg(feature = "proto")]
+pub use proto::ProtoCodec;
+
+#[cfg(feature = "aws")]
+pub use sns::*;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
 
 #[cfg(feature = "streaming")]
 pub use streaming::*;
 
+#[cfg(feature = "ws")]
+pub use ws::*;
+
 /// Crate version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
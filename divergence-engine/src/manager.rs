@@ -0,0 +1,303 @@
+//! Multi-tenant model manager.
+//!
+//! A [`ModelManager`] holds many independent, named models (one per
+//! analysis workspace) in a single process. Each tenant has its own
+//! configuration; tenants may optionally share a named [`CategoryRegistry`]
+//! so their schemes live in the same category space. The whole manager
+//! snapshots to one JSON document and restores with shared registries
+//! re-linked.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::{CompressionDynamicsModel, ModelConfig};
+use crate::registry::CategoryRegistry;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Named, independent models
+#[derive(Debug, Clone, Default)]
+pub struct ModelManager {
+    models: IndexMap<String, CompressionDynamicsModel>,
+    registries: IndexMap<String, Arc<CategoryRegistry>>,
+    /// Tenant → name of the shared registry it uses
+    tenant_registries: IndexMap<String, String>,
+}
+
+/// Serialized form of a manager
+#[derive(Serialize)]
+struct ManagerSnapshotRef<'a> {
+    models: &'a IndexMap<String, CompressionDynamicsModel>,
+    registries: IndexMap<&'a str, &'a CategoryRegistry>,
+    tenant_registries: &'a IndexMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct ManagerSnapshot {
+    models: IndexMap<String, CompressionDynamicsModel>,
+    #[serde(default)]
+    registries: IndexMap<String, CategoryRegistry>,
+    #[serde(default)]
+    tenant_registries: IndexMap<String, String>,
+}
+
+impl ModelManager {
+    /// Create an empty manager
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a registry that tenants can share, replacing any with that name
+    ///
+    /// Tenants already using the old registry keep it, also across a
+    /// snapshot and restore.
+    pub fn add_registry(&mut self, name: impl Into<String>, registry: CategoryRegistry) {
+        self.registries.insert(name.into(), registry.into_shared());
+    }
+
+    /// Shared registry by name
+    pub fn registry(&self, name: &str) -> Option<&Arc<CategoryRegistry>> {
+        self.registries.get(name)
+    }
+
+    /// Create a tenant with its own configuration
+    pub fn create(
+        &mut self,
+        tenant: impl Into<String>,
+        config: ModelConfig,
+    ) -> Result<&mut CompressionDynamicsModel> {
+        let tenant = tenant.into();
+        self.ensure_vacant(&tenant)?;
+        config.validate()?;
+        Ok(self
+            .models
+            .entry(tenant)
+            .or_insert(CompressionDynamicsModel::with_config(config)))
+    }
+
+    /// Create a tenant whose schemes use a shared registry
+    ///
+    /// `config.n_categories` is overridden by the registry size.
+    pub fn create_with_registry(
+        &mut self,
+        tenant: impl Into<String>,
+        config: ModelConfig,
+        registry: &str,
+    ) -> Result<&mut CompressionDynamicsModel> {
+        let tenant = tenant.into();
+        self.ensure_vacant(&tenant)?;
+        config.validate()?;
+        let shared = self.registries.get(registry).cloned().ok_or_else(|| {
+            DivergenceError::ConfigError(format!("Unknown registry: {}", registry))
+        })?;
+        self.tenant_registries
+            .insert(tenant.clone(), registry.to_string());
+        Ok(self
+            .models
+            .entry(tenant)
+            .or_insert(CompressionDynamicsModel::with_registry(config, shared)))
+    }
+
+    /// Insert an existing model as a tenant, returning the model it replaces
+    pub fn insert(
+        &mut self,
+        tenant: impl Into<String>,
+        model: CompressionDynamicsModel,
+    ) -> Option<CompressionDynamicsModel> {
+        let tenant = tenant.into();
+        self.tenant_registries.shift_remove(&tenant);
+        self.models.insert(tenant, model)
+    }
+
+    /// Remove a tenant, returning its model
+    pub fn remove(&mut self, tenant: &str) -> Option<CompressionDynamicsModel> {
+        self.tenant_registries.shift_remove(tenant);
+        self.models.shift_remove(tenant)
+    }
+
+    /// A tenant's model
+    pub fn get(&self, tenant: &str) -> Result<&CompressionDynamicsModel> {
+        self.models
+            .get(tenant)
+            .ok_or_else(|| DivergenceError::UnknownTenant(tenant.to_string()))
+    }
+
+    /// A tenant's model, mutably
+    pub fn get_mut(&mut self, tenant: &str) -> Result<&mut CompressionDynamicsModel> {
+        self.models
+            .get_mut(tenant)
+            .ok_or_else(|| DivergenceError::UnknownTenant(tenant.to_string()))
+    }
+
+    /// Whether a tenant exists
+    pub fn contains(&self, tenant: &str) -> bool {
+        self.models.contains_key(tenant)
+    }
+
+    /// Tenant names in creation order
+    pub fn tenants(&self) -> Vec<&str> {
+        self.models.keys().map(|t| t.as_str()).collect()
+    }
+
+    /// Number of tenants
+    pub fn len(&self) -> usize {
+        self.models.len()
+    }
+
+    /// Whether the manager has no tenants
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    /// Tenants and their models in creation order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CompressionDynamicsModel)> {
+        self.models.iter().map(|(t, m)| (t.as_str(), m))
+    }
+
+    /// Tenants and their models in creation order, mutably
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut CompressionDynamicsModel)> {
+        self.models.iter_mut().map(|(t, m)| (t.as_str(), m))
+    }
+
+    /// Serialize every tenant and shared registry to JSON
+    pub fn snapshot(&self) -> Result<String> {
+        let snapshot = ManagerSnapshotRef {
+            models: &self.models,
+            registries: self
+                .registries
+                .iter()
+                .map(|(name, r)| (name.as_str(), r.as_ref()))
+                .collect(),
+            tenant_registries: &self.tenant_registries,
+        };
+        serde_json::to_string(&snapshot)
+            .map_err(|e| DivergenceError::SerializationError(e.to_string()))
+    }
+
+    /// Restore a manager from [`snapshot`](Self::snapshot) output
+    ///
+    /// Tenants that shared a registry share it again after restoring. A
+    /// tenant whose registry has since been replaced keeps the one it was
+    /// saved with and is no longer linked to the name.
+    pub fn restore(json: &str) -> Result<Self> {
+        let snapshot: ManagerSnapshot = serde_json::from_str(json)
+            .map_err(|e| DivergenceError::SerializationError(e.to_string()))?;
+        let registries: IndexMap<String, Arc<CategoryRegistry>> = snapshot
+            .registries
+            .into_iter()
+            .map(|(name, r)| (name, r.into_shared()))
+            .collect();
+
+        let mut tenant_registries = snapshot.tenant_registries;
+        let mut models = IndexMap::with_capacity(snapshot.models.len());
+        for (tenant, mut model) in snapshot.models {
+            let shared = tenant_registries
+                .get(&tenant)
+                .and_then(|name| registries.get(name))
+                .filter(|shared| {
+                    model
+                        .registry
+                        .as_ref()
+                        .is_none_or(|own| own.names().eq(shared.names()))
+                })
+                .cloned();
+            if shared.is_none() {
+                tenant_registries.shift_remove(&tenant);
+            }
+            model.attach_registry(shared.or_else(|| model.registry.clone()));
+            models.insert(tenant, model);
+        }
+
+        Ok(Self {
+            models,
+            registries,
+            tenant_registries,
+        })
+    }
+
+    fn ensure_vacant(&self, tenant: &str) -> Result<()> {
+        if self.models.contains_key(tenant) {
+            return Err(DivergenceError::ConfigError(format!(
+                "Tenant {} already exists",
+                tenant
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manager_tenants_and_snapshot() {
+        let mut manager = ModelManager::new();
+        manager.add_registry("regions", CategoryRegistry::from_names(["N", "S", "E"]));
+
+        manager
+            .create(
+                "team-a",
+                ModelConfig {
+                    n_categories: 4,
+                    learning_rate: 0.3,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .register_actor("USA", None, None);
+        for tenant in ["team-b", "team-c"] {
+            manager
+                .create_with_registry(tenant, ModelConfig::default(), "regions")
+                .unwrap()
+                .register_actor("RUS", None, None);
+        }
+        assert!(manager.create("team-a", ModelConfig::default()).is_err());
+        assert!(manager
+            .create_with_registry("team-d", ModelConfig::default(), "nope")
+            .is_err());
+        assert!(matches!(
+            manager.get("team-z"),
+            Err(DivergenceError::UnknownTenant(_))
+        ));
+
+        assert_eq!(manager.tenants(), vec!["team-a", "team-b", "team-c"]);
+        assert_eq!(manager.get("team-a").unwrap().config().learning_rate, 0.3);
+        assert_eq!(manager.get("team-b").unwrap().config().n_categories, 3);
+        assert_eq!(manager.iter().map(|(_, m)| m.n_actors()).sum::<usize>(), 3);
+
+        let restored = ModelManager::restore(&manager.snapshot().unwrap()).unwrap();
+        assert_eq!(restored.tenants(), manager.tenants());
+        let b = restored.get("team-b").unwrap().registry().unwrap();
+        let c = restored.get("team-c").unwrap().registry().unwrap();
+        assert!(Arc::ptr_eq(b, c));
+        assert!(Arc::ptr_eq(b, restored.registry("regions").unwrap()));
+        assert!(restored.get("team-a").unwrap().registry().is_none());
+
+        assert!(manager.remove("team-b").is_some());
+        assert_eq!(manager.len(), 2);
+    }
+
+    #[test]
+    fn test_replaced_registry_survives_restore() {
+        let mut manager = ModelManager::new();
+        manager.add_registry("regions", CategoryRegistry::from_names(["N", "S", "E"]));
+        manager
+            .create_with_registry("team-a", ModelConfig::default(), "regions")
+            .unwrap()
+            .register_actor("RUS", None, None);
+        manager.add_registry(
+            "regions",
+            CategoryRegistry::from_names(["N", "S", "E", "W", "C"]),
+        );
+        manager
+            .create_with_registry("team-b", ModelConfig::default(), "regions")
+            .unwrap();
+
+        let restored = ModelManager::restore(&manager.snapshot().unwrap()).unwrap();
+        let a = restored.get("team-a").unwrap();
+        assert_eq!(a.registry().unwrap().len(), 3);
+        a.validate_registry().unwrap();
+        let b = restored.get("team-b").unwrap().registry().unwrap();
+        assert!(Arc::ptr_eq(b, restored.registry("regions").unwrap()));
+    }
+}
@@ -0,0 +1,219 @@
+//! Actor ID matching.
+//!
+//! Actor IDs are exact, case-sensitive keys by default. An
+//! [`ActorMatching`] policy in the model configuration can relax lookups
+//! so `"usa"` or `" USA "` resolve to a registered `"USA"`. An exact match
+//! always wins over a normalized one.
+//!
+//! Lookups that still fail suggest the closest registered ID, compared
+//! case-insensitively by Levenshtein distance:
+//!
+//! ```text
+//! Unknown actor: UAS (did you mean USA?)
+//! ```
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::CompressionScheme;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// How actor IDs in queries are matched against registered IDs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActorMatching {
+    /// Compare IDs ignoring case
+    pub case_insensitive: bool,
+    /// Ignore leading and trailing whitespace
+    pub trim: bool,
+    /// Suggest the closest registered ID in unknown-actor errors
+    pub suggest: bool,
+    /// Largest edit distance a suggestion may be from the query
+    pub max_suggestion_distance: usize,
+}
+
+impl Default for ActorMatching {
+    fn default() -> Self {
+        Self {
+            case_insensitive: false,
+            trim: false,
+            suggest: true,
+            max_suggestion_distance: 2,
+        }
+    }
+}
+
+impl ActorMatching {
+    /// Case-insensitive, whitespace-trimming matching
+    pub fn relaxed() -> Self {
+        Self {
+            case_insensitive: true,
+            trim: true,
+            ..Default::default()
+        }
+    }
+
+    /// Whether IDs are only ever matched exactly
+    pub fn is_exact(&self) -> bool {
+        !self.case_insensitive && !self.trim
+    }
+
+    /// Normalized form of an ID under this policy
+    pub fn normalize<'a>(&self, id: &'a str) -> Cow<'a, str> {
+        let id = if self.trim { id.trim() } else { id };
+        if self.case_insensitive {
+            Cow::Owned(id.to_lowercase())
+        } else {
+            Cow::Borrowed(id)
+        }
+    }
+
+    /// Closest of `ids` to a query, if within the suggestion distance
+    pub fn closest<'a>(
+        &self,
+        query: &str,
+        ids: impl IntoIterator<Item = &'a str>,
+    ) -> Option<&'a str> {
+        let wanted = query.trim().to_lowercase();
+        ids.into_iter()
+            .map(|id| (id, levenshtein(&wanted, &id.to_lowercase())))
+            .filter(|&(_, d)| d <= self.max_suggestion_distance)
+            .min_by_key(|&(_, d)| d)
+            .map(|(id, _)| id)
+    }
+}
+
+/// Levenshtein edit distance between two strings, by character
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+impl CompressionDynamicsModel {
+    /// Registration index of the actor a query resolves to
+    pub(crate) fn resolve_index(&self, query: &str) -> Option<usize> {
+        if let Some(i) = self.schemes.get_index_of(query) {
+            return Some(i);
+        }
+        let matching = &self.config.actor_matching;
+        if matching.is_exact() {
+            return None;
+        }
+        let wanted = matching.normalize(query);
+        self.schemes
+            .keys()
+            .position(|id| matching.normalize(id) == wanted)
+    }
+
+    /// Registered ID a query resolves to under the matching policy
+    pub fn resolve_actor(&self, query: &str) -> Option<&str> {
+        self.resolve_index(query)
+            .and_then(|i| self.schemes.get_index(i))
+            .map(|(id, _)| id.as_str())
+    }
+
+    /// Closest registered ID to an unresolved query, if within the
+    /// configured distance
+    pub fn suggest_actor(&self, query: &str) -> Option<&str> {
+        self.config
+            .actor_matching
+            .closest(query, self.schemes.keys().map(String::as_str))
+    }
+
+    /// Unknown-actor error for a query, with a suggestion when enabled
+    pub(crate) fn unknown_actor(&self, query: &str) -> DivergenceError {
+        DivergenceError::UnknownActor {
+            actor: query.to_string(),
+            suggestion: self
+                .config
+                .actor_matching
+                .suggest
+                .then(|| self.suggest_actor(query))
+                .flatten()
+                .map(String::from),
+        }
+    }
+
+    /// Registered ID a query resolves to, or an unknown-actor error
+    pub(crate) fn require_actor(&self, query: &str) -> Result<&str> {
+        self.resolve_actor(query)
+            .ok_or_else(|| self.unknown_actor(query))
+    }
+
+    /// Scheme a query resolves to, or an unknown-actor error
+    pub(crate) fn scheme_for(&self, query: &str) -> Result<&CompressionScheme> {
+        self.resolve_index(query)
+            .and_then(|i| self.schemes.get_index(i))
+            .map(|(_, scheme)| scheme)
+            .ok_or_else(|| self.unknown_actor(query))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interventions::InterventionConfig;
+    use crate::model::ModelConfig;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("USA", "USA"), 0);
+        assert_eq!(levenshtein("UAS", "USA"), 2);
+        assert_eq!(levenshtein("", "RUS"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_actor_matching_and_suggestions() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("USA", None, None);
+        model.register_actor("RUS", None, None);
+
+        assert!(model.get_scheme("usa").is_none());
+        let err = model.peek_potential("usa", "RUS").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown actor: usa (did you mean USA?)");
+        assert!(model
+            .peek_potential("CHN", "RUS")
+            .unwrap_err()
+            .to_string()
+            .ends_with("CHN"));
+
+        let mut relaxed = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 3,
+            actor_matching: ActorMatching::relaxed(),
+            ..Default::default()
+        });
+        relaxed.register_actor("USA", None, None);
+        relaxed.register_actor("RUS", None, None);
+        assert_eq!(relaxed.resolve_actor(" usa "), Some("USA"));
+        assert!(relaxed.get_scheme("usa").is_some());
+        relaxed
+            .update_scheme("rus", &[0.8, 0.1, 0.1], Some(1))
+            .unwrap();
+        assert_eq!(relaxed.n_actors(), 2);
+        relaxed.compute_conflict_potential("usa", "Rus").unwrap();
+        assert_eq!(relaxed.get_dyad_history("USA", "rus").len(), 1);
+        assert_eq!(relaxed.view().scheme_history(" RUS").len(), 1);
+
+        let plan = relaxed
+            .plan_interventions("usa", "RUS", 0.2, 0.0, &InterventionConfig::default())
+            .unwrap();
+        assert_eq!(plan.actor_a, "USA");
+        relaxed.set_parent("rus", "usa", 1.0).unwrap();
+        assert_eq!(relaxed.children_of("USA"), [("RUS", 1.0)]);
+        assert!(relaxed.set_parent("usa", "rus", 1.0).is_err());
+
+        relaxed.set_rival("usa", "rus").unwrap();
+        assert_eq!(relaxed.rival_of(" usa"), Some("RUS"));
+        assert_eq!(relaxed.remove_rival("USA ").as_deref(), Some("RUS"));
+    }
+}
@@ -10,22 +10,100 @@
 //!
 //! Escalation Probability:
 //!     P(escalation) = σ(α·Φ + β·dΦ/dt + γ·G - δ·comm)
-
-use crate::error::{DivergenceError, Result};
+//!
+//! When covariates are fitted, α also weights the covariate-explained Φ
+//! (see [`crate::covariates`]); when dyad events are tracked, their
+//! self-excited intensity adds a further term (see [`crate::hawkes`]).
+
+use crate::archetype::{Archetype, ArchetypeLibrary};
+use crate::audit::AuditEntry;
+use crate::cohesion::CohesionSample;
+use crate::covariates::{CovariateRegression, CovariateSnapshot};
+use crate::divergence::Smoothing;
+use crate::ensemble::{EnsembleConfig, PredictorBreakdown};
+use crate::error::{DivergenceError, Result, ResultExt};
+use crate::geo::GeoLocation;
+use crate::grievance::{GrievanceFormula, GrievanceModel};
+use crate::hawkes::{HawkesConfig, HawkesProcess};
+use crate::hierarchy::ParentLink;
+use crate::history::{HistoryPolicy, HistoryStorage, SchemeHistory};
+use crate::matching::ActorMatching;
+use crate::outlier::{log_rejection, OutlierFilter, RejectedObservation};
+use crate::predictor::{LearnedPredictor, PredictorKind};
+use crate::registry::{same_registry, CategoryMapping, CategoryRegistry};
+use crate::risk_index::{RiskIndexConfig, RiskIndexSample};
+use crate::rivalry::{biased_learning_rate, feedback_observation};
 use crate::scheme::{CompressionScheme, ConflictPotential, RiskLevel};
+use crate::seasonal::{SeasonalConfig, SeasonalProfile};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::Arc;
 
 /// Accumulated grievance (prediction error integral)
 ///
 /// G_A(t) = ∫₀ᵗ (y - ŷ_A)² dτ
+///
+/// The error integrated is set by `ModelConfig::grievance`; squared error
+/// by default, see [`crate::grievance`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "GrievanceRepr")]
 pub struct Grievance {
     pub actor_id: String,
     pub cumulative_error: f64,
     pub window_error: f64,
     pub error_history: Vec<f64>,
     pub timestamp_ms: Option<i64>,
+    /// Surprisal integral: Σ bits of surprise of each observation under the
+    /// actor's scheme before the update (see
+    /// [`CompressionScheme::surprisal`]), an information-theoretic
+    /// alternative to `cumulative_error`
+    pub cumulative_surprisal: f64,
+    pub window_surprisal: f64,
+    pub surprisal_history: Vec<f64>,
+    /// Cumulative error decayed with `ModelConfig::grievance_half_life_ms`
+    /// and reduced by [pardons](CompressionDynamicsModel::pardon)
+    pub decayed_error: f64,
+    /// Time `decayed_error` was last decayed to
+    pub decayed_at_ms: Option<i64>,
+}
+
+/// Serialized [`Grievance`], accepting snapshots from before the surprisal
+/// and decayed integrals
+#[derive(Deserialize)]
+struct GrievanceRepr {
+    actor_id: String,
+    cumulative_error: f64,
+    window_error: f64,
+    error_history: Vec<f64>,
+    timestamp_ms: Option<i64>,
+    #[serde(default)]
+    cumulative_surprisal: f64,
+    #[serde(default)]
+    window_surprisal: f64,
+    #[serde(default)]
+    surprisal_history: Vec<f64>,
+    decayed_error: Option<f64>,
+    decayed_at_ms: Option<i64>,
+}
+
+impl From<GrievanceRepr> for Grievance {
+    fn from(repr: GrievanceRepr) -> Self {
+        Self {
+            actor_id: repr.actor_id,
+            cumulative_error: repr.cumulative_error,
+            window_error: repr.window_error,
+            error_history: repr.error_history,
+            timestamp_ms: repr.timestamp_ms,
+            cumulative_surprisal: repr.cumulative_surprisal,
+            window_surprisal: repr.window_surprisal,
+            surprisal_history: repr.surprisal_history,
+            // Nothing decayed yet: start from the full integral
+            decayed_error: repr.decayed_error.unwrap_or(repr.cumulative_error),
+            decayed_at_ms: repr.decayed_at_ms,
+        }
+    }
 }
 
 impl Grievance {
@@ -36,23 +114,179 @@ impl Grievance {
             window_error: 0.0,
             error_history: Vec::new(),
             timestamp_ms: None,
+            cumulative_surprisal: 0.0,
+            window_surprisal: 0.0,
+            surprisal_history: Vec::new(),
+            decayed_error: 0.0,
+            decayed_at_ms: None,
         }
     }
 
     /// Update with new prediction error
     pub fn update(&mut self, prediction_error: f64, window_size: usize) {
         self.cumulative_error += prediction_error;
+        self.decayed_error += prediction_error;
         self.error_history.push(prediction_error);
+        self.window_error = window_mean(&self.error_history, window_size);
+    }
+
+    /// Update with the surprisal of a new observation, in bits
+    pub fn update_surprisal(&mut self, bits: f64, window_size: usize) {
+        self.cumulative_surprisal += bits;
+        self.surprisal_history.push(bits);
+        self.window_surprisal = window_mean(&self.surprisal_history, window_size);
+    }
+
+    /// `decayed_error` as of `now_ms` under a half-life
+    pub fn decayed_error_at(&self, now_ms: i64, half_life_ms: i64) -> f64 {
+        match self.decayed_at_ms {
+            Some(t) if now_ms > t && half_life_ms > 0 => {
+                self.decayed_error * 0.5f64.powf((now_ms - t) as f64 / half_life_ms as f64)
+            }
+            _ => self.decayed_error,
+        }
+    }
+
+    /// Decay `decayed_error` forward to `now_ms`
+    pub fn decay_to(&mut self, now_ms: i64, half_life_ms: i64) {
+        self.decayed_error = self.decayed_error_at(now_ms, half_life_ms);
+        self.decayed_at_ms = Some(self.decayed_at_ms.map_or(now_ms, |t| t.max(now_ms)));
+    }
+}
+
+/// Mean of the last `window_size` values
+fn window_mean(history: &[f64], window_size: usize) -> f64 {
+    let window = &history[history.len().saturating_sub(window_size)..];
+    window.iter().sum::<f64>() / window.len() as f64
+}
+
+/// Minimum recorded Φ samples before a dyad's z-score is reported
+pub const MIN_BASELINE_SAMPLES: usize = 3;
+
+/// Rolling Φ baseline for one dyad
+///
+/// Holds the last `window` recorded Φ values with running sums, so the
+/// z-score of a new Φ against the dyad's own history is O(1). With
+/// seasonality enabled the window holds seasonally adjusted Φ instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DyadBaseline {
+    values: VecDeque<f64>,
+    sum: f64,
+    sum_sq: f64,
+    #[serde(default)]
+    seasonal: Option<SeasonalProfile>,
+}
+
+impl DyadBaseline {
+    /// Seasonally adjusted Φ, if a seasonal profile is active and the
+    /// potential carries a timestamp
+    pub fn adjust(&self, timestamp_ms: Option<i64>, phi: f64) -> Option<f64> {
+        Some(self.seasonal.as_ref()?.adjust(timestamp_ms?, phi))
+    }
+
+    /// Learned seasonal profile, if any
+    pub fn seasonal(&self) -> Option<&SeasonalProfile> {
+        self.seasonal.as_ref()
+    }
+
+    /// Record a potential: the window receives the adjusted Φ, then the
+    /// seasonal profile learns from the raw Φ
+    fn record(
+        &mut self,
+        timestamp_ms: Option<i64>,
+        phi: f64,
+        window: usize,
+        seasonality: Option<&SeasonalConfig>,
+    ) {
+        if let Some(config) = seasonality {
+            if self.seasonal.as_ref().map(|s| s.config()) != Some(config) {
+                self.seasonal = Some(SeasonalProfile::new(*config));
+            }
+        }
+        let adjusted = self.adjust(timestamp_ms, phi).unwrap_or(phi);
+        self.push(adjusted, window);
+        if let (Some(profile), Some(ts)) = (self.seasonal.as_mut(), timestamp_ms) {
+            profile.observe(ts, phi);
+        }
+    }
+
+    /// Record a Φ value, keeping at most `window` samples
+    pub fn push(&mut self, phi: f64, window: usize) {
+        self.values.push_back(phi);
+        self.sum += phi;
+        self.sum_sq += phi * phi;
+        while self.values.len() > window.max(1) {
+            if let Some(old) = self.values.pop_front() {
+                self.sum -= old;
+                self.sum_sq -= old * old;
+            }
+        }
+    }
 
-        // Windowed error
-        let start = if self.error_history.len() > window_size {
-            self.error_history.len() - window_size
+    /// Number of samples in the window
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether no samples have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Mean Φ over the window
+    pub fn mean(&self) -> f64 {
+        if self.values.is_empty() {
+            0.0
         } else {
-            0
-        };
+            self.sum / self.values.len() as f64
+        }
+    }
 
-        let window = &self.error_history[start..];
-        self.window_error = window.iter().sum::<f64>() / window.len() as f64;
+    /// Population standard deviation of Φ over the window
+    pub fn std(&self) -> f64 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+        let mean = self.mean();
+        (self.sum_sq / self.values.len() as f64 - mean * mean)
+            .max(0.0)
+            .sqrt()
+    }
+
+    /// z-score of `phi` against this baseline
+    ///
+    /// `None` until [`MIN_BASELINE_SAMPLES`] are recorded, or while the
+    /// history has no spread.
+    pub fn z_score(&self, phi: f64) -> Option<f64> {
+        let std = self.std();
+        if self.values.len() < MIN_BASELINE_SAMPLES || std <= 1e-12 {
+            return None;
+        }
+        Some((phi - self.mean()) / std)
+    }
+}
+
+/// Serialize dyad-keyed maps as entry lists (tuple keys are not JSON keys)
+mod dyad_map {
+    use super::ActorId;
+    use indexmap::IndexMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, V>(map: &IndexMap<(ActorId, ActorId), V>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        V: Serialize,
+    {
+        s.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, D, V>(d: D) -> Result<IndexMap<(ActorId, ActorId), V>, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Deserialize<'de>,
+    {
+        let entries: Vec<((ActorId, ActorId), V)> = Vec::deserialize(d)?;
+        Ok(entries.into_iter().collect())
     }
 }
 
@@ -62,6 +296,29 @@ pub struct SchemeHistoryEntry {
     pub timestamp_ms: i64,
     pub actor_id: String,
     pub scheme: CompressionScheme,
+    /// Category-space version the scheme was recorded under
+    #[serde(default)]
+    pub category_version: u64,
+    /// Prediction error the observation added to grievance
+    #[serde(default)]
+    pub prediction_error: f64,
+    /// Event the observation came from, when known
+    #[serde(default)]
+    pub attribution: Option<EventAttribution>,
+}
+
+/// Provenance of an observation
+///
+/// Carried from streamed events into history so grievance can be traced
+/// back to the events that caused it.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct EventAttribution {
+    pub event_id: Option<String>,
+    /// Actor whose actions the event reports
+    pub source_actor: Option<String>,
+    pub event_type: Option<String>,
+    /// Location tag (country code, region or coordinates)
+    pub geo: Option<String>,
 }
 
 /// Escalation prediction result
@@ -76,9 +333,90 @@ pub struct EscalationPrediction {
     pub risk_category: RiskLevel,
     pub actor_a: String,
     pub actor_b: String,
+    /// Covariate-explained Φ added to the logit (0 without fitted covariates)
+    #[serde(default)]
+    pub covariate_contribution: f64,
+    /// Dyad event intensity λ(t) in events per hour (0 without tracked events)
+    #[serde(default)]
+    pub event_intensity: f64,
+    /// Self-excited intensity relative to background, λ/μ - 1
+    #[serde(default)]
+    pub event_excitation: f64,
+    /// Predictor that produced `probability`
+    #[serde(default)]
+    pub predictor: PredictorKind,
+    /// Per-member probabilities (ensemble predictions only)
+    #[serde(default)]
+    pub breakdown: Vec<PredictorBreakdown>,
+    /// Additive terms of the logistic formula's logit
+    #[serde(default)]
+    pub contributions: LogitContributions,
+    /// Windowed grievance A holds toward B from attributed observations
+    #[serde(default)]
+    pub grievance_a_to_b: f64,
+    /// Windowed grievance B holds toward A from attributed observations
+    #[serde(default)]
+    pub grievance_b_to_a: f64,
+}
+
+/// Additive decomposition of the escalation logit
+///
+/// `σ(total())` is the logistic formula's probability, whichever predictor
+/// produced the prediction.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct LogitContributions {
+    /// α·Φ
+    pub phi: f64,
+    /// α·Σ w·x from fitted covariates
+    pub covariates: f64,
+    /// γ·max(dΦ/dt, 0)
+    pub d_phi: f64,
+    /// 0.5·mean grievance
+    pub grievance: f64,
+    /// −β·communication (dampening, never positive)
+    pub communication: f64,
+    /// γ·shock
+    pub shock: f64,
+    /// Hawkes weight · event excitation
+    pub events: f64,
+}
+
+impl LogitContributions {
+    /// Sum of all terms
+    pub fn total(&self) -> f64 {
+        self.phi
+            + self.covariates
+            + self.d_phi
+            + self.grievance
+            + self.communication
+            + self.shock
+            + self.events
+    }
+
+    /// Terms as `(name, value)` pairs, largest magnitude first
+    pub fn ranked(&self) -> Vec<(&'static str, f64)> {
+        let mut terms = vec![
+            ("phi", self.phi),
+            ("covariates", self.covariates),
+            ("d_phi", self.d_phi),
+            ("grievance", self.grievance),
+            ("communication", self.communication),
+            ("shock", self.shock),
+            ("events", self.events),
+        ];
+        terms.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        terms
+    }
 }
 
 impl EscalationPrediction {
+    /// Directed grievance imbalance, G_{A→B} − G_{B→A}
+    ///
+    /// Positive when A is the more aggrieved side.
+    pub fn grievance_asymmetry(&self) -> f64 {
+        self.grievance_a_to_b - self.grievance_b_to_a
+    }
+
     pub fn to_json(&self) -> Result<String> {
         serde_json::to_string(self).map_err(|e| DivergenceError::SerializationError(e.to_string()))
     }
@@ -102,6 +440,136 @@ pub struct CategoryDivergence {
     pub divergence_contribution: f64,
 }
 
+/// How observations whose length differs from the scheme are handled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ObservationProjection {
+    /// Reject mismatched observations with `DimensionMismatch`
+    #[default]
+    Strict,
+    /// Drop trailing entries of longer observations, zero-pad shorter ones
+    TruncateOrPad,
+    /// Observation index `i` feeds scheme index `mapping[i]` (`None` drops it)
+    ///
+    /// Observations longer than the mapping are rejected.
+    IndexMap(Vec<Option<usize>>),
+}
+
+impl ObservationProjection {
+    /// Project an observation onto a scheme with `n_categories` categories
+    pub fn project<'a>(
+        &self,
+        observation: &'a [f64],
+        n_categories: usize,
+    ) -> Result<Cow<'a, [f64]>> {
+        if observation.len() == n_categories && !matches!(self, Self::IndexMap(_)) {
+            return Ok(Cow::Borrowed(observation));
+        }
+
+        match self {
+            Self::Strict => Err(DivergenceError::DimensionMismatch {
+                expected: n_categories,
+                got: observation.len(),
+            }),
+            Self::TruncateOrPad => {
+                let mut projected = vec![0.0; n_categories];
+                let n = observation.len().min(n_categories);
+                projected[..n].copy_from_slice(&observation[..n]);
+                Ok(Cow::Owned(projected))
+            }
+            Self::IndexMap(mapping) => {
+                if observation.len() > mapping.len() {
+                    return Err(DivergenceError::DimensionMismatch {
+                        expected: mapping.len(),
+                        got: observation.len(),
+                    });
+                }
+                let mut projected = vec![0.0; n_categories];
+                for (&x, target) in observation.iter().zip(mapping.iter()) {
+                    match *target {
+                        Some(j) if j < n_categories => projected[j] += x,
+                        Some(j) => {
+                            return Err(DivergenceError::DimensionMismatch {
+                                expected: n_categories,
+                                got: j + 1,
+                            })
+                        }
+                        None => {}
+                    }
+                }
+                Ok(Cow::Owned(projected))
+            }
+        }
+    }
+}
+
+/// How an observation is turned into a probability vector before the
+/// scheme update
+///
+/// Applied after [`ObservationProjection`]. Policies other than `Sum` also
+/// feed the normalized vector into the grievance prediction error.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum ObservationNormalization {
+    /// Divide by the sum (uniform if the sum is not positive)
+    ///
+    /// Negative entries are kept, so signed inputs can produce a
+    /// distribution that smoothing has to repair.
+    #[default]
+    Sum,
+    /// Divide absolute values by their sum (L1 norm)
+    AbsoluteL1,
+    /// Softmax of `x / temperature`; lower temperatures sharpen
+    Softmax { temperature: f64 },
+    /// Zero out negative entries, then divide by the sum
+    ClipNegatives,
+}
+
+impl ObservationNormalization {
+    /// Normalize an observation under this policy
+    ///
+    /// `Sum` returns the observation unchanged and leaves the division to
+    /// the scheme update. Degenerate inputs (all zero after clipping or
+    /// taking magnitudes) become uniform.
+    pub fn normalize<'a>(&self, observation: &'a [f64]) -> Result<Cow<'a, [f64]>> {
+        let n = observation.len();
+        let scaled = |values: Vec<f64>| -> Vec<f64> {
+            let total: f64 = values.iter().sum();
+            if total > 0.0 {
+                values.iter().map(|x| x / total).collect()
+            } else {
+                vec![1.0 / n as f64; n]
+            }
+        };
+
+        match *self {
+            Self::Sum => Ok(Cow::Borrowed(observation)),
+            Self::AbsoluteL1 => Ok(Cow::Owned(scaled(
+                observation.iter().map(|x| x.abs()).collect(),
+            ))),
+            Self::ClipNegatives => Ok(Cow::Owned(scaled(
+                observation.iter().map(|x| x.max(0.0)).collect(),
+            ))),
+            Self::Softmax { temperature } => {
+                if !(temperature > 0.0 && temperature.is_finite()) {
+                    return Err(DivergenceError::ConfigError(format!(
+                        "Softmax temperature must be positive and finite, got {}",
+                        temperature
+                    )));
+                }
+                let max = observation
+                    .iter()
+                    .copied()
+                    .fold(f64::NEG_INFINITY, f64::max);
+                Ok(Cow::Owned(scaled(
+                    observation
+                        .iter()
+                        .map(|x| ((x - max) / temperature).exp())
+                        .collect(),
+                )))
+            }
+        }
+    }
+}
+
 /// Model configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
@@ -122,6 +590,103 @@ pub struct ModelConfig {
 
     /// Window size for grievance calculation
     pub grievance_window: usize,
+
+    /// Prediction error accumulated into grievance
+    #[serde(default)]
+    pub grievance: GrievanceFormula,
+
+    /// Half-life of accumulated grievance
+    ///
+    /// When set, escalation prediction uses the decayed cumulative
+    /// grievance instead of the windowed error.
+    #[serde(default)]
+    pub grievance_half_life_ms: Option<i64>,
+
+    /// Screening of observations before they update a scheme (off by
+    /// default)
+    #[serde(default)]
+    pub outlier_filter: Option<OutlierFilter>,
+
+    /// Learning rate multiplier for observations that widen an actor's
+    /// divergence from its rival (1 = symmetric)
+    #[serde(default = "default_negativity_bias")]
+    pub negativity_bias: f64,
+
+    /// Strength κ of the push away from an actor's rival, proportional to
+    /// their current Φ (off by default)
+    #[serde(default)]
+    pub polarization_feedback: Option<f64>,
+
+    /// Handling of observations whose length differs from the scheme
+    #[serde(default)]
+    pub projection: ObservationProjection,
+
+    /// Conversion of observations to probability vectors
+    #[serde(default)]
+    pub normalization: ObservationNormalization,
+
+    /// Smoothing strategy for schemes the model creates
+    #[serde(default)]
+    pub smoothing: Smoothing,
+
+    /// Recorded Φ samples per dyad used for the `phi_z` baseline
+    #[serde(default = "default_baseline_window")]
+    pub phi_baseline_window: usize,
+
+    /// Seasonal adjustment of dyad Φ baselines (off by default)
+    #[serde(default)]
+    pub seasonality: Option<SeasonalConfig>,
+
+    /// Hawkes modeling of dyad event clustering (off by default)
+    #[serde(default)]
+    pub hawkes: Option<HawkesConfig>,
+
+    /// Escalation weight on the dyad event excitation
+    #[serde(default = "default_hawkes_weight")]
+    pub escalation_hawkes: f64,
+
+    /// Append computed potentials to the dyad history
+    ///
+    /// Read-only callers should use the `peek_*` methods instead.
+    #[serde(default = "default_true")]
+    pub record_potentials: bool,
+
+    /// Escalation predictor used by `predict_escalation`
+    #[serde(default)]
+    pub predictor: PredictorKind,
+
+    /// Members and combination rule for `PredictorKind::Ensemble`
+    #[serde(default)]
+    pub ensemble: EnsembleConfig,
+
+    /// Dyad weighting of the composite system risk index
+    #[serde(default)]
+    pub risk_index: RiskIndexConfig,
+    /// Matching of actor IDs in lookups
+    #[serde(default)]
+    pub actor_matching: ActorMatching,
+    /// Storage format of scheme history entries
+    #[serde(default)]
+    pub history_storage: HistoryStorage,
+    /// Which scheme updates are recorded in history
+    #[serde(default)]
+    pub history_policy: HistoryPolicy,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_baseline_window() -> usize {
+    30
+}
+
+fn default_hawkes_weight() -> f64 {
+    0.3
+}
+
+fn default_negativity_bias() -> f64 {
+    1.0
 }
 
 impl Default for ModelConfig {
@@ -133,6 +698,67 @@ impl Default for ModelConfig {
             escalation_beta: 0.3,
             escalation_gamma: 0.8,
             grievance_window: 30,
+            grievance: GrievanceFormula::default(),
+            grievance_half_life_ms: None,
+            outlier_filter: None,
+            negativity_bias: default_negativity_bias(),
+            polarization_feedback: None,
+            projection: ObservationProjection::default(),
+            normalization: ObservationNormalization::default(),
+            smoothing: Smoothing::default(),
+            phi_baseline_window: default_baseline_window(),
+            seasonality: None,
+            hawkes: None,
+            escalation_hawkes: default_hawkes_weight(),
+            record_potentials: true,
+            predictor: PredictorKind::default(),
+            ensemble: EnsembleConfig::default(),
+            risk_index: RiskIndexConfig::default(),
+            actor_matching: ActorMatching::default(),
+            history_storage: HistoryStorage::default(),
+            history_policy: HistoryPolicy::default(),
+        }
+    }
+}
+
+impl ModelConfig {
+    /// Check settings whose type admits invalid values
+    pub fn validate(&self) -> Result<()> {
+        self.smoothing.validate()?;
+        if let Some(half_life) = self.grievance_half_life_ms {
+            if half_life <= 0 {
+                return Err(DivergenceError::ConfigError(format!(
+                    "Grievance half-life must be positive, got {} ms",
+                    half_life
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compact handle for a registered actor
+///
+/// Handles are registration indices and stay valid for the model's
+/// lifetime (actors are never removed), so `(ActorId, ActorId)` makes a
+/// cheap `Copy` dyad key in place of a pair of owned strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ActorId(pub u32);
+
+impl ActorId {
+    /// Registration index
+    #[inline]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Canonical (unordered) dyad key for a pair of handles
+    #[inline]
+    pub fn dyad(a: ActorId, b: ActorId) -> (ActorId, ActorId) {
+        if a <= b {
+            (a, b)
+        } else {
+            (b, a)
         }
     }
 }
@@ -141,13 +767,52 @@ impl Default for ModelConfig {
 ///
 /// Tracks compression schemes over time, computes conflict potentials,
 /// and predicts escalation based on divergence dynamics.
+///
+/// Actors are stored in registration order; `actors()`, pairwise potentials
+/// and serialized output all follow that order.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompressionDynamicsModel {
-    config: ModelConfig,
-    schemes: HashMap<String, CompressionScheme>,
-    history: Vec<SchemeHistoryEntry>,
-    potentials: Vec<ConflictPotential>,
-    grievances: HashMap<String, Grievance>,
+    pub(crate) config: ModelConfig,
+    pub(crate) schemes: IndexMap<String, CompressionScheme>,
+    pub(crate) history: SchemeHistory,
+    pub(crate) potentials: Vec<ConflictPotential>,
+    pub(crate) grievances: IndexMap<String, Grievance>,
+    #[serde(default)]
+    pub(crate) registry: Option<Arc<CategoryRegistry>>,
+    #[serde(default)]
+    pub(crate) category_version: u64,
+    #[serde(default, with = "dyad_map")]
+    pub(crate) baselines: IndexMap<(ActorId, ActorId), DyadBaseline>,
+    #[serde(default)]
+    pub(crate) covariates: Option<CovariateRegression>,
+    #[serde(default, with = "dyad_map")]
+    pub(crate) dyad_covariates: IndexMap<(ActorId, ActorId), CovariateSnapshot>,
+    #[serde(default, with = "dyad_map")]
+    pub(crate) dyad_events: IndexMap<(ActorId, ActorId), HawkesProcess>,
+    #[serde(default)]
+    pub(crate) archetypes: ArchetypeLibrary,
+    /// Directed grievances keyed `(holder, source)`
+    #[serde(default, with = "dyad_map")]
+    pub(crate) directed_grievances: IndexMap<(ActorId, ActorId), Grievance>,
+    #[serde(default)]
+    pub(crate) locations: IndexMap<String, GeoLocation>,
+    #[serde(default)]
+    pub(crate) parents: IndexMap<String, ParentLink>,
+    #[serde(default)]
+    pub(crate) rejected_observations: VecDeque<RejectedObservation>,
+    /// Designated rival of each actor
+    #[serde(default)]
+    pub(crate) rivals: IndexMap<String, String>,
+    #[serde(default)]
+    pub(crate) cohesion_history: IndexMap<String, Vec<CohesionSample>>,
+    #[serde(default)]
+    pub(crate) risk_index_history: Vec<RiskIndexSample>,
+    #[serde(default)]
+    pub(crate) audit_log: Vec<AuditEntry>,
+    #[serde(skip)]
+    pub(crate) learned_predictor: Option<Arc<dyn LearnedPredictor>>,
+    #[serde(skip)]
+    pub(crate) predictors: IndexMap<String, Arc<dyn LearnedPredictor>>,
 }
 
 impl CompressionDynamicsModel {
@@ -163,29 +828,79 @@ impl CompressionDynamicsModel {
     pub fn with_config(config: ModelConfig) -> Self {
         Self {
             config,
-            schemes: HashMap::new(),
-            history: Vec::new(),
+            schemes: IndexMap::new(),
+            history: SchemeHistory::new(),
             potentials: Vec::new(),
-            grievances: HashMap::new(),
+            grievances: IndexMap::new(),
+            registry: None,
+            category_version: 0,
+            baselines: IndexMap::new(),
+            covariates: None,
+            dyad_covariates: IndexMap::new(),
+            dyad_events: IndexMap::new(),
+            archetypes: ArchetypeLibrary::new(),
+            directed_grievances: IndexMap::new(),
+            locations: IndexMap::new(),
+            parents: IndexMap::new(),
+            rivals: IndexMap::new(),
+            rejected_observations: VecDeque::new(),
+            cohesion_history: IndexMap::new(),
+            risk_index_history: Vec::new(),
+            audit_log: Vec::new(),
+            learned_predictor: None,
+            predictors: IndexMap::new(),
         }
     }
 
+    /// Create a model whose schemes all share a category registry
+    ///
+    /// `config.n_categories` is overridden by the registry size.
+    pub fn with_registry(mut config: ModelConfig, registry: Arc<CategoryRegistry>) -> Self {
+        config.n_categories = registry.len();
+        let mut model = Self::with_config(config);
+        model.registry = Some(registry);
+        model
+    }
+
+    /// Shared category registry, if any
+    pub fn registry(&self) -> Option<&Arc<CategoryRegistry>> {
+        self.registry.as_ref()
+    }
+
     /// Get model configuration
     pub fn config(&self) -> &ModelConfig {
         &self.config
     }
 
-    /// Get all registered actor IDs
+    /// Get all registered actor IDs in registration order
     pub fn actors(&self) -> Vec<&str> {
         self.schemes.keys().map(|s| s.as_str()).collect()
     }
 
+    /// Number of registered actors
+    pub fn n_actors(&self) -> usize {
+        self.schemes.len()
+    }
+
+    /// Handle for a registered actor
+    pub fn actor_id(&self, actor_id: &str) -> Option<ActorId> {
+        self.resolve_index(actor_id).map(|i| ActorId(i as u32))
+    }
+
+    /// Actor ID for a handle
+    pub fn actor_name(&self, id: ActorId) -> Option<&str> {
+        self.schemes.get_index(id.index()).map(|(k, _)| k.as_str())
+    }
+
     /// Get a scheme by actor ID
     pub fn get_scheme(&self, actor_id: &str) -> Option<&CompressionScheme> {
-        self.schemes.get(actor_id)
+        self.scheme_for(actor_id).ok()
     }
 
     /// Register a new actor with initial compression scheme
+    ///
+    /// If the model has a shared registry, the scheme references it and
+    /// `categories` is ignored.
     pub fn register_actor(
         &mut self,
         actor_id: impl Into<String>,
@@ -198,7 +913,13 @@ impl CompressionDynamicsModel {
             vec![1.0 / self.config.n_categories as f64; self.config.n_categories]
         });
 
-        let scheme = CompressionScheme::new(actor_id.clone(), distribution, categories);
+        let mut scheme = CompressionScheme::new_with_smoothing(
+            actor_id.clone(),
+            distribution,
+            categories,
+            self.config.smoothing,
+        );
+        scheme.set_registry(self.registry.clone());
 
         self.schemes.insert(actor_id.clone(), scheme);
         self.grievances
@@ -207,6 +928,145 @@ impl CompressionDynamicsModel {
         self.schemes.get(&actor_id).unwrap()
     }
 
+    /// Register a new actor warm-started from an archetype prior
+    ///
+    /// `prior_strength` is the prior's weight in pseudo-observations: the
+    /// prior dominates roughly until the actor has been observed that many
+    /// times. Custom archetypes must be defined with
+    /// [`define_archetype`](Self::define_archetype) first.
+    pub fn register_actor_with_prior(
+        &mut self,
+        actor_id: impl Into<String>,
+        archetype: &Archetype,
+        prior_strength: f64,
+    ) -> Result<&CompressionScheme> {
+        let actor_id = actor_id.into();
+        let prior = self
+            .archetypes
+            .resolve(archetype, self.config.n_categories)?;
+
+        let mut scheme = CompressionScheme::new_with_smoothing(
+            actor_id.clone(),
+            prior,
+            None,
+            self.config.smoothing,
+        )
+        .with_prior_strength(prior_strength);
+        scheme.set_registry(self.registry.clone());
+
+        self.schemes.insert(actor_id.clone(), scheme);
+        self.grievances
+            .insert(actor_id.clone(), Grievance::new(&actor_id));
+
+        Ok(self.schemes.get(&actor_id).unwrap())
+    }
+
+    /// Define (or replace) a custom archetype profile
+    pub fn define_archetype(
+        &mut self,
+        name: impl Into<String>,
+        distribution: Vec<f64>,
+    ) -> Result<()> {
+        self.archetypes.define(name, distribution)
+    }
+
+    /// Custom archetype profiles defined on this model
+    pub fn archetypes(&self) -> &ArchetypeLibrary {
+        &self.archetypes
+    }
+
+    /// Register a pre-built scheme, checking it shares the model's registry
+    pub fn register_scheme(&mut self, scheme: CompressionScheme) -> Result<()> {
+        self.check_registry(&scheme)?;
+
+        let actor_id = scheme.actor_id.clone();
+        self.grievances
+            .entry(actor_id.clone())
+            .or_insert_with(|| Grievance::new(&actor_id));
+        self.schemes.insert(actor_id, scheme);
+        Ok(())
+    }
+
+    fn check_registry(&self, scheme: &CompressionScheme) -> Result<()> {
+        if !same_registry(self.registry.as_ref(), scheme.registry()) {
+            return Err(DivergenceError::RegistryMismatch(scheme.actor_id.clone()));
+        }
+        if let Some(registry) = &self.registry {
+            if scheme.n_categories() != registry.len() {
+                return Err(DivergenceError::DimensionMismatch {
+                    expected: registry.len(),
+                    got: scheme.n_categories(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that every scheme in the model uses the model's registry
+    pub fn validate_registry(&self) -> Result<()> {
+        self.schemes
+            .values()
+            .try_for_each(|scheme| self.check_registry(scheme))
+    }
+
+    /// Current category-space version (bumped on growth or remapping)
+    pub fn category_version(&self) -> u64 {
+        self.category_version
+    }
+
+    /// Add a category to the model's space, expanding every scheme
+    ///
+    /// The new category starts with smoothing mass only; existing mass is
+    /// scaled down to make room. Returns the new category's index, or the
+    /// existing index if a shared registry already contains `name`.
+    pub fn add_category(&mut self, name: impl Into<String>) -> Result<usize> {
+        let name = name.into();
+
+        if let Some(registry) = &self.registry {
+            if let Some(idx) = registry.get(&name) {
+                return Ok(idx);
+            }
+            let mut grown = CategoryRegistry::clone(registry);
+            grown.intern(&name);
+            self.registry = Some(grown.into_shared());
+        }
+
+        let idx = self.config.n_categories;
+        for scheme in self.schemes.values_mut() {
+            scheme.grow(Some(name.clone()));
+            scheme.set_registry(self.registry.clone());
+        }
+        self.config.n_categories += 1;
+        self.category_version += 1;
+        Ok(idx)
+    }
+
+    /// Project every scheme onto a new category space (merge/split/drop)
+    pub fn remap_categories(&mut self, mapping: &CategoryMapping) -> Result<()> {
+        mapping.validate(self.config.n_categories)?;
+
+        let registry = self
+            .registry
+            .as_ref()
+            .map(|_| CategoryRegistry::from_names(&mapping.new_categories).into_shared());
+        if let Some(r) = &registry {
+            if r.len() != mapping.n_new() {
+                return Err(DivergenceError::ConfigError(
+                    "Mapping target names must be unique".to_string(),
+                ));
+            }
+        }
+
+        for scheme in self.schemes.values_mut() {
+            scheme.remap(mapping)?;
+            scheme.set_registry(registry.clone());
+        }
+        self.registry = registry;
+        self.config.n_categories = mapping.n_new();
+        self.category_version += 1;
+        Ok(())
+    }
+
     /// Update an actor's compression scheme based on new observation
     pub fn update_scheme(
         &mut self,
@@ -214,79 +1074,489 @@ impl CompressionDynamicsModel {
         observation: &[f64],
         timestamp_ms: Option<i64>,
     ) -> Result<&CompressionScheme> {
+        let (index, _) = self.observe(actor_id, observation, timestamp_ms, None)?;
+        Ok(&self.schemes[index])
+    }
+
+    /// Apply an observation, recording it as the history policy asks
+    ///
+    /// Returns the actor's index and the observation's prediction error.
+    fn observe(
+        &mut self,
+        actor_id: &str,
+        observation: &[f64],
+        timestamp_ms: Option<i64>,
+        attribution: Option<EventAttribution>,
+    ) -> Result<(usize, f64)> {
         // Get or register actor
-        if !self.schemes.contains_key(actor_id) {
-            self.register_actor(actor_id, None, None);
+        let index = match self.resolve_index(actor_id) {
+            Some(index) => index,
+            None => {
+                self.register_actor(actor_id, None, None);
+                self.schemes.len() - 1
+            }
+        };
+
+        let rival = if self.config.uses_rivals() {
+            self.rivals
+                .get(self.schemes.get_index(index).unwrap().0)
+                .and_then(|rival| self.schemes.get(rival))
+                .cloned()
+        } else {
+            None
+        };
+        let (actor_id, scheme) = self.schemes.get_index_mut(index).unwrap();
+        let mut entry = apply_observation(
+            &self.config,
+            scheme,
+            self.grievances.get_mut(actor_id.as_str()),
+            rival.as_ref(),
+            observation,
+            timestamp_ms,
+            self.category_version,
+        )
+        .inspect_err(|e| {
+            log_rejection(
+                &mut self.rejected_observations,
+                &self.config,
+                e,
+                actor_id,
+                observation,
+                timestamp_ms,
+            )
+        })?;
+        entry.attribution = attribution;
+        let error = entry.prediction_error;
+        self.history.record(entry, &self.config);
+
+        Ok((index, error))
+    }
+
+    /// Record an actor's current scheme in history, whatever the history
+    /// policy
+    ///
+    /// The entry carries no prediction error. Captures schemes at alerts
+    /// under [`HistoryPolicy::OnAlert`].
+    pub fn record_scheme(&mut self, actor_id: &str, timestamp_ms: i64) -> Result<()> {
+        let scheme = self.scheme_for(actor_id)?.clone();
+        let entry = SchemeHistoryEntry {
+            timestamp_ms,
+            actor_id: scheme.actor_id.clone(),
+            scheme,
+            category_version: self.category_version,
+            prediction_error: 0.0,
+            attribution: None,
+        };
+        self.history.force_record(entry, &self.config);
+        Ok(())
+    }
+
+    /// Update a scheme with an observation attributed to another actor
+    ///
+    /// As [`update_scheme`](Self::update_scheme), and the prediction error
+    /// also accumulates into the directed grievance G_{actor→source}.
+    /// Unknown sources are registered.
+    pub fn update_scheme_attributed(
+        &mut self,
+        actor_id: &str,
+        observation: &[f64],
+        timestamp_ms: Option<i64>,
+        source_actor: &str,
+    ) -> Result<&CompressionScheme> {
+        self.update_scheme_with_attribution(
+            actor_id,
+            observation,
+            timestamp_ms,
+            EventAttribution {
+                source_actor: Some(source_actor.to_string()),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Update a scheme and record the observation's provenance in history
+    ///
+    /// With a source actor set, behaves as
+    /// [`update_scheme_attributed`](Self::update_scheme_attributed).
+    pub fn update_scheme_with_attribution(
+        &mut self,
+        actor_id: &str,
+        observation: &[f64],
+        timestamp_ms: Option<i64>,
+        attribution: EventAttribution,
+    ) -> Result<&CompressionScheme> {
+        let source_actor = attribution.source_actor.clone();
+        if let Some(source) = &source_actor {
+            if self.same_actor(source, actor_id) {
+                return Err(DivergenceError::ConfigError(
+                    "An observation cannot be attributed to the observing actor".to_string(),
+                ));
+            }
+            if self.resolve_index(source).is_none() {
+                self.register_actor(source.as_str(), None, None);
+            }
         }
+        let (_, error) = self.observe(actor_id, observation, timestamp_ms, Some(attribution))?;
+        let holder = self.actor_id(actor_id).unwrap();
 
-        let scheme = self.schemes.get_mut(actor_id).unwrap();
-        let old_distribution = scheme.distribution().to_vec();
+        let Some(source_actor) = source_actor else {
+            return Ok(&self.schemes[holder.index()]);
+        };
+        let key = (holder, self.actor_id(&source_actor).unwrap());
+        let holder_name = self.schemes.get_index(holder.index()).unwrap().0;
+        let grievance = self
+            .directed_grievances
+            .entry(key)
+            .or_insert_with(|| Grievance::new(holder_name.as_str()));
+        if let Some(half_life) = self.config.grievance_half_life_ms {
+            grievance.decay_to(timestamp_ms.unwrap_or_else(now_ms), half_life);
+        }
+        grievance.update(error, self.config.grievance_window);
+        grievance.timestamp_ms = timestamp_ms.or(grievance.timestamp_ms);
 
-        // Update scheme
-        scheme.update(observation, self.config.learning_rate)?;
+        Ok(&self.schemes[holder.index()])
+    }
 
-        if let Some(ts) = timestamp_ms {
-            *scheme = scheme.clone().with_timestamp(ts);
+    /// Apply an event implicating two actors ("A did X to B") in one step
+    ///
+    /// The actor's observation is applied with `attribution`; the target's
+    /// is attributed to the actor, feeding G_{target→actor}. Both are
+    /// checked before either is applied, so an error leaves the model
+    /// unchanged. Unknown actors are registered.
+    pub fn update_interaction(
+        &mut self,
+        actor_id: &str,
+        observation: &[f64],
+        target_id: &str,
+        target_observation: &[f64],
+        timestamp_ms: Option<i64>,
+        attribution: EventAttribution,
+    ) -> Result<()> {
+        if self.same_actor(actor_id, target_id) {
+            return Err(DivergenceError::ConfigError(
+                "An interaction needs two distinct actors".to_string(),
+            ));
+        }
+        if let Some(source) = &attribution.source_actor {
+            if self.same_actor(source, actor_id) {
+                return Err(DivergenceError::ConfigError(
+                    "An observation cannot be attributed to the observing actor".to_string(),
+                ));
+            }
+        }
+        for (id, obs) in [(actor_id, observation), (target_id, target_observation)] {
+            if let Err(e) = self.check_observation(id, obs) {
+                // Logged as the failed update would have been
+                let logged = self.resolve_actor(id).unwrap_or(id).to_string();
+                log_rejection(
+                    &mut self.rejected_observations,
+                    &self.config,
+                    &e,
+                    &logged,
+                    obs,
+                    timestamp_ms,
+                );
+                return Err(e).with_actor(id);
+            }
         }
 
-        // Record history
-        let ts = timestamp_ms.unwrap_or_else(|| {
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_millis() as i64)
-                .unwrap_or(0)
-        });
+        let target_attribution = EventAttribution {
+            source_actor: Some(actor_id.to_string()),
+            ..attribution.clone()
+        };
+        self.update_scheme_with_attribution(actor_id, observation, timestamp_ms, attribution)?;
+        self.update_scheme_with_attribution(
+            target_id,
+            target_observation,
+            timestamp_ms,
+            target_attribution,
+        )?;
+        Ok(())
+    }
 
-        self.history.push(SchemeHistoryEntry {
-            timestamp_ms: ts,
-            actor_id: actor_id.to_string(),
-            scheme: scheme.clone(),
-        });
+    /// Whether two IDs (or aliases) name the same actor
+    fn same_actor(&self, a: &str, b: &str) -> bool {
+        match (self.resolve_index(a), self.resolve_index(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b,
+        }
+    }
 
-        // Update grievance (prediction error)
-        let prediction_error: f64 = old_distribution
+    /// Check an observation would be accepted for an actor, outlier
+    /// filter included, without applying it
+    fn check_observation(&self, actor_id: &str, observation: &[f64]) -> Result<()> {
+        let registered = self.resolve_index(actor_id).map(|i| &self.schemes[i]);
+        let n = registered.map_or(self.config.n_categories, CompressionScheme::n_categories);
+        let projected = self.config.projection.project(observation, n)?;
+        let normalized = self.config.normalization.normalize(&projected)?;
+        if normalized.len() != n {
+            return Err(DivergenceError::DimensionMismatch {
+                expected: n,
+                got: normalized.len(),
+            });
+        }
+        if let Some(filter) = &self.config.outlier_filter {
+            // Unknown actors would start from a uniform scheme
+            let uniform;
+            let scheme = match registered {
+                Some(scheme) => scheme,
+                None => {
+                    uniform = CompressionScheme::new_with_smoothing(
+                        actor_id,
+                        vec![1.0 / n as f64; n],
+                        None,
+                        self.config.smoothing,
+                    );
+                    &uniform
+                }
+            };
+            filter.weight(scheme, &normalized)?;
+        }
+        Ok(())
+    }
+
+    /// Recorded observations in a time range, largest grievance increase
+    /// first
+    ///
+    /// Restrict to one actor's observations with `actor_id`. Answers
+    /// "which events most increased grievance last week" via each entry's
+    /// `attribution`.
+    pub fn grievance_drivers(
+        &self,
+        actor_id: Option<&str>,
+        start_ms: i64,
+        end_ms: i64,
+        limit: usize,
+    ) -> Vec<SchemeHistoryEntry> {
+        let actor_id = actor_id.map(|a| self.resolve_actor(a).unwrap_or(a));
+        let mut entries: Vec<SchemeHistoryEntry> = self
+            .history
             .iter()
-            .zip(observation.iter())
-            .map(|(&p, &o)| (o - p).powi(2))
-            .sum();
+            .filter(|e| e.timestamp_ms >= start_ms && e.timestamp_ms < end_ms)
+            .filter(|e| actor_id.is_none_or(|a| e.actor_id == a))
+            .map(Cow::into_owned)
+            .collect();
+        entries.sort_by(|a, b| b.prediction_error.total_cmp(&a.prediction_error));
+        entries.truncate(limit);
+        entries
+    }
 
-        if let Some(g) = self.grievances.get_mut(actor_id) {
-            g.update(prediction_error, self.config.grievance_window);
+    /// Grievance `holder` has accumulated from observations attributed to
+    /// `source`
+    pub fn directed_grievance(&self, holder: &str, source: &str) -> Option<&Grievance> {
+        let key = (self.actor_id(holder)?, self.actor_id(source)?);
+        self.directed_grievances.get(&key)
+    }
+
+    fn directed_window_error(&self, holder: &str, source: &str) -> f64 {
+        self.directed_grievance(holder, source)
+            .map_or(0.0, |g| g.window_error)
+    }
+
+    /// Compute conflict potential between two actors without recording it
+    pub fn peek_potential(&self, actor_a: &str, actor_b: &str) -> Result<ConflictPotential> {
+        let scheme_a = self.scheme_for(actor_a)?;
+        let scheme_b = self.scheme_for(actor_b)?;
+
+        let mut potential = ConflictPotential::compute(scheme_a, scheme_b)
+            .with_dyad(&scheme_a.actor_id, &scheme_b.actor_id)?;
+        potential.timestamp_ms = scheme_a.timestamp_ms.max(scheme_b.timestamp_ms);
+        if let Some(baseline) = self.dyad_baseline(actor_a, actor_b) {
+            potential.phi_adjusted = baseline.adjust(potential.timestamp_ms, potential.phi);
+            potential.phi_z = baseline.z_score(potential.phi_adjusted.unwrap_or(potential.phi));
         }
+        Ok(potential)
+    }
 
-        Ok(self.schemes.get(actor_id).unwrap())
+    /// Rolling Φ baseline for a dyad, if any potentials have been recorded
+    pub fn dyad_baseline(&self, actor_a: &str, actor_b: &str) -> Option<&DyadBaseline> {
+        let key = ActorId::dyad(self.actor_id(actor_a)?, self.actor_id(actor_b)?);
+        self.baselines.get(&key)
     }
 
-    /// Compute conflict potential between two actors
-    pub fn compute_conflict_potential(
+    /// Append a potential to the dyad history and its Φ baseline
+    ///
+    /// No-op when `config.record_potentials` is disabled.
+    pub fn record_potential(&mut self, potential: ConflictPotential) {
+        if !self.config.record_potentials {
+            return;
+        }
+        if let (Some(a), Some(b)) = (
+            self.actor_id(&potential.actor_a),
+            self.actor_id(&potential.actor_b),
+        ) {
+            let key = ActorId::dyad(a, b);
+            if let (Some(regression), Some(snapshot)) =
+                (self.covariates.as_mut(), self.dyad_covariates.get(&key))
+            {
+                // Values were checked against the regression in set_covariates
+                let _ = regression.observe(&snapshot.values, potential.phi);
+            }
+            self.baselines.entry(key).or_default().record(
+                potential.timestamp_ms,
+                potential.phi,
+                self.config.phi_baseline_window,
+                self.config.seasonality.as_ref(),
+            );
+        }
+        self.potentials.push(potential);
+    }
+
+    /// Define the exogenous covariates attached to dyads
+    ///
+    /// Replaces any previous definition, its fitted weights and all
+    /// attached covariate values.
+    pub fn define_covariates(&mut self, names: Vec<String>, ridge: f64) {
+        self.covariates = Some(CovariateRegression::new(names, ridge));
+        self.dyad_covariates.clear();
+    }
+
+    /// Covariate regression, if covariates are defined
+    pub fn covariates(&self) -> Option<&CovariateRegression> {
+        self.covariates.as_ref()
+    }
+
+    /// Attach covariate values to a dyad
+    ///
+    /// The values apply to every potential recorded for the dyad until
+    /// replaced, and to escalation predictions once weights are fitted.
+    pub fn set_covariates(
         &mut self,
         actor_a: &str,
         actor_b: &str,
-    ) -> Result<ConflictPotential> {
-        let scheme_a = self
-            .schemes
-            .get(actor_a)
-            .ok_or_else(|| DivergenceError::UnknownActor(actor_a.to_string()))?;
+        timestamp_ms: i64,
+        values: &[f64],
+    ) -> Result<()> {
+        let regression = self
+            .covariates
+            .as_ref()
+            .ok_or_else(|| DivergenceError::ConfigError("No covariates defined".to_string()))?;
+        regression.check(values)?;
+
+        let a = self
+            .actor_id(actor_a)
+            .ok_or_else(|| self.unknown_actor(actor_a))?;
+        let b = self
+            .actor_id(actor_b)
+            .ok_or_else(|| self.unknown_actor(actor_b))?;
+
+        self.dyad_covariates.insert(
+            ActorId::dyad(a, b),
+            CovariateSnapshot {
+                timestamp_ms,
+                values: values.to_vec(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Latest covariate values attached to a dyad
+    pub fn dyad_covariates(&self, actor_a: &str, actor_b: &str) -> Option<&CovariateSnapshot> {
+        let key = ActorId::dyad(self.actor_id(actor_a)?, self.actor_id(actor_b)?);
+        self.dyad_covariates.get(&key)
+    }
+
+    /// Fit covariate weights from recorded potentials
+    ///
+    /// Returns [intercept, weight per covariate].
+    pub fn fit_covariates(&mut self) -> Result<Vec<f64>> {
+        let regression = self
+            .covariates
+            .as_mut()
+            .ok_or_else(|| DivergenceError::ConfigError("No covariates defined".to_string()))?;
+        Ok(regression.fit()?.to_vec())
+    }
 
-        let scheme_b = self
-            .schemes
-            .get(actor_b)
-            .ok_or_else(|| DivergenceError::UnknownActor(actor_b.to_string()))?;
+    /// Covariate-explained Φ for a dyad (0 without fitted weights or values)
+    fn covariate_contribution(&self, actor_a: &str, actor_b: &str) -> f64 {
+        match (&self.covariates, self.dyad_covariates(actor_a, actor_b)) {
+            (Some(regression), Some(snapshot)) => {
+                regression.contribution(&snapshot.values).unwrap_or(0.0)
+            }
+            _ => 0.0,
+        }
+    }
 
-        let potential = ConflictPotential::compute(scheme_a, scheme_b)?;
-        self.potentials.push(potential.clone());
+    /// Record a dyad event (alert, shock) initiated by `initiator`
+    ///
+    /// No-op unless `config.hawkes` is set. Bivariate processes mark the
+    /// event by initiator; univariate ones ignore it.
+    pub fn record_dyad_event(
+        &mut self,
+        initiator: &str,
+        target: &str,
+        timestamp_ms: i64,
+    ) -> Result<()> {
+        let Some(config) = self.config.hawkes.as_ref() else {
+            return Ok(());
+        };
+        let a = self
+            .actor_id(initiator)
+            .ok_or_else(|| self.unknown_actor(initiator))?;
+        let b = self
+            .actor_id(target)
+            .ok_or_else(|| self.unknown_actor(target))?;
+
+        let key = ActorId::dyad(a, b);
+        let process = self
+            .dyad_events
+            .entry(key)
+            .or_insert_with(|| HawkesProcess::new(config.clone()));
+        let mark = if process.dims() > 1 && a != key.0 {
+            1
+        } else {
+            0
+        };
+        process.record(timestamp_ms, mark)
+    }
+
+    /// Event process for a dyad, if any events have been recorded
+    pub fn dyad_events(&self, actor_a: &str, actor_b: &str) -> Option<&HawkesProcess> {
+        let key = ActorId::dyad(self.actor_id(actor_a)?, self.actor_id(actor_b)?);
+        self.dyad_events.get(&key)
+    }
+
+    /// Fit the event process for a dyad to its recorded events
+    pub fn fit_dyad_events(&mut self, actor_a: &str, actor_b: &str) -> Result<&HawkesProcess> {
+        let a = self
+            .actor_id(actor_a)
+            .ok_or_else(|| self.unknown_actor(actor_a))?;
+        let b = self
+            .actor_id(actor_b)
+            .ok_or_else(|| self.unknown_actor(actor_b))?;
+        let process = self
+            .dyad_events
+            .get_mut(&ActorId::dyad(a, b))
+            .ok_or_else(|| {
+                DivergenceError::ConfigError(format!(
+                    "No events recorded for {}-{}",
+                    actor_a, actor_b
+                ))
+            })?;
+        process.fit()?;
+        Ok(process)
+    }
+
+    /// Compute conflict potential between two actors and record it
+    pub fn compute_conflict_potential(
+        &mut self,
+        actor_a: &str,
+        actor_b: &str,
+    ) -> Result<ConflictPotential> {
+        let potential = self.peek_potential(actor_a, actor_b)?;
+        self.record_potential(potential.clone());
 
         Ok(potential)
     }
 
-    /// Compute pairwise conflict potentials for all registered actors
-    pub fn compute_all_potentials(&mut self) -> Vec<ConflictPotential> {
-        let actors: Vec<String> = self.schemes.keys().cloned().collect();
+    /// Pairwise conflict potentials for all actors, without recording
+    pub fn peek_all_potentials(&self) -> Vec<ConflictPotential> {
+        let actors: Vec<&String> = self.schemes.keys().collect();
         let mut results = Vec::new();
 
         for i in 0..actors.len() {
             for j in (i + 1)..actors.len() {
-                if let Ok(potential) = self.compute_conflict_potential(&actors[i], &actors[j]) {
+                if let Ok(potential) = self.peek_potential(actors[i], actors[j]) {
                     results.push(potential);
                 }
             }
@@ -295,56 +1565,126 @@ impl CompressionDynamicsModel {
         results
     }
 
-    /// Predict escalation probability between two actors
+    /// Compute and record pairwise conflict potentials for all registered actors
+    pub fn compute_all_potentials(&mut self) -> Vec<ConflictPotential> {
+        let results = self.peek_all_potentials();
+        for potential in &results {
+            self.record_potential(potential.clone());
+        }
+        results
+    }
+
+    /// Predict escalation probability between two actors and record Φ
+    ///
+    /// Model: P(escalation) = σ(α·(Φ + Σ w·x) + β·dΦ/dt + γ·G - δ·comm + h·(λ/μ - 1))
     ///
-    /// Model: P(escalation) = σ(α·Φ + β·dΦ/dt + γ·G - δ·comm)
+    /// The covariate term `Σ w·x` is present only after `fit_covariates`;
+    /// the event term only for dyads with recorded events. With
+    /// `config.predictor` set to `Learned`, the attached
+    /// [`LearnedPredictor`] replaces the formula; with `Ensemble`, the
+    /// formula is one member of `config.ensemble`.
     pub fn predict_escalation(
         &mut self,
         actor_a: &str,
         actor_b: &str,
         communication_level: f64,
         shock_intensity: f64,
+    ) -> Result<EscalationPrediction> {
+        let prediction =
+            self.peek_escalation(actor_a, actor_b, communication_level, shock_intensity)?;
+        let current = self.peek_potential(actor_a, actor_b)?;
+        self.record_potential(current);
+        Ok(prediction)
+    }
+
+    /// Predict escalation probability without recording anything
+    ///
+    /// dΦ/dt is measured against the most recently recorded potential for
+    /// the dyad.
+    pub fn peek_escalation(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        communication_level: f64,
+        shock_intensity: f64,
     ) -> Result<EscalationPrediction> {
         // Current potential
-        let current = self.compute_conflict_potential(actor_a, actor_b)?;
+        let current = self.peek_potential(actor_a, actor_b)?;
 
         // Estimate dΦ/dt from history
-        let dyad_history: Vec<&ConflictPotential> = self
-            .potentials
-            .iter()
-            .filter(|p| {
-                (p.actor_a == actor_a && p.actor_b == actor_b)
-                    || (p.actor_a == actor_b && p.actor_b == actor_a)
-            })
-            .collect();
-
-        let d_phi = if dyad_history.len() >= 2 {
-            let n = dyad_history.len();
-            dyad_history[n - 1].phi - dyad_history[n - 2].phi
-        } else {
-            0.0
-        };
+        let d_phi = self
+            .get_dyad_history(actor_a, actor_b)
+            .last()
+            .map(|last| current.phi - last.phi)
+            .unwrap_or(0.0);
 
         // Get grievance levels
-        let g_a = self.grievances.get(actor_a);
-        let g_b = self.grievances.get(actor_b);
+        let g_a = self.grievances.get(current.actor_a.as_str());
+        let g_b = self.grievances.get(current.actor_b.as_str());
 
+        let level = |g: &Grievance| match self.config.grievance_half_life_ms {
+            Some(half_life) => {
+                g.decayed_error_at(current.timestamp_ms.unwrap_or_else(now_ms), half_life)
+            }
+            None => g.window_error,
+        };
         let avg_grievance = match (g_a, g_b) {
-            (Some(a), Some(b)) => (a.window_error + b.window_error) / 2.0,
-            (Some(a), None) => a.window_error,
-            (None, Some(b)) => b.window_error,
+            (Some(a), Some(b)) => (level(a) + level(b)) / 2.0,
+            (Some(a), None) => level(a),
+            (None, Some(b)) => level(b),
             (None, None) => 0.0,
         };
 
+        let covariate_contribution = self.covariate_contribution(actor_a, actor_b);
+        let (event_intensity, event_excitation) = self
+            .dyad_events(actor_a, actor_b)
+            .map(|p| {
+                // Without scheme timestamps, evaluate at the latest event
+                let now = current.timestamp_ms.or(p.last_event_ms()).unwrap_or(0);
+                (p.total_intensity(now), p.excitation_ratio(now))
+            })
+            .unwrap_or((0.0, 0.0));
+
         // Escalation model (logistic)
-        let logit = self.config.escalation_alpha * current.phi
-            + self.config.escalation_gamma * d_phi.max(0.0) // Only positive changes escalate
-            + 0.5 * avg_grievance
-            - self.config.escalation_beta * communication_level
-            + self.config.escalation_gamma * shock_intensity;
+        let contributions = LogitContributions {
+            phi: self.config.escalation_alpha * current.phi,
+            covariates: self.config.escalation_alpha * covariate_contribution,
+            d_phi: self.config.escalation_gamma * d_phi.max(0.0), // Only positive changes escalate
+            grievance: 0.5 * avg_grievance,
+            communication: -self.config.escalation_beta * communication_level,
+            shock: self.config.escalation_gamma * shock_intensity,
+            events: self.config.escalation_hawkes * event_excitation,
+        };
+        let logit = contributions.total();
 
         // Sigmoid
-        let prob_escalation = 1.0 / (1.0 + (-logit).exp());
+        let logistic = 1.0 / (1.0 + (-logit).exp());
+        let mut breakdown = Vec::new();
+        let prob_escalation = match self.config.predictor {
+            PredictorKind::Logistic => logistic,
+            PredictorKind::Learned => {
+                let predictor = self.learned_predictor.as_ref().ok_or_else(|| {
+                    DivergenceError::ConfigError("No learned predictor attached".to_string())
+                })?;
+                let features = self.feature_vector(
+                    actor_a,
+                    actor_b,
+                    current.timestamp_ms.unwrap_or_else(now_ms),
+                )?;
+                predictor.predict(&features)?.clamp(0.0, 1.0)
+            }
+            PredictorKind::Ensemble => {
+                let features = self.feature_vector(
+                    actor_a,
+                    actor_b,
+                    current.timestamp_ms.unwrap_or_else(now_ms),
+                )?;
+                let (probability, members) =
+                    self.ensemble_probability(actor_a, actor_b, logistic, features)?;
+                breakdown = members;
+                probability
+            }
+        };
 
         Ok(EscalationPrediction {
             probability: prob_escalation,
@@ -356,6 +1696,14 @@ impl CompressionDynamicsModel {
             risk_category: RiskLevel::from_probability(prob_escalation),
             actor_a: actor_a.to_string(),
             actor_b: actor_b.to_string(),
+            covariate_contribution,
+            event_intensity,
+            event_excitation,
+            predictor: self.config.predictor,
+            breakdown,
+            contributions,
+            grievance_a_to_b: self.directed_window_error(actor_a, actor_b),
+            grievance_b_to_a: self.directed_window_error(actor_b, actor_a),
         })
     }
 
@@ -369,17 +1717,12 @@ impl CompressionDynamicsModel {
         actor_b: &str,
         target_phi: f64,
     ) -> Result<ReconciliationPath> {
-        let scheme_a = self
-            .schemes
-            .get(actor_a)
-            .ok_or_else(|| DivergenceError::UnknownActor(actor_a.to_string()))?;
-
-        let scheme_b = self
-            .schemes
-            .get(actor_b)
-            .ok_or_else(|| DivergenceError::UnknownActor(actor_b.to_string()))?;
+        let scheme_a = self.scheme_for(actor_a)?;
+        let scheme_b = self.scheme_for(actor_b)?;
 
-        let current_phi = scheme_a.symmetric_divergence(scheme_b)?;
+        let current_phi = scheme_a
+            .symmetric_divergence(scheme_b)
+            .with_dyad(&scheme_a.actor_id, &scheme_b.actor_id)?;
         let dist_a = scheme_a.distribution();
         let dist_b = scheme_b.distribution();
 
@@ -399,19 +1742,11 @@ impl CompressionDynamicsModel {
         let diverging_categories: Vec<CategoryDivergence> = contributions
             .into_iter()
             .take(5)
-            .map(|(idx, contrib)| {
-                let cat_name = scheme_a
-                    .categories
-                    .get(idx)
-                    .cloned()
-                    .unwrap_or_else(|| format!("cat_{}", idx));
-
-                CategoryDivergence {
-                    category: cat_name,
-                    prob_a: dist_a[idx],
-                    prob_b: dist_b[idx],
-                    divergence_contribution: contrib,
-                }
+            .map(|(idx, contrib)| CategoryDivergence {
+                category: scheme_a.category_name(idx),
+                prob_a: dist_a[idx],
+                prob_b: dist_b[idx],
+                divergence_contribution: contrib,
             })
             .collect();
 
@@ -437,6 +1772,8 @@ impl CompressionDynamicsModel {
 
     /// Get historical potentials for a dyad
     pub fn get_dyad_history(&self, actor_a: &str, actor_b: &str) -> Vec<&ConflictPotential> {
+        let actor_a = self.resolve_actor(actor_a).unwrap_or(actor_a);
+        let actor_b = self.resolve_actor(actor_b).unwrap_or(actor_b);
         self.potentials
             .iter()
             .filter(|p| {
@@ -450,10 +1787,21 @@ impl CompressionDynamicsModel {
     pub fn clear_history(&mut self) {
         self.history.clear();
         self.potentials.clear();
+        self.baselines.clear();
+        self.dyad_events.clear();
+        self.directed_grievances.clear();
+        self.cohesion_history.clear();
+        self.risk_index_history.clear();
+        self.rejected_observations.clear();
         for g in self.grievances.values_mut() {
             g.error_history.clear();
             g.cumulative_error = 0.0;
             g.window_error = 0.0;
+            g.surprisal_history.clear();
+            g.cumulative_surprisal = 0.0;
+            g.window_surprisal = 0.0;
+            g.decayed_error = 0.0;
+            g.decayed_at_ms = None;
         }
     }
 
@@ -463,8 +1811,21 @@ impl CompressionDynamicsModel {
     }
 
     /// Deserialize model state from JSON
+    ///
+    /// Schemes are re-attached to the model's shared registry, if any.
     pub fn from_json(json: &str) -> Result<Self> {
-        serde_json::from_str(json).map_err(|e| DivergenceError::SerializationError(e.to_string()))
+        let mut model: Self = serde_json::from_str(json)
+            .map_err(|e| DivergenceError::SerializationError(e.to_string()))?;
+        model.attach_registry(model.registry.clone());
+        Ok(model)
+    }
+
+    /// Point the model and all its schemes at a registry
+    pub(crate) fn attach_registry(&mut self, registry: Option<Arc<CategoryRegistry>>) {
+        for scheme in self.schemes.values_mut() {
+            scheme.set_registry(registry.clone());
+        }
+        self.registry = registry;
     }
 
     /// Export current state as a summary
@@ -479,6 +1840,82 @@ impl CompressionDynamicsModel {
     }
 }
 
+/// Apply an observation to one actor's scheme and grievance
+///
+/// Returns the history entry to record. Shared by the single-owner model and
+/// the sharded [`SharedModel`](crate::shared::SharedModel).
+pub(crate) fn apply_observation(
+    config: &ModelConfig,
+    scheme: &mut CompressionScheme,
+    grievance: Option<&mut Grievance>,
+    rival: Option<&CompressionScheme>,
+    observation: &[f64],
+    timestamp_ms: Option<i64>,
+    category_version: u64,
+) -> Result<SchemeHistoryEntry> {
+    let observation = config
+        .projection
+        .project(observation, scheme.n_categories())
+        .with_actor(&scheme.actor_id)?;
+    let observation = config
+        .normalization
+        .normalize(&observation)
+        .with_actor(&scheme.actor_id)?;
+    let observation = observation.as_ref();
+    let weight = match &config.outlier_filter {
+        Some(filter) => filter
+            .weight(scheme, observation)
+            .with_actor(&scheme.actor_id)?,
+        None => 1.0,
+    };
+    let surprisal = scheme.surprisal(observation).with_actor(&scheme.actor_id)?;
+    let prediction_error = config
+        .grievance
+        .prediction_error(scheme, observation)
+        .with_actor(&scheme.actor_id)?;
+
+    // Update scheme
+    let observation =
+        feedback_observation(config, scheme, rival, observation).with_actor(&scheme.actor_id)?;
+    let observation = observation.as_ref();
+    let learning_rate = weight
+        * biased_learning_rate(config, scheme, rival, observation).with_actor(&scheme.actor_id)?;
+    scheme
+        .update(observation, learning_rate)
+        .with_actor(&scheme.actor_id)?;
+
+    if let Some(ts) = timestamp_ms {
+        scheme.timestamp_ms = Some(ts);
+    }
+
+    // Update grievance (prediction error)
+    let timestamp_ms = timestamp_ms.unwrap_or_else(now_ms);
+    if let Some(g) = grievance {
+        if let Some(half_life) = config.grievance_half_life_ms {
+            g.decay_to(timestamp_ms, half_life);
+        }
+        g.update(prediction_error, config.grievance_window);
+        g.update_surprisal(surprisal, config.grievance_window);
+    }
+
+    Ok(SchemeHistoryEntry {
+        timestamp_ms,
+        actor_id: scheme.actor_id.clone(),
+        scheme: scheme.clone(),
+        category_version,
+        prediction_error,
+        attribution: None,
+    })
+}
+
+/// Current wall-clock time in Unix milliseconds
+pub(crate) fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
 /// Model state summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelSummary {
@@ -553,6 +1990,104 @@ mod tests {
         assert!(pred.current_phi > 0.0);
     }
 
+    #[test]
+    fn test_escalation_contributions() {
+        let mut model = CompressionDynamicsModel::new(5);
+        model.register_actor("A", Some(vec![0.8, 0.1, 0.05, 0.03, 0.02]), None);
+        model.register_actor("B", Some(vec![0.1, 0.1, 0.3, 0.3, 0.2]), None);
+        let pred = model.predict_escalation("A", "B", 0.5, 0.0).unwrap();
+
+        let contributions = pred.contributions;
+        let logistic = 1.0 / (1.0 + (-contributions.total()).exp());
+        assert!((pred.probability - logistic).abs() < 1e-12);
+        assert!((contributions.communication + 0.3 * 0.5).abs() < 1e-12);
+        assert_eq!(contributions.ranked()[0].0, "phi");
+    }
+
+    #[test]
+    fn test_directed_grievance() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.3, 0.6]), None);
+
+        model
+            .update_scheme_attributed("A", &[0.0, 0.0, 1.0], Some(0), "B")
+            .unwrap();
+        model.update_scheme("A", &[0.6, 0.3, 0.1], Some(1)).unwrap();
+
+        let directed = model.directed_grievance("A", "B").unwrap();
+        assert_eq!(directed.error_history.len(), 1);
+        assert!(directed.window_error > model.grievances["A"].window_error);
+        assert!(model.directed_grievance("B", "A").is_none());
+
+        let pred = model.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert!(pred.grievance_asymmetry() > 0.0);
+        let reversed = model.peek_escalation("B", "A", 0.5, 0.0).unwrap();
+        assert_eq!(reversed.grievance_b_to_a, pred.grievance_a_to_b);
+
+        // Unknown sources are registered; self-attribution is rejected
+        model
+            .update_scheme_attributed("B", &[0.2, 0.3, 0.5], None, "C")
+            .unwrap();
+        assert!(model.get_scheme("C").is_some());
+        assert!(model
+            .update_scheme_attributed("A", &[0.2, 0.3, 0.5], None, "A")
+            .is_err());
+    }
+
+    #[test]
+    fn test_surprisal_tracking() {
+        let mut model = CompressionDynamicsModel::new(2);
+        model.register_actor("A", Some(vec![0.75, 0.25]), None);
+        let scheme = model.get_scheme("A").unwrap();
+        assert!((scheme.surprisal(&[0.0, 3.0]).unwrap() - 2.0).abs() < 1e-6);
+        assert!((scheme.surprisal(&[1.0, 0.0]).unwrap() - 0.75f64.log2().abs()).abs() < 1e-6);
+
+        // Scored against the scheme before each update
+        model.update_scheme("A", &[0.0, 1.0], Some(0)).unwrap();
+        model.update_scheme("A", &[1.0, 0.0], Some(1)).unwrap();
+        let g = &model.grievances["A"];
+        assert_eq!(g.surprisal_history.len(), 2);
+        assert!((g.surprisal_history[0] - 2.0).abs() < 1e-6);
+        assert!((g.cumulative_surprisal - g.surprisal_history.iter().sum::<f64>()).abs() < 1e-12);
+        assert!((g.window_surprisal - g.cumulative_surprisal / 2.0).abs() < 1e-12);
+
+        model.clear_history();
+        assert_eq!(model.grievances["A"].cumulative_surprisal, 0.0);
+    }
+
+    #[test]
+    fn test_grievance_drivers() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.update_scheme("A", &[0.6, 0.3, 0.1], Some(0)).unwrap();
+        model
+            .update_scheme_with_attribution(
+                "A",
+                &[0.0, 0.0, 1.0],
+                Some(1000),
+                EventAttribution {
+                    event_id: Some("shelling".to_string()),
+                    geo: Some("UA".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        model
+            .update_scheme("A", &[0.5, 0.3, 0.2], Some(2000))
+            .unwrap();
+
+        let drivers = model.grievance_drivers(Some("A"), 0, 3000, 2);
+        assert_eq!(drivers.len(), 2);
+        assert_eq!(
+            drivers[0].attribution.as_ref().unwrap().event_id.as_deref(),
+            Some("shelling")
+        );
+        assert!(drivers[0].prediction_error > drivers[1].prediction_error);
+        assert_eq!(model.grievance_drivers(None, 0, 1000, 10).len(), 1);
+        assert!(model.directed_grievances.is_empty());
+    }
+
     #[test]
     fn test_alignment_path() {
         let mut model = CompressionDynamicsModel::new(5);
@@ -567,6 +2102,169 @@ mod tests {
         assert!(!path.recommendation.is_empty());
     }
 
+    #[test]
+    fn test_shared_registry() {
+        let registry = CategoryRegistry::from_names(["a", "b", "c"]).into_shared();
+        let mut model =
+            CompressionDynamicsModel::with_registry(ModelConfig::default(), Arc::clone(&registry));
+        assert_eq!(model.config().n_categories, 3);
+
+        model.register_actor("X", Some(vec![0.7, 0.2, 0.1]), None);
+        model.register_actor("Y", Some(vec![0.1, 0.2, 0.7]), None);
+        assert!(model.validate_registry().is_ok());
+
+        let path = model.find_alignment_path("X", "Y", 0.0).unwrap();
+        assert!(["a", "c"].contains(&path.diverging_categories[0].category.as_str()));
+
+        // A scheme with its own labels is rejected
+        let foreign = CompressionScheme::uniform("Z", 3);
+        assert!(matches!(
+            model.register_scheme(foreign),
+            Err(DivergenceError::RegistryMismatch(_))
+        ));
+
+        let shared = CompressionScheme::uniform("Z", 3)
+            .with_registry(registry)
+            .unwrap();
+        assert!(model.register_scheme(shared).is_ok());
+
+        let restored = CompressionDynamicsModel::from_json(&model.to_json().unwrap()).unwrap();
+        assert!(restored.validate_registry().is_ok());
+        assert_eq!(restored.get_scheme("X").unwrap().category_name(2), "c");
+    }
+
+    #[test]
+    fn test_category_growth_and_remap() {
+        let registry = CategoryRegistry::from_names(["a", "b"]).into_shared();
+        let mut model = CompressionDynamicsModel::with_registry(ModelConfig::default(), registry);
+        model.register_actor("X", Some(vec![0.6, 0.4]), None);
+        model.update_scheme("X", &[1.0, 0.0], Some(0)).unwrap();
+
+        assert_eq!(model.add_category("c").unwrap(), 2);
+        assert_eq!(model.add_category("c").unwrap(), 2);
+        assert_eq!(model.category_version(), 1);
+
+        let scheme = model.get_scheme("X").unwrap();
+        assert_eq!(scheme.n_categories(), 3);
+        assert!(scheme.distribution()[2] < 1e-6);
+        assert!((scheme.distribution().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(model.validate_registry().is_ok());
+
+        model.update_scheme("X", &[0.0, 0.0, 1.0], Some(1)).unwrap();
+
+        // Merge a+b, keep c
+        let mapping = CategoryMapping::new(3, vec!["ab".to_string(), "c".to_string()])
+            .map(0, 0)
+            .map(1, 0)
+            .map(2, 1);
+        model.remap_categories(&mapping).unwrap();
+
+        assert_eq!(model.config().n_categories, 2);
+        assert_eq!(model.category_version(), 2);
+        assert_eq!(model.get_scheme("X").unwrap().category_name(0), "ab");
+        assert!(model.validate_registry().is_ok());
+
+        let versions: Vec<u64> = model.history.iter().map(|h| h.category_version).collect();
+        assert_eq!(versions, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_observation_projection() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", None, None);
+        assert!(matches!(
+            model
+                .update_scheme("A", &[1.0, 0.0], None)
+                .unwrap_err()
+                .root(),
+            DivergenceError::DimensionMismatch { .. }
+        ));
+
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 3,
+            projection: ObservationProjection::TruncateOrPad,
+            ..Default::default()
+        });
+        model.register_actor("A", None, None);
+        model.update_scheme("A", &[1.0, 0.0], None).unwrap();
+        model
+            .update_scheme("A", &[1.0, 0.0, 0.0, 5.0], None)
+            .unwrap();
+        assert!(model.get_scheme("A").unwrap().distribution()[0] > 0.4);
+
+        let projection = ObservationProjection::IndexMap(vec![Some(2), None, Some(2)]);
+        let projected = projection.project(&[0.5, 0.3, 0.2], 3).unwrap();
+        assert_eq!(projected.as_ref(), &[0.0, 0.0, 0.7]);
+        assert!(projection.project(&[0.1; 4], 3).is_err());
+    }
+
+    #[test]
+    fn test_observation_normalization() {
+        let signed = [0.5, -0.25, 0.25];
+        let l1 = ObservationNormalization::AbsoluteL1
+            .normalize(&signed)
+            .unwrap();
+        assert_eq!(l1.as_ref(), &[0.5, 0.25, 0.25]);
+        let clipped = ObservationNormalization::ClipNegatives
+            .normalize(&signed)
+            .unwrap();
+        assert_eq!(clipped.as_ref(), &[2.0 / 3.0, 0.0, 1.0 / 3.0]);
+        let uniform = ObservationNormalization::ClipNegatives
+            .normalize(&[-1.0, -2.0])
+            .unwrap();
+        assert_eq!(uniform.as_ref(), &[0.5, 0.5]);
+
+        let sharp = ObservationNormalization::Softmax { temperature: 0.1 }
+            .normalize(&signed)
+            .unwrap();
+        let flat = ObservationNormalization::Softmax { temperature: 10.0 }
+            .normalize(&signed)
+            .unwrap();
+        assert!((sharp.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(sharp[0] > 0.9 && flat[0] < 0.35);
+        assert!(ObservationNormalization::Softmax { temperature: 0.0 }
+            .normalize(&signed)
+            .is_err());
+
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 3,
+            normalization: ObservationNormalization::ClipNegatives,
+            ..Default::default()
+        });
+        model.register_actor("A", None, None);
+        let scheme = model.update_scheme("A", &signed, Some(0)).unwrap();
+        assert!(scheme.distribution().iter().all(|&p| p > 0.0));
+        assert!(scheme.distribution()[1] < scheme.distribution()[2]);
+    }
+
+    #[test]
+    fn test_peek_does_not_record() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.7, 0.2, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.2, 0.7]), None);
+
+        let view: &CompressionDynamicsModel = &model;
+        let peeked = view.peek_potential("A", "B").unwrap();
+        view.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert_eq!(view.peek_all_potentials().len(), 1);
+        assert_eq!(view.summary().n_potentials, 0);
+
+        let recorded = model.compute_conflict_potential("A", "B").unwrap();
+        assert_eq!(peeked.phi, recorded.phi);
+        assert_eq!(model.summary().n_potentials, 1);
+
+        // dΦ/dt from peek matches the recording path
+        model.update_scheme("A", &[0.0, 0.0, 1.0], None).unwrap();
+        let peek = model.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        let pred = model.predict_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert!(peek.d_phi_dt < 0.0);
+        assert_eq!(peek.d_phi_dt, pred.d_phi_dt);
+
+        model.config.record_potentials = false;
+        model.compute_conflict_potential("A", "B").unwrap();
+        assert_eq!(model.summary().n_potentials, 2);
+    }
+
     #[test]
     fn test_serialization() {
         let mut model = CompressionDynamicsModel::new(5);
@@ -577,4 +2275,211 @@ mod tests {
 
         assert_eq!(model.actors().len(), restored.actors().len());
     }
+
+    #[test]
+    fn test_phi_z_against_dyad_history() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.5, 0.3, 0.2]), None);
+        model.register_actor("B", Some(vec![0.3, 0.4, 0.3]), None);
+        assert!(model.peek_potential("A", "B").unwrap().phi_z.is_none());
+
+        // Small oscillations build the baseline
+        for i in 0..10 {
+            let obs = if i % 2 == 0 {
+                [0.32, 0.38, 0.3]
+            } else {
+                [0.28, 0.42, 0.3]
+            };
+            model.update_scheme("B", &obs, Some(i)).unwrap();
+            model.compute_conflict_potential("A", "B").unwrap();
+        }
+        let baseline = model.dyad_baseline("B", "A").unwrap();
+        assert_eq!(baseline.len(), 10);
+
+        // A sharp divergence is many deviations above this dyad's norm
+        model
+            .update_scheme("B", &[0.0, 0.1, 0.9], Some(10))
+            .unwrap();
+        let spike = model.peek_potential("A", "B").unwrap();
+        assert!(spike.phi_z.unwrap() > 3.0);
+
+        let restored = CompressionDynamicsModel::from_json(&model.to_json().unwrap()).unwrap();
+        assert_eq!(
+            restored.peek_potential("A", "B").unwrap().phi_z,
+            spike.phi_z
+        );
+
+        model.clear_history();
+        assert!(model.dyad_baseline("A", "B").is_none());
+    }
+
+    #[test]
+    fn test_seasonal_adjustment_of_phi() {
+        let config = ModelConfig {
+            n_categories: 3,
+            learning_rate: 1.0,
+            seasonality: Some(SeasonalConfig::default()),
+            ..Default::default()
+        };
+        let mut model = CompressionDynamicsModel::with_config(config);
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("B", Some(vec![0.6, 0.3, 0.1]), None);
+
+        // B swings away every Sunday, with a little weekday noise
+        let monday = 1_704_067_200_000_i64;
+        let day = 86_400_000_i64;
+        let observe = |model: &mut CompressionDynamicsModel, d: i64| {
+            let obs = if d % 7 == 6 {
+                [0.2, 0.3, 0.5]
+            } else if d % 2 == 0 {
+                [0.58, 0.31, 0.11]
+            } else {
+                [0.62, 0.29, 0.09]
+            };
+            model
+                .update_scheme("B", &obs, Some(monday + d * day))
+                .unwrap();
+        };
+        for d in 0..56 {
+            observe(&mut model, d);
+            model.compute_conflict_potential("A", "B").unwrap();
+        }
+
+        // The next Sunday's spike is expected, so its adjusted Φ stays low
+        observe(&mut model, 62);
+        let sunday = model.peek_potential("A", "B").unwrap();
+        let adjusted = sunday.phi_adjusted.unwrap();
+        assert!(adjusted < sunday.phi * 0.5);
+        assert!(sunday.phi_z.unwrap() < 3.0);
+    }
+
+    #[test]
+    fn test_covariates_enter_escalation() {
+        let config = ModelConfig {
+            n_categories: 3,
+            learning_rate: 1.0,
+            ..Default::default()
+        };
+        let mut model = CompressionDynamicsModel::with_config(config);
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("B", Some(vec![0.6, 0.3, 0.1]), None);
+        assert!(model.set_covariates("A", "B", 0, &[1.0]).is_err());
+
+        model.define_covariates(vec!["troop_index".to_string()], 0.0);
+        assert!(model.set_covariates("A", "B", 0, &[1.0, 2.0]).is_err());
+        assert!(model.set_covariates("A", "C", 0, &[1.0]).is_err());
+
+        // Troop movements track Φ exactly: Φ = 2·troops
+        for step in 0..10 {
+            let shift = 0.05 * step as f64;
+            model
+                .update_scheme("B", &[0.6 - shift, 0.3, 0.1 + shift], None)
+                .unwrap();
+            let phi = model.peek_potential("A", "B").unwrap().phi;
+            model.set_covariates("B", "A", step, &[phi / 2.0]).unwrap();
+            model.compute_conflict_potential("A", "B").unwrap();
+        }
+        let weights = model.fit_covariates().unwrap();
+        assert!(weights[0].abs() < 1e-6);
+        assert!((weights[1] - 2.0).abs() < 1e-6);
+
+        model.set_covariates("A", "B", 10, &[0.0]).unwrap();
+        let calm = model.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert_eq!(calm.covariate_contribution, 0.0);
+
+        model.set_covariates("A", "B", 11, &[1.0]).unwrap();
+        let mobilized = model.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert!((mobilized.covariate_contribution - 2.0).abs() < 1e-6);
+        assert!(mobilized.probability > calm.probability);
+    }
+
+    #[test]
+    fn test_archetype_priors_yield_to_observations() {
+        let mut model = CompressionDynamicsModel::new(4);
+        model
+            .register_actor_with_prior("weak", &Archetype::Hawkish, 1.0)
+            .unwrap();
+        model
+            .register_actor_with_prior("strong", &Archetype::Hawkish, 50.0)
+            .unwrap();
+        assert!(model
+            .register_actor_with_prior("X", &Archetype::Custom("pacifist".to_string()), 1.0)
+            .is_err());
+        model
+            .define_archetype("pacifist", vec![0.7, 0.2, 0.1, 0.0])
+            .unwrap();
+        let pacifist = model
+            .register_actor_with_prior("X", &Archetype::Custom("pacifist".to_string()), 1.0)
+            .unwrap();
+        assert!(pacifist.distribution()[0] > 0.6);
+
+        let dovish = [0.7, 0.2, 0.1, 0.0];
+        for _ in 0..5 {
+            model.update_scheme("weak", &dovish, None).unwrap();
+            model.update_scheme("strong", &dovish, None).unwrap();
+        }
+        let weak = model.get_scheme("weak").unwrap();
+        let strong = model.get_scheme("strong").unwrap();
+        assert_eq!(weak.observation_count(), 5);
+        assert!(weak.distribution()[0] > strong.distribution()[0]);
+        assert!(weak.distribution()[0] > weak.distribution()[3]);
+        assert!(strong.distribution()[3] > weak.distribution()[3]);
+    }
+
+    #[test]
+    fn test_event_clustering_raises_escalation() {
+        let config = ModelConfig {
+            n_categories: 3,
+            hawkes: Some(HawkesConfig::default()),
+            ..Default::default()
+        };
+        let mut model = CompressionDynamicsModel::with_config(config);
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("B", Some(vec![0.3, 0.3, 0.4]), None);
+
+        let quiet = model.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert_eq!(quiet.event_excitation, 0.0);
+        assert!(model.record_dyad_event("A", "C", 0).is_err());
+
+        for k in 0..3 {
+            model.record_dyad_event("B", "A", (k - 2) * 60_000).unwrap();
+        }
+        let tense = model.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert!(tense.event_excitation > 0.0);
+        assert!(tense.event_intensity > 0.0);
+        assert!(tense.probability > quiet.probability);
+        assert_eq!(model.dyad_events("B", "A").unwrap().events().len(), 3);
+    }
+
+    #[test]
+    fn test_deterministic_order() {
+        let names = ["ZAF", "USA", "BRA", "CHN", "IND", "RUS", "GBR", "FRA"];
+        let mut model = CompressionDynamicsModel::new(3);
+        for name in names {
+            model.register_actor(name, None, None);
+        }
+        // Re-registering keeps the original position
+        model.register_actor("USA", Some(vec![0.5, 0.3, 0.2]), None);
+
+        assert_eq!(model.actors(), names.to_vec());
+
+        let pairs: Vec<(String, String)> = model
+            .peek_all_potentials()
+            .into_iter()
+            .map(|p| (p.actor_a, p.actor_b))
+            .collect();
+        assert_eq!(pairs[0], ("ZAF".to_string(), "USA".to_string()));
+        assert_eq!(pairs[1], ("ZAF".to_string(), "BRA".to_string()));
+
+        let json = model.to_json().unwrap();
+        let restored = CompressionDynamicsModel::from_json(&json).unwrap();
+        assert_eq!(restored.actors(), names.to_vec());
+        assert_eq!(restored.to_json().unwrap(), json);
+
+        let usa = model.actor_id("USA").unwrap();
+        assert_eq!(usa, ActorId(1));
+        assert_eq!(model.actor_name(usa), Some("USA"));
+        assert_eq!(ActorId::dyad(usa, ActorId(0)), (ActorId(0), usa));
+        assert!(model.actor_id("PRK").is_none());
+    }
 }
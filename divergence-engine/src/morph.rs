@@ -0,0 +1,161 @@
+//! Scheme interpolation and morphing.
+//!
+//! Intermediate worldviews between two schemes, for reconciliation
+//! planning visuals and for synthesizing test fixtures. Three paths are
+//! available:
+//!
+//! - `Linear`: the mixture `(1 - t)·P + t·Q`. Moves mass directly, so
+//!   Φ to the target falls fastest near the end of the path.
+//! - `Geometric`: the normalized `P^(1-t) · Q^t`. The exponential-family
+//!   path; categories either side rules out stay ruled out.
+//! - `FisherRao`: the geodesic of the Fisher information metric, found by
+//!   slerping `√P` and `√Q` on the unit sphere. Points are evenly spaced in
+//!   Fisher-Rao distance, so each step is an equally large change of view.
+
+use crate::error::{DivergenceError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Path followed between two distributions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Interpolation {
+    /// Mixture (1 - t)·P + t·Q
+    #[default]
+    Linear,
+    /// Normalized P^(1-t) · Q^t
+    Geometric,
+    /// Fisher-Rao geodesic (slerp of √P and √Q)
+    FisherRao,
+}
+
+/// Distribution at position `t ∈ [0, 1]` on the path from `p` to `q`
+///
+/// Inputs are expected to be normalized; the output is normalized.
+pub fn interpolate(p: &[f64], q: &[f64], t: f64, method: Interpolation) -> Result<Vec<f64>> {
+    if p.len() != q.len() {
+        return Err(DivergenceError::DimensionMismatch {
+            expected: p.len(),
+            got: q.len(),
+        });
+    }
+    if !(0.0..=1.0).contains(&t) {
+        return Err(DivergenceError::ConfigError(format!(
+            "Interpolation position must be in [0, 1], got {}",
+            t
+        )));
+    }
+
+    let mut out: Vec<f64> = match method {
+        Interpolation::Linear => p
+            .iter()
+            .zip(q.iter())
+            .map(|(&pi, &qi)| (1.0 - t) * pi + t * qi)
+            .collect(),
+        Interpolation::Geometric => {
+            let out: Vec<f64> = p
+                .iter()
+                .zip(q.iter())
+                .map(|(&pi, &qi)| pi.max(0.0).powf(1.0 - t) * qi.max(0.0).powf(t))
+                .collect();
+            if out.iter().sum::<f64>() <= 0.0 {
+                // Disjoint supports: no geometric mean exists
+                return Err(DivergenceError::NumericalError(
+                    "Geometric interpolation of disjoint distributions".to_string(),
+                ));
+            }
+            out
+        }
+        Interpolation::FisherRao => {
+            let cos_theta: f64 = p
+                .iter()
+                .zip(q.iter())
+                .map(|(&pi, &qi)| (pi.max(0.0) * qi.max(0.0)).sqrt())
+                .sum::<f64>()
+                .clamp(-1.0, 1.0);
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            let (wp, wq) = if sin_theta < 1e-12 {
+                // Coincident points: the geodesic degenerates to a line
+                (1.0 - t, t)
+            } else {
+                (
+                    ((1.0 - t) * theta).sin() / sin_theta,
+                    (t * theta).sin() / sin_theta,
+                )
+            };
+            p.iter()
+                .zip(q.iter())
+                .map(|(&pi, &qi)| {
+                    let s = wp * pi.max(0.0).sqrt() + wq * qi.max(0.0).sqrt();
+                    s * s
+                })
+                .collect()
+        }
+    };
+
+    let sum: f64 = out.iter().sum();
+    if sum > 0.0 {
+        for x in out.iter_mut() {
+            *x /= sum;
+        }
+    }
+    Ok(out)
+}
+
+/// `n_steps + 1` evenly spaced points from `p` (t = 0) to `q` (t = 1)
+pub fn morph_sequence(
+    p: &[f64],
+    q: &[f64],
+    n_steps: usize,
+    method: Interpolation,
+) -> Result<Vec<Vec<f64>>> {
+    if n_steps == 0 {
+        return Err(DivergenceError::ConfigError(
+            "Morph sequence needs at least one step".to_string(),
+        ));
+    }
+    (0..=n_steps)
+        .map(|k| interpolate(p, q, k as f64 / n_steps as f64, method))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::divergence::hellinger_distance;
+
+    const P: [f64; 3] = [0.7, 0.2, 0.1];
+    const Q: [f64; 3] = [0.1, 0.3, 0.6];
+
+    #[test]
+    fn test_endpoints_and_normalization() {
+        for method in [
+            Interpolation::Linear,
+            Interpolation::Geometric,
+            Interpolation::FisherRao,
+        ] {
+            let start = interpolate(&P, &Q, 0.0, method).unwrap();
+            let end = interpolate(&P, &Q, 1.0, method).unwrap();
+            let mid = interpolate(&P, &Q, 0.5, method).unwrap();
+            for i in 0..3 {
+                assert!((start[i] - P[i]).abs() < 1e-12);
+                assert!((end[i] - Q[i]).abs() < 1e-12);
+            }
+            assert!((mid.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+        assert!(interpolate(&P, &Q, 1.5, Interpolation::Linear).is_err());
+        assert!(interpolate(&P, &[0.5, 0.5], 0.5, Interpolation::Linear).is_err());
+        assert!(interpolate(&[1.0, 0.0], &[0.0, 1.0], 0.5, Interpolation::Geometric).is_err());
+    }
+
+    #[test]
+    fn test_fisher_rao_steps_are_even() {
+        // Hellinger distance is a monotone function of the Fisher-Rao angle,
+        // so equal angular steps give equal distances from either end
+        let path = morph_sequence(&P, &Q, 4, Interpolation::FisherRao).unwrap();
+        assert_eq!(path.len(), 5);
+        let first = hellinger_distance(&path[0], &path[1]).unwrap();
+        let last = hellinger_distance(&path[3], &path[4]).unwrap();
+        assert!((first - last).abs() < 1e-9);
+        assert!(morph_sequence(&P, &Q, 0, Interpolation::Linear).is_err());
+    }
+}
@@ -0,0 +1,348 @@
+//! MQTT alert sink.
+//!
+//! [`MqttAlertSink`] publishes [`DivergenceAlert`] JSON to an MQTT broker,
+//! for edge installs where a monitoring station has a small broker (or a
+//! constrained uplink) rather than a log pipeline.
+//!
+//! Topics come from a template, so subscribers can filter on risk or dyad
+//! with ordinary MQTT wildcards:
+//!
+//! ```text
+//! template: stations/north/alerts/{risk}/{actor_a}/{actor_b}
+//! alert:    RUS → UKR, CRITICAL
+//! topic:    stations/north/alerts/critical/RUS/UKR
+//! subscribe stations/+/alerts/critical/#   every critical alert
+//! ```
+//!
+//! A [`LastWillConfig`] registers a retained will with the broker, published
+//! if the station drops off without disconnecting; its `online_payload` is
+//! published to the same topic on every (re)connect, giving subscribers a
+//! station status topic.
+//!
+//! With [`MqttConfig::with_cloudevents`] alerts are published as
+//! CloudEvents envelopes (structured mode, the only one MQTT 3.1.1 allows).
+//!
+//! The connection runs on a background task that reconnects on failure.
+//! With QoS 1 or 2 the client retransmits unconfirmed alerts after a
+//! reconnect; keep `clean_session` off so the broker keeps the session too.
+
+use crate::cloudevents::{alert_payload, CloudEventsConfig};
+use crate::error::{DivergenceError, Result};
+use crate::streaming::{AlertSink, DivergenceAlert};
+use async_trait::async_trait;
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// MQTT delivery guarantee
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MqttQos {
+    /// Fire and forget (QoS 0)
+    AtMostOnce,
+    /// Acknowledged, possibly duplicated (QoS 1)
+    #[default]
+    AtLeastOnce,
+    /// Four-way handshake, no duplicates (QoS 2)
+    ExactlyOnce,
+}
+
+impl From<MqttQos> for QoS {
+    fn from(qos: MqttQos) -> Self {
+        match qos {
+            MqttQos::AtMostOnce => QoS::AtMostOnce,
+            MqttQos::AtLeastOnce => QoS::AtLeastOnce,
+            MqttQos::ExactlyOnce => QoS::ExactlyOnce,
+        }
+    }
+}
+
+/// Will published by the broker when the sink drops off uncleanly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastWillConfig {
+    pub topic: String,
+    /// Published by the broker on an unclean disconnect
+    pub offline_payload: String,
+    /// Published by the sink on each connect (`None`: nothing)
+    #[serde(default)]
+    pub online_payload: Option<String>,
+    pub qos: MqttQos,
+    pub retain: bool,
+}
+
+impl LastWillConfig {
+    /// Retained `online`/`offline` status on `topic`
+    pub fn status(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            offline_payload: "offline".to_string(),
+            online_payload: Some("online".to_string()),
+            qos: MqttQos::AtLeastOnce,
+            retain: true,
+        }
+    }
+}
+
+/// Settings for an [`MqttAlertSink`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    /// Topic template; `{risk}`, `{actor_a}` and `{actor_b}` are substituted
+    pub topic: String,
+    pub qos: MqttQos,
+    /// Retain the last alert per topic on the broker
+    pub retain: bool,
+    pub keep_alive_secs: u64,
+    /// Start a fresh broker session on connect
+    pub clean_session: bool,
+    /// Delay before reconnecting after a connection error
+    pub reconnect_delay_ms: u64,
+    #[serde(default)]
+    pub credentials: Option<(String, String)>,
+    #[serde(default)]
+    pub last_will: Option<LastWillConfig>,
+    /// Publish CloudEvents envelopes instead of bare alerts
+    #[serde(default)]
+    pub cloudevents: Option<CloudEventsConfig>,
+}
+
+impl MqttConfig {
+    pub fn new(host: impl Into<String>, port: u16, client_id: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            client_id: client_id.into(),
+            topic: "divergence/alerts/{risk}".to_string(),
+            qos: MqttQos::AtLeastOnce,
+            retain: false,
+            keep_alive_secs: 30,
+            clean_session: false,
+            reconnect_delay_ms: 1000,
+            credentials: None,
+            last_will: None,
+            cloudevents: None,
+        }
+    }
+
+    /// Publish to a topic template
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topic = topic.into();
+        self
+    }
+
+    /// Publish with a delivery guarantee
+    pub fn with_qos(mut self, qos: MqttQos) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Register a last will
+    pub fn with_last_will(mut self, will: LastWillConfig) -> Self {
+        self.last_will = Some(will);
+        self
+    }
+
+    /// Wrap alerts in CloudEvents envelopes
+    pub fn with_cloudevents(mut self, cloudevents: CloudEventsConfig) -> Self {
+        self.cloudevents = Some(cloudevents);
+        self
+    }
+
+    /// Topic for an alert
+    pub fn topic_for(&self, alert: &DivergenceAlert) -> String {
+        self.topic
+            .replace("{risk}", &alert.risk_level.as_str().to_lowercase())
+            .replace("{actor_a}", &topic_level(&alert.actor_a))
+            .replace("{actor_b}", &topic_level(&alert.actor_b))
+    }
+
+    fn options(&self) -> MqttOptions {
+        let mut options = MqttOptions::new(&self.client_id, &self.host, self.port);
+        options
+            .set_keep_alive(Duration::from_secs(self.keep_alive_secs.max(5)))
+            .set_clean_session(self.clean_session);
+        if let Some((user, password)) = &self.credentials {
+            options.set_credentials(user, password);
+        }
+        if let Some(will) = &self.last_will {
+            options.set_last_will(LastWill::new(
+                &will.topic,
+                will.offline_payload.as_bytes(),
+                will.qos.into(),
+                will.retain,
+            ));
+        }
+        options
+    }
+}
+
+/// Actor ID made safe for a single topic level
+fn topic_level(id: &str) -> String {
+    id.replace(['/', '+', '#'], "_")
+}
+
+/// Alert sink publishing JSON alerts over MQTT
+pub struct MqttAlertSink {
+    client: AsyncClient,
+    config: MqttConfig,
+    connected: Arc<AtomicBool>,
+    event_loop: JoinHandle<()>,
+}
+
+impl MqttAlertSink {
+    /// Start the connection on a background task
+    ///
+    /// Returns immediately; alerts sent before the broker is reached are
+    /// queued, up to `capacity` requests.
+    pub fn new(config: MqttConfig, capacity: usize) -> Self {
+        let (client, mut event_loop) = AsyncClient::new(config.options(), capacity.max(1));
+        let connected = Arc::new(AtomicBool::new(false));
+
+        let status = connected.clone();
+        let birth_client = client.clone();
+        let will = config.last_will.clone();
+        let reconnect_delay = Duration::from_millis(config.reconnect_delay_ms);
+        let event_loop = tokio::spawn(async move {
+            loop {
+                match event_loop.poll().await {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        status.store(true, Ordering::Relaxed);
+                        if let Some(will) = &will {
+                            if let Some(online) = &will.online_payload {
+                                // Best effort: a full queue only delays the status
+                                let _ = birth_client.try_publish(
+                                    &will.topic,
+                                    will.qos.into(),
+                                    will.retain,
+                                    online.as_bytes(),
+                                );
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(rumqttc::ConnectionError::RequestsDone) => break,
+                    Err(_) => {
+                        status.store(false, Ordering::Relaxed);
+                        tokio::time::sleep(reconnect_delay).await;
+                    }
+                }
+            }
+        });
+
+        Self {
+            client,
+            config,
+            connected,
+            event_loop,
+        }
+    }
+
+    /// Settings
+    pub fn config(&self) -> &MqttConfig {
+        &self.config
+    }
+
+    /// Whether the broker connection is currently up
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Disconnect cleanly, so the broker does not publish the will
+    pub async fn disconnect(&self) -> Result<()> {
+        self.client
+            .disconnect()
+            .await
+            .map_err(|e| DivergenceError::ConfigError(format!("MQTT disconnect failed: {}", e)))
+    }
+}
+
+impl Drop for MqttAlertSink {
+    fn drop(&mut self) {
+        self.event_loop.abort();
+    }
+}
+
+#[async_trait]
+impl AlertSink for MqttAlertSink {
+    async fn send(&mut self, alert: DivergenceAlert) -> Result<()> {
+        let payload = alert_payload(&alert, self.config.cloudevents.as_ref())?;
+        let topic = self.config.topic_for(&alert);
+        self.client
+            .publish(
+                topic.as_str(),
+                self.config.qos.into(),
+                self.config.retain,
+                payload,
+            )
+            .await
+            .map_err(|e| {
+                DivergenceError::ConfigError(format!("MQTT publish to {} failed: {}", topic, e))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::RiskLevel;
+
+    fn alert(actor_a: &str, actor_b: &str, risk_level: RiskLevel) -> DivergenceAlert {
+        DivergenceAlert {
+            alert_id: "a1".to_string(),
+            actor_a: actor_a.to_string(),
+            actor_b: actor_b.to_string(),
+            phi: 2.5,
+            js: 0.4,
+            phi_z: None,
+            phi_adjusted: None,
+            p_value: None,
+            robustness: None,
+            d_phi_dt: 0.1,
+            risk_level,
+            escalation_probability: 0.6,
+            grievance_a_to_b: 0.0,
+            grievance_b_to_a: 0.0,
+            timestamp_ms: 0,
+            reason: "test".to_string(),
+            latency: None,
+        }
+    }
+
+    #[test]
+    fn test_topic_template() {
+        let config = MqttConfig::new("localhost", 1883, "station-1")
+            .with_topic("stations/north/{risk}/{actor_a}/{actor_b}");
+        assert_eq!(
+            config.topic_for(&alert("RUS", "UKR", RiskLevel::Critical)),
+            "stations/north/critical/RUS/UKR"
+        );
+        // Separators and wildcards in IDs cannot change the topic structure
+        assert_eq!(
+            config.topic_for(&alert("a/b", "c+#", RiskLevel::Low)),
+            "stations/north/low/a_b/c__"
+        );
+
+        let config = config.with_last_will(LastWillConfig::status("stations/north/status"));
+        let will = config.options().last_will().unwrap();
+        assert_eq!(will.topic, "stations/north/status");
+        assert_eq!(&will.message[..], b"offline");
+        assert!(will.retain);
+    }
+
+    #[tokio::test]
+    async fn test_mqtt_sink_queues_while_disconnected() {
+        // Nothing listens on the port; alerts queue until a broker appears
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let config = MqttConfig::new("127.0.0.1", port, "station-1").with_qos(MqttQos::ExactlyOnce);
+        let mut sink = MqttAlertSink::new(config, 16);
+        sink.send(alert("A", "B", RiskLevel::High)).await.unwrap();
+        assert!(!sink.is_connected());
+    }
+}
@@ -0,0 +1,340 @@
+//! NATS JetStream event source and alert sink.
+//!
+//! [`NatsEventSource`] pulls [`StreamEvent`] JSON (one event or an array
+//! per message) through a durable pull consumer; [`NatsAlertSink`]
+//! publishes [`DivergenceAlert`] JSON to a subject captured by a stream.
+//!
+//! ## Delivery
+//!
+//! ```text
+//! publish ──Nats-Msg-Id──▶ stream ──durable consumer──▶ receive
+//!   (deduplicated within the         (redelivered until acknowledged,
+//!    stream's duplicate window)        up to max_deliver times)
+//! ```
+//!
+//! Messages are acknowledged explicitly and only once their events are
+//! acknowledged, with a double ack that waits for the server to confirm,
+//! so a confirmed event is not redelivered. An event processed but not
+//! confirmed (crash, ack timeout) is delivered again; the processor's
+//! event-ID deduplication absorbs the repeat. Alerts are published with
+//! their alert ID as `Nats-Msg-Id`, so a retried publish is stored once.
+//!
+//! Malformed messages are terminated rather than redelivered.
+//!
+//! With [`NatsAlertSink::with_cloudevents`] alerts are published as
+//! structured-mode CloudEvents, with the `Content-Type` header set.
+
+use crate::cloudevents::{alert_payload, CloudEventsConfig, CLOUDEVENTS_CONTENT_TYPE};
+use crate::error::{DivergenceError, Result};
+use crate::streaming::{decode_events, AlertSink, DivergenceAlert, EventSource, StreamEvent};
+use async_nats::jetstream::consumer::{pull, AckPolicy, PullConsumer};
+use async_nats::jetstream::context::Publish;
+use async_nats::jetstream::{self, AckKind};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Server connection and the stream to use
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsConfig {
+    /// Server address, e.g. `nats://localhost:4222`
+    pub url: String,
+    #[serde(default)]
+    pub token: Option<String>,
+    /// JetStream stream name
+    pub stream: String,
+    /// Subjects the stream captures, used when it has to be created
+    pub subjects: Vec<String>,
+}
+
+impl NatsConfig {
+    pub fn new(url: impl Into<String>, stream: impl Into<String>, subjects: Vec<String>) -> Self {
+        Self {
+            url: url.into(),
+            token: None,
+            stream: stream.into(),
+            subjects,
+        }
+    }
+
+    /// Authenticate with a token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Connect and look up the stream, creating it if missing
+    async fn open(&self) -> Result<(jetstream::Context, jetstream::stream::Stream)> {
+        let mut options = async_nats::ConnectOptions::new();
+        if let Some(token) = &self.token {
+            options = options.token(token.clone());
+        }
+        let client = options
+            .connect(self.url.as_str())
+            .await
+            .map_err(|e| nats_error("connect", &self.url, e))?;
+        let context = jetstream::new(client);
+        let stream = context
+            .get_or_create_stream(jetstream::stream::Config {
+                name: self.stream.clone(),
+                subjects: self.subjects.clone(),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| nats_error("stream lookup", &self.stream, e))?;
+        Ok((context, stream))
+    }
+}
+
+fn nats_error(call: &str, target: &str, error: impl std::fmt::Display) -> DivergenceError {
+    DivergenceError::ConfigError(format!("NATS {} for {} failed: {}", call, target, error))
+}
+
+/// Durable consumer settings for a [`NatsEventSource`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsConsumerConfig {
+    /// Durable name; the server keeps the consumer's position under it
+    pub durable_name: String,
+    /// Only deliver these subjects (empty: everything in the stream)
+    #[serde(default)]
+    pub filter_subject: String,
+    /// Most messages pulled per `receive`
+    pub batch_size: usize,
+    /// How long a pull waits for messages
+    pub fetch_wait_ms: u64,
+    /// Redelivery delay for unacknowledged messages
+    pub ack_wait_ms: u64,
+    /// Deliveries before a message is given up on
+    pub max_deliver: i64,
+    /// Consecutive failed calls before the source reports unhealthy
+    pub max_consecutive_errors: usize,
+}
+
+impl NatsConsumerConfig {
+    pub fn new(durable_name: impl Into<String>) -> Self {
+        Self {
+            durable_name: durable_name.into(),
+            filter_subject: String::new(),
+            batch_size: 100,
+            fetch_wait_ms: 1000,
+            ack_wait_ms: 30_000,
+            max_deliver: 5,
+            max_consecutive_errors: 10,
+        }
+    }
+}
+
+/// Event source pulling from a durable JetStream consumer
+pub struct NatsEventSource {
+    consumer: PullConsumer,
+    config: NatsConsumerConfig,
+    /// Messages awaiting acknowledgement, with their unacknowledged event IDs
+    pending: Vec<(jetstream::Message, Vec<String>)>,
+    consecutive_errors: usize,
+    malformed: u64,
+}
+
+impl NatsEventSource {
+    /// Connect and bind the durable consumer, creating it if missing
+    pub async fn connect(nats: NatsConfig, config: NatsConsumerConfig) -> Result<Self> {
+        let (_, stream) = nats.open().await?;
+        let consumer = stream
+            .get_or_create_consumer(
+                &config.durable_name,
+                pull::Config {
+                    durable_name: Some(config.durable_name.clone()),
+                    filter_subject: config.filter_subject.clone(),
+                    ack_policy: AckPolicy::Explicit,
+                    ack_wait: Duration::from_millis(config.ack_wait_ms),
+                    max_deliver: config.max_deliver,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| nats_error("consumer setup", &config.durable_name, e))?;
+        Ok(Self {
+            consumer,
+            config,
+            pending: Vec::new(),
+            consecutive_errors: 0,
+            malformed: 0,
+        })
+    }
+
+    /// Consumer settings
+    pub fn config(&self) -> &NatsConsumerConfig {
+        &self.config
+    }
+
+    /// Messages terminated because they did not parse as events
+    pub fn malformed_messages(&self) -> u64 {
+        self.malformed
+    }
+
+    /// Messages delivered but not yet acknowledged
+    pub fn pending_messages(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn failed(&mut self, call: &str, error: impl std::fmt::Display) -> Result<()> {
+        self.consecutive_errors += 1;
+        if self.consecutive_errors >= self.config.max_consecutive_errors {
+            return Err(nats_error(call, &self.config.durable_name, error));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventSource for NatsEventSource {
+    async fn receive(&mut self) -> Result<Vec<StreamEvent>> {
+        let batch = self
+            .consumer
+            .batch()
+            .max_messages(self.config.batch_size)
+            .expires(Duration::from_millis(self.config.fetch_wait_ms))
+            .messages()
+            .await;
+        let mut messages = match batch {
+            Ok(messages) => messages,
+            Err(e) => {
+                self.failed("fetch", e)?;
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut events = Vec::new();
+        while let Some(message) = messages.next().await {
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    self.failed("fetch", e)?;
+                    break;
+                }
+            };
+            match decode_events(&message.payload) {
+                Some(decoded) if !decoded.is_empty() => {
+                    let ids = decoded.iter().map(|e| e.event_id.clone()).collect();
+                    self.pending.push((message, ids));
+                    events.extend(decoded);
+                }
+                _ => {
+                    self.malformed += 1;
+                    if let Err(e) = message.ack_with(AckKind::Term).await {
+                        self.failed("term", e)?;
+                    }
+                }
+            }
+        }
+        self.consecutive_errors = 0;
+        Ok(events)
+    }
+
+    async fn acknowledge(&mut self, event_ids: &[String]) -> Result<()> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for id in event_ids {
+            *counts.entry(id.as_str()).or_default() += 1;
+        }
+        // Oldest deliveries first, so duplicate IDs settle in order
+        for (_, ids) in &mut self.pending {
+            ids.retain(|id| match counts.get_mut(id.as_str()) {
+                Some(n) if *n > 0 => {
+                    *n -= 1;
+                    false
+                }
+                _ => true,
+            });
+        }
+
+        let (done, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(_, ids)| ids.is_empty());
+        self.pending = pending;
+        for (message, _) in done {
+            // Unconfirmed messages are redelivered after ack_wait
+            if let Err(e) = message.double_ack().await {
+                self.failed("ack", e)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.consecutive_errors < self.config.max_consecutive_errors
+    }
+}
+
+/// Alert sink publishing JSON alerts to a JetStream subject
+pub struct NatsAlertSink {
+    context: jetstream::Context,
+    subject: String,
+    cloudevents: Option<CloudEventsConfig>,
+}
+
+impl NatsAlertSink {
+    /// Connect and ensure the stream capturing `subject` exists
+    pub async fn connect(nats: NatsConfig, subject: impl Into<String>) -> Result<Self> {
+        let (context, _) = nats.open().await?;
+        Ok(Self {
+            context,
+            subject: subject.into(),
+            cloudevents: None,
+        })
+    }
+
+    /// Wrap alerts in CloudEvents envelopes
+    pub fn with_cloudevents(mut self, cloudevents: CloudEventsConfig) -> Self {
+        self.cloudevents = Some(cloudevents);
+        self
+    }
+
+    /// Subject alerts are published to
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+}
+
+#[async_trait]
+impl AlertSink for NatsAlertSink {
+    async fn send(&mut self, alert: DivergenceAlert) -> Result<()> {
+        let payload = alert_payload(&alert, self.cloudevents.as_ref())?;
+        let mut publish = Publish::build()
+            .payload(payload.into())
+            .message_id(&alert.alert_id);
+        if self.cloudevents.is_some() {
+            publish = publish.header("Content-Type", CLOUDEVENTS_CONTENT_TYPE);
+        }
+        // Wait for the stream to store it
+        self.context
+            .send_publish(self.subject.clone(), publish)
+            .await
+            .map_err(|e| nats_error("publish", &self.subject, e))?
+            .await
+            .map_err(|e| nats_error("publish ack", &self.subject, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_nats_connect_failure() {
+        // Bind then drop, leaving a port nothing listens on
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("nats://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let nats = NatsConfig::new(url, "EVENTS", vec!["events.>".to_string()]).with_token("t");
+        let err = NatsEventSource::connect(nats.clone(), NatsConsumerConfig::new("engine"))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, DivergenceError::ConfigError(_)));
+        assert!(NatsAlertSink::connect(nats, "alerts").await.is_err());
+    }
+}
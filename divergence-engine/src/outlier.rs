@@ -0,0 +1,282 @@
+//! Outlier screening of observations before scheme updates.
+//!
+//! A single corrupted observation (a mis-coded batch, a duplicated feed)
+//! can yank a scheme far from where the evidence puts it. With
+//! `ModelConfig::outlier_filter` set, each normalized observation `o` is
+//! first measured against the actor's current scheme `p`:
+//!
+//! ```text
+//! JensenShannon   JS(o, p)                       bits, in [0, 1]
+//! ChiSquared      sqrt(Σ (o_i - p_i)² / p_i)     root of Pearson's χ²
+//! ```
+//!
+//! Observations within the threshold update normally. Beyond it, `Reject`
+//! fails the update with `OutlierRejected` and leaves scheme and grievance
+//! untouched; `Downweight` applies the update at `η · threshold / distance`.
+//! The model keeps a log of the most recent rejected observations, up to
+//! the filter's `log_capacity`.
+
+use crate::divergence::{jensen_shannon, EPSILON};
+use crate::error::{DivergenceError, Result};
+use crate::model::{now_ms, CompressionDynamicsModel, ModelConfig};
+use crate::scheme::CompressionScheme;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Distance of an observation from the scheme that is about to learn it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutlierMetric {
+    #[default]
+    JensenShannon,
+    /// Square root of Pearson's χ² of the observation against the scheme
+    ChiSquared,
+}
+
+impl OutlierMetric {
+    /// Distance of `observation` from `scheme`
+    pub fn distance(&self, scheme: &CompressionScheme, observation: &[f64]) -> Result<f64> {
+        let p = scheme.distribution();
+        match self {
+            OutlierMetric::JensenShannon => jensen_shannon(observation, p),
+            OutlierMetric::ChiSquared => {
+                if observation.len() != p.len() {
+                    return Err(DivergenceError::DimensionMismatch {
+                        expected: p.len(),
+                        got: observation.len(),
+                    });
+                }
+                Ok(observation
+                    .iter()
+                    .zip(p)
+                    .map(|(&o, &p)| (o - p).powi(2) / p.max(EPSILON))
+                    .sum::<f64>()
+                    .sqrt())
+            }
+        }
+    }
+}
+
+/// What happens to observations beyond the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutlierAction {
+    #[default]
+    Reject,
+    Downweight,
+}
+
+/// Robust-update settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OutlierFilter {
+    pub metric: OutlierMetric,
+    pub threshold: f64,
+    pub action: OutlierAction,
+    /// Rejected observations kept in the model's log, oldest dropped first
+    #[serde(default = "default_log_capacity")]
+    pub log_capacity: usize,
+}
+
+fn default_log_capacity() -> usize {
+    1000
+}
+
+impl OutlierFilter {
+    /// Reject observations farther than `threshold` (Jensen-Shannon)
+    pub fn reject_beyond(threshold: f64) -> Self {
+        Self {
+            metric: OutlierMetric::JensenShannon,
+            threshold,
+            action: OutlierAction::Reject,
+            log_capacity: default_log_capacity(),
+        }
+    }
+
+    /// Downweight observations farther than `threshold` (Jensen-Shannon)
+    pub fn downweight_beyond(threshold: f64) -> Self {
+        Self {
+            action: OutlierAction::Downweight,
+            ..Self::reject_beyond(threshold)
+        }
+    }
+
+    pub fn with_metric(mut self, metric: OutlierMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    pub fn with_log_capacity(mut self, capacity: usize) -> Self {
+        self.log_capacity = capacity;
+        self
+    }
+
+    /// Learning rate multiplier for an observation of `scheme`
+    ///
+    /// 1 within the threshold; `OutlierRejected` or `threshold / distance`
+    /// beyond it.
+    pub fn weight(&self, scheme: &CompressionScheme, observation: &[f64]) -> Result<f64> {
+        let distance = self.metric.distance(scheme, observation)?;
+        if distance <= self.threshold {
+            return Ok(1.0);
+        }
+        match self.action {
+            OutlierAction::Reject => Err(DivergenceError::OutlierRejected {
+                distance,
+                threshold: self.threshold,
+            }),
+            OutlierAction::Downweight => Ok(self.threshold.max(0.0) / distance),
+        }
+    }
+}
+
+/// Observation refused by the outlier filter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RejectedObservation {
+    pub timestamp_ms: i64,
+    pub actor_id: String,
+    /// Observation as submitted
+    pub observation: Vec<f64>,
+    pub distance: f64,
+}
+
+/// Append a failed update to a rejection log, if the filter refused it
+///
+/// The log keeps the filter's `log_capacity` most recent entries.
+pub(crate) fn log_rejection(
+    log: &mut VecDeque<RejectedObservation>,
+    config: &ModelConfig,
+    err: &DivergenceError,
+    actor_id: &str,
+    observation: &[f64],
+    timestamp_ms: Option<i64>,
+) {
+    let DivergenceError::OutlierRejected { distance, .. } = *err.root() else {
+        return;
+    };
+    log.push_back(RejectedObservation {
+        timestamp_ms: timestamp_ms.unwrap_or_else(now_ms),
+        actor_id: actor_id.to_string(),
+        observation: observation.to_vec(),
+        distance,
+    });
+    let capacity = config.outlier_filter.map_or(0, |f| f.log_capacity);
+    while log.len() > capacity {
+        log.pop_front();
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Most recent observations rejected by the outlier filter, oldest
+    /// first
+    pub fn rejected_observations(&self) -> &VecDeque<RejectedObservation> {
+        &self.rejected_observations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{EventAttribution, ModelConfig};
+    use crate::shared::SharedModel;
+
+    #[test]
+    fn test_outlier_filter() {
+        let model = |filter: OutlierFilter| {
+            let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+                n_categories: 2,
+                outlier_filter: Some(filter),
+                ..Default::default()
+            });
+            model.register_actor("A", Some(vec![0.8, 0.2]), None);
+            model
+        };
+
+        let mut rejecting = model(OutlierFilter::reject_beyond(0.2));
+        rejecting.update_scheme("A", &[0.7, 0.3], Some(1)).unwrap();
+        let before = rejecting
+            .view()
+            .scheme("A")
+            .unwrap()
+            .distribution()
+            .to_vec();
+        let err = rejecting
+            .update_scheme("A", &[0.0, 1.0], Some(2))
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            DivergenceError::OutlierRejected { .. }
+        ));
+        assert!(err.is_recoverable());
+        assert_eq!(rejecting.view().scheme("A").unwrap().distribution(), before);
+        assert_eq!(
+            rejecting.view().grievance("A").unwrap().error_history.len(),
+            1
+        );
+
+        let log = rejecting.rejected_observations();
+        assert_eq!(log.len(), 1);
+        assert_eq!(
+            (log[0].timestamp_ms, log[0].observation.clone()),
+            (2, vec![0.0, 1.0])
+        );
+        assert!(log[0].distance > 0.2);
+
+        // Downweighting learns the outlier, just less
+        let mut plain = model(OutlierFilter::reject_beyond(f64::INFINITY));
+        let mut damped =
+            model(OutlierFilter::downweight_beyond(0.5).with_metric(OutlierMetric::ChiSquared));
+        let full = plain
+            .update_scheme("A", &[0.0, 1.0], None)
+            .unwrap()
+            .distribution()[1];
+        let part = damped
+            .update_scheme("A", &[0.0, 1.0], None)
+            .unwrap()
+            .distribution()[1];
+        assert!(part > 0.2 && part < full);
+        assert!(damped.rejected_observations().is_empty());
+
+        // The log keeps only the most recent rejections
+        let mut capped = model(OutlierFilter::reject_beyond(0.2).with_log_capacity(2));
+        for t in 0..5 {
+            assert!(capped.update_scheme("A", &[0.0, 1.0], Some(t)).is_err());
+        }
+        let log = capped.rejected_observations();
+        assert_eq!(log.len(), 2);
+        assert_eq!((log[0].timestamp_ms, log[1].timestamp_ms), (3, 4));
+
+        // The sharded model logs its rejections too
+        let shared = SharedModel::new(model(OutlierFilter::reject_beyond(0.2)));
+        assert!(shared.update_scheme("A", &[0.0, 1.0], Some(7)).is_err());
+        assert_eq!(shared.rejected_observations().len(), 1);
+        assert_eq!(shared.snapshot().rejected_observations()[0].timestamp_ms, 7);
+    }
+
+    #[test]
+    fn test_interaction_target_rejected() {
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 2,
+            outlier_filter: Some(OutlierFilter::reject_beyond(0.2)),
+            ..Default::default()
+        });
+        model.register_actor("A", Some(vec![0.8, 0.2]), None);
+        model.register_actor("B", Some(vec![0.8, 0.2]), None);
+
+        // A's side is fine, B's is an outlier: neither is applied
+        let err = model
+            .update_interaction(
+                "A",
+                &[0.7, 0.3],
+                "B",
+                &[0.0, 1.0],
+                Some(1),
+                EventAttribution::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            DivergenceError::OutlierRejected { .. }
+        ));
+        assert_eq!(model.get_scheme("A").unwrap().observation_count(), 0);
+        assert_eq!(model.get_scheme("B").unwrap().observation_count(), 0);
+        assert_eq!(model.rejected_observations()[0].actor_id, "B");
+    }
+}
@@ -0,0 +1,317 @@
+//! Ready-to-plot summaries of actor state.
+//!
+//! UI layers chart the same few things for every actor: the current scheme
+//! as a bar chart and how its entropy has moved. These helpers derive the
+//! arrays directly from model state so front-ends do not re-walk history:
+//!
+//! ```text
+//! scheme_histogram(actor, bins)                  labels[i], values[i]
+//! entropy_sparkline(actor, points)               timestamps_ms[i], values[i]
+//! category_timeseries(actor, cat, range, step)   timestamps_ms[i], values[i]
+//! ```
+//!
+//! Resampled series treat history as a step function: each point takes the
+//! value of the last history entry at or before its time.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::scheme::CompressionScheme;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Bar chart of an actor's current scheme
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemeHistogram {
+    pub actor_id: String,
+    /// Bar labels: category names, `first–last` ranges or `other`
+    pub labels: Vec<String>,
+    /// Probability mass per bar, summing to 1
+    pub values: Vec<f64>,
+}
+
+/// Entropy of an actor's scheme over time
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Sparkline {
+    pub timestamps_ms: Vec<i64>,
+    /// Shannon entropy in bits
+    pub values: Vec<f64>,
+    /// Smallest value, for fixing the y axis; 0 when empty
+    pub min: f64,
+    /// Largest value; 0 when empty
+    pub max: f64,
+}
+
+/// Probability mass of one category in an actor's scheme over time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategorySeries {
+    pub actor_id: String,
+    pub category: String,
+    pub timestamps_ms: Vec<i64>,
+    pub values: Vec<f64>,
+}
+
+/// Value of the last entry at or before each time; `times` ascending and
+/// not before the first entry
+fn sample_steps(entries: &[(i64, f64)], times: impl Iterator<Item = i64>) -> Vec<(i64, f64)> {
+    let mut next = 0;
+    times
+        .map(|t| {
+            while next + 1 < entries.len() && entries[next + 1].0 <= t {
+                next += 1;
+            }
+            (t, entries[next].1)
+        })
+        .collect()
+}
+
+/// Index of a category by name, including the `cat_{i}` names of
+/// unlabelled schemes
+fn category_position(scheme: &CompressionScheme, name: &str) -> Option<usize> {
+    scheme
+        .category_index(name)
+        .or_else(|| (0..scheme.n_categories()).find(|&i| scheme.category_name(i) == name))
+}
+
+impl CompressionDynamicsModel {
+    /// Current scheme of an actor grouped into at most `bins` bars
+    ///
+    /// Schemes with at most `bins` categories get one bar per category in
+    /// category order. Larger ordered schemes are split into contiguous
+    /// ranges; larger unordered ones show the `bins - 1` heaviest
+    /// categories, heaviest first, and lump the rest into `other`.
+    pub fn scheme_histogram(&self, actor_id: &str, bins: usize) -> Result<SchemeHistogram> {
+        if bins == 0 {
+            return Err(DivergenceError::ConfigError(
+                "histogram needs at least one bin".to_string(),
+            ));
+        }
+        let scheme = self.scheme_for(actor_id)?;
+        let dist = scheme.distribution();
+        let n = dist.len();
+
+        let (labels, values): (Vec<String>, Vec<f64>) = if n <= bins {
+            (
+                (0..n).map(|i| scheme.category_name(i)).collect(),
+                dist.to_vec(),
+            )
+        } else if scheme.ordered_categories {
+            (0..bins)
+                .map(|b| {
+                    let (start, end) = (b * n / bins, (b + 1) * n / bins);
+                    let label = if end - start == 1 {
+                        scheme.category_name(start)
+                    } else {
+                        format!(
+                            "{}–{}",
+                            scheme.category_name(start),
+                            scheme.category_name(end - 1)
+                        )
+                    };
+                    (label, dist[start..end].iter().sum::<f64>())
+                })
+                .unzip()
+        } else {
+            let mut order: Vec<usize> = (0..n).collect();
+            order.sort_by(|&a, &b| dist[b].total_cmp(&dist[a]));
+            let (top, rest) = order.split_at(bins - 1);
+            top.iter()
+                .map(|&i| (scheme.category_name(i), dist[i]))
+                .chain(std::iter::once((
+                    "other".to_string(),
+                    rest.iter().map(|&i| dist[i]).sum::<f64>(),
+                )))
+                .unzip()
+        };
+
+        Ok(SchemeHistogram {
+            actor_id: scheme.actor_id.clone(),
+            labels,
+            values,
+        })
+    }
+
+    /// Entropy of an actor's recorded schemes at `n_points` evenly spaced
+    /// times from its first to its last history entry
+    ///
+    /// Histories with at most `n_points` entries are returned as recorded.
+    pub fn entropy_sparkline(&self, actor_id: &str, n_points: usize) -> Result<Sparkline> {
+        let actor_id = self.scheme_for(actor_id)?.actor_id.as_str();
+        let entries: Vec<(i64, f64)> = self
+            .history
+            .iter()
+            .filter(|e| e.actor_id == actor_id)
+            .map(|e| (e.timestamp_ms, e.scheme.entropy()))
+            .collect();
+
+        let points: Vec<(i64, f64)> = if entries.len() <= n_points {
+            entries
+        } else if n_points == 0 {
+            Vec::new()
+        } else {
+            let (first, last) = (entries[0].0, entries[entries.len() - 1].0);
+            let span = (last - first) as f64;
+            let times = (0..n_points).map(|i| {
+                if n_points == 1 {
+                    last
+                } else {
+                    first + (span * i as f64 / (n_points - 1) as f64).round() as i64
+                }
+            });
+            sample_steps(&entries, times)
+        };
+
+        let (timestamps_ms, values): (Vec<i64>, Vec<f64>) = points.into_iter().unzip();
+        let min = values.iter().copied().reduce(f64::min).unwrap_or(0.0);
+        let max = values.iter().copied().reduce(f64::max).unwrap_or(0.0);
+        Ok(Sparkline {
+            timestamps_ms,
+            values,
+            min,
+            max,
+        })
+    }
+
+    /// Probability of `category` in each of an actor's history entries
+    /// within `range`, oldest first
+    ///
+    /// With `step_ms`, the series is resampled at the multiples of `step_ms`
+    /// from its first to its last entry. The category is looked up by name
+    /// in each entry, so series survive category remaps; entries recorded
+    /// without it are skipped.
+    pub fn category_timeseries(
+        &self,
+        actor_id: &str,
+        category: &str,
+        range: Range<i64>,
+        step_ms: Option<i64>,
+    ) -> Result<CategorySeries> {
+        let scheme = self.scheme_for(actor_id)?;
+        if category_position(scheme, category).is_none() {
+            return Err(DivergenceError::UnknownCategory(category.to_string()));
+        }
+        if step_ms.is_some_and(|s| s <= 0) {
+            return Err(DivergenceError::ConfigError(
+                "resampling step must be positive".to_string(),
+            ));
+        }
+
+        let entries: Vec<(i64, f64)> = self
+            .history
+            .iter()
+            .filter(|e| e.actor_id == scheme.actor_id && range.contains(&e.timestamp_ms))
+            .filter_map(|e| {
+                let i = category_position(&e.scheme, category)?;
+                Some((e.timestamp_ms, e.scheme.distribution()[i]))
+            })
+            .collect();
+
+        let points = match (step_ms, entries.first(), entries.last()) {
+            (Some(step), Some(&(first, _)), Some(&(last, _))) => {
+                let start = first.div_euclid(step) * step;
+                let start = if start < first { start + step } else { start };
+                let times = (0..).map(|k| start + k * step).take_while(|&t| t <= last);
+                sample_steps(&entries, times)
+            }
+            _ => entries,
+        };
+
+        let (timestamps_ms, values) = points.into_iter().unzip();
+        Ok(CategorySeries {
+            actor_id: scheme.actor_id.clone(),
+            category: category.to_string(),
+            timestamps_ms,
+            values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_and_sparkline() {
+        let mut model = CompressionDynamicsModel::new(4);
+        model.register_actor("A", Some(vec![0.1, 0.4, 0.2, 0.3]), None);
+        let ordered = CompressionScheme::new("B", vec![0.1, 0.4, 0.2, 0.3], None)
+            .with_ordered_categories(true);
+        model.register_scheme(ordered).unwrap();
+
+        let bars = model.scheme_histogram("A", 4).unwrap();
+        assert_eq!(bars.labels, ["cat_0", "cat_1", "cat_2", "cat_3"]);
+        let bars = model.scheme_histogram("A", 3).unwrap();
+        assert_eq!(bars.labels, ["cat_1", "cat_3", "other"]);
+        assert!((bars.values[2] - 0.3).abs() < 1e-6);
+        let bars = model.scheme_histogram("B", 2).unwrap();
+        assert_eq!(bars.labels, ["cat_0–cat_1", "cat_2–cat_3"]);
+        assert!((bars.values[0] - 0.5).abs() < 1e-6);
+        assert!(model.scheme_histogram("A", 0).is_err());
+        assert!(model.scheme_histogram("Z", 3).is_err());
+
+        assert!(model.entropy_sparkline("A", 10).unwrap().values.is_empty());
+        // Concentrating updates at t = 0, 10, ..., 90
+        for t in 0..10 {
+            model
+                .update_scheme("A", &[0.0, 1.0, 0.0, 0.0], Some(t * 10))
+                .unwrap();
+        }
+        let full = model.entropy_sparkline("A", 20).unwrap();
+        assert_eq!(full.values.len(), 10);
+        assert!(full.values.windows(2).all(|w| w[1] < w[0]));
+
+        let spark = model.entropy_sparkline("A", 4).unwrap();
+        assert_eq!(spark.timestamps_ms, [0, 30, 60, 90]);
+        assert_eq!(
+            spark.values,
+            [
+                full.values[0],
+                full.values[3],
+                full.values[6],
+                full.values[9]
+            ]
+        );
+        assert_eq!((spark.min, spark.max), (full.values[9], full.values[0]));
+        // Between entries, the earlier entry's value holds
+        let spark = model.entropy_sparkline("A", 3).unwrap();
+        assert_eq!(spark.timestamps_ms, [0, 45, 90]);
+        assert_eq!(spark.values[1], full.values[4]);
+    }
+
+    #[test]
+    fn test_category_timeseries() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.2, 0.3, 0.5]), None);
+        for t in [5, 12, 18, 31] {
+            model.update_scheme("A", &[1.0, 0.0, 0.0], Some(t)).unwrap();
+        }
+
+        let all = model
+            .category_timeseries("A", "cat_0", i64::MIN..i64::MAX, None)
+            .unwrap();
+        assert_eq!(all.timestamps_ms, [5, 12, 18, 31]);
+        assert!(all.values.windows(2).all(|w| w[1] > w[0]));
+
+        let window = model
+            .category_timeseries("A", "cat_0", 10..31, None)
+            .unwrap();
+        assert_eq!(window.timestamps_ms, [12, 18]);
+
+        // Multiples of 10 within [5, 31], each holding the latest entry
+        let resampled = model
+            .category_timeseries("A", "cat_0", i64::MIN..i64::MAX, Some(10))
+            .unwrap();
+        assert_eq!(resampled.timestamps_ms, [10, 20, 30]);
+        assert_eq!(
+            resampled.values,
+            [all.values[0], all.values[2], all.values[2]]
+        );
+
+        assert!(matches!(
+            model.category_timeseries("A", "cat_9", 0..10, None),
+            Err(DivergenceError::UnknownCategory(_))
+        ));
+        assert!(model
+            .category_timeseries("A", "cat_0", 0..10, Some(0))
+            .is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Pluggable escalation predictors.
+//!
+//! `predict_escalation` uses a hand-set logistic formula by default. Users
+//! who have trained a classifier on exported [feature vectors](crate::features)
+//! can swap it in: implement [`LearnedPredictor`] (or load an ONNX model
+//! with the `onnx` feature), attach it with
+//! [`set_learned_predictor`](CompressionDynamicsModel::set_learned_predictor)
+//! and set `ModelConfig::predictor` to [`PredictorKind::Learned`].
+//!
+//! The predictor itself is not serialized; re-attach it after
+//! `from_json`.
+
+use crate::error::{DivergenceError, Result};
+use crate::features::FeatureVector;
+use crate::model::CompressionDynamicsModel;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Which predictor `predict_escalation` uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PredictorKind {
+    /// Built-in logistic formula
+    #[default]
+    Logistic,
+    /// The model's attached [`LearnedPredictor`]
+    Learned,
+    /// Combination of several predictors, see [`crate::ensemble`]
+    Ensemble,
+}
+
+/// Escalation probability from engine features
+pub trait LearnedPredictor: Send + Sync + std::fmt::Debug {
+    /// Probability of escalation in [0, 1]
+    fn predict(&self, features: &FeatureVector) -> Result<f64>;
+
+    /// Probability of escalation given the dyad's feature history, oldest
+    /// first and ending with the current features
+    ///
+    /// Stateless predictors only look at the last entry.
+    fn predict_history(&self, history: &[FeatureVector]) -> Result<f64> {
+        match history.last() {
+            Some(features) => self.predict(features),
+            None => Err(DivergenceError::ConfigError(
+                "Predictor needs at least one feature vector".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "onnx")]
+pub use onnx::OnnxPredictor;
+
+#[cfg(feature = "onnx")]
+mod onnx {
+    use super::LearnedPredictor;
+    use crate::error::{DivergenceError, Result};
+    use crate::features::{FeatureVector, N_FEATURES};
+    use tract_onnx::prelude::*;
+
+    /// ONNX classifier over the engine's feature vector
+    ///
+    /// The graph takes one `f32[1, N_FEATURES]` input in
+    /// [`FEATURE_NAMES`](crate::features::FEATURE_NAMES) order. The first
+    /// output is read as the escalation probability: a single value, or
+    /// `[p(no escalation), p(escalation)]`, in which case the last value is
+    /// used.
+    pub struct OnnxPredictor {
+        plan: TypedRunnableModel<TypedModel>,
+    }
+
+    impl std::fmt::Debug for OnnxPredictor {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("OnnxPredictor").finish_non_exhaustive()
+        }
+    }
+
+    fn onnx_error(e: impl std::fmt::Display) -> DivergenceError {
+        DivergenceError::ConfigError(format!("ONNX model: {}", e))
+    }
+
+    impl OnnxPredictor {
+        /// Load and optimize a model from an `.onnx` file
+        pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self> {
+            let model = tract_onnx::onnx()
+                .model_for_path(path)
+                .map_err(onnx_error)?;
+            Self::from_inference_model(model)
+        }
+
+        /// Load and optimize a model from in-memory `.onnx` bytes
+        pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+            let model = tract_onnx::onnx()
+                .model_for_read(&mut std::io::Cursor::new(bytes))
+                .map_err(onnx_error)?;
+            Self::from_inference_model(model)
+        }
+
+        fn from_inference_model(model: InferenceModel) -> Result<Self> {
+            let plan = model
+                .with_input_fact(0, f32::fact([1, N_FEATURES]).into())
+                .map_err(onnx_error)?
+                .into_optimized()
+                .map_err(onnx_error)?
+                .into_runnable()
+                .map_err(onnx_error)?;
+            Ok(Self { plan })
+        }
+    }
+
+    impl LearnedPredictor for OnnxPredictor {
+        fn predict(&self, features: &FeatureVector) -> Result<f64> {
+            let values: Vec<f32> = features.values.iter().map(|&x| x as f32).collect();
+            let input = Tensor::from_shape(&[1, N_FEATURES], &values).map_err(onnx_error)?;
+            let outputs = self
+                .plan
+                .run(tvec!(input.into_tvalue()))
+                .map_err(onnx_error)?;
+            let output = outputs
+                .first()
+                .ok_or_else(|| onnx_error("model has no outputs"))?
+                .cast_to::<f32>()
+                .map_err(onnx_error)?;
+            let probability = output
+                .as_slice::<f32>()
+                .map_err(onnx_error)?
+                .last()
+                .copied()
+                .ok_or_else(|| onnx_error("empty output"))?;
+            Ok(probability as f64)
+        }
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Attach a learned predictor for `PredictorKind::Learned`
+    pub fn set_learned_predictor(&mut self, predictor: Arc<dyn LearnedPredictor>) {
+        self.learned_predictor = Some(predictor);
+    }
+
+    /// Attached learned predictor, if any
+    pub fn learned_predictor(&self) -> Option<&Arc<dyn LearnedPredictor>> {
+        self.learned_predictor.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+
+    /// Escalates whenever Φ exceeds a cutoff
+    #[derive(Debug)]
+    struct PhiCutoff(f64);
+
+    impl LearnedPredictor for PhiCutoff {
+        fn predict(&self, features: &FeatureVector) -> Result<f64> {
+            Ok(if features.get("phi").unwrap() > self.0 {
+                0.99
+            } else {
+                0.01
+            })
+        }
+    }
+
+    #[test]
+    fn test_learned_predictor_replaces_logistic() {
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 3,
+            predictor: PredictorKind::Learned,
+            ..Default::default()
+        });
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.3, 0.6]), None);
+
+        // Selected but not attached
+        assert!(model.peek_escalation("A", "B", 0.5, 0.0).is_err());
+
+        model.set_learned_predictor(Arc::new(PhiCutoff(0.5)));
+        let prediction = model.predict_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert_eq!(prediction.probability, 0.99);
+        assert_eq!(prediction.predictor, PredictorKind::Learned);
+
+        model.set_learned_predictor(Arc::new(PhiCutoff(100.0)));
+        let prediction = model.peek_escalation("A", "B", 0.5, 0.0).unwrap();
+        assert_eq!(prediction.probability, 0.01);
+    }
+}
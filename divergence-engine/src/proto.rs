@@ -0,0 +1,436 @@
+//! Protobuf codec for events, alerts and conflict potentials.
+//!
+//! The wire format is published in `proto/divergence.proto` (package
+//! `divergence.v1`) so producers and consumers in other languages can
+//! generate their own bindings. The [`pb`] messages mirror that file and
+//! [`ProtoCodec`] converts to and from the engine's types:
+//!
+//! ```text
+//! StreamEvent ──encode_proto──▶ bytes ──decode_proto──▶ StreamEvent
+//!                                 │
+//!                        Kafka value / gRPC body
+//! ```
+//!
+//! Decoded events are checked against the event schema like JSON ones
+//! (see [`crate::event_schema`]); an unset `schema_version` reads as the
+//! legacy version.
+
+use crate::error::{DivergenceError, Result};
+use crate::event_schema::{
+    validate_decoded_event, EVENT_SCHEMA_VERSION, LEGACY_EVENT_SCHEMA_VERSION,
+};
+use crate::latency::AlertLatency;
+use crate::scheme::{ConflictPotential, RiskLevel};
+use crate::streaming::{DivergenceAlert, StreamEvent};
+use prost::Message;
+use std::sync::Arc;
+
+/// Messages of `proto/divergence.proto`
+pub mod pb {
+    use std::collections::HashMap;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct StreamEvent {
+        #[prost(uint32, tag = "1")]
+        pub schema_version: u32,
+        #[prost(string, tag = "2")]
+        pub event_id: String,
+        #[prost(string, tag = "3")]
+        pub actor_id: String,
+        #[prost(double, repeated, tag = "4")]
+        pub observation: Vec<f64>,
+        #[prost(int64, tag = "5")]
+        pub timestamp_ms: i64,
+        #[prost(string, tag = "6")]
+        pub source: String,
+        #[prost(map = "string, string", tag = "7")]
+        pub metadata: HashMap<String, String>,
+        #[prost(string, optional, tag = "8")]
+        pub target_actor_id: Option<String>,
+        #[prost(double, repeated, tag = "9")]
+        pub target_observation: Vec<f64>,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum RiskLevel {
+        Unspecified = 0,
+        Low = 1,
+        Moderate = 2,
+        Elevated = 3,
+        High = 4,
+        Critical = 5,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct DivergenceAlert {
+        #[prost(string, tag = "1")]
+        pub alert_id: String,
+        #[prost(string, tag = "2")]
+        pub actor_a: String,
+        #[prost(string, tag = "3")]
+        pub actor_b: String,
+        #[prost(double, tag = "4")]
+        pub phi: f64,
+        #[prost(double, tag = "5")]
+        pub js: f64,
+        #[prost(double, optional, tag = "6")]
+        pub phi_z: Option<f64>,
+        #[prost(double, optional, tag = "7")]
+        pub phi_adjusted: Option<f64>,
+        #[prost(double, optional, tag = "8")]
+        pub p_value: Option<f64>,
+        #[prost(double, optional, tag = "9")]
+        pub robustness: Option<f64>,
+        #[prost(double, tag = "10")]
+        pub d_phi_dt: f64,
+        #[prost(enumeration = "RiskLevel", tag = "11")]
+        pub risk_level: i32,
+        #[prost(double, tag = "12")]
+        pub escalation_probability: f64,
+        #[prost(double, tag = "13")]
+        pub grievance_a_to_b: f64,
+        #[prost(double, tag = "14")]
+        pub grievance_b_to_a: f64,
+        #[prost(int64, tag = "15")]
+        pub timestamp_ms: i64,
+        #[prost(string, tag = "16")]
+        pub reason: String,
+        #[prost(message, optional, tag = "17")]
+        pub latency: Option<AlertLatency>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AlertLatency {
+        #[prost(int64, tag = "1")]
+        pub ingested_ms: i64,
+        #[prost(int64, tag = "2")]
+        pub processed_ms: i64,
+        #[prost(int64, optional, tag = "3")]
+        pub emitted_ms: Option<i64>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ConflictPotential {
+        #[prost(string, tag = "1")]
+        pub actor_a: String,
+        #[prost(string, tag = "2")]
+        pub actor_b: String,
+        #[prost(double, tag = "3")]
+        pub phi: f64,
+        #[prost(double, tag = "4")]
+        pub js: f64,
+        #[prost(double, tag = "5")]
+        pub hellinger: f64,
+        #[prost(double, tag = "6")]
+        pub kl_a_b: f64,
+        #[prost(double, tag = "7")]
+        pub kl_b_a: f64,
+        #[prost(double, optional, tag = "8")]
+        pub emd: Option<f64>,
+        #[prost(double, optional, tag = "9")]
+        pub phi_z: Option<f64>,
+        #[prost(double, optional, tag = "10")]
+        pub phi_adjusted: Option<f64>,
+        #[prost(double, tag = "11")]
+        pub entropy_a: f64,
+        #[prost(double, tag = "12")]
+        pub entropy_b: f64,
+        #[prost(int64, optional, tag = "13")]
+        pub timestamp_ms: Option<i64>,
+    }
+}
+
+/// Protobuf encoding of an engine type
+pub trait ProtoCodec: Sized {
+    /// Encode as the matching `divergence.v1` message
+    fn encode_proto(&self) -> Vec<u8>;
+
+    /// Decode from the matching `divergence.v1` message
+    fn decode_proto(bytes: &[u8]) -> Result<Self>;
+}
+
+fn decode_error(message: &str, error: impl std::fmt::Display) -> DivergenceError {
+    DivergenceError::SerializationError(format!("Invalid {} protobuf: {}", message, error))
+}
+
+impl From<RiskLevel> for pb::RiskLevel {
+    fn from(level: RiskLevel) -> Self {
+        match level {
+            RiskLevel::Low => pb::RiskLevel::Low,
+            RiskLevel::Moderate => pb::RiskLevel::Moderate,
+            RiskLevel::Elevated => pb::RiskLevel::Elevated,
+            RiskLevel::High => pb::RiskLevel::High,
+            RiskLevel::Critical => pb::RiskLevel::Critical,
+        }
+    }
+}
+
+impl TryFrom<pb::RiskLevel> for RiskLevel {
+    type Error = DivergenceError;
+
+    fn try_from(level: pb::RiskLevel) -> Result<Self> {
+        match level {
+            pb::RiskLevel::Low => Ok(RiskLevel::Low),
+            pb::RiskLevel::Moderate => Ok(RiskLevel::Moderate),
+            pb::RiskLevel::Elevated => Ok(RiskLevel::Elevated),
+            pb::RiskLevel::High => Ok(RiskLevel::High),
+            pb::RiskLevel::Critical => Ok(RiskLevel::Critical),
+            pb::RiskLevel::Unspecified => {
+                Err(decode_error("DivergenceAlert", "risk_level: unspecified"))
+            }
+        }
+    }
+}
+
+impl ProtoCodec for StreamEvent {
+    fn encode_proto(&self) -> Vec<u8> {
+        pb::StreamEvent {
+            schema_version: self.schema_version,
+            event_id: self.event_id.clone(),
+            actor_id: self.actor_id.clone(),
+            observation: self.observation.to_vec(),
+            timestamp_ms: self.timestamp_ms,
+            source: self.source.to_string(),
+            metadata: self
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            target_actor_id: self.target_actor_id.clone(),
+            target_observation: self
+                .target_observation
+                .as_deref()
+                .map(<[f64]>::to_vec)
+                .unwrap_or_default(),
+        }
+        .encode_to_vec()
+    }
+
+    fn decode_proto(bytes: &[u8]) -> Result<Self> {
+        let message = pb::StreamEvent::decode(bytes).map_err(|e| decode_error("StreamEvent", e))?;
+        let version = match message.schema_version {
+            0 => LEGACY_EVENT_SCHEMA_VERSION,
+            v if v > EVENT_SCHEMA_VERSION => {
+                return Err(DivergenceError::SerializationError(format!(
+                    "Unsupported event schema version {} (newest supported is {})",
+                    v, EVENT_SCHEMA_VERSION
+                )))
+            }
+            v => v,
+        };
+        let event = StreamEvent {
+            schema_version: EVENT_SCHEMA_VERSION,
+            event_id: message.event_id,
+            actor_id: message.actor_id,
+            observation: Arc::from(message.observation),
+            target_actor_id: message.target_actor_id,
+            target_observation: (!message.target_observation.is_empty())
+                .then(|| Arc::from(message.target_observation)),
+            timestamp_ms: message.timestamp_ms,
+            source: Arc::from(message.source),
+            metadata: message
+                .metadata
+                .into_iter()
+                .map(|(k, v)| (Arc::from(k), Arc::from(v)))
+                .collect(),
+            ingested_ms: None,
+        };
+
+        // Same checks as a JSON payload of the declared version
+        let violations = validate_decoded_event(&event, version);
+        if !violations.is_empty() {
+            let problems: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+            return Err(decode_error("StreamEvent", problems.join("; ")));
+        }
+        Ok(event)
+    }
+}
+
+impl ProtoCodec for DivergenceAlert {
+    fn encode_proto(&self) -> Vec<u8> {
+        pb::DivergenceAlert {
+            alert_id: self.alert_id.clone(),
+            actor_a: self.actor_a.clone(),
+            actor_b: self.actor_b.clone(),
+            phi: self.phi,
+            js: self.js,
+            phi_z: self.phi_z,
+            phi_adjusted: self.phi_adjusted,
+            p_value: self.p_value,
+            robustness: self.robustness,
+            d_phi_dt: self.d_phi_dt,
+            risk_level: pb::RiskLevel::from(self.risk_level) as i32,
+            escalation_probability: self.escalation_probability,
+            grievance_a_to_b: self.grievance_a_to_b,
+            grievance_b_to_a: self.grievance_b_to_a,
+            timestamp_ms: self.timestamp_ms,
+            reason: self.reason.clone(),
+            latency: self.latency.map(|latency| pb::AlertLatency {
+                ingested_ms: latency.ingested_ms,
+                processed_ms: latency.processed_ms,
+                emitted_ms: latency.emitted_ms,
+            }),
+        }
+        .encode_to_vec()
+    }
+
+    fn decode_proto(bytes: &[u8]) -> Result<Self> {
+        let message =
+            pb::DivergenceAlert::decode(bytes).map_err(|e| decode_error("DivergenceAlert", e))?;
+        let risk_level = pb::RiskLevel::try_from(message.risk_level)
+            .map_err(|e| decode_error("DivergenceAlert", e))?
+            .try_into()?;
+        Ok(DivergenceAlert {
+            alert_id: message.alert_id,
+            actor_a: message.actor_a,
+            actor_b: message.actor_b,
+            phi: message.phi,
+            js: message.js,
+            phi_z: message.phi_z,
+            phi_adjusted: message.phi_adjusted,
+            p_value: message.p_value,
+            robustness: message.robustness,
+            d_phi_dt: message.d_phi_dt,
+            risk_level,
+            escalation_probability: message.escalation_probability,
+            grievance_a_to_b: message.grievance_a_to_b,
+            grievance_b_to_a: message.grievance_b_to_a,
+            timestamp_ms: message.timestamp_ms,
+            reason: message.reason,
+            latency: message.latency.map(|latency| AlertLatency {
+                ingested_ms: latency.ingested_ms,
+                processed_ms: latency.processed_ms,
+                emitted_ms: latency.emitted_ms,
+            }),
+        })
+    }
+}
+
+impl ProtoCodec for ConflictPotential {
+    fn encode_proto(&self) -> Vec<u8> {
+        pb::ConflictPotential {
+            actor_a: self.actor_a.clone(),
+            actor_b: self.actor_b.clone(),
+            phi: self.phi,
+            js: self.js,
+            hellinger: self.hellinger,
+            kl_a_b: self.kl_a_b,
+            kl_b_a: self.kl_b_a,
+            emd: self.emd,
+            phi_z: self.phi_z,
+            phi_adjusted: self.phi_adjusted,
+            entropy_a: self.entropy_a,
+            entropy_b: self.entropy_b,
+            timestamp_ms: self.timestamp_ms,
+        }
+        .encode_to_vec()
+    }
+
+    fn decode_proto(bytes: &[u8]) -> Result<Self> {
+        let message = pb::ConflictPotential::decode(bytes)
+            .map_err(|e| decode_error("ConflictPotential", e))?;
+        Ok(ConflictPotential {
+            actor_a: message.actor_a,
+            actor_b: message.actor_b,
+            phi: message.phi,
+            js: message.js,
+            hellinger: message.hellinger,
+            kl_a_b: message.kl_a_b,
+            kl_b_a: message.kl_b_a,
+            emd: message.emd,
+            phi_z: message.phi_z,
+            phi_adjusted: message.phi_adjusted,
+            entropy_a: message.entropy_a,
+            entropy_b: message.entropy_b,
+            timestamp_ms: message.timestamp_ms,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::CompressionScheme;
+
+    #[test]
+    fn test_proto_roundtrips() {
+        let event = StreamEvent::new("e1", "A", vec![0.25, 0.5, 0.25], 1_700_000_000_000)
+            .with_source(Arc::from("gdelt"))
+            .with_source_actor(Arc::from("C"))
+            .with_target("B", vec![0.1, 0.1, 0.8]);
+        let bytes = event.encode_proto();
+        assert!(bytes.len() < serde_json::to_vec(&event).unwrap().len());
+        let back = StreamEvent::decode_proto(&bytes).unwrap();
+        assert_eq!(back.event_id, "e1");
+        assert_eq!(&*back.observation, &*event.observation);
+        assert_eq!(back.target_actor_id.as_deref(), Some("B"));
+        assert_eq!(
+            back.target_observation.as_deref(),
+            Some(&[0.1, 0.1, 0.8][..])
+        );
+        assert_eq!(back.source_actor(), Some("C"));
+
+        let a = CompressionScheme::new("A", vec![0.7, 0.2, 0.1], None);
+        let b = CompressionScheme::new("B", vec![0.1, 0.2, 0.7], None);
+        let potential = ConflictPotential::compute(&a, &b).unwrap();
+        let back = ConflictPotential::decode_proto(&potential.encode_proto()).unwrap();
+        assert_eq!(back.phi, potential.phi);
+        assert_eq!(back.emd, potential.emd);
+        assert_eq!(back.timestamp_ms, None);
+
+        let alert = DivergenceAlert {
+            alert_id: "a1".to_string(),
+            actor_a: "A".to_string(),
+            actor_b: "B".to_string(),
+            phi: potential.phi,
+            js: potential.js,
+            phi_z: Some(2.5),
+            phi_adjusted: None,
+            p_value: None,
+            robustness: Some(0.9),
+            d_phi_dt: 0.1,
+            risk_level: RiskLevel::High,
+            escalation_probability: 0.7,
+            grievance_a_to_b: 0.2,
+            grievance_b_to_a: 0.0,
+            timestamp_ms: 5,
+            reason: "test".to_string(),
+            latency: Some(AlertLatency {
+                ingested_ms: 10,
+                processed_ms: 25,
+                emitted_ms: None,
+            }),
+        };
+        let back = DivergenceAlert::decode_proto(&alert.encode_proto()).unwrap();
+        assert_eq!(back.risk_level, RiskLevel::High);
+        assert_eq!(back.phi_z, Some(2.5));
+        assert_eq!(back.phi_adjusted, None);
+        assert_eq!(back.latency, alert.latency);
+    }
+
+    #[test]
+    fn test_proto_rejects_invalid_messages() {
+        // Missing IDs and observation
+        let empty = pb::StreamEvent::default().encode_to_vec();
+        let err = StreamEvent::decode_proto(&empty).unwrap_err().to_string();
+        assert!(err.contains("event_id") && err.contains("observation"));
+
+        // Interaction fields are not part of a v1 event
+        let mut v1 = pb::StreamEvent::decode(
+            StreamEvent::new("e1", "A", vec![1.0], 0)
+                .with_target("B", vec![1.0])
+                .encode_proto()
+                .as_slice(),
+        )
+        .unwrap();
+        v1.schema_version = 1;
+        assert!(StreamEvent::decode_proto(&v1.encode_to_vec()).is_err());
+        v1.schema_version = EVENT_SCHEMA_VERSION + 1;
+        assert!(StreamEvent::decode_proto(&v1.encode_to_vec()).is_err());
+
+        let unrated = pb::DivergenceAlert::default().encode_to_vec();
+        assert!(DivergenceAlert::decode_proto(&unrated).is_err());
+        assert!(ConflictPotential::decode_proto(&[0xff, 0xff]).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! Regime-filter escalation predictor.
+//!
+//! A hidden Markov model over calm / tension / crisis dyad regimes, run as
+//! a forward filter over a dyad's feature history. Each regime emits
+//! `[Φ, dΦ, variance phase, mean grievance]` from a diagonal Gaussian; the
+//! escalation probability is the chance of escalating from the regime the
+//! dyad is predicted to be in next:
+//!
+//! ```text
+//! P(escalation) = Σ_j (Σ_i P(s_t = i | x_1..t) · A_ij) · r_j
+//! ```
+//!
+//! Unlike the logistic formula it carries belief across steps, so one
+//! noisy Φ sample moves it little. Parameters mirror the regime model in
+//! `nucleation` and are hand-set priors.
+
+use crate::error::{DivergenceError, Result};
+use crate::features::FeatureVector;
+use crate::predictor::LearnedPredictor;
+use serde::{Deserialize, Serialize};
+
+/// Number of regimes
+pub const N_REGIMES: usize = 3;
+
+/// Floor on emission variances, keeping densities finite
+const MIN_VARIANCE: f64 = 1e-6;
+
+/// HMM parameters and per-regime escalation rates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegimePredictor {
+    /// Initial regime probabilities
+    pub initial: [f64; N_REGIMES],
+    /// `transition[i][j]` = P(next = j | current = i)
+    pub transition: [[f64; N_REGIMES]; N_REGIMES],
+    /// Per-regime means of `[Φ, dΦ, phase, grievance]`
+    pub means: [[f64; 4]; N_REGIMES],
+    /// Per-regime variances of `[Φ, dΦ, phase, grievance]`
+    pub variances: [[f64; 4]; N_REGIMES],
+    /// Probability of escalation from each regime
+    pub escalation_rates: [f64; N_REGIMES],
+}
+
+impl Default for RegimePredictor {
+    fn default() -> Self {
+        Self {
+            initial: [0.8, 0.15, 0.05],
+            transition: [[0.95, 0.045, 0.005], [0.05, 0.90, 0.05], [0.01, 0.09, 0.90]],
+            means: [
+                [0.2, 0.0, 0.0, 0.01],
+                [1.0, 0.01, 1.0, 0.05],
+                [2.5, 0.05, 2.0, 0.2],
+            ],
+            variances: [
+                [0.04, 0.0004, 0.25, 0.0004],
+                [0.25, 0.0025, 0.5, 0.0025],
+                [1.0, 0.01, 1.0, 0.04],
+            ],
+            escalation_rates: [0.05, 0.35, 0.85],
+        }
+    }
+}
+
+impl RegimePredictor {
+    /// Emission features of a feature vector
+    fn emission_features(features: &FeatureVector) -> [f64; 4] {
+        let v = &features.values;
+        [
+            v[0],
+            v[4],
+            features.phase() as usize as f64,
+            0.5 * (v[5] + v[6]),
+        ]
+    }
+
+    fn log_emission(&self, s: usize, x: &[f64; 4]) -> f64 {
+        let mut log_p = 0.0;
+        for ((&xk, &mean), &var) in x.iter().zip(&self.means[s]).zip(&self.variances[s]) {
+            let var = var.max(MIN_VARIANCE);
+            let d = xk - mean;
+            log_p -= 0.5 * ((2.0 * std::f64::consts::PI * var).ln() + d * d / var);
+        }
+        log_p
+    }
+
+    /// Filtered regime probabilities after a feature history (oldest first)
+    pub fn filter(&self, history: &[FeatureVector]) -> [f64; N_REGIMES] {
+        let mut belief = self.initial;
+        for (t, features) in history.iter().enumerate() {
+            let predicted = if t == 0 {
+                self.initial
+            } else {
+                self.propagate(&belief)
+            };
+            let x = Self::emission_features(features);
+            let logs: Vec<f64> = (0..N_REGIMES).map(|s| self.log_emission(s, &x)).collect();
+            let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let mut posterior = [0.0; N_REGIMES];
+            for s in 0..N_REGIMES {
+                posterior[s] = predicted[s] * (logs[s] - max).exp();
+            }
+            let total: f64 = posterior.iter().sum();
+            belief = if total > 0.0 && total.is_finite() {
+                posterior.map(|p| p / total)
+            } else {
+                predicted
+            };
+        }
+        belief
+    }
+
+    fn propagate(&self, belief: &[f64; N_REGIMES]) -> [f64; N_REGIMES] {
+        let mut next = [0.0; N_REGIMES];
+        for (i, &pi) in belief.iter().enumerate() {
+            for (j, nj) in next.iter_mut().enumerate() {
+                *nj += pi * self.transition[i][j];
+            }
+        }
+        next
+    }
+}
+
+impl LearnedPredictor for RegimePredictor {
+    fn predict(&self, features: &FeatureVector) -> Result<f64> {
+        self.predict_history(std::slice::from_ref(features))
+    }
+
+    fn predict_history(&self, history: &[FeatureVector]) -> Result<f64> {
+        if history.is_empty() {
+            return Err(DivergenceError::ConfigError(
+                "Regime predictor needs at least one feature vector".to_string(),
+            ));
+        }
+        let next = self.propagate(&self.filter(history));
+        Ok(next
+            .iter()
+            .zip(self.escalation_rates.iter())
+            .map(|(p, r)| p * r)
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::N_FEATURES;
+
+    fn features(phi: f64, d_phi: f64, grievance: f64) -> FeatureVector {
+        let mut values = [0.0; N_FEATURES];
+        values[0] = phi;
+        values[4] = d_phi;
+        values[5] = grievance;
+        values[6] = grievance;
+        values[9] = 1.0;
+        FeatureVector {
+            actor_a: "A".to_string(),
+            actor_b: "B".to_string(),
+            timestamp_ms: None,
+            values,
+        }
+    }
+
+    #[test]
+    fn test_regime_belief_persists() {
+        let predictor = RegimePredictor::default();
+        let calm: Vec<FeatureVector> = (0..10).map(|_| features(0.2, 0.0, 0.01)).collect();
+        let p_calm = predictor.predict_history(&calm).unwrap();
+        assert!(p_calm < 0.1);
+
+        // One crisis-like sample after a long calm barely moves the belief
+        let mut blip = calm.clone();
+        blip.push(features(2.5, 0.0, 0.01));
+        let p_blip = predictor.predict_history(&blip).unwrap();
+        let p_cold = predictor.predict(&features(2.5, 0.0, 0.01)).unwrap();
+        assert!(p_blip > p_calm);
+        assert!(p_blip < p_cold);
+
+        let crisis: Vec<FeatureVector> = (0..10).map(|_| features(2.5, 0.05, 0.2)).collect();
+        assert!(predictor.predict_history(&crisis).unwrap() > 0.7);
+        assert!(predictor.predict_history(&[]).is_err());
+    }
+}
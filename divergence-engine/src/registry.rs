@@ -0,0 +1,270 @@
+//! Category Registry - a shared, interned category space.
+//!
+//! Schemes historically carry their own positional `Vec<String>` of labels.
+//! With tens of thousands of categories and hundreds of actors that is a
+//! lot of duplicated memory, and nothing guarantees that index `i` means
+//! the same thing in two schemes. A [`CategoryRegistry`] interns each name
+//! once and is shared (via `Arc`) by every scheme in a model.
+
+use crate::error::{DivergenceError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Interned mapping between category names and positional indices
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<String>", into = "Vec<String>")]
+pub struct CategoryRegistry {
+    names: Vec<Arc<str>>,
+    index: HashMap<Arc<str>, usize>,
+}
+
+impl CategoryRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry from category names (duplicates are ignored)
+    pub fn from_names<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut registry = Self::new();
+        for name in names {
+            registry.intern(name.as_ref());
+        }
+        registry
+    }
+
+    /// Create a registry of positional names `cat_0 .. cat_{n-1}`
+    pub fn positional(n_categories: usize) -> Self {
+        Self::from_names((0..n_categories).map(|i| format!("cat_{}", i)))
+    }
+
+    /// Intern a name, returning its index (existing or newly assigned)
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(&idx) = self.index.get(name) {
+            return idx;
+        }
+        let name: Arc<str> = Arc::from(name);
+        let idx = self.names.len();
+        self.names.push(Arc::clone(&name));
+        self.index.insert(name, idx);
+        idx
+    }
+
+    /// Wrap in an `Arc` for sharing across schemes
+    pub fn into_shared(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+
+    /// Index of a category name
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    /// Index of a category name, or `UnknownCategory`
+    pub fn index_of(&self, name: &str) -> Result<usize> {
+        self.get(name)
+            .ok_or_else(|| DivergenceError::UnknownCategory(name.to_string()))
+    }
+
+    /// Name of the category at an index
+    #[inline]
+    pub fn name(&self, idx: usize) -> Option<&str> {
+        self.names.get(idx).map(|s| s.as_ref())
+    }
+
+    /// Number of categories
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether the registry has no categories
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterate over category names in index order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|s| s.as_ref())
+    }
+
+    /// Materialize the names as owned strings
+    pub fn to_vec(&self) -> Vec<String> {
+        self.names().map(String::from).collect()
+    }
+}
+
+impl From<Vec<String>> for CategoryRegistry {
+    fn from(names: Vec<String>) -> Self {
+        Self::from_names(names)
+    }
+}
+
+impl From<CategoryRegistry> for Vec<String> {
+    fn from(registry: CategoryRegistry) -> Self {
+        registry.to_vec()
+    }
+}
+
+/// Mapping from an old category space onto a new one
+///
+/// Each old category sends its mass to one or more new categories.
+/// Merging maps several old categories onto the same new index; splitting
+/// spreads one old category across several new indices by weight. Old
+/// categories with no assignment have their mass dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryMapping {
+    /// Names of the categories in the new space
+    pub new_categories: Vec<String>,
+
+    /// Per old index: (new index, weight) pairs
+    pub assignments: Vec<Vec<(usize, f64)>>,
+}
+
+impl CategoryMapping {
+    /// Create an empty mapping from `n_old` categories onto `new_categories`
+    pub fn new(n_old: usize, new_categories: Vec<String>) -> Self {
+        Self {
+            new_categories,
+            assignments: vec![Vec::new(); n_old],
+        }
+    }
+
+    /// Send all of an old category's mass to a new category
+    pub fn map(mut self, old: usize, new: usize) -> Self {
+        if let Some(a) = self.assignments.get_mut(old) {
+            *a = vec![(new, 1.0)];
+        }
+        self
+    }
+
+    /// Spread an old category's mass across new categories by weight
+    pub fn split(mut self, old: usize, targets: &[(usize, f64)]) -> Self {
+        if let Some(a) = self.assignments.get_mut(old) {
+            *a = targets.to_vec();
+        }
+        self
+    }
+
+    /// Number of categories in the new space
+    #[inline]
+    pub fn n_new(&self) -> usize {
+        self.new_categories.len()
+    }
+
+    /// Check indices and weights against an old space of `n_old` categories
+    pub fn validate(&self, n_old: usize) -> Result<()> {
+        if self.assignments.len() != n_old {
+            return Err(DivergenceError::DimensionMismatch {
+                expected: n_old,
+                got: self.assignments.len(),
+            });
+        }
+        if self.new_categories.is_empty() {
+            return Err(DivergenceError::ConfigError(
+                "Mapping has no target categories".to_string(),
+            ));
+        }
+        for targets in &self.assignments {
+            for &(new, weight) in targets {
+                if new >= self.n_new() {
+                    return Err(DivergenceError::DimensionMismatch {
+                        expected: self.n_new(),
+                        got: new + 1,
+                    });
+                }
+                if !weight.is_finite() || weight < 0.0 {
+                    return Err(DivergenceError::ConfigError(format!(
+                        "Mapping weight must be finite and non-negative, got {}",
+                        weight
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Project a distribution over the old space onto the new space
+    pub fn apply(&self, distribution: &[f64]) -> Result<Vec<f64>> {
+        self.validate(distribution.len())?;
+
+        let mut projected = vec![0.0; self.n_new()];
+        for (&mass, targets) in distribution.iter().zip(self.assignments.iter()) {
+            let total: f64 = targets.iter().map(|&(_, w)| w).sum();
+            if total <= 0.0 {
+                continue;
+            }
+            for &(new, weight) in targets {
+                projected[new] += mass * weight / total;
+            }
+        }
+        Ok(projected)
+    }
+}
+
+/// Whether two optional shared registries are the same instance
+pub(crate) fn same_registry(
+    a: Option<&Arc<CategoryRegistry>>,
+    b: Option<&Arc<CategoryRegistry>>,
+) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning() {
+        let mut registry = CategoryRegistry::from_names(["cooperate", "threaten"]);
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.intern("threaten"), 1);
+        assert_eq!(registry.intern("protest"), 2);
+        assert_eq!(registry.name(2), Some("protest"));
+        assert!(registry.index_of("invade").is_err());
+    }
+
+    #[test]
+    fn test_mapping_merge_and_split() {
+        let names = vec![
+            "conflict".to_string(),
+            "left".to_string(),
+            "right".to_string(),
+        ];
+        // old: [verbal, material, cooperation] → merge 0+1, split 2
+        let mapping = CategoryMapping::new(3, names)
+            .map(0, 0)
+            .map(1, 0)
+            .split(2, &[(1, 1.0), (2, 3.0)]);
+
+        let projected = mapping.apply(&[0.3, 0.3, 0.4]).unwrap();
+        assert!((projected[0] - 0.6).abs() < 1e-12);
+        assert!((projected[1] - 0.1).abs() < 1e-12);
+        assert!((projected[2] - 0.3).abs() < 1e-12);
+
+        assert!(mapping.apply(&[0.5, 0.5]).is_err());
+        let bad = CategoryMapping::new(1, vec!["a".to_string()]).map(0, 4);
+        assert!(bad.validate(1).is_err());
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let registry = CategoryRegistry::from_names(["a", "b", "c"]);
+        let json = serde_json::to_string(&registry).unwrap();
+        assert_eq!(json, r#"["a","b","c"]"#);
+
+        let restored: CategoryRegistry = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.get("c"), Some(2));
+    }
+}
@@ -0,0 +1,215 @@
+//! System-wide composite risk index.
+//!
+//! One top-line number for operations, summing weighted dyad risk:
+//!
+//! ```text
+//! R = Σ_ij w_ij · Φ_ij · P_ij(escalation)   (÷ Σ w_ij when normalized)
+//! ```
+//!
+//! Every dyad's term is kept for drill-down. Recorded values form a
+//! history that is monitored with the same variance-phase classifier as
+//! dyad Φ (see [`VariancePhase`]), so a system drifting toward a
+//! transition shows up before any single dyad crosses a threshold.
+
+use crate::error::{DivergenceError, Result};
+use crate::features::VariancePhase;
+use crate::model::CompressionDynamicsModel;
+use serde::{Deserialize, Serialize};
+
+/// Weight of one dyad in the index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DyadWeight {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub weight: f64,
+}
+
+/// Composite index settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskIndexConfig {
+    /// Per-dyad weight overrides (order of actors does not matter)
+    #[serde(default)]
+    pub dyad_weights: Vec<DyadWeight>,
+    /// Weight of dyads without an override; 0 restricts the index to the
+    /// listed dyads
+    pub default_weight: f64,
+    /// Communication level assumed for every dyad's escalation probability
+    pub communication_level: f64,
+    /// Divide by the total weight, making the index a weighted mean
+    pub normalize: bool,
+}
+
+impl Default for RiskIndexConfig {
+    fn default() -> Self {
+        Self {
+            dyad_weights: Vec::new(),
+            default_weight: 1.0,
+            communication_level: 0.5,
+            normalize: true,
+        }
+    }
+}
+
+impl RiskIndexConfig {
+    fn weight(&self, actor_a: &str, actor_b: &str) -> f64 {
+        self.dyad_weights
+            .iter()
+            .find(|w| {
+                (w.actor_a == actor_a && w.actor_b == actor_b)
+                    || (w.actor_a == actor_b && w.actor_b == actor_a)
+            })
+            .map_or(self.default_weight, |w| w.weight)
+    }
+}
+
+/// One dyad's term of the index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DyadRisk {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub weight: f64,
+    pub phi: f64,
+    pub escalation_probability: f64,
+    /// Share of the index value, `w·Φ·P` (normalized if the index is)
+    pub contribution: f64,
+}
+
+/// Composite risk index with drill-down
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemRiskIndex {
+    pub value: f64,
+    /// Dyad terms, largest contribution first
+    pub dyads: Vec<DyadRisk>,
+    /// Variance phase of the index history including this value
+    pub phase: VariancePhase,
+}
+
+/// A recorded index value
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskIndexSample {
+    pub timestamp_ms: i64,
+    pub value: f64,
+}
+
+impl CompressionDynamicsModel {
+    /// Current composite risk index, without recording anything
+    pub fn system_risk_index(&self) -> Result<SystemRiskIndex> {
+        let config = &self.config.risk_index;
+        let actors = self.actors();
+        let mut dyads = Vec::new();
+        let mut total_weight = 0.0;
+        for i in 0..actors.len() {
+            for j in (i + 1)..actors.len() {
+                let weight = config.weight(actors[i], actors[j]);
+                if weight <= 0.0 {
+                    continue;
+                }
+                let prediction =
+                    self.peek_escalation(actors[i], actors[j], config.communication_level, 0.0)?;
+                total_weight += weight;
+                dyads.push(DyadRisk {
+                    actor_a: actors[i].to_string(),
+                    actor_b: actors[j].to_string(),
+                    weight,
+                    phi: prediction.current_phi,
+                    escalation_probability: prediction.probability,
+                    contribution: weight * prediction.current_phi * prediction.probability,
+                });
+            }
+        }
+        if config.normalize && total_weight > 0.0 {
+            for dyad in &mut dyads {
+                dyad.contribution /= total_weight;
+            }
+        }
+        dyads.sort_by(|a, b| b.contribution.total_cmp(&a.contribution));
+        let value = dyads.iter().map(|d| d.contribution).sum();
+
+        let window = self.config.phi_baseline_window.max(2);
+        let mut series: Vec<f64> = self
+            .risk_index_history
+            .iter()
+            .rev()
+            .take(window - 1)
+            .rev()
+            .map(|s| s.value)
+            .collect();
+        series.push(value);
+
+        Ok(SystemRiskIndex {
+            value,
+            dyads,
+            phase: VariancePhase::from_series(&series),
+        })
+    }
+
+    /// Compute the composite risk index and append it to its history
+    pub fn record_system_risk_index(&mut self, timestamp_ms: i64) -> Result<SystemRiskIndex> {
+        if let Some(last) = self.risk_index_history.last() {
+            if timestamp_ms < last.timestamp_ms {
+                return Err(DivergenceError::ConfigError(format!(
+                    "Risk index timestamp {} precedes last recorded {}",
+                    timestamp_ms, last.timestamp_ms
+                )));
+            }
+        }
+        let index = self.system_risk_index()?;
+        self.risk_index_history.push(RiskIndexSample {
+            timestamp_ms,
+            value: index.value,
+        });
+        Ok(index)
+    }
+
+    /// Recorded index values, oldest first
+    pub fn risk_index_history(&self) -> &[RiskIndexSample] {
+        &self.risk_index_history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+
+    #[test]
+    fn test_system_risk_index() {
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 3,
+            risk_index: RiskIndexConfig {
+                dyad_weights: vec![DyadWeight {
+                    actor_a: "B".to_string(),
+                    actor_b: "A".to_string(),
+                    weight: 3.0,
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        model.register_actor("A", Some(vec![0.8, 0.1, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.1, 0.8]), None);
+        model.register_actor("C", Some(vec![0.4, 0.3, 0.3]), None);
+
+        let index = model.system_risk_index().unwrap();
+        assert_eq!(index.dyads.len(), 3);
+        assert_eq!(index.dyads[0].weight, 3.0);
+        let sum: f64 = index.dyads.iter().map(|d| d.contribution).sum();
+        assert!((index.value - sum).abs() < 1e-12);
+        let d = &index.dyads[0];
+        assert!((d.contribution - 3.0 * d.phi * d.escalation_probability / 5.0).abs() < 1e-12);
+
+        for t in 0..8 {
+            model.record_system_risk_index(t).unwrap();
+        }
+        assert_eq!(model.risk_index_history().len(), 8);
+        assert_eq!(
+            model.system_risk_index().unwrap().phase,
+            VariancePhase::Stable
+        );
+        assert!(model.record_system_risk_index(3).is_err());
+
+        // Restricting to listed dyads
+        model.config.risk_index.default_weight = 0.0;
+        assert_eq!(model.system_risk_index().unwrap().dyads.len(), 1);
+    }
+}
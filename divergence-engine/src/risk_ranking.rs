@@ -0,0 +1,281 @@
+//! Incrementally maintained ranking of the riskiest dyads.
+//!
+//! Dashboards refresh a "top N" list far more often than the model's
+//! pairwise state changes wholesale, and rescanning all `n(n-1)/2` dyads per
+//! refresh does not scale. [`RiskRanking`] instead keeps one indexed max-heap
+//! per metric, keyed by dyad:
+//!
+//! ```text
+//! update(dyad)  ──▶ position[dyad] ──▶ sift up/down     O(log n)
+//! top(k)        ──▶ best-first walk from the root       O(k log k)
+//! ```
+//!
+//! A dyad's score is replaced whenever it is re-evaluated, so the ranking
+//! reflects each dyad's most recent evaluation rather than a fresh scan.
+
+use crate::model::ActorId;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+type DyadKey = (ActorId, ActorId);
+
+/// Score a [`RiskRanking`] orders dyads by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RiskMetric {
+    /// Conflict potential Φ
+    Phi,
+    /// Predicted escalation probability
+    EscalationProbability,
+}
+
+/// A dyad's latest evaluation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedDyad {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub phi: f64,
+    pub escalation_probability: f64,
+    /// Event time of the evaluation
+    pub timestamp_ms: i64,
+}
+
+impl RankedDyad {
+    pub fn score(&self, metric: RiskMetric) -> f64 {
+        match metric {
+            RiskMetric::Phi => self.phi,
+            RiskMetric::EscalationProbability => self.escalation_probability,
+        }
+    }
+}
+
+/// Binary max-heap of dyad scores with a dyad → slot index
+#[derive(Debug, Clone, Default)]
+struct IndexedMaxHeap {
+    nodes: Vec<(DyadKey, f64)>,
+    position: HashMap<DyadKey, usize>,
+}
+
+impl IndexedMaxHeap {
+    fn set(&mut self, key: DyadKey, score: f64) {
+        match self.position.get(&key) {
+            Some(&i) => {
+                let old = std::mem::replace(&mut self.nodes[i].1, score);
+                if score.total_cmp(&old) == Ordering::Greater {
+                    self.sift_up(i);
+                } else {
+                    self.sift_down(i);
+                }
+            }
+            None => {
+                self.nodes.push((key, score));
+                self.position.insert(key, self.nodes.len() - 1);
+                self.sift_up(self.nodes.len() - 1);
+            }
+        }
+    }
+
+    fn greater(&self, i: usize, j: usize) -> bool {
+        self.nodes[i].1.total_cmp(&self.nodes[j].1) == Ordering::Greater
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.nodes.swap(i, j);
+        self.position.insert(self.nodes[i].0, i);
+        self.position.insert(self.nodes[j].0, j);
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !self.greater(i, parent) {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut largest = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.nodes.len() && self.greater(child, largest) {
+                    largest = child;
+                }
+            }
+            if largest == i {
+                break;
+            }
+            self.swap(i, largest);
+            i = largest;
+        }
+    }
+
+    /// Keys of the `k` highest scores, highest first
+    ///
+    /// Every node outranks its children, so the next-highest score is always
+    /// a child of a node already taken; a frontier of those suffices.
+    fn top(&self, k: usize) -> Vec<DyadKey> {
+        struct Slot(f64, usize);
+        impl PartialEq for Slot {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+        impl Eq for Slot {}
+        impl PartialOrd for Slot {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Slot {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        let mut out = Vec::with_capacity(k.min(self.nodes.len()));
+        let mut frontier = BinaryHeap::new();
+        if let Some(root) = self.nodes.first() {
+            frontier.push(Slot(root.1, 0));
+        }
+        while out.len() < k {
+            let Some(Slot(_, i)) = frontier.pop() else {
+                break;
+            };
+            out.push(self.nodes[i].0);
+            for child in [2 * i + 1, 2 * i + 2] {
+                if let Some(node) = self.nodes.get(child) {
+                    frontier.push(Slot(node.1, child));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Riskiest dyads by Φ and by escalation probability
+#[derive(Debug, Clone, Default)]
+pub struct RiskRanking {
+    dyads: HashMap<DyadKey, RankedDyad>,
+    by_phi: IndexedMaxHeap,
+    by_escalation: IndexedMaxHeap,
+}
+
+impl RiskRanking {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a dyad's latest evaluation, replacing any earlier one
+    pub fn update(&mut self, a: ActorId, b: ActorId, dyad: RankedDyad) {
+        let key = ActorId::dyad(a, b);
+        self.by_phi.set(key, dyad.phi);
+        self.by_escalation.set(key, dyad.escalation_probability);
+        self.dyads.insert(key, dyad);
+    }
+
+    /// The `k` highest-scoring dyads, highest first
+    pub fn top(&self, k: usize, metric: RiskMetric) -> Vec<RankedDyad> {
+        let heap = match metric {
+            RiskMetric::Phi => &self.by_phi,
+            RiskMetric::EscalationProbability => &self.by_escalation,
+        };
+        heap.top(k)
+            .into_iter()
+            .map(|key| self.dyads[&key].clone())
+            .collect()
+    }
+
+    /// Number of dyads ranked
+    pub fn len(&self) -> usize {
+        self.dyads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dyads.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::{RngCore, SplitMix64};
+
+    fn dyad(a: u32, b: u32, phi: f64, p: f64) -> RankedDyad {
+        RankedDyad {
+            actor_a: a.to_string(),
+            actor_b: b.to_string(),
+            phi,
+            escalation_probability: p,
+            timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_top_matches_full_sort() {
+        let mut ranking = RiskRanking::new();
+        let mut latest: HashMap<DyadKey, (f64, f64)> = HashMap::new();
+        let mut rng = SplitMix64::new(7);
+        for _ in 0..2_000 {
+            let a = (rng.next_u64() % 12) as u32;
+            let b = (rng.next_u64() % 12) as u32;
+            if a == b {
+                continue;
+            }
+            let (phi, p) = (
+                rng.next_u32() as f64 / 1e9,
+                rng.next_u32() as f64 / u32::MAX as f64,
+            );
+            // Either orientation addresses the same dyad
+            ranking.update(ActorId(b), ActorId(a), dyad(a, b, phi, p));
+            latest.insert(ActorId::dyad(ActorId(a), ActorId(b)), (phi, p));
+        }
+        assert_eq!(ranking.len(), latest.len());
+
+        let mut phis: Vec<f64> = latest.values().map(|v| v.0).collect();
+        phis.sort_by(|x, y| y.total_cmp(x));
+        let top: Vec<f64> = ranking
+            .top(10, RiskMetric::Phi)
+            .iter()
+            .map(|d| d.phi)
+            .collect();
+        assert_eq!(top, phis[..10]);
+
+        let mut ps: Vec<f64> = latest.values().map(|v| v.1).collect();
+        ps.sort_by(|x, y| y.total_cmp(x));
+        let top = ranking.top(usize::MAX, RiskMetric::EscalationProbability);
+        assert_eq!(
+            top.iter()
+                .map(|d| d.escalation_probability)
+                .collect::<Vec<_>>(),
+            ps
+        );
+    }
+
+    #[test]
+    fn test_update_moves_dyad() {
+        let mut ranking = RiskRanking::new();
+        ranking.update(ActorId(0), ActorId(1), dyad(0, 1, 3.0, 0.1));
+        ranking.update(ActorId(0), ActorId(2), dyad(0, 2, 2.0, 0.2));
+        ranking.update(ActorId(1), ActorId(2), dyad(1, 2, 1.0, 0.3));
+        assert_eq!(ranking.top(1, RiskMetric::Phi)[0].actor_b, "1");
+        assert_eq!(
+            ranking.top(1, RiskMetric::EscalationProbability)[0].actor_a,
+            "1"
+        );
+
+        ranking.update(ActorId(1), ActorId(0), dyad(0, 1, 0.5, 0.9));
+        let top = ranking.top(3, RiskMetric::Phi);
+        assert_eq!(
+            top.iter().map(|d| d.phi).collect::<Vec<_>>(),
+            [2.0, 1.0, 0.5]
+        );
+        assert_eq!(
+            ranking.top(1, RiskMetric::EscalationProbability)[0].phi,
+            0.5
+        );
+        assert!(RiskRanking::new().top(5, RiskMetric::Phi).is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+//! Designated rivals and asymmetric learning.
+//!
+//! Each actor can be given one rival. Updates are then classified by what
+//! they do to the actor's divergence from that rival, and conflictual ones
+//! (those that widen it) are learned faster when
+//! `ModelConfig::negativity_bias` exceeds 1:
+//!
+//! ```text
+//! η' = min(1, b · η)   if Φ(C_new, C_rival) > Φ(C_old, C_rival)
+//! η' = η               otherwise
+//! ```
+//!
+//! where `C_new` is the scheme after a trial update at plain η. A bias of 1
+//! (the default) is symmetric learning; below 1 conflictual observations
+//! are discounted instead. Actors without a rival always learn at η.
+//!
+//! ## Polarization feedback
+//!
+//! With `ModelConfig::polarization_feedback` set to κ, divergence feeds
+//! back into learning: before the update, the observation is pushed away
+//! from the rival in proportion to the current Φ,
+//!
+//! ```text
+//! obs' ∝ max(0, obs + κ · Φ(C, C_rival) · (C - C_rival))
+//! ```
+//!
+//! so divergent dyads diverge faster and, left alone, run away. The Monte
+//! Carlo simulation applies the same feedback with each actor of the dyad
+//! as the other's rival.
+
+use crate::error::{DivergenceError, Result};
+use crate::model::{CompressionDynamicsModel, ModelConfig};
+use crate::scheme::CompressionScheme;
+use std::borrow::Cow;
+
+impl CompressionDynamicsModel {
+    /// Designate `rival` as the rival of `actor_id`, replacing any previous
+    /// one
+    ///
+    /// Rivalry is one-way; call again with the roles swapped for a mutual
+    /// rivalry.
+    pub fn set_rival(&mut self, actor_id: &str, rival: &str) -> Result<()> {
+        let actor_id = self.require_actor(actor_id)?.to_string();
+        let rival = self.require_actor(rival)?.to_string();
+        if actor_id == rival {
+            return Err(DivergenceError::ConfigError(format!(
+                "{} cannot be its own rival",
+                actor_id
+            )));
+        }
+        self.rivals.insert(actor_id, rival);
+        Ok(())
+    }
+
+    /// Remove an actor's rival, returning it
+    pub fn remove_rival(&mut self, actor_id: &str) -> Option<String> {
+        let actor_id = self.resolve_actor(actor_id).unwrap_or(actor_id).to_string();
+        self.rivals.shift_remove(&actor_id)
+    }
+
+    /// Designated rival of an actor, if any
+    pub fn rival_of(&self, actor_id: &str) -> Option<&str> {
+        let actor_id = self.resolve_actor(actor_id).unwrap_or(actor_id);
+        self.rivals.get(actor_id).map(String::as_str)
+    }
+}
+
+impl ModelConfig {
+    /// Whether updates depend on the actor's rival
+    pub(crate) fn uses_rivals(&self) -> bool {
+        self.negativity_bias != 1.0 || self.polarization_feedback.is_some()
+    }
+}
+
+/// Observation pushed away from the rival by polarization feedback
+pub(crate) fn polarized_observation<'a>(
+    strength: f64,
+    scheme: &CompressionScheme,
+    rival: &CompressionScheme,
+    observation: &'a [f64],
+) -> Result<Cow<'a, [f64]>> {
+    let phi = scheme.symmetric_divergence(rival)?;
+    let push = strength * phi;
+    if push == 0.0 {
+        return Ok(Cow::Borrowed(observation));
+    }
+    let pushed: Vec<f64> = observation
+        .iter()
+        .zip(scheme.distribution().iter().zip(rival.distribution()))
+        .map(|(&o, (&c, &r))| (o + push * (c - r)).max(0.0))
+        .collect();
+    let total: f64 = pushed.iter().sum();
+    Ok(Cow::Owned(pushed.iter().map(|x| x / total).collect()))
+}
+
+/// Observation to learn from after polarization feedback, if any
+pub(crate) fn feedback_observation<'a>(
+    config: &ModelConfig,
+    scheme: &CompressionScheme,
+    rival: Option<&CompressionScheme>,
+    observation: &'a [f64],
+) -> Result<Cow<'a, [f64]>> {
+    match (config.polarization_feedback, rival) {
+        (Some(strength), Some(rival)) => {
+            polarized_observation(strength, scheme, rival, observation)
+        }
+        _ => Ok(Cow::Borrowed(observation)),
+    }
+}
+
+/// Learning rate for an update of `scheme` by a normalized `observation`,
+/// given the actor's rival scheme
+pub(crate) fn biased_learning_rate(
+    config: &ModelConfig,
+    scheme: &CompressionScheme,
+    rival: Option<&CompressionScheme>,
+    observation: &[f64],
+) -> Result<f64> {
+    let eta = config.learning_rate;
+    let Some(rival) = rival else {
+        return Ok(eta);
+    };
+    if config.negativity_bias == 1.0 {
+        return Ok(eta);
+    }
+    let before = scheme.symmetric_divergence(rival)?;
+    let mut trial = scheme.clone();
+    trial.update(observation, eta)?;
+    if trial.symmetric_divergence(rival)? > before {
+        Ok((eta * config.negativity_bias.max(0.0)).min(1.0))
+    } else {
+        Ok(eta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::SharedModel;
+
+    #[test]
+    fn test_negativity_bias() {
+        let config = ModelConfig {
+            n_categories: 2,
+            negativity_bias: 3.0,
+            ..Default::default()
+        };
+        let mut model = CompressionDynamicsModel::with_config(config);
+        for actor in ["A", "B", "R"] {
+            model.register_actor(actor, Some(vec![0.5, 0.5]), None);
+        }
+        model.register_actor("RIVAL", Some(vec![0.2, 0.8]), None);
+        model.set_rival("A", "RIVAL").unwrap();
+        model.set_rival("R", "RIVAL").unwrap();
+        assert_eq!(model.rival_of("A"), Some("RIVAL"));
+        let shared = SharedModel::new(model.clone());
+
+        // Moving away from the rival is learned three times as fast
+        let away = [1.0, 0.0];
+        let a = model
+            .update_scheme("A", &away, Some(0))
+            .unwrap()
+            .distribution()[0];
+        let b = model
+            .update_scheme("B", &away, Some(0))
+            .unwrap()
+            .distribution()[0];
+        assert!((a - 0.65).abs() < 1e-6);
+        assert!((b - 0.55).abs() < 1e-6);
+
+        // Moving toward it is not
+        let toward = [0.0, 1.0];
+        let r = model
+            .update_scheme("R", &toward, Some(0))
+            .unwrap()
+            .distribution()[0];
+        assert!((r - 0.45).abs() < 1e-6);
+
+        // The sharded model learns the same way and keeps the rivals
+        let shared_a = shared
+            .update_scheme("A", &away, Some(0))
+            .unwrap()
+            .distribution()[0];
+        assert!((shared_a - a).abs() < 1e-12);
+        assert_eq!(shared.snapshot().rival_of("R"), Some("RIVAL"));
+
+        assert_eq!(model.remove_rival("A").as_deref(), Some("RIVAL"));
+        assert!(model.set_rival("A", "A").is_err());
+        assert!(model.set_rival("A", "Z").is_err());
+    }
+
+    #[test]
+    fn test_polarization_feedback() {
+        let setup = |feedback: Option<f64>| {
+            let config = ModelConfig {
+                n_categories: 2,
+                polarization_feedback: feedback,
+                ..Default::default()
+            };
+            let mut model = CompressionDynamicsModel::with_config(config);
+            model.register_actor("A", Some(vec![0.6, 0.4]), None);
+            model.register_actor("B", Some(vec![0.4, 0.6]), None);
+            model.set_rival("A", "B").unwrap();
+            model.set_rival("B", "A").unwrap();
+            model
+        };
+        let run = |feedback: Option<f64>| {
+            let mut model = setup(feedback);
+            let start = model.peek_potential("A", "B").unwrap().phi;
+            // Both see the same neutral events
+            for t in 0..10 {
+                model.update_scheme("A", &[0.5, 0.5], Some(t)).unwrap();
+                model.update_scheme("B", &[0.5, 0.5], Some(t)).unwrap();
+            }
+            let end = model.peek_potential("A", "B").unwrap().phi;
+            (model, start, end)
+        };
+
+        let (_, start, end) = run(None);
+        assert!(end < start);
+        // Strong feedback runs away despite neutral evidence
+        let (model, start, end) = run(Some(5.0));
+        assert!(end > start);
+
+        // The sharded model feeds back the same way
+        let shared = SharedModel::new(setup(Some(5.0)));
+        for t in 0..10 {
+            shared.update_scheme("A", &[0.5, 0.5], Some(t)).unwrap();
+            shared.update_scheme("B", &[0.5, 0.5], Some(t)).unwrap();
+        }
+        let shared_end = shared.conflict_potential("A", "B").unwrap().phi;
+        assert!((shared_end - end).abs() < 1e-12);
+
+        let open_loop = run(None).0.simulate_escalation_paths("A", "B", 10, 200);
+        let closed_loop = model.simulate_escalation_paths("A", "B", 10, 200);
+        assert!(
+            closed_loop.unwrap().phi_quantiles[9].p50 > open_loop.unwrap().phi_quantiles[9].p50
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Robustness of a dyad's Φ to observation noise.
+//!
+//! Schemes are estimated from noisy data, so a Φ that crosses a risk
+//! threshold only because of a few stray observations deserves less trust
+//! than one that holds up when the inputs wobble. [`perturb`] draws a noisy
+//! copy of a scheme; [`phi_robustness`](CompressionDynamicsModel::phi_robustness)
+//! perturbs both sides of a dyad `n` times and reports how far Φ moves and
+//! how often the risk level changes:
+//!
+//! ```text
+//! stability = share of samples with RiskLevel(Φ̃) = RiskLevel(Φ)
+//! cv        = sd(Φ̃) / Φ
+//! score     = stability / (1 + cv)                 ∈ [0, 1]
+//! ```
+
+use crate::divergence::EPSILON;
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::rng::{standard_normal, uniform, RngCore, SeedableRng, SplitMix64};
+use crate::scheme::{CompressionScheme, ConflictPotential, RiskLevel};
+use serde::{Deserialize, Serialize};
+
+/// Seed used by [`phi_robustness`](CompressionDynamicsModel::phi_robustness)
+pub const ROBUSTNESS_SEED: u64 = 0x5eed_0b5e;
+
+/// Noise applied to a scheme's distribution
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NoiseModel {
+    /// Multiplicative `p_i · exp(σ·z_i)`, renormalized
+    LogNormal { sigma: f64 },
+    /// Additive `p_i + σ·z_i`, clipped at zero and renormalized
+    Gaussian { sigma: f64 },
+    /// `(1 − ε)·p + ε·u` with `u` a uniformly random point on the simplex
+    Mixture { epsilon: f64 },
+}
+
+impl Default for NoiseModel {
+    fn default() -> Self {
+        NoiseModel::LogNormal { sigma: 0.1 }
+    }
+}
+
+impl NoiseModel {
+    /// Check the noise scale
+    pub fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            NoiseModel::LogNormal { sigma } | NoiseModel::Gaussian { sigma } => {
+                sigma.is_finite() && sigma >= 0.0
+            }
+            NoiseModel::Mixture { epsilon } => (0.0..=1.0).contains(&epsilon),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(DivergenceError::ConfigError(format!(
+                "Invalid noise model {:?}",
+                self
+            )))
+        }
+    }
+
+    fn apply<R: RngCore + ?Sized>(&self, distribution: &[f64], rng: &mut R) -> Vec<f64> {
+        match *self {
+            NoiseModel::LogNormal { sigma } => distribution
+                .iter()
+                .map(|p| p * (sigma * standard_normal(rng)).exp())
+                .collect(),
+            NoiseModel::Gaussian { sigma } => distribution
+                .iter()
+                .map(|p| (p + sigma * standard_normal(rng)).max(0.0))
+                .collect(),
+            NoiseModel::Mixture { epsilon } => {
+                // Normalized exponentials are uniform on the simplex
+                let u: Vec<f64> = distribution
+                    .iter()
+                    .map(|_| -uniform(rng).max(f64::MIN_POSITIVE).ln())
+                    .collect();
+                let total: f64 = u.iter().sum();
+                distribution
+                    .iter()
+                    .zip(&u)
+                    .map(|(p, x)| (1.0 - epsilon) * p + epsilon * x / total)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Noisy copy of a scheme
+///
+/// The copy keeps the actor ID, categories and smoothing, and is untimed
+/// and without prior or observations.
+pub fn perturb<R: RngCore + ?Sized>(
+    scheme: &CompressionScheme,
+    noise: &NoiseModel,
+    rng: &mut R,
+) -> Result<CompressionScheme> {
+    noise.validate()?;
+    let distribution = noise.apply(scheme.distribution(), rng);
+    Ok(scheme.derived(scheme.actor_id.clone(), distribution))
+}
+
+/// Stability of a dyad's Φ and risk level under observation noise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhiRobustness {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub noise: NoiseModel,
+    pub n_samples: usize,
+    /// Unperturbed Φ
+    pub phi: f64,
+    pub risk_level: RiskLevel,
+    pub mean_phi: f64,
+    pub std_phi: f64,
+    /// Share of samples keeping the unperturbed risk level
+    pub risk_stability: f64,
+    /// `risk_stability / (1 + sd(Φ̃)/Φ)`; 1 = fully robust
+    pub score: f64,
+}
+
+impl CompressionDynamicsModel {
+    /// Robustness of a dyad's Φ to `n` draws of `noise`, reproducibly
+    /// seeded with [`ROBUSTNESS_SEED`]
+    pub fn phi_robustness(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        noise: &NoiseModel,
+        n: usize,
+    ) -> Result<PhiRobustness> {
+        let mut rng = SplitMix64::seed_from_u64(ROBUSTNESS_SEED);
+        self.phi_robustness_with_rng(actor_a, actor_b, noise, n, &mut rng)
+    }
+
+    /// [`phi_robustness`](Self::phi_robustness) drawing from `rng`
+    pub fn phi_robustness_with_rng<R: RngCore + ?Sized>(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        noise: &NoiseModel,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<PhiRobustness> {
+        if n == 0 {
+            return Err(DivergenceError::ConfigError(
+                "Robustness needs at least one sample".to_string(),
+            ));
+        }
+        noise.validate()?;
+        let scheme_a = self.scheme_for(actor_a)?;
+        let scheme_b = self.scheme_for(actor_b)?;
+        let phi = ConflictPotential::compute(scheme_a, scheme_b)?.phi;
+        let risk_level = RiskLevel::from_phi(phi);
+
+        let mut phis = Vec::with_capacity(n);
+        for _ in 0..n {
+            let a = perturb(scheme_a, noise, rng)?;
+            let b = perturb(scheme_b, noise, rng)?;
+            phis.push(a.symmetric_divergence(&b)?);
+        }
+
+        let mean_phi = phis.iter().sum::<f64>() / n as f64;
+        let std_phi = (phis.iter().map(|p| (p - mean_phi).powi(2)).sum::<f64>() / n as f64).sqrt();
+        let kept = phis
+            .iter()
+            .filter(|&&p| RiskLevel::from_phi(p) == risk_level)
+            .count();
+        let risk_stability = kept as f64 / n as f64;
+
+        Ok(PhiRobustness {
+            actor_a: scheme_a.actor_id.clone(),
+            actor_b: scheme_b.actor_id.clone(),
+            noise: *noise,
+            n_samples: n,
+            phi,
+            risk_level,
+            mean_phi,
+            std_phi,
+            risk_stability,
+            score: risk_stability / (1.0 + std_phi / phi.max(EPSILON)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perturb_keeps_simplex() {
+        let scheme = CompressionScheme::new("A", vec![0.7, 0.2, 0.1], None);
+        let mut rng = SplitMix64::seed_from_u64(3);
+        for noise in [
+            NoiseModel::LogNormal { sigma: 0.5 },
+            NoiseModel::Gaussian { sigma: 0.2 },
+            NoiseModel::Mixture { epsilon: 0.3 },
+        ] {
+            let noisy = perturb(&scheme, &noise, &mut rng).unwrap();
+            assert_eq!(noisy.actor_id, "A");
+            assert!((noisy.distribution().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!(noisy.distribution() != scheme.distribution());
+        }
+        assert!(perturb(&scheme, &NoiseModel::Mixture { epsilon: 2.0 }, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_phi_robustness() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.9, 0.05, 0.05]), None);
+        model.register_actor("B", Some(vec![0.05, 0.05, 0.9]), None);
+        model.register_actor("C", Some(vec![0.4, 0.3, 0.3]), None);
+
+        let noise = NoiseModel::default();
+        let far = model.phi_robustness("A", "B", &noise, 200).unwrap();
+        assert_eq!(far.n_samples, 200);
+        assert!(far.score > 0.8);
+        assert_eq!(far, model.phi_robustness("A", "B", &noise, 200).unwrap());
+
+        // Φ near the Low/Moderate boundary flips under heavier noise
+        let loud = NoiseModel::LogNormal { sigma: 0.8 };
+        let near = model.phi_robustness("A", "C", &loud, 200).unwrap();
+        assert!(near.score < far.score);
+
+        assert!(model.phi_robustness("A", "B", &noise, 0).is_err());
+        assert!(model.phi_robustness("A", "Z", &noise, 10).is_err());
+    }
+}
@@ -3,12 +3,18 @@
 //! The scheme captures HOW an actor "compresses" the world into
 //! meaningful categories - their predictive model of reality.
 
+use crate::archetype::prior_learning_rate;
 use crate::divergence::{
-    bhattacharyya_coefficient, cosine_similarity, entropy, hellinger_distance, jensen_shannon,
-    kl_divergence, normalize, smooth, symmetric_kl, DivergenceMetrics, SMOOTHING,
+    bhattacharyya_coefficient, cosine_similarity, cross_entropy, entropy, hellinger_distance,
+    jensen_shannon, kl_divergence, kl_divergence_with, normalize, perplexity, symmetric_kl,
+    symmetric_kl_with, wasserstein_1d, DivergenceMetrics, Smoothing, SupportPolicy,
 };
 use crate::error::{DivergenceError, Result};
+use crate::estimation::{estimate_distribution, CountPrior};
+use crate::morph::{interpolate, morph_sequence, Interpolation};
+use crate::registry::{same_registry, CategoryMapping, CategoryRegistry};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Source of compression scheme data
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -39,17 +45,42 @@ pub struct CompressionScheme {
     distribution: Vec<f64>,
 
     /// Category labels (optional, for interpretability)
+    ///
+    /// Empty when the scheme references a shared [`CategoryRegistry`].
     pub categories: Vec<String>,
 
+    /// Shared category space (not serialized; re-attached by the model)
+    #[serde(skip)]
+    registry: Option<Arc<CategoryRegistry>>,
+
     /// Unix timestamp in milliseconds (for WASM compatibility)
     pub timestamp_ms: Option<i64>,
 
     /// Source of this scheme
     pub source: SchemeSource,
 
+    /// Whether category order is meaningful (e.g. Goldstein bins)
+    ///
+    /// Enables earth mover's distance between schemes.
+    #[serde(default)]
+    pub ordered_categories: bool,
+
     /// Additional metadata
     #[serde(default)]
-    pub metadata: std::collections::HashMap<String, String>,
+    pub metadata: std::collections::BTreeMap<String, String>,
+
+    /// Smoothing re-applied after every update
+    #[serde(default)]
+    smoothing: Smoothing,
+
+    /// Pseudo-observation weight of the initial distribution, if it is an
+    /// informed prior (see [`crate::archetype`])
+    #[serde(default)]
+    prior_strength: Option<f64>,
+
+    /// Number of observations folded in via `update`
+    #[serde(default)]
+    observations: u64,
 }
 
 impl CompressionScheme {
@@ -58,6 +89,16 @@ impl CompressionScheme {
         actor_id: impl Into<String>,
         distribution: Vec<f64>,
         categories: Option<Vec<String>>,
+    ) -> Self {
+        Self::new_with_smoothing(actor_id, distribution, categories, Smoothing::default())
+    }
+
+    /// Create a new compression scheme with an explicit smoothing strategy
+    pub fn new_with_smoothing(
+        actor_id: impl Into<String>,
+        distribution: Vec<f64>,
+        categories: Option<Vec<String>>,
+        smoothing: Smoothing,
     ) -> Self {
         let actor_id = actor_id.into();
         let n = distribution.len();
@@ -69,9 +110,14 @@ impl CompressionScheme {
             actor_id,
             distribution,
             categories,
+            registry: None,
             timestamp_ms: None,
             source: SchemeSource::default(),
-            metadata: std::collections::HashMap::new(),
+            ordered_categories: false,
+            metadata: std::collections::BTreeMap::new(),
+            smoothing,
+            prior_strength: None,
+            observations: 0,
         };
 
         // Normalize and smooth
@@ -85,10 +131,108 @@ impl CompressionScheme {
         Self::new(actor_id, distribution, None)
     }
 
-    /// Normalize distribution to sum to 1.0 and apply Laplace smoothing
+    /// Create a scheme from raw category counts under a shrinkage prior
+    pub fn from_counts(
+        actor_id: impl Into<String>,
+        counts: &[f64],
+        prior: CountPrior,
+    ) -> Result<Self> {
+        let distribution = estimate_distribution(counts, prior)?;
+        Ok(Self::new(actor_id, distribution, None))
+    }
+
+    /// Reference a shared category registry instead of per-scheme labels
+    pub fn with_registry(mut self, registry: Arc<CategoryRegistry>) -> Result<Self> {
+        if registry.len() != self.distribution.len() {
+            return Err(DivergenceError::DimensionMismatch {
+                expected: registry.len(),
+                got: self.distribution.len(),
+            });
+        }
+        self.categories = Vec::new();
+        self.registry = Some(registry);
+        Ok(self)
+    }
+
+    /// Shared category registry, if any
+    pub fn registry(&self) -> Option<&Arc<CategoryRegistry>> {
+        self.registry.as_ref()
+    }
+
+    /// Smoothing strategy applied after updates
+    pub fn smoothing(&self) -> Smoothing {
+        self.smoothing
+    }
+
+    /// Switch smoothing strategy and re-apply it to the current distribution
+    ///
+    /// Mass already added by a previous strategy is not removed; construct
+    /// with [`new_with_smoothing`](Self::new_with_smoothing) for a clean start.
+    pub fn with_smoothing(mut self, smoothing: Smoothing) -> Self {
+        self.smoothing = smoothing;
+        self.normalize_and_smooth();
+        self
+    }
+
+    pub(crate) fn set_registry(&mut self, registry: Option<Arc<CategoryRegistry>>) {
+        if registry.is_some() {
+            self.categories = Vec::new();
+        }
+        self.registry = registry;
+    }
+
+    /// Name of the category at an index
+    pub fn category_name(&self, idx: usize) -> String {
+        if let Some(name) = self.registry.as_ref().and_then(|r| r.name(idx)) {
+            return name.to_string();
+        }
+        self.categories
+            .get(idx)
+            .cloned()
+            .unwrap_or_else(|| format!("cat_{}", idx))
+    }
+
+    /// Index of a named category
+    pub fn category_index(&self, name: &str) -> Option<usize> {
+        match &self.registry {
+            Some(registry) => registry.get(name),
+            None => self.categories.iter().position(|c| c == name),
+        }
+    }
+
+    /// Append a category holding only smoothing mass
+    ///
+    /// Existing mass is scaled down so the new category receives the
+    /// strategy's floor mass and the distribution still sums to 1.0. The
+    /// caller is responsible for keeping any shared registry in step.
+    pub(crate) fn grow(&mut self, label: Option<String>) {
+        let epsilon = self.smoothing.floor_mass(self.distribution.len() + 1);
+        for x in self.distribution.iter_mut() {
+            *x *= 1.0 - epsilon;
+        }
+        self.distribution.push(epsilon);
+
+        if let Some(label) = label {
+            if self.registry.is_none() {
+                self.categories.push(label);
+            }
+        }
+    }
+
+    /// Project this scheme onto a new category space
+    ///
+    /// Any shared registry is detached; the new labels are stored locally.
+    pub fn remap(&mut self, mapping: &CategoryMapping) -> Result<()> {
+        self.distribution = mapping.apply(&self.distribution)?;
+        self.categories = mapping.new_categories.clone();
+        self.registry = None;
+        self.normalize_and_smooth();
+        Ok(())
+    }
+
+    /// Normalize distribution to sum to 1.0 and apply the smoothing strategy
     fn normalize_and_smooth(&mut self) {
-        normalize(&mut self.distribution);
-        smooth(&mut self.distribution, SMOOTHING);
+        self.smoothing.apply(&mut self.distribution);
     }
 
     /// Get the distribution as a slice
@@ -134,6 +278,37 @@ impl CompressionScheme {
         }
     }
 
+    /// Perplexity 2^H: the effective number of categories attended to
+    #[inline]
+    pub fn perplexity(&self) -> f64 {
+        perplexity(&self.distribution)
+    }
+
+    /// Cross-entropy H(self, other) in bits
+    ///
+    /// Expected bits to encode self's distribution with other's scheme;
+    /// exceeds self's own entropy by D_KL(self || other).
+    pub fn cross_entropy(&self, other: &CompressionScheme) -> Result<f64> {
+        cross_entropy(&self.distribution, &other.distribution)
+    }
+
+    /// Surprisal of an observation under this scheme, in bits
+    ///
+    /// The cross-entropy H(o, self) of the normalized observation o: how
+    /// many bits the actor's worldview spends encoding what happened. A
+    /// one-hot observation of category i scores -log2(p_i). Observations
+    /// summing to zero count as uniform, as in [`update`](Self::update).
+    pub fn surprisal(&self, observation: &[f64]) -> Result<f64> {
+        let sum: f64 = observation.iter().sum();
+        if sum > 0.0 {
+            let normalized: Vec<f64> = observation.iter().map(|&x| x / sum).collect();
+            cross_entropy(&normalized, &self.distribution)
+        } else {
+            let uniform = vec![1.0 / observation.len() as f64; observation.len()];
+            cross_entropy(&uniform, &self.distribution)
+        }
+    }
+
     /// KL divergence D_KL(self || other)
     ///
     /// Measures information lost when using other's compression
@@ -145,6 +320,24 @@ impl CompressionScheme {
         kl_divergence(&self.distribution, &other.distribution)
     }
 
+    /// KL divergence D_KL(self || other) under an absolute-continuity policy
+    pub fn kl_divergence_with(
+        &self,
+        other: &CompressionScheme,
+        policy: SupportPolicy,
+    ) -> Result<f64> {
+        kl_divergence_with(&self.distribution, &other.distribution, policy)
+    }
+
+    /// Symmetric KL divergence under an absolute-continuity policy
+    pub fn symmetric_divergence_with(
+        &self,
+        other: &CompressionScheme,
+        policy: SupportPolicy,
+    ) -> Result<f64> {
+        symmetric_kl_with(&self.distribution, &other.distribution, policy)
+    }
+
     /// Symmetric KL divergence (conflict potential)
     ///
     /// Φ(A,B) = D_KL(A||B) + D_KL(B||A)
@@ -174,11 +367,128 @@ impl CompressionScheme {
         cosine_similarity(&self.distribution, &other.distribution)
     }
 
+    /// Earth mover's distance over ordered categories
+    ///
+    /// Errors unless both schemes have `ordered_categories` set.
+    pub fn earth_movers_distance(&self, other: &CompressionScheme) -> Result<f64> {
+        if !(self.ordered_categories && other.ordered_categories) {
+            return Err(DivergenceError::ConfigError(format!(
+                "Earth mover's distance needs ordered categories ({} vs {})",
+                self.actor_id, other.actor_id
+            )));
+        }
+        wasserstein_1d(&self.distribution, &other.distribution)
+    }
+
     /// Compute all divergence metrics at once
     pub fn all_metrics(&self, other: &CompressionScheme) -> Result<DivergenceMetrics> {
         DivergenceMetrics::compute(&self.distribution, &other.distribution)
     }
 
+    /// Intermediate scheme at position `t ∈ [0, 1]` between self and other
+    ///
+    /// The result keeps this scheme's categories and smoothing, starts with
+    /// no observations, and is labelled `"{self}->{other}@{t}"`.
+    pub fn interpolate(
+        &self,
+        other: &CompressionScheme,
+        t: f64,
+        method: Interpolation,
+    ) -> Result<CompressionScheme> {
+        let distribution = interpolate(&self.distribution, &other.distribution, t, method)?;
+        Ok(self.morphed(other, t, distribution))
+    }
+
+    /// `n_steps + 1` schemes morphing from self (first) into other (last)
+    pub fn morph_sequence(
+        &self,
+        other: &CompressionScheme,
+        n_steps: usize,
+        method: Interpolation,
+    ) -> Result<Vec<CompressionScheme>> {
+        let path = morph_sequence(&self.distribution, &other.distribution, n_steps, method)?;
+        Ok(path
+            .into_iter()
+            .enumerate()
+            .map(|(k, distribution)| self.morphed(other, k as f64 / n_steps as f64, distribution))
+            .collect())
+    }
+
+    fn morphed(&self, other: &CompressionScheme, t: f64, distribution: Vec<f64>) -> Self {
+        let actor_id = format!("{}->{}@{:.3}", self.actor_id, other.actor_id, t);
+        self.derived(actor_id, distribution)
+    }
+
+    /// A new scheme over the same category space with a computed
+    /// distribution, untimed and without prior or observations
+    pub(crate) fn derived(&self, actor_id: String, distribution: Vec<f64>) -> Self {
+        let mut scheme = self.clone();
+        scheme.actor_id = actor_id;
+        scheme.distribution = distribution;
+        scheme.timestamp_ms = None;
+        scheme.prior_strength = None;
+        scheme.observations = 0;
+        scheme.normalize_and_smooth();
+        scheme
+    }
+
+    /// Whether `other` differs from this scheme at most in distribution,
+    /// timestamp and observation count
+    pub(crate) fn same_layout(&self, other: &CompressionScheme) -> bool {
+        self.actor_id == other.actor_id
+            && self.n_categories() == other.n_categories()
+            && self.categories == other.categories
+            && same_registry(self.registry.as_ref(), other.registry.as_ref())
+            && self.source == other.source
+            && self.ordered_categories == other.ordered_categories
+            && self.metadata == other.metadata
+            && self.smoothing == other.smoothing
+            && self.prior_strength == other.prior_strength
+    }
+
+    /// Copy carrying a recorded distribution, normalized but not
+    /// re-smoothed
+    pub(crate) fn restored(
+        &self,
+        mut distribution: Vec<f64>,
+        timestamp_ms: Option<i64>,
+        observations: u64,
+    ) -> Self {
+        normalize(&mut distribution);
+        let mut scheme = self.clone();
+        scheme.distribution = distribution;
+        scheme.timestamp_ms = timestamp_ms;
+        scheme.observations = observations;
+        scheme
+    }
+
+    /// Copy with the distribution tempered by `temperature`
+    ///
+    /// Each probability is raised to `1/t` and the result renormalized:
+    /// `t < 1` sharpens toward the dominant categories, `t > 1` flattens
+    /// toward uniform and `t = 1` returns an equal copy. Zero-probability
+    /// categories stay at zero before smoothing.
+    pub fn with_temperature(&self, temperature: f64) -> Result<Self> {
+        if !(temperature > 0.0 && temperature.is_finite()) {
+            return Err(DivergenceError::ConfigError(format!(
+                "Temperature must be positive and finite, got {}",
+                temperature
+            )));
+        }
+        // Work in log space so small temperatures do not underflow
+        let logits: Vec<f64> = self
+            .distribution
+            .iter()
+            .map(|p| p.ln() / temperature)
+            .collect();
+        let max = logits.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let mut scheme = self.clone();
+        scheme.distribution = logits.iter().map(|l| (l - max).exp()).collect();
+        scheme.normalize_and_smooth();
+        Ok(scheme)
+    }
+
     /// Get top n categories by probability mass
     pub fn top_categories(&self, n: usize) -> Vec<(String, f64)> {
         let mut indexed: Vec<(usize, f64)> = self
@@ -193,20 +503,43 @@ impl CompressionScheme {
         indexed
             .into_iter()
             .take(n)
-            .map(|(i, p)| {
-                let name = self
-                    .categories
-                    .get(i)
-                    .cloned()
-                    .unwrap_or_else(|| format!("cat_{}", i));
-                (name, p)
-            })
+            .map(|(i, p)| (self.category_name(i), p))
             .collect()
     }
 
+    /// Treat the current distribution as a prior worth `strength`
+    /// pseudo-observations
+    pub fn with_prior_strength(mut self, strength: f64) -> Self {
+        self.prior_strength = Some(strength.max(0.0));
+        self
+    }
+
+    /// Prior strength, if the scheme was warm-started from a prior
+    pub fn prior_strength(&self) -> Option<f64> {
+        self.prior_strength
+    }
+
+    /// Number of observations this scheme has been updated with
+    pub fn observation_count(&self) -> u64 {
+        self.observations
+    }
+
+    /// Learning rate the next update will actually apply
+    ///
+    /// Plain η, unless a prior strength is set; then η is raised while the
+    /// prior still dominates.
+    pub fn effective_learning_rate(&self, learning_rate: f64) -> f64 {
+        match self.prior_strength {
+            Some(s) => prior_learning_rate(learning_rate, s, self.observations),
+            None => learning_rate,
+        }
+    }
+
     /// Bayesian update with new observation
     ///
     /// C_new = (1 - η) * C_old + η * observation
+    ///
+    /// η is adjusted by [`effective_learning_rate`](Self::effective_learning_rate).
     pub fn update(&mut self, observation: &[f64], learning_rate: f64) -> Result<()> {
         if observation.len() != self.distribution.len() {
             return Err(DivergenceError::DimensionMismatch {
@@ -224,11 +557,13 @@ impl CompressionScheme {
         };
 
         // Exponential moving average update
+        let learning_rate = self.effective_learning_rate(learning_rate);
         for (dist, obs) in self.distribution.iter_mut().zip(obs_normalized.iter()) {
             *dist = (1.0 - learning_rate) * *dist + learning_rate * obs;
         }
 
         self.normalize_and_smooth();
+        self.observations += 1;
         Ok(())
     }
 
@@ -244,6 +579,12 @@ impl CompressionScheme {
         self
     }
 
+    /// Mark categories as ordered (enables earth mover's distance)
+    pub fn with_ordered_categories(mut self, ordered: bool) -> Self {
+        self.ordered_categories = ordered;
+        self
+    }
+
     /// Add metadata
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.metadata.insert(key.into(), value.into());
@@ -285,6 +626,31 @@ pub struct ConflictPotential {
     /// D_KL(B || A)
     pub kl_b_a: f64,
 
+    /// Earth mover's distance, when both schemes have ordered categories
+    #[serde(default)]
+    pub emd: Option<f64>,
+
+    /// Φ z-scored against this dyad's own recorded history
+    ///
+    /// Filled in by the model; `None` until the dyad has a baseline. With
+    /// seasonality enabled this scores the seasonally adjusted Φ.
+    #[serde(default)]
+    pub phi_z: Option<f64>,
+
+    /// Φ minus the dyad's expected seasonal offset at this timestamp
+    ///
+    /// Filled in by the model when seasonality is configured.
+    #[serde(default)]
+    pub phi_adjusted: Option<f64>,
+
+    /// Shannon entropy of A's scheme at computation time
+    #[serde(default)]
+    pub entropy_a: f64,
+
+    /// Shannon entropy of B's scheme at computation time
+    #[serde(default)]
+    pub entropy_b: f64,
+
     /// Timestamp in milliseconds
     pub timestamp_ms: Option<i64>,
 }
@@ -302,6 +668,12 @@ impl ConflictPotential {
             hellinger: metrics.hellinger,
             kl_a_b: metrics.kl_p_q,
             kl_b_a: metrics.kl_q_p,
+            emd: (scheme_a.ordered_categories && scheme_b.ordered_categories)
+                .then_some(metrics.wasserstein),
+            phi_z: None,
+            phi_adjusted: None,
+            entropy_a: scheme_a.entropy(),
+            entropy_b: scheme_b.entropy(),
             timestamp_ms: None,
         })
     }
@@ -430,6 +802,39 @@ mod tests {
         assert!(scheme.distribution()[0] > 0.25);
     }
 
+    #[test]
+    fn test_from_counts() {
+        let scheme =
+            CompressionScheme::from_counts("A", &[8.0, 2.0, 0.0], CountPrior::Laplace).unwrap();
+        assert_eq!(scheme.n_categories(), 3);
+        assert!(scheme.distribution()[0] > scheme.distribution()[1]);
+        assert!(scheme.distribution()[2] > 0.0);
+
+        assert!(CompressionScheme::from_counts("B", &[], CountPrior::Laplace).is_err());
+    }
+
+    #[test]
+    fn test_shared_registry() {
+        let registry = CategoryRegistry::from_names(["x", "y", "z"]).into_shared();
+        let scheme = CompressionScheme::new("A", vec![0.1, 0.7, 0.2], None)
+            .with_registry(Arc::clone(&registry))
+            .unwrap();
+
+        assert!(scheme.categories.is_empty());
+        assert_eq!(scheme.category_name(1), "y");
+        assert_eq!(scheme.category_index("z"), Some(2));
+        assert_eq!(scheme.top_categories(1)[0].0, "y");
+
+        let wrong = CompressionScheme::uniform("B", 2).with_registry(registry);
+        assert!(matches!(
+            wrong,
+            Err(DivergenceError::DimensionMismatch {
+                expected: 3,
+                got: 2
+            })
+        ));
+    }
+
     #[test]
     fn test_conflict_potential() {
         let a = CompressionScheme::new("USA", vec![0.5, 0.3, 0.2], None);
@@ -441,4 +846,97 @@ mod tests {
         assert_eq!(potential.actor_b, "RUS");
         assert!(potential.phi > 0.0);
     }
+
+    #[test]
+    fn test_ordered_categories_emd() {
+        let goldstein = |id: &str, d: Vec<f64>| {
+            CompressionScheme::new(id, d, None)
+                .with_source(SchemeSource::Goldstein)
+                .with_ordered_categories(true)
+        };
+        let a = goldstein("A", vec![0.8, 0.1, 0.1]);
+        let near = goldstein("B", vec![0.1, 0.8, 0.1]);
+        let far = goldstein("C", vec![0.1, 0.1, 0.8]);
+
+        let emd_near = a.earth_movers_distance(&near).unwrap();
+        let emd_far = a.earth_movers_distance(&far).unwrap();
+        assert!(emd_far > emd_near);
+        assert_eq!(
+            ConflictPotential::compute(&a, &far).unwrap().emd,
+            Some(emd_far)
+        );
+
+        let unordered = CompressionScheme::new("D", vec![0.1, 0.1, 0.8], None);
+        assert!(a.earth_movers_distance(&unordered).is_err());
+        assert!(ConflictPotential::compute(&a, &unordered)
+            .unwrap()
+            .emd
+            .is_none());
+    }
+
+    #[test]
+    fn test_smoothing_strategies() {
+        let raw = vec![0.8, 0.2, 0.0];
+
+        let none = CompressionScheme::new_with_smoothing("A", raw.clone(), None, Smoothing::None);
+        assert_eq!(none.distribution()[2], 0.0);
+        // Identical unsmoothed schemes: exactly zero Φ despite the zero
+        let twin = CompressionScheme::new_with_smoothing("B", raw.clone(), None, Smoothing::None);
+        assert_eq!(none.symmetric_divergence(&twin).unwrap(), 0.0);
+        assert!(none
+            .symmetric_divergence(&CompressionScheme::uniform("U", 3))
+            .unwrap()
+            .is_finite());
+
+        let jeffreys = Smoothing::Jeffreys { effective_n: 10.0 };
+        let thin = CompressionScheme::new_with_smoothing("C", raw, None, jeffreys);
+        assert!((thin.distribution()[2] - jeffreys.floor_mass(3)).abs() < 1e-12);
+
+        let mut grown = thin.clone();
+        grown.grow(None);
+        assert!((grown.distribution().iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        assert!(Smoothing::Additive(-1.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_morph_sequence() {
+        let a =
+            CompressionScheme::new("A", vec![0.8, 0.1, 0.1], None).with_ordered_categories(true);
+        let b = CompressionScheme::new("B", vec![0.1, 0.1, 0.8], None);
+
+        let path = a.morph_sequence(&b, 4, Interpolation::FisherRao).unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(path[2].ordered_categories);
+        assert_eq!(path[2].actor_id, "A->B@0.500");
+        let phis: Vec<f64> = path
+            .iter()
+            .map(|s| s.symmetric_divergence(&b).unwrap())
+            .collect();
+        assert!(phis.windows(2).all(|w| w[0] > w[1]));
+        assert!(phis[4] < 1e-9);
+
+        let mid = a.interpolate(&b, 0.5, Interpolation::Linear).unwrap();
+        assert!((mid.distribution()[0] - 0.45).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_with_temperature() {
+        let scheme = CompressionScheme::new("A", vec![0.6, 0.3, 0.1], None);
+
+        let same = scheme.with_temperature(1.0).unwrap();
+        assert!((same.distribution()[0] - 0.6).abs() < 1e-6);
+
+        let sharp = scheme.with_temperature(0.5).unwrap();
+        let flat = scheme.with_temperature(4.0).unwrap();
+        assert!(sharp.entropy() < scheme.entropy());
+        assert!(flat.entropy() > scheme.entropy());
+        assert!((sharp.distribution()[0] - 36.0 / 46.0).abs() < 1e-6);
+        assert_eq!(sharp.actor_id, "A");
+
+        let frozen = scheme.with_temperature(1e-3).unwrap();
+        assert!(frozen.distribution()[0] > 0.99);
+        assert!(scheme.with_temperature(0.0).is_err());
+        assert!(scheme.with_temperature(f64::INFINITY).is_err());
+    }
 }
@@ -0,0 +1,223 @@
+//! Seasonal baselines for Φ.
+//!
+//! Many dyads have calendar rhythms (weekly news cycles, annual
+//! commemorations, election seasons) that push Φ up on schedule. Alerting
+//! on raw Φ turns every such rhythm into a false alarm. A
+//! [`SeasonalProfile`] learns the typical Φ level per calendar bucket
+//! (day of week, month, hour) and reports the seasonally adjusted residual
+//! `Φ - (bucket mean - overall mean)`, which keeps Φ's scale but removes the
+//! expected seasonal swing.
+
+use serde::{Deserialize, Serialize};
+
+const MS_PER_HOUR: i64 = 3_600_000;
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Samples a bucket needs before its seasonal component is applied
+pub const MIN_BUCKET_SAMPLES: u64 = 2;
+
+/// Calendar period used to bucket timestamps (UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SeasonalPeriod {
+    /// 24 hourly buckets
+    HourOfDay,
+    /// 7 daily buckets, Monday = 0
+    #[default]
+    DayOfWeek,
+    /// 12 monthly buckets, January = 0
+    MonthOfYear,
+    /// `n_buckets` equal slices of a fixed period
+    Custom { period_ms: i64, n_buckets: usize },
+}
+
+impl SeasonalPeriod {
+    /// Number of buckets in one cycle
+    pub fn n_buckets(&self) -> usize {
+        match *self {
+            SeasonalPeriod::HourOfDay => 24,
+            SeasonalPeriod::DayOfWeek => 7,
+            SeasonalPeriod::MonthOfYear => 12,
+            SeasonalPeriod::Custom { n_buckets, .. } => n_buckets.max(1),
+        }
+    }
+
+    /// Bucket index for a Unix timestamp in milliseconds
+    pub fn bucket(&self, timestamp_ms: i64) -> usize {
+        match *self {
+            SeasonalPeriod::HourOfDay => {
+                (timestamp_ms.rem_euclid(MS_PER_DAY) / MS_PER_HOUR) as usize
+            }
+            SeasonalPeriod::DayOfWeek => {
+                // 1970-01-01 was a Thursday (Monday = 0 → 3)
+                (timestamp_ms.div_euclid(MS_PER_DAY) + 3).rem_euclid(7) as usize
+            }
+            SeasonalPeriod::MonthOfYear => month_of_year(timestamp_ms.div_euclid(MS_PER_DAY)),
+            SeasonalPeriod::Custom {
+                period_ms,
+                n_buckets,
+            } => {
+                let period = period_ms.max(1);
+                let n = n_buckets.max(1) as i64;
+                (timestamp_ms.rem_euclid(period) * n / period) as usize
+            }
+        }
+    }
+}
+
+/// Zero-based month for days since the Unix epoch (proleptic Gregorian)
+fn month_of_year(days: i64) -> usize {
+    let (_, month, _) = civil_from_days(days);
+    (month - 1) as usize
+}
+
+/// Year, month (1-12) and day (1-31) of a day count since 1970-01-01
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Civil-from-days (H. Hinnant): shift to a March-based year
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
+
+/// Seasonal adjustment settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeasonalConfig {
+    /// Calendar period to learn
+    pub period: SeasonalPeriod,
+
+    /// Floor on the per-bucket learning rate
+    ///
+    /// Buckets average their first `1/alpha` samples exactly, then track
+    /// an exponential moving average so the profile can drift.
+    pub alpha: f64,
+}
+
+impl Default for SeasonalConfig {
+    fn default() -> Self {
+        Self {
+            period: SeasonalPeriod::default(),
+            alpha: 0.05,
+        }
+    }
+}
+
+/// Learned Φ level per calendar bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeasonalProfile {
+    config: SeasonalConfig,
+    bucket_means: Vec<f64>,
+    bucket_counts: Vec<u64>,
+    overall_mean: f64,
+    count: u64,
+}
+
+impl SeasonalProfile {
+    /// Create an empty profile
+    pub fn new(config: SeasonalConfig) -> Self {
+        let n = config.period.n_buckets();
+        Self {
+            config,
+            bucket_means: vec![0.0; n],
+            bucket_counts: vec![0; n],
+            overall_mean: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Settings this profile was built with
+    pub fn config(&self) -> &SeasonalConfig {
+        &self.config
+    }
+
+    /// Learn from an observed Φ
+    pub fn observe(&mut self, timestamp_ms: i64, phi: f64) {
+        let b = self.config.period.bucket(timestamp_ms);
+        let alpha = self.config.alpha;
+
+        self.bucket_counts[b] += 1;
+        let rate = (1.0 / self.bucket_counts[b] as f64).max(alpha);
+        self.bucket_means[b] += rate * (phi - self.bucket_means[b]);
+
+        self.count += 1;
+        let rate = (1.0 / self.count as f64).max(alpha);
+        self.overall_mean += rate * (phi - self.overall_mean);
+    }
+
+    /// Expected seasonal offset at a timestamp (0 until the bucket is learned)
+    pub fn component(&self, timestamp_ms: i64) -> f64 {
+        let b = self.config.period.bucket(timestamp_ms);
+        if self.bucket_counts[b] < MIN_BUCKET_SAMPLES {
+            return 0.0;
+        }
+        self.bucket_means[b] - self.overall_mean
+    }
+
+    /// Seasonally adjusted Φ
+    pub fn adjust(&self, timestamp_ms: i64, phi: f64) -> f64 {
+        phi - self.component(timestamp_ms)
+    }
+
+    /// Learned profile as (bucket mean - overall mean) per bucket
+    pub fn components(&self) -> Vec<f64> {
+        self.bucket_means
+            .iter()
+            .zip(self.bucket_counts.iter())
+            .map(|(&m, &c)| {
+                if c < MIN_BUCKET_SAMPLES {
+                    0.0
+                } else {
+                    m - self.overall_mean
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_buckets() {
+        // 2024-01-01T00:00:00Z was a Monday
+        let monday = 1_704_067_200_000;
+        assert_eq!(SeasonalPeriod::DayOfWeek.bucket(monday), 0);
+        assert_eq!(SeasonalPeriod::DayOfWeek.bucket(monday + 6 * MS_PER_DAY), 6);
+        assert_eq!(SeasonalPeriod::MonthOfYear.bucket(monday), 0);
+        // 2024-03-01 (leap year)
+        assert_eq!(
+            SeasonalPeriod::MonthOfYear.bucket(monday + 60 * MS_PER_DAY),
+            2
+        );
+        assert_eq!(
+            SeasonalPeriod::HourOfDay.bucket(monday + 13 * MS_PER_HOUR),
+            13
+        );
+        // Pre-epoch: 1969-12-31 was a Wednesday
+        assert_eq!(SeasonalPeriod::DayOfWeek.bucket(-1), 2);
+        assert_eq!(SeasonalPeriod::MonthOfYear.bucket(-1), 11);
+    }
+
+    #[test]
+    fn test_weekly_rhythm_is_removed() {
+        let monday = 1_704_067_200_000;
+        let mut profile = SeasonalProfile::new(SeasonalConfig::default());
+
+        // Φ jumps every Sunday
+        for day in 0..70 {
+            let phi = if day % 7 == 6 { 3.0 } else { 1.0 };
+            profile.observe(monday + day * MS_PER_DAY, phi);
+        }
+
+        let sunday = monday + 76 * MS_PER_DAY;
+        let tuesday = monday + 71 * MS_PER_DAY;
+        assert!((profile.adjust(sunday, 3.0) - profile.adjust(tuesday, 1.0)).abs() < 0.1);
+        assert!(profile.component(sunday) > 1.5);
+    }
+}
@@ -0,0 +1,153 @@
+//! Local sensitivity of the escalation formula.
+//!
+//! Partial derivatives of the logistic escalation probability at a dyad's
+//! current operating point. With `P = σ(z)`,
+//!
+//! ```text
+//! ∂P/∂θ = P·(1 − P) · ∂z/∂θ
+//! ```
+//!
+//! so every term is the logit slope scaled by how steep the sigmoid is
+//! where the dyad sits. Derivatives describe the built-in formula even
+//! when a learned predictor or ensemble is selected.
+
+use crate::error::Result;
+use crate::model::CompressionDynamicsModel;
+use serde::{Deserialize, Serialize};
+
+/// ∂P with respect to each escalation coefficient
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CoefficientSensitivity {
+    /// `escalation_alpha`
+    pub alpha: f64,
+    /// `escalation_beta`
+    pub beta: f64,
+    /// `escalation_gamma`
+    pub gamma: f64,
+    /// `escalation_hawkes`
+    pub hawkes: f64,
+}
+
+/// ∂P with respect to each formula input
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct InputSensitivity {
+    pub phi: f64,
+    /// Zero while Φ is falling, since only rises escalate
+    pub d_phi: f64,
+    /// Mean windowed grievance of the dyad
+    pub grievance: f64,
+    pub communication: f64,
+    pub shock: f64,
+    pub event_excitation: f64,
+}
+
+/// Escalation sensitivity at a dyad's current operating point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscalationSensitivity {
+    pub actor_a: String,
+    pub actor_b: String,
+    /// Logistic formula probability at the operating point
+    pub probability: f64,
+    /// P·(1 − P)
+    pub slope: f64,
+    pub coefficients: CoefficientSensitivity,
+    pub inputs: InputSensitivity,
+}
+
+impl EscalationSensitivity {
+    /// Inputs as `(name, ∂P)` pairs, most escalation-reducing first
+    ///
+    /// A lever with negative ∂P lowers escalation when increased; one with
+    /// positive ∂P when decreased. Sorted by magnitude.
+    pub fn levers(&self) -> Vec<(&'static str, f64)> {
+        let i = &self.inputs;
+        let mut levers = vec![
+            ("phi", i.phi),
+            ("d_phi", i.d_phi),
+            ("grievance", i.grievance),
+            ("communication", i.communication),
+            ("shock", i.shock),
+            ("event_excitation", i.event_excitation),
+        ];
+        levers.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        levers
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Partial derivatives of P(escalation) for a dyad, without recording
+    pub fn escalation_sensitivity(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        communication_level: f64,
+        shock_intensity: f64,
+    ) -> Result<EscalationSensitivity> {
+        let prediction =
+            self.peek_escalation(actor_a, actor_b, communication_level, shock_intensity)?;
+        let probability = 1.0 / (1.0 + (-prediction.contributions.total()).exp());
+        let slope = probability * (1.0 - probability);
+        let config = &self.config;
+        let rising = if prediction.d_phi_dt > 0.0 { 1.0 } else { 0.0 };
+
+        Ok(EscalationSensitivity {
+            actor_a: actor_a.to_string(),
+            actor_b: actor_b.to_string(),
+            probability,
+            slope,
+            coefficients: CoefficientSensitivity {
+                alpha: slope * (prediction.current_phi + prediction.covariate_contribution),
+                beta: -slope * communication_level,
+                gamma: slope * (prediction.d_phi_dt.max(0.0) + shock_intensity),
+                hawkes: slope * prediction.event_excitation,
+            },
+            inputs: InputSensitivity {
+                phi: slope * config.escalation_alpha,
+                d_phi: slope * config.escalation_gamma * rising,
+                grievance: slope * 0.5,
+                communication: -slope * config.escalation_beta,
+                shock: slope * config.escalation_gamma,
+                event_excitation: slope * config.escalation_hawkes,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensitivity_matches_finite_difference() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        model.register_actor("B", Some(vec![0.2, 0.3, 0.5]), None);
+
+        let s = model.escalation_sensitivity("A", "B", 0.5, 0.2).unwrap();
+        assert!((s.slope - s.probability * (1.0 - s.probability)).abs() < 1e-12);
+        assert!(s.inputs.communication < 0.0);
+        assert_eq!(s.inputs.d_phi, 0.0);
+
+        let h = 1e-6;
+        let p = |comm: f64| {
+            model
+                .peek_escalation("A", "B", comm, 0.2)
+                .unwrap()
+                .probability
+        };
+        let numeric = (p(0.5 + h) - p(0.5 - h)) / (2.0 * h);
+        assert!((numeric - s.inputs.communication).abs() < 1e-6);
+
+        let mut bumped = model.clone();
+        bumped.config.escalation_alpha += h;
+        let p_alpha = bumped
+            .peek_escalation("A", "B", 0.5, 0.2)
+            .unwrap()
+            .probability;
+        let numeric = (p_alpha - s.probability) / h;
+        assert!((numeric - s.coefficients.alpha).abs() < 1e-4);
+
+        assert_eq!(s.levers()[0].0, "shock");
+        assert!(model.escalation_sensitivity("A", "Z", 0.5, 0.0).is_err());
+    }
+}
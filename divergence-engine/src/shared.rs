@@ -0,0 +1,532 @@
+//! Shared Model - a concurrent, clone-able handle on model state.
+//!
+//! [`CompressionDynamicsModel`] is a plain single-owner struct; embedding it
+//! in a multi-threaded server means wrapping the whole thing in one lock.
+//! [`SharedModel`] instead shards actors across independent `RwLock`s:
+//!
+//! - Reads of schemes, grievances and potentials take shared locks and run
+//!   concurrently with each other
+//! - Updates to an actor take an exclusive lock on that actor's shard only,
+//!   so writers touching different shards never contend
+//!
+//! Actors keep their registration order across shards, matching the plain
+//! model. Designated rivals are fixed when the model is wrapped. Queries
+//! on the handle do not record potentials. Use
+//! [`SharedModel::snapshot`] to get back a plain model for serialization,
+//! escalation prediction or other history-dependent analysis.
+
+use crate::error::{DivergenceError, Result};
+use crate::history::SchemeHistory;
+use crate::model::{apply_observation, CompressionDynamicsModel, Grievance, ModelConfig};
+use crate::outlier::{log_rejection, RejectedObservation};
+use crate::registry::CategoryRegistry;
+use crate::scheme::{CompressionScheme, ConflictPotential};
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Default number of shards
+pub const DEFAULT_SHARDS: usize = 16;
+
+/// Per-actor state held in a shard
+#[derive(Debug, Clone)]
+struct ActorState {
+    scheme: CompressionScheme,
+    grievance: Grievance,
+    /// Registration sequence number (for stable cross-shard ordering)
+    order: u64,
+}
+
+/// One independently-locked partition of the actor space
+#[derive(Debug, Default)]
+struct Shard {
+    actors: HashMap<String, ActorState>,
+    history: SchemeHistory,
+}
+
+#[derive(Debug)]
+struct Inner {
+    config: ModelConfig,
+    registry: Option<Arc<CategoryRegistry>>,
+    category_version: u64,
+    potentials: Vec<ConflictPotential>,
+    rejected: Mutex<VecDeque<RejectedObservation>>,
+    /// Designated rival of each actor
+    rivals: IndexMap<String, String>,
+    shards: Vec<RwLock<Shard>>,
+    next_order: AtomicU64,
+}
+
+/// Clone-able, thread-safe handle on a sharded model
+#[derive(Debug, Clone)]
+pub struct SharedModel {
+    inner: Arc<Inner>,
+}
+
+impl SharedModel {
+    /// Wrap a model using the default shard count
+    pub fn new(model: CompressionDynamicsModel) -> Self {
+        Self::with_shards(model, DEFAULT_SHARDS)
+    }
+
+    /// Wrap a model, distributing actors across `n_shards` locks
+    pub fn with_shards(model: CompressionDynamicsModel, n_shards: usize) -> Self {
+        let n_shards = n_shards.max(1);
+        let mut shards: Vec<Shard> = (0..n_shards).map(|_| Shard::default()).collect();
+
+        let mut grievances = model.grievances;
+        let n_actors = model.schemes.len() as u64;
+        for (order, (actor_id, scheme)) in model.schemes.into_iter().enumerate() {
+            let grievance = grievances
+                .swap_remove(&actor_id)
+                .unwrap_or_else(|| Grievance::new(&actor_id));
+            let idx = shard_index(&actor_id, n_shards);
+            shards[idx].actors.insert(
+                actor_id,
+                ActorState {
+                    scheme,
+                    grievance,
+                    order: order as u64,
+                },
+            );
+        }
+        let storage = model.config.history_storage;
+        for entry in model.history.into_entries() {
+            let idx = shard_index(&entry.actor_id, n_shards);
+            shards[idx].history.push(entry, storage);
+        }
+
+        Self {
+            inner: Arc::new(Inner {
+                config: model.config,
+                registry: model.registry,
+                category_version: model.category_version,
+                potentials: model.potentials,
+                rejected: Mutex::new(model.rejected_observations),
+                rivals: model.rivals,
+                shards: shards.into_iter().map(RwLock::new).collect(),
+                next_order: AtomicU64::new(n_actors),
+            }),
+        }
+    }
+
+    /// Model configuration
+    pub fn config(&self) -> &ModelConfig {
+        &self.inner.config
+    }
+
+    /// Number of shards
+    pub fn n_shards(&self) -> usize {
+        self.inner.shards.len()
+    }
+
+    fn shard_for(&self, actor_id: &str) -> usize {
+        shard_index(actor_id, self.inner.shards.len())
+    }
+
+    fn read(&self, idx: usize) -> RwLockReadGuard<'_, Shard> {
+        self.inner.shards[idx].read().expect("shard lock poisoned")
+    }
+
+    fn write(&self, idx: usize) -> RwLockWriteGuard<'_, Shard> {
+        self.inner.shards[idx].write().expect("shard lock poisoned")
+    }
+
+    fn rejected(&self) -> MutexGuard<'_, VecDeque<RejectedObservation>> {
+        self.inner.rejected.lock().expect("rejection log poisoned")
+    }
+
+    fn next_order(&self) -> u64 {
+        self.inner.next_order.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Clone every actor's state, in registration order
+    fn ordered_states(&self) -> Vec<(String, ActorState)> {
+        let mut states: Vec<(String, ActorState)> = (0..self.n_shards())
+            .flat_map(|i| {
+                self.read(i)
+                    .actors
+                    .iter()
+                    .map(|(id, state)| (id.clone(), state.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        states.sort_by_key(|(_, state)| state.order);
+        states
+    }
+
+    /// All registered actor IDs in registration order
+    pub fn actors(&self) -> Vec<String> {
+        let mut ids: Vec<(u64, String)> = (0..self.n_shards())
+            .flat_map(|i| {
+                self.read(i)
+                    .actors
+                    .iter()
+                    .map(|(id, state)| (state.order, id.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        ids.sort();
+        ids.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Registered ID a query resolves to under the matching policy
+    pub fn resolve_actor(&self, query: &str) -> Option<String> {
+        if self.contains(query) {
+            return Some(query.to_string());
+        }
+        let matching = &self.inner.config.actor_matching;
+        if matching.is_exact() {
+            return None;
+        }
+        let wanted = matching.normalize(query);
+        self.actors()
+            .into_iter()
+            .find(|id| matching.normalize(id) == wanted)
+    }
+
+    /// Unknown-actor error for a query, with a suggestion when enabled
+    fn unknown_actor(&self, query: &str) -> DivergenceError {
+        let matching = &self.inner.config.actor_matching;
+        let actors = self.actors();
+        DivergenceError::UnknownActor {
+            actor: query.to_string(),
+            suggestion: matching
+                .suggest
+                .then(|| matching.closest(query, actors.iter().map(String::as_str)))
+                .flatten()
+                .map(String::from),
+        }
+    }
+
+    /// Whether an actor is registered
+    pub fn contains(&self, actor_id: &str) -> bool {
+        self.read(self.shard_for(actor_id))
+            .actors
+            .contains_key(actor_id)
+    }
+
+    /// Run a closure against an actor's scheme under a shared lock
+    pub fn with_scheme<R>(
+        &self,
+        actor_id: &str,
+        f: impl FnOnce(&CompressionScheme) -> R,
+    ) -> Option<R> {
+        let actor_id = self.resolve_actor(actor_id)?;
+        self.read(self.shard_for(&actor_id))
+            .actors
+            .get(&actor_id)
+            .map(|state| f(&state.scheme))
+    }
+
+    /// Clone of an actor's scheme
+    pub fn scheme(&self, actor_id: &str) -> Option<CompressionScheme> {
+        self.with_scheme(actor_id, CompressionScheme::clone)
+    }
+
+    /// Clone of an actor's grievance
+    pub fn grievance(&self, actor_id: &str) -> Option<Grievance> {
+        let actor_id = self.resolve_actor(actor_id)?;
+        self.read(self.shard_for(&actor_id))
+            .actors
+            .get(&actor_id)
+            .map(|state| state.grievance.clone())
+    }
+
+    /// Register (or replace) an actor
+    pub fn register_actor(
+        &self,
+        actor_id: impl Into<String>,
+        initial_distribution: Option<Vec<f64>>,
+        categories: Option<Vec<String>>,
+    ) -> CompressionScheme {
+        let actor_id = actor_id.into();
+        let n = self.inner.config.n_categories;
+        let distribution = initial_distribution.unwrap_or_else(|| vec![1.0 / n as f64; n]);
+
+        let mut scheme = CompressionScheme::new_with_smoothing(
+            actor_id.clone(),
+            distribution,
+            categories,
+            self.inner.config.smoothing,
+        );
+        scheme.set_registry(self.inner.registry.clone());
+
+        let mut shard = self.write(self.shard_for(&actor_id));
+        let order = match shard.actors.get(&actor_id) {
+            Some(existing) => existing.order,
+            None => self.next_order(),
+        };
+        let state = ActorState {
+            scheme: scheme.clone(),
+            grievance: Grievance::new(&actor_id),
+            order,
+        };
+        shard.actors.insert(actor_id, state);
+        scheme
+    }
+
+    /// Update an actor's scheme, locking only that actor's shard
+    ///
+    /// Unknown actors are registered with a uniform scheme first.
+    pub fn update_scheme(
+        &self,
+        actor_id: &str,
+        observation: &[f64],
+        timestamp_ms: Option<i64>,
+    ) -> Result<CompressionScheme> {
+        let resolved = self.resolve_actor(actor_id);
+        let actor_id = resolved.as_deref().unwrap_or(actor_id);
+        // Cloned before taking the write lock: locking the rival's shard
+        // while holding it could deadlock against the rival's own update
+        let rival = if self.inner.config.uses_rivals() {
+            self.inner
+                .rivals
+                .get(actor_id)
+                .and_then(|rival| self.scheme(rival))
+        } else {
+            None
+        };
+        let mut shard = self.write(self.shard_for(actor_id));
+        let shard = &mut *shard;
+
+        if !shard.actors.contains_key(actor_id) {
+            let n = self.inner.config.n_categories;
+            let mut scheme = CompressionScheme::new_with_smoothing(
+                actor_id,
+                vec![1.0 / n as f64; n],
+                None,
+                self.inner.config.smoothing,
+            );
+            scheme.set_registry(self.inner.registry.clone());
+            shard.actors.insert(
+                actor_id.to_string(),
+                ActorState {
+                    scheme,
+                    grievance: Grievance::new(actor_id),
+                    order: self.next_order(),
+                },
+            );
+        }
+
+        let state = shard.actors.get_mut(actor_id).unwrap();
+        let entry = apply_observation(
+            &self.inner.config,
+            &mut state.scheme,
+            Some(&mut state.grievance),
+            rival.as_ref(),
+            observation,
+            timestamp_ms,
+            self.inner.category_version,
+        )
+        .inspect_err(|e| {
+            log_rejection(
+                &mut self.rejected(),
+                &self.inner.config,
+                e,
+                actor_id,
+                observation,
+                timestamp_ms,
+            )
+        })?;
+        shard.history.record(entry, &self.inner.config);
+        Ok(state.scheme.clone())
+    }
+
+    /// Most recent observations rejected by the outlier filter, oldest
+    /// first
+    pub fn rejected_observations(&self) -> Vec<RejectedObservation> {
+        self.rejected().iter().cloned().collect()
+    }
+
+    /// Conflict potential between two actors (not recorded)
+    ///
+    /// Takes shared locks on at most two shards, in index order.
+    pub fn conflict_potential(&self, actor_a: &str, actor_b: &str) -> Result<ConflictPotential> {
+        let resolve = |query: &str| {
+            self.resolve_actor(query)
+                .ok_or_else(|| self.unknown_actor(query))
+        };
+        let (actor_a, actor_b) = (resolve(actor_a)?, resolve(actor_b)?);
+        let (actor_a, actor_b) = (actor_a.as_str(), actor_b.as_str());
+        let (ia, ib) = (self.shard_for(actor_a), self.shard_for(actor_b));
+        let (lo, hi) = (ia.min(ib), ia.max(ib));
+
+        let guard_lo = self.read(lo);
+        let guard_hi = (hi != lo).then(|| self.read(hi));
+        let shard = |idx: usize| -> &Shard {
+            match &guard_hi {
+                Some(g) if idx == hi => g,
+                _ => &guard_lo,
+            }
+        };
+
+        let lookup = |idx: usize, actor: &str| -> Result<&CompressionScheme> {
+            shard(idx)
+                .actors
+                .get(actor)
+                .map(|s| &s.scheme)
+                .ok_or_else(|| DivergenceError::unknown_actor(actor))
+        };
+
+        ConflictPotential::compute(lookup(ia, actor_a)?, lookup(ib, actor_b)?)
+    }
+
+    /// Pairwise potentials over a consistent-per-shard view of all actors
+    pub fn all_potentials(&self) -> Vec<ConflictPotential> {
+        let schemes: Vec<CompressionScheme> = self
+            .ordered_states()
+            .into_iter()
+            .map(|(_, state)| state.scheme)
+            .collect();
+
+        let mut results = Vec::new();
+        for i in 0..schemes.len() {
+            for j in (i + 1)..schemes.len() {
+                if let Ok(p) = ConflictPotential::compute(&schemes[i], &schemes[j]) {
+                    results.push(p);
+                }
+            }
+        }
+        results
+    }
+
+    /// Materialize a plain model from the current state
+    ///
+    /// History entries from different shards are merged in timestamp order.
+    pub fn snapshot(&self) -> CompressionDynamicsModel {
+        let mut model = CompressionDynamicsModel::with_config(self.inner.config.clone());
+        model.registry = self.inner.registry.clone();
+        model.category_version = self.inner.category_version;
+        model.potentials = self.inner.potentials.clone();
+        model.rejected_observations = self.rejected().clone();
+        model.rivals = self.inner.rivals.clone();
+
+        for (actor_id, state) in self.ordered_states() {
+            model.schemes.insert(actor_id.clone(), state.scheme);
+            model.grievances.insert(actor_id, state.grievance);
+        }
+        let mut history = Vec::new();
+        for i in 0..self.n_shards() {
+            history.extend(self.read(i).history.iter().map(Cow::into_owned));
+        }
+        history.sort_by_key(|h| h.timestamp_ms);
+        for entry in history {
+            model.history.push(entry, model.config.history_storage);
+        }
+        model
+    }
+}
+
+fn shard_index(actor_id: &str, n_shards: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    actor_id.hash(&mut hasher);
+    (hasher.finish() % n_shards as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::ActorMatching;
+    use std::thread;
+
+    #[test]
+    fn test_roundtrip_through_snapshot() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.7, 0.2, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.2, 0.7]), None);
+
+        let shared = SharedModel::with_shards(model, 4);
+        shared
+            .update_scheme("A", &[1.0, 0.0, 0.0], Some(10))
+            .unwrap();
+        shared
+            .update_scheme("C", &[0.0, 1.0, 0.0], Some(5))
+            .unwrap();
+
+        let phi = shared.conflict_potential("A", "B").unwrap().phi;
+        assert!(phi > 0.0);
+        assert!(shared.conflict_potential("A", "Z").is_err());
+
+        let snapshot = shared.snapshot();
+        assert_eq!(snapshot.actors().len(), 3);
+        assert_eq!(snapshot.summary().n_history_entries, 2);
+        assert_eq!(snapshot.summary().n_potentials, 0);
+        assert!(
+            (snapshot
+                .get_scheme("A")
+                .unwrap()
+                .symmetric_divergence(snapshot.get_scheme("B").unwrap())
+                .unwrap()
+                - phi)
+                .abs()
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_concurrent_updates_and_reads() {
+        let shared = SharedModel::new(CompressionDynamicsModel::new(4));
+        for i in 0..8 {
+            shared.register_actor(format!("actor_{}", i), None, None);
+        }
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let handle = shared.clone();
+                thread::spawn(move || {
+                    let me = format!("actor_{}", i);
+                    let other = format!("actor_{}", (i + 1) % 8);
+                    for t in 0..50 {
+                        let mut obs = vec![0.0; 4];
+                        obs[i % 4] = 1.0;
+                        handle.update_scheme(&me, &obs, Some(t)).unwrap();
+                        handle.conflict_potential(&me, &other).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for h in handles {
+            h.join().unwrap();
+        }
+
+        let expected: Vec<String> = (0..8).map(|i| format!("actor_{}", i)).collect();
+        assert_eq!(shared.actors(), expected);
+        assert_eq!(shared.snapshot().actors(), expected);
+        assert_eq!(shared.snapshot().summary().n_history_entries, 400);
+        assert_eq!(shared.all_potentials().len(), 28);
+    }
+
+    #[test]
+    fn test_shared_actor_matching() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("USA", None, None);
+        model.register_actor("RUS", None, None);
+        let exact = SharedModel::with_shards(model, 4);
+        assert!(exact.scheme("usa").is_none());
+        let err = exact.conflict_potential("usa", "RUS").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown actor: usa (did you mean USA?)");
+
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 3,
+            actor_matching: ActorMatching::relaxed(),
+            ..Default::default()
+        });
+        model.register_actor("USA", None, None);
+        model.register_actor("RUS", None, None);
+        let relaxed = SharedModel::with_shards(model, 4);
+        assert_eq!(relaxed.resolve_actor(" usa ").as_deref(), Some("USA"));
+        assert!(relaxed.scheme("usa").is_some());
+        assert!(relaxed.grievance("rus").is_some());
+        assert!(relaxed.conflict_potential("usa", " Rus").is_ok());
+        relaxed
+            .update_scheme("rus", &[0.8, 0.1, 0.1], Some(1))
+            .unwrap();
+        assert_eq!(relaxed.actors(), ["USA", "RUS"]);
+    }
+}
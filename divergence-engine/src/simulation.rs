@@ -0,0 +1,375 @@
+//! Monte Carlo escalation simulation.
+//!
+//! `predict_escalation` collapses the future into one probability. This
+//! module rolls a dyad forward many times instead: each path perturbs both
+//! actors' observations with noise, injects random shocks, applies the
+//! model's learning rule and evaluates the escalation logit at every step.
+//! The spread of paths gives quantiles of future Φ, exceedance
+//! probabilities and a distribution of escalation timing.
+//!
+//! Per step and actor:
+//!
+//! ```text
+//! obs_i  = p_i · exp(σ·z_i)                    z_i ~ N(0, 1)
+//! obs    = (1 - s)·obs + s·e_k  with prob. λ   s ~ U(0, shock_scale), k uniform
+//! p      ← (1 - η)·p + η·obs
+//! ```
+//!
+//! With `ModelConfig::polarization_feedback` set, each actor is pushed away
+//! from the other before the update, as described in [`crate::rivalry`],
+//! and the path escalates at that step with probability
+//! σ(α·Φ + γ·max(dΦ, 0) + 0.5·G - β·comm + γ·s).
+
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::rivalry::polarized_observation;
+use crate::rng::{standard_normal, uniform, RngCore, SeedableRng, SplitMix64};
+use serde::{Deserialize, Serialize};
+
+/// Monte Carlo simulation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationConfig {
+    /// Steps to simulate
+    pub horizon: usize,
+
+    /// Number of sampled paths
+    pub n_paths: usize,
+
+    /// Log-scale noise σ applied to each observation
+    pub observation_noise: f64,
+
+    /// Probability λ of a shock per step
+    pub shock_rate: f64,
+
+    /// Maximum shock intensity (mass moved onto one category)
+    pub shock_scale: f64,
+
+    /// Communication level held over the horizon
+    pub communication_level: f64,
+
+    /// Φ levels for which exceedance probabilities are reported
+    pub phi_thresholds: Vec<f64>,
+
+    /// Seed for reproducible paths
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            horizon: 30,
+            n_paths: 1000,
+            observation_noise: 0.2,
+            shock_rate: 0.05,
+            shock_scale: 0.5,
+            communication_level: 0.5,
+            phi_thresholds: vec![0.5, 1.0, 2.0],
+            seed: 0x5eed,
+        }
+    }
+}
+
+/// Quantiles of a sampled quantity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantileSummary {
+    pub mean: f64,
+    pub p05: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+impl QuantileSummary {
+    /// Summarize samples; `None` when empty
+    pub fn from_samples(samples: &mut [f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_by(|a, b| a.total_cmp(b));
+        let q = |p: f64| {
+            let pos = p * (samples.len() - 1) as f64;
+            let lo = pos.floor() as usize;
+            let hi = pos.ceil() as usize;
+            samples[lo] + (samples[hi] - samples[lo]) * (pos - lo as f64)
+        };
+        Some(Self {
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+            p05: q(0.05),
+            p25: q(0.25),
+            p50: q(0.5),
+            p75: q(0.75),
+            p95: q(0.95),
+        })
+    }
+}
+
+/// Probability that Φ reaches a level within the horizon
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhiExceedance {
+    pub threshold: f64,
+    pub probability: f64,
+}
+
+/// Distributional escalation forecast for a dyad
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationSimulation {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub horizon: usize,
+    pub n_paths: usize,
+    /// Φ quantiles at each step (index 0 = one step ahead)
+    pub phi_quantiles: Vec<QuantileSummary>,
+    /// Fraction of paths escalated by each step
+    pub cumulative_escalation: Vec<f64>,
+    /// Fraction of paths escalated within the horizon
+    pub escalation_probability: f64,
+    /// Step of first escalation (1-based), over escalated paths only
+    pub escalation_step: Option<QuantileSummary>,
+    /// P(max Φ over the horizon ≥ threshold)
+    pub exceedance: Vec<PhiExceedance>,
+}
+
+impl EscalationSimulation {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| DivergenceError::SerializationError(e.to_string()))
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Simulate escalation paths with default settings
+    pub fn simulate_escalation_paths(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        horizon: usize,
+        n_paths: usize,
+    ) -> Result<EscalationSimulation> {
+        self.simulate_escalation_paths_with(
+            actor_a,
+            actor_b,
+            &SimulationConfig {
+                horizon,
+                n_paths,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Simulate escalation paths
+    ///
+    /// The model itself is not modified; each path works on copies of the
+    /// two schemes, starting from the current grievance levels.
+    pub fn simulate_escalation_paths_with(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        sim: &SimulationConfig,
+    ) -> Result<EscalationSimulation> {
+        self.simulate_escalation_paths_with_rng::<SplitMix64>(actor_a, actor_b, sim)
+    }
+
+    /// Simulate escalation paths drawing from generator `R` seeded with
+    /// `sim.seed`
+    pub fn simulate_escalation_paths_with_rng<R: RngCore + SeedableRng>(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        sim: &SimulationConfig,
+    ) -> Result<EscalationSimulation> {
+        if sim.horizon == 0 || sim.n_paths == 0 {
+            return Err(DivergenceError::ConfigError(
+                "Simulation needs a positive horizon and path count".to_string(),
+            ));
+        }
+
+        let start = self.peek_escalation(actor_a, actor_b, sim.communication_level, 0.0)?;
+        let scheme_a = self.scheme_for(actor_a)?;
+        let scheme_b = self.scheme_for(actor_b)?;
+        let grievance_of = |id: &str| self.grievances.get(id).map_or(0.0, |g| g.window_error);
+        let window = self.config.grievance_window.max(1) as f64;
+
+        let mut rng = R::seed_from_u64(sim.seed);
+        let mut phi_by_step = vec![Vec::with_capacity(sim.n_paths); sim.horizon];
+        let mut escalated_by_step = vec![0usize; sim.horizon];
+        let mut escalation_steps = Vec::new();
+        let mut max_phi = Vec::with_capacity(sim.n_paths);
+
+        for _ in 0..sim.n_paths {
+            let mut schemes = [scheme_a.clone(), scheme_b.clone()];
+            let mut grievance = [
+                grievance_of(&scheme_a.actor_id),
+                grievance_of(&scheme_b.actor_id),
+            ];
+            let mut phi = start.current_phi;
+            let mut path_max = phi;
+            let mut escalated_at = None;
+
+            for (step, step_phi) in phi_by_step.iter_mut().enumerate() {
+                let mut shock = 0.0;
+                // Feedback pushes each actor away from the other's pre-step scheme
+                let rivals = self
+                    .config
+                    .polarization_feedback
+                    .map(|_| [schemes[1].clone(), schemes[0].clone()]);
+                for (i, (scheme, g)) in schemes.iter_mut().zip(grievance.iter_mut()).enumerate() {
+                    let current = scheme.distribution().to_vec();
+                    let mut obs: Vec<f64> = current
+                        .iter()
+                        .map(|&p| p * (sim.observation_noise * standard_normal(&mut rng)).exp())
+                        .collect();
+                    let sum: f64 = obs.iter().sum();
+                    obs.iter_mut().for_each(|o| *o /= sum);
+
+                    if uniform(&mut rng) < sim.shock_rate {
+                        let s = sim.shock_scale * uniform(&mut rng);
+                        let k =
+                            ((uniform(&mut rng) * obs.len() as f64) as usize).min(obs.len() - 1);
+                        obs.iter_mut().for_each(|o| *o *= 1.0 - s);
+                        obs[k] += s;
+                        shock = f64::max(shock, s);
+                    }
+
+                    let error: f64 = current
+                        .iter()
+                        .zip(&obs)
+                        .map(|(&p, &o)| (o - p).powi(2))
+                        .sum();
+                    *g += (error - *g) / window;
+                    if let (Some(strength), Some(rivals)) =
+                        (self.config.polarization_feedback, &rivals)
+                    {
+                        obs =
+                            polarized_observation(strength, scheme, &rivals[i], &obs)?.into_owned();
+                    }
+                    scheme.update(&obs, self.config.learning_rate)?;
+                }
+
+                let next_phi = schemes[0].symmetric_divergence(&schemes[1])?;
+                let d_phi = next_phi - phi;
+                phi = next_phi;
+                path_max = path_max.max(phi);
+                step_phi.push(phi);
+
+                if escalated_at.is_none() {
+                    let logit = self.config.escalation_alpha * phi
+                        + self.config.escalation_gamma * d_phi.max(0.0)
+                        + 0.5 * (grievance[0] + grievance[1]) / 2.0
+                        - self.config.escalation_beta * sim.communication_level
+                        + self.config.escalation_gamma * shock;
+                    let p = 1.0 / (1.0 + (-logit).exp());
+                    if uniform(&mut rng) < p {
+                        escalated_at = Some(step);
+                    }
+                }
+            }
+
+            if let Some(step) = escalated_at {
+                escalated_by_step[step] += 1;
+                escalation_steps.push((step + 1) as f64);
+            }
+            max_phi.push(path_max);
+        }
+
+        let n = sim.n_paths as f64;
+        let mut cumulative = 0usize;
+        let cumulative_escalation = escalated_by_step
+            .iter()
+            .map(|&k| {
+                cumulative += k;
+                cumulative as f64 / n
+            })
+            .collect();
+
+        let exceedance = sim
+            .phi_thresholds
+            .iter()
+            .map(|&threshold| PhiExceedance {
+                threshold,
+                probability: max_phi.iter().filter(|&&m| m >= threshold).count() as f64 / n,
+            })
+            .collect();
+
+        Ok(EscalationSimulation {
+            actor_a: actor_a.to_string(),
+            actor_b: actor_b.to_string(),
+            horizon: sim.horizon,
+            n_paths: sim.n_paths,
+            phi_quantiles: phi_by_step
+                .iter_mut()
+                .filter_map(|samples| QuantileSummary::from_samples(samples))
+                .collect(),
+            escalation_probability: escalation_steps.len() as f64 / n,
+            cumulative_escalation,
+            escalation_step: QuantileSummary::from_samples(&mut escalation_steps),
+            exceedance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> CompressionDynamicsModel {
+        let mut model = CompressionDynamicsModel::new(4);
+        model.register_actor("A", Some(vec![0.4, 0.3, 0.2, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.2, 0.3, 0.4]), None);
+        model
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible() {
+        let model = model();
+        let first = model.simulate_escalation_paths("A", "B", 10, 200).unwrap();
+        let second = model.simulate_escalation_paths("A", "B", 10, 200).unwrap();
+
+        assert_eq!(first.phi_quantiles, second.phi_quantiles);
+        let explicit = model
+            .simulate_escalation_paths_with_rng::<SplitMix64>(
+                "A",
+                "B",
+                &SimulationConfig {
+                    horizon: 10,
+                    n_paths: 200,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(first.phi_quantiles, explicit.phi_quantiles);
+        assert_eq!(first.phi_quantiles.len(), 10);
+        assert_eq!(first.cumulative_escalation.len(), 10);
+        assert!(model.simulate_escalation_paths("A", "C", 10, 200).is_err());
+        assert!(model.simulate_escalation_paths("A", "B", 0, 200).is_err());
+    }
+
+    #[test]
+    fn test_distributional_outputs_are_consistent() {
+        let model = model();
+        let sim = model
+            .simulate_escalation_paths_with(
+                "A",
+                "B",
+                &SimulationConfig {
+                    horizon: 20,
+                    n_paths: 300,
+                    shock_rate: 0.2,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        for q in &sim.phi_quantiles {
+            assert!(q.p05 <= q.p25 && q.p25 <= q.p50 && q.p50 <= q.p75 && q.p75 <= q.p95);
+        }
+        assert!(sim.cumulative_escalation.windows(2).all(|w| w[0] <= w[1]));
+        assert!((sim.cumulative_escalation[19] - sim.escalation_probability).abs() < 1e-12);
+        assert!(sim
+            .exceedance
+            .windows(2)
+            .all(|w| w[0].probability >= w[1].probability));
+
+        let steps = sim.escalation_step.unwrap();
+        assert!(steps.p05 >= 1.0 && steps.p95 <= 20.0);
+    }
+}
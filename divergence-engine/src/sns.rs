@@ -0,0 +1,190 @@
+//! AWS SNS alert sink.
+//!
+//! [`SnsAlertSink`] publishes [`DivergenceAlert`] JSON to an SNS topic. The
+//! risk level and both actors go along as message attributes, so
+//! subscriptions can filter with a filter policy rather than by parsing
+//! the body:
+//!
+//! ```text
+//! attributes:     risk_level=CRITICAL  actor_a=RUS  actor_b=UKR
+//! filter policy:  {"risk_level": ["HIGH", "CRITICAL"]}
+//! ```
+//!
+//! On FIFO topics (ARN ending in `.fifo`) the dyad is the message group, so
+//! one dyad's alerts arrive in order, and the alert ID is the deduplication
+//! ID, so a retried publish is delivered once.
+//!
+//! With [`SnsAlertSink::with_cloudevents`] alerts are published as
+//! structured-mode CloudEvents.
+
+use crate::cloudevents::{alert_payload, CloudEventsConfig};
+use crate::error::{DivergenceError, Result};
+use crate::streaming::{AlertSink, DivergenceAlert};
+use async_trait::async_trait;
+use aws_sdk_sns::error::DisplayErrorContext;
+use aws_sdk_sns::types::MessageAttributeValue;
+use aws_sdk_sns::Client;
+
+/// Longest message group or deduplication ID SNS accepts
+const MAX_FIFO_ID_LEN: usize = 128;
+
+/// Alert sink publishing JSON alerts to an SNS topic
+pub struct SnsAlertSink {
+    client: Client,
+    topic_arn: String,
+    cloudevents: Option<CloudEventsConfig>,
+}
+
+impl SnsAlertSink {
+    /// Sink publishing through an existing client
+    pub fn new(client: Client, topic_arn: impl Into<String>) -> Self {
+        Self {
+            client,
+            topic_arn: topic_arn.into(),
+            cloudevents: None,
+        }
+    }
+
+    /// Sink with a client configured from the environment (region,
+    /// credentials chain)
+    pub async fn from_env(topic_arn: impl Into<String>) -> Self {
+        let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        Self::new(Client::new(&sdk_config), topic_arn)
+    }
+
+    /// Wrap alerts in CloudEvents envelopes
+    pub fn with_cloudevents(mut self, cloudevents: CloudEventsConfig) -> Self {
+        self.cloudevents = Some(cloudevents);
+        self
+    }
+
+    /// Topic alerts are published to
+    pub fn topic_arn(&self) -> &str {
+        &self.topic_arn
+    }
+
+    /// Whether the topic is a FIFO topic
+    pub fn is_fifo(&self) -> bool {
+        self.topic_arn.ends_with(".fifo")
+    }
+}
+
+/// Message attributes for subscription filter policies
+fn message_attributes(
+    alert: &DivergenceAlert,
+) -> Result<Vec<(&'static str, MessageAttributeValue)>> {
+    [
+        ("risk_level", alert.risk_level.as_str()),
+        ("actor_a", alert.actor_a.as_str()),
+        ("actor_b", alert.actor_b.as_str()),
+    ]
+    .into_iter()
+    .map(|(name, value)| {
+        MessageAttributeValue::builder()
+            .data_type("String")
+            .string_value(value)
+            .build()
+            .map(|attribute| (name, attribute))
+            .map_err(|e| DivergenceError::ConfigError(format!("SNS attribute {}: {}", name, e)))
+    })
+    .collect()
+}
+
+/// FIFO message ID: printable ASCII, at most 128 characters
+fn fifo_id(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+        .take(MAX_FIFO_ID_LEN)
+        .collect()
+}
+
+/// Message group shared by both orderings of a dyad
+fn message_group_id(alert: &DivergenceAlert) -> String {
+    let (a, b) = if alert.actor_a <= alert.actor_b {
+        (&alert.actor_a, &alert.actor_b)
+    } else {
+        (&alert.actor_b, &alert.actor_a)
+    };
+    fifo_id(&format!("{}/{}", a, b))
+}
+
+#[async_trait]
+impl AlertSink for SnsAlertSink {
+    async fn send(&mut self, alert: DivergenceAlert) -> Result<()> {
+        let payload = alert_payload(&alert, self.cloudevents.as_ref())?;
+        let message = String::from_utf8(payload)
+            .map_err(|e| DivergenceError::ConfigError(format!("SNS message: {}", e)))?;
+        let mut request = self
+            .client
+            .publish()
+            .topic_arn(&self.topic_arn)
+            .message(message);
+        for (name, attribute) in message_attributes(&alert)? {
+            request = request.message_attributes(name, attribute);
+        }
+        if self.is_fifo() {
+            request = request
+                .message_group_id(message_group_id(&alert))
+                .message_deduplication_id(fifo_id(&alert.alert_id));
+        }
+        request.send().await.map_err(|e| {
+            DivergenceError::ConfigError(format!(
+                "SNS Publish to {} failed: {}",
+                self.topic_arn,
+                DisplayErrorContext(e)
+            ))
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::RiskLevel;
+
+    fn alert(actor_a: &str, actor_b: &str) -> DivergenceAlert {
+        DivergenceAlert {
+            alert_id: "a1".to_string(),
+            actor_a: actor_a.to_string(),
+            actor_b: actor_b.to_string(),
+            phi: 2.5,
+            js: 0.4,
+            phi_z: None,
+            phi_adjusted: None,
+            p_value: None,
+            robustness: None,
+            d_phi_dt: 0.1,
+            risk_level: RiskLevel::Critical,
+            escalation_probability: 0.9,
+            grievance_a_to_b: 0.0,
+            grievance_b_to_a: 0.0,
+            timestamp_ms: 0,
+            reason: "test".to_string(),
+            latency: None,
+        }
+    }
+
+    #[test]
+    fn test_sns_message_routing() {
+        let attributes = message_attributes(&alert("RUS", "UKR")).unwrap();
+        let values: Vec<(&str, Option<&str>)> = attributes
+            .iter()
+            .map(|(name, value)| (*name, value.string_value()))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("risk_level", Some(RiskLevel::Critical.as_str())),
+                ("actor_a", Some("RUS")),
+                ("actor_b", Some("UKR")),
+            ]
+        );
+
+        // Both orderings of a dyad share a message group
+        assert_eq!(message_group_id(&alert("UKR", "RUS")), "RUS/UKR");
+        assert_eq!(message_group_id(&alert("RUS", "UKR")), "RUS/UKR");
+        assert_eq!(fifo_id("Côte d'Ivoire"), "C_te_d'Ivoire");
+        assert_eq!(fifo_id(&"x".repeat(200)).len(), MAX_FIFO_ID_LEN);
+    }
+}
@@ -0,0 +1,640 @@
+//! SQLite event source and alert sink.
+//!
+//! A zero-infrastructure persistence option for small deployments: events
+//! are replayed from a table and alerts archived to another, in one file.
+//!
+//! ```text
+//! events(seq, event_id, actor_id, timestamp_ms, payload)
+//!        └─ read in (timestamp_ms, seq) order from a resume offset
+//! events_offsets(consumer, timestamp_ms, seq)
+//!        └─ acknowledged position per named consumer
+//! alerts(alert_id, actor_a, actor_b, timestamp_ms, risk_level, phi, payload)
+//!        └─ indexed on (actor_a, actor_b, timestamp_ms) and timestamp_ms
+//! ```
+//!
+//! Payloads are the JSON of the [`StreamEvent`] or [`DivergenceAlert`];
+//! the other columns exist for ordering and lookup.
+//!
+//! The offset only moves past events once they and every earlier event are
+//! acknowledged, so a restarted consumer resumes with at-least-once
+//! delivery. Reading follows timestamps, not insertion: a row appended
+//! later with a timestamp before the offset is not read by that consumer.
+//! Rows whose payload fails to parse are skipped and counted.
+//!
+//! SQLite calls block, so they run on tokio's blocking pool.
+
+use crate::error::{DivergenceError, Result};
+use crate::streaming::{AlertSink, DivergenceAlert, EventSource, StreamEvent};
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Position in an event table: the last event read or acknowledged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SqliteOffset {
+    pub timestamp_ms: i64,
+    pub seq: i64,
+}
+
+impl SqliteOffset {
+    /// Before every event
+    pub const START: SqliteOffset = SqliteOffset {
+        timestamp_ms: i64::MIN,
+        seq: i64::MIN,
+    };
+}
+
+/// Settings for a [`SqliteEventSource`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqliteSourceConfig {
+    pub table: String,
+    /// Name offsets are stored under (`None`: not persisted)
+    #[serde(default)]
+    pub consumer: Option<String>,
+    /// Most events returned per `receive`
+    pub batch_size: usize,
+    /// Pause when no new events are available
+    pub poll_interval_ms: u64,
+}
+
+impl Default for SqliteSourceConfig {
+    fn default() -> Self {
+        Self {
+            table: "events".to_string(),
+            consumer: None,
+            batch_size: 500,
+            poll_interval_ms: 1000,
+        }
+    }
+}
+
+impl SqliteSourceConfig {
+    /// Persist offsets under a consumer name
+    pub fn with_consumer(mut self, consumer: impl Into<String>) -> Self {
+        self.consumer = Some(consumer.into());
+        self
+    }
+}
+
+fn sqlite_error(error: rusqlite::Error) -> DivergenceError {
+    DivergenceError::ConfigError(format!("SQLite error: {}", error))
+}
+
+/// Reject table names that would need quoting
+fn table_name(name: &str) -> Result<String> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(DivergenceError::ConfigError(format!(
+            "Invalid SQLite table name: {:?}",
+            name
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// Run a query on the blocking pool
+async fn blocking<T, F>(conn: &Arc<Mutex<Connection>>, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+{
+    let conn = conn.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut conn)
+    })
+    .await
+    .map_err(|e| DivergenceError::ConfigError(format!("SQLite task failed: {}", e)))?
+}
+
+/// Event source replaying events from a SQLite table
+pub struct SqliteEventSource {
+    conn: Arc<Mutex<Connection>>,
+    config: SqliteSourceConfig,
+    /// Last event handed out
+    read: SqliteOffset,
+    /// Last event acknowledged along with everything before it
+    committed: SqliteOffset,
+    /// Delivered events in order, with whether each is acknowledged
+    in_flight: VecDeque<(String, SqliteOffset, bool)>,
+    malformed: u64,
+}
+
+impl SqliteEventSource {
+    /// Open (or create) a database file
+    pub fn open(path: impl AsRef<Path>, config: SqliteSourceConfig) -> Result<Self> {
+        Self::from_connection(Connection::open(path).map_err(sqlite_error)?, config)
+    }
+
+    /// Use an open connection, creating the tables if missing and resuming
+    /// from the consumer's stored offset
+    pub fn from_connection(conn: Connection, config: SqliteSourceConfig) -> Result<Self> {
+        let table = table_name(&config.table)?;
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {t} (
+                 seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                 event_id TEXT NOT NULL,
+                 actor_id TEXT NOT NULL,
+                 timestamp_ms INTEGER NOT NULL,
+                 payload TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS {t}_order ON {t} (timestamp_ms, seq);
+             CREATE TABLE IF NOT EXISTS {t}_offsets (
+                 consumer TEXT PRIMARY KEY,
+                 timestamp_ms INTEGER NOT NULL,
+                 seq INTEGER NOT NULL
+             );",
+            t = table
+        ))
+        .map_err(sqlite_error)?;
+
+        let stored = match &config.consumer {
+            Some(consumer) => conn
+                .query_row(
+                    &format!(
+                        "SELECT timestamp_ms, seq FROM {}_offsets WHERE consumer = ?1",
+                        table
+                    ),
+                    [consumer],
+                    |row| {
+                        Ok(SqliteOffset {
+                            timestamp_ms: row.get(0)?,
+                            seq: row.get(1)?,
+                        })
+                    },
+                )
+                .optional()
+                .map_err(sqlite_error)?,
+            None => None,
+        };
+        let offset = stored.unwrap_or(SqliteOffset::START);
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            config,
+            read: offset,
+            committed: offset,
+            in_flight: VecDeque::new(),
+            malformed: 0,
+        })
+    }
+
+    /// Start after an offset instead of the stored one
+    pub fn with_offset(mut self, offset: SqliteOffset) -> Self {
+        self.read = offset;
+        self.committed = offset;
+        self.in_flight.clear();
+        self
+    }
+
+    /// Settings
+    pub fn config(&self) -> &SqliteSourceConfig {
+        &self.config
+    }
+
+    /// Offset a restarted consumer resumes from
+    pub fn offset(&self) -> SqliteOffset {
+        self.committed
+    }
+
+    /// Rows skipped because their payload did not parse as an event
+    pub fn malformed_rows(&self) -> u64 {
+        self.malformed
+    }
+
+    /// Move the committed offset over the acknowledged prefix and store it
+    async fn commit(&mut self) -> Result<()> {
+        let before = self.committed;
+        while let Some(&(_, offset, true)) = self.in_flight.front() {
+            self.committed = offset;
+            self.in_flight.pop_front();
+        }
+
+        if let (Some(consumer), true) = (self.config.consumer.clone(), self.committed != before) {
+            let table = self.config.table.clone();
+            let offset = self.committed;
+            blocking(&self.conn, move |conn| {
+                conn.execute(
+                    &format!(
+                        "INSERT INTO {}_offsets (consumer, timestamp_ms, seq) VALUES (?1, ?2, ?3)
+                         ON CONFLICT (consumer) DO UPDATE
+                         SET timestamp_ms = excluded.timestamp_ms, seq = excluded.seq",
+                        table
+                    ),
+                    params![consumer, offset.timestamp_ms, offset.seq],
+                )
+                .map_err(sqlite_error)?;
+                Ok(())
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Append events to the table, returning how many were written
+    pub async fn append(&self, events: &[StreamEvent]) -> Result<usize> {
+        let rows = events
+            .iter()
+            .map(|event| {
+                serde_json::to_string(event)
+                    .map(|payload| {
+                        (
+                            event.event_id.clone(),
+                            event.actor_id.clone(),
+                            event.timestamp_ms,
+                            payload,
+                        )
+                    })
+                    .map_err(|e| DivergenceError::SerializationError(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let table = self.config.table.clone();
+        blocking(&self.conn, move |conn| {
+            let tx = conn.transaction().map_err(sqlite_error)?;
+            {
+                let mut insert = tx
+                    .prepare_cached(&format!(
+                        "INSERT INTO {} (event_id, actor_id, timestamp_ms, payload)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        table
+                    ))
+                    .map_err(sqlite_error)?;
+                for (event_id, actor_id, timestamp_ms, payload) in &rows {
+                    insert
+                        .execute(params![event_id, actor_id, timestamp_ms, payload])
+                        .map_err(sqlite_error)?;
+                }
+            }
+            tx.commit().map_err(sqlite_error)?;
+            Ok(rows.len())
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl EventSource for SqliteEventSource {
+    async fn receive(&mut self) -> Result<Vec<StreamEvent>> {
+        let table = self.config.table.clone();
+        let after = self.read;
+        let limit = self.config.batch_size.max(1) as i64;
+        let rows = blocking(&self.conn, move |conn| {
+            let mut select = conn
+                .prepare_cached(&format!(
+                    "SELECT timestamp_ms, seq, payload FROM {}
+                     WHERE (timestamp_ms, seq) > (?1, ?2)
+                     ORDER BY timestamp_ms, seq LIMIT ?3",
+                    table
+                ))
+                .map_err(sqlite_error)?;
+            let rows = select
+                .query_map(params![after.timestamp_ms, after.seq, limit], |row| {
+                    Ok((
+                        SqliteOffset {
+                            timestamp_ms: row.get(0)?,
+                            seq: row.get(1)?,
+                        },
+                        row.get::<_, String>(2)?,
+                    ))
+                })
+                .map_err(sqlite_error)?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(sqlite_error)
+        })
+        .await?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        let mut skipped = false;
+        for (offset, payload) in rows {
+            self.read = offset;
+            match StreamEvent::from_json(payload.as_bytes()) {
+                Ok(event) => {
+                    self.in_flight
+                        .push_back((event.event_id.clone(), offset, false));
+                    events.push(event);
+                }
+                Err(_) => {
+                    // Nothing will acknowledge it: count it as done
+                    self.malformed += 1;
+                    self.in_flight.push_back((String::new(), offset, true));
+                    skipped = true;
+                }
+            }
+        }
+        if skipped {
+            self.commit().await?;
+        }
+
+        if events.is_empty() {
+            tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+        }
+        Ok(events)
+    }
+
+    async fn acknowledge(&mut self, event_ids: &[String]) -> Result<()> {
+        for id in event_ids {
+            // Oldest unacknowledged delivery of this ID
+            if let Some(entry) = self
+                .in_flight
+                .iter_mut()
+                .find(|(event_id, _, acked)| !acked && event_id == id)
+            {
+                entry.2 = true;
+            }
+        }
+        self.commit().await
+    }
+
+    async fn health_check(&self) -> bool {
+        true
+    }
+}
+
+/// Alert sink appending alerts to a SQLite table
+pub struct SqliteAlertSink {
+    conn: Arc<Mutex<Connection>>,
+    table: String,
+}
+
+impl SqliteAlertSink {
+    /// Open (or create) a database file
+    pub fn open(path: impl AsRef<Path>, table: &str) -> Result<Self> {
+        Self::from_connection(Connection::open(path).map_err(sqlite_error)?, table)
+    }
+
+    /// Use an open connection, creating the table and indices if missing
+    pub fn from_connection(conn: Connection, table: &str) -> Result<Self> {
+        let table = table_name(table)?;
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {t} (
+                 alert_id TEXT NOT NULL,
+                 actor_a TEXT NOT NULL,
+                 actor_b TEXT NOT NULL,
+                 timestamp_ms INTEGER NOT NULL,
+                 risk_level TEXT NOT NULL,
+                 phi REAL NOT NULL,
+                 payload TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS {t}_dyad ON {t} (actor_a, actor_b, timestamp_ms);
+             CREATE INDEX IF NOT EXISTS {t}_time ON {t} (timestamp_ms);",
+            t = table
+        ))
+        .map_err(sqlite_error)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            table,
+        })
+    }
+
+    /// Archived alerts for a dyad (either orientation) since a time, oldest
+    /// first
+    pub async fn alerts_for_dyad(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        since_ms: i64,
+    ) -> Result<Vec<DivergenceAlert>> {
+        let table = self.table.clone();
+        let (a, b) = (actor_a.to_string(), actor_b.to_string());
+        let payloads = blocking(&self.conn, move |conn| {
+            let mut select = conn
+                .prepare_cached(&format!(
+                    "SELECT payload FROM {}
+                     WHERE ((actor_a = ?1 AND actor_b = ?2) OR (actor_a = ?2 AND actor_b = ?1))
+                       AND timestamp_ms >= ?3
+                     ORDER BY timestamp_ms",
+                    table
+                ))
+                .map_err(sqlite_error)?;
+            let rows = select
+                .query_map(params![a, b, since_ms], |row| row.get::<_, String>(0))
+                .map_err(sqlite_error)?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(sqlite_error)
+        })
+        .await?;
+        payloads
+            .iter()
+            .map(|payload| {
+                serde_json::from_str(payload)
+                    .map_err(|e| DivergenceError::SerializationError(e.to_string()))
+            })
+            .collect()
+    }
+
+    /// Number of archived alerts
+    pub async fn count(&self) -> Result<usize> {
+        let table = self.table.clone();
+        blocking(&self.conn, move |conn| {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map(|n| n as usize)
+            .map_err(sqlite_error)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl AlertSink for SqliteAlertSink {
+    async fn send(&mut self, alert: DivergenceAlert) -> Result<()> {
+        self.send_batch(vec![alert]).await
+    }
+
+    /// Appends the batch in one transaction
+    async fn send_batch(&mut self, alerts: Vec<DivergenceAlert>) -> Result<()> {
+        let rows = alerts
+            .into_iter()
+            .map(|alert| {
+                serde_json::to_string(&alert)
+                    .map(|payload| (alert, payload))
+                    .map_err(|e| DivergenceError::SerializationError(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let table = self.table.clone();
+        blocking(&self.conn, move |conn| {
+            let tx = conn.transaction().map_err(sqlite_error)?;
+            {
+                let mut insert = tx
+                    .prepare_cached(&format!(
+                        "INSERT INTO {} (alert_id, actor_a, actor_b, timestamp_ms, risk_level, phi, payload)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        table
+                    ))
+                    .map_err(sqlite_error)?;
+                for (alert, payload) in &rows {
+                    insert
+                        .execute(params![
+                            alert.alert_id,
+                            alert.actor_a,
+                            alert.actor_b,
+                            alert.timestamp_ms,
+                            alert.risk_level.as_str(),
+                            alert.phi,
+                            payload
+                        ])
+                        .map_err(sqlite_error)?;
+                }
+            }
+            tx.commit().map_err(sqlite_error)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::RiskLevel;
+
+    fn event(id: &str, ts: i64) -> StreamEvent {
+        StreamEvent::new(id, "A", vec![0.5, 0.5], ts)
+    }
+
+    fn ids(events: &[StreamEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.event_id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_source_resumes_from_offset() {
+        let path = std::env::temp_dir().join(format!("divergence-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let config = SqliteSourceConfig {
+            batch_size: 3,
+            poll_interval_ms: 1,
+            ..Default::default()
+        }
+        .with_consumer("engine");
+
+        {
+            let mut source = SqliteEventSource::open(&path, config.clone()).unwrap();
+            // Appended out of order, read by timestamp
+            let appended = [
+                event("e3", 30),
+                event("e1", 10),
+                event("e2", 20),
+                event("e5", 50),
+                event("e4", 40),
+            ];
+            assert_eq!(source.append(&appended).await.unwrap(), 5);
+
+            let batch = source.receive().await.unwrap();
+            assert_eq!(ids(&batch), ["e1", "e2", "e3"]);
+            // e2 outstanding holds the offset at e1
+            source
+                .acknowledge(&["e1".to_string(), "e3".to_string()])
+                .await
+                .unwrap();
+            assert_eq!(source.offset().timestamp_ms, 10);
+        }
+
+        // Restart: everything after the committed offset is redelivered
+        let mut source = SqliteEventSource::open(&path, config.clone()).unwrap();
+        let batch = source.receive().await.unwrap();
+        assert_eq!(ids(&batch), ["e2", "e3", "e4"]);
+        source
+            .acknowledge(&["e2".to_string(), "e3".to_string(), "e4".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(ids(&source.receive().await.unwrap()), ["e5"]);
+        assert!(source.receive().await.unwrap().is_empty());
+
+        // An explicit offset overrides the stored one
+        let mut replay = SqliteEventSource::open(&path, config)
+            .unwrap()
+            .with_offset(SqliteOffset::START);
+        assert_eq!(ids(&replay.receive().await.unwrap()), ["e1", "e2", "e3"]);
+
+        drop((source, replay));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_source_skips_malformed_rows() {
+        let config = SqliteSourceConfig {
+            poll_interval_ms: 1,
+            ..Default::default()
+        }
+        .with_consumer("engine");
+        let mut source =
+            SqliteEventSource::from_connection(Connection::open_in_memory().unwrap(), config)
+                .unwrap();
+        source.append(&[event("e1", 10)]).await.unwrap();
+        source
+            .conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO events (event_id, actor_id, timestamp_ms, payload)
+                 VALUES ('bad', 'A', 20, '{\"event_id\": 5}')",
+                [],
+            )
+            .unwrap();
+        source.append(&[event("e3", 30)]).await.unwrap();
+
+        let batch = source.receive().await.unwrap();
+        assert_eq!(ids(&batch), ["e1", "e3"]);
+        assert_eq!(source.malformed_rows(), 1);
+        assert!(source.receive().await.unwrap().is_empty());
+
+        // The bad row does not hold the offset back
+        source.acknowledge(&["e1".to_string()]).await.unwrap();
+        assert_eq!(source.offset().timestamp_ms, 20);
+        source.acknowledge(&["e3".to_string()]).await.unwrap();
+        assert_eq!(source.offset().timestamp_ms, 30);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_alert_sink() {
+        let alert = |id: &str, a: &str, b: &str, ts: i64| DivergenceAlert {
+            alert_id: id.to_string(),
+            actor_a: a.to_string(),
+            actor_b: b.to_string(),
+            phi: 1.5,
+            js: 0.3,
+            phi_z: None,
+            phi_adjusted: None,
+            p_value: None,
+            robustness: None,
+            d_phi_dt: 0.0,
+            risk_level: RiskLevel::Elevated,
+            escalation_probability: 0.4,
+            grievance_a_to_b: 0.0,
+            grievance_b_to_a: 0.0,
+            timestamp_ms: ts,
+            reason: "test".to_string(),
+            latency: None,
+        };
+
+        let mut sink =
+            SqliteAlertSink::from_connection(Connection::open_in_memory().unwrap(), "alerts")
+                .unwrap();
+        sink.send_batch(vec![
+            alert("a1", "A", "B", 100),
+            alert("a2", "A", "C", 200),
+            alert("a3", "B", "A", 300),
+        ])
+        .await
+        .unwrap();
+        sink.send(alert("a4", "A", "B", 50)).await.unwrap();
+        assert_eq!(sink.count().await.unwrap(), 4);
+
+        let dyad = sink.alerts_for_dyad("A", "B", 60).await.unwrap();
+        let found: Vec<&str> = dyad.iter().map(|a| a.alert_id.as_str()).collect();
+        assert_eq!(found, ["a1", "a3"]);
+
+        assert!(
+            SqliteAlertSink::from_connection(Connection::open_in_memory().unwrap(), "x; DROP")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Two-sample significance tests for scheme differences.
+//!
+//! A large Φ between two schemes estimated from thin data may be nothing
+//! but sampling noise. These tests treat the two actors' category counts as
+//! a 2×K contingency table and ask whether both could have been drawn from
+//! one distribution:
+//!
+//! - G-test (likelihood ratio): `G = 2 Σ O·ln(O/E)`, asymptotically χ²
+//! - Pearson χ²: `X² = Σ (O - E)² / E`
+//! - Permutation test: the G statistic's null distribution is sampled by
+//!   shuffling pooled observations between the two actors; exact in the
+//!   limit and valid for small or sparse tables where the χ² approximation
+//!   breaks down
+//!
+//! The G statistic equals `2·N·JS_w` (weighted Jensen-Shannon in nats), so
+//! a significant G means the observed divergence is not explained by
+//! sample size alone.
+
+use crate::error::{DivergenceError, Result};
+use crate::rng::{uniform, RngCore, SeedableRng, SplitMix64};
+use crate::scheme::CompressionScheme;
+use serde::{Deserialize, Serialize};
+
+/// Which two-sample test to run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum TwoSampleTest {
+    /// Likelihood-ratio G-test
+    #[default]
+    GTest,
+    /// Pearson χ² test
+    ChiSquared,
+    /// Permutation test on the G statistic
+    Permutation { n_permutations: usize, seed: u64 },
+}
+
+/// Outcome of a two-sample test
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestResult {
+    pub test: TwoSampleTest,
+    pub statistic: f64,
+    /// Categories observed in either sample, minus one
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+}
+
+impl TestResult {
+    /// Whether the difference is significant at level `alpha`
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+/// Validate a pair of count vectors, returning their totals
+fn validate_pair(a: &[f64], b: &[f64]) -> Result<(f64, f64)> {
+    if a.len() != b.len() {
+        return Err(DivergenceError::DimensionMismatch {
+            expected: a.len(),
+            got: b.len(),
+        });
+    }
+    if a.iter().chain(b).any(|&c| !c.is_finite() || c < 0.0) {
+        return Err(DivergenceError::InvalidDistribution(
+            "Counts must be finite and non-negative".to_string(),
+        ));
+    }
+    let (na, nb) = (a.iter().sum::<f64>(), b.iter().sum::<f64>());
+    if na <= 0.0 || nb <= 0.0 {
+        return Err(DivergenceError::InvalidDistribution(
+            "Both samples need at least one observation".to_string(),
+        ));
+    }
+    Ok((na, nb))
+}
+
+/// Observed categories minus one
+fn degrees_of_freedom(a: &[f64], b: &[f64]) -> usize {
+    a.iter()
+        .zip(b)
+        .filter(|(&x, &y)| x + y > 0.0)
+        .count()
+        .saturating_sub(1)
+}
+
+fn g_statistic(a: &[f64], b: &[f64], na: f64, nb: f64) -> f64 {
+    let n = na + nb;
+    let mut g = 0.0;
+    for (&x, &y) in a.iter().zip(b) {
+        let col = x + y;
+        for (o, row) in [(x, na), (y, nb)] {
+            if o > 0.0 {
+                g += o * (o / (row * col / n)).ln();
+            }
+        }
+    }
+    2.0 * g
+}
+
+fn chi_squared_statistic(a: &[f64], b: &[f64], na: f64, nb: f64) -> f64 {
+    let n = na + nb;
+    let mut x2 = 0.0;
+    for (&x, &y) in a.iter().zip(b) {
+        let col = x + y;
+        if col <= 0.0 {
+            continue;
+        }
+        for (o, row) in [(x, na), (y, nb)] {
+            let e = row * col / n;
+            x2 += (o - e).powi(2) / e;
+        }
+    }
+    x2
+}
+
+/// G-test of homogeneity between two count vectors
+pub fn g_test(a_counts: &[f64], b_counts: &[f64]) -> Result<TestResult> {
+    let (na, nb) = validate_pair(a_counts, b_counts)?;
+    let df = degrees_of_freedom(a_counts, b_counts);
+    let statistic = g_statistic(a_counts, b_counts, na, nb);
+    Ok(TestResult {
+        test: TwoSampleTest::GTest,
+        statistic,
+        degrees_of_freedom: df,
+        p_value: chi_squared_sf(statistic, df),
+    })
+}
+
+/// Pearson χ² test of homogeneity between two count vectors
+pub fn chi_squared_test(a_counts: &[f64], b_counts: &[f64]) -> Result<TestResult> {
+    let (na, nb) = validate_pair(a_counts, b_counts)?;
+    let df = degrees_of_freedom(a_counts, b_counts);
+    let statistic = chi_squared_statistic(a_counts, b_counts, na, nb);
+    Ok(TestResult {
+        test: TwoSampleTest::ChiSquared,
+        statistic,
+        degrees_of_freedom: df,
+        p_value: chi_squared_sf(statistic, df),
+    })
+}
+
+/// Permutation test on the G statistic
+///
+/// Counts are rounded to whole observations. The p-value is
+/// `(1 + #{G_perm ≥ G_obs}) / (1 + n_permutations)`.
+pub fn permutation_test(
+    a_counts: &[f64],
+    b_counts: &[f64],
+    n_permutations: usize,
+    seed: u64,
+) -> Result<TestResult> {
+    permutation_test_with_rng::<SplitMix64>(a_counts, b_counts, n_permutations, seed)
+}
+
+/// Permutation test shuffling with generator `R` seeded with `seed`
+pub fn permutation_test_with_rng<R: RngCore + SeedableRng>(
+    a_counts: &[f64],
+    b_counts: &[f64],
+    n_permutations: usize,
+    seed: u64,
+) -> Result<TestResult> {
+    let a: Vec<f64> = a_counts.iter().map(|c| c.round()).collect();
+    let b: Vec<f64> = b_counts.iter().map(|c| c.round()).collect();
+    let (na, nb) = validate_pair(&a, &b)?;
+    if n_permutations == 0 {
+        return Err(DivergenceError::ConfigError(
+            "Permutation test needs at least one permutation".to_string(),
+        ));
+    }
+
+    let observed = g_statistic(&a, &b, na, nb);
+
+    // Pooled observations as category labels
+    let mut pooled: Vec<usize> = a
+        .iter()
+        .zip(&b)
+        .enumerate()
+        .flat_map(|(k, (&x, &y))| std::iter::repeat_n(k, (x + y) as usize))
+        .collect();
+    let n_a = na as usize;
+
+    let mut rng = R::seed_from_u64(seed);
+    let mut perm_a = vec![0.0; a.len()];
+    let mut perm_b = vec![0.0; a.len()];
+    let mut at_least = 0usize;
+    for _ in 0..n_permutations {
+        // Partial Fisher-Yates: only the first n_a draws matter
+        let len = pooled.len();
+        for i in 0..n_a {
+            let j = i + (uniform(&mut rng) * (len - i) as f64) as usize;
+            pooled.swap(i, j.min(len - 1));
+        }
+        perm_a.iter_mut().for_each(|c| *c = 0.0);
+        for &k in &pooled[..n_a] {
+            perm_a[k] += 1.0;
+        }
+        for ((pb, &x), (&y, &pa)) in perm_b.iter_mut().zip(&a).zip(b.iter().zip(&perm_a)) {
+            *pb = x + y - pa;
+        }
+        if g_statistic(&perm_a, &perm_b, na, nb) >= observed - 1e-12 {
+            at_least += 1;
+        }
+    }
+
+    Ok(TestResult {
+        test: TwoSampleTest::Permutation {
+            n_permutations,
+            seed,
+        },
+        statistic: observed,
+        degrees_of_freedom: degrees_of_freedom(&a, &b),
+        p_value: (1 + at_least) as f64 / (1 + n_permutations) as f64,
+    })
+}
+
+/// Run the chosen two-sample test
+pub fn two_sample_test(
+    a_counts: &[f64],
+    b_counts: &[f64],
+    test: TwoSampleTest,
+) -> Result<TestResult> {
+    match test {
+        TwoSampleTest::GTest => g_test(a_counts, b_counts),
+        TwoSampleTest::ChiSquared => chi_squared_test(a_counts, b_counts),
+        TwoSampleTest::Permutation {
+            n_permutations,
+            seed,
+        } => permutation_test(a_counts, b_counts, n_permutations, seed),
+    }
+}
+
+impl CompressionScheme {
+    /// Test whether this scheme differs significantly from another
+    ///
+    /// Schemes do not keep raw counts, so each distribution is scaled to
+    /// its (effective) sample size to reconstruct expected counts.
+    pub fn significance(
+        &self,
+        other: &CompressionScheme,
+        n_self: f64,
+        n_other: f64,
+        test: TwoSampleTest,
+    ) -> Result<TestResult> {
+        let a: Vec<f64> = self.distribution().iter().map(|p| p * n_self).collect();
+        let b: Vec<f64> = other.distribution().iter().map(|p| p * n_other).collect();
+        two_sample_test(&a, &b, test)
+    }
+}
+
+/// Survival function of the χ² distribution, P(X ≥ x)
+pub fn chi_squared_sf(x: f64, df: usize) -> f64 {
+    if df == 0 || x <= 0.0 {
+        return 1.0;
+    }
+    upper_regularized_gamma(df as f64 / 2.0, x / 2.0)
+}
+
+/// ln Γ(x) (Lanczos approximation, g = 7)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut a = COEFFS[0];
+    let t = x + 7.5;
+    for (i, &c) in COEFFS.iter().enumerate().skip(1) {
+        a += c / (x + i as f64);
+    }
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+/// Q(s, x) = Γ(s, x) / Γ(s)
+fn upper_regularized_gamma(s: f64, x: f64) -> f64 {
+    let log_prefix = s * x.ln() - x - ln_gamma(s);
+    if x < s + 1.0 {
+        // Series for P(s, x)
+        let mut term = 1.0 / s;
+        let mut sum = term;
+        let mut denom = s;
+        for _ in 0..500 {
+            denom += 1.0;
+            term *= x / denom;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (1.0 - sum * log_prefix.exp()).clamp(0.0, 1.0)
+    } else {
+        // Continued fraction for Q(s, x) (modified Lentz)
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - s;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - s);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        (h * log_prefix.exp()).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chi_squared_sf_reference_values() {
+        // Critical values at α = 0.05
+        assert!((chi_squared_sf(3.841_458_8, 1) - 0.05).abs() < 1e-6);
+        assert!((chi_squared_sf(11.070_497_7, 5) - 0.05).abs() < 1e-6);
+        assert!((chi_squared_sf(2.0, 2) - (-1.0f64).exp()).abs() < 1e-12);
+        assert_eq!(chi_squared_sf(0.0, 3), 1.0);
+    }
+
+    #[test]
+    fn test_sample_size_decides_significance() {
+        // Same proportions, ten times the data
+        let small = g_test(&[6.0, 4.0], &[4.0, 6.0]).unwrap();
+        let large = g_test(&[60.0, 40.0], &[40.0, 60.0]).unwrap();
+        assert!(!small.is_significant(0.05));
+        assert!(large.is_significant(0.05));
+        assert!((large.statistic - 10.0 * small.statistic).abs() < 1e-9);
+
+        let chi = chi_squared_test(&[60.0, 40.0], &[40.0, 60.0]).unwrap();
+        assert!((chi.statistic - 8.0).abs() < 1e-9);
+        assert_eq!(chi.degrees_of_freedom, 1);
+
+        assert!(g_test(&[1.0, 2.0], &[1.0]).is_err());
+        assert!(g_test(&[0.0, 0.0], &[1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_permutation_agrees_with_asymptotics() {
+        let a = [30.0, 20.0, 10.0];
+        let b = [15.0, 20.0, 25.0];
+        let asymptotic = g_test(&a, &b).unwrap();
+        let perm = permutation_test(&a, &b, 2000, 7).unwrap();
+        assert!((perm.p_value - asymptotic.p_value).abs() < 0.02);
+
+        let same = permutation_test(&a, &a, 200, 7).unwrap();
+        assert!(same.p_value > 0.5);
+    }
+
+    #[test]
+    fn test_scheme_significance() {
+        let a = CompressionScheme::new("A", vec![0.6, 0.4], None);
+        let b = CompressionScheme::new("B", vec![0.4, 0.6], None);
+        let thin = a
+            .significance(&b, 10.0, 10.0, TwoSampleTest::GTest)
+            .unwrap();
+        let rich = a
+            .significance(&b, 1000.0, 1000.0, TwoSampleTest::GTest)
+            .unwrap();
+        assert!(thin.p_value > rich.p_value);
+        assert!(rich.is_significant(0.01));
+    }
+}
@@ -3,7 +3,8 @@
 //! Designed for integration with:
 //! - Apache Kafka
 //! - Apache Flink
-//! - AWS Kinesis
+//! - AWS Kinesis (`KinesisEventSource`, feature `aws`)
+//! - NATS JetStream (`NatsEventSource`/`NatsAlertSink`, feature `nats`)
 //! - Custom event streams
 //!
 //! ## Architecture
@@ -13,19 +14,51 @@
 //!                                ↓
 //!                    [CompressionScheme Updates]
 //! ```
-
-use crate::error::{DivergenceError, Result};
-use crate::model::CompressionDynamicsModel;
+//!
+//! ## Ownership
+//!
+//! At high event rates the per-event copies dominate, so event payloads are
+//! shared rather than owned:
+//!
+//! - `observation` is an `Arc<[f64]>`: cloning an event (fan-out to several
+//!   processors, retries) bumps a refcount instead of copying the vector.
+//! - `source` and metadata keys/values are `Arc<str>`. A [`MetadataArena`]
+//!   interns them so the handful of distinct strings a feed uses is stored
+//!   once, however many events carry them.
+//! - [`StreamProcessor::process_observation`] takes borrowed slices, for
+//!   callers that decode straight from a buffer and never build an event.
+//!
+//! The model copies the observation into the scheme on update; nothing
+//! downstream retains the event.
+
+use crate::audit::config_value;
+use crate::digest::{Digest, DigestBuilder, DigestConfig};
+use crate::error::{DivergenceError, Result, ResultExt};
+use crate::event_schema::{EVENT_SCHEMA_VERSION, LEGACY_EVENT_SCHEMA_VERSION};
+use crate::history::HistoryPolicy;
+use crate::latency::{now_ms, AlertLatency, LatencyReport, LatencyWindow};
+use crate::model::{ActorId, CompressionDynamicsModel, EventAttribution};
+use crate::risk_ranking::{RankedDyad, RiskMetric, RiskRanking};
+use crate::rng::{SeedableRng, SplitMix64};
+use crate::robustness::NoiseModel;
 use crate::scheme::RiskLevel;
+use crate::stats::TwoSampleTest;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 
 /// Incoming event from data stream
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamEvent {
+    /// Payload schema version (see [`crate::event_schema`])
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+
     /// Event ID (for deduplication)
     pub event_id: String,
 
@@ -33,17 +66,247 @@ pub struct StreamEvent {
     pub actor_id: String,
 
     /// Observation vector (category distribution update)
-    pub observation: Vec<f64>,
+    pub observation: Arc<[f64]>,
+
+    /// Actor on the receiving end, for events implicating two actors
+    /// ("A did X to B"); set together with `target_observation`
+    #[serde(default)]
+    pub target_actor_id: Option<String>,
+
+    /// Target's observation of the same event
+    #[serde(default)]
+    pub target_observation: Option<Arc<[f64]>>,
 
     /// Event timestamp in milliseconds
     pub timestamp_ms: i64,
 
     /// Event source (GDELT, news, social, etc.)
-    pub source: String,
+    pub source: Arc<str>,
 
     /// Additional metadata
     #[serde(default)]
-    pub metadata: HashMap<String, String>,
+    pub metadata: EventMetadata,
+
+    /// Wall-clock time the event entered the pipeline (ms); stamped on
+    /// receipt rather than carried in the payload
+    #[serde(skip)]
+    pub ingested_ms: Option<i64>,
+}
+
+fn legacy_schema_version() -> u32 {
+    LEGACY_EVENT_SCHEMA_VERSION
+}
+
+/// Event metadata with shared keys and values
+pub type EventMetadata = HashMap<Arc<str>, Arc<str>>;
+
+/// Metadata key naming the actor an event is attributed to
+pub const SOURCE_ACTOR_KEY: &str = "source_actor";
+/// Metadata key for the event type (e.g. a CAMEO code)
+pub const EVENT_TYPE_KEY: &str = "event_type";
+/// Metadata key for the event location
+pub const GEO_KEY: &str = "geo";
+/// Metadata key for the processing lane (`"high"` or `"normal"`)
+pub const PRIORITY_KEY: &str = "priority";
+
+/// Processing lane of an event
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+pub enum EventPriority {
+    #[default]
+    Normal,
+    High,
+}
+
+impl EventPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventPriority::Normal => "normal",
+            EventPriority::High => "high",
+        }
+    }
+}
+
+impl StreamEvent {
+    /// Create an event with empty source and metadata
+    pub fn new(
+        event_id: impl Into<String>,
+        actor_id: impl Into<String>,
+        observation: impl Into<Arc<[f64]>>,
+        timestamp_ms: i64,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            event_id: event_id.into(),
+            actor_id: actor_id.into(),
+            observation: observation.into(),
+            target_actor_id: None,
+            target_observation: None,
+            timestamp_ms,
+            source: Arc::from(""),
+            metadata: EventMetadata::new(),
+            ingested_ms: None,
+        }
+    }
+
+    /// Set the event source
+    pub fn with_source(mut self, source: Arc<str>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Set the event metadata
+    pub fn with_metadata(mut self, metadata: EventMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Attribute the event to another actor's actions
+    pub fn with_source_actor(mut self, source_actor: Arc<str>) -> Self {
+        self.metadata
+            .insert(Arc::from(SOURCE_ACTOR_KEY), source_actor);
+        self
+    }
+
+    /// Make the event an interaction with a target actor, who observes it
+    /// as `observation`
+    pub fn with_target(
+        mut self,
+        target_actor_id: impl Into<String>,
+        observation: impl Into<Arc<[f64]>>,
+    ) -> Self {
+        self.target_actor_id = Some(target_actor_id.into());
+        self.target_observation = Some(observation.into());
+        self
+    }
+
+    /// Target actor and observation of an interaction event
+    ///
+    /// Errors when only one of the two is set.
+    pub fn target(&self) -> Result<Option<(&str, &[f64])>> {
+        match (&self.target_actor_id, &self.target_observation) {
+            (Some(target), Some(observation)) => Ok(Some((target, observation))),
+            (None, None) => Ok(None),
+            _ => Err(DivergenceError::ConfigError(
+                "target_actor_id and target_observation must be set together".to_string(),
+            ))
+            .with_event(&self.event_id),
+        }
+    }
+
+    /// Apply the event to a model: the actor's observation, and for an
+    /// interaction the target's too, in one step
+    pub fn apply(&self, model: &mut CompressionDynamicsModel) -> Result<()> {
+        let timestamp_ms = Some(self.timestamp_ms);
+        match self.target()? {
+            Some((target, target_observation)) => model.update_interaction(
+                &self.actor_id,
+                &self.observation,
+                target,
+                target_observation,
+                timestamp_ms,
+                self.attribution(),
+            ),
+            None => model
+                .update_scheme_with_attribution(
+                    &self.actor_id,
+                    &self.observation,
+                    timestamp_ms,
+                    self.attribution(),
+                )
+                .map(|_| ()),
+        }
+        .with_event(&self.event_id)
+    }
+
+    /// Tag the event with a processing lane
+    pub fn with_priority(mut self, priority: EventPriority) -> Self {
+        self.metadata
+            .insert(Arc::from(PRIORITY_KEY), Arc::from(priority.as_str()));
+        self
+    }
+
+    /// Lane the event was tagged with (`Normal` when untagged)
+    pub fn priority(&self) -> EventPriority {
+        match self.metadata.get(PRIORITY_KEY).map(|s| s.as_ref()) {
+            Some("high") => EventPriority::High,
+            _ => EventPriority::Normal,
+        }
+    }
+
+    /// Actor the event is attributed to, if tagged and not the actor itself
+    pub fn source_actor(&self) -> Option<&str> {
+        self.metadata
+            .get(SOURCE_ACTOR_KEY)
+            .map(|s| s.as_ref())
+            .filter(|s| !s.is_empty() && *s != self.actor_id)
+    }
+
+    /// Provenance recorded in model history when the event is ingested
+    pub fn attribution(&self) -> EventAttribution {
+        let get = |key: &str| self.metadata.get(key).map(|v| v.to_string());
+        EventAttribution {
+            event_id: Some(self.event_id.clone()),
+            source_actor: self.source_actor().map(String::from),
+            event_type: get(EVENT_TYPE_KEY),
+            geo: get(GEO_KEY),
+        }
+    }
+}
+
+/// Interning arena for event sources and metadata strings
+///
+/// Feeds reuse a small vocabulary of keys and values (`"source"`,
+/// `"GDELT"`, country codes). Interning hands out clones of a single
+/// `Arc<str>` per distinct string, so building an event's metadata
+/// allocates only the map itself.
+#[derive(Debug, Default)]
+pub struct MetadataArena {
+    strings: HashSet<Arc<str>>,
+}
+
+impl MetadataArena {
+    /// Create an empty arena
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shared copy of a string, allocating only on first sight
+    pub fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(existing) = self.strings.get(s) {
+            return Arc::clone(existing);
+        }
+        let interned: Arc<str> = Arc::from(s);
+        self.strings.insert(Arc::clone(&interned));
+        interned
+    }
+
+    /// Build event metadata from borrowed key/value pairs
+    pub fn metadata<'a, I>(&mut self, pairs: I) -> EventMetadata
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        pairs
+            .into_iter()
+            .map(|(k, v)| (self.intern(k), self.intern(v)))
+            .collect()
+    }
+
+    /// Number of distinct strings held
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether the arena is empty
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Drop strings no longer referenced by any event
+    pub fn purge(&mut self) {
+        self.strings.retain(|s| Arc::strong_count(s) > 1);
+    }
 }
 
 /// Alert generated when divergence exceeds threshold
@@ -59,17 +322,40 @@ pub struct DivergenceAlert {
     /// Current metrics
     pub phi: f64,
     pub js: f64,
+    /// Φ z-scored against the dyad's own history
+    #[serde(default)]
+    pub phi_z: Option<f64>,
+    /// Seasonally adjusted Φ (when the model has seasonality configured)
+    #[serde(default)]
+    pub phi_adjusted: Option<f64>,
+    /// Significance of the scheme difference (when gating is configured)
+    #[serde(default)]
+    pub p_value: Option<f64>,
+    /// Robustness score of Φ to observation noise (when configured)
+    #[serde(default)]
+    pub robustness: Option<f64>,
     pub d_phi_dt: f64,
 
     /// Risk assessment
     pub risk_level: RiskLevel,
     pub escalation_probability: f64,
 
+    /// Directed grievance A holds toward B
+    #[serde(default)]
+    pub grievance_a_to_b: f64,
+    /// Directed grievance B holds toward A
+    #[serde(default)]
+    pub grievance_b_to_a: f64,
+
     /// Timestamp
     pub timestamp_ms: i64,
 
     /// Alert reason
     pub reason: String,
+
+    /// Pipeline timestamps (see [`crate::latency`])
+    #[serde(default)]
+    pub latency: Option<AlertLatency>,
 }
 
 /// Configuration for streaming processor
@@ -81,9 +367,22 @@ pub struct StreamConfig {
     /// Alert threshold for JS divergence
     pub js_alert_threshold: f64,
 
+    /// Alert threshold for Φ in z-units relative to the dyad's history
+    #[serde(default)]
+    pub phi_z_alert_threshold: Option<f64>,
+
     /// Alert threshold for escalation probability
     pub escalation_alert_threshold: f64,
 
+    /// Suppress alerts whose scheme difference is not statistically
+    /// significant (off by default)
+    #[serde(default)]
+    pub significance: Option<SignificanceGate>,
+
+    /// Attach a Φ robustness score to alerts (off by default)
+    #[serde(default)]
+    pub robustness: Option<RobustnessCheck>,
+
     /// Minimum interval between alerts for same dyad (ms)
     pub alert_cooldown_ms: i64,
 
@@ -92,6 +391,22 @@ pub struct StreamConfig {
 
     /// Enable deduplication
     pub deduplicate: bool,
+
+    /// Also drop events whose content repeats under a new ID (off by default)
+    #[serde(default)]
+    pub content_dedup: Option<ContentDedup>,
+
+    /// Reorder batches so high-priority events go first (off by default)
+    #[serde(default)]
+    pub priority: Option<PriorityLanes>,
+
+    /// Degrade gracefully when the queue backs up (off by default)
+    #[serde(default)]
+    pub load_shedding: Option<LoadShedding>,
+
+    /// Build periodic digests on event time (off by default)
+    #[serde(default)]
+    pub digest: Option<DigestConfig>,
 }
 
 impl Default for StreamConfig {
@@ -99,14 +414,306 @@ impl Default for StreamConfig {
         Self {
             phi_alert_threshold: 2.0,
             js_alert_threshold: 0.6,
+            phi_z_alert_threshold: None,
             escalation_alert_threshold: 0.7,
+            significance: None,
+            robustness: None,
             alert_cooldown_ms: 300_000, // 5 minutes
             batch_size: 100,
             deduplicate: true,
+            content_dedup: None,
+            priority: None,
+            load_shedding: None,
+            digest: None,
+        }
+    }
+}
+
+impl StreamConfig {
+    /// Check settings whose type admits invalid values
+    pub fn validate(&self) -> Result<()> {
+        if let Some(check) = &self.robustness {
+            check.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Significance gate for alerts
+///
+/// Schemes carry no raw counts, so each is treated as `sample_size`
+/// observations when testing whether the two differ.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignificanceGate {
+    /// Maximum p-value for an alert to fire
+    pub alpha: f64,
+
+    /// Effective observations behind each scheme
+    pub sample_size: f64,
+
+    /// Test to run
+    pub test: TwoSampleTest,
+}
+
+impl Default for SignificanceGate {
+    fn default() -> Self {
+        Self {
+            alpha: 0.05,
+            sample_size: 100.0,
+            test: TwoSampleTest::GTest,
+        }
+    }
+}
+
+/// Content-hash deduplication
+///
+/// Two events are duplicates when they share the actor ID, the exact
+/// observation values and the timestamp bucket, whatever their event IDs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentDedup {
+    /// Width of the timestamp bucket (ms); 1 requires identical timestamps
+    pub bucket_ms: i64,
+}
+
+impl Default for ContentDedup {
+    fn default() -> Self {
+        Self { bucket_ms: 1_000 }
+    }
+}
+
+impl ContentDedup {
+    /// Content hash of an event under this policy
+    pub fn hash(&self, actor_id: &str, observation: &[f64], timestamp_ms: i64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        actor_id.hash(&mut hasher);
+        for x in observation {
+            x.to_bits().hash(&mut hasher);
+        }
+        timestamp_ms
+            .div_euclid(self.bucket_ms.max(1))
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Priority lanes for batch processing
+///
+/// During a backlog, events tagged [`EventPriority::High`], shock events
+/// and updates to actors in high-stakes dyads are processed before the rest
+/// of the batch. After `max_consecutive_high` high-lane events one normal
+/// event is let through, so a steady stream of shocks cannot starve the
+/// normal lane. Order within each lane is preserved, and so is each
+/// actor's order across lanes: events before one of an actor's high-lane
+/// events move up with it, and a normal event is only let through once no
+/// earlier event of its actors is waiting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityLanes {
+    /// Event types (the `event_type` metadata) treated as shocks
+    #[serde(default)]
+    pub shock_event_types: Vec<String>,
+
+    /// Dyads whose members' updates are always high priority
+    #[serde(default)]
+    pub high_stakes_dyads: Vec<(String, String)>,
+
+    /// High-lane events processed before a normal one gets a turn
+    /// (0 disables starvation protection)
+    #[serde(default = "default_max_consecutive_high")]
+    pub max_consecutive_high: usize,
+}
+
+fn default_max_consecutive_high() -> usize {
+    8
+}
+
+impl Default for PriorityLanes {
+    fn default() -> Self {
+        Self {
+            shock_event_types: Vec::new(),
+            high_stakes_dyads: Vec::new(),
+            max_consecutive_high: default_max_consecutive_high(),
+        }
+    }
+}
+
+impl PriorityLanes {
+    /// Lane an event is processed in
+    pub fn classify(&self, event: &StreamEvent) -> EventPriority {
+        let is_shock = event
+            .metadata
+            .get(EVENT_TYPE_KEY)
+            .is_some_and(|t| self.shock_event_types.iter().any(|s| s == t.as_ref()));
+        let high_stakes = self
+            .high_stakes_dyads
+            .iter()
+            .any(|(a, b)| *a == event.actor_id || *b == event.actor_id);
+        if is_shock || high_stakes {
+            EventPriority::High
+        } else {
+            event.priority()
+        }
+    }
+
+    /// Processing order for a batch
+    pub fn schedule(&self, events: Vec<StreamEvent>) -> Vec<StreamEvent> {
+        let order = {
+            let actors = |i: usize| {
+                let event = &events[i];
+                std::iter::once(event.actor_id.as_str()).chain(event.target_actor_id.as_deref())
+            };
+
+            // Newest first, so an event joins its actors' later high events
+            let mut high = vec![false; events.len()];
+            let mut promoted: HashSet<&str> = HashSet::new();
+            for i in (0..events.len()).rev() {
+                if self.classify(&events[i]) == EventPriority::High
+                    || actors(i).any(|a| promoted.contains(a))
+                {
+                    high[i] = true;
+                    promoted.extend(actors(i));
+                }
+            }
+            let (high_lane, mut normal_lane): (Vec<usize>, Vec<usize>) =
+                (0..events.len()).partition(|&i| high[i]);
+
+            if self.max_consecutive_high == 0 {
+                high_lane.into_iter().chain(normal_lane).collect()
+            } else {
+                // Unscheduled events per actor, oldest first
+                let mut waiting: HashMap<&str, VecDeque<usize>> = HashMap::new();
+                for i in 0..events.len() {
+                    for actor in actors(i) {
+                        waiting.entry(actor).or_default().push_back(i);
+                    }
+                }
+                let mut order = Vec::with_capacity(events.len());
+                let mut take = |i: usize, waiting: &mut HashMap<&str, VecDeque<usize>>| {
+                    for actor in actors(i) {
+                        waiting.get_mut(actor).and_then(VecDeque::pop_front);
+                    }
+                    order.push(i);
+                };
+                for chunk in high_lane.chunks(self.max_consecutive_high) {
+                    for &i in chunk {
+                        take(i, &mut waiting);
+                    }
+                    if chunk.len() == self.max_consecutive_high {
+                        let ready = normal_lane
+                            .iter()
+                            .position(|&i| actors(i).all(|a| waiting[a].front() == Some(&i)));
+                        if let Some(pos) = ready {
+                            take(normal_lane.remove(pos), &mut waiting);
+                        }
+                    }
+                }
+                order.extend(normal_lane);
+                order
+            }
+        };
+
+        let mut events: Vec<Option<StreamEvent>> = events.into_iter().map(Some).collect();
+        order.into_iter().filter_map(|i| events[i].take()).collect()
+    }
+}
+
+/// Load shedding under overload
+///
+/// Engages when the queue depth reported through
+/// [`StreamProcessor::observe_queue_depth`] reaches `engage_depth`, and
+/// releases once it falls to `release_depth`. While engaged,
+/// [`StreamProcessor::process_batch`] trades alert freshness for throughput:
+///
+/// - each actor keeps only its latest `max_events_per_actor` normal-priority
+///   events per batch; the rest are dropped (and still acknowledged)
+/// - normal-priority updates check an actor's dyads at most once per
+///   `check_interval_ms` of event time
+///
+/// High-priority events (see [`PriorityLanes`]) are never shed and always
+/// checked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadShedding {
+    /// Queue depth at which shedding starts
+    pub engage_depth: usize,
+    /// Queue depth at which shedding stops
+    pub release_depth: usize,
+    /// Normal-priority events kept per actor per batch
+    pub max_events_per_actor: usize,
+    /// Minimum event time between an actor's normal-priority checks
+    pub check_interval_ms: i64,
+}
+
+impl Default for LoadShedding {
+    fn default() -> Self {
+        Self {
+            engage_depth: 10_000,
+            release_depth: 1_000,
+            max_events_per_actor: 1,
+            check_interval_ms: 60_000,
+        }
+    }
+}
+
+/// Load-shedding counters
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SheddingMetrics {
+    /// Shedding currently engaged
+    pub active: bool,
+    /// Times shedding engaged
+    pub activations: u64,
+    /// Events dropped by per-actor sampling
+    pub events_shed: u64,
+    /// Actor updates whose dyad checks were skipped
+    pub checks_skipped: u64,
+}
+
+/// Robustness scoring of alerted dyads
+///
+/// Each alert perturbs the dyad's schemes `n_samples` times; see
+/// [`CompressionDynamicsModel::phi_robustness`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobustnessCheck {
+    pub noise: NoiseModel,
+    pub n_samples: usize,
+    /// Seed for the perturbation draws
+    pub seed: u64,
+}
+
+impl RobustnessCheck {
+    /// Check the noise model and sample count
+    pub fn validate(&self) -> Result<()> {
+        if self.n_samples == 0 {
+            return Err(DivergenceError::ConfigError(
+                "Robustness needs at least one sample".to_string(),
+            ));
+        }
+        self.noise.validate()
+    }
+}
+
+impl Default for RobustnessCheck {
+    fn default() -> Self {
+        Self {
+            noise: NoiseModel::default(),
+            n_samples: 100,
+            seed: crate::robustness::ROBUSTNESS_SEED,
         }
     }
 }
 
+/// Decode a JSON payload holding one event or an array of events
+///
+/// Events are validated and upgraded as by [`StreamEvent::from_json`].
+/// `None` when the payload or any event in it is invalid.
+pub fn decode_events(bytes: &[u8]) -> Option<Vec<StreamEvent>> {
+    match serde_json::from_slice(bytes).ok()? {
+        serde_json::Value::Array(payloads) => payloads
+            .into_iter()
+            .map(|payload| StreamEvent::from_json_value(payload).ok())
+            .collect(),
+        payload => StreamEvent::from_json_value(payload).ok().map(|e| vec![e]),
+    }
+}
+
 /// Trait for event sources
 #[async_trait]
 pub trait EventSource: Send + Sync {
@@ -118,6 +725,11 @@ pub trait EventSource: Send + Sync {
 
     /// Check if source is healthy
     async fn health_check(&self) -> bool;
+
+    /// Events waiting beyond the last batch, when the source can tell
+    async fn backlog(&self) -> Option<usize> {
+        None
+    }
 }
 
 /// Trait for alert sinks
@@ -133,14 +745,29 @@ pub trait AlertSink: Send + Sync {
         }
         Ok(())
     }
+
+    /// Send a periodic digest; sinks without a digest format ignore it
+    async fn send_digest(&mut self, _digest: &Digest) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Real-time divergence monitoring processor
 pub struct StreamProcessor {
     model: Arc<RwLock<CompressionDynamicsModel>>,
     config: StreamConfig,
-    last_alert: HashMap<(String, String), i64>,
+    last_alert: HashMap<(ActorId, ActorId), i64>,
     processed_events: HashMap<String, i64>,
+    processed_content: HashMap<u64, i64>,
+    processing_latency: LatencyWindow,
+    end_to_end_latency: LatencyWindow,
+    shedding: SheddingMetrics,
+    /// Event time of each actor's last dyad check while shedding
+    last_check: HashMap<String, i64>,
+    ranking: RiskRanking,
+    digest: Option<DigestBuilder>,
+    digests: Vec<Digest>,
+    rejected_events: u64,
 }
 
 impl StreamProcessor {
@@ -151,71 +778,183 @@ impl StreamProcessor {
             config,
             last_alert: HashMap::new(),
             processed_events: HashMap::new(),
+            processed_content: HashMap::new(),
+            processing_latency: LatencyWindow::default(),
+            end_to_end_latency: LatencyWindow::default(),
+            shedding: SheddingMetrics::default(),
+            last_check: HashMap::new(),
+            ranking: RiskRanking::new(),
+            digest: None,
+            digests: Vec::new(),
+            rejected_events: 0,
         }
     }
 
     /// Process a single event
+    ///
+    /// The event's attribution metadata is recorded in model history, and
+    /// events tagged with a source actor also feed directed grievance.
+    /// Interaction events update both actors (see [`StreamEvent::apply`]).
     pub async fn process_event(&mut self, event: StreamEvent) -> Result<Vec<DivergenceAlert>> {
+        let ingested_ms = event.ingested_ms.unwrap_or_else(now_ms);
+        if self.is_duplicate(
+            &event.event_id,
+            &event.actor_id,
+            &event.observation,
+            event.timestamp_ms,
+        ) {
+            return Ok(vec![]);
+        }
+        {
+            let mut model = self.model.write().await;
+            event.apply(&mut model)?;
+        }
+        let mut alerts = self
+            .check_alerts(&event.actor_id, event.timestamp_ms)
+            .await
+            .with_event(&event.event_id)?;
+        if let Some(target) = &event.target_actor_id {
+            alerts.extend(
+                self.check_alerts(target, event.timestamp_ms)
+                    .await
+                    .with_event(&event.event_id)?,
+            );
+        }
+        self.record_processed(ingested_ms, now_ms(), &mut alerts);
+        self.advance_digest(&alerts, event.timestamp_ms).await?;
+        Ok(alerts)
+    }
+
+    /// Process a single observation from borrowed data
+    ///
+    /// Zero-copy counterpart to [`process_event`](Self::process_event): the
+    /// only copy made is the event ID when deduplication records it.
+    pub async fn process_observation(
+        &mut self,
+        event_id: &str,
+        actor_id: &str,
+        observation: &[f64],
+        timestamp_ms: i64,
+    ) -> Result<Vec<DivergenceAlert>> {
+        let ingested_ms = now_ms();
+
         // Deduplication
-        if self.config.deduplicate {
-            if self.processed_events.contains_key(&event.event_id) {
-                return Ok(vec![]);
-            }
-            self.processed_events
-                .insert(event.event_id.clone(), event.timestamp_ms);
+        if self.is_duplicate(event_id, actor_id, observation, timestamp_ms) {
+            return Ok(vec![]);
         }
 
         // Update model
         {
             let mut model = self.model.write().await;
-            model.update_scheme(
-                &event.actor_id,
-                &event.observation,
-                Some(event.timestamp_ms),
-            )?;
+            model
+                .update_scheme(actor_id, observation, Some(timestamp_ms))
+                .with_event(event_id)?;
         }
 
         // Check for alerts
-        self.check_alerts(&event.actor_id, event.timestamp_ms).await
+        let mut alerts = self
+            .check_alerts(actor_id, timestamp_ms)
+            .await
+            .with_event(event_id)?;
+        self.record_processed(ingested_ms, now_ms(), &mut alerts);
+        self.advance_digest(&alerts, timestamp_ms).await?;
+        Ok(alerts)
     }
 
     /// Process batch of events
+    ///
+    /// With priority lanes configured, the batch is reordered first. While
+    /// load shedding is engaged, the batch is sampled and checks are
+    /// thinned (see [`LoadShedding`]). Events the outlier filter rejects
+    /// are skipped and counted in [`rejected_events`](Self::rejected_events).
     pub async fn process_batch(
         &mut self,
         events: Vec<StreamEvent>,
     ) -> Result<Vec<DivergenceAlert>> {
+        let events = match &self.config.priority {
+            Some(lanes) => lanes.schedule(events),
+            None => events,
+        };
+        let events = self.shed_events(events);
+        let received_ms = now_ms();
         let mut all_alerts = Vec::new();
         let mut actors_updated = Vec::new();
+        let mut ingested = Vec::new();
+        let mut latest_ms = None;
 
         // Batch update model
         {
-            let mut model = self.model.write().await;
+            let model = Arc::clone(&self.model);
+            let mut model = model.write().await;
             for event in events {
-                if self.config.deduplicate && self.processed_events.contains_key(&event.event_id) {
+                if self.already_seen(
+                    &event.event_id,
+                    &event.actor_id,
+                    &event.observation,
+                    event.timestamp_ms,
+                ) {
                     continue;
                 }
 
-                model.update_scheme(
+                let applied = match event.apply(&mut model) {
+                    Ok(()) => true,
+                    // Already in the model's rejection log; failing the
+                    // batch would only redeliver it to be rejected again
+                    Err(e) if matches!(e.root(), DivergenceError::OutlierRejected { .. }) => {
+                        self.rejected_events += 1;
+                        false
+                    }
+                    Err(e) => return Err(e),
+                };
+                // Only once applied, so retrying a failed batch replays the
+                // event that failed
+                self.mark_seen(
+                    &event.event_id,
                     &event.actor_id,
                     &event.observation,
-                    Some(event.timestamp_ms),
-                )?;
-
-                actors_updated.push((event.actor_id.clone(), event.timestamp_ms));
-
-                if self.config.deduplicate {
-                    self.processed_events
-                        .insert(event.event_id.clone(), event.timestamp_ms);
+                    event.timestamp_ms,
+                );
+                if !applied {
+                    continue;
+                }
+                latest_ms = latest_ms.max(Some(event.timestamp_ms));
+
+                let ingested_ms = event.ingested_ms.unwrap_or(received_ms);
+                let high = self.priority_of(&event) == EventPriority::High;
+                ingested.push(ingested_ms);
+                actors_updated.push((event.actor_id, event.timestamp_ms, ingested_ms, high));
+                if let Some(target) = event.target_actor_id {
+                    actors_updated.push((target, event.timestamp_ms, ingested_ms, high));
                 }
             }
         }
 
         // Check alerts for all updated actors
-        for (actor_id, timestamp_ms) in actors_updated {
+        let mut alert_ingest = Vec::new();
+        for (actor_id, timestamp_ms, ingested_ms, high) in actors_updated {
+            if !high && self.skip_check(&actor_id, timestamp_ms) {
+                continue;
+            }
             let alerts = self.check_alerts(&actor_id, timestamp_ms).await?;
+            alert_ingest.extend(std::iter::repeat_n(ingested_ms, alerts.len()));
             all_alerts.extend(alerts);
         }
 
+        let processed_ms = now_ms();
+        for ingested_ms in ingested {
+            self.processing_latency.record(processed_ms - ingested_ms);
+        }
+        for (alert, ingested_ms) in all_alerts.iter_mut().zip(alert_ingest) {
+            alert.latency = Some(AlertLatency {
+                ingested_ms,
+                processed_ms,
+                emitted_ms: None,
+            });
+        }
+
+        if let Some(latest_ms) = latest_ms {
+            self.advance_digest(&all_alerts, latest_ms).await?;
+        }
         Ok(all_alerts)
     }
 
@@ -228,19 +967,20 @@ impl StreamProcessor {
         let mut alerts = Vec::new();
         let mut model = self.model.write().await;
 
+        let Some(updated_id) = model.actor_id(updated_actor) else {
+            return Ok(alerts);
+        };
         let actors: Vec<String> = model.actors().iter().map(|s| s.to_string()).collect();
+        let record_on_alert = model.config().history_policy == HistoryPolicy::OnAlert;
 
-        for other_actor in &actors {
-            if other_actor == updated_actor {
+        for (i, other_actor) in actors.iter().enumerate() {
+            let other_id = ActorId(i as u32);
+            if other_id == updated_id {
                 continue;
             }
 
             // Check cooldown
-            let dyad_key = if updated_actor < other_actor {
-                (updated_actor.to_string(), other_actor.to_string())
-            } else {
-                (other_actor.to_string(), updated_actor.to_string())
-            };
+            let dyad_key = ActorId::dyad(updated_id, other_id);
 
             if let Some(&last_time) = self.last_alert.get(&dyad_key) {
                 if timestamp_ms - last_time < self.config.alert_cooldown_ms {
@@ -249,21 +989,60 @@ impl StreamProcessor {
             }
 
             // Compute metrics
-            let potential = model.compute_conflict_potential(updated_actor, other_actor)?;
+            let potential = model
+                .peek_potential(updated_actor, other_actor)
+                .with_dyad(updated_actor, other_actor)?;
+
+            let prediction = model
+                .predict_escalation(updated_actor, other_actor, 0.5, 0.0)
+                .with_dyad(updated_actor, other_actor)?;
 
-            let prediction = model.predict_escalation(updated_actor, other_actor, 0.5, 0.0)?;
+            let (actor_a, actor_b) = if updated_id < other_id {
+                (updated_actor, other_actor.as_str())
+            } else {
+                (other_actor.as_str(), updated_actor)
+            };
+            self.ranking.update(
+                updated_id,
+                other_id,
+                RankedDyad {
+                    actor_a: actor_a.to_string(),
+                    actor_b: actor_b.to_string(),
+                    phi: potential.phi,
+                    escalation_probability: prediction.probability,
+                    timestamp_ms,
+                },
+            );
 
             // Check thresholds
             let mut reasons = Vec::new();
 
-            if potential.phi >= self.config.phi_alert_threshold {
-                reasons.push(format!("Φ={:.3} exceeds threshold", potential.phi));
+            // Seasonally adjusted Φ when available, so scheduled rhythms
+            // do not trip the threshold
+            match potential.phi_adjusted {
+                Some(adjusted) if adjusted >= self.config.phi_alert_threshold => {
+                    reasons.push(format!(
+                        "Φ={:.3} (seasonally adjusted {:.3}) exceeds threshold",
+                        potential.phi, adjusted
+                    ));
+                }
+                None if potential.phi >= self.config.phi_alert_threshold => {
+                    reasons.push(format!("Φ={:.3} exceeds threshold", potential.phi));
+                }
+                _ => {}
             }
 
             if potential.js >= self.config.js_alert_threshold {
                 reasons.push(format!("JS={:.3} exceeds threshold", potential.js));
             }
 
+            if let (Some(z), Some(threshold)) = (potential.phi_z, self.config.phi_z_alert_threshold)
+            {
+                if z >= threshold {
+                    reasons.push(format!("Φ z={:.2} exceeds threshold", z));
+                }
+            }
+
             if prediction.probability >= self.config.escalation_alert_threshold {
                 reasons.push(format!(
                     "P(escalation)={:.3} exceeds threshold",
@@ -271,54 +1050,370 @@ impl StreamProcessor {
                 ));
             }
 
+            let p_value = match &self.config.significance {
+                Some(gate) if !reasons.is_empty() => {
+                    let scheme_a = model.get_scheme(updated_actor);
+                    let scheme_b = model.get_scheme(other_actor);
+                    match (scheme_a, scheme_b) {
+                        (Some(a), Some(b)) => {
+                            let result = a
+                                .significance(b, gate.sample_size, gate.sample_size, gate.test)
+                                .with_dyad(updated_actor, other_actor)?;
+                            if !result.is_significant(gate.alpha) {
+                                continue;
+                            }
+                            Some(result.p_value)
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+
             if !reasons.is_empty() {
+                let robustness = match &self.config.robustness {
+                    Some(check) => {
+                        let mut rng = SplitMix64::seed_from_u64(check.seed);
+                        let result = model
+                            .phi_robustness_with_rng(
+                                updated_actor,
+                                other_actor,
+                                &check.noise,
+                                check.n_samples,
+                                &mut rng,
+                            )
+                            .with_dyad(updated_actor, other_actor)?;
+                        Some(result.score)
+                    }
+                    None => None,
+                };
+                let (actor_a, actor_b, grievance_a_to_b, grievance_b_to_a) =
+                    if updated_actor < other_actor.as_str() {
+                        (
+                            updated_actor,
+                            other_actor.as_str(),
+                            prediction.grievance_a_to_b,
+                            prediction.grievance_b_to_a,
+                        )
+                    } else {
+                        (
+                            other_actor.as_str(),
+                            updated_actor,
+                            prediction.grievance_b_to_a,
+                            prediction.grievance_a_to_b,
+                        )
+                    };
                 let alert = DivergenceAlert {
-                    alert_id: format!("{}-{}-{}", dyad_key.0, dyad_key.1, timestamp_ms),
-                    actor_a: dyad_key.0.clone(),
-                    actor_b: dyad_key.1.clone(),
+                    alert_id: format!("{}-{}-{}", actor_a, actor_b, timestamp_ms),
+                    actor_a: actor_a.to_string(),
+                    actor_b: actor_b.to_string(),
                     phi: potential.phi,
                     js: potential.js,
+                    phi_z: potential.phi_z,
+                    phi_adjusted: potential.phi_adjusted,
+                    p_value,
+                    robustness,
                     d_phi_dt: prediction.d_phi_dt,
                     risk_level: prediction.risk_category,
                     escalation_probability: prediction.probability,
+                    grievance_a_to_b,
+                    grievance_b_to_a,
                     timestamp_ms,
                     reason: reasons.join("; "),
+                    latency: None,
                 };
 
                 alerts.push(alert);
                 self.last_alert.insert(dyad_key, timestamp_ms);
+                model
+                    .record_dyad_event(updated_actor, other_actor, timestamp_ms)
+                    .with_dyad(updated_actor, other_actor)?;
+                if record_on_alert {
+                    model
+                        .record_scheme(other_actor, timestamp_ms)
+                        .with_actor(other_actor)?;
+                }
             }
         }
 
+        if record_on_alert && !alerts.is_empty() {
+            model
+                .record_scheme(updated_actor, timestamp_ms)
+                .with_actor(updated_actor)?;
+        }
+
         Ok(alerts)
     }
 
-    /// Get current model state (for snapshots)
-    pub async fn get_model_state(&self) -> Result<String> {
-        let model = self.model.read().await;
-        model.to_json()
+    /// Report the current queue depth, engaging or releasing load shedding
+    ///
+    /// [`run_pipeline`] reports the batch plus the source's backlog;
+    /// callers driving [`process_batch`](Self::process_batch) themselves
+    /// report their own queue.
+    pub fn observe_queue_depth(&mut self, depth: usize) {
+        let Some(policy) = &self.config.load_shedding else {
+            self.shedding.active = false;
+            return;
+        };
+        if !self.shedding.active && depth >= policy.engage_depth {
+            self.shedding.active = true;
+            self.shedding.activations += 1;
+        } else if self.shedding.active && depth <= policy.release_depth {
+            self.shedding.active = false;
+            self.last_check.clear();
+        }
     }
 
-    /// Get reference to model
-    pub fn model(&self) -> Arc<RwLock<CompressionDynamicsModel>> {
-        Arc::clone(&self.model)
+    /// Load-shedding state and counters
+    pub fn shedding(&self) -> &SheddingMetrics {
+        &self.shedding
     }
 
-    /// Clean up old processed events (memory management)
-    pub fn cleanup_old_events(&mut self, max_age_ms: i64) {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis() as i64)
-            .unwrap_or(0);
+    /// Batch events skipped because the outlier filter rejected them
+    pub fn rejected_events(&self) -> u64 {
+        self.rejected_events
+    }
 
-        self.processed_events
-            .retain(|_, &mut ts| now - ts < max_age_ms);
+    fn priority_of(&self, event: &StreamEvent) -> EventPriority {
+        match &self.config.priority {
+            Some(lanes) => lanes.classify(event),
+            None => event.priority(),
+        }
     }
-}
 
-/// Channel-based event source (for in-process streaming)
-pub struct ChannelEventSource {
-    receiver: mpsc::Receiver<StreamEvent>,
+    /// Keep each actor's latest normal-priority events while shedding
+    fn shed_events(&mut self, events: Vec<StreamEvent>) -> Vec<StreamEvent> {
+        let max_per_actor = match &self.config.load_shedding {
+            Some(policy) if self.shedding.active => policy.max_events_per_actor,
+            _ => return events,
+        };
+        let mut kept: HashMap<String, usize> = HashMap::new();
+        let mut keep = vec![true; events.len()];
+        for (i, event) in events.iter().enumerate().rev() {
+            if self.priority_of(event) == EventPriority::High {
+                continue;
+            }
+            let n = kept.entry(event.actor_id.clone()).or_default();
+            if *n < max_per_actor {
+                *n += 1;
+            } else {
+                keep[i] = false;
+            }
+        }
+        let before = events.len();
+        let events: Vec<StreamEvent> = events
+            .into_iter()
+            .zip(keep)
+            .filter_map(|(event, keep)| keep.then_some(event))
+            .collect();
+        self.shedding.events_shed += (before - events.len()) as u64;
+        events
+    }
+
+    /// Whether shedding skips this normal-priority check; records it otherwise
+    fn skip_check(&mut self, actor_id: &str, timestamp_ms: i64) -> bool {
+        let interval = match &self.config.load_shedding {
+            Some(policy) if self.shedding.active => policy.check_interval_ms,
+            _ => return false,
+        };
+        if let Some(&last) = self.last_check.get(actor_id) {
+            if timestamp_ms - last < interval {
+                self.shedding.checks_skipped += 1;
+                return true;
+            }
+        }
+        self.last_check.insert(actor_id.to_string(), timestamp_ms);
+        false
+    }
+
+    /// Record one event's processing latency and stamp its alerts
+    fn record_processed(
+        &mut self,
+        ingested_ms: i64,
+        processed_ms: i64,
+        alerts: &mut [DivergenceAlert],
+    ) {
+        self.processing_latency.record(processed_ms - ingested_ms);
+        for alert in alerts {
+            alert.latency = Some(AlertLatency {
+                ingested_ms,
+                processed_ms,
+                emitted_ms: None,
+            });
+        }
+    }
+
+    /// Record end-to-end latency of alerts handed to a sink
+    pub fn record_emitted(&mut self, latencies: &[AlertLatency]) {
+        for latency in latencies {
+            if let Some(ms) = latency.end_to_end_ms() {
+                self.end_to_end_latency.record(ms);
+            }
+        }
+    }
+
+    /// Add alerts to the current digest period, closing it if event time
+    /// has passed its end
+    ///
+    /// The first processed event starts the first period. Periods end on
+    /// multiples of the interval from there, so a quiet stretch yields one
+    /// digest spanning it. Alerts at or after the end go to the next period.
+    async fn advance_digest(
+        &mut self,
+        alerts: &[DivergenceAlert],
+        timestamp_ms: i64,
+    ) -> Result<()> {
+        let Some(config) = &self.config.digest else {
+            return Ok(());
+        };
+        let model = self.model.read().await;
+        let builder = match &mut self.digest {
+            Some(builder) => builder,
+            None => self
+                .digest
+                .insert(DigestBuilder::new(config.clone(), &model, timestamp_ms)?),
+        };
+        if builder.is_due(timestamp_ms) {
+            let start = builder.period_start_ms();
+            let interval = builder.config().interval_ms;
+            let end = start + (timestamp_ms - start) / interval * interval;
+            let (closing, next): (Vec<_>, Vec<_>) =
+                alerts.iter().cloned().partition(|a| a.timestamp_ms < end);
+            builder.record_alerts(&closing);
+            self.digests.push(builder.finish(&model, end)?);
+            builder.record_alerts(&next);
+        } else {
+            builder.record_alerts(alerts);
+        }
+        Ok(())
+    }
+
+    /// Digests completed since the last call, oldest first
+    pub fn take_digests(&mut self) -> Vec<Digest> {
+        std::mem::take(&mut self.digests)
+    }
+
+    /// The `k` riskiest dyads by `metric`, highest first
+    ///
+    /// Scores are those of each dyad's latest alert check, kept in an
+    /// indexed heap so a refresh costs O(k log k) rather than a scan of all
+    /// pairs. Dyads in alert cooldown are not re-checked and keep their last
+    /// score until the cooldown ends.
+    pub fn top_risky_dyads(&self, k: usize, metric: RiskMetric) -> Vec<RankedDyad> {
+        self.ranking.top(k, metric)
+    }
+
+    /// Latency percentiles over recent events and alerts
+    pub fn latency(&self) -> LatencyReport {
+        LatencyReport {
+            processing: self.processing_latency.stats(),
+            end_to_end: self.end_to_end_latency.stats(),
+        }
+    }
+
+    /// Whether an event was already seen, by ID or by content as
+    /// configured; records it otherwise
+    fn is_duplicate(
+        &mut self,
+        event_id: &str,
+        actor_id: &str,
+        observation: &[f64],
+        timestamp_ms: i64,
+    ) -> bool {
+        if self.already_seen(event_id, actor_id, observation, timestamp_ms) {
+            return true;
+        }
+        self.mark_seen(event_id, actor_id, observation, timestamp_ms);
+        false
+    }
+
+    /// Whether an event was already seen, by ID or by content as configured
+    fn already_seen(
+        &self,
+        event_id: &str,
+        actor_id: &str,
+        observation: &[f64],
+        timestamp_ms: i64,
+    ) -> bool {
+        if self.config.deduplicate && self.processed_events.contains_key(event_id) {
+            return true;
+        }
+        self.config.content_dedup.as_ref().is_some_and(|dedup| {
+            self.processed_content
+                .contains_key(&dedup.hash(actor_id, observation, timestamp_ms))
+        })
+    }
+
+    /// Record an event for deduplication
+    fn mark_seen(
+        &mut self,
+        event_id: &str,
+        actor_id: &str,
+        observation: &[f64],
+        timestamp_ms: i64,
+    ) {
+        if let Some(dedup) = &self.config.content_dedup {
+            self.processed_content.insert(
+                dedup.hash(actor_id, observation, timestamp_ms),
+                timestamp_ms,
+            );
+        }
+        if self.config.deduplicate {
+            self.processed_events
+                .insert(event_id.to_string(), timestamp_ms);
+        }
+    }
+
+    /// Get current model state (for snapshots)
+    pub async fn get_model_state(&self) -> Result<String> {
+        let model = self.model.read().await;
+        model.to_json()
+    }
+
+    /// Current processor configuration
+    pub fn config(&self) -> &StreamConfig {
+        &self.config
+    }
+
+    /// Change alert thresholds or other processor settings at runtime
+    ///
+    /// Every changed setting is logged in the model's audit log under
+    /// `stream.`. Returns the number of settings changed. An invalid update
+    /// is rejected and leaves the configuration unchanged.
+    pub async fn update_config(
+        &mut self,
+        timestamp_ms: i64,
+        update: impl FnOnce(&mut StreamConfig),
+    ) -> Result<usize> {
+        let mut config = self.config.clone();
+        update(&mut config);
+        config.validate()?;
+        let before = config_value(&self.config)?;
+        let after = config_value(&config)?;
+        self.config = config;
+        let mut model = self.model.write().await;
+        Ok(model.record_config_changes("stream", before, after, timestamp_ms))
+    }
+
+    /// Get reference to model
+    pub fn model(&self) -> Arc<RwLock<CompressionDynamicsModel>> {
+        Arc::clone(&self.model)
+    }
+
+    /// Clean up old processed events (memory management)
+    pub fn cleanup_old_events(&mut self, max_age_ms: i64) {
+        let now = now_ms();
+
+        self.processed_events
+            .retain(|_, &mut ts| now - ts < max_age_ms);
+        self.processed_content
+            .retain(|_, &mut ts| now - ts < max_age_ms);
+    }
+}
+
+/// Channel-based event source (for in-process streaming)
+pub struct ChannelEventSource {
+    receiver: mpsc::Receiver<StreamEvent>,
     batch_size: usize,
 }
 
@@ -367,6 +1462,10 @@ impl EventSource for ChannelEventSource {
     async fn health_check(&self) -> bool {
         !self.receiver.is_closed()
     }
+
+    async fn backlog(&self) -> Option<usize> {
+        Some(self.receiver.len())
+    }
 }
 
 /// Channel-based alert sink
@@ -395,16 +1494,353 @@ impl AlertSink for ChannelAlertSink {
     }
 }
 
+/// What happens to alerts arriving while the sink buffer is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OverflowPolicy {
+    /// Discard the oldest buffered alert
+    #[default]
+    DropOldest,
+    /// Discard the incoming alert
+    DropNewest,
+    /// Stop the pipeline with an error
+    Fail,
+}
+
+/// Retry and circuit-breaker settings for the alert sink
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinkPolicy {
+    /// Retries per batch after the first failed send
+    pub max_retries: usize,
+    /// Delay before the first retry; doubles on each further retry
+    pub initial_backoff_ms: u64,
+    /// Cap on the retry delay
+    pub max_backoff_ms: u64,
+    /// Consecutive failed deliveries that open the circuit
+    pub failure_threshold: usize,
+    /// Time the circuit stays open before a probe send
+    pub open_duration_ms: u64,
+    /// Alerts held while the sink is down
+    pub buffer_capacity: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for SinkPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 10_000,
+            failure_threshold: 5,
+            open_duration_ms: 30_000,
+            buffer_capacity: 1_000,
+            overflow: OverflowPolicy::DropOldest,
+        }
+    }
+}
+
+impl SinkPolicy {
+    /// Delay before retry number `attempt` (0-based)
+    pub fn backoff_ms(&self, attempt: usize) -> u64 {
+        let factor = 1u64.checked_shl(attempt as u32).unwrap_or(u64::MAX);
+        self.initial_backoff_ms
+            .saturating_mul(factor)
+            .min(self.max_backoff_ms)
+    }
+}
+
+/// Circuit-breaker state of the alert sink
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CircuitState {
+    /// Sending normally
+    #[default]
+    Closed,
+    /// Sink considered down; alerts are buffered
+    Open,
+    /// Cool-down elapsed; the next send probes the sink
+    HalfOpen,
+}
+
+/// Alert sink health
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SinkHealth {
+    pub state: CircuitState,
+    pub consecutive_failures: usize,
+    pub total_failures: u64,
+    pub retries: u64,
+    pub delivered: u64,
+    /// Alerts waiting for the sink
+    pub buffered: usize,
+    /// Alerts discarded by the overflow policy
+    pub dropped: u64,
+    pub last_error: Option<String>,
+}
+
+/// Pipeline counters, updated after every batch
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PipelineMetrics {
+    pub batches: u64,
+    pub events: u64,
+    pub alerts: u64,
+    pub sink: SinkHealth,
+    pub latency: LatencyReport,
+    pub shedding: SheddingMetrics,
+    pub digests: u64,
+    /// Events skipped because the outlier filter rejected them
+    pub rejected: u64,
+}
+
+/// Alert sink wrapper with retries, a circuit breaker and a bounded buffer
+///
+/// Buffered alerts go to the sink as one batch through
+/// [`AlertSink::send_batch`], oldest first. A batch that still fails after
+/// `max_retries` counts as one failure and is resent whole later, so a sink
+/// without atomic batches may see some alerts twice. `failure_threshold`
+/// failures in a row open the circuit. While open, alerts are only
+/// buffered. After `open_duration_ms` a single probe batch decides whether
+/// the circuit closes again. At most `buffer_capacity` alerts are held;
+/// with a capacity of 0 nothing is buffered.
+///
+/// Events handed over with [`deliver_for`](Self::deliver_for) become
+/// acknowledgeable once all of their alerts are delivered, and never if
+/// one of them is dropped.
+pub struct ResilientSink<A: AlertSink> {
+    sink: A,
+    policy: SinkPolicy,
+    /// Undelivered alerts with the delivery they arrived in
+    buffer: VecDeque<(u64, DivergenceAlert)>,
+    health: SinkHealth,
+    opened_at: Option<Instant>,
+    emitted: Vec<AlertLatency>,
+    deliveries: u64,
+    pending_acks: VecDeque<PendingAck>,
+}
+
+/// Events waiting for their alerts to reach the sink
+struct PendingAck {
+    delivery: u64,
+    event_ids: Vec<String>,
+    /// An alert of this delivery was dropped
+    lost: bool,
+}
+
+impl<A: AlertSink> ResilientSink<A> {
+    pub fn new(sink: A, policy: SinkPolicy) -> Self {
+        Self {
+            sink,
+            policy,
+            buffer: VecDeque::new(),
+            health: SinkHealth::default(),
+            opened_at: None,
+            emitted: Vec::new(),
+            deliveries: 0,
+            pending_acks: VecDeque::new(),
+        }
+    }
+
+    /// Current health
+    pub fn health(&self) -> &SinkHealth {
+        &self.health
+    }
+
+    /// Wrapped sink
+    pub fn inner(&self) -> &A {
+        &self.sink
+    }
+
+    /// Latencies of alerts delivered since the last call
+    pub fn take_emitted(&mut self) -> Vec<AlertLatency> {
+        std::mem::take(&mut self.emitted)
+    }
+
+    /// Events whose alerts have all been delivered since the last call,
+    /// in delivery order
+    ///
+    /// Events with a dropped alert are left out, so the source redelivers
+    /// them after a restart.
+    pub fn take_acknowledgeable(&mut self) -> Vec<String> {
+        let settled = self.buffer.front().map_or(self.deliveries, |(d, _)| *d);
+        let mut event_ids = Vec::new();
+        while let Some(pending) = self.pending_acks.front() {
+            if pending.delivery >= settled {
+                break;
+            }
+            let pending = self.pending_acks.pop_front().expect("front exists");
+            if !pending.lost {
+                event_ids.extend(pending.event_ids);
+            }
+        }
+        event_ids
+    }
+
+    /// Send a digest once; a failure is recorded in health but neither
+    /// retried nor counted toward the circuit breaker
+    pub async fn send_digest(&mut self, digest: &Digest) {
+        if let Err(e) = self.sink.send_digest(digest).await {
+            self.health.total_failures += 1;
+            self.health.last_error = Some(e.to_string());
+        }
+    }
+
+    /// Buffer alerts and send as many as the sink accepts
+    ///
+    /// Errors only when the overflow policy is [`OverflowPolicy::Fail`]
+    /// and the buffer is full; sink errors are absorbed into health.
+    pub async fn deliver(&mut self, alerts: Vec<DivergenceAlert>) -> Result<()> {
+        self.deliver_for(Vec::new(), alerts).await
+    }
+
+    /// [`deliver`](Self::deliver) the alerts raised by `event_ids`
+    pub async fn deliver_for(
+        &mut self,
+        event_ids: Vec<String>,
+        alerts: Vec<DivergenceAlert>,
+    ) -> Result<()> {
+        let delivery = self.deliveries;
+        self.deliveries += 1;
+        self.pending_acks.push_back(PendingAck {
+            delivery,
+            event_ids,
+            lost: false,
+        });
+        self.buffer
+            .extend(alerts.into_iter().map(|alert| (delivery, alert)));
+
+        if self.health.state == CircuitState::Open {
+            let elapsed = self
+                .opened_at
+                .map_or(u128::MAX, |t| t.elapsed().as_millis());
+            if elapsed < u128::from(self.policy.open_duration_ms) {
+                return self.trim_buffer();
+            }
+            self.health.state = CircuitState::HalfOpen;
+        }
+
+        if !self.buffer.is_empty() {
+            match self.send_with_retry().await {
+                Ok(()) => {
+                    self.health.delivered += self.buffer.len() as u64;
+                    self.buffer.clear();
+                    self.health.consecutive_failures = 0;
+                    self.health.state = CircuitState::Closed;
+                    self.opened_at = None;
+                }
+                Err(e) => {
+                    self.health.total_failures += 1;
+                    self.health.consecutive_failures += 1;
+                    self.health.last_error = Some(e.to_string());
+                    if self.health.state == CircuitState::HalfOpen
+                        || self.health.consecutive_failures >= self.policy.failure_threshold
+                    {
+                        self.health.state = CircuitState::Open;
+                        self.opened_at = Some(Instant::now());
+                    }
+                }
+            }
+        }
+        self.trim_buffer()
+    }
+
+    /// Apply the overflow policy to undelivered alerts beyond capacity
+    fn trim_buffer(&mut self) -> Result<()> {
+        let excess = self
+            .buffer
+            .len()
+            .saturating_sub(self.policy.buffer_capacity);
+        if excess > 0 {
+            let dropped: Vec<u64> = match self.policy.overflow {
+                OverflowPolicy::DropOldest => self.buffer.drain(..excess).map(|(d, _)| d).collect(),
+                OverflowPolicy::DropNewest => {
+                    let keep = self.buffer.len() - excess;
+                    self.buffer.drain(keep..).map(|(d, _)| d).collect()
+                }
+                OverflowPolicy::Fail => {
+                    return Err(DivergenceError::ConfigError(format!(
+                        "Alert buffer full ({} alerts) while the sink is down",
+                        self.policy.buffer_capacity
+                    )));
+                }
+            };
+            self.health.dropped += excess as u64;
+            for pending in &mut self.pending_acks {
+                if dropped.contains(&pending.delivery) {
+                    pending.lost = true;
+                }
+            }
+        }
+        self.health.buffered = self.buffer.len();
+        Ok(())
+    }
+
+    /// Send the buffer as one batch, stamped with its emission time; a
+    /// half-open probe gets no retries
+    async fn send_with_retry(&mut self) -> Result<()> {
+        let retries = match self.health.state {
+            CircuitState::HalfOpen => 0,
+            _ => self.policy.max_retries,
+        };
+        let mut attempt = 0;
+        loop {
+            let emitted_ms = now_ms();
+            let alerts: Vec<DivergenceAlert> = self
+                .buffer
+                .iter()
+                .map(|(_, alert)| {
+                    let mut alert = alert.clone();
+                    if let Some(latency) = &mut alert.latency {
+                        latency.emitted_ms = Some(emitted_ms);
+                    }
+                    alert
+                })
+                .collect();
+            let latencies: Vec<AlertLatency> = alerts.iter().filter_map(|a| a.latency).collect();
+            match self.sink.send_batch(alerts).await {
+                Ok(()) => {
+                    self.emitted.extend(latencies);
+                    return Ok(());
+                }
+                Err(e) if attempt >= retries => return Err(e),
+                Err(_) => {
+                    tokio::time::sleep(Duration::from_millis(self.policy.backoff_ms(attempt)))
+                        .await;
+                    self.health.retries += 1;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
 /// Run the streaming pipeline
-pub async fn run_pipeline<S, A>(
+///
+/// Alerts go through a [`ResilientSink`] with the default [`SinkPolicy`],
+/// so a failing sink no longer stops the pipeline.
+pub async fn run_pipeline<S, A>(source: S, sink: A, processor: StreamProcessor) -> Result<()>
+where
+    S: EventSource,
+    A: AlertSink,
+{
+    let metrics = Arc::new(RwLock::new(PipelineMetrics::default()));
+    run_pipeline_with(source, sink, processor, SinkPolicy::default(), metrics).await
+}
+
+/// Run the streaming pipeline with a sink policy, publishing counters and
+/// sink health to `metrics`
+///
+/// Events are acknowledged only once their alerts reach the sink; events
+/// whose alerts are still buffered wait, and events with a dropped alert
+/// are never acknowledged, so the source redelivers them after a restart.
+pub async fn run_pipeline_with<S, A>(
     mut source: S,
-    mut sink: A,
+    sink: A,
     mut processor: StreamProcessor,
+    policy: SinkPolicy,
+    metrics: Arc<RwLock<PipelineMetrics>>,
 ) -> Result<()>
 where
     S: EventSource,
     A: AlertSink,
 {
+    let mut sink = ResilientSink::new(sink, policy);
     loop {
         // Check source health
         if !source.health_check().await {
@@ -414,24 +1850,50 @@ where
         }
 
         // Receive events
-        let events = source.receive().await?;
+        let mut events = source.receive().await?;
 
         if events.is_empty() {
             continue;
         }
+        let received_ms = now_ms();
+        for event in &mut events {
+            event.ingested_ms.get_or_insert(received_ms);
+        }
+        let backlog = source.backlog().await.unwrap_or(0);
+        processor.observe_queue_depth(events.len() + backlog);
 
         let event_ids: Vec<String> = events.iter().map(|e| e.event_id.clone()).collect();
+        let n_events = event_ids.len() as u64;
 
         // Process
         let alerts = processor.process_batch(events).await?;
+        let n_alerts = alerts.len() as u64;
+
+        // Send alerts (and anything buffered while the sink was down)
+        sink.deliver_for(event_ids, alerts).await?;
+        processor.record_emitted(&sink.take_emitted());
+        let digests = processor.take_digests();
+        for digest in &digests {
+            sink.send_digest(digest).await;
+        }
 
-        // Send alerts
-        if !alerts.is_empty() {
-            sink.send_batch(alerts).await?;
+        {
+            let mut m = metrics.write().await;
+            m.batches += 1;
+            m.events += n_events;
+            m.alerts += n_alerts;
+            m.sink = sink.health().clone();
+            m.latency = processor.latency();
+            m.shedding = processor.shedding().clone();
+            m.digests += digests.len() as u64;
+            m.rejected = processor.rejected_events();
         }
 
-        // Acknowledge
-        source.acknowledge(&event_ids).await?;
+        // Acknowledge only events whose alerts reached the sink
+        let settled = sink.take_acknowledgeable();
+        if !settled.is_empty() {
+            source.acknowledge(&settled).await?;
+        }
 
         // Periodic cleanup
         processor.cleanup_old_events(3_600_000); // 1 hour
@@ -441,7 +1903,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::CompressionDynamicsModel;
+    use crate::model::{CompressionDynamicsModel, ModelConfig};
+    use crate::outlier::OutlierFilter;
 
     #[tokio::test]
     async fn test_stream_processor() {
@@ -457,20 +1920,341 @@ mod tests {
         }
 
         // Process event
-        let event = StreamEvent {
-            event_id: "test-1".to_string(),
-            actor_id: "USA".to_string(),
-            observation: vec![0.5, 0.25, 0.1, 0.1, 0.05],
-            timestamp_ms: 1700000000000,
-            source: "test".to_string(),
-            metadata: HashMap::new(),
-        };
+        let event = StreamEvent::new(
+            "test-1",
+            "USA",
+            vec![0.5, 0.25, 0.1, 0.1, 0.05],
+            1700000000000,
+        )
+        .with_source(Arc::from("test"));
 
         let alerts = processor.process_event(event).await.unwrap();
         // May or may not generate alerts depending on thresholds
         assert!(alerts.len() <= 1);
     }
 
+    #[tokio::test]
+    async fn test_stream_error_context() {
+        let model = CompressionDynamicsModel::new(5);
+        let mut processor = StreamProcessor::new(model, StreamConfig::default());
+        processor.model.write().await.register_actor(
+            "RUS",
+            Some(vec![0.2, 0.2, 0.2, 0.2, 0.2]),
+            None,
+        );
+
+        // Malformed events name the event and actor
+        let bad = StreamEvent::new("test-2", "RUS", vec![0.5, 0.5], 1700000000001);
+        let err = processor.process_event(bad).await.unwrap_err();
+        let context = err.context().unwrap();
+        assert_eq!(context.event_id.as_deref(), Some("test-2"));
+        assert_eq!(context.actor_id.as_deref(), Some("RUS"));
+    }
+
+    #[tokio::test]
+    async fn test_significance_gate() {
+        async fn alerts_with(sample_size: f64) -> Vec<DivergenceAlert> {
+            let config = StreamConfig {
+                phi_alert_threshold: 0.0,
+                significance: Some(SignificanceGate {
+                    sample_size,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let mut processor = StreamProcessor::new(CompressionDynamicsModel::new(3), config);
+            {
+                let mut m = processor.model.write().await;
+                m.register_actor("A", Some(vec![0.5, 0.3, 0.2]), None);
+                m.register_actor("B", Some(vec![0.4, 0.35, 0.25]), None);
+            }
+            processor
+                .process_observation("e1", "A", &[0.5, 0.3, 0.2], 0)
+                .await
+                .unwrap()
+        }
+
+        // A small Φ backed by few observations is indistinguishable from noise
+        assert!(alerts_with(10.0).await.is_empty());
+
+        let alerts = alerts_with(10_000.0).await;
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].p_value.unwrap() < 0.05);
+        assert!(alerts[0].robustness.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_alert_robustness_score() {
+        let config = StreamConfig {
+            phi_alert_threshold: 0.0,
+            robustness: Some(RobustnessCheck::default()),
+            ..Default::default()
+        };
+        let mut processor = StreamProcessor::new(CompressionDynamicsModel::new(3), config);
+        {
+            let mut m = processor.model.write().await;
+            m.register_actor("A", Some(vec![0.9, 0.05, 0.05]), None);
+            m.register_actor("B", Some(vec![0.05, 0.05, 0.9]), None);
+        }
+        let alerts = processor
+            .process_observation("e1", "A", &[0.9, 0.05, 0.05], 0)
+            .await
+            .unwrap();
+        let score = alerts[0].robustness.unwrap();
+        assert!((0.0..=1.0).contains(&score));
+    }
+
+    #[test]
+    fn test_priority_lanes() {
+        let lanes = PriorityLanes {
+            shock_event_types: vec!["19".to_string()],
+            high_stakes_dyads: vec![("USA".to_string(), "RUS".to_string())],
+            max_consecutive_high: 2,
+        };
+        let event = |id: &str, actor: &str| StreamEvent::new(id, actor, vec![1.0], 0);
+        let mut arena = MetadataArena::new();
+        let events = vec![
+            event("n1", "FRA"),
+            event("n2", "DEU"),
+            event("h1", "RUS"),
+            event("h2", "FRA").with_metadata(arena.metadata([(EVENT_TYPE_KEY, "19")])),
+            event("h3", "GBR").with_priority(EventPriority::High),
+            event("n3", "ITA"),
+        ];
+        assert_eq!(lanes.classify(&events[2]), EventPriority::High);
+        assert_eq!(events[4].priority(), EventPriority::High);
+        assert_eq!(events[0].priority(), EventPriority::Normal);
+
+        let order: Vec<String> = lanes
+            .schedule(events)
+            .into_iter()
+            .map(|e| e.event_id)
+            .collect();
+        // n1 moves up with FRA's later shock h2
+        assert_eq!(order, vec!["n1", "h1", "n2", "h2", "h3", "n3"]);
+
+        // A normal event does not overtake its actor's pending high event
+        let lanes = PriorityLanes {
+            max_consecutive_high: 1,
+            ..lanes
+        };
+        let events = vec![
+            event("h1", "GBR").with_priority(EventPriority::High),
+            event("h2", "RUS"),
+            event("n1", "RUS"),
+            event("n2", "ITA"),
+        ];
+        let order: Vec<String> = lanes
+            .schedule(events)
+            .into_iter()
+            .map(|e| e.event_id)
+            .collect();
+        assert_eq!(order, vec!["h1", "n2", "h2", "n1"]);
+    }
+
+    #[tokio::test]
+    async fn test_content_dedup() {
+        let config = StreamConfig {
+            content_dedup: Some(ContentDedup { bucket_ms: 1_000 }),
+            ..Default::default()
+        };
+        let mut processor = StreamProcessor::new(CompressionDynamicsModel::new(3), config);
+        let obs = [0.6, 0.3, 0.1];
+        let events = vec![
+            StreamEvent::new("e1", "A", obs.to_vec(), 100),
+            StreamEvent::new("e2", "A", obs.to_vec(), 900),
+            StreamEvent::new("e3", "B", obs.to_vec(), 900),
+            StreamEvent::new("e4", "A", obs.to_vec(), 1_100),
+        ];
+        processor.process_batch(events).await.unwrap();
+        processor
+            .process_observation("e5", "B", &obs, 950)
+            .await
+            .unwrap();
+
+        // e2 and e5 repeat earlier content in the same bucket
+        let model = processor.model.read().await;
+        assert_eq!(model.history.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_event_is_retried() {
+        let mut processor =
+            StreamProcessor::new(CompressionDynamicsModel::new(3), StreamConfig::default());
+        let good = StreamEvent::new("e1", "A", vec![0.6, 0.3, 0.1], 0);
+        let bad = StreamEvent::new("e2", "A", vec![0.5, 0.5], 1);
+        assert!(processor
+            .process_batch(vec![good.clone(), bad])
+            .await
+            .is_err());
+
+        // The retry skips e1, already applied, and applies the corrected e2
+        let fixed = StreamEvent::new("e2", "A", vec![0.1, 0.3, 0.6], 1);
+        processor.process_batch(vec![good, fixed]).await.unwrap();
+        let model = processor.model.read().await;
+        assert_eq!(model.get_scheme("A").unwrap().observation_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_batch_event_is_skipped() {
+        let mut model = CompressionDynamicsModel::with_config(ModelConfig {
+            n_categories: 3,
+            outlier_filter: Some(OutlierFilter::reject_beyond(0.2)),
+            ..Default::default()
+        });
+        model.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+        let mut processor = StreamProcessor::new(model, StreamConfig::default());
+        let batch = vec![
+            StreamEvent::new("e1", "A", vec![0.0, 0.0, 1.0], 0),
+            StreamEvent::new("e2", "A", vec![0.6, 0.3, 0.1], 1),
+        ];
+        processor.process_batch(batch.clone()).await.unwrap();
+        assert_eq!(processor.rejected_events(), 1);
+
+        // Redelivery skips the rejected event as already seen
+        processor.process_batch(batch).await.unwrap();
+        assert_eq!(processor.rejected_events(), 1);
+        let model = processor.model.read().await;
+        assert_eq!(model.get_scheme("A").unwrap().observation_count(), 1);
+        assert_eq!(model.rejected_observations().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_attributed_events_feed_directed_grievance() {
+        let config = StreamConfig {
+            phi_alert_threshold: 0.0,
+            ..Default::default()
+        };
+        let mut processor = StreamProcessor::new(CompressionDynamicsModel::new(3), config);
+        {
+            let mut m = processor.model.write().await;
+            m.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+            m.register_actor("B", Some(vec![0.1, 0.3, 0.6]), None);
+        }
+
+        let event =
+            StreamEvent::new("e1", "A", vec![0.0, 0.0, 1.0], 0).with_source_actor(Arc::from("B"));
+        assert_eq!(event.source_actor(), Some("B"));
+        let alerts = processor.process_event(event).await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].grievance_a_to_b > 0.0);
+        assert_eq!(alerts[0].grievance_b_to_a, 0.0);
+
+        // Self-attribution is ignored
+        let own =
+            StreamEvent::new("e2", "A", vec![1.0, 0.0, 0.0], 1).with_source_actor(Arc::from("A"));
+        assert_eq!(own.source_actor(), None);
+
+        // Attribution metadata lands in history
+        let mut arena = MetadataArena::new();
+        let tagged = StreamEvent::new("e3", "B", vec![1.0, 0.0, 0.0], 2)
+            .with_metadata(arena.metadata([(EVENT_TYPE_KEY, "protest"), (GEO_KEY, "UA")]))
+            .with_source_actor(arena.intern("A"));
+        processor.process_batch(vec![tagged]).await.unwrap();
+        let model = processor.model.read().await;
+        let drivers = model.grievance_drivers(Some("B"), 0, 10, 5);
+        let attribution = drivers[0].attribution.as_ref().unwrap();
+        assert_eq!(attribution.event_id.as_deref(), Some("e3"));
+        assert_eq!(attribution.source_actor.as_deref(), Some("A"));
+        assert_eq!(attribution.event_type.as_deref(), Some("protest"));
+        assert_eq!(attribution.geo.as_deref(), Some("UA"));
+        assert!(model.directed_grievance("B", "A").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_interaction_events_update_both_actors() {
+        let config = StreamConfig {
+            phi_alert_threshold: 0.0,
+            ..Default::default()
+        };
+        let mut processor = StreamProcessor::new(CompressionDynamicsModel::new(3), config);
+        {
+            let mut m = processor.model.write().await;
+            m.register_actor("A", Some(vec![0.6, 0.3, 0.1]), None);
+            m.register_actor("B", Some(vec![0.1, 0.3, 0.6]), None);
+        }
+
+        // A did X to B: both observe it, B's side is attributed to A
+        let event = StreamEvent::new("e1", "A", vec![0.0, 1.0, 0.0], 0)
+            .with_target("B", vec![1.0, 0.0, 0.0]);
+        let json = serde_json::to_string(&event).unwrap();
+        let event: StreamEvent = serde_json::from_str(&json).unwrap();
+        let alerts = processor.process_event(event).await.unwrap();
+        assert!(!alerts.is_empty());
+        {
+            let model = processor.model.read().await;
+            assert_eq!(model.get_scheme("A").unwrap().observation_count(), 1);
+            assert_eq!(model.get_scheme("B").unwrap().observation_count(), 1);
+            assert!(model.directed_grievance("B", "A").is_some());
+            assert!(model.directed_grievance("A", "B").is_none());
+        }
+
+        // A bad target observation leaves both actors untouched
+        let bad =
+            StreamEvent::new("e2", "A", vec![0.0, 1.0, 0.0], 1).with_target("B", vec![1.0, 0.0]);
+        assert!(processor.process_batch(vec![bad]).await.is_err());
+        let mut half = StreamEvent::new("e3", "A", vec![0.0, 1.0, 0.0], 2);
+        half.target_actor_id = Some("B".to_string());
+        assert!(processor.process_event(half).await.is_err());
+        let self_target = StreamEvent::new("e4", "A", vec![0.0, 1.0, 0.0], 3)
+            .with_target("A", vec![0.0, 1.0, 0.0]);
+        assert!(processor.process_event(self_target).await.is_err());
+
+        let model = processor.model.read().await;
+        assert_eq!(model.get_scheme("A").unwrap().observation_count(), 1);
+        assert_eq!(model.get_scheme("B").unwrap().observation_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_shared_payloads() {
+        let mut arena = MetadataArena::new();
+        let gdelt = arena.intern("GDELT");
+        let observation: Arc<[f64]> = Arc::from(vec![0.6, 0.4]);
+
+        let events: Vec<StreamEvent> = (0..3)
+            .map(|i| {
+                StreamEvent::new(format!("e{}", i), "A", Arc::clone(&observation), i)
+                    .with_source(arena.intern("GDELT"))
+                    .with_metadata(arena.metadata([("region", "EU")]))
+            })
+            .collect();
+
+        // One allocation per distinct string, shared by every event
+        assert_eq!(arena.len(), 3);
+        assert!(Arc::ptr_eq(&events[2].source, &gdelt));
+        assert!(Arc::ptr_eq(&events[1].observation, &observation));
+
+        let json = serde_json::to_string(&events[0]).unwrap();
+        let restored: StreamEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.metadata.get("region").map(|v| v.as_ref()),
+            Some("EU")
+        );
+
+        drop(events);
+        drop(gdelt);
+        arena.purge();
+        assert!(arena.is_empty());
+
+        let mut model = CompressionDynamicsModel::new(2);
+        model.register_actor("A", None, None);
+        let mut processor = StreamProcessor::new(model, StreamConfig::default());
+        processor
+            .process_observation("e0", "A", &observation, 0)
+            .await
+            .unwrap();
+        // Deduplicated
+        assert!(processor
+            .process_observation("e0", "A", &observation, 1)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            processor.model().read().await.summary().n_history_entries,
+            1
+        );
+    }
+
     #[tokio::test]
     async fn test_channel_source_sink() {
         let (sender, mut source) = ChannelEventSource::create_pair(10, 5);
@@ -478,14 +2262,7 @@ mod tests {
 
         // Send event
         sender
-            .send(StreamEvent {
-                event_id: "e1".to_string(),
-                actor_id: "A".to_string(),
-                observation: vec![0.5, 0.5],
-                timestamp_ms: 0,
-                source: "test".to_string(),
-                metadata: HashMap::new(),
-            })
+            .send(StreamEvent::new("e1", "A", vec![0.5, 0.5], 0))
             .await
             .unwrap();
 
@@ -500,11 +2277,18 @@ mod tests {
             actor_b: "B".to_string(),
             phi: 1.0,
             js: 0.5,
+            phi_z: None,
+            phi_adjusted: None,
+            p_value: None,
+            robustness: None,
             d_phi_dt: 0.1,
             risk_level: RiskLevel::Moderate,
             escalation_probability: 0.3,
+            grievance_a_to_b: 0.0,
+            grievance_b_to_a: 0.0,
             timestamp_ms: 0,
             reason: "test".to_string(),
+            latency: None,
         })
         .await
         .unwrap();
@@ -513,4 +2297,343 @@ mod tests {
         let alert = receiver.recv().await.unwrap();
         assert_eq!(alert.alert_id, "a1");
     }
+
+    /// Sink that fails its first `failures` sends
+    struct FlakySink {
+        failures: usize,
+        received: Vec<String>,
+        batches: usize,
+    }
+
+    impl FlakySink {
+        fn failing(failures: usize) -> Self {
+            Self {
+                failures,
+                received: Vec::new(),
+                batches: 0,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AlertSink for FlakySink {
+        async fn send_batch(&mut self, alerts: Vec<DivergenceAlert>) -> Result<()> {
+            self.batches += 1;
+            for alert in alerts {
+                self.send(alert).await?;
+            }
+            Ok(())
+        }
+
+        async fn send(&mut self, alert: DivergenceAlert) -> Result<()> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(DivergenceError::ConfigError("sink down".to_string()));
+            }
+            self.received.push(alert.alert_id);
+            Ok(())
+        }
+    }
+
+    fn test_alert(id: &str) -> DivergenceAlert {
+        DivergenceAlert {
+            alert_id: id.to_string(),
+            actor_a: "A".to_string(),
+            actor_b: "B".to_string(),
+            phi: 1.0,
+            js: 0.5,
+            phi_z: None,
+            phi_adjusted: None,
+            p_value: None,
+            robustness: None,
+            d_phi_dt: 0.0,
+            risk_level: RiskLevel::Elevated,
+            escalation_probability: 0.5,
+            grievance_a_to_b: 0.0,
+            grievance_b_to_a: 0.0,
+            timestamp_ms: 0,
+            reason: "test".to_string(),
+            latency: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resilient_sink_retries_and_breaks() {
+        let policy = SinkPolicy {
+            max_retries: 1,
+            initial_backoff_ms: 1,
+            failure_threshold: 1,
+            open_duration_ms: 0,
+            buffer_capacity: 2,
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff_ms(3), 8);
+
+        // A single failure is absorbed by the retry
+        let flaky = FlakySink::failing(1);
+        let mut sink = ResilientSink::new(flaky, policy.clone());
+        sink.deliver(vec![test_alert("a0")]).await.unwrap();
+        assert_eq!(sink.health().retries, 1);
+        assert_eq!(sink.health().delivered, 1);
+
+        // Two failures exhaust the retry and open the circuit
+        let flaky = FlakySink::failing(4);
+        let mut sink = ResilientSink::new(flaky, policy);
+        sink.deliver(vec![test_alert("a1")]).await.unwrap();
+        assert_eq!(sink.health().state, CircuitState::Open);
+        // Half-open probes fail once each, while the buffer overflows
+        sink.deliver(vec![test_alert("a2")]).await.unwrap();
+        sink.deliver(vec![test_alert("a3")]).await.unwrap();
+        assert_eq!(sink.health().state, CircuitState::Open);
+        assert_eq!(sink.health().buffered, 2);
+        assert_eq!(sink.health().dropped, 1);
+
+        sink.deliver(Vec::new()).await.unwrap();
+        let health = sink.health();
+        assert_eq!(health.state, CircuitState::Closed);
+        assert_eq!((health.delivered, health.buffered), (2, 0));
+        assert_eq!(
+            health.last_error.as_deref(),
+            Some("Configuration error: sink down")
+        );
+        assert_eq!(sink.inner().received, vec!["a2", "a3"]);
+        assert_eq!(sink.inner().batches, 5);
+    }
+
+    #[tokio::test]
+    async fn test_events_acknowledged_after_delivery() {
+        let policy = SinkPolicy {
+            max_retries: 0,
+            failure_threshold: 1,
+            open_duration_ms: 0,
+            buffer_capacity: 1,
+            ..Default::default()
+        };
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let mut sink = ResilientSink::new(FlakySink::failing(2), policy.clone());
+        sink.deliver_for(ids(&["e1"]), vec![test_alert("a1")])
+            .await
+            .unwrap();
+        assert!(sink.take_acknowledgeable().is_empty());
+        // a1 is dropped for a2, so e1 is never acknowledged
+        sink.deliver_for(ids(&["e2"]), vec![test_alert("a2")])
+            .await
+            .unwrap();
+        assert!(sink.take_acknowledgeable().is_empty());
+        sink.deliver_for(ids(&["e3"]), Vec::new()).await.unwrap();
+        assert_eq!(sink.take_acknowledgeable(), ids(&["e2", "e3"]));
+        assert_eq!(sink.inner().received, vec!["a2"]);
+
+        // With no buffer, undeliverable alerts are dropped at once
+        let unbuffered = SinkPolicy {
+            buffer_capacity: 0,
+            ..policy
+        };
+        let mut sink = ResilientSink::new(FlakySink::failing(1), unbuffered);
+        sink.deliver_for(ids(&["e1"]), vec![test_alert("a1")])
+            .await
+            .unwrap();
+        assert_eq!((sink.health().buffered, sink.health().dropped), (0, 1));
+        assert!(sink.take_acknowledgeable().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_shedding() {
+        let config = StreamConfig {
+            phi_alert_threshold: 0.0,
+            alert_cooldown_ms: 0,
+            load_shedding: Some(LoadShedding {
+                engage_depth: 100,
+                release_depth: 10,
+                max_events_per_actor: 1,
+                check_interval_ms: 1_000,
+            }),
+            ..Default::default()
+        };
+        let mut processor = StreamProcessor::new(CompressionDynamicsModel::new(3), config);
+        {
+            let mut m = processor.model.write().await;
+            m.register_actor("A", Some(vec![0.8, 0.1, 0.1]), None);
+            m.register_actor("B", Some(vec![0.1, 0.1, 0.8]), None);
+        }
+        let event = |id: &str, ts: i64| StreamEvent::new(id, "A", vec![0.8, 0.1, 0.1], ts);
+
+        processor.observe_queue_depth(99);
+        assert!(!processor.shedding().active);
+        processor.observe_queue_depth(150);
+        assert!(processor.shedding().active);
+
+        // A's latest normal event and the high-priority one survive
+        let batch = vec![
+            event("e1", 0),
+            event("e2", 10).with_priority(EventPriority::High),
+            event("e3", 20),
+            event("e4", 30),
+        ];
+        let alerts = processor.process_batch(batch).await.unwrap();
+        assert_eq!(processor.shedding().events_shed, 2);
+        assert_eq!(alerts.len(), 2);
+
+        // Within the check interval, only high-priority updates are checked
+        let batch = vec![event("e5", 500)];
+        assert!(processor.process_batch(batch).await.unwrap().is_empty());
+        let batch = vec![event("e6", 600).with_priority(EventPriority::High)];
+        assert_eq!(processor.process_batch(batch).await.unwrap().len(), 1);
+        assert_eq!(processor.shedding().checks_skipped, 1);
+
+        // Hysteresis: still engaged above the release depth
+        processor.observe_queue_depth(50);
+        assert!(processor.shedding().active);
+        processor.observe_queue_depth(10);
+        assert!(!processor.shedding().active);
+        let batch = vec![event("e7", 700), event("e8", 800)];
+        assert_eq!(processor.process_batch(batch).await.unwrap().len(), 2);
+        assert_eq!(processor.shedding().activations, 1);
+
+        let (sender, source) = ChannelEventSource::create_pair(8, 4);
+        sender.send(event("e9", 900)).await.unwrap();
+        assert_eq!(source.backlog().await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_top_risky_dyads() {
+        let mut processor =
+            StreamProcessor::new(CompressionDynamicsModel::new(3), StreamConfig::default());
+        {
+            let mut m = processor.model.write().await;
+            m.register_actor("A", Some(vec![0.8, 0.1, 0.1]), None);
+            m.register_actor("B", Some(vec![0.1, 0.1, 0.8]), None);
+            m.register_actor("C", Some(vec![0.7, 0.2, 0.1]), None);
+        }
+        assert!(processor.top_risky_dyads(3, RiskMetric::Phi).is_empty());
+
+        let event = StreamEvent::new("e1", "C", vec![0.7, 0.2, 0.1], 0);
+        processor.process_event(event).await.unwrap();
+        let top = processor.top_risky_dyads(3, RiskMetric::Phi);
+        assert_eq!(top.len(), 2);
+        assert_eq!(
+            (top[0].actor_a.as_str(), top[0].actor_b.as_str()),
+            ("B", "C")
+        );
+        assert!(top[0].phi > top[1].phi);
+    }
+
+    #[tokio::test]
+    async fn test_digest_schedule() {
+        let config = StreamConfig {
+            phi_alert_threshold: 0.0,
+            alert_cooldown_ms: 0,
+            digest: Some(DigestConfig {
+                interval_ms: 1_000,
+                ..DigestConfig::daily()
+            }),
+            ..Default::default()
+        };
+        let mut processor = StreamProcessor::new(CompressionDynamicsModel::new(3), config);
+        {
+            let mut m = processor.model.write().await;
+            m.register_actor("A", Some(vec![0.8, 0.1, 0.1]), None);
+            m.register_actor("B", Some(vec![0.1, 0.1, 0.8]), None);
+        }
+        let event = |id: &str, ts: i64| StreamEvent::new(id, "A", vec![0.8, 0.1, 0.1], ts);
+
+        processor.process_event(event("e1", 100)).await.unwrap();
+        processor
+            .process_batch(vec![event("e2", 600)])
+            .await
+            .unwrap();
+        assert!(processor.take_digests().is_empty());
+
+        // A gap past two boundaries closes one period, on a boundary
+        processor.process_event(event("e3", 2_500)).await.unwrap();
+        let digests = processor.take_digests();
+        assert_eq!(digests.len(), 1);
+        assert_eq!(
+            (digests[0].period_start_ms, digests[0].period_end_ms),
+            (100, 2_100)
+        );
+        // e3's alert opens the next period rather than closing this one
+        assert_eq!(digests[0].alert_total(), 2);
+        assert!(processor.take_digests().is_empty());
+
+        // A batch across a boundary splits its alerts
+        processor
+            .process_batch(vec![event("e4", 2_900), event("e5", 3_200)])
+            .await
+            .unwrap();
+        let digests = processor.take_digests();
+        assert_eq!(
+            (digests[0].period_start_ms, digests[0].period_end_ms),
+            (2_100, 3_100)
+        );
+        assert_eq!(digests[0].alert_total(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_latency_tracking() {
+        let config = StreamConfig {
+            phi_alert_threshold: 0.0,
+            ..Default::default()
+        };
+        let mut processor = StreamProcessor::new(CompressionDynamicsModel::new(3), config);
+        {
+            let mut m = processor.model.write().await;
+            m.register_actor("A", Some(vec![0.8, 0.1, 0.1]), None);
+            m.register_actor("B", Some(vec![0.1, 0.1, 0.8]), None);
+        }
+
+        // Ingested 250 ms ago, e.g. queued behind a slow batch
+        let mut event = StreamEvent::new("e1", "A", vec![0.8, 0.1, 0.1], 0);
+        event.ingested_ms = Some(now_ms() - 250);
+        let alerts = processor.process_batch(vec![event]).await.unwrap();
+        let latency = alerts[0].latency.unwrap();
+        assert!(latency.processing_ms() >= 250);
+        assert_eq!(latency.emitted_ms, None);
+
+        let mut sink = ResilientSink::new(FlakySink::failing(0), SinkPolicy::default());
+        sink.deliver(alerts).await.unwrap();
+        let emitted = sink.take_emitted();
+        assert!(emitted[0].end_to_end_ms().unwrap() >= 250);
+        assert!(sink.take_emitted().is_empty());
+        processor.record_emitted(&emitted);
+
+        let report = processor.latency();
+        assert_eq!(report.processing.count, 1);
+        assert_eq!(report.end_to_end.count, 1);
+        assert!(report.end_to_end.p99_ms >= 250);
+        // Timestamps are local bookkeeping, not part of the event payload
+        let json = serde_json::to_value(StreamEvent::new("e2", "A", vec![1.0], 0)).unwrap();
+        assert!(json.get("ingested_ms").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_threshold_changes_are_audited() {
+        let mut processor =
+            StreamProcessor::new(CompressionDynamicsModel::new(3), StreamConfig::default());
+        let changed = processor
+            .update_config(5_000, |c| c.phi_alert_threshold = 1.5)
+            .await
+            .unwrap();
+        assert_eq!(changed, 1);
+        assert_eq!(processor.config().phi_alert_threshold, 1.5);
+
+        // Invalid robustness settings are rejected without being applied
+        let check = |n_samples, sigma| RobustnessCheck {
+            noise: NoiseModel::Gaussian { sigma },
+            n_samples,
+            ..Default::default()
+        };
+        for bad in [check(0, 0.1), check(10, -1.0)] {
+            assert!(processor
+                .update_config(6_000, |c| c.robustness = Some(bad))
+                .await
+                .is_err());
+        }
+        assert!(processor.config().robustness.is_none());
+
+        let model = processor.model.read().await;
+        let entry = &model.audit_log()[0];
+        assert_eq!(entry.setting, "stream.phi_alert_threshold");
+        assert_eq!(entry.timestamp_ms, 5_000);
+    }
 }
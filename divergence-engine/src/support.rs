@@ -0,0 +1,200 @@
+//! Comparison of schemes over different category sets.
+//!
+//! Divergences need both distributions on the same support, so actors
+//! tracked with different category lists cannot be compared directly.
+//! Both schemes are first projected onto a shared support, matched by
+//! category name:
+//!
+//! ```text
+//! Intersection: categories both track; mass elsewhere is dropped
+//! Union:        every category either tracks, missing ones at zero
+//! ```
+//!
+//! Projected distributions are renormalized and re-smoothed with each
+//! scheme's own smoothing, which lifts the zero padding of a union.
+
+use crate::divergence::DivergenceMetrics;
+use crate::error::{DivergenceError, Result};
+use crate::model::CompressionDynamicsModel;
+use crate::registry::CategoryMapping;
+use crate::scheme::CompressionScheme;
+use serde::{Deserialize, Serialize};
+
+/// How two category sets are reconciled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SharedSupport {
+    /// Categories both schemes track
+    #[default]
+    Intersection,
+    /// Categories either scheme tracks, zero-padded
+    Union,
+}
+
+/// Divergences between two schemes on a shared support
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportComparison {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub support: SharedSupport,
+    /// Shared categories, in the first scheme's order
+    pub categories: Vec<String>,
+    /// Mass of the first scheme outside the support (zero for a union)
+    pub dropped_a: f64,
+    /// Mass of the second scheme outside the support (zero for a union)
+    pub dropped_b: f64,
+    /// Symmetric KL on the shared support
+    pub phi: f64,
+    pub metrics: DivergenceMetrics,
+}
+
+impl CompressionScheme {
+    /// Names of all categories in index order
+    pub fn category_names(&self) -> Vec<String> {
+        (0..self.n_categories())
+            .map(|i| self.category_name(i))
+            .collect()
+    }
+
+    /// Copies of both schemes projected onto their shared support
+    ///
+    /// Errors when an intersection is empty.
+    pub fn on_shared_support(
+        &self,
+        other: &CompressionScheme,
+        support: SharedSupport,
+    ) -> Result<(CompressionScheme, CompressionScheme)> {
+        let names_a = self.category_names();
+        let names_b = other.category_names();
+        let shared: Vec<String> = match support {
+            SharedSupport::Intersection => names_a
+                .iter()
+                .filter(|name| names_b.contains(name))
+                .cloned()
+                .collect(),
+            SharedSupport::Union => {
+                let mut names = names_a.clone();
+                names.extend(names_b.iter().filter(|n| !names_a.contains(n)).cloned());
+                names
+            }
+        };
+        if shared.is_empty() {
+            return Err(DivergenceError::ConfigError(format!(
+                "{} and {} share no categories",
+                self.actor_id, other.actor_id
+            )));
+        }
+
+        let mut projected_a = self.clone();
+        projected_a.remap(&support_mapping(&names_a, &shared))?;
+        let mut projected_b = other.clone();
+        projected_b.remap(&support_mapping(&names_b, &shared))?;
+        Ok((projected_a, projected_b))
+    }
+
+    /// Divergences against another scheme on the shared support
+    pub fn compare_on_shared_support(
+        &self,
+        other: &CompressionScheme,
+        support: SharedSupport,
+    ) -> Result<SupportComparison> {
+        let (a, b) = self.on_shared_support(other, support)?;
+        let kept = |scheme: &CompressionScheme, projected: &CompressionScheme| -> f64 {
+            scheme
+                .distribution()
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| projected.category_index(&scheme.category_name(i)).is_some())
+                .map(|(_, p)| p)
+                .sum()
+        };
+        let metrics = a.all_metrics(&b)?;
+
+        Ok(SupportComparison {
+            actor_a: self.actor_id.clone(),
+            actor_b: other.actor_id.clone(),
+            support,
+            dropped_a: (1.0 - kept(self, &a)).max(0.0),
+            dropped_b: (1.0 - kept(other, &b)).max(0.0),
+            categories: a.categories,
+            phi: metrics.symmetric_kl,
+            metrics,
+        })
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Compare two actors on the categories both track
+    pub fn compare_on_shared_support(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+    ) -> Result<SupportComparison> {
+        self.compare_on_shared_support_with(actor_a, actor_b, SharedSupport::Intersection)
+    }
+
+    /// Compare two actors on a chosen shared support
+    pub fn compare_on_shared_support_with(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        support: SharedSupport,
+    ) -> Result<SupportComparison> {
+        let scheme_a = self.scheme_for(actor_a)?;
+        let scheme_b = self.scheme_for(actor_b)?;
+        scheme_a.compare_on_shared_support(scheme_b, support)
+    }
+}
+
+/// Mapping sending each named category to its slot in `shared`
+fn support_mapping(names: &[String], shared: &[String]) -> CategoryMapping {
+    names.iter().enumerate().fold(
+        CategoryMapping::new(names.len(), shared.to_vec()),
+        |m, (old, name)| match shared.iter().position(|s| s == name) {
+            Some(new) => m.map(old, new),
+            None => m,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Option<Vec<String>> {
+        Some(names.iter().map(|n| n.to_string()).collect())
+    }
+
+    #[test]
+    fn test_compare_on_shared_support() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.5, 0.3, 0.2]), names(&["x", "y", "z"]));
+        model.register_actor(
+            "B",
+            Some(vec![0.1, 0.6, 0.3, 0.0]),
+            names(&["w", "z", "y", "x"]),
+        );
+        assert!(model.peek_potential("A", "B").is_err());
+
+        let inter = model.compare_on_shared_support("A", "B").unwrap();
+        assert_eq!(inter.categories, ["x", "y", "z"]);
+        assert!(inter.dropped_a < 1e-6);
+        assert!((inter.dropped_b - 0.1).abs() < 1e-6);
+        assert!(inter.phi > 0.0 && inter.phi.is_finite());
+
+        // Same shape in a different order compares as identical
+        model.register_actor("C", Some(vec![0.2, 0.5, 0.3]), names(&["z", "x", "y"]));
+        let same = model.compare_on_shared_support("A", "C").unwrap();
+        assert!(same.phi < 1e-9);
+
+        let union = model
+            .compare_on_shared_support_with("A", "B", SharedSupport::Union)
+            .unwrap();
+        assert_eq!(union.categories, ["x", "y", "z", "w"]);
+        assert!(union.dropped_b < 1e-12);
+        assert!(union.phi > inter.phi);
+
+        model.register_actor("D", Some(vec![0.5, 0.5]), names(&["p", "q"]));
+        assert!(model.compare_on_shared_support("A", "D").is_err());
+        assert!(model.compare_on_shared_support("A", "Z").is_err());
+    }
+}
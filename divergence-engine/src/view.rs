@@ -0,0 +1,172 @@
+//! Read-only model views.
+//!
+//! A [`ModelView`] is a snapshot of a model exposing only read operations:
+//! schemes, potentials, predictions and histories. Hand it to dashboard or
+//! report components to guarantee at the type level that they cannot
+//! update schemes or append history. Clones of a view share one snapshot.
+//! Potentials and predictions are computed with the `peek_*` variants and
+//! never recorded.
+
+use crate::audit::AuditEntry;
+use crate::cohesion::CohesionSample;
+use crate::error::Result;
+use crate::model::{
+    CompressionDynamicsModel, EscalationPrediction, Grievance, ModelConfig, ModelSummary,
+    SchemeHistoryEntry,
+};
+use crate::risk_index::{RiskIndexSample, SystemRiskIndex};
+use crate::scheme::{CompressionScheme, ConflictPotential};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Read-only snapshot of a model
+#[derive(Debug, Clone)]
+pub struct ModelView {
+    model: Arc<CompressionDynamicsModel>,
+}
+
+impl From<CompressionDynamicsModel> for ModelView {
+    fn from(model: CompressionDynamicsModel) -> Self {
+        Self {
+            model: Arc::new(model),
+        }
+    }
+}
+
+impl CompressionDynamicsModel {
+    /// Read-only snapshot of the current state
+    pub fn view(&self) -> ModelView {
+        ModelView::from(self.clone())
+    }
+}
+
+impl ModelView {
+    /// Model configuration
+    pub fn config(&self) -> &ModelConfig {
+        self.model.config()
+    }
+
+    /// Registered actor IDs in registration order
+    pub fn actors(&self) -> Vec<&str> {
+        self.model.actors()
+    }
+
+    /// Number of registered actors
+    pub fn n_actors(&self) -> usize {
+        self.model.n_actors()
+    }
+
+    /// Current scheme of an actor
+    pub fn scheme(&self, actor_id: &str) -> Option<&CompressionScheme> {
+        self.model.get_scheme(actor_id)
+    }
+
+    /// Accumulated grievance of an actor
+    pub fn grievance(&self, actor_id: &str) -> Option<&Grievance> {
+        let actor_id = self.model.resolve_actor(actor_id)?;
+        self.model.grievances.get(actor_id)
+    }
+
+    /// Grievance `holder` attributes to `source`
+    pub fn directed_grievance(&self, holder: &str, source: &str) -> Option<&Grievance> {
+        self.model.directed_grievance(holder, source)
+    }
+
+    /// Conflict potential between two actors
+    pub fn potential(&self, actor_a: &str, actor_b: &str) -> Result<ConflictPotential> {
+        self.model.peek_potential(actor_a, actor_b)
+    }
+
+    /// Pairwise conflict potentials for all actors
+    pub fn all_potentials(&self) -> Vec<ConflictPotential> {
+        self.model.peek_all_potentials()
+    }
+
+    /// Escalation prediction for a dyad
+    pub fn escalation(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+        communication_level: f64,
+        shock_intensity: f64,
+    ) -> Result<EscalationPrediction> {
+        self.model
+            .peek_escalation(actor_a, actor_b, communication_level, shock_intensity)
+    }
+
+    /// Composite system risk index
+    pub fn system_risk_index(&self) -> Result<SystemRiskIndex> {
+        self.model.system_risk_index()
+    }
+
+    /// Recorded potentials of a dyad, oldest first
+    pub fn dyad_history(&self, actor_a: &str, actor_b: &str) -> Vec<&ConflictPotential> {
+        self.model.get_dyad_history(actor_a, actor_b)
+    }
+
+    /// Scheme updates of an actor, oldest first
+    pub fn scheme_history(&self, actor_id: &str) -> Vec<SchemeHistoryEntry> {
+        let Some(actor_id) = self.model.resolve_actor(actor_id) else {
+            return Vec::new();
+        };
+        self.model
+            .history
+            .iter()
+            .filter(|e| e.actor_id == actor_id)
+            .map(Cow::into_owned)
+            .collect()
+    }
+
+    /// Recorded cohesion samples of a parent, oldest first
+    pub fn cohesion_history(&self, parent: &str) -> &[CohesionSample] {
+        self.model.cohesion_history(parent)
+    }
+
+    /// Recorded system risk index values, oldest first
+    pub fn risk_index_history(&self) -> &[RiskIndexSample] {
+        self.model.risk_index_history()
+    }
+
+    /// Recorded configuration changes, oldest first
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        self.model.audit_log()
+    }
+
+    /// Summary of the viewed state
+    pub fn summary(&self) -> ModelSummary {
+        self.model.summary()
+    }
+
+    /// Serialize the viewed state to JSON
+    pub fn to_json(&self) -> Result<String> {
+        self.model.to_json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_is_a_snapshot() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.8, 0.1, 0.1]), None);
+        model.register_actor("B", Some(vec![0.1, 0.1, 0.8]), None);
+        model
+            .update_scheme("A", &[0.9, 0.05, 0.05], Some(1))
+            .unwrap();
+
+        let view = model.view();
+        let shared = view.clone();
+        assert_eq!(view.actors(), vec!["A", "B"]);
+        assert_eq!(view.scheme_history("A").len(), 1);
+        let phi = view.potential("A", "B").unwrap().phi;
+        view.escalation("A", "B", 0.5, 0.0).unwrap();
+        // Reads never record
+        assert!(view.dyad_history("A", "B").is_empty());
+
+        model.update_scheme("A", &[0.1, 0.1, 0.8], Some(2)).unwrap();
+        assert_eq!(shared.potential("A", "B").unwrap().phi, phi);
+        assert_eq!(shared.summary().n_history_entries, 1);
+    }
+}
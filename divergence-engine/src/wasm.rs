@@ -52,6 +52,9 @@ impl WasmDivergenceEngine {
     pub fn with_config(config_json: &str) -> Result<WasmDivergenceEngine, JsValue> {
         let config: ModelConfig = serde_json::from_str(config_json)
             .map_err(|e| JsValue::from_str(&format!("Invalid config: {}", e)))?;
+        config
+            .validate()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         Ok(Self {
             model: CompressionDynamicsModel::with_config(config),
@@ -109,6 +112,24 @@ impl WasmDivergenceEngine {
         Ok(JsValue::from_str(&json))
     }
 
+    /// Compute conflict potential without recording it in history
+    #[wasm_bindgen(js_name = "peekConflictPotential")]
+    pub fn peek_conflict_potential(
+        &self,
+        actor_a: &str,
+        actor_b: &str,
+    ) -> Result<JsValue, JsValue> {
+        let potential = self
+            .model
+            .peek_potential(actor_a, actor_b)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let json = potential
+            .to_json()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(JsValue::from_str(&json))
+    }
+
     /// Compute all pairwise potentials
     #[wasm_bindgen(js_name = "computeAllPotentials")]
     pub fn compute_all_potentials(&mut self) -> Result<JsValue, JsValue> {
@@ -166,6 +187,53 @@ impl WasmDivergenceEngine {
             .collect()
     }
 
+    /// Current scheme of an actor as at most `bins` bars (JSON
+    /// `{actor_id, labels, values}`)
+    #[wasm_bindgen(js_name = "schemeHistogram")]
+    pub fn scheme_histogram(&self, actor_id: &str, bins: usize) -> Result<JsValue, JsValue> {
+        let histogram = self
+            .model
+            .scheme_histogram(actor_id, bins)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let json = serde_json::to_string(&histogram)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))?;
+        Ok(JsValue::from_str(&json))
+    }
+
+    /// Scheme entropy of an actor at `n_points` times (JSON
+    /// `{timestamps_ms, values, min, max}`)
+    #[wasm_bindgen(js_name = "entropySparkline")]
+    pub fn entropy_sparkline(&self, actor_id: &str, n_points: usize) -> Result<JsValue, JsValue> {
+        let sparkline = self
+            .model
+            .entropy_sparkline(actor_id, n_points)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let json = serde_json::to_string(&sparkline)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))?;
+        Ok(JsValue::from_str(&json))
+    }
+
+    /// Probability of one category for an actor over `[start_ms, end_ms)`,
+    /// optionally resampled every `step_ms` (JSON `{actor_id, category,
+    /// timestamps_ms, values}`)
+    #[wasm_bindgen(js_name = "categoryTimeseries")]
+    pub fn category_timeseries(
+        &self,
+        actor_id: &str,
+        category: &str,
+        start_ms: i64,
+        end_ms: i64,
+        step_ms: Option<i64>,
+    ) -> Result<JsValue, JsValue> {
+        let series = self
+            .model
+            .category_timeseries(actor_id, category, start_ms..end_ms, step_ms)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let json = serde_json::to_string(&series)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))?;
+        Ok(JsValue::from_str(&json))
+    }
+
     /// Get model summary
     #[wasm_bindgen(js_name = "getSummary")]
     pub fn get_summary(&self) -> Result<JsValue, JsValue> {
@@ -0,0 +1,348 @@
+//! WebSocket event source.
+//!
+//! [`WebSocketEventSource`] reads [`StreamEvent`] JSON from a `ws://` (or
+//! `wss://`) feed. Each text or binary message holds one event or an array
+//! of events; messages that fail to parse are skipped and counted.
+//!
+//! The connection is kept alive without caller involvement:
+//!
+//! - it is opened on the first `receive`, with an optional bearer token in
+//!   the `Authorization` header;
+//! - after `heartbeat_interval_ms` without traffic a ping is sent, and if
+//!   another interval passes in silence the connection is considered dead;
+//! - closed, failed or dead connections are reopened with exponential
+//!   backoff. Only when `max_reconnects` consecutive attempts fail does
+//!   `receive` error and `health_check` turn false.
+
+use crate::error::{DivergenceError, Result};
+use crate::streaming::{decode_events, EventSource, StreamEvent};
+use async_trait::async_trait;
+use futures::{FutureExt, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Connection settings for a [`WebSocketEventSource`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketConfig {
+    /// Feed endpoint, e.g. `ws://localhost:9000/events`
+    pub url: String,
+    /// Sent as `Authorization: Bearer <token>` when set
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    /// Most events returned per `receive`
+    pub batch_size: usize,
+    /// Idle time before a ping, and again before giving up on the pong
+    pub heartbeat_interval_ms: u64,
+    /// Backoff before the first reconnect attempt; doubles per failure
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Consecutive failed connection attempts before giving up (`None`:
+    /// keep trying)
+    #[serde(default)]
+    pub max_reconnects: Option<usize>,
+}
+
+impl WebSocketConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            bearer_token: None,
+            batch_size: 100,
+            heartbeat_interval_ms: 30_000,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 10_000,
+            max_reconnects: None,
+        }
+    }
+
+    /// Authenticate with a bearer token
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Backoff before reconnect attempt `attempt` (0-based)
+    pub fn backoff_ms(&self, attempt: usize) -> u64 {
+        let factor = 1u64.checked_shl(attempt.min(32) as u32).unwrap_or(u64::MAX);
+        self.initial_backoff_ms
+            .saturating_mul(factor)
+            .min(self.max_backoff_ms)
+    }
+}
+
+/// Event source reading JSON events from a WebSocket feed
+pub struct WebSocketEventSource {
+    config: WebSocketConfig,
+    socket: Option<Socket>,
+    /// Connections opened, the first included
+    connections: u64,
+    malformed: u64,
+    gave_up: bool,
+}
+
+impl WebSocketEventSource {
+    /// Source connecting on first use
+    pub fn new(config: WebSocketConfig) -> Self {
+        Self {
+            config,
+            socket: None,
+            connections: 0,
+            malformed: 0,
+            gave_up: false,
+        }
+    }
+
+    /// Source connected up front, failing if the first attempt fails
+    pub async fn connect(config: WebSocketConfig) -> Result<Self> {
+        let mut source = Self::new(config);
+        source.socket = Some(source.open().await?);
+        source.connections = 1;
+        Ok(source)
+    }
+
+    /// Connection settings
+    pub fn config(&self) -> &WebSocketConfig {
+        &self.config
+    }
+
+    /// Connections opened so far, reconnects included
+    pub fn connections(&self) -> u64 {
+        self.connections
+    }
+
+    /// Messages skipped because they did not parse as events
+    pub fn malformed_messages(&self) -> u64 {
+        self.malformed
+    }
+
+    async fn open(&self) -> Result<Socket> {
+        let mut request = self
+            .config
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| DivergenceError::ConfigError(format!("Invalid WebSocket URL: {}", e)))?;
+        if let Some(token) = &self.config.bearer_token {
+            let value = HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|e| {
+                DivergenceError::ConfigError(format!("Invalid bearer token: {}", e))
+            })?;
+            request.headers_mut().insert("Authorization", value);
+        }
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .map_err(|e| {
+                DivergenceError::ConfigError(format!(
+                    "WebSocket connection to {} failed: {}",
+                    self.config.url, e
+                ))
+            })?;
+        Ok(socket)
+    }
+
+    /// Open a connection unless one is live, backing off between attempts
+    async fn ensure_connected(&mut self) -> Result<()> {
+        if self.socket.is_some() {
+            return Ok(());
+        }
+        if self.gave_up {
+            return Err(DivergenceError::ConfigError(format!(
+                "Gave up reconnecting to {}",
+                self.config.url
+            )));
+        }
+        let mut attempt = 0;
+        loop {
+            match self.open().await {
+                Ok(socket) => {
+                    self.socket = Some(socket);
+                    self.connections += 1;
+                    return Ok(());
+                }
+                Err(e) => {
+                    attempt += 1;
+                    if self.config.max_reconnects.is_some_and(|max| attempt >= max) {
+                        self.gave_up = true;
+                        return Err(e);
+                    }
+                    let backoff = self.config.backoff_ms(attempt - 1);
+                    tokio::time::sleep(Duration::from_millis(backoff)).await;
+                }
+            }
+        }
+    }
+
+    /// Append the events in a data message; control frames are ignored
+    fn decode(&mut self, message: Message, events: &mut Vec<StreamEvent>) {
+        let parsed = match &message {
+            Message::Text(text) => decode_events(text.as_bytes()),
+            Message::Binary(bytes) => decode_events(bytes),
+            _ => return,
+        };
+        match parsed {
+            Some(batch) => events.extend(batch),
+            None => self.malformed += 1,
+        }
+    }
+}
+
+#[async_trait]
+impl EventSource for WebSocketEventSource {
+    async fn receive(&mut self) -> Result<Vec<StreamEvent>> {
+        let heartbeat = Duration::from_millis(self.config.heartbeat_interval_ms.max(1));
+        let mut events = Vec::new();
+        let mut pinged = false;
+
+        while events.is_empty() {
+            self.ensure_connected().await?;
+            let socket = self.socket.as_mut().unwrap();
+
+            let message = match tokio::time::timeout(heartbeat, socket.next()).await {
+                Ok(Some(Ok(message))) => message,
+                // Closed or failed: reconnect
+                Ok(None) | Ok(Some(Err(_))) => {
+                    self.socket = None;
+                    continue;
+                }
+                Err(_) if !pinged => {
+                    pinged = true;
+                    if socket.send(Message::Ping(Vec::new())).await.is_err() {
+                        self.socket = None;
+                    }
+                    continue;
+                }
+                // No pong within a heartbeat: assume the connection is dead
+                Err(_) => {
+                    self.socket = None;
+                    pinged = false;
+                    continue;
+                }
+            };
+            pinged = false;
+            if let Message::Close(_) = message {
+                self.socket = None;
+                continue;
+            }
+            self.decode(message, &mut events);
+
+            // Take whatever else has already arrived, up to the batch size
+            while events.len() < self.config.batch_size {
+                let Some(socket) = self.socket.as_mut() else {
+                    break;
+                };
+                match socket.next().now_or_never() {
+                    Some(Some(Ok(Message::Close(_)))) | Some(None) | Some(Some(Err(_))) => {
+                        self.socket = None;
+                    }
+                    Some(Some(Ok(message))) => {
+                        self.decode(message, &mut events);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    async fn acknowledge(&mut self, _event_ids: &[String]) -> Result<()> {
+        // WebSocket feeds have no delivery acknowledgement
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        !self.gave_up
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+    fn event_json(id: &str, ts: i64) -> String {
+        format!(
+            r#"{{"event_id":"{}","actor_id":"A","observation":[0.5,0.5],"timestamp_ms":{},"source":"ws"}}"#,
+            id, ts
+        )
+    }
+
+    // The handshake callback's error type is tungstenite's, not ours
+    #[allow(clippy::result_large_err)]
+    #[tokio::test]
+    async fn test_websocket_source_reconnects() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut tokens = Vec::new();
+            // First connection sends a batch and hangs up; the second sends one more
+            for batch in [
+                vec![
+                    event_json("e1", 1),
+                    "not json".to_string(),
+                    format!("[{},{}]", event_json("e2", 2), event_json("e3", 3)),
+                ],
+                vec![event_json("e4", 4)],
+            ] {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut socket = tokio_tungstenite::accept_hdr_async(
+                    stream,
+                    |request: &Request, response: Response| {
+                        tokens.push(request.headers().get("Authorization").cloned());
+                        Ok(response)
+                    },
+                )
+                .await
+                .unwrap();
+                for message in batch {
+                    socket.send(Message::Text(message)).await.unwrap();
+                }
+                socket.close(None).await.unwrap();
+            }
+            tokens
+        });
+
+        let mut config = WebSocketConfig::new(url).with_bearer_token("secret");
+        config.initial_backoff_ms = 1;
+        let mut source = WebSocketEventSource::new(config);
+
+        let mut ids = Vec::new();
+        while ids.len() < 4 {
+            let events = source.receive().await.unwrap();
+            ids.extend(events.into_iter().map(|e| e.event_id));
+        }
+        assert_eq!(ids, ["e1", "e2", "e3", "e4"]);
+        assert_eq!(source.connections(), 2);
+        assert_eq!(source.malformed_messages(), 1);
+        assert!(source.health_check().await);
+
+        let tokens = server.await.unwrap();
+        assert!(tokens
+            .iter()
+            .all(|t| t.as_ref().unwrap() == "Bearer secret"));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_source_gives_up() {
+        // Bind then drop, leaving a port nothing listens on
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let mut config = WebSocketConfig::new(url);
+        config.initial_backoff_ms = 1;
+        config.max_reconnects = Some(2);
+        let mut source = WebSocketEventSource::new(config);
+        assert!(source.receive().await.is_err());
+        assert!(!source.health_check().await);
+        assert_eq!(source.config().backoff_ms(40), 10_000);
+    }
+}
@@ -0,0 +1,138 @@
+//! Benchmarks for nucleation hot paths.
+//!
+//! Run with: cargo bench
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use nucleation::{
+    permutation_entropy, shannon_entropy, ShepherdDynamics, VarianceConfig,
+    VarianceInflectionDetector,
+};
+
+fn generate_series(n: usize, seed: u64) -> Vec<f64> {
+    // Simple deterministic pseudo-random for reproducibility
+    let mut series = Vec::with_capacity(n);
+    let mut x = seed;
+    for _ in 0..n {
+        x = x.wrapping_mul(6364136223846793005).wrapping_add(1);
+        series.push((x >> 11) as f64 / (1u64 << 53) as f64);
+    }
+    series
+}
+
+fn generate_distribution(n: usize, seed: u64) -> Vec<f64> {
+    let mut dist = generate_series(n, seed);
+    let sum: f64 = dist.iter().sum();
+    for x in &mut dist {
+        *x /= sum;
+    }
+    dist
+}
+
+fn generate_symbols(n: usize, alphabet: u32, seed: u64) -> Vec<u32> {
+    generate_series(n, seed)
+        .into_iter()
+        .map(|x| (x * alphabet as f64) as u32)
+        .collect()
+}
+
+fn bench_variance_detector(c: &mut Criterion) {
+    let mut group = c.benchmark_group("variance_detector_update");
+
+    for window in [20, 40, 100, 250].iter() {
+        let config = VarianceConfig {
+            window_size: *window,
+            ..Default::default()
+        };
+        let series = generate_series(10_000, 42);
+
+        // Warm the detector up so every update runs the full pipeline
+        let mut detector = VarianceInflectionDetector::new(config);
+        detector.update_batch(&series[..window * 4]);
+
+        let mut i = 0;
+        group.throughput(Throughput::Elements(1));
+        group.bench_with_input(BenchmarkId::from_parameter(window), window, |b, _| {
+            b.iter(|| {
+                i = (i + 1) % series.len();
+                detector.update(black_box(series[i]))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_shepherd_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shepherd_update_actor");
+    group.sample_size(20);
+
+    let n_categories = 20;
+
+    for n_actors in [10, 100, 500].iter() {
+        let mut shepherd = ShepherdDynamics::new(n_categories);
+        for i in 0..*n_actors {
+            let dist = generate_distribution(n_categories, i as u64);
+            shepherd.register_actor(format!("Actor{}", i), Some(dist));
+        }
+
+        let observations: Vec<Vec<f64>> = (0..64)
+            .map(|i| generate_distribution(n_categories, 1000 + i))
+            .collect();
+
+        let mut t = 0usize;
+        group.throughput(Throughput::Elements(*n_actors as u64 - 1));
+        group.bench_with_input(BenchmarkId::from_parameter(n_actors), n_actors, |b, _| {
+            b.iter(|| {
+                t += 1;
+                let obs = &observations[t % observations.len()];
+                shepherd.update_actor("Actor0", black_box(obs), t as f64)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_shannon_entropy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shannon_entropy");
+
+    for size in [64, 256, 1024, 4096].iter() {
+        let data = generate_symbols(*size, 16, 42);
+
+        group.throughput(Throughput::Elements(*size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, _| {
+            b.iter(|| shannon_entropy(black_box(&data)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_permutation_entropy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("permutation_entropy");
+
+    for size in [64, 256, 1024, 4096].iter() {
+        let data = generate_series(*size, 42);
+
+        for order in [3, 5].iter() {
+            group.throughput(Throughput::Elements(*size as u64));
+            group.bench_with_input(
+                BenchmarkId::new(format!("order_{}", order), size),
+                size,
+                |b, _| b.iter(|| permutation_entropy(black_box(&data), *order, 1)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_variance_detector,
+    bench_shepherd_update,
+    bench_shannon_entropy,
+    bench_permutation_entropy,
+);
+
+criterion_main!(benches);
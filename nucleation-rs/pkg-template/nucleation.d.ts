@@ -157,6 +157,8 @@ export interface ConflictPotentialDetails {
   klBA: number;
   /** Risk category: "LOW" | "MODERATE" | "ELEVATED" | "HIGH" | "CRITICAL". */
   riskCategory: string;
+  /** Product of both schemes' reliabilities (0-1); low for barely observed actors. */
+  reliability: number;
 }
 
 /**
@@ -230,6 +232,8 @@ export interface NucleationAlertData {
   phi: number;
   phiTrend: number;
   confidence: number;
+  /** Observation-backed reliability of the underlying potential (0-1). */
+  reliability: number;
   timestamp: number;
   message: string;
 }
@@ -294,12 +298,61 @@ export class Shepherd {
    */
   actors(): string[];
 
+  /**
+   * Latest `limit` dyad alerts as a JSON array of alert cards, oldest first.
+   * Cards share their JSON shape with server-side `AlertCard::to_json`.
+   */
+  alertCards(limit: number): string;
+
   /**
    * Get phi history for a dyad as flat array [t1, phi1, t2, phi2, ...].
    */
   phiHistory(actor_a: string, actor_b: string): Float64Array;
+
+  /**
+   * Mark a context event on an actor.
+   * @returns False if the actor is unknown.
+   */
+  annotateActor(actor_id: string, timestamp: number, label: string): boolean;
+
+  /**
+   * Mark a context event on a dyad.
+   * @returns False if either actor is unknown.
+   */
+  annotateDyad(actor_a: string, actor_b: string, timestamp: number, label: string): boolean;
+
+  /**
+   * Get annotations to draw over `phiHistory` for a dyad, oldest first.
+   */
+  phiHistoryAnnotations(actor_a: string, actor_b: string): PhiAnnotation[];
 }
 
+/**
+ * Context event marked on a dyad's phi history.
+ */
+export interface PhiAnnotation {
+  timestamp: number;
+  label: string;
+  /** Annotated actor, or null for dyad annotations. */
+  actor: string | null;
+}
+
+/**
+ * Presentation attributes of an alert level.
+ */
+export interface AlertLevelDisplay {
+  label: string;
+  /** CSS color, "#rrggbb". */
+  colorHex: string;
+  emoji: string;
+  toast: "success" | "info" | "warning" | "error";
+}
+
+/**
+ * Get the presentation attributes of an alert level.
+ */
+export function alertLevelDisplay(level: AlertLevel): AlertLevelDisplay;
+
 /**
  * Get the library version.
  */
@@ -307,16 +360,19 @@ export function version(): string;
 
 /**
  * Compute KL divergence between two distributions.
+ * @throws If the distributions have different lengths.
  */
 export function klDivergence(p: Float64Array | number[], q: Float64Array | number[]): number;
 
 /**
  * Compute Hellinger distance between two distributions.
+ * @throws If the distributions have different lengths.
  */
 export function hellingerDistance(p: Float64Array | number[], q: Float64Array | number[]): number;
 
 /**
  * Compute Jensen-Shannon divergence between two distributions.
+ * @throws If the distributions have different lengths.
  */
 export function jensenShannonDivergence(p: Float64Array | number[], q: Float64Array | number[]): number;
 
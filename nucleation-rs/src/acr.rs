@@ -8,6 +8,7 @@
 //! - d(phi_int)/dt = omega_int + K(E) * sin(phi_ext - phi_int) + beta * u(t)
 //! - R(t) = |<exp(i * delta_phi)>| (resonance metric)
 
+use std::collections::VecDeque;
 use std::f64::consts::PI;
 
 /// Cognitive modality types (from empirical data analysis)
@@ -135,6 +136,7 @@ pub struct ACRController {
     /// Coupling strength base
     coupling_base: f64,
     /// Damping coefficient
+    #[allow(dead_code)]
     damping: f64,
     /// Control gain for u(t)
     beta: f64,
@@ -143,7 +145,7 @@ pub struct ACRController {
     /// Minimum energy for stable insight
     energy_min: f64,
     /// Resonance history for averaging
-    resonance_history: Vec<f64>,
+    resonance_history: VecDeque<f64>,
     /// History window size
     window_size: usize,
 }
@@ -159,11 +161,18 @@ impl ACRController {
             beta: 0.3,
             gamma_crit: 0.8,
             energy_min: 0.4,
-            resonance_history: Vec::with_capacity(50),
+            resonance_history: VecDeque::with_capacity(50),
             window_size: 50,
         }
     }
 
+    /// Set the resonance averaging window (number of samples)
+    pub fn with_window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size.max(1);
+        self.resonance_history = VecDeque::with_capacity(self.window_size);
+        self
+    }
+
     /// Update controller with new observation
     pub fn update(
         &mut self,
@@ -233,9 +242,9 @@ impl ACRController {
         let resonance_sample = (self.state.phase_error.cos(), self.state.phase_error.sin());
 
         if self.resonance_history.len() >= self.window_size {
-            self.resonance_history.remove(0);
+            self.resonance_history.pop_front();
         }
-        self.resonance_history.push(resonance_sample.0); // Real part for simplicity
+        self.resonance_history.push_back(resonance_sample.0); // Real part for simplicity
 
         // Average resonance
         if !self.resonance_history.is_empty() {
@@ -0,0 +1,112 @@
+//! Actor handles
+//!
+//! Dyad bookkeeping (phi history, trackers) is keyed per pair of actors and
+//! looked up on every potential computation. Keying by `(String, String)`
+//! allocates two strings per lookup; interning actor IDs into compact
+//! [`ActorId`] handles makes dyad keys `Copy` and hashing O(1).
+
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Compact numeric handle for an interned actor ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActorId(pub u32);
+
+impl ActorId {
+    /// Position of the actor in registration order.
+    #[inline]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Canonical (unordered) dyad key for a pair of actors.
+#[inline]
+pub fn dyad(a: ActorId, b: ActorId) -> (ActorId, ActorId) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Bidirectional mapping between actor names and [`ActorId`] handles.
+///
+/// Handles are assigned sequentially in registration order and never reused.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActorInterner {
+    names: Vec<String>,
+    ids: HashMap<String, ActorId>,
+}
+
+impl ActorInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Intern a name, returning its existing or newly assigned handle.
+    pub fn intern(&mut self, name: &str) -> ActorId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = ActorId(self.names.len() as u32);
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    /// Handle for a name, if interned.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<ActorId> {
+        self.ids.get(name).copied()
+    }
+
+    /// Name for a handle.
+    #[inline]
+    pub fn name(&self, id: ActorId) -> Option<&str> {
+        self.names.get(id.index()).map(|s| s.as_str())
+    }
+
+    /// Number of interned actors.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// All handles in registration order.
+    pub fn ids(&self) -> impl Iterator<Item = ActorId> {
+        (0..self.names.len() as u32).map(ActorId)
+    }
+
+    /// All names in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|s| s.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning_is_stable() {
+        let mut interner = ActorInterner::new();
+        let usa = interner.intern("USA");
+        let rus = interner.intern("RUS");
+
+        assert_eq!(interner.intern("USA"), usa);
+        assert_eq!(interner.get("RUS"), Some(rus));
+        assert_eq!(interner.name(rus), Some("RUS"));
+        assert_eq!(interner.get("CHN"), None);
+        assert_eq!(dyad(rus, usa), dyad(usa, rus));
+    }
+}
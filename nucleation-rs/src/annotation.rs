@@ -0,0 +1,77 @@
+//! Annotations: named time markers on actors and dyads
+//!
+//! Analysts mark context events ("summit held", "sanctions imposed") at a
+//! timestamp on an actor or a dyad. Annotations are stored with the
+//! [`CompressionDynamicsModel`](crate::compression::CompressionDynamicsModel)
+//! and come back with Φ history, so plots and reports can show them:
+//!
+//! ```text
+//! Φ(USA, RUS)
+//!   │        ╭──╮
+//!   │   ╭────╯  ╰──╮
+//!   │───╯          ╰───
+//!   └──┬─────────┬──────▶ t
+//!      │         └ "talks resumed"   (dyad USA–RUS)
+//!      └ "sanctions imposed"         (actor RUS)
+//! ```
+//!
+//! A dyad's annotations include those of both its actors.
+
+use std::collections::VecDeque;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// What an annotation is attached to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AnnotationTarget {
+    Actor(String),
+    /// A dyad, in the order it was annotated
+    Dyad(String, String),
+}
+
+/// Named marker at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Annotation {
+    pub timestamp: f64,
+    pub label: String,
+    pub target: AnnotationTarget,
+}
+
+impl Annotation {
+    /// Whether the annotation is on `actor` itself.
+    pub fn is_on_actor(&self, actor: &str) -> bool {
+        matches!(&self.target, AnnotationTarget::Actor(a) if a == actor)
+    }
+
+    /// Whether the annotation is on the dyad or on either of its actors.
+    pub fn is_on_dyad(&self, actor_a: &str, actor_b: &str) -> bool {
+        match &self.target {
+            AnnotationTarget::Actor(a) => a == actor_a || a == actor_b,
+            AnnotationTarget::Dyad(x, y) => {
+                (x == actor_a && y == actor_b) || (x == actor_b && y == actor_a)
+            }
+        }
+    }
+}
+
+/// Φ history of a dyad with its annotations.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnnotatedPhiHistory {
+    /// `(timestamp, phi)` points, oldest first
+    pub points: Vec<(f64, f64)>,
+    /// Annotations on the dyad and its actors, oldest first
+    pub annotations: Vec<Annotation>,
+}
+
+impl AnnotatedPhiHistory {
+    pub(crate) fn new(points: &VecDeque<(f64, f64)>, annotations: Vec<Annotation>) -> Self {
+        Self {
+            points: points.iter().copied().collect(),
+            annotations,
+        }
+    }
+}
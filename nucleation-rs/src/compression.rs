@@ -9,29 +9,26 @@
 //! Where C_A and C_B are probability distributions encoding how actors
 //! compress world-states into meaningful categories.
 
+use crate::actor::{dyad, ActorId, ActorInterner};
+use crate::annotation::{AnnotatedPhiHistory, Annotation, AnnotationTarget};
 use crate::distance::{hellinger_distance, jensen_shannon_divergence};
-use crate::entropy::kl_divergence;
-use std::collections::HashMap;
+use crate::entropy::{capped_kl_divergence, kl_divergence, trimmed_kl_divergence};
+use std::collections::{HashMap, VecDeque};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Source of compression scheme data
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SchemeSource {
     Text,
     Events,
     Hybrid,
+    #[default]
     Manual,
 }
 
-impl Default for SchemeSource {
-    fn default() -> Self {
-        Self::Manual
-    }
-}
-
 /// An actor's compression scheme - their probability distribution over world-states.
 ///
 /// The scheme captures HOW an actor "compresses" the world into meaningful
@@ -44,6 +41,19 @@ pub struct CompressionScheme {
     pub categories: Vec<String>,
     pub timestamp: f64,
     pub source: SchemeSource,
+    /// Number of observations folded in via `update`
+    #[cfg_attr(feature = "serde", serde(default))]
+    observations: u64,
+    /// Share of the EMA weight carried by observations rather than the
+    /// initial distribution: 1 - Π(1 - η_t). Snapshots saved before it
+    /// existed load as fully reliable, matching their pre-reliability alerts.
+    #[cfg_attr(feature = "serde", serde(default = "full_reliability"))]
+    observed_weight: f64,
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn full_reliability() -> f64 {
+    1.0
 }
 
 impl CompressionScheme {
@@ -64,6 +74,8 @@ impl CompressionScheme {
             categories: cats,
             timestamp: 0.0,
             source: SchemeSource::default(),
+            observations: 0,
+            observed_weight: 0.0,
         };
         scheme.normalize();
         scheme.smooth(1e-8);
@@ -104,6 +116,21 @@ impl CompressionScheme {
         &self.distribution
     }
 
+    /// Number of observations this scheme has been updated with.
+    pub fn observation_count(&self) -> u64 {
+        self.observations
+    }
+
+    /// How much of the scheme is backed by observations (0-1).
+    ///
+    /// After `t` EMA updates at rate η the initial distribution still
+    /// carries weight (1 - η)^t; reliability is the remainder. A freshly
+    /// registered actor has reliability 0 however confident its initial
+    /// distribution looks.
+    pub fn reliability(&self) -> f64 {
+        self.observed_weight
+    }
+
     /// Number of categories.
     pub fn n_categories(&self) -> usize {
         self.distribution.len()
@@ -142,6 +169,11 @@ impl CompressionScheme {
         kl_divergence(&self.distribution, &other.distribution)
     }
 
+    /// KL divergence D_KL(self || other) under a robust variant.
+    pub fn kl_divergence_with(&self, other: &CompressionScheme, variant: KlVariant) -> f64 {
+        variant.divergence(&self.distribution, &other.distribution)
+    }
+
     /// Symmetric divergence (conflict potential).
     /// Φ(A,B) = D_KL(A||B) + D_KL(B||A)
     pub fn symmetric_divergence(&self, other: &CompressionScheme) -> f64 {
@@ -179,6 +211,10 @@ impl CompressionScheme {
         }
 
         self.normalize();
+
+        self.observations += 1;
+        let rate = learning_rate.clamp(0.0, 1.0);
+        self.observed_weight += rate * (1.0 - self.observed_weight);
     }
 
     /// Get top N categories by probability mass.
@@ -197,6 +233,32 @@ impl CompressionScheme {
     }
 }
 
+/// KL estimator used for Φ.
+/// Robust variants stop a single artifact category (e.g. a coding glitch
+/// that zeroes a category for one actor) from dominating the divergence.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum KlVariant {
+    /// Plain D_KL
+    #[default]
+    Standard,
+    /// Each category contributes at most `cap` nats
+    Capped { cap: f64 },
+    /// The `top_k` largest category contributions are dropped
+    Trimmed { top_k: usize },
+}
+
+impl KlVariant {
+    /// D_KL(p || q) under this variant.
+    pub fn divergence(&self, p: &[f64], q: &[f64]) -> f64 {
+        match *self {
+            KlVariant::Standard => kl_divergence(p, q),
+            KlVariant::Capped { cap } => capped_kl_divergence(p, q, cap),
+            KlVariant::Trimmed { top_k } => trimmed_kl_divergence(p, q, top_k),
+        }
+    }
+}
+
 /// Computed conflict potential between two actors.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -214,13 +276,26 @@ pub struct ConflictPotential {
     /// D_KL(B || A)
     pub kl_b_a: f64,
     pub timestamp: f64,
+    /// Product of both schemes' reliabilities (0-1); low when either
+    /// actor has seen few observations
+    #[cfg_attr(feature = "serde", serde(default = "full_reliability"))]
+    pub reliability: f64,
 }
 
 impl ConflictPotential {
     /// Compute conflict potential between two schemes.
     pub fn compute(scheme_a: &CompressionScheme, scheme_b: &CompressionScheme) -> Self {
-        let kl_a_b = scheme_a.kl_divergence(scheme_b);
-        let kl_b_a = scheme_b.kl_divergence(scheme_a);
+        Self::compute_with(scheme_a, scheme_b, KlVariant::Standard)
+    }
+
+    /// Compute conflict potential with a robust KL variant for Φ.
+    pub fn compute_with(
+        scheme_a: &CompressionScheme,
+        scheme_b: &CompressionScheme,
+        variant: KlVariant,
+    ) -> Self {
+        let kl_a_b = scheme_a.kl_divergence_with(scheme_b, variant);
+        let kl_b_a = scheme_b.kl_divergence_with(scheme_a, variant);
 
         Self {
             actor_a: scheme_a.actor_id.clone(),
@@ -231,6 +306,7 @@ impl ConflictPotential {
             kl_a_b,
             kl_b_a,
             timestamp: scheme_a.timestamp.max(scheme_b.timestamp),
+            reliability: scheme_a.reliability() * scheme_b.reliability(),
         }
     }
 
@@ -273,7 +349,7 @@ pub struct Grievance {
     pub actor_id: String,
     pub cumulative_error: f64,
     pub window_error: f64,
-    error_history: Vec<f64>,
+    error_history: VecDeque<f64>,
     window_size: usize,
 }
 
@@ -283,7 +359,7 @@ impl Grievance {
             actor_id: actor_id.into(),
             cumulative_error: 0.0,
             window_error: 0.0,
-            error_history: Vec::with_capacity(window_size),
+            error_history: VecDeque::with_capacity(window_size + 1),
             window_size,
         }
     }
@@ -291,11 +367,11 @@ impl Grievance {
     /// Update grievance with new prediction error.
     pub fn update(&mut self, prediction_error: f64) {
         self.cumulative_error += prediction_error;
-        self.error_history.push(prediction_error);
+        self.error_history.push_back(prediction_error);
 
         // Maintain window
         if self.error_history.len() > self.window_size {
-            self.error_history.remove(0);
+            self.error_history.pop_front();
         }
 
         // Compute windowed error
@@ -305,6 +381,11 @@ impl Grievance {
         }
     }
 
+    /// Number of recent errors averaged into `window_error`.
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
     pub fn reset(&mut self) {
         self.cumulative_error = 0.0;
         self.window_error = 0.0;
@@ -312,6 +393,12 @@ impl Grievance {
     }
 }
 
+/// Default number of (timestamp, phi) samples retained per dyad.
+pub const DEFAULT_PHI_HISTORY_CAPACITY: usize = 1000;
+
+/// Default grievance averaging window.
+pub const DEFAULT_GRIEVANCE_WINDOW: usize = 30;
+
 /// Main compression dynamics model.
 /// Tracks actor schemes over time and computes conflict potentials.
 #[derive(Debug)]
@@ -319,10 +406,19 @@ impl Grievance {
 pub struct CompressionDynamicsModel {
     pub n_categories: usize,
     pub learning_rate: f64,
+    actor_ids: ActorInterner,
     schemes: HashMap<String, CompressionScheme>,
     grievances: HashMap<String, Grievance>,
     potential_history: Vec<ConflictPotential>,
-    phi_history: HashMap<(String, String), Vec<(f64, f64)>>, // (timestamp, phi)
+    phi_history: HashMap<(ActorId, ActorId), VecDeque<(f64, f64)>>, // (timestamp, phi)
+    phi_history_capacity: usize,
+    grievance_window: usize,
+    /// Context markers, ordered by timestamp
+    #[cfg_attr(feature = "serde", serde(default))]
+    annotations: Vec<Annotation>,
+    /// KL estimator used for Φ
+    #[cfg_attr(feature = "serde", serde(default))]
+    kl_variant: KlVariant,
 }
 
 impl CompressionDynamicsModel {
@@ -330,10 +426,15 @@ impl CompressionDynamicsModel {
         Self {
             n_categories,
             learning_rate: 0.1,
+            actor_ids: ActorInterner::new(),
             schemes: HashMap::new(),
             grievances: HashMap::new(),
             potential_history: Vec::new(),
             phi_history: HashMap::new(),
+            phi_history_capacity: DEFAULT_PHI_HISTORY_CAPACITY,
+            grievance_window: DEFAULT_GRIEVANCE_WINDOW,
+            annotations: Vec::new(),
+            kl_variant: KlVariant::Standard,
         }
     }
 
@@ -342,6 +443,29 @@ impl CompressionDynamicsModel {
         self
     }
 
+    /// Use a robust KL variant when computing Φ.
+    pub fn with_kl_variant(mut self, variant: KlVariant) -> Self {
+        self.kl_variant = variant;
+        self
+    }
+
+    /// KL estimator used for Φ.
+    pub fn kl_variant(&self) -> KlVariant {
+        self.kl_variant
+    }
+
+    /// Set how many phi samples are retained per dyad (oldest dropped first).
+    pub fn with_phi_history_capacity(mut self, capacity: usize) -> Self {
+        self.phi_history_capacity = capacity.max(1);
+        self
+    }
+
+    /// Set the grievance averaging window for actors registered afterwards.
+    pub fn with_grievance_window(mut self, window: usize) -> Self {
+        self.grievance_window = window.max(1);
+        self
+    }
+
     /// Register a new actor with initial distribution.
     pub fn register_actor(
         &mut self,
@@ -354,7 +478,8 @@ impl CompressionDynamicsModel {
         });
 
         let scheme = CompressionScheme::new(id.clone(), dist, None);
-        self.grievances.insert(id.clone(), Grievance::new(id.clone(), 30));
+        self.actor_ids.intern(&id);
+        self.grievances.insert(id.clone(), Grievance::new(id.clone(), self.grievance_window));
         self.schemes.insert(id.clone(), scheme);
         self.schemes.get(&id).unwrap()
     }
@@ -399,19 +524,46 @@ impl CompressionDynamicsModel {
         self.grievances.get(actor_id)
     }
 
+    /// Handle for a registered actor.
+    pub fn actor_id(&self, actor_id: &str) -> Option<ActorId> {
+        self.actor_ids.get(actor_id)
+    }
+
+    /// Actor name for a handle.
+    pub fn actor_name(&self, id: ActorId) -> Option<&str> {
+        self.actor_ids.name(id)
+    }
+
+    /// Number of registered actors.
+    pub fn actor_count(&self) -> usize {
+        self.actor_ids.len()
+    }
+
+    /// Handles of all registered actors, in registration order.
+    pub fn actor_ids(&self) -> impl Iterator<Item = ActorId> {
+        self.actor_ids.ids()
+    }
+
     /// Compute conflict potential between two actors.
     pub fn conflict_potential(&mut self, actor_a: &str, actor_b: &str) -> Option<ConflictPotential> {
-        let scheme_a = self.schemes.get(actor_a)?;
-        let scheme_b = self.schemes.get(actor_b)?;
+        let a = self.actor_ids.get(actor_a)?;
+        let b = self.actor_ids.get(actor_b)?;
+        self.conflict_potential_by_id(a, b)
+    }
 
-        let potential = ConflictPotential::compute(scheme_a, scheme_b);
+    /// Compute conflict potential between two actor handles.
+    pub fn conflict_potential_by_id(&mut self, a: ActorId, b: ActorId) -> Option<ConflictPotential> {
+        let scheme_a = self.schemes.get(self.actor_ids.name(a)?)?;
+        let scheme_b = self.schemes.get(self.actor_ids.name(b)?)?;
+
+        let potential = ConflictPotential::compute_with(scheme_a, scheme_b, self.kl_variant);
 
         // Store in history
-        let key = Self::dyad_key(actor_a, actor_b);
-        self.phi_history
-            .entry(key)
-            .or_insert_with(Vec::new)
-            .push((potential.timestamp, potential.phi));
+        let history = self.phi_history.entry(dyad(a, b)).or_default();
+        history.push_back((potential.timestamp, potential.phi));
+        if history.len() > self.phi_history_capacity {
+            history.pop_front();
+        }
 
         self.potential_history.push(potential.clone());
 
@@ -419,24 +571,109 @@ impl CompressionDynamicsModel {
     }
 
     /// Get phi history for a dyad.
-    pub fn phi_history(&self, actor_a: &str, actor_b: &str) -> Option<&Vec<(f64, f64)>> {
-        let key = Self::dyad_key(actor_a, actor_b);
-        self.phi_history.get(&key)
+    pub fn phi_history(&self, actor_a: &str, actor_b: &str) -> Option<&VecDeque<(f64, f64)>> {
+        let a = self.actor_ids.get(actor_a)?;
+        let b = self.actor_ids.get(actor_b)?;
+        self.phi_history_by_id(a, b)
     }
 
-    /// Get all registered actor IDs.
+    /// Get phi history for a dyad of actor handles.
+    pub fn phi_history_by_id(&self, a: ActorId, b: ActorId) -> Option<&VecDeque<(f64, f64)>> {
+        self.phi_history.get(&dyad(a, b))
+    }
+
+    /// Merge every dyad's Φ points older than `cutoff` into per-bucket means.
+    ///
+    /// Returns the number of points removed. See [`merge_phi_points`].
+    pub fn compact_phi_history(&mut self, cutoff: f64, bucket_width: f64) -> usize {
+        self.phi_history
+            .values_mut()
+            .map(|history| merge_phi_points(history, cutoff, bucket_width))
+            .sum()
+    }
+
+    /// Mark a context event on an actor; `false` if the actor is unknown.
+    pub fn annotate_actor(&mut self, actor_id: &str, timestamp: f64, label: impl Into<String>) -> bool {
+        if self.actor_ids.get(actor_id).is_none() {
+            return false;
+        }
+        self.insert_annotation(Annotation {
+            timestamp,
+            label: label.into(),
+            target: AnnotationTarget::Actor(actor_id.to_string()),
+        });
+        true
+    }
+
+    /// Mark a context event on a dyad; `false` if either actor is unknown.
+    pub fn annotate_dyad(
+        &mut self,
+        actor_a: &str,
+        actor_b: &str,
+        timestamp: f64,
+        label: impl Into<String>,
+    ) -> bool {
+        if self.actor_ids.get(actor_a).is_none() || self.actor_ids.get(actor_b).is_none() {
+            return false;
+        }
+        self.insert_annotation(Annotation {
+            timestamp,
+            label: label.into(),
+            target: AnnotationTarget::Dyad(actor_a.to_string(), actor_b.to_string()),
+        });
+        true
+    }
+
+    fn insert_annotation(&mut self, annotation: Annotation) {
+        let at = self.annotations.partition_point(|a| a.timestamp <= annotation.timestamp);
+        self.annotations.insert(at, annotation);
+    }
+
+    /// Remove every annotation with `label`, returning how many were removed.
+    pub fn remove_annotations(&mut self, label: &str) -> usize {
+        let before = self.annotations.len();
+        self.annotations.retain(|a| a.label != label);
+        before - self.annotations.len()
+    }
+
+    /// All annotations, oldest first.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Annotations on an actor itself, oldest first.
+    pub fn actor_annotations(&self, actor_id: &str) -> Vec<&Annotation> {
+        self.annotations.iter().filter(|a| a.is_on_actor(actor_id)).collect()
+    }
+
+    /// Annotations on a dyad and on either of its actors, oldest first.
+    pub fn dyad_annotations(&self, actor_a: &str, actor_b: &str) -> Vec<&Annotation> {
+        self.annotations
+            .iter()
+            .filter(|a| a.is_on_dyad(actor_a, actor_b))
+            .collect()
+    }
+
+    /// Phi history for a dyad together with its annotations.
+    pub fn annotated_phi_history(&self, actor_a: &str, actor_b: &str) -> Option<AnnotatedPhiHistory> {
+        let history = self.phi_history(actor_a, actor_b)?;
+        let annotations = self.dyad_annotations(actor_a, actor_b).into_iter().cloned().collect();
+        Some(AnnotatedPhiHistory::new(history, annotations))
+    }
+
+    /// Get all registered actor IDs, in registration order.
     pub fn actors(&self) -> Vec<&str> {
-        self.schemes.keys().map(|s| s.as_str()).collect()
+        self.actor_ids.names().collect()
     }
 
     /// Compute pairwise potentials for all actors.
     pub fn all_potentials(&mut self) -> Vec<ConflictPotential> {
-        let actors: Vec<String> = self.schemes.keys().cloned().collect();
+        let n = self.actor_ids.len() as u32;
         let mut results = Vec::new();
 
-        for i in 0..actors.len() {
-            for j in (i + 1)..actors.len() {
-                if let Some(p) = self.conflict_potential(&actors[i], &actors[j]) {
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if let Some(p) = self.conflict_potential_by_id(ActorId(i), ActorId(j)) {
                     results.push(p);
                 }
             }
@@ -444,14 +681,40 @@ impl CompressionDynamicsModel {
 
         results
     }
+}
 
-    fn dyad_key(a: &str, b: &str) -> (String, String) {
-        if a < b {
-            (a.to_string(), b.to_string())
-        } else {
-            (b.to_string(), a.to_string())
+/// Merge `(timestamp, phi)` points older than `cutoff` into one mean point
+/// per `bucket_width`-wide time bucket.
+///
+/// Buckets are aligned to multiples of `bucket_width`, so repeated passes
+/// leave earlier aggregates in place; an aggregate merged again counts as a
+/// single point. Returns the number of points removed (0 for a non-positive
+/// width).
+pub(crate) fn merge_phi_points(history: &mut VecDeque<(f64, f64)>, cutoff: f64, bucket_width: f64) -> usize {
+    if !(bucket_width > 0.0 && bucket_width.is_finite()) {
+        return 0;
+    }
+    let n_old = history.iter().take_while(|&&(t, _)| t < cutoff).count();
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    let mut bucket = None;
+    let mut count = 0.0;
+    for (t, phi) in history.drain(..n_old) {
+        let key = (t / bucket_width).floor();
+        if bucket != Some(key) {
+            bucket = Some(key);
+            count = 0.0;
+            merged.push((0.0, 0.0));
         }
+        count += 1.0;
+        let last = merged.last_mut().unwrap();
+        last.0 += (t - last.0) / count;
+        last.1 += (phi - last.1) / count;
     }
+    let removed = n_old - merged.len();
+    for point in merged.into_iter().rev() {
+        history.push_front(point);
+    }
+    removed
 }
 
 #[cfg(test)]
@@ -510,6 +773,27 @@ mod tests {
         assert!(potential.phi > 0.0);
     }
 
+    #[test]
+    fn test_reliability_grows_with_observations() {
+        let mut a = CompressionScheme::new("A", vec![0.9, 0.1], None);
+        let mut b = CompressionScheme::new("B", vec![0.1, 0.9], None);
+        assert_eq!(ConflictPotential::compute(&a, &b).reliability, 0.0);
+
+        for _ in 0..10 {
+            a.update(&[0.9, 0.1], 0.1);
+            b.update(&[0.1, 0.9], 0.1);
+        }
+        assert_eq!(a.observation_count(), 10);
+        assert!((a.reliability() - (1.0 - 0.9f64.powi(10))).abs() < 1e-12);
+
+        let potential = ConflictPotential::compute(&a, &b);
+        assert!((potential.reliability - a.reliability() * b.reliability()).abs() < 1e-12);
+
+        // Mismatched observations are ignored and not counted
+        a.update(&[1.0], 0.1);
+        assert_eq!(a.observation_count(), 10);
+    }
+
     #[test]
     fn test_scheme_update() {
         let mut scheme = CompressionScheme::new("A", vec![0.5, 0.5], None);
@@ -519,4 +803,52 @@ mod tests {
         assert!(scheme.distribution()[0] > 0.5);
         assert!(scheme.distribution()[1] < 0.5);
     }
+
+    #[test]
+    fn test_annotations_returned_with_phi_history() {
+        let mut model = CompressionDynamicsModel::new(2);
+        model.register_actor("USA", Some(vec![0.7, 0.3]));
+        model.register_actor("RUS", Some(vec![0.3, 0.7]));
+        model.register_actor("CHN", None);
+        model.conflict_potential("USA", "RUS");
+
+        assert!(model.annotate_dyad("RUS", "USA", 5.0, "talks resumed"));
+        assert!(model.annotate_actor("RUS", 2.0, "sanctions imposed"));
+        assert!(model.annotate_actor("CHN", 3.0, "plenum"));
+        assert!(!model.annotate_actor("XYZ", 1.0, "unknown actor"));
+
+        let history = model.annotated_phi_history("USA", "RUS").unwrap();
+        assert_eq!(history.points.len(), 1);
+        let labels: Vec<&str> = history.annotations.iter().map(|a| a.label.as_str()).collect();
+        assert_eq!(labels, ["sanctions imposed", "talks resumed"]);
+        assert_eq!(model.actor_annotations("RUS").len(), 1);
+        assert!(model.annotated_phi_history("USA", "CHN").is_none());
+
+        assert_eq!(model.remove_annotations("plenum"), 1);
+        assert_eq!(model.annotations().len(), 2);
+    }
+
+    #[test]
+    fn test_robust_kl_variants() {
+        // Identical but for one category A barely uses
+        let a = CompressionScheme::new("A", vec![0.4, 0.3, 0.3, 0.0001], None);
+        let b = CompressionScheme::new("B", vec![0.3, 0.3, 0.3, 0.1], None);
+
+        let standard = ConflictPotential::compute(&a, &b).phi;
+        let capped = ConflictPotential::compute_with(&a, &b, KlVariant::Capped { cap: 0.1 }).phi;
+        let trimmed = ConflictPotential::compute_with(&a, &b, KlVariant::Trimmed { top_k: 1 }).phi;
+        assert!(capped < standard);
+        assert!(trimmed < capped);
+        assert!(b.kl_divergence_with(&a, KlVariant::Capped { cap: 0.1 }) <= 0.4);
+
+        // The model computes Φ with its configured variant
+        let phi = |variant: KlVariant| {
+            let mut model = CompressionDynamicsModel::new(4).with_kl_variant(variant);
+            model.register_actor("A", Some(a.distribution.clone()));
+            model.register_actor("B", Some(b.distribution.clone()));
+            model.conflict_potential("A", "B").unwrap().phi
+        };
+        assert!((phi(KlVariant::Standard) - standard).abs() < 1e-4);
+        assert!((phi(KlVariant::Trimmed { top_k: 1 }) - trimmed).abs() < 1e-4);
+    }
 }
@@ -0,0 +1,234 @@
+//! Display helpers: presentation mapping shared by every front-end
+//!
+//! Each UI used to map alert levels to colors, icons and toast styles on
+//! its own, and the mappings drifted. The canonical one lives here:
+//!
+//! ```text
+//! Green   #2e7d32  🟢  success
+//! Yellow  #f9a825  🟡  info
+//! Orange  #ef6c00  🟠  warning
+//! Red     #c62828  🔴  error
+//! ```
+//!
+//! [`AlertCard`] is the compact alert shape for dashboards and toasts, the
+//! same from server code and from the wasm bindings. Its JSON keys are
+//! camelCase to match the rest of the JS API.
+
+use crate::monitor::SignalAlert;
+use crate::shepherd::{ActorAlert, AlertLevel, NucleationAlert};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Toast style for an alert level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ToastKind {
+    Success,
+    Info,
+    Warning,
+    Error,
+}
+
+/// Presentation attributes of an alert level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct LevelDisplay {
+    pub label: &'static str,
+    pub color_hex: &'static str,
+    pub emoji: &'static str,
+    pub toast: ToastKind,
+}
+
+impl AlertLevel {
+    /// Upper-case label, e.g. `RED`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertLevel::Green => "GREEN",
+            AlertLevel::Yellow => "YELLOW",
+            AlertLevel::Orange => "ORANGE",
+            AlertLevel::Red => "RED",
+        }
+    }
+
+    /// CSS color, `#rrggbb`.
+    pub fn color_hex(&self) -> &'static str {
+        match self {
+            AlertLevel::Green => "#2e7d32",
+            AlertLevel::Yellow => "#f9a825",
+            AlertLevel::Orange => "#ef6c00",
+            AlertLevel::Red => "#c62828",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            AlertLevel::Green => "🟢",
+            AlertLevel::Yellow => "🟡",
+            AlertLevel::Orange => "🟠",
+            AlertLevel::Red => "🔴",
+        }
+    }
+
+    pub fn toast(&self) -> ToastKind {
+        match self {
+            AlertLevel::Green => ToastKind::Success,
+            AlertLevel::Yellow => ToastKind::Info,
+            AlertLevel::Orange => ToastKind::Warning,
+            AlertLevel::Red => ToastKind::Error,
+        }
+    }
+
+    /// All presentation attributes at once.
+    pub fn to_display_struct(&self) -> LevelDisplay {
+        LevelDisplay {
+            label: self.label(),
+            color_hex: self.color_hex(),
+            emoji: self.emoji(),
+            toast: self.toast(),
+        }
+    }
+}
+
+/// What an alert card describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CardKind {
+    Dyad,
+    Actor,
+    Signal,
+}
+
+/// Compact alert for dashboards and toasts.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AlertCard {
+    /// Store ID of dyad alerts; 0 for actor and signal alerts
+    pub id: u64,
+    pub kind: CardKind,
+    /// `A–B` for dyads, the actor or the signal name otherwise
+    pub subject: String,
+    /// `emoji label · subject`, e.g. `🔴 RED · USA–RUS`
+    pub title: String,
+    pub message: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub display: LevelDisplay,
+    /// Φ for dyads, the signal value otherwise
+    pub value: f64,
+    pub trend: f64,
+    pub timestamp: f64,
+}
+
+impl AlertCard {
+    fn new(kind: CardKind, subject: String, level: AlertLevel, message: String) -> Self {
+        Self {
+            id: 0,
+            kind,
+            title: format!("{} {} · {}", level.emoji(), level.label(), subject),
+            subject,
+            message,
+            display: level.to_display_struct(),
+            value: 0.0,
+            trend: 0.0,
+            timestamp: 0.0,
+        }
+    }
+
+    /// Card JSON.
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> String {
+        // Plain strings and numbers; serialization cannot fail
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl NucleationAlert {
+    pub fn to_card(&self) -> AlertCard {
+        AlertCard {
+            id: self.alert_id,
+            value: self.phi,
+            trend: self.phi_trend,
+            timestamp: self.timestamp,
+            ..AlertCard::new(
+                CardKind::Dyad,
+                format!("{}–{}", self.actor_a, self.actor_b),
+                self.alert_level,
+                self.message.clone(),
+            )
+        }
+    }
+}
+
+impl ActorAlert {
+    pub fn to_card(&self) -> AlertCard {
+        AlertCard {
+            value: self.value,
+            trend: self.trend,
+            timestamp: self.timestamp,
+            ..AlertCard::new(
+                CardKind::Actor,
+                self.actor.clone(),
+                self.alert_level,
+                self.message.clone(),
+            )
+        }
+    }
+}
+
+impl SignalAlert {
+    pub fn to_card(&self) -> AlertCard {
+        let message = format!(
+            "{}: {} {:?} ({:.3}, Δ={:+.3})",
+            self.alert_level.label(),
+            self.signal,
+            self.phase,
+            self.value,
+            self.trend
+        );
+        AlertCard {
+            value: self.value,
+            trend: self.trend,
+            timestamp: self.timestamp,
+            ..AlertCard::new(CardKind::Signal, self.signal.clone(), self.alert_level, message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shepherd::ShepherdDynamics;
+
+    #[test]
+    fn test_alert_cards() {
+        let display = AlertLevel::Orange.to_display_struct();
+        assert_eq!(display.color_hex, "#ef6c00");
+        assert_eq!(display.toast, ToastKind::Warning);
+
+        let mut shepherd = ShepherdDynamics::new(3);
+        shepherd.register_actor("USA", Some(vec![0.4, 0.3, 0.3]));
+        shepherd.register_actor("RUS", Some(vec![0.3, 0.4, 0.3]));
+        shepherd.check_dyad("USA", "RUS", 1.0);
+        let mut alert = shepherd.last_alert("USA", "RUS").unwrap().clone();
+        alert.alert_level = AlertLevel::Red;
+        alert.alert_id = 7;
+
+        let card = alert.to_card();
+        assert_eq!(card.title, "🔴 RED · USA–RUS");
+        assert_eq!(card.kind, CardKind::Dyad);
+        assert_eq!(card.value, alert.phi);
+
+        #[cfg(feature = "serde_json")]
+        {
+            let json: serde_json::Value = serde_json::from_str(&card.to_json()).unwrap();
+            assert_eq!(json["id"], 7);
+            assert_eq!(json["kind"], "dyad");
+            assert_eq!(json["colorHex"], "#c62828");
+            assert_eq!(json["toast"], "error");
+        }
+    }
+}
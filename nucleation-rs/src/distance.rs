@@ -2,11 +2,54 @@
 //!
 //! Implements Hellinger, Jensen-Shannon, and Fisher-Rao distances
 //! for measuring distributional shift in behavioral patterns.
+//!
+//! Each metric has a `try_*` variant returning [`DistanceError`] on
+//! malformed input; the plain versions panic instead.
+
+use std::fmt;
+
+/// Error from a `try_*` distance function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceError {
+    /// The two distributions have different lengths
+    LengthMismatch { p: usize, q: usize },
+}
+
+impl fmt::Display for DistanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DistanceError::LengthMismatch { p, q } => write!(
+                f,
+                "Distributions must have same length (got {} and {})",
+                p, q
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DistanceError {}
+
+/// Check that two distributions can be compared
+pub(crate) fn check_lengths(p: &[f64], q: &[f64]) -> Result<(), DistanceError> {
+    if p.len() == q.len() {
+        Ok(())
+    } else {
+        Err(DistanceError::LengthMismatch {
+            p: p.len(),
+            q: q.len(),
+        })
+    }
+}
 
 /// Hellinger distance: d_H(P, Q) = (1/sqrt(2)) * sqrt(sum((sqrt(p) - sqrt(q))^2))
 /// Range: [0, 1], where 0 = identical, 1 = disjoint support
 pub fn hellinger_distance(p: &[f64], q: &[f64]) -> f64 {
-    assert_eq!(p.len(), q.len(), "Distributions must have same length");
+    try_hellinger_distance(p, q).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`hellinger_distance`]
+pub fn try_hellinger_distance(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    check_lengths(p, q)?;
 
     let sum_sq: f64 = p
         .iter()
@@ -17,14 +60,19 @@ pub fn hellinger_distance(p: &[f64], q: &[f64]) -> f64 {
         })
         .sum();
 
-    (sum_sq / 2.0).sqrt()
+    Ok((sum_sq / 2.0).sqrt())
 }
 
 /// Jensen-Shannon divergence: symmetric, bounded KL
 /// D_JS(P || Q) = 0.5 * D_KL(P || M) + 0.5 * D_KL(Q || M)
 /// where M = 0.5 * (P + Q)
 pub fn jensen_shannon_divergence(p: &[f64], q: &[f64]) -> f64 {
-    assert_eq!(p.len(), q.len(), "Distributions must have same length");
+    try_jensen_shannon_divergence(p, q).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`jensen_shannon_divergence`]
+pub fn try_jensen_shannon_divergence(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    check_lengths(p, q)?;
 
     // Compute mixture M
     let m: Vec<f64> = p.iter().zip(q.iter()).map(|(pi, qi)| 0.5 * (pi + qi)).collect();
@@ -33,7 +81,7 @@ pub fn jensen_shannon_divergence(p: &[f64], q: &[f64]) -> f64 {
     let kl_p_m = kl_divergence_internal(p, &m);
     let kl_q_m = kl_divergence_internal(q, &m);
 
-    0.5 * (kl_p_m + kl_q_m)
+    Ok(0.5 * (kl_p_m + kl_q_m))
 }
 
 fn kl_divergence_internal(p: &[f64], q: &[f64]) -> f64 {
@@ -51,10 +99,20 @@ pub fn jensen_shannon_distance(p: &[f64], q: &[f64]) -> f64 {
     jensen_shannon_divergence(p, q).sqrt()
 }
 
+/// Non-panicking [`jensen_shannon_distance`]
+pub fn try_jensen_shannon_distance(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    try_jensen_shannon_divergence(p, q).map(f64::sqrt)
+}
+
 /// Fisher-Rao distance (geodesic on probability simplex)
 /// d_FR(P, Q) = 2 * arccos(sum(sqrt(p * q)))
 pub fn fisher_rao_distance(p: &[f64], q: &[f64]) -> f64 {
-    assert_eq!(p.len(), q.len(), "Distributions must have same length");
+    try_fisher_rao_distance(p, q).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`fisher_rao_distance`]
+pub fn try_fisher_rao_distance(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    check_lengths(p, q)?;
 
     let bhattacharyya: f64 = p
         .iter()
@@ -64,46 +122,66 @@ pub fn fisher_rao_distance(p: &[f64], q: &[f64]) -> f64 {
 
     // Clamp to valid arccos domain
     let clamped = bhattacharyya.clamp(-1.0, 1.0);
-    2.0 * clamped.acos()
+    Ok(2.0 * clamped.acos())
 }
 
 /// Bhattacharyya coefficient: BC(P, Q) = sum(sqrt(p * q))
 /// Range: [0, 1], where 1 = identical
 pub fn bhattacharyya_coefficient(p: &[f64], q: &[f64]) -> f64 {
-    assert_eq!(p.len(), q.len(), "Distributions must have same length");
+    try_bhattacharyya_coefficient(p, q).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`bhattacharyya_coefficient`]
+pub fn try_bhattacharyya_coefficient(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    check_lengths(p, q)?;
 
-    p.iter()
+    Ok(p.iter()
         .zip(q.iter())
         .map(|(pi, qi)| (pi * qi).sqrt())
-        .sum()
+        .sum())
 }
 
 /// Bhattacharyya distance: -ln(BC)
 pub fn bhattacharyya_distance(p: &[f64], q: &[f64]) -> f64 {
-    let bc = bhattacharyya_coefficient(p, q);
+    try_bhattacharyya_distance(p, q).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`bhattacharyya_distance`]
+pub fn try_bhattacharyya_distance(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    let bc = try_bhattacharyya_coefficient(p, q)?;
     if bc <= 0.0 {
-        f64::INFINITY
+        Ok(f64::INFINITY)
     } else {
-        -bc.ln()
+        Ok(-bc.ln())
     }
 }
 
 /// Total variation distance: TV(P, Q) = 0.5 * sum(|p - q|)
 /// Range: [0, 1]
 pub fn total_variation_distance(p: &[f64], q: &[f64]) -> f64 {
-    assert_eq!(p.len(), q.len(), "Distributions must have same length");
+    try_total_variation_distance(p, q).expect("Distributions must have same length")
+}
 
-    0.5 * p
-        .iter()
-        .zip(q.iter())
-        .map(|(pi, qi)| (pi - qi).abs())
-        .sum::<f64>()
+/// Non-panicking [`total_variation_distance`]
+pub fn try_total_variation_distance(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    check_lengths(p, q)?;
+
+    Ok(0.5
+        * p.iter()
+            .zip(q.iter())
+            .map(|(pi, qi)| (pi - qi).abs())
+            .sum::<f64>())
 }
 
 /// Wasserstein-1 (Earth Mover's) distance for 1D distributions
 /// Assumes p and q are PMFs over ordered discrete support
 pub fn wasserstein_1d(p: &[f64], q: &[f64]) -> f64 {
-    assert_eq!(p.len(), q.len(), "Distributions must have same length");
+    try_wasserstein_1d(p, q).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`wasserstein_1d`]
+pub fn try_wasserstein_1d(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    check_lengths(p, q)?;
 
     // Compute CDFs
     let cdf_p: Vec<f64> = p
@@ -123,11 +201,11 @@ pub fn wasserstein_1d(p: &[f64], q: &[f64]) -> f64 {
         .collect();
 
     // EMD = integral of |CDF_P - CDF_Q|
-    cdf_p
+    Ok(cdf_p
         .iter()
         .zip(cdf_q.iter())
         .map(|(cp, cq)| (cp - cq).abs())
-        .sum()
+        .sum())
 }
 
 #[cfg(test)]
@@ -163,7 +241,7 @@ mod tests {
         let p = vec![0.5, 0.5];
         let q = vec![0.3, 0.7];
         let tv = total_variation_distance(&p, &q);
-        assert!(tv >= 0.0 && tv <= 1.0);
+        assert!((0.0..=1.0).contains(&tv));
     }
 
     #[test]
@@ -172,4 +250,24 @@ mod tests {
         let d = fisher_rao_distance(&p, &p);
         assert!(d.abs() < 1e-10);
     }
+
+    #[test]
+    fn test_try_variants_reject_length_mismatch() {
+        let p = vec![0.5, 0.5];
+        let q = vec![0.2, 0.3, 0.5];
+        let err = DistanceError::LengthMismatch { p: 2, q: 3 };
+        assert_eq!(try_hellinger_distance(&p, &q), Err(err));
+        assert_eq!(try_jensen_shannon_distance(&p, &q), Err(err));
+        assert_eq!(try_bhattacharyya_distance(&p, &q), Err(err));
+        assert_eq!(try_wasserstein_1d(&p, &q), Err(err));
+        assert_eq!(
+            err.to_string(),
+            "Distributions must have same length (got 2 and 3)"
+        );
+
+        assert_eq!(
+            try_total_variation_distance(&p, &[0.3, 0.7]),
+            Ok(total_variation_distance(&p, &[0.3, 0.7]))
+        );
+    }
 }
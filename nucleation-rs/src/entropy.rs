@@ -3,6 +3,7 @@
 //! Implements Shannon, permutation, and relative entropy measures
 //! calibrated for cognitive event detection.
 
+use crate::distance::{check_lengths, DistanceError};
 use std::collections::HashMap;
 
 /// Shannon entropy: H(X) = -sum(p(x) * log2(p(x)))
@@ -49,11 +50,69 @@ pub fn normalized_entropy(data: &[u32]) -> f64 {
 /// Permutation entropy for ordinal patterns
 /// Captures temporal structure in time series
 pub fn permutation_entropy(data: &[f64], order: usize, delay: usize) -> f64 {
-    if data.len() < order * delay {
+    ordinal_pattern_entropy(data, order, delay, false)
+}
+
+/// Permutation entropy divided by its maximum log2(order!), in [0, 1]
+///
+/// Comparable across windows and embedding orders.
+pub fn normalized_permutation_entropy(data: &[f64], order: usize, delay: usize) -> f64 {
+    let max = max_permutation_entropy(order);
+    if max > 0.0 {
+        permutation_entropy(data, order, delay) / max
+    } else {
+        0.0
+    }
+}
+
+/// Weighted permutation entropy (Fadlallah et al., 2013)
+///
+/// Each ordinal pattern counts with the variance of its embedding vector,
+/// so small fluctuations around a plateau weigh less than large swings.
+/// In bits; divide by [`max_permutation_entropy`] to normalize.
+pub fn weighted_permutation_entropy(data: &[f64], order: usize, delay: usize) -> f64 {
+    ordinal_pattern_entropy(data, order, delay, true)
+}
+
+/// Largest possible permutation entropy for an order: log2(order!)
+pub fn max_permutation_entropy(order: usize) -> f64 {
+    (2..=order).map(|k| (k as f64).log2()).sum()
+}
+
+/// Embedding delay at the first zero of the autocorrelation function
+///
+/// Returns the smallest lag in `1..=max_lag` at which the autocorrelation
+/// drops to zero or below, the usual choice of delay for ordinal
+/// embeddings. Falls back to 1 when it stays positive throughout or the
+/// series is constant.
+pub fn auto_delay(data: &[f64], max_lag: usize) -> usize {
+    let n = data.len();
+    if n < 2 {
+        return 1;
+    }
+    let mean = data.iter().sum::<f64>() / n as f64;
+    let variance: f64 = data.iter().map(|x| (x - mean).powi(2)).sum();
+    if variance <= 0.0 {
+        return 1;
+    }
+    (1..=max_lag.min(n - 1))
+        .find(|&lag| {
+            let covariance: f64 = data[..n - lag]
+                .iter()
+                .zip(&data[lag..])
+                .map(|(a, b)| (a - mean) * (b - mean))
+                .sum();
+            covariance / variance <= 0.0
+        })
+        .unwrap_or(1)
+}
+
+fn ordinal_pattern_entropy(data: &[f64], order: usize, delay: usize, weighted: bool) -> f64 {
+    if order < 2 || data.len() < order * delay {
         return 0.0;
     }
 
-    let mut pattern_counts: HashMap<Vec<usize>, usize> = HashMap::new();
+    let mut pattern_weights: HashMap<Vec<usize>, f64> = HashMap::new();
     let n_patterns = data.len() - (order - 1) * delay;
 
     for i in 0..n_patterns {
@@ -62,20 +121,30 @@ pub fn permutation_entropy(data: &[f64], order: usize, delay: usize) -> f64 {
             .map(|j| (j, data[i + j * delay]))
             .collect();
 
+        let weight = if weighted {
+            let mean = indices.iter().map(|(_, x)| x).sum::<f64>() / order as f64;
+            indices.iter().map(|(_, x)| (x - mean).powi(2)).sum::<f64>() / order as f64
+        } else {
+            1.0
+        };
+
         // Sort by value to get ordinal pattern
         indices.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
 
         let pattern: Vec<usize> = indices.iter().map(|(idx, _)| *idx).collect();
-        *pattern_counts.entry(pattern).or_insert(0) += 1;
+        *pattern_weights.entry(pattern).or_insert(0.0) += weight;
     }
 
     // Compute entropy of pattern distribution
-    let total = n_patterns as f64;
+    let total: f64 = pattern_weights.values().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
     let mut entropy = 0.0;
 
-    for &count in pattern_counts.values() {
-        if count > 0 {
-            let p = count as f64 / total;
+    for &weight in pattern_weights.values() {
+        if weight > 0.0 {
+            let p = weight / total;
             entropy -= p * p.log2();
         }
     }
@@ -83,10 +152,60 @@ pub fn permutation_entropy(data: &[f64], order: usize, delay: usize) -> f64 {
     entropy
 }
 
+/// Order and delay of an ordinal embedding, with presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrdinalEmbedding {
+    pub order: usize,
+    pub delay: usize,
+}
+
+impl OrdinalEmbedding {
+    /// Order 3: 6 patterns, stable on short windows (tens of samples)
+    pub const SHORT: Self = Self { order: 3, delay: 1 };
+    /// Order 4: 24 patterns, for windows of a few hundred samples
+    pub const STANDARD: Self = Self { order: 4, delay: 1 };
+    /// Order 5: 120 patterns, for long windows (thousands of samples)
+    pub const DETAILED: Self = Self { order: 5, delay: 1 };
+
+    pub fn new(order: usize, delay: usize) -> Self {
+        Self { order, delay: delay.max(1) }
+    }
+
+    /// Same order, delay chosen by [`auto_delay`] for `data`
+    pub fn with_auto_delay(self, data: &[f64], max_lag: usize) -> Self {
+        Self { delay: auto_delay(data, max_lag), ..self }
+    }
+
+    /// log2(order!)
+    pub fn max_entropy(&self) -> f64 {
+        max_permutation_entropy(self.order)
+    }
+
+    /// Permutation entropy in bits
+    pub fn entropy(&self, data: &[f64]) -> f64 {
+        permutation_entropy(data, self.order, self.delay)
+    }
+
+    /// Permutation entropy in [0, 1]
+    pub fn normalized_entropy(&self, data: &[f64]) -> f64 {
+        normalized_permutation_entropy(data, self.order, self.delay)
+    }
+
+    /// Weighted permutation entropy in bits
+    pub fn weighted_entropy(&self, data: &[f64]) -> f64 {
+        weighted_permutation_entropy(data, self.order, self.delay)
+    }
+}
+
 /// Relative entropy (KL divergence): D_KL(P || Q)
 /// Measures divergence from baseline distribution
 pub fn kl_divergence(p: &[f64], q: &[f64]) -> f64 {
-    assert_eq!(p.len(), q.len(), "Distributions must have same length");
+    try_kl_divergence(p, q).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`kl_divergence`]
+pub fn try_kl_divergence(p: &[f64], q: &[f64]) -> Result<f64, DistanceError> {
+    check_lengths(p, q)?;
 
     let mut divergence = 0.0;
     for (pi, qi) in p.iter().zip(q.iter()) {
@@ -95,7 +214,40 @@ pub fn kl_divergence(p: &[f64], q: &[f64]) -> f64 {
         }
     }
 
-    divergence
+    Ok(divergence)
+}
+
+/// Per-category KL terms p_i ln(p_i / q_i), zero where either side is.
+fn kl_terms(p: &[f64], q: &[f64]) -> Result<Vec<f64>, DistanceError> {
+    check_lengths(p, q)?;
+    Ok(p.iter()
+        .zip(q)
+        .map(|(&pi, &qi)| if pi > 0.0 && qi > 0.0 { pi * (pi / qi).ln() } else { 0.0 })
+        .collect())
+}
+
+/// KL divergence with each category's contribution capped at `cap`.
+/// Limits how far one artifact category can drive the total; floored at zero.
+pub fn capped_kl_divergence(p: &[f64], q: &[f64], cap: f64) -> f64 {
+    try_capped_kl_divergence(p, q, cap).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`capped_kl_divergence`]
+pub fn try_capped_kl_divergence(p: &[f64], q: &[f64], cap: f64) -> Result<f64, DistanceError> {
+    let total: f64 = kl_terms(p, q)?.into_iter().map(|t| t.min(cap)).sum();
+    Ok(total.max(0.0))
+}
+
+/// KL divergence ignoring the `k` categories contributing the most; floored at zero.
+pub fn trimmed_kl_divergence(p: &[f64], q: &[f64], k: usize) -> f64 {
+    try_trimmed_kl_divergence(p, q, k).expect("Distributions must have same length")
+}
+
+/// Non-panicking [`trimmed_kl_divergence`]
+pub fn try_trimmed_kl_divergence(p: &[f64], q: &[f64], k: usize) -> Result<f64, DistanceError> {
+    let mut terms = kl_terms(p, q)?;
+    terms.sort_by(|a, b| b.total_cmp(a));
+    Ok(terms.iter().skip(k).sum::<f64>().max(0.0))
 }
 
 /// Entropy rate estimation using block entropy
@@ -178,4 +330,35 @@ mod tests {
 
         assert!(h_mix > h_asc);
     }
+
+    #[test]
+    fn test_permutation_entropy_variants() {
+        assert!((max_permutation_entropy(3) - 6f64.log2()).abs() < 1e-12);
+        assert_eq!(max_permutation_entropy(1), 0.0);
+
+        // Alternating up/down steps use two of the six order-3 patterns equally
+        let zigzag: Vec<f64> = (0..40)
+            .map(|i| if i % 2 == 0 { 0.0 } else { 1.0 + i as f64 * 0.01 })
+            .collect();
+        let h = normalized_permutation_entropy(&zigzag, 3, 1);
+        assert!((h - 1.0 / 6f64.log2()).abs() < 0.05);
+        assert!((0.0..=1.0).contains(&h));
+
+        // Tiny wiggles on a rising trend barely count once weighted
+        let mut wiggly: Vec<f64> = (0..60).map(|i| (i / 6) as f64 * 10.0).collect();
+        for (i, x) in wiggly.iter_mut().enumerate() {
+            *x += [0.0, 0.001, -0.001][i % 3];
+        }
+        assert!(weighted_permutation_entropy(&wiggly, 3, 1) < permutation_entropy(&wiggly, 3, 1));
+
+        // A sine with period 22 decorrelates just after a quarter period
+        let sine: Vec<f64> = (0..200)
+            .map(|i| (i as f64 * std::f64::consts::TAU / 22.0).sin())
+            .collect();
+        assert_eq!(auto_delay(&sine, 50), 6);
+        assert_eq!(auto_delay(&[1.0; 10], 5), 1);
+        let embedding = OrdinalEmbedding::STANDARD.with_auto_delay(&sine, 50);
+        assert_eq!((embedding.order, embedding.delay), (4, 6));
+        assert!(embedding.normalized_entropy(&sine) < 1.0);
+    }
 }
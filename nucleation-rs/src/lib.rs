@@ -10,6 +10,7 @@
 //! - **Variance Inflection Detection**: Identify phase transitions via d²V/dt²
 //! - **Compression Dynamics**: KL-divergence framework for conflict modeling
 //! - **Shepherd Dynamics**: Unified early warning combining both approaches
+//! - **Regime Model**: HMM filtering of dyads into calm / tension / crisis
 //!
 //! ## Supporting Modules
 //!
@@ -118,15 +119,27 @@
 //!
 //! ## Crate Features
 //!
-//! - `std`: Standard library support (default)
+//! - `std`: Standard library support, including the threaded `ShepherdRunner` (default)
 //! - `wasm`: WASM-compatible builds with JS bindings
 //! - `serialize`: Serde serialization support
 //! - `simd`: SIMD optimizations (requires nightly)
 
 // Core modules
+pub mod actor;
+pub mod annotation;
 pub mod variance;
 pub mod compression;
 pub mod shepherd;
+pub mod regime;
+pub mod monitor;
+pub mod messages;
+pub mod display;
+pub mod watchlist;
+pub mod sandbox;
+#[cfg(feature = "std")]
+pub mod runner;
+#[cfg(feature = "std")]
+pub mod sweep;
 
 // Primitive modules
 pub mod entropy;
@@ -149,11 +162,23 @@ pub use variance::{
     InflectionResult,
 };
 
+pub use actor::{
+    ActorId,
+    ActorInterner,
+};
+
+pub use annotation::{
+    AnnotatedPhiHistory,
+    Annotation,
+    AnnotationTarget,
+};
+
 pub use compression::{
     CompressionScheme,
     CompressionDynamicsModel,
     ConflictPotential,
     Grievance,
+    KlVariant,
     SchemeSource,
 };
 
@@ -161,6 +186,83 @@ pub use shepherd::{
     ShepherdDynamics,
     NucleationAlert,
     AlertLevel,
+    AlertStatus,
+    RegimeChange,
+    ActorAlert,
+    ActorSignal,
+    AlertSnapshot,
+    RecordedObservation,
+    CompactionPolicy,
+    CompactionReport,
+};
+
+pub use monitor::{
+    SignalMonitor,
+    SignalMonitorConfig,
+    SignalSource,
+    SignalAlert,
+    AlertPolicy,
+    TrendDirection,
+};
+
+pub use messages::{
+    AlertMessage,
+    MessageArg,
+    MessageCatalog,
+    MessageRenderer,
+    render_english,
+};
+
+pub use display::{
+    AlertCard,
+    CardKind,
+    LevelDisplay,
+    ToastKind,
+};
+
+pub use watchlist::{
+    Watchlist,
+    WatchlistConfig,
+    NotificationRule,
+    Delivery,
+    Notification,
+};
+
+pub use sandbox::{
+    Sandbox,
+    SandboxConfig,
+    SandboxReport,
+    SandboxAgent,
+    AgentPolicy,
+    Intervention,
+};
+
+pub use regime::{
+    Regime,
+    RegimeModel,
+    RegimeFilter,
+    RegimeFeatures,
+};
+
+#[cfg(feature = "std")]
+pub use runner::{
+    ShepherdRunner,
+    RunnerConfig,
+    Observation,
+    AlertCallback,
+    NotificationCallback,
+};
+
+#[cfg(feature = "std")]
+pub use sweep::{
+    Sweep,
+    SweepParams,
+    SweepRow,
+    ParamGrid,
+    TruthEvent,
+    Scoring,
+    score as score_sweep_run,
+    replay as replay_observations,
 };
 
 // ============================================================================
@@ -171,7 +273,17 @@ pub use entropy::{
     shannon_entropy,
     normalized_entropy,
     permutation_entropy,
+    normalized_permutation_entropy,
+    weighted_permutation_entropy,
+    max_permutation_entropy,
+    auto_delay,
+    OrdinalEmbedding,
     kl_divergence,
+    try_kl_divergence,
+    capped_kl_divergence,
+    try_capped_kl_divergence,
+    trimmed_kl_divergence,
+    try_trimmed_kl_divergence,
     entropy_rate,
 };
 
@@ -184,6 +296,15 @@ pub use distance::{
     bhattacharyya_distance,
     total_variation_distance,
     wasserstein_1d,
+    try_hellinger_distance,
+    try_jensen_shannon_divergence,
+    try_jensen_shannon_distance,
+    try_fisher_rao_distance,
+    try_bhattacharyya_coefficient,
+    try_bhattacharyya_distance,
+    try_total_variation_distance,
+    try_wasserstein_1d,
+    DistanceError,
 };
 
 pub use signal::{
@@ -0,0 +1,284 @@
+//! Message Catalog: localizable alert text
+//!
+//! Alerts carry their text as a [`AlertMessage`] — a stable key plus named
+//! arguments — alongside the English rendering in `message`. Integrators
+//! localize by rendering the key through their own catalog instead of
+//! parsing the English string:
+//!
+//! ```text
+//! key:  dyad.elevated
+//! args: actor_a=USA, actor_b=RUS, phi=1.234, trend=@trend.increasing
+//! en:   "WATCH: {actor_a}-{actor_b} divergence elevated (Φ={phi:.2}, {trend})"
+//!       → WATCH: USA-RUS divergence elevated (Φ=1.23, increasing)
+//! ```
+//!
+//! Templates reference arguments as `{name}`, with an optional number
+//! format `{name:.2}` or `{name:+.3}`. Term arguments (enumerated words
+//! such as trends and phases) are catalog keys themselves, so they are
+//! translated too. Anything a catalog lacks falls back to English.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::shepherd::AlertLevel;
+use crate::variance::Phase;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Argument of a message.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MessageArg {
+    /// Verbatim text, e.g. an actor name
+    Text(String),
+    Number(f64),
+    /// Catalog key of a translatable word, e.g. `trend.increasing`
+    Term(String),
+}
+
+/// Alert text as a message key plus named arguments.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlertMessage {
+    pub key: String,
+    pub args: Vec<(String, MessageArg)>,
+}
+
+impl AlertMessage {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Add a text argument.
+    pub fn text(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.args.push((name.to_string(), MessageArg::Text(value.into())));
+        self
+    }
+
+    /// Add a number argument.
+    pub fn number(mut self, name: &str, value: f64) -> Self {
+        self.args.push((name.to_string(), MessageArg::Number(value)));
+        self
+    }
+
+    /// Add a term argument.
+    pub fn term(mut self, name: &str, key: impl Into<String>) -> Self {
+        self.args.push((name.to_string(), MessageArg::Term(key.into())));
+        self
+    }
+
+    /// Argument by name.
+    pub fn arg(&self, name: &str) -> Option<&MessageArg> {
+        self.args.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// Message for a dyad alert.
+    pub fn dyad(
+        actor_a: &str,
+        actor_b: &str,
+        level: AlertLevel,
+        phase: Phase,
+        phi: f64,
+        phi_trend: f64,
+    ) -> Self {
+        let key = match level {
+            AlertLevel::Red => "dyad.nucleation",
+            AlertLevel::Orange => "dyad.pre_transition",
+            AlertLevel::Yellow => "dyad.elevated",
+            AlertLevel::Green => "dyad.normal",
+        };
+        let trend = if phi_trend > 0.05 {
+            "trend.increasing"
+        } else if phi_trend < -0.05 {
+            "trend.decreasing"
+        } else {
+            "trend.stable"
+        };
+        Self::new(key)
+            .text("actor_a", actor_a)
+            .text("actor_b", actor_b)
+            .number("phi", phi)
+            .term("trend", trend)
+            .term("phase", phase_term(phase))
+    }
+}
+
+/// Catalog key of a detector phase.
+pub fn phase_term(phase: Phase) -> &'static str {
+    match phase {
+        Phase::Stable => "phase.stable",
+        Phase::Approaching => "phase.approaching",
+        Phase::Critical => "phase.critical",
+        Phase::Transitioning => "phase.transitioning",
+    }
+}
+
+/// Turns messages into display text.
+pub trait MessageRenderer {
+    fn render(&self, message: &AlertMessage) -> String;
+}
+
+/// English templates and terms.
+const ENGLISH: &[(&str, &str)] = &[
+    (
+        "dyad.nucleation",
+        "NUCLEATION ALERT: {actor_a}-{actor_b} divergence critical (Φ={phi:.2}, {trend}). Transition imminent.",
+    ),
+    (
+        "dyad.pre_transition",
+        "WARNING: {actor_a}-{actor_b} showing pre-transition signature (Φ={phi:.2}, {trend}, phase={phase})",
+    ),
+    ("dyad.elevated", "WATCH: {actor_a}-{actor_b} divergence elevated (Φ={phi:.2}, {trend})"),
+    ("dyad.normal", "{actor_a}-{actor_b} normal (Φ={phi:.2})"),
+    (
+        "actor.warning",
+        "WARNING: {actor} {signal} {direction} ({signal}={value:.3}, Δ={trend:+.3})",
+    ),
+    (
+        "actor.watch",
+        "WATCH: {actor} {signal} {direction} ({signal}={value:.3}, Δ={trend:+.3})",
+    ),
+    ("trend.increasing", "increasing"),
+    ("trend.decreasing", "decreasing"),
+    ("trend.stable", "stable"),
+    ("phase.stable", "Stable"),
+    ("phase.approaching", "Approaching"),
+    ("phase.critical", "Critical"),
+    ("phase.transitioning", "Transitioning"),
+    ("signal.grievance", "grievance"),
+    ("signal.entropy", "entropy"),
+    ("direction.accelerating", "accelerating"),
+    ("direction.collapsing", "collapsing"),
+    ("direction.dispersing", "dispersing"),
+];
+
+/// Template catalog keyed by message and term keys.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MessageCatalog {
+    entries: HashMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Catalog with no entries of its own; everything renders in English.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in English catalog.
+    pub fn english() -> Self {
+        Self {
+            entries: ENGLISH
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Set the template or term text for a key.
+    pub fn with_entry(mut self, key: impl Into<String>, text: impl Into<String>) -> Self {
+        self.entries.insert(key.into(), text.into());
+        self
+    }
+
+    /// Template or term text for a key, falling back to English.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .get(key)
+            .map(String::as_str)
+            .or_else(|| ENGLISH.iter().find(|(k, _)| *k == key).map(|(_, v)| *v))
+    }
+
+    fn render_arg(&self, arg: &MessageArg, spec: &str, out: &mut String) {
+        match arg {
+            MessageArg::Text(text) => out.push_str(text),
+            MessageArg::Term(key) => out.push_str(self.get(key).unwrap_or(key)),
+            MessageArg::Number(value) => {
+                let sign = spec.starts_with('+');
+                let precision = spec
+                    .trim_start_matches('+')
+                    .strip_prefix('.')
+                    .and_then(|p| p.parse::<usize>().ok());
+                // Writing to a String cannot fail
+                let _ = match (sign, precision) {
+                    (true, Some(p)) => write!(out, "{:+.*}", p, value),
+                    (false, Some(p)) => write!(out, "{:.*}", p, value),
+                    (true, None) => write!(out, "{:+}", value),
+                    (false, None) => write!(out, "{}", value),
+                };
+            }
+        }
+    }
+}
+
+impl MessageRenderer for MessageCatalog {
+    /// Fill the key's template; unknown keys render as the key itself and
+    /// unknown placeholders are left in place.
+    fn render(&self, message: &AlertMessage) -> String {
+        let Some(template) = self.get(&message.key) else {
+            return message.key.clone();
+        };
+        let mut out = String::with_capacity(template.len() + 16);
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('}') else {
+                rest = &rest[open..];
+                break;
+            };
+            let placeholder = &rest[open + 1..open + close];
+            let (name, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+            match message.arg(name) {
+                Some(arg) => self.render_arg(arg, spec, &mut out),
+                None => out.push_str(&rest[open..=open + close]),
+            }
+            rest = &rest[open + close + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Render a message with the built-in English catalog.
+pub fn render_english(message: &AlertMessage) -> String {
+    MessageCatalog::new().render(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_and_localized_rendering() {
+        let message = AlertMessage::dyad("USA", "RUS", AlertLevel::Orange, Phase::Critical, 1.234, 0.2);
+        assert_eq!(message.key, "dyad.pre_transition");
+        assert_eq!(
+            render_english(&message),
+            "WARNING: USA-RUS showing pre-transition signature (Φ=1.23, increasing, phase=Critical)"
+        );
+
+        let german = MessageCatalog::new()
+            .with_entry("dyad.pre_transition", "WARNUNG: {actor_a}–{actor_b} vor dem Übergang (Φ={phi:.1}, {trend}) {missing}")
+            .with_entry("trend.increasing", "steigend");
+        assert_eq!(
+            german.render(&message),
+            "WARNUNG: USA–RUS vor dem Übergang (Φ=1.2, steigend) {missing}"
+        );
+
+        let signed = AlertMessage::new("actor.watch")
+            .text("actor", "A")
+            .term("signal", "signal.entropy")
+            .term("direction", "direction.collapsing")
+            .number("value", 0.5)
+            .number("trend", -0.25);
+        assert_eq!(
+            MessageCatalog::english().render(&signed),
+            "WATCH: A entropy collapsing (entropy=0.500, Δ=-0.250)"
+        );
+        assert_eq!(german.render(&AlertMessage::new("no.such.key")), "no.such.key");
+    }
+}
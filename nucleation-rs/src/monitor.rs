@@ -0,0 +1,352 @@
+//! Signal Monitors: variance inflection detection over any model signal
+//!
+//! A dyad tracker watches one series, Φ(A,B). A [`SignalMonitor`] watches
+//! any named scalar series a model produces — Φ per dyad, entropy or
+//! grievance per actor, the polarization index — with its own detector
+//! settings and alert policy. Monitors are declared with plain,
+//! serializable [`SignalMonitorConfig`] values.
+//!
+//! Alert levels follow the dyad scheme, with an optional value threshold
+//! raising the level by one:
+//!
+//! ```text
+//! Stable → Green, Approaching → Yellow, Critical/Transitioning → Orange
+//! value ≥ threshold: one level higher
+//! trend against the policy direction: Green
+//! ```
+//!
+//! Polarization is the mean pairwise Φ over all registered actors.
+
+use std::collections::VecDeque;
+
+use crate::compression::{CompressionDynamicsModel, ConflictPotential};
+use crate::shepherd::AlertLevel;
+use crate::variance::{Phase, VarianceConfig, VarianceInflectionDetector};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default number of samples retained per monitor.
+pub const DEFAULT_SIGNAL_HISTORY: usize = 256;
+
+/// Scalar signal read from a model.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SignalSource {
+    /// Conflict potential between two actors
+    Phi { actor_a: String, actor_b: String },
+    /// Shannon entropy of an actor's scheme
+    Entropy { actor: String },
+    /// Windowed prediction error of an actor's scheme
+    Grievance { actor: String },
+    /// Mean pairwise Φ over all actors
+    Polarization,
+}
+
+impl SignalSource {
+    /// Stable signal name, e.g. `phi:USA-RUS` or `entropy:USA`.
+    pub fn name(&self) -> String {
+        match self {
+            SignalSource::Phi { actor_a, actor_b } => format!("phi:{}-{}", actor_a, actor_b),
+            SignalSource::Entropy { actor } => format!("entropy:{}", actor),
+            SignalSource::Grievance { actor } => format!("grievance:{}", actor),
+            SignalSource::Polarization => "polarization".to_string(),
+        }
+    }
+
+    /// Current value of the signal, if its actors are registered.
+    pub fn read(&self, model: &CompressionDynamicsModel) -> Option<f64> {
+        match self {
+            SignalSource::Phi { actor_a, actor_b } => {
+                let a = model.get_scheme(actor_a)?;
+                let b = model.get_scheme(actor_b)?;
+                Some(ConflictPotential::compute_with(a, b, model.kl_variant()).phi)
+            }
+            SignalSource::Entropy { actor } => model.get_scheme(actor).map(|s| s.entropy()),
+            SignalSource::Grievance { actor } => model.get_grievance(actor).map(|g| g.window_error),
+            SignalSource::Polarization => {
+                let actors = model.actors();
+                let mut total = 0.0;
+                let mut pairs = 0usize;
+                for i in 0..actors.len() {
+                    for j in (i + 1)..actors.len() {
+                        let a = model.get_scheme(actors[i])?;
+                        let b = model.get_scheme(actors[j])?;
+                        total += ConflictPotential::compute_with(a, b, model.kl_variant()).phi;
+                        pairs += 1;
+                    }
+                }
+                (pairs > 0).then(|| total / pairs as f64)
+            }
+        }
+    }
+}
+
+/// Direction of change that counts toward an alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TrendDirection {
+    Rising,
+    Falling,
+    #[default]
+    Either,
+}
+
+impl TrendDirection {
+    fn matches(&self, trend: f64) -> bool {
+        match self {
+            TrendDirection::Rising => trend > 0.0,
+            TrendDirection::Falling => trend < 0.0,
+            TrendDirection::Either => true,
+        }
+    }
+}
+
+/// How detector output becomes an alert.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlertPolicy {
+    /// Direction of change that may alert
+    pub direction: TrendDirection,
+    /// Value at or above which the alert level is raised by one
+    pub value_threshold: Option<f64>,
+    /// Lowest level reported
+    pub min_level: AlertLevel,
+    /// Samples the trend is measured over
+    pub trend_window: usize,
+}
+
+impl Default for AlertPolicy {
+    fn default() -> Self {
+        Self {
+            direction: TrendDirection::Either,
+            value_threshold: None,
+            min_level: AlertLevel::Yellow,
+            trend_window: 10,
+        }
+    }
+}
+
+impl AlertPolicy {
+    /// Policy alerting only on rising values.
+    pub fn rising() -> Self {
+        Self {
+            direction: TrendDirection::Rising,
+            ..Default::default()
+        }
+    }
+
+    /// Raise the level of alerts at or above `threshold`.
+    pub fn with_value_threshold(mut self, threshold: f64) -> Self {
+        self.value_threshold = Some(threshold);
+        self
+    }
+
+    /// Alert level for a detector phase, value and trend.
+    pub fn level(&self, phase: Phase, value: f64, trend: f64) -> AlertLevel {
+        if !self.direction.matches(trend) {
+            return AlertLevel::Green;
+        }
+        let level = match phase {
+            Phase::Stable => AlertLevel::Green,
+            Phase::Approaching => AlertLevel::Yellow,
+            Phase::Critical | Phase::Transitioning => AlertLevel::Orange,
+        };
+        match self.value_threshold {
+            Some(threshold) if value >= threshold => match level {
+                AlertLevel::Green => AlertLevel::Yellow,
+                AlertLevel::Yellow => AlertLevel::Orange,
+                AlertLevel::Orange | AlertLevel::Red => AlertLevel::Red,
+            },
+            _ => level,
+        }
+    }
+}
+
+/// Declarative monitor definition.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SignalMonitorConfig {
+    pub source: SignalSource,
+    pub variance: VarianceConfig,
+    pub policy: AlertPolicy,
+    /// Samples retained for `history`
+    pub history_capacity: usize,
+}
+
+impl SignalMonitorConfig {
+    /// Monitor a source with default detector and policy.
+    pub fn new(source: SignalSource) -> Self {
+        Self {
+            source,
+            variance: VarianceConfig::default(),
+            policy: AlertPolicy::default(),
+            history_capacity: DEFAULT_SIGNAL_HISTORY,
+        }
+    }
+
+    /// Configure variance detection sensitivity.
+    pub fn with_variance_config(mut self, config: VarianceConfig) -> Self {
+        self.variance = config;
+        self
+    }
+
+    /// Configure the alert policy.
+    pub fn with_policy(mut self, policy: AlertPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+/// Alert from a signal monitor.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SignalAlert {
+    pub signal: String,
+    pub alert_level: AlertLevel,
+    pub phase: Phase,
+    pub value: f64,
+    pub trend: f64,
+    pub confidence: f64,
+    pub timestamp: f64,
+}
+
+impl SignalAlert {
+    pub fn is_actionable(&self) -> bool {
+        self.alert_level >= AlertLevel::Orange
+    }
+}
+
+/// Variance inflection detector plus alert policy over one signal.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SignalMonitor {
+    config: SignalMonitorConfig,
+    name: String,
+    detector: VarianceInflectionDetector,
+    history: VecDeque<(f64, f64)>, // (timestamp, value)
+    last_alert: Option<SignalAlert>,
+}
+
+impl SignalMonitor {
+    /// Create a monitor from its definition.
+    pub fn new(config: SignalMonitorConfig) -> Self {
+        Self {
+            name: config.source.name(),
+            detector: VarianceInflectionDetector::new(config.variance.clone()),
+            history: VecDeque::with_capacity(config.history_capacity.min(64) + 1),
+            last_alert: None,
+            config,
+        }
+    }
+
+    /// Signal name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Monitor definition.
+    pub fn config(&self) -> &SignalMonitorConfig {
+        &self.config
+    }
+
+    /// Read the signal from a model and feed it to the detector.
+    ///
+    /// Returns `None` when the signal cannot be read or the alert falls
+    /// below the policy's minimum level.
+    pub fn poll(
+        &mut self,
+        model: &CompressionDynamicsModel,
+        timestamp: f64,
+    ) -> Option<SignalAlert> {
+        let value = self.config.source.read(model)?;
+        self.update(value, timestamp)
+    }
+
+    /// Feed a value to the detector.
+    pub fn update(&mut self, value: f64, timestamp: f64) -> Option<SignalAlert> {
+        self.history.push_back((timestamp, value));
+        if self.history.len() > self.config.history_capacity.max(1) {
+            self.history.pop_front();
+        }
+        let result = self.detector.update(value);
+
+        let len = self.history.len();
+        let oldest = len - len.min(self.config.policy.trend_window.max(2));
+        let trend = value - self.history[oldest].1;
+
+        let alert = SignalAlert {
+            signal: self.name.clone(),
+            alert_level: self.config.policy.level(result.phase, value, trend),
+            phase: result.phase,
+            value,
+            trend,
+            confidence: result.confidence,
+            timestamp,
+        };
+        self.last_alert = Some(alert.clone());
+        (alert.alert_level >= self.config.policy.min_level).then_some(alert)
+    }
+
+    /// Retained `(timestamp, value)` samples, oldest first.
+    pub fn history(&self) -> &VecDeque<(f64, f64)> {
+        &self.history
+    }
+
+    /// Most recent evaluation, whether or not it was reported.
+    pub fn last_alert(&self) -> Option<&SignalAlert> {
+        self.last_alert.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_sources() {
+        let mut model = CompressionDynamicsModel::new(3);
+        model.register_actor("A", Some(vec![0.8, 0.1, 0.1]));
+        model.register_actor("B", Some(vec![0.1, 0.1, 0.8]));
+        model.register_actor("C", Some(vec![0.8, 0.1, 0.1]));
+
+        let phi = SignalSource::Phi {
+            actor_a: "A".into(),
+            actor_b: "B".into(),
+        };
+        assert_eq!(phi.name(), "phi:A-B");
+        let phi_ab = phi.read(&model).unwrap();
+        let polarization = SignalSource::Polarization.read(&model).unwrap();
+        assert!((polarization - 2.0 * phi_ab / 3.0).abs() < 1e-9);
+        assert!(SignalSource::Entropy { actor: "Z".into() }
+            .read(&model)
+            .is_none());
+    }
+
+    #[test]
+    fn test_alert_policy() {
+        let policy = AlertPolicy::rising().with_value_threshold(1.0);
+        assert_eq!(policy.level(Phase::Critical, 0.5, -0.1), AlertLevel::Green);
+        assert_eq!(
+            policy.level(Phase::Approaching, 0.5, 0.1),
+            AlertLevel::Yellow
+        );
+        assert_eq!(policy.level(Phase::Stable, 1.5, 0.1), AlertLevel::Yellow);
+        assert_eq!(policy.level(Phase::Critical, 1.5, 0.1), AlertLevel::Red);
+    }
+
+    #[test]
+    fn test_monitor_history_and_trend() {
+        let mut config = SignalMonitorConfig::new(SignalSource::Polarization);
+        config.history_capacity = 4;
+        config.policy.min_level = AlertLevel::Green;
+        let mut monitor = SignalMonitor::new(config);
+        for i in 0..6 {
+            monitor.update(i as f64, i as f64);
+        }
+        assert_eq!(monitor.history().len(), 4);
+        let alert = monitor.last_alert().unwrap();
+        assert_eq!(alert.signal, "polarization");
+        assert_eq!(alert.trend, 3.0);
+    }
+}
@@ -0,0 +1,469 @@
+//! Regime Model: Hidden Markov model over dyad states
+//!
+//! Thresholds on Φ and the variance phase answer "is this dyad past a
+//! line?". A hidden Markov model answers "which state is this dyad most
+//! likely in?", weighing every signal together and carrying belief across
+//! updates so a single noisy sample does not flip the verdict.
+//!
+//! States are calm / tension / crisis. Each emits a feature vector
+//! `[Φ, dΦ, variance phase, grievance]` from a diagonal Gaussian, and
+//! belief is updated online with the forward filter:
+//!
+//! ```text
+//! P(s_t | x_1..t) ∝ p(x_t | s_t) · Σ_s P(s_t | s) · P(s | x_1..t-1)
+//! ```
+//!
+//! The default parameters are hand-set priors; `fit` re-estimates them by
+//! Baum-Welch on a dyad's own feature history.
+
+use crate::variance::Phase;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Number of regimes.
+pub const N_REGIMES: usize = 3;
+
+/// Number of features per observation.
+pub const N_FEATURES: usize = 4;
+
+/// Feature vector `[Φ, dΦ, variance phase, grievance]`.
+pub type RegimeFeatures = [f64; N_FEATURES];
+
+/// Floor on emission variances, keeping densities finite.
+const MIN_VARIANCE: f64 = 1e-6;
+
+/// Dyad regime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Regime {
+    /// Low divergence, no transition signature
+    #[default]
+    Calm,
+    /// Elevated or rising divergence
+    Tension,
+    /// High divergence with active transition dynamics
+    Crisis,
+}
+
+impl Regime {
+    /// All regimes in index order.
+    pub const ALL: [Regime; N_REGIMES] = [Regime::Calm, Regime::Tension, Regime::Crisis];
+
+    /// Index into probability vectors.
+    #[inline]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Numeric encoding of a variance phase for use as a feature.
+pub fn phase_feature(phase: Phase) -> f64 {
+    match phase {
+        Phase::Stable => 0.0,
+        Phase::Approaching => 1.0,
+        Phase::Critical => 2.0,
+        Phase::Transitioning => 3.0,
+    }
+}
+
+/// Gaussian-emission HMM over dyad regimes.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RegimeModel {
+    /// Initial regime probabilities.
+    pub initial: [f64; N_REGIMES],
+    /// `transition[i][j]` = P(next = j | current = i).
+    pub transition: [[f64; N_REGIMES]; N_REGIMES],
+    /// Per-regime feature means.
+    pub means: [RegimeFeatures; N_REGIMES],
+    /// Per-regime feature variances.
+    pub variances: [RegimeFeatures; N_REGIMES],
+}
+
+impl Default for RegimeModel {
+    fn default() -> Self {
+        Self {
+            initial: [0.8, 0.15, 0.05],
+            transition: [
+                [0.95, 0.045, 0.005],
+                [0.05, 0.90, 0.05],
+                [0.01, 0.09, 0.90],
+            ],
+            means: [
+                [0.2, 0.0, 0.0, 0.01],
+                [1.0, 0.01, 1.0, 0.05],
+                [2.5, 0.05, 2.0, 0.2],
+            ],
+            variances: [
+                [0.04, 0.0004, 0.25, 0.0004],
+                [0.25, 0.0025, 0.5, 0.0025],
+                [1.0, 0.01, 1.0, 0.04],
+            ],
+        }
+    }
+}
+
+impl RegimeModel {
+    /// Log density of `x` under regime `s`.
+    fn log_emission(&self, s: usize, x: &RegimeFeatures) -> f64 {
+        let mut log_p = 0.0;
+        for ((&xk, &mean), &var) in x.iter().zip(&self.means[s]).zip(&self.variances[s]) {
+            let var = var.max(MIN_VARIANCE);
+            let d = xk - mean;
+            log_p -= 0.5 * ((2.0 * std::f64::consts::PI * var).ln() + d * d / var);
+        }
+        log_p
+    }
+
+    /// Emission likelihoods scaled by their maximum, with the log of the scale.
+    fn scaled_emissions(&self, x: &RegimeFeatures) -> ([f64; N_REGIMES], f64) {
+        let mut logs = [0.0; N_REGIMES];
+        for (s, l) in logs.iter_mut().enumerate() {
+            *l = self.log_emission(s, x);
+        }
+        let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mut e = [0.0; N_REGIMES];
+        for s in 0..N_REGIMES {
+            e[s] = (logs[s] - max).exp();
+        }
+        (e, max)
+    }
+
+    /// One forward-filter step: prior belief → posterior after observing `x`.
+    ///
+    /// Pass `None` as the prior for the first observation.
+    pub fn filter_step(&self, prior: Option<&[f64; N_REGIMES]>, x: &RegimeFeatures) -> [f64; N_REGIMES] {
+        let predicted = match prior {
+            Some(p) => {
+                let mut pred = [0.0; N_REGIMES];
+                for (i, &pi) in p.iter().enumerate() {
+                    for (j, pj) in pred.iter_mut().enumerate() {
+                        *pj += pi * self.transition[i][j];
+                    }
+                }
+                pred
+            }
+            None => self.initial,
+        };
+
+        let (e, _) = self.scaled_emissions(x);
+        let mut post = [0.0; N_REGIMES];
+        for s in 0..N_REGIMES {
+            post[s] = predicted[s] * e[s];
+        }
+        match normalize(&mut post) {
+            Some(()) => post,
+            None => predicted,
+        }
+    }
+
+    /// Scaled forward-backward pass.
+    ///
+    /// Returns (forward, backward, per-step scales, log-likelihood).
+    #[allow(clippy::type_complexity)]
+    fn forward_backward(
+        &self,
+        seq: &[RegimeFeatures],
+    ) -> (Vec<[f64; N_REGIMES]>, Vec<[f64; N_REGIMES]>, Vec<[f64; N_REGIMES]>, Vec<f64>, f64) {
+        let t_len = seq.len();
+        let mut emissions = Vec::with_capacity(t_len);
+        let mut log_likelihood = 0.0;
+        for x in seq {
+            let (e, max) = self.scaled_emissions(x);
+            emissions.push(e);
+            log_likelihood += max;
+        }
+
+        let mut alpha = vec![[0.0; N_REGIMES]; t_len];
+        let mut scales = vec![0.0; t_len];
+        for t in 0..t_len {
+            for j in 0..N_REGIMES {
+                let pred = if t == 0 {
+                    self.initial[j]
+                } else {
+                    (0..N_REGIMES).map(|i| alpha[t - 1][i] * self.transition[i][j]).sum()
+                };
+                alpha[t][j] = pred * emissions[t][j];
+            }
+            let c: f64 = alpha[t].iter().sum();
+            let c = c.max(f64::MIN_POSITIVE);
+            for a in &mut alpha[t] {
+                *a /= c;
+            }
+            scales[t] = c;
+            log_likelihood += c.ln();
+        }
+
+        let mut beta = vec![[1.0; N_REGIMES]; t_len];
+        for t in (0..t_len.saturating_sub(1)).rev() {
+            for i in 0..N_REGIMES {
+                beta[t][i] = (0..N_REGIMES)
+                    .map(|j| self.transition[i][j] * emissions[t + 1][j] * beta[t + 1][j])
+                    .sum::<f64>()
+                    / scales[t + 1];
+            }
+        }
+
+        (alpha, beta, emissions, scales, log_likelihood)
+    }
+
+    /// Log-likelihood of a feature sequence.
+    pub fn log_likelihood(&self, seq: &[RegimeFeatures]) -> f64 {
+        self.forward_backward(seq).4
+    }
+
+    /// Re-estimate parameters by Baum-Welch.
+    ///
+    /// Regimes are relabeled afterwards so that mean Φ increases from calm
+    /// to crisis. Returns the final log-likelihood, or `None` if the
+    /// sequence is too short to fit (fewer than `4 * N_REGIMES` samples).
+    pub fn fit(&mut self, seq: &[RegimeFeatures], iterations: usize) -> Option<f64> {
+        self.fit_relabeled(seq, iterations).map(|(log_likelihood, _)| log_likelihood)
+    }
+
+    /// As [`fit`](Self::fit), also returning the relabeling: new regime `i`
+    /// is the regime numbered `order[i]` before the fit.
+    fn fit_relabeled(
+        &mut self,
+        seq: &[RegimeFeatures],
+        iterations: usize,
+    ) -> Option<(f64, [usize; N_REGIMES])> {
+        if seq.len() < 4 * N_REGIMES {
+            return None;
+        }
+
+        for _ in 0..iterations {
+            let (alpha, beta, emissions, scales, _) = self.forward_backward(seq);
+
+            let mut gamma_sum = [0.0; N_REGIMES];
+            let mut mean_acc = [[0.0; N_FEATURES]; N_REGIMES];
+            let mut sq_acc = [[0.0; N_FEATURES]; N_REGIMES];
+            let mut xi_sum = [[0.0; N_REGIMES]; N_REGIMES];
+            let mut gamma_from = [0.0; N_REGIMES];
+            let mut initial = [0.0; N_REGIMES];
+
+            for t in 0..seq.len() {
+                let mut gamma = [0.0; N_REGIMES];
+                for s in 0..N_REGIMES {
+                    gamma[s] = alpha[t][s] * beta[t][s];
+                }
+                normalize(&mut gamma);
+                if t == 0 {
+                    initial = gamma;
+                }
+                for s in 0..N_REGIMES {
+                    gamma_sum[s] += gamma[s];
+                    for k in 0..N_FEATURES {
+                        mean_acc[s][k] += gamma[s] * seq[t][k];
+                        sq_acc[s][k] += gamma[s] * seq[t][k] * seq[t][k];
+                    }
+                }
+
+                if t + 1 < seq.len() {
+                    for i in 0..N_REGIMES {
+                        gamma_from[i] += gamma[i];
+                        for j in 0..N_REGIMES {
+                            xi_sum[i][j] += alpha[t][i]
+                                * self.transition[i][j]
+                                * emissions[t + 1][j]
+                                * beta[t + 1][j]
+                                / scales[t + 1];
+                        }
+                    }
+                }
+            }
+
+            self.initial = initial;
+            for i in 0..N_REGIMES {
+                if gamma_from[i] > 0.0 {
+                    let mut row = xi_sum[i];
+                    if normalize(&mut row).is_some() {
+                        self.transition[i] = row;
+                    }
+                }
+                if gamma_sum[i] > 1e-9 {
+                    for k in 0..N_FEATURES {
+                        let mean = mean_acc[i][k] / gamma_sum[i];
+                        self.means[i][k] = mean;
+                        self.variances[i][k] =
+                            (sq_acc[i][k] / gamma_sum[i] - mean * mean).max(MIN_VARIANCE);
+                    }
+                }
+            }
+        }
+
+        let order = self.sort_by_phi();
+        Some((self.log_likelihood(seq), order))
+    }
+
+    /// Relabel regimes so mean Φ is increasing, returning the old label of
+    /// each new one.
+    fn sort_by_phi(&mut self) -> [usize; N_REGIMES] {
+        let mut order: [usize; N_REGIMES] = std::array::from_fn(|i| i);
+        order.sort_by(|&a, &b| self.means[a][0].total_cmp(&self.means[b][0]));
+
+        let old = self.clone();
+        for (new, &o) in order.iter().enumerate() {
+            self.initial[new] = old.initial[o];
+            self.means[new] = old.means[o];
+            self.variances[new] = old.variances[o];
+            for (new_j, &o_j) in order.iter().enumerate() {
+                self.transition[new][new_j] = old.transition[o][o_j];
+            }
+        }
+        order
+    }
+}
+
+/// Normalize to sum 1; `None` (and unchanged) if the sum is not positive.
+fn normalize(p: &mut [f64; N_REGIMES]) -> Option<()> {
+    let sum: f64 = p.iter().sum();
+    if !(sum > 0.0 && sum.is_finite()) {
+        return None;
+    }
+    for v in p.iter_mut() {
+        *v /= sum;
+    }
+    Some(())
+}
+
+/// Online regime filter for a single dyad.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RegimeFilter {
+    model: RegimeModel,
+    belief: Option<[f64; N_REGIMES]>,
+}
+
+impl RegimeFilter {
+    pub fn new(model: RegimeModel) -> Self {
+        Self { model, belief: None }
+    }
+
+    /// Update belief with a new observation.
+    pub fn update(&mut self, x: &RegimeFeatures) -> [f64; N_REGIMES] {
+        let post = self.model.filter_step(self.belief.as_ref(), x);
+        self.belief = Some(post);
+        post
+    }
+
+    /// Current regime probabilities (the initial distribution before any update).
+    pub fn probabilities(&self) -> [f64; N_REGIMES] {
+        self.belief.unwrap_or(self.model.initial)
+    }
+
+    /// Most probable regime.
+    pub fn regime(&self) -> Regime {
+        let p = self.probabilities();
+        let mut best = 0;
+        for s in 1..N_REGIMES {
+            if p[s] > p[best] {
+                best = s;
+            }
+        }
+        Regime::ALL[best]
+    }
+
+    pub fn model(&self) -> &RegimeModel {
+        &self.model
+    }
+
+    /// Replace the model parameters, keeping the current belief.
+    pub fn set_model(&mut self, model: RegimeModel) {
+        self.model = model;
+    }
+
+    /// Re-estimate the model by Baum-Welch (see [`RegimeModel::fit`]).
+    ///
+    /// The current belief is relabeled along with the regimes, so it keeps
+    /// pointing at the same fitted states.
+    pub fn fit(&mut self, seq: &[RegimeFeatures], iterations: usize) -> Option<f64> {
+        let (log_likelihood, order) = self.model.fit_relabeled(seq, iterations)?;
+        if let Some(belief) = &mut self.belief {
+            let old = *belief;
+            for (new, &o) in order.iter().enumerate() {
+                belief[new] = old[o];
+            }
+        }
+        Some(log_likelihood)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_tracks_crisis() {
+        let mut filter = RegimeFilter::default();
+        assert_eq!(filter.regime(), Regime::Calm);
+
+        for _ in 0..5 {
+            filter.update(&[0.15, 0.0, 0.0, 0.01]);
+        }
+        assert_eq!(filter.regime(), Regime::Calm);
+
+        // A Φ spike alone, without phase or grievance support, is not a regime change
+        filter.update(&[0.6, 0.0, 0.0, 0.01]);
+        assert_eq!(filter.regime(), Regime::Calm);
+
+        for _ in 0..10 {
+            filter.update(&[2.8, 0.06, 2.0, 0.25]);
+        }
+        assert_eq!(filter.regime(), Regime::Crisis);
+        let p = filter.probabilities();
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_learns_dyad_levels() {
+        // This dyad lives at a much lower Φ scale than the priors
+        let mut seq = Vec::new();
+        for block in 0..6 {
+            let phi = if block % 2 == 0 { 0.05 } else { 0.4 };
+            for k in 0..20 {
+                let jitter = 0.01 * ((k % 3) as f64 - 1.0);
+                seq.push([phi + jitter, jitter, 0.0, 0.01]);
+            }
+        }
+
+        let mut model = RegimeModel::default();
+        let before = model.log_likelihood(&seq);
+        let after = model.fit(&seq, 20).unwrap();
+        assert!(after > before);
+        assert!(model.means[0][0] < model.means[2][0]);
+        assert!(RegimeModel::default().fit(&seq[..5], 10).is_none());
+    }
+
+    #[test]
+    fn test_fit_relabels_belief() {
+        // Priors with calm and crisis swapped, so fitting relabels them
+        let mut model = RegimeModel::default();
+        model.initial.swap(0, 2);
+        model.means.swap(0, 2);
+        model.variances.swap(0, 2);
+        model.transition = RegimeModel::default().transition;
+        let mut filter = RegimeFilter::new(model);
+
+        let calm = [0.15, 0.0, 0.0, 0.01];
+        let crisis = [2.8, 0.06, 2.0, 0.25];
+        let mut seq = Vec::new();
+        for block in 0..6 {
+            for _ in 0..10 {
+                seq.push(if block % 2 == 0 { calm } else { crisis });
+            }
+        }
+        for _ in 0..5 {
+            filter.update(&calm);
+        }
+        let belief = filter.probabilities();
+        assert!(belief[2] > 0.9);
+
+        filter.fit(&seq, 20).unwrap();
+        let p = filter.probabilities();
+        assert!((p[0] - belief[2]).abs() < 1e-12);
+        assert_eq!(filter.regime(), Regime::Calm);
+        assert!(filter.model().means[0][0] < filter.model().means[2][0]);
+    }
+}
@@ -0,0 +1,321 @@
+//! Background runner for Shepherd Dynamics.
+//!
+//! [`ShepherdRunner`] owns a [`ShepherdDynamics`] on a worker thread that
+//! wakes every tick, drains the observation queue, runs
+//! [`check_scheduled_dyads`](ShepherdDynamics::check_scheduled_dyads) once
+//! at the latest observed timestamp, and hands each alert to the registered
+//! callbacks. Watchlist notifications due at that timestamp go to the
+//! callbacks registered for their sink. Only dyads of actors observed in
+//! the tick are checked, plus a full sweep at the shepherd's
+//! [sweep interval](ShepherdDynamics::with_full_sweep_interval). Ticks
+//! without new observations do nothing.
+//!
+//! While paused, observations keep queueing and are applied on resume.
+//! With a [`CompactionPolicy`] configured, the worker also runs
+//! [`compact`](ShepherdDynamics::compact) at a fixed interval, except
+//! while paused.
+
+use crate::shepherd::{CompactionPolicy, NucleationAlert, ShepherdDynamics};
+use crate::watchlist::Notification;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Callback invoked for every alert raised by the runner
+pub type AlertCallback = Box<dyn FnMut(&NucleationAlert) + Send>;
+
+/// Callback invoked for watchlist notifications addressed to its sink
+pub type NotificationCallback = Box<dyn FnMut(&Notification) + Send>;
+
+/// Runner configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunnerConfig {
+    /// Time between ticks
+    pub tick_interval: Duration,
+    /// Most observations applied per tick; the rest wait for the next tick
+    pub max_batch: usize,
+    /// Compaction run every `compaction_interval`, if any
+    pub compaction: Option<CompactionPolicy>,
+    pub compaction_interval: Duration,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        Self {
+            tick_interval: Duration::from_millis(100),
+            max_batch: 1024,
+            compaction: None,
+            compaction_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RunnerConfig {
+    /// Set the tick interval.
+    pub fn with_tick_interval(mut self, interval: Duration) -> Self {
+        self.tick_interval = interval;
+        self
+    }
+
+    /// Set the per-tick batch limit (at least 1).
+    pub fn with_max_batch(mut self, max_batch: usize) -> Self {
+        self.max_batch = max_batch.max(1);
+        self
+    }
+
+    /// Compact the shepherd under `policy` every `interval`.
+    pub fn with_compaction(mut self, policy: CompactionPolicy, interval: Duration) -> Self {
+        self.compaction = Some(policy);
+        self.compaction_interval = interval;
+        self
+    }
+}
+
+/// A queued actor observation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub actor_id: String,
+    pub values: Vec<f64>,
+    pub timestamp: f64,
+}
+
+struct Shared {
+    shepherd: Mutex<ShepherdDynamics>,
+    callbacks: Mutex<Vec<AlertCallback>>,
+    sinks: Mutex<Vec<(String, NotificationCallback)>>,
+    paused: AtomicBool,
+    stopped: AtomicBool,
+    ticks: AtomicU64,
+}
+
+/// Shepherd Dynamics running on a background thread.
+///
+/// Dropping the runner stops the thread; [`stop`](Self::stop) also
+/// returns the final state.
+pub struct ShepherdRunner {
+    shared: Arc<Shared>,
+    queue: Sender<Observation>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl ShepherdRunner {
+    /// Start ticking `shepherd` on a new thread.
+    pub fn spawn(shepherd: ShepherdDynamics, config: RunnerConfig) -> Self {
+        let shared = Arc::new(Shared {
+            shepherd: Mutex::new(shepherd),
+            callbacks: Mutex::new(Vec::new()),
+            sinks: Mutex::new(Vec::new()),
+            paused: AtomicBool::new(false),
+            stopped: AtomicBool::new(false),
+            ticks: AtomicU64::new(0),
+        });
+        let (queue, receiver) = mpsc::channel();
+        let worker_shared = Arc::clone(&shared);
+        let worker = thread::spawn(move || run(worker_shared, receiver, config));
+        Self {
+            shared,
+            queue,
+            worker: Some(worker),
+        }
+    }
+
+    /// Queue an observation for the next tick.
+    ///
+    /// Returns false if the runner has stopped.
+    pub fn submit(&self, actor_id: impl Into<String>, values: Vec<f64>, timestamp: f64) -> bool {
+        self.queue
+            .send(Observation {
+                actor_id: actor_id.into(),
+                values,
+                timestamp,
+            })
+            .is_ok()
+    }
+
+    /// Register a callback for every subsequent alert.
+    pub fn on_alert(&self, callback: impl FnMut(&NucleationAlert) + Send + 'static) {
+        lock(&self.shared.callbacks).push(Box::new(callback));
+    }
+
+    /// Register a callback for watchlist notifications addressed to `sink`.
+    pub fn on_notification(
+        &self,
+        sink: impl Into<String>,
+        callback: impl FnMut(&Notification) + Send + 'static,
+    ) {
+        lock(&self.shared.sinks).push((sink.into(), Box::new(callback)));
+    }
+
+    /// Stop applying observations and checking dyads until resumed.
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resume ticking after [`pause`](Self::pause).
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether the runner is paused.
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::SeqCst)
+    }
+
+    /// Number of ticks that applied at least one observation.
+    pub fn ticks(&self) -> u64 {
+        self.shared.ticks.load(Ordering::SeqCst)
+    }
+
+    /// Run `f` against the shepherd between ticks.
+    pub fn with_shepherd<R>(&self, f: impl FnOnce(&mut ShepherdDynamics) -> R) -> R {
+        f(&mut lock(&self.shared.shepherd))
+    }
+
+    /// Stop the worker thread and return the shepherd.
+    ///
+    /// Observations still queued are discarded.
+    pub fn stop(mut self) -> ShepherdDynamics {
+        self.shutdown();
+        let placeholder = ShepherdDynamics::new(0);
+        std::mem::replace(&mut *lock(&self.shared.shepherd), placeholder)
+    }
+
+    fn shutdown(&mut self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            worker.thread().unpark();
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for ShepherdRunner {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl std::fmt::Debug for ShepherdRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShepherdRunner")
+            .field("paused", &self.is_paused())
+            .field("ticks", &self.ticks())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Lock a mutex, recovering the data if a callback panicked while held
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn run(shared: Arc<Shared>, receiver: Receiver<Observation>, config: RunnerConfig) {
+    let mut last_compaction = Instant::now();
+    while !shared.stopped.load(Ordering::SeqCst) {
+        thread::park_timeout(config.tick_interval);
+        if shared.stopped.load(Ordering::SeqCst) || shared.paused.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        if let Some(policy) = &config.compaction {
+            if last_compaction.elapsed() >= config.compaction_interval {
+                lock(&shared.shepherd).compact(policy);
+                last_compaction = Instant::now();
+            }
+        }
+
+        let batch: Vec<Observation> = receiver.try_iter().take(config.max_batch).collect();
+        let Some(latest) = batch.iter().map(|o| o.timestamp).reduce(f64::max) else {
+            continue;
+        };
+
+        let (alerts, notifications) = {
+            let mut shepherd = lock(&shared.shepherd);
+            for observation in &batch {
+                shepherd.observe_actor(
+                    &observation.actor_id,
+                    &observation.values,
+                    observation.timestamp,
+                );
+            }
+            let alerts = shepherd.check_scheduled_dyads(latest);
+            (alerts, shepherd.poll_notifications(latest))
+        };
+        shared.ticks.fetch_add(1, Ordering::SeqCst);
+
+        let mut callbacks = lock(&shared.callbacks);
+        for alert in &alerts {
+            for callback in callbacks.iter_mut() {
+                callback(alert);
+            }
+        }
+        drop(callbacks);
+
+        let mut sinks = lock(&shared.sinks);
+        for notification in &notifications {
+            for (sink, callback) in sinks.iter_mut() {
+                if *sink == notification.sink {
+                    callback(notification);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn wait_for(condition: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(2));
+        }
+        false
+    }
+
+    #[test]
+    fn test_runner_ticks_and_pauses() {
+        let mut shepherd = ShepherdDynamics::new(3);
+        shepherd.register_actor("A", Some(vec![0.8, 0.1, 0.1]));
+        shepherd.register_actor("B", Some(vec![0.1, 0.1, 0.8]));
+
+        let config = RunnerConfig::default().with_tick_interval(Duration::from_millis(5));
+        let runner = ShepherdRunner::spawn(shepherd, config);
+        let seen = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&seen);
+        runner.on_alert(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        assert!(runner.submit("A", vec![0.9, 0.05, 0.05], 1.0));
+        assert!(runner.submit("B", vec![0.05, 0.05, 0.9], 1.0));
+        assert!(wait_for(|| runner.ticks() >= 1));
+        assert!(runner.with_shepherd(|s| s.phi_history("A", "B").is_some()));
+
+        runner.pause();
+        assert!(runner.is_paused());
+        let ticks = runner.ticks();
+        runner.submit("A", vec![0.9, 0.05, 0.05], 2.0);
+        thread::sleep(Duration::from_millis(30));
+        assert_eq!(runner.ticks(), ticks);
+
+        runner.resume();
+        assert!(wait_for(|| runner.ticks() > ticks));
+
+        let shepherd = runner.stop();
+        assert_eq!(shepherd.actors().len(), 2);
+        assert_eq!(
+            seen.load(Ordering::SeqCst) as usize,
+            shepherd.alert_history().len()
+        );
+    }
+}
@@ -0,0 +1,391 @@
+//! Sandbox: agent-based simulation through the full Shepherd pipeline
+//!
+//! Synthetic agents emit one event distribution each per step, reacting to
+//! what the others emitted the step before. Every event is observed by a
+//! [`ShepherdDynamics`] and all dyads are checked, so schemes, grievance,
+//! detectors and alerts behave exactly as they would on real data. Use it
+//! to compare intervention strategies or to stress-test detector settings
+//! against known dynamics.
+//!
+//! Categories are taken as ordered from most cooperative (first) to most
+//! conflictual (last). With `b` the agent's baseline, `o` the mean of the
+//! other agents' last events and `c(·)` the mean category position in
+//! `[0, 1]`, each policy emits:
+//!
+//! ```text
+//! Stubborn                 b
+//! Accommodating { rate }   (1 - rate)·b + rate·o
+//! Reactive { gain }        (1 - w)·b + w·e_last    w = clamp(gain·(c(o) - c(b)), 0, 1)
+//! ```
+//!
+//! Reactive agents answer hostility beyond their own baseline with
+//! hostility and otherwise keep to the baseline. Events are then jittered
+//! by the configured noise. Interventions scheduled at a time switch an
+//! agent's policy or force its next event (a shock).
+
+use crate::shepherd::{AlertLevel, NucleationAlert, ShepherdDynamics};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How an agent chooses its events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AgentPolicy {
+    /// Always emits its baseline
+    Stubborn,
+    /// Moves toward what the others emit by `rate` in `[0, 1]`
+    Accommodating { rate: f64 },
+    /// Escalates by `gain` per unit of hostility above its baseline
+    Reactive { gain: f64 },
+}
+
+/// Scheduled change to the simulation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Intervention {
+    /// Switch the agent to a new policy
+    SetPolicy { agent: String, policy: AgentPolicy },
+    /// Replace the agent's next event
+    Event { agent: String, event: Vec<f64> },
+}
+
+/// Sandbox settings.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SandboxConfig {
+    /// Simulated time between steps
+    pub time_step: f64,
+    /// Relative jitter applied to each event weight, in `[0, 1]`
+    pub noise: f64,
+    /// Seed for reproducible noise
+    pub seed: u64,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            time_step: 1.0,
+            noise: 0.05,
+            seed: 0x5eed,
+        }
+    }
+}
+
+/// Synthetic actor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SandboxAgent {
+    pub id: String,
+    pub baseline: Vec<f64>,
+    pub policy: AgentPolicy,
+}
+
+/// Outcome of a [`Sandbox::run`].
+#[derive(Debug, Clone)]
+pub struct SandboxReport {
+    pub steps: usize,
+    /// Simulated time after the last step
+    pub end_time: f64,
+    /// Alerts raised during the run, oldest first
+    pub alerts: Vec<NucleationAlert>,
+}
+
+impl SandboxReport {
+    /// First alert at or above `level`.
+    pub fn first_alert(&self, level: AlertLevel) -> Option<&NucleationAlert> {
+        self.alerts.iter().find(|a| a.alert_level >= level)
+    }
+}
+
+/// Mean category position in `[0, 1]`; 0 = cooperative, 1 = conflictual.
+fn conflict_score(dist: &[f64]) -> f64 {
+    if dist.len() < 2 {
+        return 0.0;
+    }
+    let last = (dist.len() - 1) as f64;
+    dist.iter().enumerate().map(|(i, &p)| p * i as f64 / last).sum()
+}
+
+fn normalized(mut dist: Vec<f64>) -> Vec<f64> {
+    let total: f64 = dist.iter().sum();
+    if total > 0.0 {
+        dist.iter_mut().for_each(|p| *p /= total);
+    } else {
+        let n = dist.len() as f64;
+        dist.iter_mut().for_each(|p| *p = 1.0 / n);
+    }
+    dist
+}
+
+/// Agents exchanging events over simulated time.
+#[derive(Debug)]
+pub struct Sandbox {
+    shepherd: ShepherdDynamics,
+    config: SandboxConfig,
+    agents: Vec<SandboxAgent>,
+    /// Each agent's last event, parallel to `agents`
+    last_events: Vec<Vec<f64>>,
+    /// Pending interventions, in scheduling order
+    scheduled: Vec<(f64, Intervention)>,
+    time: f64,
+    rng: u64,
+}
+
+impl Sandbox {
+    /// Sandbox driving `shepherd`, with its detector settings as given.
+    pub fn new(shepherd: ShepherdDynamics, config: SandboxConfig) -> Self {
+        Self {
+            shepherd,
+            rng: config.seed,
+            config,
+            agents: Vec::new(),
+            last_events: Vec::new(),
+            scheduled: Vec::new(),
+            time: 0.0,
+        }
+    }
+
+    /// Add an agent, registering it with the shepherd at its baseline.
+    ///
+    /// Returns false if the ID is taken or the baseline does not match the
+    /// shepherd's categories.
+    pub fn add_agent(&mut self, id: impl Into<String>, baseline: Vec<f64>, policy: AgentPolicy) -> bool {
+        let id = id.into();
+        if baseline.len() != self.shepherd.n_categories() || self.agent(&id).is_some() {
+            return false;
+        }
+        let baseline = normalized(baseline);
+        self.shepherd.register_actor(id.clone(), Some(baseline.clone()));
+        self.last_events.push(baseline.clone());
+        self.agents.push(SandboxAgent { id, baseline, policy });
+        true
+    }
+
+    /// Agent by ID.
+    pub fn agent(&self, id: &str) -> Option<&SandboxAgent> {
+        self.agents.iter().find(|a| a.id == id)
+    }
+
+    pub fn agents(&self) -> &[SandboxAgent] {
+        &self.agents
+    }
+
+    /// Change an agent's policy now. Returns false for unknown agents.
+    pub fn set_policy(&mut self, id: &str, policy: AgentPolicy) -> bool {
+        match self.agents.iter_mut().find(|a| a.id == id) {
+            Some(agent) => {
+                agent.policy = policy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Apply an intervention at the first step at or after `time`.
+    pub fn schedule(&mut self, time: f64, intervention: Intervention) {
+        self.scheduled.push((time, intervention));
+    }
+
+    /// Simulated time of the last step.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The shepherd observing the agents.
+    pub fn shepherd(&self) -> &ShepherdDynamics {
+        &self.shepherd
+    }
+
+    /// Mutable shepherd, e.g. to annotate or resolve alerts mid-run.
+    pub fn shepherd_mut(&mut self) -> &mut ShepherdDynamics {
+        &mut self.shepherd
+    }
+
+    /// Hand back the shepherd, e.g. for inspection after a run.
+    pub fn into_shepherd(self) -> ShepherdDynamics {
+        self.shepherd
+    }
+
+    /// Advance one step: every agent emits an event, the shepherd observes
+    /// them all and checks every dyad. Returns the alerts raised.
+    pub fn step(&mut self) -> Vec<NucleationAlert> {
+        self.time += self.config.time_step;
+
+        // Interventions due now
+        let mut forced: Vec<Option<Vec<f64>>> = vec![None; self.agents.len()];
+        let (due, pending) = std::mem::take(&mut self.scheduled)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(at, _)| *at <= self.time);
+        self.scheduled = pending;
+        for (_, intervention) in due {
+            match intervention {
+                Intervention::SetPolicy { agent, policy } => {
+                    self.set_policy(&agent, policy);
+                }
+                Intervention::Event { agent, event } => {
+                    if let Some(i) = self.agents.iter().position(|a| a.id == agent) {
+                        if event.len() == self.agents[i].baseline.len() {
+                            forced[i] = Some(normalized(event));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Every agent reacts to the previous step's events
+        let events: Vec<Vec<f64>> = (0..self.agents.len())
+            .map(|i| match forced[i].take() {
+                Some(event) => event,
+                None => {
+                    let event = self.emit(i);
+                    self.jitter(event)
+                }
+            })
+            .collect();
+
+        for (agent, event) in self.agents.iter().zip(&events) {
+            self.shepherd.observe_actor(&agent.id, event, self.time);
+        }
+        self.last_events = events;
+        self.shepherd.check_all_dyads(self.time)
+    }
+
+    /// Run `steps` steps.
+    pub fn run(&mut self, steps: usize) -> SandboxReport {
+        let alerts = (0..steps).flat_map(|_| self.step()).collect();
+        SandboxReport {
+            steps,
+            end_time: self.time,
+            alerts,
+        }
+    }
+
+    /// Event agent `i` chooses under its policy, before noise.
+    fn emit(&self, i: usize) -> Vec<f64> {
+        let agent = &self.agents[i];
+        let b = &agent.baseline;
+        let n_others = self.agents.len() - 1;
+        if n_others == 0 {
+            return b.clone();
+        }
+        let others: Vec<f64> = (0..b.len())
+            .map(|k| {
+                self.last_events
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, e)| e[k])
+                    .sum::<f64>()
+                    / n_others as f64
+            })
+            .collect();
+
+        let mix = |w: f64, target: &[f64]| -> Vec<f64> {
+            b.iter().zip(target).map(|(&p, &t)| (1.0 - w) * p + w * t).collect()
+        };
+        match agent.policy {
+            AgentPolicy::Stubborn => b.clone(),
+            AgentPolicy::Accommodating { rate } => mix(rate.clamp(0.0, 1.0), &others),
+            AgentPolicy::Reactive { gain } => {
+                let w = (gain * (conflict_score(&others) - conflict_score(b))).clamp(0.0, 1.0);
+                let mut hostile = vec![0.0; b.len()];
+                hostile[b.len() - 1] = 1.0;
+                mix(w, &hostile)
+            }
+        }
+    }
+
+    /// Scale each weight by a factor in `1 ± noise` and renormalize.
+    fn jitter(&mut self, event: Vec<f64>) -> Vec<f64> {
+        let noise = self.config.noise.clamp(0.0, 1.0);
+        if noise == 0.0 {
+            return event;
+        }
+        let jittered = event
+            .into_iter()
+            .map(|p| {
+                self.rng = self
+                    .rng
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let u = (self.rng >> 33) as f64 / (1u64 << 31) as f64;
+                p * (1.0 + noise * (2.0 * u - 1.0))
+            })
+            .collect();
+        normalized(jittered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variance::VarianceConfig;
+
+    fn sandbox(policy: AgentPolicy) -> Sandbox {
+        let shepherd = ShepherdDynamics::new(3)
+            .with_learning_rate(0.5)
+            .with_variance_config(VarianceConfig {
+                window_size: 10,
+                smoothing_window: 3,
+                ..VarianceConfig::sensitive()
+            });
+        let mut sandbox = Sandbox::new(shepherd, SandboxConfig::default());
+        assert!(sandbox.add_agent("HAWK", vec![0.1, 0.3, 0.6], AgentPolicy::Stubborn));
+        assert!(sandbox.add_agent("DOVE", vec![0.6, 0.3, 0.1], policy));
+        sandbox
+    }
+
+    fn phi(sandbox: &mut Sandbox) -> f64 {
+        sandbox
+            .shepherd_mut()
+            .conflict_potential("HAWK", "DOVE")
+            .unwrap()
+            .phi
+    }
+
+    #[test]
+    fn test_policies_and_interventions() {
+        // Accommodating agents close the gap, reactive ones answer in kind
+        let mut accommodating = sandbox(AgentPolicy::Accommodating { rate: 0.8 });
+        let mut reactive = sandbox(AgentPolicy::Reactive { gain: 2.0 });
+        let start = phi(&mut accommodating);
+        accommodating.run(40);
+        reactive.run(40);
+        assert!(phi(&mut accommodating) < start);
+        assert!(phi(&mut reactive) > start);
+        assert_eq!(reactive.time(), 40.0);
+
+        // Same seed, same run
+        let mut replay = sandbox(AgentPolicy::Reactive { gain: 2.0 });
+        replay.run(40);
+        assert_eq!(phi(&mut replay), phi(&mut reactive));
+
+        // A shock to a calm, stubborn dyad raises an alert after it lands
+        let mut shocked = sandbox(AgentPolicy::Stubborn);
+        shocked.run(60);
+        for t in 61..=70 {
+            let event = Intervention::Event {
+                agent: "DOVE".into(),
+                event: vec![0.0, 0.0, 1.0],
+            };
+            shocked.schedule(t as f64, event);
+        }
+        shocked.schedule(
+            65.0,
+            Intervention::SetPolicy {
+                agent: "HAWK".into(),
+                policy: AgentPolicy::Reactive { gain: 1.0 },
+            },
+        );
+        let report = shocked.run(20);
+        assert_eq!(report.end_time, 80.0);
+        assert!(report.first_alert(AlertLevel::Yellow).unwrap().timestamp > 60.0);
+        assert_eq!(
+            shocked.agent("HAWK").unwrap().policy,
+            AgentPolicy::Reactive { gain: 1.0 }
+        );
+
+        assert!(!shocked.add_agent("HAWK", vec![0.2, 0.3, 0.5], AgentPolicy::Stubborn));
+        assert!(!shocked.add_agent("OWL", vec![0.5, 0.5], AgentPolicy::Stubborn));
+    }
+}
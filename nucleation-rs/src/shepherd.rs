@@ -13,22 +13,43 @@
 //! 2. Compute conflict potential Φ(A,B) = D_KL(A||B) + D_KL(B||A)
 //! 3. Monitor Φ trajectory with variance inflection detector
 //! 4. Alert when nucleation signature detected in Φ dynamics
+//! 5. Filter each dyad's regime (calm / tension / crisis) with an HMM
+//!    over Φ, dΦ, variance phase and grievance, flagging regime changes
+//! 6. Run the same variance inflection detection on each actor's grievance
+//!    and scheme entropy for actor-level alerts
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
+use crate::actor::{dyad, ActorId};
+use crate::annotation::{AnnotatedPhiHistory, Annotation};
 use crate::compression::{
-    CompressionDynamicsModel, CompressionScheme, ConflictPotential, Grievance,
+    merge_phi_points, CompressionDynamicsModel, CompressionScheme, ConflictPotential, Grievance,
+    KlVariant, DEFAULT_PHI_HISTORY_CAPACITY,
 };
+use crate::messages::{render_english, AlertMessage};
+use crate::monitor::{AlertPolicy, SignalAlert, SignalMonitor, SignalMonitorConfig, SignalSource};
+use crate::regime::{phase_feature, Regime, RegimeFeatures, RegimeFilter, RegimeModel, N_REGIMES};
 use crate::variance::{Phase, VarianceConfig, VarianceInflectionDetector};
+use crate::watchlist::{Notification, Watchlist, WatchlistConfig};
+
+/// Probability a new regime needs before a regime change is reported.
+pub const REGIME_CHANGE_PROBABILITY: f64 = 0.6;
+
+/// Reliability a dyad needs before alerts may escalate past Yellow.
+pub const DEFAULT_MIN_RELIABILITY: f64 = 0.5;
+
+/// Baum-Welch iterations used by `fit_dyad_regimes`.
+const REGIME_FIT_ITERATIONS: usize = 25;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Alert level for Shepherd warnings.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AlertLevel {
     /// Normal - no significant changes
+    #[default]
     Green,
     /// Watch - elevated divergence or approaching transition
     Yellow,
@@ -38,16 +59,26 @@ pub enum AlertLevel {
     Red,
 }
 
-impl Default for AlertLevel {
-    fn default() -> Self {
-        Self::Green
-    }
+/// Lifecycle state of a recorded alert.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlertStatus {
+    /// Raised and not yet handled
+    #[default]
+    Open,
+    /// Seen by a responder; no longer re-alerted
+    Acknowledged { by: String, at: f64 },
+    /// Closed out
+    Resolved { at: f64 },
 }
 
 /// Nucleation alert from Shepherd analysis.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NucleationAlert {
+    /// Store ID, assigned when the alert enters the history (0 before)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub alert_id: u64,
     pub actor_a: String,
     pub actor_b: String,
     pub alert_level: AlertLevel,
@@ -57,12 +88,106 @@ pub struct NucleationAlert {
     pub confidence: f64,
     pub timestamp: f64,
     pub message: String,
+    /// `message` as a catalog key and arguments, for localized rendering
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub i18n: Option<AlertMessage>,
+    /// Most probable dyad regime after this update
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub regime: Regime,
+    /// Observation-backed reliability of the underlying potential
+    #[cfg_attr(feature = "serde", serde(default = "crate::compression::full_reliability"))]
+    pub reliability: f64,
+    /// Evidence captured for Red alerts when snapshots are enabled
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub snapshot: Option<AlertSnapshot>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status: AlertStatus,
+    /// Times the alert was raised again while unacknowledged
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub realerts: u32,
+    /// Timestamp of the latest (re-)alert
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub notified_at: f64,
 }
 
 impl NucleationAlert {
     pub fn is_actionable(&self) -> bool {
         self.alert_level >= AlertLevel::Orange
     }
+
+    /// Whether the alert has not been resolved.
+    pub fn is_unresolved(&self) -> bool {
+        !matches!(self.status, AlertStatus::Resolved { .. })
+    }
+}
+
+/// Observation recorded for alert snapshots.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecordedObservation {
+    pub actor_id: String,
+    pub observation: Vec<f64>,
+    pub timestamp: f64,
+}
+
+/// Compact model context attached to a Red alert.
+///
+/// Carries the evidence behind the alert so it can be reviewed without
+/// access to the running system.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlertSnapshot {
+    pub scheme_a: Vec<f64>,
+    pub scheme_b: Vec<f64>,
+    /// Last Φ points of the dyad as (timestamp, phi), oldest first
+    pub phi_history: Vec<(f64, f64)>,
+    pub grievance_a: f64,
+    pub grievance_b: f64,
+    /// Last observations of either actor, oldest first
+    pub recent_observations: Vec<RecordedObservation>,
+}
+
+/// Dyad regime change reported by the HMM filter.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RegimeChange {
+    pub actor_a: String,
+    pub actor_b: String,
+    pub from: Regime,
+    pub to: Regime,
+    pub probabilities: [f64; N_REGIMES],
+    pub timestamp: f64,
+}
+
+/// Retention rules for [`ShepherdDynamics::compact`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompactionPolicy {
+    /// Φ points this recent (before the current timestamp) stay raw
+    pub raw_window: f64,
+    /// Width of the time buckets older Φ points are averaged into
+    pub bucket_width: f64,
+    /// How long resolved alerts are kept after resolution
+    pub alert_retention: f64,
+}
+
+impl CompactionPolicy {
+    pub fn new(raw_window: f64, bucket_width: f64, alert_retention: f64) -> Self {
+        Self {
+            raw_window,
+            bucket_width,
+            alert_retention,
+        }
+    }
+}
+
+/// What a compaction pass removed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompactionReport {
+    /// Φ points folded into aggregates, across trackers and the model
+    pub phi_points_merged: usize,
+    pub alerts_pruned: usize,
 }
 
 /// Per-dyad tracker for Φ dynamics.
@@ -72,52 +197,97 @@ struct DyadTracker {
     actor_a: String,
     actor_b: String,
     detector: VarianceInflectionDetector,
-    phi_history: Vec<(f64, f64)>, // (timestamp, phi)
+    phi_history: VecDeque<(f64, f64)>, // (timestamp, phi)
+    capacity: usize,
     last_alert: Option<NucleationAlert>,
+    regime_filter: RegimeFilter,
+    regime: Regime,
+    features: VecDeque<RegimeFeatures>,
+    min_reliability: f64,
 }
 
 impl DyadTracker {
-    fn new(actor_a: String, actor_b: String, config: VarianceConfig) -> Self {
+    fn new(
+        actor_a: String,
+        actor_b: String,
+        config: VarianceConfig,
+        capacity: usize,
+        regime_model: RegimeModel,
+        min_reliability: f64,
+    ) -> Self {
         Self {
             actor_a,
             actor_b,
             detector: VarianceInflectionDetector::new(config),
-            phi_history: Vec::new(),
+            phi_history: VecDeque::with_capacity(capacity.min(64) + 1),
+            capacity,
             last_alert: None,
+            regime_filter: RegimeFilter::new(regime_model),
+            regime: Regime::Calm,
+            features: VecDeque::with_capacity(capacity.min(64) + 1),
+            min_reliability,
         }
     }
 
-    fn update(&mut self, phi: f64, timestamp: f64) -> Option<NucleationAlert> {
-        self.phi_history.push((timestamp, phi));
+    fn update(
+        &mut self,
+        phi: f64,
+        timestamp: f64,
+        grievance: f64,
+        reliability: f64,
+    ) -> (Option<NucleationAlert>, Option<RegimeChange>) {
+        let d_phi = self.phi_history.back().map_or(0.0, |&(_, last)| phi - last);
+        self.phi_history.push_back((timestamp, phi));
 
         // Limit history size
-        if self.phi_history.len() > 1000 {
-            self.phi_history.remove(0);
+        if self.phi_history.len() > self.capacity {
+            self.phi_history.pop_front();
         }
 
         // Update variance inflection detector with phi value
         let result = self.detector.update(phi);
 
-        // Compute phi trend
-        let phi_trend = if self.phi_history.len() >= 2 {
-            let recent: Vec<f64> = self.phi_history.iter()
-                .rev()
-                .take(10)
-                .map(|(_, p)| *p)
-                .collect();
-            if recent.len() >= 2 {
-                recent[0] - recent[recent.len() - 1]
-            } else {
-                0.0
-            }
+        // Compute phi trend over the last (up to) 10 samples
+        let len = self.phi_history.len();
+        let phi_trend = if len >= 2 {
+            let oldest = len - len.min(10);
+            self.phi_history[len - 1].1 - self.phi_history[oldest].1
         } else {
             0.0
         };
 
-        // Determine alert level
-        let alert_level = Self::compute_alert_level(phi, &result, phi_trend);
+        // Filter regime
+        let features = [phi, d_phi, phase_feature(result.phase), grievance];
+        if self.features.len() == self.capacity {
+            self.features.pop_front();
+        }
+        self.features.push_back(features);
+        let probabilities = self.regime_filter.update(&features);
+        let candidate = self.regime_filter.regime();
+        let change = if candidate != self.regime
+            && probabilities[candidate.index()] >= REGIME_CHANGE_PROBABILITY
+        {
+            let change = RegimeChange {
+                actor_a: self.actor_a.clone(),
+                actor_b: self.actor_b.clone(),
+                from: self.regime,
+                to: candidate,
+                probabilities,
+                timestamp,
+            };
+            self.regime = candidate;
+            Some(change)
+        } else {
+            None
+        };
+
+        // Determine alert level; thinly observed dyads cannot escalate past Yellow
+        let mut alert_level = Self::compute_alert_level(phi, &result, phi_trend);
+        if reliability < self.min_reliability {
+            alert_level = alert_level.min(AlertLevel::Yellow);
+        }
 
-        let message = Self::generate_message(
+        let i18n = AlertMessage::dyad(
             &self.actor_a,
             &self.actor_b,
             alert_level,
@@ -127,25 +297,34 @@ impl DyadTracker {
         );
 
         let alert = NucleationAlert {
+            alert_id: 0,
             actor_a: self.actor_a.clone(),
             actor_b: self.actor_b.clone(),
             alert_level,
             phase: result.phase,
             phi,
             phi_trend,
-            confidence: result.confidence,
+            confidence: result.confidence * reliability,
             timestamp,
-            message,
+            message: render_english(&i18n),
+            i18n: Some(i18n),
+            regime: self.regime,
+            reliability,
+            snapshot: None,
+            status: AlertStatus::Open,
+            realerts: 0,
+            notified_at: timestamp,
         };
 
         self.last_alert = Some(alert.clone());
 
         // Only return if significant
-        if alert_level >= AlertLevel::Yellow {
+        let alert = if alert_level >= AlertLevel::Yellow {
             Some(alert)
         } else {
             None
-        }
+        };
+        (alert, change)
     }
 
     fn compute_alert_level(phi: f64, result: &crate::variance::InflectionResult, phi_trend: f64) -> AlertLevel {
@@ -170,9 +349,7 @@ impl DyadTracker {
                 }
             }
             Phase::Stable => {
-                if phi > 2.0 {
-                    AlertLevel::Yellow
-                } else if phi > 1.0 && phi_trend > 0.05 {
+                if phi > 2.0 || (phi > 1.0 && phi_trend > 0.05) {
                     AlertLevel::Yellow
                 } else {
                     AlertLevel::Green
@@ -181,41 +358,137 @@ impl DyadTracker {
         }
     }
 
-    fn generate_message(
-        actor_a: &str,
-        actor_b: &str,
-        level: AlertLevel,
-        phase: Phase,
-        phi: f64,
-        phi_trend: f64,
-    ) -> String {
-        let trend_desc = if phi_trend > 0.05 {
-            "increasing"
-        } else if phi_trend < -0.05 {
-            "decreasing"
-        } else {
-            "stable"
-        };
+}
 
-        match level {
-            AlertLevel::Red => format!(
-                "NUCLEATION ALERT: {}-{} divergence critical (Φ={:.2}, {}). Transition imminent.",
-                actor_a, actor_b, phi, trend_desc
-            ),
-            AlertLevel::Orange => format!(
-                "WARNING: {}-{} showing pre-transition signature (Φ={:.2}, {}, phase={:?})",
-                actor_a, actor_b, phi, trend_desc, phase
-            ),
-            AlertLevel::Yellow => format!(
-                "WATCH: {}-{} divergence elevated (Φ={:.2}, {})",
-                actor_a, actor_b, phi, trend_desc
+/// Per-actor signal monitored for rate-of-change alerts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ActorSignal {
+    /// Windowed prediction error of the actor's scheme
+    Grievance,
+    /// Shannon entropy of the actor's scheme
+    Entropy,
+}
+
+impl ActorSignal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActorSignal::Grievance => "grievance",
+            ActorSignal::Entropy => "entropy",
+        }
+    }
+}
+
+/// Actor-level alert from variance dynamics of a single signal.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActorAlert {
+    pub actor: String,
+    pub signal: ActorSignal,
+    pub alert_level: AlertLevel,
+    pub phase: Phase,
+    pub value: f64,
+    pub trend: f64,
+    pub confidence: f64,
+    pub timestamp: f64,
+    pub message: String,
+    /// `message` as a catalog key and arguments, for localized rendering
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub i18n: Option<AlertMessage>,
+}
+
+/// Per-actor monitors for grievance and scheme entropy.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct ActorTracker {
+    grievance: SignalMonitor,
+    entropy: SignalMonitor,
+}
+
+impl ActorTracker {
+    fn new(actor: &str, config: &VarianceConfig) -> Self {
+        // Falling grievance is de-escalation; entropy matters both ways
+        // (collapse = hardening worldview, rise = fragmenting one)
+        let monitor = |source, policy| {
+            let mut monitor_config = SignalMonitorConfig::new(source)
+                .with_variance_config(config.clone())
+                .with_policy(policy);
+            monitor_config.history_capacity = 10;
+            SignalMonitor::new(monitor_config)
+        };
+        Self {
+            grievance: monitor(
+                SignalSource::Grievance { actor: actor.to_string() },
+                AlertPolicy::rising(),
             ),
-            AlertLevel::Green => format!(
-                "{}-{} normal (Φ={:.2})",
-                actor_a, actor_b, phi
+            entropy: monitor(
+                SignalSource::Entropy { actor: actor.to_string() },
+                AlertPolicy::default(),
             ),
         }
     }
+
+    fn update(
+        &mut self,
+        actor: &str,
+        grievance: f64,
+        entropy: f64,
+        timestamp: f64,
+    ) -> Vec<ActorAlert> {
+        let signals = [
+            (ActorSignal::Grievance, grievance, &mut self.grievance),
+            (ActorSignal::Entropy, entropy, &mut self.entropy),
+        ];
+        signals
+            .into_iter()
+            .filter_map(|(signal, value, monitor)| {
+                let alert = monitor.update(value, timestamp)?;
+                let i18n = Self::generate_message(
+                    actor,
+                    signal,
+                    alert.alert_level,
+                    value,
+                    alert.trend,
+                );
+                Some(ActorAlert {
+                    actor: actor.to_string(),
+                    signal,
+                    alert_level: alert.alert_level,
+                    phase: alert.phase,
+                    value,
+                    trend: alert.trend,
+                    confidence: alert.confidence,
+                    timestamp,
+                    message: render_english(&i18n),
+                    i18n: Some(i18n),
+                })
+            })
+            .collect()
+    }
+
+    fn generate_message(
+        actor: &str,
+        signal: ActorSignal,
+        level: AlertLevel,
+        value: f64,
+        trend: f64,
+    ) -> AlertMessage {
+        let direction = match signal {
+            ActorSignal::Grievance => "direction.accelerating",
+            ActorSignal::Entropy if trend < 0.0 => "direction.collapsing",
+            ActorSignal::Entropy => "direction.dispersing",
+        };
+        let key = match level {
+            AlertLevel::Red | AlertLevel::Orange => "actor.warning",
+            AlertLevel::Yellow | AlertLevel::Green => "actor.watch",
+        };
+        AlertMessage::new(key)
+            .text("actor", actor)
+            .term("signal", format!("signal.{}", signal.as_str()))
+            .term("direction", direction)
+            .number("value", value)
+            .number("trend", trend)
+    }
 }
 
 /// Shepherd Dynamics: Unified early warning system.
@@ -226,10 +499,44 @@ impl DyadTracker {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ShepherdDynamics {
     model: CompressionDynamicsModel,
-    dyad_trackers: HashMap<(String, String), DyadTracker>,
+    dyad_trackers: HashMap<(ActorId, ActorId), DyadTracker>,
     variance_config: VarianceConfig,
+    phi_history_capacity: usize,
     current_timestamp: f64,
     alert_history: Vec<NucleationAlert>,
+    regime_model: RegimeModel,
+    regime_changes: Vec<RegimeChange>,
+    min_reliability: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    actor_trackers: HashMap<ActorId, ActorTracker>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    actor_alert_history: Vec<ActorAlert>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    signal_monitors: Vec<SignalMonitor>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    signal_alert_history: Vec<SignalAlert>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    snapshot_depth: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    recent_observations: HashMap<ActorId, VecDeque<(f64, Vec<f64>)>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    next_alert_id: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    realert_interval: Option<f64>,
+    /// Actors observed since their dyads were last checked
+    #[cfg_attr(feature = "serde", serde(default))]
+    dirty_actors: HashSet<ActorId>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    full_sweep_interval: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    last_full_sweep: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    dyad_checks: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    watchlists: Vec<Watchlist>,
+    /// Immediate watchlist notifications awaiting `poll_notifications`
+    #[cfg_attr(feature = "serde", serde(default))]
+    notifications: Vec<Notification>,
 }
 
 impl ShepherdDynamics {
@@ -239,8 +546,26 @@ impl ShepherdDynamics {
             model: CompressionDynamicsModel::new(n_categories),
             dyad_trackers: HashMap::new(),
             variance_config: VarianceConfig::default(),
+            phi_history_capacity: DEFAULT_PHI_HISTORY_CAPACITY,
             current_timestamp: 0.0,
             alert_history: Vec::new(),
+            regime_model: RegimeModel::default(),
+            regime_changes: Vec::new(),
+            min_reliability: DEFAULT_MIN_RELIABILITY,
+            actor_trackers: HashMap::new(),
+            actor_alert_history: Vec::new(),
+            signal_monitors: Vec::new(),
+            signal_alert_history: Vec::new(),
+            snapshot_depth: 0,
+            recent_observations: HashMap::new(),
+            next_alert_id: 0,
+            realert_interval: None,
+            dirty_actors: HashSet::new(),
+            full_sweep_interval: None,
+            last_full_sweep: None,
+            dyad_checks: 0,
+            watchlists: Vec::new(),
+            notifications: Vec::new(),
         }
     }
 
@@ -256,6 +581,162 @@ impl ShepherdDynamics {
         self
     }
 
+    /// Compute Φ with a robust KL variant (capped or trimmed).
+    pub fn with_kl_variant(mut self, variant: KlVariant) -> Self {
+        self.model = self.model.with_kl_variant(variant);
+        self
+    }
+
+    /// Configure how many phi samples are retained per dyad.
+    pub fn with_phi_history_capacity(mut self, capacity: usize) -> Self {
+        self.phi_history_capacity = capacity.max(1);
+        self.model = self.model.with_phi_history_capacity(capacity);
+        self
+    }
+
+    /// Configure the reliability dyads need before alerting above Yellow.
+    ///
+    /// Reliability is the product of both actors' observation-backed
+    /// weights (see [`CompressionScheme::reliability`]); 0 disables the cap.
+    pub fn with_min_reliability(mut self, min_reliability: f64) -> Self {
+        self.min_reliability = min_reliability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Configure the regime HMM parameters used for new dyads.
+    pub fn with_regime_model(mut self, model: RegimeModel) -> Self {
+        self.regime_model = model;
+        self
+    }
+
+    /// Configure the grievance averaging window for new actors.
+    pub fn with_grievance_window(mut self, window: usize) -> Self {
+        self.model = self.model.with_grievance_window(window);
+        self
+    }
+
+    /// Attach an [`AlertSnapshot`] to Red alerts.
+    ///
+    /// `depth` bounds the Φ points and the observations per actor kept for
+    /// snapshots; 0 (the default) disables them.
+    pub fn with_alert_snapshots(mut self, depth: usize) -> Self {
+        self.snapshot_depth = depth;
+        if depth == 0 {
+            self.recent_observations.clear();
+        }
+        self
+    }
+
+    /// Re-raise open Red alerts left unacknowledged for `interval`.
+    ///
+    /// See [`poll_realerts`](Self::poll_realerts).
+    pub fn with_realert_interval(mut self, interval: f64) -> Self {
+        self.realert_interval = Some(interval.max(0.0));
+        self
+    }
+
+    /// Check every dyad at least once per `interval`.
+    ///
+    /// See [`check_scheduled_dyads`](Self::check_scheduled_dyads).
+    pub fn with_full_sweep_interval(mut self, interval: f64) -> Self {
+        self.full_sweep_interval = Some(interval.max(0.0));
+        self
+    }
+
+    /// Add a monitor over a model signal.
+    pub fn with_signal_monitor(mut self, config: SignalMonitorConfig) -> Self {
+        self.add_signal_monitor(config);
+        self
+    }
+
+    /// Add a monitor over a model signal, replacing one with the same name.
+    pub fn add_signal_monitor(&mut self, config: SignalMonitorConfig) {
+        let monitor = SignalMonitor::new(config);
+        match self.signal_monitors.iter_mut().find(|m| m.name() == monitor.name()) {
+            Some(existing) => *existing = monitor,
+            None => self.signal_monitors.push(monitor),
+        }
+    }
+
+    /// Remove a signal monitor by name.
+    pub fn remove_signal_monitor(&mut self, name: &str) -> Option<SignalMonitor> {
+        let index = self.signal_monitors.iter().position(|m| m.name() == name)?;
+        Some(self.signal_monitors.remove(index))
+    }
+
+    /// Signal monitor by name.
+    pub fn signal_monitor(&self, name: &str) -> Option<&SignalMonitor> {
+        self.signal_monitors.iter().find(|m| m.name() == name)
+    }
+
+    /// Feed every signal monitor its current value.
+    pub fn poll_signal_monitors(&mut self, timestamp: f64) -> Vec<SignalAlert> {
+        let model = &self.model;
+        let alerts: Vec<SignalAlert> = self
+            .signal_monitors
+            .iter_mut()
+            .filter_map(|m| m.poll(model, timestamp))
+            .collect();
+        self.signal_alert_history.extend(alerts.iter().cloned());
+        alerts
+    }
+
+    /// Alerts raised by signal monitors, oldest first.
+    pub fn signal_alerts(&self) -> &[SignalAlert] {
+        &self.signal_alert_history
+    }
+
+    /// Add a watchlist.
+    pub fn with_watchlist(mut self, config: WatchlistConfig) -> Self {
+        self.add_watchlist(config);
+        self
+    }
+
+    /// Add a watchlist, replacing one with the same name.
+    ///
+    /// A replaced watchlist's undelivered digests are dropped.
+    pub fn add_watchlist(&mut self, config: WatchlistConfig) {
+        let watchlist = Watchlist::new(config);
+        match self.watchlists.iter_mut().find(|w| w.name() == watchlist.name()) {
+            Some(existing) => *existing = watchlist,
+            None => self.watchlists.push(watchlist),
+        }
+    }
+
+    /// Remove a watchlist by name.
+    pub fn remove_watchlist(&mut self, name: &str) -> Option<Watchlist> {
+        let index = self.watchlists.iter().position(|w| w.name() == name)?;
+        Some(self.watchlists.remove(index))
+    }
+
+    /// Watchlist by name.
+    pub fn watchlist(&self, name: &str) -> Option<&Watchlist> {
+        self.watchlists.iter().find(|w| w.name() == name)
+    }
+
+    /// All watchlists, in the order added.
+    pub fn watchlists(&self) -> &[Watchlist] {
+        &self.watchlists
+    }
+
+    /// Watchlist notifications ready for delivery.
+    ///
+    /// Immediate notifications for dyad alerts raised since the last call,
+    /// followed by digests whose period has ended by `timestamp`.
+    pub fn poll_notifications(&mut self, timestamp: f64) -> Vec<Notification> {
+        let mut notifications = std::mem::take(&mut self.notifications);
+        for watchlist in &mut self.watchlists {
+            notifications.extend(watchlist.poll(timestamp));
+        }
+        notifications
+    }
+
+    fn notify_watchlists(&mut self, alert: &NucleationAlert) {
+        for watchlist in &mut self.watchlists {
+            self.notifications.extend(watchlist.route(alert));
+        }
+    }
+
     /// Register a new actor with initial compression scheme.
     pub fn register_actor(
         &mut self,
@@ -274,20 +755,22 @@ impl ShepherdDynamics {
     ) -> Vec<NucleationAlert> {
         self.current_timestamp = timestamp;
 
-        // Update the model
+        // Update the model and the actor's own signals
         self.model.update_actor(actor_id, observation, timestamp);
+        self.track_actor(actor_id, timestamp);
+        self.record_observation(actor_id, observation, timestamp);
 
         // Recompute potentials and check for nucleation with all other actors
-        let actors: Vec<String> = self.model.actors()
-            .iter()
-            .filter(|&&a| a != actor_id)
-            .map(|&s| s.to_string())
-            .collect();
+        let Some(id) = self.model.actor_id(actor_id) else {
+            return Vec::new();
+        };
+        let n = self.model.actor_count() as u32;
+        self.dirty_actors.remove(&id);
 
         let mut alerts = Vec::new();
 
-        for other in actors {
-            if let Some(alert) = self.check_dyad(actor_id, &other, timestamp) {
+        for other in (0..n).map(ActorId).filter(|&other| other != id) {
+            if let Some(alert) = self.check_dyad_by_id(id, other, timestamp) {
                 alerts.push(alert);
             }
         }
@@ -295,53 +778,247 @@ impl ShepherdDynamics {
         alerts
     }
 
+    /// Update an actor's compression scheme without checking any dyads.
+    ///
+    /// Use with [`check_all_dyads`](Self::check_all_dyads) to batch several
+    /// observations into a single check per dyad.
+    pub fn observe_actor(&mut self, actor_id: &str, observation: &[f64], timestamp: f64) {
+        self.current_timestamp = timestamp;
+        self.model.update_actor(actor_id, observation, timestamp);
+        self.track_actor(actor_id, timestamp);
+        self.record_observation(actor_id, observation, timestamp);
+        if let Some(id) = self.model.actor_id(actor_id) {
+            self.dirty_actors.insert(id);
+        }
+    }
+
+    /// Keep an actor's latest observations for alert snapshots.
+    fn record_observation(&mut self, actor_id: &str, observation: &[f64], timestamp: f64) {
+        if self.snapshot_depth == 0 {
+            return;
+        }
+        let Some(id) = self.model.actor_id(actor_id) else {
+            return;
+        };
+        let recent = self.recent_observations.entry(id).or_default();
+        recent.push_back((timestamp, observation.to_vec()));
+        if recent.len() > self.snapshot_depth {
+            recent.pop_front();
+        }
+    }
+
+    /// Evidence for an alert on the dyad `(a, b)`.
+    fn snapshot(&self, a: ActorId, b: ActorId, phi_history: &VecDeque<(f64, f64)>) -> Option<AlertSnapshot> {
+        let depth = self.snapshot_depth;
+        let name_a = self.model.actor_name(a)?;
+        let name_b = self.model.actor_name(b)?;
+        let grievance = |name| self.model.get_grievance(name).map_or(0.0, |g| g.window_error);
+
+        let mut recent_observations: Vec<RecordedObservation> = [(a, name_a), (b, name_b)]
+            .iter()
+            .flat_map(|&(id, name)| {
+                self.recent_observations
+                    .get(&id)
+                    .into_iter()
+                    .flatten()
+                    .map(move |(timestamp, observation)| RecordedObservation {
+                        actor_id: name.to_string(),
+                        observation: observation.clone(),
+                        timestamp: *timestamp,
+                    })
+            })
+            .collect();
+        recent_observations.sort_by(|x, y| x.timestamp.total_cmp(&y.timestamp));
+        let excess = recent_observations.len().saturating_sub(depth);
+        recent_observations.drain(..excess);
+
+        Some(AlertSnapshot {
+            scheme_a: self.model.get_scheme(name_a)?.distribution().to_vec(),
+            scheme_b: self.model.get_scheme(name_b)?.distribution().to_vec(),
+            phi_history: phi_history.iter().skip(phi_history.len().saturating_sub(depth)).copied().collect(),
+            grievance_a: grievance(name_a),
+            grievance_b: grievance(name_b),
+            recent_observations,
+        })
+    }
+
+    /// Feed an actor's grievance and scheme entropy to its trackers.
+    fn track_actor(&mut self, actor_id: &str, timestamp: f64) {
+        let Some(id) = self.model.actor_id(actor_id) else {
+            return;
+        };
+        let grievance = self.model.get_grievance(actor_id).map_or(0.0, |g| g.window_error);
+        let entropy = self.model.get_scheme(actor_id).map_or(0.0, |s| s.entropy());
+        let config = &self.variance_config;
+        let alerts = self
+            .actor_trackers
+            .entry(id)
+            .or_insert_with(|| ActorTracker::new(actor_id, config))
+            .update(actor_id, grievance, entropy, timestamp);
+        self.actor_alert_history.extend(alerts);
+    }
+
+    /// Actor-level alerts on grievance and entropy dynamics, oldest first.
+    ///
+    /// The detectors calibrate their baseline from unit scale, so small
+    /// signals such as grievance need a long history before alerting.
+    pub fn actor_alerts(&self) -> &[ActorAlert] {
+        &self.actor_alert_history
+    }
+
     /// Check a specific actor dyad for nucleation.
     pub fn check_dyad(&mut self, actor_a: &str, actor_b: &str, timestamp: f64) -> Option<NucleationAlert> {
+        let a = self.model.actor_id(actor_a)?;
+        let b = self.model.actor_id(actor_b)?;
+        self.check_dyad_by_id(a, b, timestamp)
+    }
+
+    /// Check a dyad of actor handles for nucleation.
+    pub fn check_dyad_by_id(&mut self, a: ActorId, b: ActorId, timestamp: f64) -> Option<NucleationAlert> {
         // Compute current potential
-        let potential = self.model.conflict_potential(actor_a, actor_b)?;
+        let potential = self.model.conflict_potential_by_id(a, b)?;
+        self.dyad_checks += 1;
 
         // Get or create dyad tracker
-        let key = Self::dyad_key(actor_a, actor_b);
+        let key = dyad(a, b);
+        let model = &self.model;
         let tracker = self.dyad_trackers
             .entry(key)
             .or_insert_with(|| {
                 DyadTracker::new(
-                    actor_a.to_string(),
-                    actor_b.to_string(),
+                    model.actor_name(key.0).unwrap_or_default().to_string(),
+                    model.actor_name(key.1).unwrap_or_default().to_string(),
                     self.variance_config.clone(),
+                    self.phi_history_capacity,
+                    self.regime_model.clone(),
+                    self.min_reliability,
                 )
             });
 
+        // Mean grievance of the two actors
+        let grievance = [&tracker.actor_a, &tracker.actor_b]
+            .iter()
+            .filter_map(|id| model.get_grievance(id))
+            .map(|g| g.window_error)
+            .sum::<f64>()
+            / 2.0;
+
         // Update tracker with new phi
-        let alert = tracker.update(potential.phi, timestamp);
+        let (mut alert, change) = tracker.update(potential.phi, timestamp, grievance, potential.reliability);
 
-        if let Some(ref a) = alert {
+        if let Some(a) = alert.as_mut() {
+            // Capture evidence for Red alerts
+            if self.snapshot_depth > 0 && a.alert_level == AlertLevel::Red {
+                let tracker = &self.dyad_trackers[&key];
+                a.snapshot = self.snapshot(key.0, key.1, &tracker.phi_history);
+            }
+            self.next_alert_id += 1;
+            a.alert_id = self.next_alert_id;
+            if let Some(tracker) = self.dyad_trackers.get_mut(&key) {
+                tracker.last_alert = Some(a.clone());
+            }
             self.alert_history.push(a.clone());
+            self.notify_watchlists(a);
+        }
+        if let Some(change) = change {
+            self.regime_changes.push(change);
         }
 
         alert
     }
 
+    /// Current regime probabilities `[calm, tension, crisis]` for a dyad.
+    pub fn dyad_regime_probabilities(&self, actor_a: &str, actor_b: &str) -> Option<[f64; N_REGIMES]> {
+        self.tracker(actor_a, actor_b).map(|t| t.regime_filter.probabilities())
+    }
+
+    /// Current reported regime for a dyad.
+    pub fn dyad_regime(&self, actor_a: &str, actor_b: &str) -> Option<Regime> {
+        self.tracker(actor_a, actor_b).map(|t| t.regime)
+    }
+
+    /// Re-estimate a dyad's regime HMM from its own feature history.
+    ///
+    /// Returns the fitted log-likelihood, or `None` if the dyad is unknown
+    /// or has too little history.
+    pub fn fit_dyad_regimes(&mut self, actor_a: &str, actor_b: &str) -> Option<f64> {
+        let key = dyad(self.model.actor_id(actor_a)?, self.model.actor_id(actor_b)?);
+        let tracker = self.dyad_trackers.get_mut(&key)?;
+        let history: Vec<RegimeFeatures> = tracker.features.iter().copied().collect();
+        tracker.regime_filter.fit(&history, REGIME_FIT_ITERATIONS)
+    }
+
+    /// All regime changes reported so far.
+    pub fn regime_changes(&self) -> &[RegimeChange] {
+        &self.regime_changes
+    }
+
+    fn tracker(&self, actor_a: &str, actor_b: &str) -> Option<&DyadTracker> {
+        let key = dyad(self.model.actor_id(actor_a)?, self.model.actor_id(actor_b)?);
+        self.dyad_trackers.get(&key)
+    }
+
     /// Check all dyads for nucleation.
     pub fn check_all_dyads(&mut self, timestamp: f64) -> Vec<NucleationAlert> {
-        let actors: Vec<String> = self.model.actors()
-            .iter()
-            .map(|&s| s.to_string())
-            .collect();
+        let n = self.model.actor_count() as u32;
 
         let mut alerts = Vec::new();
 
-        for i in 0..actors.len() {
-            for j in (i + 1)..actors.len() {
-                if let Some(alert) = self.check_dyad(&actors[i], &actors[j], timestamp) {
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if let Some(alert) = self.check_dyad_by_id(ActorId(i), ActorId(j), timestamp) {
                     alerts.push(alert);
                 }
             }
         }
+        self.dirty_actors.clear();
+        self.last_full_sweep = Some(timestamp);
 
         alerts
     }
 
+    /// Check the dyads that may have changed since they were last checked.
+    ///
+    /// A dyad is checked when either actor was observed through
+    /// [`observe_actor`](Self::observe_actor) since then, so a quiet steady
+    /// state costs nothing rather than a full O(n²) pass. With a
+    /// [full sweep interval](Self::with_full_sweep_interval), every dyad is
+    /// checked once that interval has passed since the last sweep, keeping
+    /// the trackers of quiet dyads sampled.
+    pub fn check_scheduled_dyads(&mut self, timestamp: f64) -> Vec<NucleationAlert> {
+        let sweep_due = self.full_sweep_interval.is_some_and(|interval| {
+            self.last_full_sweep.is_none_or(|last| timestamp - last >= interval)
+        });
+        if sweep_due {
+            return self.check_all_dyads(timestamp);
+        }
+
+        let mut dirty: Vec<ActorId> = self.dirty_actors.drain().collect();
+        dirty.sort_unstable();
+        let n = self.model.actor_count() as u32;
+
+        let mut alerts = Vec::new();
+
+        for (i, &a) in dirty.iter().enumerate() {
+            for b in (0..n).map(ActorId) {
+                // A dyad of two dirty actors is checked once, from the first
+                if b == a || dirty[..i].binary_search(&b).is_ok() {
+                    continue;
+                }
+                if let Some(alert) = self.check_dyad_by_id(a, b, timestamp) {
+                    alerts.push(alert);
+                }
+            }
+        }
+
+        alerts
+    }
+
+    /// Dyad checks run so far.
+    pub fn dyad_checks(&self) -> u64 {
+        self.dyad_checks
+    }
+
     /// Get current conflict potential between two actors.
     pub fn conflict_potential(&mut self, actor_a: &str, actor_b: &str) -> Option<ConflictPotential> {
         self.model.conflict_potential(actor_a, actor_b)
@@ -363,14 +1040,52 @@ impl ShepherdDynamics {
     }
 
     /// Get phi history for a dyad.
-    pub fn phi_history(&self, actor_a: &str, actor_b: &str) -> Option<&Vec<(f64, f64)>> {
-        let key = Self::dyad_key(actor_a, actor_b);
+    pub fn phi_history(&self, actor_a: &str, actor_b: &str) -> Option<&VecDeque<(f64, f64)>> {
+        let key = dyad(self.model.actor_id(actor_a)?, self.model.actor_id(actor_b)?);
         self.dyad_trackers.get(&key).map(|t| &t.phi_history)
     }
 
+    /// Get phi history for a dyad together with its annotations.
+    pub fn annotated_phi_history(&self, actor_a: &str, actor_b: &str) -> Option<AnnotatedPhiHistory> {
+        let history = self.phi_history(actor_a, actor_b)?;
+        let annotations = self
+            .model
+            .dyad_annotations(actor_a, actor_b)
+            .into_iter()
+            .cloned()
+            .collect();
+        Some(AnnotatedPhiHistory::new(history, annotations))
+    }
+
+    /// Mark a context event on an actor; `false` if the actor is unknown.
+    pub fn annotate_actor(&mut self, actor_id: &str, timestamp: f64, label: impl Into<String>) -> bool {
+        self.model.annotate_actor(actor_id, timestamp, label)
+    }
+
+    /// Mark a context event on a dyad; `false` if either actor is unknown.
+    pub fn annotate_dyad(
+        &mut self,
+        actor_a: &str,
+        actor_b: &str,
+        timestamp: f64,
+        label: impl Into<String>,
+    ) -> bool {
+        self.model.annotate_dyad(actor_a, actor_b, timestamp, label)
+    }
+
+    /// Remove every annotation with `label`, returning how many were removed.
+    pub fn remove_annotations(&mut self, label: &str) -> usize {
+        self.model.remove_annotations(label)
+    }
+
+    /// All annotations, oldest first.
+    pub fn annotations(&self) -> &[Annotation] {
+        self.model.annotations()
+    }
+
     /// Get last alert for a dyad.
     pub fn last_alert(&self, actor_a: &str, actor_b: &str) -> Option<&NucleationAlert> {
-        let key = Self::dyad_key(actor_a, actor_b);
+        let key = dyad(self.model.actor_id(actor_a)?, self.model.actor_id(actor_b)?);
         self.dyad_trackers.get(&key)?.last_alert.as_ref()
     }
 
@@ -379,6 +1094,11 @@ impl ShepherdDynamics {
         self.model.actors()
     }
 
+    /// Number of categories in compression space.
+    pub fn n_categories(&self) -> usize {
+        self.model.n_categories
+    }
+
     /// Get recent alert history.
     pub fn alert_history(&self) -> &[NucleationAlert] {
         &self.alert_history
@@ -391,11 +1111,98 @@ impl ShepherdDynamics {
             .collect()
     }
 
-    fn dyad_key(a: &str, b: &str) -> (String, String) {
-        if a < b {
-            (a.to_string(), b.to_string())
-        } else {
-            (b.to_string(), a.to_string())
+    /// Recorded alert by ID.
+    pub fn alert(&self, alert_id: u64) -> Option<&NucleationAlert> {
+        self.alert_history.iter().find(|a| a.alert_id == alert_id)
+    }
+
+    /// Alerts not yet resolved, oldest first.
+    pub fn unresolved_alerts(&self) -> Vec<&NucleationAlert> {
+        self.alert_history.iter()
+            .filter(|a| a.is_unresolved())
+            .collect()
+    }
+
+    /// Mark an open alert as seen by `who`, stopping re-alerts.
+    ///
+    /// Returns false for unknown, already acknowledged or resolved alerts.
+    pub fn acknowledge_alert(&mut self, alert_id: u64, who: &str) -> bool {
+        let at = self.current_timestamp;
+        match self.alert_history.iter_mut().find(|a| a.alert_id == alert_id) {
+            Some(alert) if alert.status == AlertStatus::Open => {
+                alert.status = AlertStatus::Acknowledged { by: who.to_string(), at };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Close out an alert, acknowledged or not.
+    ///
+    /// Returns false for unknown or already resolved alerts.
+    pub fn resolve_alert(&mut self, alert_id: u64) -> bool {
+        let at = self.current_timestamp;
+        match self.alert_history.iter_mut().find(|a| a.alert_id == alert_id) {
+            Some(alert) if alert.is_unresolved() => {
+                alert.status = AlertStatus::Resolved { at };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Re-raise Red alerts still open `realert_interval` after their last
+    /// notification.
+    ///
+    /// Returned alerts keep their ID, so a responder acknowledges the
+    /// original. Nothing is re-raised unless an interval is configured.
+    pub fn poll_realerts(&mut self, timestamp: f64) -> Vec<NucleationAlert> {
+        let Some(interval) = self.realert_interval else {
+            return Vec::new();
+        };
+        self.alert_history
+            .iter_mut()
+            .filter(|a| {
+                a.alert_level == AlertLevel::Red
+                    && a.status == AlertStatus::Open
+                    && timestamp - a.notified_at >= interval
+            })
+            .map(|a| {
+                a.realerts += 1;
+                a.notified_at = timestamp;
+                a.clone()
+            })
+            .collect()
+    }
+
+    /// Shrink long-running state, relative to the current timestamp.
+    ///
+    /// Φ points older than `raw_window` are averaged per time bucket, in the
+    /// dyad trackers and the underlying model alike; resolved alerts older
+    /// than `alert_retention` are dropped; spare capacity left behind is
+    /// released. Recent Φ, which drives alert levels and trends, is
+    /// untouched. Meant to be called periodically, e.g. by
+    /// [`ShepherdRunner`](crate::runner::ShepherdRunner).
+    pub fn compact(&mut self, policy: &CompactionPolicy) -> CompactionReport {
+        let now = self.current_timestamp;
+        let cutoff = now - policy.raw_window;
+
+        let mut phi_points_merged = self.model.compact_phi_history(cutoff, policy.bucket_width);
+        for tracker in self.dyad_trackers.values_mut() {
+            phi_points_merged += merge_phi_points(&mut tracker.phi_history, cutoff, policy.bucket_width);
+            tracker.phi_history.shrink_to_fit();
+        }
+
+        let before = self.alert_history.len();
+        self.alert_history.retain(|a| match a.status {
+            AlertStatus::Resolved { at } => now - at <= policy.alert_retention,
+            _ => true,
+        });
+        self.alert_history.shrink_to_fit();
+
+        CompactionReport {
+            phi_points_merged,
+            alerts_pruned: before - self.alert_history.len(),
         }
     }
 }
@@ -462,6 +1269,207 @@ mod tests {
         assert!(!history.unwrap().is_empty());
     }
 
+    #[test]
+    fn test_phi_history_capacity() {
+        let mut shepherd = ShepherdDynamics::new(3)
+            .with_phi_history_capacity(16)
+            .with_grievance_window(5);
+
+        shepherd.register_actor("A", Some(vec![0.5, 0.3, 0.2]));
+        shepherd.register_actor("B", Some(vec![0.2, 0.3, 0.5]));
+
+        for i in 0..50 {
+            shepherd.update_actor("A", &[0.6, 0.3, 0.1], i as f64);
+        }
+
+        let history = shepherd.phi_history("A", "B").unwrap();
+        assert_eq!(history.len(), 16);
+        assert_eq!(history.back().unwrap().0, 49.0);
+        assert_eq!(shepherd.get_grievance("A").unwrap().window_size(), 5);
+    }
+
+    #[test]
+    fn test_regime_change_reported() {
+        let mut shepherd = ShepherdDynamics::new(3).with_learning_rate(0.5);
+
+        shepherd.register_actor("A", Some(vec![0.4, 0.3, 0.3]));
+        shepherd.register_actor("B", Some(vec![0.4, 0.3, 0.3]));
+        assert!(shepherd.dyad_regime_probabilities("A", "B").is_none());
+
+        for i in 0..20 {
+            shepherd.update_actor("A", &[0.4, 0.3, 0.3], i as f64);
+        }
+        assert_eq!(shepherd.dyad_regime("A", "B"), Some(Regime::Calm));
+
+        // A's worldview collapses onto one category
+        for i in 20..40 {
+            shepherd.update_actor("A", &[0.98, 0.01, 0.01], i as f64);
+        }
+
+        let probabilities = shepherd.dyad_regime_probabilities("A", "B").unwrap();
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_ne!(shepherd.dyad_regime("A", "B"), Some(Regime::Calm));
+
+        let change = &shepherd.regime_changes()[0];
+        assert_eq!(change.from, Regime::Calm);
+        assert!(change.timestamp >= 20.0);
+        assert!(shepherd.fit_dyad_regimes("A", "B").is_some());
+    }
+
+    #[test]
+    fn test_new_actors_cannot_trigger_red() {
+        let mut shepherd = ShepherdDynamics::new(5)
+            .with_variance_config(VarianceConfig::sensitive());
+
+        shepherd.register_actor("A", Some(vec![0.9, 0.04, 0.03, 0.02, 0.01]));
+        shepherd.register_actor("B", Some(vec![0.01, 0.02, 0.03, 0.04, 0.9]));
+
+        // A couple of updates on extreme, unobserved priors
+        for i in 0..3 {
+            shepherd.update_actor("A", &[0.9, 0.04, 0.03, 0.02, 0.01], i as f64);
+        }
+        let alert = shepherd.last_alert("A", "B").unwrap();
+        assert!(alert.phi > 2.0);
+        assert_eq!(alert.reliability, 0.0);
+        assert!(alert.alert_level <= AlertLevel::Yellow);
+
+        // Once both actors have history the cap lifts
+        for i in 3..40 {
+            shepherd.update_actor("A", &[0.9, 0.04, 0.03, 0.02, 0.01], i as f64);
+            shepherd.update_actor("B", &[0.01, 0.02, 0.03, 0.04, 0.9], i as f64);
+        }
+        let alert = shepherd.last_alert("A", "B").unwrap();
+        assert!(alert.reliability > DEFAULT_MIN_RELIABILITY);
+        assert!(alert.alert_level >= AlertLevel::Yellow);
+    }
+
+    #[test]
+    fn test_red_alerts_carry_snapshots() {
+        let run = |depth: usize| {
+            let config = VarianceConfig {
+                window_size: 10,
+                smoothing_window: 3,
+                ..VarianceConfig::sensitive()
+            };
+            let mut shepherd = ShepherdDynamics::new(3)
+                .with_learning_rate(0.8)
+                .with_variance_config(config)
+                .with_alert_snapshots(depth);
+            shepherd.register_actor("A", Some(vec![0.4, 0.3, 0.3]));
+            shepherd.register_actor("B", Some(vec![0.3, 0.4, 0.3]));
+
+            // Long noisy calm so the detector baseline settles, then a break
+            let mut seed = 5u64;
+            for i in 0..200 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                let jitter = 0.02 * (seed >> 33) as f64 / (1u64 << 31) as f64;
+                shepherd.update_actor("A", &[0.4 + jitter, 0.3, 0.3], i as f64);
+                shepherd.update_actor("B", &[0.3, 0.4, 0.3], i as f64);
+            }
+            for i in 200..210 {
+                shepherd.update_actor("A", &[0.9, 0.05, 0.05], i as f64);
+                shepherd.update_actor("B", &[0.05, 0.05, 0.9], i as f64);
+            }
+            shepherd
+        };
+
+        let shepherd = run(4);
+        let red = shepherd
+            .alert_history()
+            .iter()
+            .find(|a| a.alert_level == AlertLevel::Red)
+            .unwrap();
+        let snapshot = red.snapshot.as_ref().unwrap();
+        assert_eq!(snapshot.phi_history.len(), 4);
+        assert_eq!(snapshot.phi_history[3], (red.timestamp, red.phi));
+        assert_eq!(snapshot.scheme_b, vec![0.3, 0.4, 0.3]);
+        assert_eq!(snapshot.recent_observations.len(), 4);
+        let latest = snapshot.recent_observations.last().unwrap();
+        assert_eq!(latest.actor_id, "A");
+        assert_eq!(latest.observation, vec![0.9, 0.05, 0.05]);
+        assert!(shepherd
+            .alert_history()
+            .iter()
+            .filter(|a| a.alert_level < AlertLevel::Red)
+            .all(|a| a.snapshot.is_none()));
+
+        let shepherd = run(0);
+        assert!(shepherd.alert_history().iter().any(|a| a.alert_level == AlertLevel::Red));
+        assert!(shepherd.alert_history().iter().all(|a| a.snapshot.is_none()));
+    }
+
+    #[test]
+    fn test_alert_acknowledgment_lifecycle() {
+        let mut shepherd = ShepherdDynamics::new(3).with_realert_interval(10.0);
+        shepherd.register_actor("A", Some(vec![0.4, 0.3, 0.3]));
+        shepherd.register_actor("B", Some(vec![0.3, 0.4, 0.3]));
+        shepherd.update_actor("A", &[0.9, 0.05, 0.05], 0.0);
+
+        // Stand-ins for Red alerts raised by the detector
+        let mut red = shepherd.last_alert("A", "B").unwrap().clone();
+        red.alert_level = AlertLevel::Red;
+        for id in [1, 2] {
+            shepherd.alert_history.push(NucleationAlert { alert_id: id, ..red.clone() });
+        }
+
+        assert!(shepherd.poll_realerts(5.0).is_empty());
+        let realerts = shepherd.poll_realerts(12.0);
+        assert_eq!(realerts.len(), 2);
+        assert_eq!(realerts[0].alert_id, 1);
+        assert_eq!(shepherd.alert(1).unwrap().realerts, 1);
+        assert!(shepherd.poll_realerts(15.0).is_empty());
+
+        shepherd.update_actor("A", &[0.9, 0.05, 0.05], 20.0);
+        assert!(shepherd.acknowledge_alert(1, "analyst"));
+        assert!(!shepherd.acknowledge_alert(1, "analyst"));
+        assert_eq!(
+            shepherd.alert(1).unwrap().status,
+            AlertStatus::Acknowledged { by: "analyst".to_string(), at: 20.0 }
+        );
+        assert!(shepherd.resolve_alert(2));
+        assert!(!shepherd.resolve_alert(2));
+        assert!(!shepherd.acknowledge_alert(2, "analyst"));
+        assert!(!shepherd.resolve_alert(99));
+
+        // Neither an acknowledged nor a resolved alert is re-raised
+        assert!(shepherd.poll_realerts(100.0).is_empty());
+        assert_eq!(shepherd.unresolved_alerts().len(), 1);
+    }
+
+    #[test]
+    fn test_compaction() {
+        let mut shepherd = ShepherdDynamics::new(3);
+        shepherd.register_actor("A", Some(vec![0.4, 0.3, 0.3]));
+        shepherd.register_actor("B", Some(vec![0.3, 0.4, 0.3]));
+        for t in 0..100 {
+            shepherd.update_actor("A", &[0.5, 0.25, 0.25], t as f64);
+        }
+        let red = NucleationAlert {
+            alert_id: 1,
+            alert_level: AlertLevel::Red,
+            ..shepherd.last_alert("A", "B").unwrap().clone()
+        };
+        shepherd.alert_history = vec![red.clone(), NucleationAlert { alert_id: 2, ..red }];
+        shepherd.current_timestamp = 20.0;
+        assert!(shepherd.resolve_alert(1));
+        shepherd.current_timestamp = 99.0;
+
+        let report = shepherd.compact(&CompactionPolicy::new(20.0, 10.0, 50.0));
+        // 79 old points per history collapse into 8 buckets
+        assert_eq!(report.phi_points_merged, 2 * (79 - 8));
+        assert_eq!(report.alerts_pruned, 1);
+        assert_eq!(shepherd.alert_history()[0].alert_id, 2);
+
+        let history = shepherd.phi_history("A", "B").unwrap();
+        assert_eq!(history.len(), 8 + 21);
+        assert_eq!(history[0].0, 4.5);
+        assert_eq!(history[8].0, 79.0);
+        assert_eq!(shepherd.model.phi_history("A", "B").unwrap().len(), 29);
+
+        // A second pass over the same window changes nothing
+        assert_eq!(shepherd.compact(&CompactionPolicy::new(20.0, 10.0, 50.0)), CompactionReport::default());
+    }
+
     #[test]
     fn test_escalation_detection() {
         let mut shepherd = ShepherdDynamics::new(5)
@@ -503,4 +1511,137 @@ mod tests {
         // May or may not have alerts depending on dynamics
         println!("Actionable alerts: {}", alerts.len());
     }
+
+    #[test]
+    fn test_actor_signal_alerts() {
+        let mut shepherd = ShepherdDynamics::new(3)
+            .with_variance_config(VarianceConfig::sensitive())
+            .with_learning_rate(0.3)
+            .with_grievance_window(1);
+        shepherd.register_actor("USA", Some(vec![0.4, 0.3, 0.3]));
+        shepherd.register_actor("RUS", Some(vec![0.4, 0.3, 0.3]));
+
+        let mut seed = 7u64;
+        let mut noise = |scale: f64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((seed >> 33) as f64 / (1u64 << 31) as f64 - 0.5) * scale
+        };
+
+        // Calm period with a little observation noise
+        for i in 0..1500 {
+            let n = noise(0.01);
+            shepherd.observe_actor("USA", &[0.4 + n, 0.3, 0.3 - n], i as f64);
+        }
+        assert!(shepherd.actor_alerts().is_empty());
+
+        // Observations start swinging wildly
+        for i in 1500..1540 {
+            let n = noise(0.8);
+            shepherd.observe_actor("USA", &[0.45 + n, 0.1, 0.45 - n], i as f64);
+        }
+        let alert = shepherd
+            .actor_alerts()
+            .iter()
+            .find(|a| a.signal == ActorSignal::Grievance)
+            .unwrap();
+        assert_eq!(alert.actor, "USA");
+        assert!(alert.trend > 0.0);
+        assert!(alert.timestamp >= 1500.0);
+        assert!(alert.message.contains("USA grievance accelerating"));
+    }
+
+    #[test]
+    fn test_signal_monitors() {
+        let source = SignalSource::Phi {
+            actor_a: "A".into(),
+            actor_b: "B".into(),
+        };
+        let mut config = SignalMonitorConfig::new(source.clone())
+            .with_policy(AlertPolicy::rising().with_value_threshold(1.0));
+        config.policy.min_level = AlertLevel::Green;
+        let mut shepherd = ShepherdDynamics::new(3)
+            .with_signal_monitor(config)
+            .with_signal_monitor(SignalMonitorConfig::new(SignalSource::Polarization));
+        shepherd.register_actor("A", Some(vec![0.4, 0.3, 0.3]));
+        shepherd.register_actor("B", Some(vec![0.4, 0.3, 0.3]));
+
+        for i in 0..20 {
+            shepherd.observe_actor("A", &[0.98, 0.01, 0.01], i as f64);
+            shepherd.poll_signal_monitors(i as f64);
+        }
+        let monitor = shepherd.signal_monitor("phi:A-B").unwrap();
+        assert_eq!(monitor.history().len(), 20);
+        let last = monitor.last_alert().unwrap();
+        assert!(last.value > 1.0);
+        assert_eq!(last.alert_level, AlertLevel::Yellow);
+        // Only the Green-or-above phi monitor reported every poll
+        assert_eq!(shepherd.signal_alerts().len(), 20);
+
+        assert!(shepherd.remove_signal_monitor("polarization").is_some());
+        assert!(shepherd.signal_monitor("polarization").is_none());
+    }
+
+    #[test]
+    fn test_scheduled_dyad_checks() {
+        let mut shepherd = ShepherdDynamics::new(3).with_full_sweep_interval(100.0);
+        for name in ["A", "B", "C", "D", "E"] {
+            shepherd.register_actor(name, Some(vec![0.4, 0.3, 0.3]));
+        }
+
+        // First call sweeps all 10 dyads
+        shepherd.check_scheduled_dyads(0.0);
+        assert_eq!(shepherd.dyad_checks(), 10);
+
+        // Nothing observed: nothing checked
+        shepherd.check_scheduled_dyads(10.0);
+        assert_eq!(shepherd.dyad_checks(), 10);
+
+        // A and B observed: their 7 dyads, A-B once
+        shepherd.observe_actor("A", &[0.5, 0.3, 0.2], 20.0);
+        shepherd.observe_actor("B", &[0.2, 0.3, 0.5], 20.0);
+        shepherd.observe_actor("A", &[0.6, 0.2, 0.2], 21.0);
+        shepherd.check_scheduled_dyads(21.0);
+        assert_eq!(shepherd.dyad_checks(), 17);
+
+        // update_actor checks its dyads itself
+        shepherd.update_actor("C", &[0.3, 0.3, 0.4], 30.0);
+        assert_eq!(shepherd.dyad_checks(), 21);
+        shepherd.check_scheduled_dyads(30.0);
+        assert_eq!(shepherd.dyad_checks(), 21);
+
+        // The sweep comes round again
+        shepherd.check_scheduled_dyads(100.0);
+        assert_eq!(shepherd.dyad_checks(), 31);
+    }
+
+    #[test]
+    fn test_watchlist_notifications() {
+        use crate::watchlist::NotificationRule;
+
+        let mut shepherd = ShepherdDynamics::new(3).with_watchlist(
+            WatchlistConfig::new("flashpoints")
+                .with_dyad("A", "B")
+                .with_rule(NotificationRule::immediate(AlertLevel::Yellow, "ops")),
+        );
+        shepherd.register_actor("A", Some(vec![0.4, 0.3, 0.3]));
+        shepherd.register_actor("B", Some(vec![0.3, 0.4, 0.3]));
+        shepherd.update_actor("A", &[0.9, 0.05, 0.05], 0.0);
+
+        let mut alert = shepherd.last_alert("A", "B").unwrap().clone();
+        alert.alert_level = AlertLevel::Orange;
+        shepherd.notify_watchlists(&alert);
+        let notifications = shepherd.poll_notifications(1.0);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].watchlist, "flashpoints");
+        assert!(shepherd.poll_notifications(2.0).is_empty());
+
+        // Replacing by name keeps one watchlist
+        shepherd.add_watchlist(WatchlistConfig::new("flashpoints").with_actor("C"));
+        assert_eq!(shepherd.watchlists().len(), 1);
+        assert_eq!(shepherd.watchlist("flashpoints").unwrap().config().actors, ["C"]);
+        shepherd.notify_watchlists(&alert);
+        assert!(shepherd.poll_notifications(3.0).is_empty());
+        assert!(shepherd.remove_watchlist("flashpoints").is_some());
+        assert!(shepherd.watchlist("flashpoints").is_none());
+    }
 }
@@ -0,0 +1,405 @@
+//! Parameter sweeps: grid search over Shepherd settings
+//!
+//! Runs the same scenario (a [`Sandbox`](crate::sandbox::Sandbox) run, a
+//! replay of recorded observations, ...) once per point of a settings grid,
+//! in parallel, and scores each run's alerts against known transitions:
+//!
+//! ```text
+//! learning_rate  threshold  window   precision  recall  lead
+//! 0.1            1.0        20       0.40       1.00    12.0
+//! 0.1            2.5        20       1.00       0.50     4.0
+//! ...
+//! ```
+//!
+//! An alert at or above the scoring level is a hit if it is on the dyad of
+//! a known transition and falls between `max_lead` before and `max_lag`
+//! after its onset. Lead time is measured from a transition's earliest hit.
+
+use std::thread;
+
+use crate::runner::Observation;
+use crate::shepherd::{AlertLevel, NucleationAlert, ShepherdDynamics};
+use crate::variance::VarianceConfig;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// One point of the settings grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SweepParams {
+    pub learning_rate: f64,
+    /// Variance inflection threshold (z-score)
+    pub threshold: f64,
+    /// Rolling variance window
+    pub window_size: usize,
+}
+
+impl SweepParams {
+    /// Shepherd with these settings on top of `variance`.
+    pub fn shepherd(&self, n_categories: usize, variance: &VarianceConfig) -> ShepherdDynamics {
+        ShepherdDynamics::new(n_categories)
+            .with_learning_rate(self.learning_rate)
+            .with_variance_config(VarianceConfig {
+                threshold: self.threshold,
+                window_size: self.window_size,
+                ..variance.clone()
+            })
+    }
+}
+
+/// Values to try per setting; an empty axis keeps the base value.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParamGrid {
+    pub learning_rates: Vec<f64>,
+    pub thresholds: Vec<f64>,
+    pub window_sizes: Vec<usize>,
+}
+
+impl ParamGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_learning_rates(mut self, values: Vec<f64>) -> Self {
+        self.learning_rates = values;
+        self
+    }
+
+    pub fn with_thresholds(mut self, values: Vec<f64>) -> Self {
+        self.thresholds = values;
+        self
+    }
+
+    pub fn with_window_sizes(mut self, values: Vec<usize>) -> Self {
+        self.window_sizes = values;
+        self
+    }
+
+    /// Every combination, learning rate slowest and window size fastest.
+    pub fn points(&self, base: &SweepParams) -> Vec<SweepParams> {
+        fn axis<T: Copy>(values: &[T], base: T) -> Vec<T> {
+            if values.is_empty() {
+                vec![base]
+            } else {
+                values.to_vec()
+            }
+        }
+        let mut points = Vec::new();
+        for &learning_rate in &axis(&self.learning_rates, base.learning_rate) {
+            for &threshold in &axis(&self.thresholds, base.threshold) {
+                for &window_size in &axis(&self.window_sizes, base.window_size) {
+                    points.push(SweepParams {
+                        learning_rate,
+                        threshold,
+                        window_size,
+                    });
+                }
+            }
+        }
+        points
+    }
+}
+
+/// Known transition of a dyad.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TruthEvent {
+    pub actor_a: String,
+    pub actor_b: String,
+    /// Onset time
+    pub time: f64,
+}
+
+impl TruthEvent {
+    pub fn new(actor_a: impl Into<String>, actor_b: impl Into<String>, time: f64) -> Self {
+        Self {
+            actor_a: actor_a.into(),
+            actor_b: actor_b.into(),
+            time,
+        }
+    }
+
+    /// Whether `alert` is on this dyad and within the scoring window.
+    fn matches(&self, alert: &NucleationAlert, scoring: &Scoring) -> bool {
+        let same_dyad = (alert.actor_a == self.actor_a && alert.actor_b == self.actor_b)
+            || (alert.actor_a == self.actor_b && alert.actor_b == self.actor_a);
+        same_dyad
+            && alert.timestamp >= self.time - scoring.max_lead
+            && alert.timestamp <= self.time + scoring.max_lag
+    }
+}
+
+/// How alerts are matched to transitions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Scoring {
+    /// Alerts below this level are ignored
+    pub min_level: AlertLevel,
+    /// Earliest a hit may come before onset
+    pub max_lead: f64,
+    /// Latest a hit may come after onset
+    pub max_lag: f64,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            min_level: AlertLevel::Orange,
+            max_lead: 20.0,
+            max_lag: 0.0,
+        }
+    }
+}
+
+/// Scores of one grid point.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SweepRow {
+    pub params: SweepParams,
+    /// Alerts at or above the scoring level
+    pub alerts: usize,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    /// Transitions with at least one hit
+    pub detected: usize,
+    /// Hits over alerts; 0 without alerts
+    pub precision: f64,
+    /// Detected over known transitions; 0 without transitions
+    pub recall: f64,
+    /// Mean time from earliest hit to onset over detected transitions;
+    /// negative when hits came late
+    pub mean_lead_time: Option<f64>,
+}
+
+impl SweepRow {
+    /// Harmonic mean of precision and recall.
+    pub fn f1(&self) -> f64 {
+        if self.precision + self.recall > 0.0 {
+            2.0 * self.precision * self.recall / (self.precision + self.recall)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Score one run's alerts against known transitions.
+pub fn score(
+    params: SweepParams,
+    alerts: &[NucleationAlert],
+    truth: &[TruthEvent],
+    scoring: &Scoring,
+) -> SweepRow {
+    let counted: Vec<&NucleationAlert> = alerts
+        .iter()
+        .filter(|a| a.alert_level >= scoring.min_level)
+        .collect();
+    let true_positives = counted
+        .iter()
+        .filter(|a| truth.iter().any(|e| e.matches(a, scoring)))
+        .count();
+    let leads: Vec<f64> = truth
+        .iter()
+        .filter_map(|e| {
+            counted
+                .iter()
+                .filter(|a| e.matches(a, scoring))
+                .map(|a| e.time - a.timestamp)
+                .reduce(f64::max)
+        })
+        .collect();
+
+    let ratio = |num: usize, den: usize| if den > 0 { num as f64 / den as f64 } else { 0.0 };
+    SweepRow {
+        params,
+        alerts: counted.len(),
+        true_positives,
+        false_positives: counted.len() - true_positives,
+        detected: leads.len(),
+        precision: ratio(true_positives, counted.len()),
+        recall: ratio(leads.len(), truth.len()),
+        mean_lead_time: (!leads.is_empty())
+            .then(|| leads.iter().sum::<f64>() / leads.len() as f64),
+    }
+}
+
+/// Feed recorded observations through a shepherd in order, returning the
+/// alerts raised. The usual scenario for backtests.
+pub fn replay(shepherd: &mut ShepherdDynamics, observations: &[Observation]) -> Vec<NucleationAlert> {
+    observations
+        .iter()
+        .flat_map(|o| shepherd.update_actor(&o.actor_id, &o.values, o.timestamp))
+        .collect()
+}
+
+/// Grid search over Shepherd settings.
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    n_categories: usize,
+    base: SweepParams,
+    variance: VarianceConfig,
+    grid: ParamGrid,
+    scoring: Scoring,
+    threads: usize,
+}
+
+impl Sweep {
+    /// Sweep with default settings and an empty grid (a single run).
+    pub fn new(n_categories: usize) -> Self {
+        let variance = VarianceConfig::default();
+        Self {
+            n_categories,
+            base: SweepParams {
+                learning_rate: 0.1,
+                threshold: variance.threshold,
+                window_size: variance.window_size,
+            },
+            variance,
+            grid: ParamGrid::default(),
+            scoring: Scoring::default(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    /// Detector settings the grid varies; also sets the base threshold and
+    /// window size.
+    pub fn with_variance_config(mut self, config: VarianceConfig) -> Self {
+        self.base.threshold = config.threshold;
+        self.base.window_size = config.window_size;
+        self.variance = config;
+        self
+    }
+
+    /// Learning rate used when the grid does not vary it.
+    pub fn with_learning_rate(mut self, rate: f64) -> Self {
+        self.base.learning_rate = rate;
+        self
+    }
+
+    pub fn with_grid(mut self, grid: ParamGrid) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    pub fn with_scoring(mut self, scoring: Scoring) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Worker threads (at least 1); defaults to the available parallelism.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Grid points in run order.
+    pub fn points(&self) -> Vec<SweepParams> {
+        self.grid.points(&self.base)
+    }
+
+    /// Run `scenario` on a fresh shepherd per grid point and score its
+    /// alerts against `truth`. Rows come back in grid order.
+    pub fn run<F>(&self, truth: &[TruthEvent], scenario: F) -> Vec<SweepRow>
+    where
+        F: Fn(ShepherdDynamics) -> Vec<NucleationAlert> + Sync,
+    {
+        let points = self.points();
+        let chunk = points.len().div_ceil(self.threads).max(1);
+        let scenario = &scenario;
+        thread::scope(|s| {
+            let workers: Vec<_> = points
+                .chunks(chunk)
+                .map(|chunk| {
+                    s.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|&params| {
+                                let shepherd = params.shepherd(self.n_categories, &self.variance);
+                                score(params, &scenario(shepherd), truth, &self.scoring)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().expect("sweep scenario panicked"))
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::{AgentPolicy, Intervention, Sandbox, SandboxConfig};
+
+    /// Calm stubborn dyad, then a shock from t = 61.
+    fn shocked(shepherd: ShepherdDynamics) -> Vec<NucleationAlert> {
+        let mut sandbox = Sandbox::new(shepherd, SandboxConfig::default());
+        sandbox.add_agent("HAWK", vec![0.1, 0.3, 0.6], AgentPolicy::Stubborn);
+        sandbox.add_agent("DOVE", vec![0.6, 0.3, 0.1], AgentPolicy::Stubborn);
+        for t in 61..=70 {
+            let event = Intervention::Event {
+                agent: "DOVE".into(),
+                event: vec![0.0, 0.0, 1.0],
+            };
+            sandbox.schedule(t as f64, event);
+        }
+        sandbox.run(80).alerts
+    }
+
+    #[test]
+    fn test_sweep() {
+        let sweep = Sweep::new(3)
+            .with_variance_config(VarianceConfig {
+                smoothing_window: 3,
+                ..VarianceConfig::sensitive()
+            })
+            .with_learning_rate(0.5)
+            .with_grid(
+                ParamGrid::new()
+                    .with_thresholds(vec![0.5, 1.0, 50.0])
+                    .with_window_sizes(vec![10, 20]),
+            )
+            .with_scoring(Scoring {
+                min_level: AlertLevel::Yellow,
+                max_lead: 5.0,
+                max_lag: 10.0,
+            })
+            .with_threads(4);
+        let truth = [TruthEvent::new("DOVE", "HAWK", 61.0)];
+
+        let rows = sweep.run(&truth, shocked);
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows.iter().map(|r| r.params).collect::<Vec<_>>(), sweep.points());
+        assert_eq!(rows[1].params.window_size, 20);
+        assert!(rows.iter().all(|r| r.true_positives + r.false_positives == r.alerts));
+        assert!(rows.iter().any(|r| r.recall == 1.0 && r.mean_lead_time.is_some()));
+        // Same rows whatever the thread count
+        assert_eq!(sweep.clone().with_threads(1).run(&truth, shocked), rows);
+
+        // Against a transition that never happened, every alert is false
+        let best = rows.iter().find(|r| r.recall == 1.0).unwrap();
+        let alerts = shocked(best.params.shepherd(3, &sweep.variance));
+        let late = [TruthEvent::new("DOVE", "HAWK", 500.0)];
+        let row = score(best.params, &alerts, &late, &sweep.scoring);
+        assert_eq!(row.alerts, best.alerts);
+        assert_eq!((row.precision, row.recall, row.f1()), (0.0, 0.0, 0.0));
+
+        // Replays of recorded observations work as scenarios too
+        let observations: Vec<Observation> = (0..50)
+            .map(|i| Observation {
+                actor_id: if i % 2 == 0 { "A" } else { "B" }.to_string(),
+                values: vec![0.5, 0.5],
+                timestamp: (i / 2) as f64,
+            })
+            .collect();
+        let mut shepherd = ShepherdDynamics::new(2);
+        shepherd.register_actor("A", None);
+        shepherd.register_actor("B", None);
+        replay(&mut shepherd, &observations);
+        assert_eq!(shepherd.get_scheme("A").unwrap().distribution(), &[0.5, 0.5]);
+    }
+}
@@ -14,17 +14,25 @@
 //! 3. Smooth V(t) with convolution kernel
 //! 4. Compute second derivative d²V/dt²
 //! 5. Detect peaks in |d²V/dt²| above threshold
+//!
+//! Rolling variance and smoothing are maintained incrementally (running
+//! sums updated as values enter and leave their windows), so each update
+//! is O(1) and allocation-free.
 
 use std::collections::VecDeque;
 
+/// Updates between exact recomputations of the running sums (bounds drift).
+const RESYNC_INTERVAL: usize = 4096;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Phase classification for the detector state.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Phase {
     /// Normal operation, no transition detected
+    #[default]
     Stable,
     /// Variance dynamics changing, possible transition approaching
     Approaching,
@@ -34,28 +42,17 @@ pub enum Phase {
     Transitioning,
 }
 
-impl Default for Phase {
-    fn default() -> Self {
-        Self::Stable
-    }
-}
-
 /// Smoothing kernel type for variance trajectory.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SmoothingKernel {
     /// Uniform weights (box filter)
+    #[default]
     Uniform,
     /// Gaussian-like weights (approximated triangular)
     Gaussian,
 }
 
-impl Default for SmoothingKernel {
-    fn default() -> Self {
-        Self::Uniform
-    }
-}
-
 /// Configuration for the variance inflection detector.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -123,9 +120,20 @@ pub struct InflectionResult {
 pub struct VarianceInflectionDetector {
     config: VarianceConfig,
 
-    // Raw observation buffer
+    // Raw observation buffer (last `window_size` values)
     observations: VecDeque<f64>,
 
+    // Running sums over the observation window: Σx and Σx²,
+    // both taken relative to `shift` for numerical stability
+    obs_sum: f64,
+    obs_sum_sq: f64,
+    shift: f64,
+
+    // Running sums over the smoothing window of the variance trajectory:
+    // Σv and Σ age·v (age 0 = newest), for uniform and triangular kernels
+    smooth_sum: f64,
+    smooth_age_sum: f64,
+
     // Variance trajectory
     variance_history: VecDeque<f64>,
 
@@ -152,14 +160,19 @@ pub struct VarianceInflectionDetector {
 
 impl VarianceInflectionDetector {
     pub fn new(config: VarianceConfig) -> Self {
-        let cap = config.window_size * 3;
+        let cap = config.window_size * 2;
         Self {
+            observations: VecDeque::with_capacity(config.window_size + 1),
+            obs_sum: 0.0,
+            obs_sum_sq: 0.0,
+            shift: 0.0,
+            smooth_sum: 0.0,
+            smooth_age_sum: 0.0,
+            variance_history: VecDeque::with_capacity(Self::history_capacity(&config) + 1),
+            smoothed_variance: VecDeque::with_capacity(cap + 1),
+            d1_variance: VecDeque::with_capacity(cap + 1),
+            d2_variance: VecDeque::with_capacity(cap + 1),
             config,
-            observations: VecDeque::with_capacity(cap),
-            variance_history: VecDeque::with_capacity(cap),
-            smoothed_variance: VecDeque::with_capacity(cap),
-            d1_variance: VecDeque::with_capacity(cap),
-            d2_variance: VecDeque::with_capacity(cap),
             baseline_d2_mean: 0.0,
             baseline_d2_std: 1.0,
             baseline_samples: 0,
@@ -176,11 +189,25 @@ impl VarianceInflectionDetector {
     pub fn update(&mut self, value: f64) -> InflectionResult {
         self.count += 1;
 
-        // Add to observation buffer
-        if self.observations.len() >= self.config.window_size * 3 {
-            self.observations.pop_front();
+        // Add to observation buffer, retiring the value leaving the window
+        if self.observations.is_empty() {
+            self.shift = value;
         }
+        let x = value - self.shift;
+        self.obs_sum += x;
+        self.obs_sum_sq += x * x;
         self.observations.push_back(value);
+        if self.observations.len() > self.config.window_size {
+            if let Some(old) = self.observations.pop_front() {
+                let y = old - self.shift;
+                self.obs_sum -= y;
+                self.obs_sum_sq -= y * y;
+            }
+        }
+
+        if self.count.is_multiple_of(RESYNC_INTERVAL) {
+            self.resync();
+        }
 
         // Compute rolling variance if we have enough data
         if self.observations.len() >= self.config.window_size {
@@ -239,6 +266,11 @@ impl VarianceInflectionDetector {
     /// Reset detector state.
     pub fn reset(&mut self) {
         self.observations.clear();
+        self.obs_sum = 0.0;
+        self.obs_sum_sq = 0.0;
+        self.shift = 0.0;
+        self.smooth_sum = 0.0;
+        self.smooth_age_sum = 0.0;
         self.variance_history.clear();
         self.smoothed_variance.clear();
         self.d1_variance.clear();
@@ -260,34 +292,63 @@ impl VarianceInflectionDetector {
         &self.config
     }
 
-    // Internal: compute rolling variance of recent observations
+    // Internal: variance history capacity (must retain the value leaving
+    // the smoothing window)
+    fn history_capacity(config: &VarianceConfig) -> usize {
+        (config.window_size * 2).max(config.smoothing_window + 1)
+    }
+
+    // Internal: recompute running sums exactly from the buffers
+    fn resync(&mut self) {
+        self.shift = self.observations.back().copied().unwrap_or(0.0);
+        self.obs_sum = 0.0;
+        self.obs_sum_sq = 0.0;
+        for &v in &self.observations {
+            let x = v - self.shift;
+            self.obs_sum += x;
+            self.obs_sum_sq += x * x;
+        }
+
+        let n = self.config.smoothing_window.min(self.variance_history.len());
+        self.smooth_sum = 0.0;
+        self.smooth_age_sum = 0.0;
+        for (age, &v) in self.variance_history.iter().rev().take(n).enumerate() {
+            self.smooth_sum += v;
+            self.smooth_age_sum += age as f64 * v;
+        }
+    }
+
+    // Internal: rolling variance of recent observations from running sums
     fn compute_rolling_variance(&self) -> f64 {
         let n = self.config.window_size;
         if self.observations.len() < n {
             return 0.0;
         }
 
-        let window: Vec<f64> = self.observations.iter()
-            .rev()
-            .take(n)
-            .copied()
-            .collect();
-
-        let mean: f64 = window.iter().sum::<f64>() / n as f64;
-        let variance: f64 = window.iter()
-            .map(|x| (x - mean).powi(2))
-            .sum::<f64>() / n as f64;
-
-        variance
+        let n = n as f64;
+        let mean = self.obs_sum / n;
+        (self.obs_sum_sq / n - mean * mean).max(0.0)
     }
 
     // Internal: update variance trajectory and derivatives
     fn update_variance_trajectory(&mut self, variance: f64) {
-        // Store raw variance
-        if self.variance_history.len() >= self.config.window_size * 2 {
+        // Store raw variance, updating the smoothing sums: every entry in
+        // the window ages by one, and the oldest may leave it
+        let window = self.config.smoothing_window;
+        self.variance_history.push_back(variance);
+        let len = self.variance_history.len();
+        if window > 0 {
+            self.smooth_age_sum += self.smooth_sum;
+            self.smooth_sum += variance;
+            if len > window {
+                let leaving = self.variance_history[len - 1 - window];
+                self.smooth_sum -= leaving;
+                self.smooth_age_sum -= window as f64 * leaving;
+            }
+        }
+        if len > Self::history_capacity(&self.config) {
             self.variance_history.pop_front();
         }
-        self.variance_history.push_back(variance);
 
         // Smooth variance
         let smoothed = self.smooth_variance();
@@ -297,9 +358,9 @@ impl VarianceInflectionDetector {
         self.smoothed_variance.push_back(smoothed);
 
         // Compute first derivative (gradient)
-        if self.smoothed_variance.len() >= 2 {
-            let d1 = self.smoothed_variance.back().unwrap()
-                - self.smoothed_variance.iter().rev().nth(1).unwrap();
+        let len = self.smoothed_variance.len();
+        if len >= 2 {
+            let d1 = self.smoothed_variance[len - 1] - self.smoothed_variance[len - 2];
 
             if self.d1_variance.len() >= self.config.window_size * 2 {
                 self.d1_variance.pop_front();
@@ -308,9 +369,9 @@ impl VarianceInflectionDetector {
         }
 
         // Compute second derivative (inflection)
-        if self.d1_variance.len() >= 2 {
-            let d2 = self.d1_variance.back().unwrap()
-                - self.d1_variance.iter().rev().nth(1).unwrap();
+        let len = self.d1_variance.len();
+        if len >= 2 {
+            let d2 = self.d1_variance[len - 1] - self.d1_variance[len - 2];
 
             if self.d2_variance.len() >= self.config.window_size * 2 {
                 self.d2_variance.pop_front();
@@ -322,37 +383,20 @@ impl VarianceInflectionDetector {
         }
     }
 
-    // Internal: smooth variance using configured kernel
+    // Internal: smooth variance using configured kernel from running sums
     fn smooth_variance(&self) -> f64 {
         let n = self.config.smoothing_window.min(self.variance_history.len());
         if n == 0 {
             return self.variance_history.back().copied().unwrap_or(0.0);
         }
-
-        let window: Vec<f64> = self.variance_history.iter()
-            .rev()
-            .take(n)
-            .copied()
-            .collect();
+        let n = n as f64;
 
         match self.config.kernel {
-            SmoothingKernel::Uniform => {
-                window.iter().sum::<f64>() / n as f64
-            }
+            SmoothingKernel::Uniform => self.smooth_sum / n,
             SmoothingKernel::Gaussian => {
-                // Triangular approximation of Gaussian
-                let weights: Vec<f64> = (0..n)
-                    .map(|i| {
-                        let x = i as f64 / n as f64;
-                        1.0 - x // Linear decay
-                    })
-                    .collect();
-                let weight_sum: f64 = weights.iter().sum();
-
-                window.iter()
-                    .zip(weights.iter())
-                    .map(|(v, w)| v * w)
-                    .sum::<f64>() / weight_sum
+                // Triangular approximation of Gaussian: weight 1 - age/n,
+                // so Σw·v = Σv - Σage·v / n and Σw = (n + 1) / 2
+                (self.smooth_sum - self.smooth_age_sum / n) / ((n + 1.0) / 2.0)
             }
         }
     }
@@ -511,4 +555,46 @@ mod tests {
         detector.update_batch(&values);
         assert_eq!(detector.count(), 100);
     }
+
+    #[test]
+    fn test_incremental_matches_direct() {
+        for kernel in [SmoothingKernel::Uniform, SmoothingKernel::Gaussian] {
+            let config = VarianceConfig {
+                window_size: 8,
+                smoothing_window: 5,
+                kernel,
+                ..Default::default()
+            };
+            let mut detector = VarianceInflectionDetector::new(config);
+            let values: Vec<f64> = (0..300)
+                .map(|i| 1000.0 + (i as f64 * 0.7).sin() * (1.0 + i as f64 / 50.0))
+                .collect();
+
+            let mut variances = Vec::new();
+            for (t, &v) in values.iter().enumerate() {
+                detector.update(v);
+                if t + 1 < 8 {
+                    continue;
+                }
+
+                let window = &values[t + 1 - 8..=t];
+                let mean = window.iter().sum::<f64>() / 8.0;
+                let var = window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 8.0;
+                assert!((detector.current_variance() - var).abs() < 1e-8);
+                variances.push(var);
+
+                let n = variances.len().min(5);
+                let recent = variances.iter().rev().take(n);
+                let expected = match kernel {
+                    SmoothingKernel::Uniform => recent.sum::<f64>() / n as f64,
+                    SmoothingKernel::Gaussian => {
+                        let w = |i: usize| 1.0 - i as f64 / n as f64;
+                        let total: f64 = (0..n).map(w).sum();
+                        recent.enumerate().map(|(i, v)| v * w(i)).sum::<f64>() / total
+                    }
+                };
+                assert!((detector.smooth_variance() - expected).abs() < 1e-8);
+            }
+        }
+    }
 }
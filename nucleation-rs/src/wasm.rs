@@ -15,6 +15,8 @@ use crate::variance::{
     SmoothingKernel,
 };
 use crate::compression::CompressionDynamicsModel as RustCompressionModel;
+use crate::annotation::AnnotationTarget;
+use crate::display::ToastKind;
 use crate::shepherd::{
     ShepherdDynamics as RustShepherd,
     AlertLevel as RustAlertLevel,
@@ -57,6 +59,17 @@ pub enum AlertLevel {
     Red = 3,
 }
 
+impl From<AlertLevel> for RustAlertLevel {
+    fn from(a: AlertLevel) -> Self {
+        match a {
+            AlertLevel::Green => RustAlertLevel::Green,
+            AlertLevel::Yellow => RustAlertLevel::Yellow,
+            AlertLevel::Orange => RustAlertLevel::Orange,
+            AlertLevel::Red => RustAlertLevel::Red,
+        }
+    }
+}
+
 impl From<RustAlertLevel> for AlertLevel {
     fn from(a: RustAlertLevel) -> Self {
         match a {
@@ -82,6 +95,12 @@ pub struct DetectorConfig {
     kernel: String,
 }
 
+impl Default for DetectorConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 impl DetectorConfig {
     #[wasm_bindgen(constructor)]
@@ -329,6 +348,7 @@ impl CompressionModel {
             let _ = Reflect::set(&obj, &"hellinger".into(), &JsValue::from_f64(p.hellinger));
             let _ = Reflect::set(&obj, &"klAB".into(), &JsValue::from_f64(p.kl_a_b));
             let _ = Reflect::set(&obj, &"klBA".into(), &JsValue::from_f64(p.kl_b_a));
+            let _ = Reflect::set(&obj, &"reliability".into(), &JsValue::from_f64(p.reliability));
             let _ = Reflect::set(&obj, &"riskCategory".into(), &JsValue::from_str(p.risk_category()));
             JsValue::from(obj)
         } else {
@@ -394,6 +414,7 @@ impl Shepherd {
             let _ = Reflect::set(&obj, &"phi".into(), &JsValue::from_f64(a.phi));
             let _ = Reflect::set(&obj, &"phiTrend".into(), &JsValue::from_f64(a.phi_trend));
             let _ = Reflect::set(&obj, &"confidence".into(), &JsValue::from_f64(a.confidence));
+            let _ = Reflect::set(&obj, &"reliability".into(), &JsValue::from_f64(a.reliability));
             let _ = Reflect::set(&obj, &"timestamp".into(), &JsValue::from_f64(a.timestamp));
             let _ = Reflect::set(&obj, &"message".into(), &JsValue::from_str(&a.message));
             JsValue::from(obj)
@@ -411,6 +432,7 @@ impl Shepherd {
             let _ = Reflect::set(&obj, &"phi".into(), &JsValue::from_f64(a.phi));
             let _ = Reflect::set(&obj, &"phiTrend".into(), &JsValue::from_f64(a.phi_trend));
             let _ = Reflect::set(&obj, &"confidence".into(), &JsValue::from_f64(a.confidence));
+            let _ = Reflect::set(&obj, &"reliability".into(), &JsValue::from_f64(a.reliability));
             let _ = Reflect::set(&obj, &"message".into(), &JsValue::from_str(&a.message));
             JsValue::from(obj)
         } else {
@@ -448,6 +470,20 @@ impl Shepherd {
             .collect()
     }
 
+    /// Latest `limit` dyad alerts as an array of alert cards, oldest first.
+    ///
+    /// Cards share their JSON shape with server-side `AlertCard::to_json`.
+    #[wasm_bindgen(js_name = alertCards)]
+    pub fn alert_cards(&self, limit: usize) -> Result<String, JsValue> {
+        let history = self.inner.alert_history();
+        let cards: Vec<_> = history[history.len().saturating_sub(limit)..]
+            .iter()
+            .map(|a| a.to_card())
+            .collect();
+        serde_json::to_string(&cards)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     /// Get phi history for a dyad as Float64Array pairs [timestamp, phi, ...].
     #[wasm_bindgen(js_name = phiHistory)]
     pub fn phi_history(&self, actor_a: &str, actor_b: &str) -> Float64Array {
@@ -460,12 +496,64 @@ impl Shepherd {
             Float64Array::new_with_length(0)
         }
     }
+
+    /// Mark a context event on an actor; false if the actor is unknown.
+    #[wasm_bindgen(js_name = annotateActor)]
+    pub fn annotate_actor(&mut self, actor_id: &str, timestamp: f64, label: &str) -> bool {
+        self.inner.annotate_actor(actor_id, timestamp, label)
+    }
+
+    /// Mark a context event on a dyad; false if either actor is unknown.
+    #[wasm_bindgen(js_name = annotateDyad)]
+    pub fn annotate_dyad(&mut self, actor_a: &str, actor_b: &str, timestamp: f64, label: &str) -> bool {
+        self.inner.annotate_dyad(actor_a, actor_b, timestamp, label)
+    }
+
+    /// Annotations to draw over `phiHistory` for a dyad, oldest first, as
+    /// `{timestamp, label, actor}` objects (`actor` is null for dyad
+    /// annotations).
+    #[wasm_bindgen(js_name = phiHistoryAnnotations)]
+    pub fn phi_history_annotations(&self, actor_a: &str, actor_b: &str) -> Array {
+        let Some(history) = self.inner.annotated_phi_history(actor_a, actor_b) else {
+            return Array::new();
+        };
+        history.annotations.into_iter().map(|a| {
+            let obj = Object::new();
+            let _ = Reflect::set(&obj, &"timestamp".into(), &JsValue::from_f64(a.timestamp));
+            let _ = Reflect::set(&obj, &"label".into(), &JsValue::from_str(&a.label));
+            let actor = match &a.target {
+                AnnotationTarget::Actor(actor) => JsValue::from_str(actor),
+                AnnotationTarget::Dyad(..) => JsValue::NULL,
+            };
+            let _ = Reflect::set(&obj, &"actor".into(), &actor);
+            JsValue::from(obj)
+        }).collect()
+    }
 }
 
 // ============================================================================
 // Utility functions
 // ============================================================================
 
+/// Presentation attributes of an alert level as a JS object
+/// `{label, colorHex, emoji, toast}`.
+#[wasm_bindgen(js_name = alertLevelDisplay)]
+pub fn alert_level_display(level: AlertLevel) -> JsValue {
+    let display = RustAlertLevel::from(level).to_display_struct();
+    let obj = Object::new();
+    let _ = Reflect::set(&obj, &"label".into(), &JsValue::from_str(display.label));
+    let _ = Reflect::set(&obj, &"colorHex".into(), &JsValue::from_str(display.color_hex));
+    let _ = Reflect::set(&obj, &"emoji".into(), &JsValue::from_str(display.emoji));
+    let toast = match display.toast {
+        ToastKind::Success => "success",
+        ToastKind::Info => "info",
+        ToastKind::Warning => "warning",
+        ToastKind::Error => "error",
+    };
+    let _ = Reflect::set(&obj, &"toast".into(), &JsValue::from_str(toast));
+    JsValue::from(obj)
+}
+
 /// Get the library version.
 #[wasm_bindgen]
 pub fn version() -> String {
@@ -474,20 +562,20 @@ pub fn version() -> String {
 
 /// Compute KL divergence between two distributions.
 #[wasm_bindgen(js_name = klDivergence)]
-pub fn kl_divergence_wasm(p: &[f64], q: &[f64]) -> f64 {
-    crate::entropy::kl_divergence(p, q)
+pub fn kl_divergence_wasm(p: &[f64], q: &[f64]) -> Result<f64, JsValue> {
+    crate::entropy::try_kl_divergence(p, q).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 /// Compute Hellinger distance between two distributions.
 #[wasm_bindgen(js_name = hellingerDistance)]
-pub fn hellinger_distance_wasm(p: &[f64], q: &[f64]) -> f64 {
-    crate::distance::hellinger_distance(p, q)
+pub fn hellinger_distance_wasm(p: &[f64], q: &[f64]) -> Result<f64, JsValue> {
+    crate::distance::try_hellinger_distance(p, q).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 /// Compute Jensen-Shannon divergence between two distributions.
 #[wasm_bindgen(js_name = jensenShannonDivergence)]
-pub fn jensen_shannon_wasm(p: &[f64], q: &[f64]) -> f64 {
-    crate::distance::jensen_shannon_divergence(p, q)
+pub fn jensen_shannon_wasm(p: &[f64], q: &[f64]) -> Result<f64, JsValue> {
+    crate::distance::try_jensen_shannon_divergence(p, q).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 /// Compute Shannon entropy of a distribution.
@@ -0,0 +1,271 @@
+//! Watchlists: named sets of actors and dyads with their own notification rules
+//!
+//! A watchlist selects the dyad alerts it cares about — every dyad listed,
+//! and every dyad involving a listed actor — and routes each through the
+//! first of its rules the alert's level reaches:
+//!
+//! ```text
+//! "Asia-Pacific flashpoints"
+//!   Red     → immediately, to "pager"
+//!   Yellow+ → daily digest, to "email"
+//! ```
+//!
+//! Rules name their sink; delivering to it is up to the caller (see
+//! [`ShepherdRunner::on_notification`](crate::runner::ShepherdRunner::on_notification)).
+//! Digest intervals are in alert timestamp units, so schedules follow
+//! model time rather than the wall clock.
+
+use crate::shepherd::{AlertLevel, NucleationAlert};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// When a rule's alerts are delivered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Delivery {
+    /// One notification per alert, as it is raised
+    Immediate,
+    /// Alerts collected and delivered together once per `interval`
+    Digest { interval: f64 },
+}
+
+/// Delivery of alerts at or above a level to a named sink.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NotificationRule {
+    pub min_level: AlertLevel,
+    pub delivery: Delivery,
+    pub sink: String,
+}
+
+impl NotificationRule {
+    /// Deliver alerts at or above `min_level` as they are raised.
+    pub fn immediate(min_level: AlertLevel, sink: impl Into<String>) -> Self {
+        Self {
+            min_level,
+            delivery: Delivery::Immediate,
+            sink: sink.into(),
+        }
+    }
+
+    /// Deliver alerts at or above `min_level` once per `interval`.
+    pub fn digest(min_level: AlertLevel, interval: f64, sink: impl Into<String>) -> Self {
+        Self {
+            min_level,
+            delivery: Delivery::Digest { interval: interval.max(0.0) },
+            sink: sink.into(),
+        }
+    }
+}
+
+/// Declarative watchlist definition.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WatchlistConfig {
+    pub name: String,
+    /// Actors whose every dyad is watched
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub actors: Vec<String>,
+    /// Individual dyads watched, in either order
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dyads: Vec<(String, String)>,
+    /// Rules tried in order; the first the alert level reaches applies
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rules: Vec<NotificationRule>,
+}
+
+impl WatchlistConfig {
+    /// Empty watchlist.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            actors: Vec::new(),
+            dyads: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Watch every dyad involving `actor`.
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actors.push(actor.into());
+        self
+    }
+
+    /// Watch one dyad.
+    pub fn with_dyad(mut self, actor_a: impl Into<String>, actor_b: impl Into<String>) -> Self {
+        self.dyads.push((actor_a.into(), actor_b.into()));
+        self
+    }
+
+    /// Append a notification rule.
+    pub fn with_rule(mut self, rule: NotificationRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Whether the watchlist covers an alert's dyad.
+    pub fn watches(&self, alert: &NucleationAlert) -> bool {
+        let (a, b) = (alert.actor_a.as_str(), alert.actor_b.as_str());
+        self.actors.iter().any(|actor| actor == a || actor == b)
+            || self
+                .dyads
+                .iter()
+                .any(|(x, y)| (x == a && y == b) || (x == b && y == a))
+    }
+
+    /// Index of the rule an alert falls under, if any.
+    pub fn rule_for(&self, alert: &NucleationAlert) -> Option<usize> {
+        self.rules.iter().position(|r| alert.alert_level >= r.min_level)
+    }
+}
+
+/// Alerts delivered to a sink on behalf of a watchlist.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Notification {
+    pub watchlist: String,
+    pub sink: String,
+    /// One alert when immediate; the period's alerts, oldest first, for digests
+    pub alerts: Vec<NucleationAlert>,
+    pub digest: bool,
+    pub timestamp: f64,
+}
+
+/// Watchlist with its pending digests.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Watchlist {
+    config: WatchlistConfig,
+    /// Alerts awaiting each rule's next digest
+    pending: Vec<Vec<NucleationAlert>>,
+    /// Start of each digest rule's current period
+    period_start: Vec<Option<f64>>,
+}
+
+impl Watchlist {
+    /// Create a watchlist from its definition.
+    pub fn new(config: WatchlistConfig) -> Self {
+        let n = config.rules.len();
+        Self {
+            config,
+            pending: vec![Vec::new(); n],
+            period_start: vec![None; n],
+        }
+    }
+
+    /// Watchlist name.
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// Watchlist definition.
+    pub fn config(&self) -> &WatchlistConfig {
+        &self.config
+    }
+
+    /// Alerts waiting for a rule's next digest.
+    pub fn pending(&self, rule: usize) -> &[NucleationAlert] {
+        self.pending.get(rule).map_or(&[], Vec::as_slice)
+    }
+
+    /// Route a raised alert.
+    ///
+    /// Returns the notification for immediate rules; digest rules queue the
+    /// alert until [`poll`](Self::poll) finds their period over.
+    pub fn route(&mut self, alert: &NucleationAlert) -> Option<Notification> {
+        if !self.config.watches(alert) {
+            return None;
+        }
+        let index = self.config.rule_for(alert)?;
+        let rule = &self.config.rules[index];
+        match rule.delivery {
+            Delivery::Immediate => Some(Notification {
+                watchlist: self.config.name.clone(),
+                sink: rule.sink.clone(),
+                alerts: vec![alert.clone()],
+                digest: false,
+                timestamp: alert.timestamp,
+            }),
+            Delivery::Digest { .. } => {
+                self.period_start[index].get_or_insert(alert.timestamp);
+                self.pending[index].push(alert.clone());
+                None
+            }
+        }
+    }
+
+    /// Digests whose period has ended by `timestamp`.
+    ///
+    /// A period starts at the first poll or queued alert and restarts at
+    /// each delivery; periods without alerts deliver nothing.
+    pub fn poll(&mut self, timestamp: f64) -> Vec<Notification> {
+        let mut notifications = Vec::new();
+        for (index, rule) in self.config.rules.iter().enumerate() {
+            let Delivery::Digest { interval } = rule.delivery else {
+                continue;
+            };
+            let start = *self.period_start[index].get_or_insert(timestamp);
+            if timestamp - start < interval {
+                continue;
+            }
+            self.period_start[index] = Some(timestamp);
+            if self.pending[index].is_empty() {
+                continue;
+            }
+            notifications.push(Notification {
+                watchlist: self.config.name.clone(),
+                sink: rule.sink.clone(),
+                alerts: std::mem::take(&mut self.pending[index]),
+                digest: true,
+                timestamp,
+            });
+        }
+        notifications
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shepherd::ShepherdDynamics;
+
+    fn alert(actor_a: &str, actor_b: &str, level: AlertLevel, timestamp: f64) -> NucleationAlert {
+        let mut shepherd = ShepherdDynamics::new(3);
+        shepherd.register_actor(actor_a, Some(vec![0.4, 0.3, 0.3]));
+        shepherd.register_actor(actor_b, Some(vec![0.3, 0.4, 0.3]));
+        shepherd.check_dyad(actor_a, actor_b, timestamp);
+        NucleationAlert {
+            alert_level: level,
+            ..shepherd.last_alert(actor_a, actor_b).unwrap().clone()
+        }
+    }
+
+    #[test]
+    fn test_watchlist_routing() {
+        let config = WatchlistConfig::new("apac")
+            .with_actor("CHN")
+            .with_dyad("PRK", "KOR")
+            .with_rule(NotificationRule::immediate(AlertLevel::Red, "pager"))
+            .with_rule(NotificationRule::digest(AlertLevel::Yellow, 100.0, "email"));
+        let mut watchlist = Watchlist::new(config);
+
+        let red = watchlist.route(&alert("CHN", "TWN", AlertLevel::Red, 1.0)).unwrap();
+        assert_eq!((red.sink.as_str(), red.digest), ("pager", false));
+        assert!(watchlist.route(&alert("KOR", "PRK", AlertLevel::Orange, 2.0)).is_none());
+        assert!(watchlist.route(&alert("TWN", "CHN", AlertLevel::Yellow, 3.0)).is_none());
+        // Unwatched dyads and levels below every rule are ignored
+        assert!(watchlist.route(&alert("USA", "RUS", AlertLevel::Red, 4.0)).is_none());
+        assert!(watchlist.route(&alert("CHN", "IND", AlertLevel::Green, 5.0)).is_none());
+        assert_eq!(watchlist.pending(1).len(), 2);
+
+        // The digest period started with the first queued alert
+        assert!(watchlist.poll(50.0).is_empty());
+        let digests = watchlist.poll(102.0);
+        assert_eq!(digests.len(), 1);
+        assert_eq!(digests[0].sink, "email");
+        assert_eq!(digests[0].alerts.len(), 2);
+        assert!(watchlist.pending(1).is_empty());
+        assert!(watchlist.poll(500.0).is_empty());
+    }
+}